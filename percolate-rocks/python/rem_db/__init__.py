@@ -192,16 +192,19 @@ class _DatabaseWrapper:
         # TODO: Delegate to Rust
         pass
 
-    def search(self, query: str, schema: str, top_k: int = 10) -> list[dict]:
+    def search(
+        self, query: str, schema: str, top_k: int = 10, mode: str = "balanced"
+    ) -> list[dict]:
         """Semantic search using vector embeddings.
 
         Args:
             query: Search query text
             schema: Schema name to search
             top_k: Number of results
+            mode: Recall/latency preset ("fast", "balanced", "accurate")
 
         Returns:
-            List of (entity, score) tuples
+            List of dicts with "entity", "score", and "rank" (0-based) keys, best first
 
         Note:
             Uses HNSW index for 200x speedup
@@ -245,17 +248,27 @@ class _DatabaseWrapper:
         pass
 
     def traverse(
-        self, start_id: str, direction: str = "out", depth: int = 2
-    ) -> list[str]:
+        self,
+        start_id: str,
+        direction: str = "out",
+        depth: int = 2,
+        max_nodes: int | None = None,
+        max_nodes_per_level: int | None = None,
+        rel_types: list[str] | None = None,
+    ) -> dict:
         """Graph traversal from starting entity.
 
         Args:
             start_id: Starting entity UUID
             direction: Traversal direction ("out", "in", "both")
             depth: Maximum traversal depth
+            max_nodes: Maximum nodes returned (default 10,000)
+            max_nodes_per_level: Maximum nodes discovered per depth (default 1,000)
+            rel_types: Only follow edges of these types (default: all)
 
         Returns:
-            List of entity UUIDs in traversal order
+            Dict with "nodes" (entity UUIDs in traversal order) and
+            "truncated" (whether a node limit was hit)
 
         Note:
             Uses bidirectional edges for 20x speedup
@@ -105,7 +105,7 @@ class AsyncDatabase:
             top_k: Number of results
 
         Returns:
-            List of (entity, score) tuples
+            List of dicts with "entity", "score", and "rank" (0-based) keys, best first
 
         Note:
             Query embedding generation is async (OpenAI API)
@@ -155,17 +155,27 @@ class AsyncDatabase:
         pass
 
     async def traverse(
-        self, start_id: str, direction: str = "out", depth: int = 2
-    ) -> list[str]:
+        self,
+        start_id: str,
+        direction: str = "out",
+        depth: int = 2,
+        max_nodes: int | None = None,
+        max_nodes_per_level: int | None = None,
+        rel_types: list[str] | None = None,
+    ) -> dict:
         """Graph traversal from starting entity (async).
 
         Args:
             start_id: Starting entity UUID
             direction: Traversal direction ("out", "in", "both")
             depth: Maximum traversal depth
+            max_nodes: Maximum nodes returned (default 10,000)
+            max_nodes_per_level: Maximum nodes discovered per depth (default 1,000)
+            rel_types: Only follow edges of these types (default: all)
 
         Returns:
-            List of entity UUIDs in traversal order
+            Dict with "nodes" (entity UUIDs in traversal order) and
+            "truncated" (whether a node limit was hit)
 
         Note:
             Fast operation, no network calls
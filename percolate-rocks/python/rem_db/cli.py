@@ -23,6 +23,15 @@ def get_db_path() -> Path:
     return Path(path_str).expanduser()
 
 
+def linked_edges(edge_plan: dict) -> list:
+    """Keep edges whose destination resolved to an entity, with dst set to its UUID."""
+    return [
+        {**edge, "dst": edge["resolved_id"]}
+        for edge in edge_plan.get("edges", [])
+        if edge.get("resolved_id")
+    ]
+
+
 def get_database():
     """Get database instance with default tenant."""
     from rem_db import Database
@@ -212,8 +221,8 @@ def ingest(
 
                 # Extract edges from full document (not chunks!)
                 try:
-                    edge_plan = db.extract_edges(full_content, str(file_path.name))
-                    document_edges = edge_plan.get("edges", [])
+                    edge_plan = db.extract_edges(full_content, str(file_path.name), resolve=True)
+                    document_edges = linked_edges(edge_plan)
 
                     if document_edges:
                         console.print(f"[green]✓[/green] Extracted {len(document_edges)} edges from document")
@@ -320,9 +329,9 @@ def index(
                 uri = entity.get("properties", {}).get("uri", "")
                 context = name or uri or f"Entity {entity_id[:8]}"
 
-                edge_plan = db.extract_edges(content, context)
+                edge_plan = db.extract_edges(content, context, resolve=True)
 
-                edges = edge_plan.get("edges", [])
+                edges = linked_edges(edge_plan)
                 if edges:
                     # Append edges to entity
                     entity["edges"] = existing_edges + edges
@@ -411,9 +420,9 @@ def search(
         results = db.search(query, schema, top_k)
 
         if results:
-            for entity, score in results:
-                console.print(f"\n[bold cyan]Score: {score:.4f}[/bold cyan]")
-                console.print_json(json.dumps(entity, indent=2))
+            for hit in results:
+                console.print(f"\n[bold cyan]#{hit['rank'] + 1} Score: {hit['score']:.4f}[/bold cyan]")
+                console.print_json(json.dumps(hit["entity"], indent=2))
         else:
             console.print("[yellow]No results found[/yellow]")
     except Exception as e:
@@ -486,6 +495,8 @@ def traverse(
     entity_id: Annotated[str, typer.Argument(help="Starting entity UUID")],
     depth: Annotated[int, typer.Option("--depth", help="Traversal depth")] = 2,
     direction: Annotated[str, typer.Option("--direction", help="Direction (out/in/both)")] = "out",
+    max_nodes: Annotated[int | None, typer.Option("--max-nodes", help="Maximum nodes returned")] = None,
+    rel_type: Annotated[list[str] | None, typer.Option("--rel-type", help="Only follow edges of this type (repeatable)")] = None,
 ):
     """Graph traversal from entity.
 
@@ -494,12 +505,15 @@ def traverse(
     """
     try:
         db = get_database()
-        uuids = db.traverse(entity_id, direction, depth)
+        result = db.traverse(entity_id, direction, depth, max_nodes, None, rel_type)
+        uuids = result["nodes"]
 
         if uuids:
             console.print(f"[green]Found {len(uuids)} entities:[/green]")
             for uuid in uuids:
                 console.print(f"  - {uuid}")
+            if result["truncated"]:
+                console.print("[yellow]Result truncated by node limit[/yellow]")
         else:
             console.print("[yellow]No connected entities found[/yellow]")
     except Exception as e:
@@ -0,0 +1,251 @@
+//! Compare the entities of two databases.
+//!
+//! Used by `rem diff` to verify replicas and migrations. Each database is
+//! scanned once per tenant, keeping only a BLAKE3 hash of every entity's
+//! content, so memory grows with entity count rather than data size.
+//! Entities whose hashes differ are then fetched from both sides with
+//! `get_batch` (RocksDB multi-get) to name the fields that changed.
+//!
+//! Content is the properties, inline edges, and deleted state. Timestamps
+//! are ignored: replays and migrations legitimately rewrite them.
+
+use crate::database::Database;
+use crate::types::{Entity, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
+
+/// Entities fetched per multi-get when resolving changed fields.
+const DIFF_FETCH_BATCH_SIZE: usize = 256;
+
+/// An entity present in both databases with different content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedEntity {
+    pub id: Uuid,
+    /// Differing properties, plus `edges`/`deleted_at` if those differ
+    pub fields: Vec<String>,
+}
+
+/// Differences within one table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableDiff {
+    pub table: String,
+    /// Entities only in the first database
+    pub only_in_a: Vec<Uuid>,
+    /// Entities only in the second database
+    pub only_in_b: Vec<Uuid>,
+    /// Entities in both with different content
+    pub changed: Vec<ChangedEntity>,
+    /// Entities in both with identical content
+    pub unchanged: usize,
+}
+
+impl TableDiff {
+    /// Whether the table is identical in both databases.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Differences between two databases, per table.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatabaseDiff {
+    /// Tables in name order (identical tables included)
+    pub tables: Vec<TableDiff>,
+}
+
+impl DatabaseDiff {
+    /// Whether every compared table is identical.
+    pub fn is_identical(&self) -> bool {
+        self.tables.iter().all(TableDiff::is_empty)
+    }
+
+    /// Get the diff of one table.
+    pub fn table(&self, name: &str) -> Option<&TableDiff> {
+        self.tables.iter().find(|t| t.table == name)
+    }
+}
+
+/// Compare the entities of two databases.
+///
+/// Soft-deleted entities are included, so a delete that didn't replicate
+/// shows up as a change.
+///
+/// # Arguments
+///
+/// * `a` - First database
+/// * `b` - Second database
+/// * `tenant_id` - Tenant to compare
+/// * `table` - Only this table (`None` = every table in either database)
+///
+/// # Returns
+///
+/// Per-table differences
+///
+/// # Errors
+///
+/// Returns `DatabaseError::StorageError` if a scan fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let diff = diff_databases(&primary, &replica, "default", None)?;
+/// if !diff.is_identical() {
+///     for table in &diff.tables {
+///         println!("{}: {} changed", table.table, table.changed.len());
+///     }
+/// }
+/// ```
+pub fn diff_databases(a: &Database, b: &Database, tenant_id: &str, table: Option<&str>) -> Result<DatabaseDiff> {
+    let hashes_a = content_hashes(a, tenant_id, table)?;
+    let hashes_b = content_hashes(b, tenant_id, table)?;
+
+    let tables: BTreeSet<&String> = hashes_a.keys().chain(hashes_b.keys()).collect();
+    let empty = HashMap::new();
+
+    let mut diff = DatabaseDiff::default();
+    for name in tables {
+        let in_a = hashes_a.get(name).unwrap_or(&empty);
+        let in_b = hashes_b.get(name).unwrap_or(&empty);
+
+        let mut table_diff = TableDiff {
+            table: name.clone(),
+            ..Default::default()
+        };
+        let mut changed_ids = Vec::new();
+
+        for (id, hash) in in_a {
+            match in_b.get(id) {
+                None => table_diff.only_in_a.push(*id),
+                Some(other) if other != hash => changed_ids.push(*id),
+                Some(_) => table_diff.unchanged += 1,
+            }
+        }
+        table_diff.only_in_b = in_b.keys().filter(|id| !in_a.contains_key(id)).copied().collect();
+
+        table_diff.only_in_a.sort();
+        table_diff.only_in_b.sort();
+        changed_ids.sort();
+
+        for ids in changed_ids.chunks(DIFF_FETCH_BATCH_SIZE) {
+            let entities_a = a.get_batch(tenant_id, ids)?;
+            let entities_b = b.get_batch(tenant_id, ids)?;
+
+            for ((id, entity_a), entity_b) in ids.iter().zip(entities_a).zip(entities_b) {
+                let fields = match (entity_a, entity_b) {
+                    (Some(ea), Some(eb)) => changed_fields(&ea, &eb),
+                    // Written between the scan and the fetch
+                    _ => Vec::new(),
+                };
+                table_diff.changed.push(ChangedEntity { id: *id, fields });
+            }
+        }
+
+        diff.tables.push(table_diff);
+    }
+
+    Ok(diff)
+}
+
+/// Scan a tenant's entities into `table -> (id -> content hash)`.
+fn content_hashes(db: &Database, tenant_id: &str, table: Option<&str>) -> Result<BTreeMap<String, HashMap<Uuid, [u8; 32]>>> {
+    let prefix = format!("entity:{}:", tenant_id).into_bytes();
+
+    let mut hashes: BTreeMap<String, HashMap<Uuid, [u8; 32]>> = BTreeMap::new();
+    for item in db.provider().iterator(crate::storage::column_families::CF_ENTITIES, &prefix) {
+        let (_, value) = item?;
+        let entity: Entity = serde_json::from_slice(&value)?;
+        if table.is_some_and(|t| t != entity.system.entity_type) {
+            continue;
+        }
+
+        hashes
+            .entry(entity.system.entity_type.clone())
+            .or_default()
+            .insert(entity.system.id, content_hash(&entity)?);
+    }
+
+    Ok(hashes)
+}
+
+/// Hash the compared content of an entity.
+fn content_hash(entity: &Entity) -> Result<[u8; 32]> {
+    let content = serde_json::to_vec(&(
+        &entity.properties,
+        &entity.system.edges,
+        entity.system.deleted_at.is_some(),
+    ))?;
+    Ok(*blake3::hash(&content).as_bytes())
+}
+
+/// Name the content fields that differ between two versions of an entity.
+fn changed_fields(a: &Entity, b: &Entity) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let props_a = a.properties.as_object().unwrap_or(&empty);
+    let props_b = b.properties.as_object().unwrap_or(&empty);
+
+    let keys: BTreeSet<&String> = props_a.keys().chain(props_b.keys()).collect();
+    let mut fields: Vec<String> = keys
+        .into_iter()
+        .filter(|key| props_a.get(*key) != props_b.get(*key))
+        .cloned()
+        .collect();
+
+    if a.system.edges != b.system.edges {
+        fields.push("edges".to_string());
+    }
+    if a.is_deleted() != b.is_deleted() {
+        fields.push("deleted_at".to_string());
+    }
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn open_with_schema() -> Database {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("articles", json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {
+                "title": {"type": "string"},
+                "views": {"type": "integer"}
+            },
+            "required": ["title"],
+            "json_schema_extra": {"key_field": "title"}
+        })).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_diff_databases() {
+        let a = open_with_schema();
+        let b = open_with_schema();
+
+        for db in [&a, &b] {
+            db.insert("default", "articles", json!({"title": "Same", "views": 1})).unwrap();
+        }
+        let modified = a.insert("default", "articles", json!({"title": "Edited", "views": 1})).unwrap();
+        b.insert("default", "articles", json!({"title": "Edited", "views": 2})).unwrap();
+        let extra = a.insert("default", "articles", json!({"title": "Extra", "views": 1})).unwrap();
+
+        let diff = diff_databases(&a, &b, "default", None).unwrap();
+        assert!(!diff.is_identical());
+
+        let articles = diff.table("articles").unwrap();
+        assert_eq!(articles.only_in_a, vec![extra]);
+        assert!(articles.only_in_b.is_empty());
+        assert_eq!(articles.changed, vec![ChangedEntity { id: modified, fields: vec!["views".to_string()] }]);
+        assert_eq!(articles.unchanged, 1);
+
+        // Reversed, the extra entity is on the other side
+        let reversed = diff_databases(&b, &a, "default", Some("articles")).unwrap();
+        assert_eq!(reversed.table("articles").unwrap().only_in_b, vec![extra]);
+
+        assert!(diff_databases(&a, &a, "default", None).unwrap().is_identical());
+    }
+}
@@ -7,6 +7,7 @@
 //! - **Indexing**: Rebuild HNSW vector indexes and field indexes
 //! - **Verification**: Database integrity checks and validation
 //! - **Statistics**: Database size, entity counts, performance metrics
+//! - **Diff**: Compare the entities of two databases (replicas, migrations)
 //!
 //! # Architecture
 //!
@@ -79,6 +80,7 @@ pub mod vacuum;
 pub mod indexing;
 pub mod verification;
 pub mod statistics;
+pub mod diff;
 
 pub use backup::{BackupManager, BackupMetadata, BackupRef, RestoreOptions};
 pub use compaction::{CompactionManager, CompactionStats};
@@ -86,3 +88,4 @@ pub use vacuum::{VacuumManager, VacuumStats};
 pub use indexing::{IndexManager, IndexRebuildOptions};
 pub use verification::{VerificationManager, VerificationReport};
 pub use statistics::{StatisticsManager, DatabaseStats};
+pub use diff::{diff_databases, ChangedEntity, DatabaseDiff, TableDiff};
@@ -3,7 +3,7 @@
 //! This module provides:
 //! - **Vacuum deleted entities**: Remove entities with `deleted_at` set
 //! - **Vacuum old versions**: Remove superseded entity versions (if versioning enabled)
-//! - **Vacuum orphaned data**: Clean up embeddings/indexes for deleted entities
+//! - **Vacuum orphaned data**: Clean up embeddings/indexes/blobs for deleted entities
 //! - **Statistics**: Space reclaimed, entities removed
 //!
 //! # Soft Delete Model
@@ -113,6 +113,7 @@ impl VacuumManager {
             3. For each entity:
                - Delete from entities CF
                - Delete embeddings (if exists)
+               - Delete blob from blobs CF (if exists)
                - Delete edges (forward and reverse)
                - Delete from indexes
             4. Return VacuumStats with counts and size"
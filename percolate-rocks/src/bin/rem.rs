@@ -4,7 +4,9 @@
 
 use clap::{Parser, Subcommand};
 use percolate_rocks::database::Database;
-use std::path::PathBuf;
+use percolate_rocks::storage::StorageProvider;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// REM Database CLI - Resources-Entities-Moments
 #[derive(Parser)]
@@ -44,6 +46,10 @@ enum Commands {
         /// Batch insert from stdin (JSONL format)
         #[arg(long)]
         batch: bool,
+
+        /// Register a schema inferred from the data if the table doesn't exist
+        #[arg(long)]
+        infer_schema: bool,
     },
 
     /// Get entity by UUID
@@ -181,6 +187,10 @@ enum Commands {
         /// Show query plan without executing
         #[arg(long)]
         plan: bool,
+
+        /// Show query plan, then run each stage and report result counts
+        #[arg(long, conflicts_with = "plan")]
+        explain: bool,
     },
 
     /// Graph traversal
@@ -195,6 +205,23 @@ enum Commands {
         /// Direction: in, out, both
         #[arg(long, default_value = "out")]
         direction: String,
+
+        /// Only follow edges of this type (repeat or comma-separate for several)
+        #[arg(long = "rel-type", value_delimiter = ',')]
+        rel_types: Vec<String>,
+    },
+
+    /// Stream live insert/update/delete events (Ctrl-C to stop)
+    ///
+    /// Follows writes made by other processes through a read-only secondary
+    /// instance, polling for changes.
+    Watch {
+        /// Table to watch (default: all tables)
+        table: Option<String>,
+
+        /// Milliseconds between polls
+        #[arg(long, default_value = "500")]
+        interval_ms: u64,
     },
 
     /// Export data
@@ -219,6 +246,23 @@ enum Commands {
         include_deleted: bool,
     },
 
+    /// Compare the entities of two databases (exits 1 if they differ)
+    Diff {
+        /// First database path
+        db_a: PathBuf,
+
+        /// Second database path
+        db_b: PathBuf,
+
+        /// Only compare this table
+        #[arg(long)]
+        table: Option<String>,
+
+        /// List differing entity IDs and changed fields
+        #[arg(long)]
+        detailed: bool,
+    },
+
     /// Start replication server
     Serve {
         /// Host to bind
@@ -228,6 +272,10 @@ enum Commands {
         /// Port to bind
         #[arg(long, env = "P8_REPLICATION_PORT", default_value = "50051")]
         port: u16,
+
+        /// Serve Prometheus metrics at /metrics on this port
+        #[arg(long, env = "P8_METRICS_PORT")]
+        metrics_port: Option<u16>,
     },
 
     /// Replicate from primary
@@ -332,6 +380,8 @@ enum ReplicationCommands {
 }
 
 fn main() -> anyhow::Result<()> {
+    percolate_rocks::otel::init_logging();
+
     let cli = Cli::parse();
 
     // Expand ~ in path
@@ -362,8 +412,8 @@ fn main() -> anyhow::Result<()> {
                 cmd_schema_templates()?;
             }
         },
-        Commands::Insert { table, json, batch } => {
-            cmd_insert(&db_path, &table, json.as_deref(), batch)?;
+        Commands::Insert { table, json, batch, infer_schema } => {
+            cmd_insert(&db_path, &table, json.as_deref(), batch, infer_schema)?;
         }
         Commands::Get { uuid } => {
             cmd_get(&db_path, &uuid)?;
@@ -405,21 +455,28 @@ fn main() -> anyhow::Result<()> {
         Commands::Query { sql } => {
             cmd_query(&db_path, &sql)?;
         }
-        Commands::Ask { question, plan } => {
-            cmd_ask(&db_path, &question, plan)?;
+        Commands::Ask { question, plan, explain } => {
+            cmd_ask(&db_path, &question, plan, explain)?;
         }
         Commands::Traverse {
             uuid,
             depth,
             direction,
+            rel_types,
         } => {
-            cmd_traverse(&db_path, &uuid, depth, &direction)?;
+            cmd_traverse(&db_path, &uuid, depth, &direction, rel_types)?;
+        }
+        Commands::Watch { table, interval_ms } => {
+            cmd_watch(&db_path, table.as_deref(), interval_ms)?;
         }
         Commands::Export { table, all, output, format, include_deleted } => {
             cmd_export(&db_path, table.as_deref(), all, &output, &format, include_deleted)?;
         }
-        Commands::Serve { host, port } => {
-            cmd_serve(&db_path, &host, port)?;
+        Commands::Diff { db_a, db_b, table, detailed } => {
+            cmd_diff(&db_a, &db_b, table.as_deref(), detailed)?;
+        }
+        Commands::Serve { host, port, metrics_port } => {
+            cmd_serve(&db_path, &host, port, metrics_port)?;
         }
         Commands::Replicate { primary, follow } => {
             cmd_replicate(&db_path, &primary, follow)?;
@@ -468,6 +525,25 @@ fn main() -> anyhow::Result<()> {
 // IMPLEMENTED COMMANDS
 // ============================================================================
 
+/// PostgreSQL entity backend, if `P8_POSTGRES_URL` is set (requires the
+/// `postgres` feature).
+fn postgres_provider() -> anyhow::Result<Option<Arc<dyn StorageProvider>>> {
+    #[cfg(feature = "postgres")]
+    if std::env::var_os("P8_POSTGRES_URL").is_some() {
+        let provider = percolate_rocks::storage::PostgresProvider::from_env("default")?;
+        return Ok(Some(Arc::new(provider)));
+    }
+    Ok(None)
+}
+
+/// Open the database, keeping entities in PostgreSQL when configured.
+fn open_db<P: AsRef<Path>>(path: P) -> anyhow::Result<Database> {
+    Ok(match postgres_provider()? {
+        Some(provider) => Database::open_with_provider(path, provider)?,
+        None => Database::open(path)?,
+    })
+}
+
 fn cmd_init(path: &PathBuf) -> anyhow::Result<()> {
     println!("Initializing database at: {}", path.display());
 
@@ -475,7 +551,7 @@ fn cmd_init(path: &PathBuf) -> anyhow::Result<()> {
     std::fs::create_dir_all(path)?;
 
     // Open database (will create if doesn't exist)
-    let db = Database::open(path)?;
+    let db = open_db(path)?;
 
     println!("✓ Database initialized successfully");
     println!("  Path: {}", path.display());
@@ -491,8 +567,9 @@ fn cmd_insert(
     table: &str,
     json: Option<&str>,
     batch: bool,
+    infer_schema: bool,
 ) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     if batch {
         println!("Batch insert not yet implemented");
@@ -504,6 +581,12 @@ fn cmd_insert(
         // Parse JSON
         let data: serde_json::Value = serde_json::from_str(json_data)?;
 
+        if infer_schema && !db.has_schema(table) {
+            let schema = percolate_rocks::schema::infer_schema(table, std::slice::from_ref(&data))?;
+            db.register_schema(table, schema)?;
+            println!("✓ Registered inferred schema '{}'", table);
+        }
+
         // Insert entity (with schema validation)
         let id = db.insert("default", table, data)?;
 
@@ -518,7 +601,7 @@ fn cmd_insert(
 }
 
 fn cmd_get(db_path: &PathBuf, uuid_str: &str) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUID
     let id = uuid::Uuid::parse_str(uuid_str)?;
@@ -537,7 +620,7 @@ fn cmd_get(db_path: &PathBuf, uuid_str: &str) -> anyhow::Result<()> {
 }
 
 fn cmd_lookup(db_path: &PathBuf, key: &str) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Get all schemas to search across
     let schemas = db.list_schemas()?;
@@ -579,7 +662,7 @@ fn cmd_add_edge(
     rel_type: &str,
     properties_str: Option<&str>,
 ) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUIDs
     let src_id = uuid::Uuid::parse_str(src_str)?;
@@ -618,7 +701,7 @@ fn cmd_get_edges(
     rel_type: Option<&str>,
     direction: &str,
 ) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUID
     let id = uuid::Uuid::parse_str(uuid_str)?;
@@ -670,7 +753,7 @@ fn cmd_delete_edge(
     dst_str: &str,
     rel_type: &str,
 ) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUIDs
     let src_id = uuid::Uuid::parse_str(src_str)?;
@@ -688,7 +771,7 @@ fn cmd_delete_edge(
 }
 
 fn cmd_update(db_path: &PathBuf, uuid_str: &str, updates_str: &str) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUID
     let id = uuid::Uuid::parse_str(uuid_str)?;
@@ -713,7 +796,7 @@ fn cmd_update(db_path: &PathBuf, uuid_str: &str, updates_str: &str) -> anyhow::R
 }
 
 fn cmd_delete(db_path: &PathBuf, uuid_str: &str, hard: bool) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUID
     let id = uuid::Uuid::parse_str(uuid_str)?;
@@ -741,10 +824,10 @@ fn cmd_list(
     include_deleted: bool,
     limit: Option<usize>,
 ) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // List entities
-    let entities = db.list("default", table, include_deleted, limit)?;
+    let entities = db.list("default", table, include_deleted, limit, None)?;
 
     println!("Entities in '{}' (found: {})", table, entities.len());
     println!();
@@ -766,7 +849,7 @@ fn cmd_list(
 }
 
 fn cmd_count(db_path: &PathBuf, table: &str, include_deleted: bool) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Count entities
     let count = db.count("default", table, include_deleted)?;
@@ -789,7 +872,7 @@ fn cmd_schema_add(
     template: Option<String>,
     output: Option<PathBuf>,
 ) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Handle file-based schema registration
     if let Some(schema_file) = file {
@@ -860,7 +943,7 @@ fn cmd_schema_add(
 }
 
 fn cmd_schema_list(db_path: &PathBuf) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
     let schemas = db.list_schemas()?;
 
     println!("Registered schemas ({}):", schemas.len());
@@ -872,7 +955,7 @@ fn cmd_schema_list(db_path: &PathBuf) -> anyhow::Result<()> {
 }
 
 fn cmd_schema_show(db_path: &PathBuf, name: &str) -> anyhow::Result<()> {
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
     let schema = db.get_schema(name)?;
 
     println!("{}", serde_json::to_string_pretty(&schema)?);
@@ -893,7 +976,7 @@ fn cmd_ingest(db_path: &PathBuf, file: &PathBuf, schema: &str) -> anyhow::Result
     use std::io::{BufRead, BufReader};
     use std::fs::File;
 
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Verify schema exists
     if !db.has_schema(schema) {
@@ -1002,7 +1085,7 @@ fn cmd_search(
 ) -> anyhow::Result<()> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
-        let db = Database::open(db_path)?;
+        let db = open_db(db_path)?;
 
         // Perform semantic search
         let results = db.search("default", schema, query, top_k).await?;
@@ -1015,8 +1098,9 @@ fn cmd_search(
         println!("Found {} result(s) for: {}", results.len(), query);
         println!();
 
-        for (i, (entity, score)) in results.iter().enumerate() {
-            println!("{}. Score: {:.4}", i + 1, score);
+        for hit in results.iter() {
+            let entity = &hit.entity;
+            println!("{}. Score: {:.4}", hit.rank + 1, hit.score);
             println!("   ID: {}", entity.system.id);
             println!("   Type: {}", entity.system.entity_type);
             println!("   Created: {}", entity.system.created_at);
@@ -1046,7 +1130,7 @@ fn cmd_query(db_path: &PathBuf, sql: &str) -> anyhow::Result<()> {
 
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
-        let db = Database::open(db_path)?;
+        let db = open_db(db_path)?;
 
         // Parse and execute extended SQL syntax
         match parse_extended_query(sql) {
@@ -1074,8 +1158,9 @@ fn cmd_query(db_path: &PathBuf, sql: &str) -> anyhow::Result<()> {
                     println!("Found {} result(s)", results.len());
                     println!();
 
-                    for (i, (entity, score)) in results.iter().enumerate() {
-                        println!("{}. Score: {:.4}", i + 1, score);
+                    for hit in results.iter() {
+                        let entity = &hit.entity;
+                        println!("{}. Score: {:.4}", hit.rank + 1, hit.score);
                         println!("   ID: {}", entity.system.id);
                         println!("   Type: {}", entity.system.entity_type);
 
@@ -1114,9 +1199,10 @@ fn cmd_query(db_path: &PathBuf, sql: &str) -> anyhow::Result<()> {
                     percolate_rocks::query::TraverseDirection::Both => TraversalDirection::Both,
                 };
 
-                let nodes = db.traverse_bfs(start_id, direction, traverse.depth, traverse.rel_type.as_deref())?;
+                let result = db.traverse_bfs(start_id, direction, traverse.depth, traverse.rel_type.clone().map(|t| vec![t]))?;
+                let nodes = &result.nodes;
 
-                println!("Found {} node(s)", nodes.len());
+                println!("Found {} node(s){}", nodes.len(), if result.truncated { " (truncated)" } else { "" });
                 println!();
 
                 for (i, node_id) in nodes.iter().enumerate() {
@@ -1147,12 +1233,12 @@ fn cmd_query(db_path: &PathBuf, sql: &str) -> anyhow::Result<()> {
     })
 }
 
-fn cmd_ask(db_path: &PathBuf, question: &str, plan: bool) -> anyhow::Result<()> {
+fn cmd_ask(db_path: &PathBuf, question: &str, plan: bool, explain: bool) -> anyhow::Result<()> {
     use percolate_rocks::llm::query_builder::LlmQueryBuilder;
 
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
-        let db = Database::open(db_path)?;
+        let db = open_db(db_path)?;
 
         // Build query builder from environment
         let query_builder = LlmQueryBuilder::from_env()
@@ -1160,7 +1246,32 @@ fn cmd_ask(db_path: &PathBuf, question: &str, plan: bool) -> anyhow::Result<()>
 
         // Get schema context
         let schemas = db.list_schemas()?;
-        let schema_context = format!("Available schemas: {}", schemas.join(", "));
+        let brute_force_limit = std::env::var("P8_SCHEMA_BRUTE_FORCE_LIMIT")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(10);
+
+        let schema_context = if schemas.len() > brute_force_limit {
+            // Too many schemas to list: offer only those related to the question
+            let candidates = detect_schemas(&db, question).await?;
+            if candidates.is_empty() {
+                println!("No schema looks related to this question. Which kind of data do you mean?");
+                println!("Available schemas: {}", schemas.join(", "));
+                return Ok(());
+            }
+            let names: Vec<&str> = candidates.iter().map(|(name, _)| name.as_str()).collect();
+            format!("Relevant schemas: {}", names.join(", "))
+        } else {
+            format!("Available schemas: {}", schemas.join(", "))
+        };
+
+        if explain {
+            // Plan, then replay the plan stage by stage
+            let explanation = db.explain_ask(&query_builder, "default", question, &schema_context).await
+                .map_err(|e| anyhow::anyhow!("Query planning failed: {}", e))?;
+            print!("{}", explanation);
+            return Ok(());
+        }
 
         // Plan query
         let query_plan = query_builder.plan_query(question, &schema_context).await
@@ -1214,8 +1325,9 @@ fn cmd_ask(db_path: &PathBuf, question: &str, plan: bool) -> anyhow::Result<()>
                     println!("Found {} result(s)", results.len());
                     println!();
 
-                    for (i, (entity, score)) in results.iter().enumerate() {
-                        println!("{}. Score: {:.4}", i + 1, score);
+                    for hit in results.iter() {
+                        let entity = &hit.entity;
+                        println!("{}. Score: {:.4}", hit.rank + 1, hit.score);
                         println!("   ID: {}", entity.system.id);
                         println!("   Type: {}", entity.system.entity_type);
 
@@ -1246,9 +1358,10 @@ fn cmd_ask(db_path: &PathBuf, question: &str, plan: bool) -> anyhow::Result<()>
                     percolate_rocks::query::TraverseDirection::Both => TraversalDirection::Both,
                 };
 
-                let nodes = db.traverse_bfs(start_id, direction, traverse.depth, traverse.rel_type.as_deref())?;
+                let result = db.traverse_bfs(start_id, direction, traverse.depth, traverse.rel_type.clone().map(|t| vec![t]))?;
+                let nodes = &result.nodes;
 
-                println!("Found {} node(s)", nodes.len());
+                println!("Found {} node(s){}", nodes.len(), if result.truncated { " (truncated)" } else { "" });
                 for (i, node_id) in nodes.iter().enumerate() {
                     if let Some(entity) = db.get("default", *node_id)? {
                         println!("{}. ID: {} ({})", i + 1, node_id, entity.system.entity_type);
@@ -1269,15 +1382,44 @@ fn cmd_ask(db_path: &PathBuf, question: &str, plan: bool) -> anyhow::Result<()>
     })
 }
 
+/// Find schemas whose descriptions match a question (`rem ask` routing).
+///
+/// Returns nothing if every schema scores below `P8_SCHEMA_MIN_SIMILARITY`.
+async fn detect_schemas(db: &Database, question: &str) -> anyhow::Result<Vec<(String, f32)>> {
+    use percolate_rocks::embeddings::ProviderFactory;
+    use percolate_rocks::schema::DEFAULT_SCHEMA_MIN_SIMILARITY;
+
+    let top_k = std::env::var("P8_SCHEMA_SEARCH_TOP_K")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(3);
+    let min_similarity = std::env::var("P8_SCHEMA_MIN_SIMILARITY")
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .unwrap_or(DEFAULT_SCHEMA_MIN_SIMILARITY);
+
+    let provider = std::env::var("P8_DEFAULT_EMBEDDING")
+        .unwrap_or_else(|_| "openai:text-embedding-3-small".to_string());
+    let embedder = ProviderFactory::create(&provider)?;
+
+    // Clone so the lock isn't held while embedding
+    let registry = db.registry().read()
+        .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?
+        .clone();
+
+    Ok(registry.auto_detect_schema(question, top_k, min_similarity, &*embedder).await?)
+}
+
 fn cmd_traverse(
     db_path: &PathBuf,
     uuid_str: &str,
     depth: usize,
     direction_str: &str,
+    rel_types: Vec<String>,
 ) -> anyhow::Result<()> {
     use percolate_rocks::graph::TraversalDirection;
 
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Parse UUID
     let start_id = uuid::Uuid::parse_str(uuid_str)?;
@@ -1291,10 +1433,15 @@ fn cmd_traverse(
     };
 
     // Perform BFS traversal
-    let nodes = db.traverse_bfs(start_id, direction, depth, None)?;
+    let filter = (!rel_types.is_empty()).then(|| rel_types.clone());
+    let result = db.traverse_bfs(start_id, direction, depth, filter)?;
+    let nodes = &result.nodes;
 
     println!("Graph traversal from {} (depth: {}, direction: {})", start_id, depth, direction_str);
-    println!("Found {} node(s)", nodes.len());
+    if !rel_types.is_empty() {
+        println!("Following: {}", rel_types.join(", "));
+    }
+    println!("Found {} node(s){}", nodes.len(), if result.truncated { " (truncated)" } else { "" });
     println!();
 
     // Get and display each entity
@@ -1332,7 +1479,7 @@ fn cmd_export(
 ) -> anyhow::Result<()> {
     use percolate_rocks::export::{JsonlExporter, CsvExporter, ParquetExporter};
 
-    let db = Database::open(db_path)?;
+    let db = open_db(db_path)?;
 
     // Determine which tables to export
     let tables_to_export: Vec<String> = if all {
@@ -1347,7 +1494,7 @@ fn cmd_export(
     let mut all_entities = Vec::new();
 
     for table_name in &tables_to_export {
-        let entities = db.list("default", table_name, include_deleted, None)?;
+        let entities = db.list("default", table_name, include_deleted, None, None)?;
         all_entities.extend(entities);
     }
 
@@ -1383,14 +1530,107 @@ fn cmd_export(
     Ok(())
 }
 
-fn cmd_serve(_db_path: &PathBuf, host: &str, port: u16) -> anyhow::Result<()> {
-    println!("Replication server not yet implemented");
-    println!("  Host: {}", host);
-    println!("  Port: {}", port);
-    println!("\nRequires: gRPC server, WAL streaming");
+fn cmd_watch(db_path: &PathBuf, table: Option<&str>, interval_ms: u64) -> anyhow::Result<()> {
+    // Secondary instances don't take the lock, so writers keep running
+    let secondary_path = std::env::temp_dir().join(format!("rem-watch-{}", std::process::id()));
+    let db = Arc::new(match postgres_provider()? {
+        // Entities are read from PostgreSQL, so the local side is scratch
+        Some(provider) => Database::open_with_provider(&secondary_path, provider)?,
+        None => Database::open_secondary(db_path, &secondary_path)?,
+    });
+
+    println!("Watching changes in {} (Ctrl-C to stop)", table.unwrap_or("all tables"));
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let count = rt.block_on(async {
+        let shutdown = async {
+            tokio::signal::ctrl_c().await.ok();
+        };
+        let interval = std::time::Duration::from_millis(interval_ms.max(1));
+        percolate_rocks::changes::watch(Arc::clone(&db), "default", table, interval, &mut std::io::stdout(), shutdown).await
+    });
+
+    drop(db);
+    std::fs::remove_dir_all(&secondary_path).ok();
+    let count = count?;
+
+    println!("\nStopped after {} event(s)", count);
+
     Ok(())
 }
 
+fn cmd_diff(db_a: &PathBuf, db_b: &PathBuf, table: Option<&str>, detailed: bool) -> anyhow::Result<()> {
+    use percolate_rocks::admin::diff_databases;
+
+    let open = |path: &PathBuf| open_db(shellexpand::tilde(&path.to_string_lossy()).to_string());
+    let a = open(db_a)?;
+    let b = open(db_b)?;
+
+    let diff = diff_databases(&a, &b, "default", table)?;
+
+    println!("A: {}", db_a.display());
+    println!("B: {}", db_b.display());
+    println!();
+    println!("{:<24} {:>10} {:>10} {:>10} {:>10}", "TABLE", "ONLY A", "ONLY B", "CHANGED", "SAME");
+    for t in &diff.tables {
+        println!(
+            "{:<24} {:>10} {:>10} {:>10} {:>10}",
+            t.table,
+            t.only_in_a.len(),
+            t.only_in_b.len(),
+            t.changed.len(),
+            t.unchanged
+        );
+    }
+
+    if detailed {
+        for t in diff.tables.iter().filter(|t| !t.is_empty()) {
+            println!("\n{}:", t.table);
+            for id in &t.only_in_a {
+                println!("  - {}  (only in A)", id);
+            }
+            for id in &t.only_in_b {
+                println!("  + {}  (only in B)", id);
+            }
+            for changed in &t.changed {
+                println!("  ~ {}  ({})", changed.id, changed.fields.join(", "));
+            }
+        }
+    }
+
+    if diff.is_identical() {
+        println!("\n✓ Databases are identical");
+        Ok(())
+    } else {
+        println!("\n✗ Databases differ");
+        std::process::exit(1);
+    }
+}
+
+fn cmd_serve(db_path: &PathBuf, host: &str, port: u16, metrics_port: Option<u16>) -> anyhow::Result<()> {
+    let metrics_port = match metrics_port {
+        Some(p) => p,
+        None => {
+            println!("Replication server not yet implemented");
+            println!("  Host: {}", host);
+            println!("  Port: {}", port);
+            println!("\nRequires: gRPC server, WAL streaming");
+            return Ok(());
+        }
+    };
+
+    let db = Arc::new(open_db(db_path)?);
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let listener = tokio::net::TcpListener::bind((host, metrics_port)).await?;
+        println!("Serving metrics at http://{}/metrics", listener.local_addr()?);
+
+        percolate_rocks::otel::serve_metrics(listener, db, percolate_rocks::otel::metrics()).await?;
+        Ok(())
+    })
+}
+
 fn cmd_replicate(_db_path: &PathBuf, primary: &str, follow: bool) -> anyhow::Result<()> {
     println!("Replication client not yet implemented");
     println!("  Primary: {}", primary);
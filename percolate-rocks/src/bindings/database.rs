@@ -4,6 +4,7 @@ use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 use crate::database::Database as RustDatabase;
 use crate::types::Entity;
+use super::types::PyEntity;
 use std::sync::Arc;
 use std::path::PathBuf;
 
@@ -97,6 +98,40 @@ fn chunk_text(text: &str, min_chars: usize, max_chars: usize) -> Vec<String> {
     chunks
 }
 
+/// Python iterator over streamed SQL results (see `Database.query_iter`).
+#[pyclass(name = "QueryIterator")]
+pub struct PyQueryIter {
+    db: Arc<RustDatabase>,
+    cursor: crate::query::SqlCursor,
+    /// Rows of the current page not yet yielded
+    buffer: std::collections::VecDeque<serde_json::Value>,
+}
+
+#[pymethods]
+impl PyQueryIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        if slf.buffer.is_empty() {
+            let this = &mut *slf;
+            if let Some(page) = this.cursor.next_page(&this.db)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Query failed: {}", e)))?
+            {
+                this.buffer.extend(page);
+            }
+        }
+
+        match slf.buffer.pop_front() {
+            Some(row) => pythonize::pythonize(py, &row)
+                .map(Some)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert result: {}", e))),
+            None => Ok(None),
+        }
+    }
+}
+
 /// Python wrapper for Database.
 ///
 /// Exposes high-level API to Python with automatic type conversions.
@@ -208,16 +243,46 @@ impl PyDatabase {
         Ok(uuids.iter().map(|u| u.to_string()).collect())
     }
 
+    /// Validate entities against a schema without writing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Table/schema name
+    /// * `entities` - List of entity dicts
+    ///
+    /// # Returns
+    ///
+    /// Dict with `valid` (count of passing rows) and `errors`
+    /// (list of `[index, message]` for every failing row)
+    fn preflight(&self, py: Python<'_>, table: String, entities: &PyList) -> PyResult<PyObject> {
+        let mut entity_values = Vec::with_capacity(entities.len());
+
+        for item in entities.iter() {
+            let dict = item.downcast::<PyDict>()?;
+            let value: serde_json::Value = pythonize::depythonize(dict)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to convert data: {}", e)))?;
+            entity_values.push(value);
+        }
+
+        let report = self.inner.preflight(&table, &entity_values)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to preflight: {}", e)))?;
+
+        pythonize::pythonize(py, &report)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert report: {}", e)))
+    }
+
     /// Get entity by ID.
     ///
     /// # Arguments
     ///
     /// * `entity_id` - Entity UUID string
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) to each dict (default: true)
+    /// * `typed` - Return an `Entity` object instead of a dict (default: false)
     ///
     /// # Returns
     ///
-    /// Entity dict or None
-    fn get(&self, py: Python<'_>, entity_id: String) -> PyResult<Option<PyObject>> {
+    /// Entity dict (or `Entity` with `typed`) or None
+    fn get(&self, py: Python<'_>, entity_id: String, include_system: Option<bool>, typed: Option<bool>) -> PyResult<Option<PyObject>> {
         let uuid = uuid::Uuid::parse_str(&entity_id)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid UUID: {}", e)))?;
 
@@ -225,9 +290,57 @@ impl PyDatabase {
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to get entity: {}", e)))?;
 
         match entity {
+            Some(ent) if typed.unwrap_or(false) => Ok(Some(Py::new(py, PyEntity::from(ent))?.into_py(py))),
             Some(ent) => {
                 // Convert Entity to Python dict
-                let dict = entity_to_pydict(py, &ent)?;
+                let dict = entity_to_pydict(py, &ent, include_system.unwrap_or(true))?;
+                Ok(Some(dict.into()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Attach binary payload to an entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_id` - Entity UUID string
+    /// * `data` - Payload bytes
+    /// * `content_type` - MIME type (e.g. "image/png")
+    fn put_blob(&self, py: Python<'_>, entity_id: String, data: &[u8], content_type: String) -> PyResult<()> {
+        let uuid = uuid::Uuid::parse_str(&entity_id)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid UUID: {}", e)))?;
+
+        let inner = self.inner.clone();
+        let tenant_id = self.tenant_id.clone();
+        let data = data.to_vec();
+
+        py.allow_threads(move || inner.put_blob(&tenant_id, uuid, &data, &content_type))
+            .map(|_| ())
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to put blob: {}", e)))
+    }
+
+    /// Get binary payload attached to an entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_id` - Entity UUID string
+    ///
+    /// # Returns
+    ///
+    /// Dict with `data` (bytes) and `content_type`, or None
+    fn get_blob(&self, py: Python<'_>, entity_id: String) -> PyResult<Option<PyObject>> {
+        let uuid = uuid::Uuid::parse_str(&entity_id)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid UUID: {}", e)))?;
+
+        let blob = self.inner.get_blob(&self.tenant_id, uuid)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to get blob: {}", e)))?;
+
+        match blob {
+            Some(blob) => {
+                let dict = PyDict::new(py);
+                dict.set_item("data", pyo3::types::PyBytes::new(py, &blob.data))?;
+                dict.set_item("content_type", blob.content_type)?;
                 Ok(Some(dict.into()))
             }
             None => Ok(None),
@@ -239,6 +352,7 @@ impl PyDatabase {
     /// # Arguments
     ///
     /// * `entity_ids` - List of entity UUID strings
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) to each dict (default: true)
     ///
     /// # Returns
     ///
@@ -256,7 +370,7 @@ impl PyDatabase {
     /// entities = db.get_batch(ids)
     /// # entities[0] corresponds to uuid1, etc.
     /// ```
-    fn get_batch(&self, py: Python<'_>, entity_ids: Vec<String>) -> PyResult<Vec<Option<PyObject>>> {
+    fn get_batch(&self, py: Python<'_>, entity_ids: Vec<String>, include_system: Option<bool>) -> PyResult<Vec<Option<PyObject>>> {
         // Parse UUIDs
         let uuids: Result<Vec<_>, _> = entity_ids.iter()
             .map(|id| uuid::Uuid::parse_str(id))
@@ -277,7 +391,7 @@ impl PyDatabase {
         for entity_opt in entities {
             match entity_opt {
                 Some(entity) => {
-                    let dict = entity_to_pydict(py, &entity)?;
+                    let dict = entity_to_pydict(py, &entity, include_system.unwrap_or(true))?;
                     results.push(Some(dict.into()));
                 }
                 None => results.push(None),
@@ -292,6 +406,7 @@ impl PyDatabase {
     /// # Arguments
     ///
     /// * `key_values` - List of key values to lookup
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) to each dict (default: true)
     ///
     /// # Returns
     ///
@@ -311,7 +426,7 @@ impl PyDatabase {
     /// # results[1] = all entities with key "Bob"
     /// # results[2] = all entities with key "Charlie"
     /// ```
-    fn lookup_batch(&self, py: Python<'_>, key_values: Vec<String>) -> PyResult<Vec<Vec<PyObject>>> {
+    fn lookup_batch(&self, py: Python<'_>, key_values: Vec<String>, include_system: Option<bool>) -> PyResult<Vec<Vec<PyObject>>> {
         // Batch lookup from database
         let entities_by_key = self.inner.lookup_batch(&self.tenant_id, &key_values)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
@@ -323,7 +438,7 @@ impl PyDatabase {
         for entities in entities_by_key {
             let mut group = Vec::with_capacity(entities.len());
             for entity in entities {
-                let dict = entity_to_pydict(py, &entity)?;
+                let dict = entity_to_pydict(py, &entity, include_system.unwrap_or(true))?;
                 group.push(dict.into());
             }
             results.push(group);
@@ -339,6 +454,7 @@ impl PyDatabase {
     /// # Arguments
     ///
     /// * `key_value` - Key field value (e.g., "Alice", "alice@example.com")
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) to each dict (default: true)
     ///
     /// # Returns
     ///
@@ -358,21 +474,77 @@ impl PyDatabase {
     ///
     /// # Could return multiple entities if "Alice" exists in different schemas
     /// for entity in results:
-    ///     print(f"Found in {entity.get('id', 'unknown')}")
+    ///     print(f"Found {entity['_id']} in {entity['_entity_type']}")
     /// ```
-    fn lookup(&self, py: Python<'_>, key_value: String) -> PyResult<Vec<PyObject>> {
+    fn lookup(&self, py: Python<'_>, key_value: String, include_system: Option<bool>) -> PyResult<Vec<PyObject>> {
         let entities = self.inner.lookup_global(&self.tenant_id, &key_value)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to lookup: {}", e)))?;
 
         let mut results = Vec::new();
         for entity in entities {
-            let dict = entity_to_pydict(py, &entity)?;
+            let dict = entity_to_pydict(py, &entity, include_system.unwrap_or(true))?;
             results.push(dict.into());
         }
 
         Ok(results)
     }
 
+    /// Find entities similar to an existing one.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity_id` - Entity UUID string (must have an embedding)
+    /// * `top_k` - Maximum number of results
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) to each dict (default: true)
+    /// * `min_similarity` - Drop neighbors scoring below this floor (default: 0.0)
+    ///
+    /// # Returns
+    ///
+    /// List of dicts with `entity`, `score`, `rank`, and `raw_distance` keys,
+    /// best first, never including the entity itself
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// for hit in db.more_like_this(article_id, 5, min_similarity=0.8):
+    ///     print(hit["entity"]["title"], hit["score"])
+    /// ```
+    fn more_like_this(
+        &self,
+        py: Python<'_>,
+        entity_id: String,
+        top_k: usize,
+        include_system: Option<bool>,
+        min_similarity: Option<f32>,
+    ) -> PyResult<Vec<PyObject>> {
+        let uuid = uuid::Uuid::parse_str(&entity_id)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid UUID: {}", e)))?;
+
+        let inner = self.inner.clone();
+        let tenant_id = self.tenant_id.clone();
+
+        let results = py.allow_threads(|| {
+            tokio::runtime::Runtime::new()
+                .unwrap()
+                .block_on(async {
+                    inner.more_like_this(&tenant_id, uuid, top_k, min_similarity.unwrap_or(0.0)).await
+                })
+        })
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("More like this failed: {}", e)))?;
+
+        let mut py_results = Vec::new();
+        for hit in results {
+            let dict = PyDict::new(py);
+            dict.set_item("entity", entity_to_pydict(py, &hit.entity, include_system.unwrap_or(true))?)?;
+            dict.set_item("score", hit.score)?;
+            dict.set_item("rank", hit.rank)?;
+            dict.set_item("raw_distance", hit.raw_distance)?;
+            py_results.push(dict.into());
+        }
+
+        Ok(py_results)
+    }
+
     /// Search entities by semantic similarity.
     ///
     /// # Arguments
@@ -380,11 +552,30 @@ impl PyDatabase {
     /// * `query` - Search query text
     /// * `schema` - Schema name to search
     /// * `top_k` - Number of results
+    /// * `mode` - "fast", "balanced" (default), or "accurate"
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) to each dict (default: true)
+    /// * `typed` - Return each `entity` as an `Entity` object instead of a dict (default: false)
     ///
     /// # Returns
     ///
-    /// List of (entity, score) tuples
-    fn search(&self, py: Python<'_>, query: String, schema: String, top_k: usize) -> PyResult<Vec<PyObject>> {
+    /// List of dicts with `entity`, `score` (similarity in [0, 1]), `rank`
+    /// (0-based), and `raw_distance` (index distance) keys, best first
+    fn search(
+        &self,
+        py: Python<'_>,
+        query: String,
+        schema: String,
+        top_k: usize,
+        mode: Option<String>,
+        include_system: Option<bool>,
+        typed: Option<bool>,
+    ) -> PyResult<Vec<PyObject>> {
+        let mode = match mode {
+            Some(mode) => crate::index::SearchMode::parse(&mode)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?,
+            None => crate::index::SearchMode::default(),
+        };
+
         // Search is async, need to run in async runtime
         let inner = self.inner.clone();
         let tenant_id = self.tenant_id.clone();
@@ -393,17 +584,24 @@ impl PyDatabase {
             tokio::runtime::Runtime::new()
                 .unwrap()
                 .block_on(async {
-                    inner.search(&tenant_id, &schema, &query, top_k).await
+                    inner.search_with_mode(&tenant_id, &schema, &query, top_k, mode).await
                 })
         })
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Search failed: {}", e)))?;
 
-        // Convert results to Python list of (entity, score) tuples
+        // Convert results to Python list of {entity, score, rank} dicts
         let mut py_results = Vec::new();
-        for (entity, score) in results {
-            let entity_dict = entity_to_pydict(py, &entity)?;
-            let tuple = (entity_dict, score).to_object(py);
-            py_results.push(tuple);
+        for hit in results {
+            let dict = PyDict::new(py);
+            if typed.unwrap_or(false) {
+                dict.set_item("entity", Py::new(py, PyEntity::from(hit.entity))?)?;
+            } else {
+                dict.set_item("entity", entity_to_pydict(py, &hit.entity, include_system.unwrap_or(true))?)?;
+            }
+            dict.set_item("score", hit.score)?;
+            dict.set_item("rank", hit.rank)?;
+            dict.set_item("raw_distance", hit.raw_distance)?;
+            py_results.push(dict.into());
         }
 
         Ok(py_results)
@@ -427,6 +625,41 @@ impl PyDatabase {
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert result: {}", e)))
     }
 
+    /// Execute SQL query, streaming results.
+    ///
+    /// Returns an iterator that fetches one page of rows at a time, so large
+    /// result sets never load into memory at once. Queries with ORDER BY or
+    /// aggregates are executed in full first, then paged.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - SQL query string
+    /// * `page_size` - Rows fetched per page (default: 100)
+    ///
+    /// # Returns
+    ///
+    /// Iterator over result rows (dicts)
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// for row in db.query_iter("SELECT * FROM logs WHERE level = 'warn'"):
+    ///     print(row["message"])
+    /// ```
+    fn query_iter(&self, sql: String, page_size: Option<usize>) -> PyResult<PyQueryIter> {
+        use crate::query::cursor::DEFAULT_PAGE_SIZE;
+
+        let cursor = self.inner
+            .query_sql_cursor(&self.tenant_id, &sql, page_size.unwrap_or(DEFAULT_PAGE_SIZE))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Query failed: {}", e)))?;
+
+        Ok(PyQueryIter {
+            db: self.inner.clone(),
+            cursor,
+            buffer: std::collections::VecDeque::new(),
+        })
+    }
+
     /// Execute natural language query.
     ///
     /// # Arguments
@@ -434,25 +667,16 @@ impl PyDatabase {
     /// * `question` - Natural language question
     /// * `execute` - Execute query or just plan
     /// * `schema_hint` - Optional schema name hint
+    /// * `model` - LLM model for this call only, overriding `P8_QUERY_LLM`/`P8_DEFAULT_LLM`
     ///
     /// # Returns
     ///
     /// Query results or plan
-    fn ask(&self, py: Python<'_>, question: String, execute: bool, schema_hint: Option<String>) -> PyResult<PyObject> {
-        use crate::llm::query_builder::LlmQueryBuilder;
-
-        // Get API key from environment
-        let api_key = std::env::var("OPENAI_API_KEY")
-            .or_else(|_| std::env::var("ANTHROPIC_API_KEY"))
-            .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                "No API key found. Set OPENAI_API_KEY or ANTHROPIC_API_KEY environment variable."
-            ))?;
-
-        // Determine model and provider
-        let model = std::env::var("P8_DEFAULT_LLM").unwrap_or_else(|_| "gpt-4-turbo".to_string());
+    fn ask(&self, py: Python<'_>, question: String, execute: bool, schema_hint: Option<String>, model: Option<String>) -> PyResult<PyObject> {
+        use crate::llm::query_builder::{LlmOperation, LlmQueryBuilder};
 
-        // Build query plan
-        let builder = LlmQueryBuilder::from_env()
+        // Build query plan (API key chosen by the model's provider)
+        let builder = LlmQueryBuilder::from_env_with_model(LlmOperation::Query, model.as_deref())
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to create query builder: {}", e)))?;
 
         // Get schema context
@@ -534,6 +758,7 @@ impl PyDatabase {
     ///
     /// * `question` - Natural language question
     /// * `schema_context` - Optional schema context for better planning
+    /// * `model` - LLM model for this call only, overriding `P8_QUERY_LLM`/`P8_DEFAULT_LLM`
     ///
     /// # Returns
     ///
@@ -550,12 +775,15 @@ impl PyDatabase {
     ///
     /// # Then execute the plan separately if desired
     /// results = db.run_plan(plan)
+    ///
+    /// # Force a stronger model for a hard question
+    /// plan = db.plan_query("which authors cite each other most?", model="gpt-4o")
     /// ```
-    fn plan_query(&self, py: Python<'_>, question: String, schema_context: Option<String>) -> PyResult<PyObject> {
-        use crate::llm::query_builder::LlmQueryBuilder;
+    fn plan_query(&self, py: Python<'_>, question: String, schema_context: Option<String>, model: Option<String>) -> PyResult<PyObject> {
+        use crate::llm::query_builder::{LlmOperation, LlmQueryBuilder};
 
-        // Build query plan (uses environment variables for API keys and model)
-        let builder = LlmQueryBuilder::from_env()
+        // Build query plan (uses environment variables for API keys and, unless overridden, model)
+        let builder = LlmQueryBuilder::from_env_with_model(LlmOperation::Query, model.as_deref())
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
                 format!("Failed to create query builder: {}", e)
             ))?;
@@ -594,12 +822,17 @@ impl PyDatabase {
     ///
     /// * `content` - Document content (text, markdown, etc.)
     /// * `context` - Optional context about the document (file name, type, etc.)
+    /// * `min_confidence` - Drop edges with confidence below this (default: 0.0, keep all)
+    /// * `resolve` - Match each `dst` name to an existing entity (default: False)
+    /// * `placeholder_table` - With `resolve`, create `{"name": dst}` entities here for unmatched destinations
+    /// * `model` - LLM model for this call only, overriding `P8_EDGE_LLM`/`P8_DEFAULT_LLM`
     ///
     /// # Returns
     ///
     /// EdgePlan JSON object with:
-    /// - edges: Array of inline edges (dst, rel_type, properties, created_at)
-    /// - summary: Statistics (total_edges, relationship_types, avg_confidence)
+    /// - edges: Array of inline edges (dst, rel_type, properties, created_at,
+    ///   plus resolved_id and resolution_confidence when resolved)
+    /// - summary: Statistics (total_edges, relationship_types, avg_confidence, filtered_edges)
     ///
     /// # Example
     ///
@@ -614,6 +847,14 @@ impl PyDatabase {
     /// for edge in plan['edges']:
     ///     print(f"Found: {edge['rel_type']} -> {edge['dst']}")
     ///
+    /// # Keep only confident edges
+    /// plan = db.extract_edges(content, min_confidence=0.7)
+    /// print(f"Dropped {plan['summary']['filtered_edges']} low-confidence edges")
+    ///
+    /// # Resolve names like "Alice" to existing entity UUIDs
+    /// plan = db.extract_edges(content, resolve=True)
+    /// linked = [e for e in plan['edges'] if e.get('resolved_id')]
+    ///
     /// # Append to resource
     /// resource = db.get(resource_id)
     /// resource['edges'] = resource.get('edges', []) + plan['edges']
@@ -623,28 +864,47 @@ impl PyDatabase {
     /// # Requires
     ///
     /// Environment variables:
-    /// - P8_DEFAULT_LLM: LLM model (default: "gpt-4-turbo")
-    /// - OPENAI_API_KEY or ANTHROPIC_API_KEY: API key for LLM provider
+    /// - P8_EDGE_LLM or P8_DEFAULT_LLM: LLM model (default: "gpt-4-turbo"), unless `model` is given
+    /// - OPENAI_API_KEY, ANTHROPIC_API_KEY, or CEREBRAS_API_KEY: API key for the model's provider
+    #[allow(clippy::too_many_arguments)]
     fn extract_edges(
         &self,
         py: Python<'_>,
         content: String,
         context: Option<String>,
+        min_confidence: Option<f64>,
+        resolve: Option<bool>,
+        placeholder_table: Option<String>,
+        model: Option<String>,
     ) -> PyResult<PyObject> {
         use crate::llm::edge_builder::LlmEdgeBuilder;
 
-        // Create edge builder (uses environment variables for API keys and model)
-        let builder = LlmEdgeBuilder::from_env()
+        // Create edge builder (uses environment variables for API keys and, unless overridden, model)
+        let builder = LlmEdgeBuilder::from_env_with_model(model.as_deref())
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
                 format!("Failed to create edge builder: {}", e)
             ))?;
+        let inner = self.inner.clone();
+        let tenant_id = self.tenant_id.clone();
+        let min_confidence = min_confidence.unwrap_or(0.0);
 
         // Extract edges asynchronously
         let result: serde_json::Value = py.allow_threads(|| -> crate::types::Result<serde_json::Value> {
             tokio::runtime::Runtime::new()
                 .unwrap()
                 .block_on(async {
-                    let plan = builder.extract_edges(&content, context.as_deref()).await?;
+                    let plan = if resolve.unwrap_or(false) {
+                        inner.extract_and_link(
+                            &tenant_id,
+                            &builder,
+                            &content,
+                            context.as_deref(),
+                            min_confidence,
+                            placeholder_table.as_deref(),
+                        ).await?
+                    } else {
+                        builder.extract_edges(&content, context.as_deref(), min_confidence).await?
+                    };
                     Ok(serde_json::to_value(&plan)?)
                 })
         })
@@ -685,6 +945,37 @@ impl PyDatabase {
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert schema: {}", e)))
     }
 
+    /// Get extracted schema metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Schema name
+    ///
+    /// # Returns
+    ///
+    /// Dict with name, version, short_name, description, category,
+    /// key_field, indexed_fields, embedding_fields, embedding_provider
+    fn schema_info(&self, py: Python<'_>, name: String) -> PyResult<PyObject> {
+        let info = self.inner.schema_info(&name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to get schema info: {}", e)))?;
+
+        pythonize::pythonize(py, &info)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert schema info: {}", e)))
+    }
+
+    /// Get extracted metadata for all schemas.
+    ///
+    /// # Returns
+    ///
+    /// List of schema info dicts (see `schema_info`), sorted by name
+    fn all_schema_info(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let infos = self.inner.all_schema_info()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to get schema info: {}", e)))?;
+
+        pythonize::pythonize(py, &infos)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert schema info: {}", e)))
+    }
+
     /// Graph traversal from entity.
     ///
     /// # Arguments
@@ -692,11 +983,23 @@ impl PyDatabase {
     /// * `start_id` - Starting entity UUID
     /// * `direction` - Traversal direction ("out", "in", "both")
     /// * `depth` - Maximum depth
+    /// * `max_nodes` - Maximum nodes returned (default: 10,000)
+    /// * `max_nodes_per_level` - Maximum nodes discovered per depth (default: 1,000)
+    /// * `rel_types` - Only follow edges of these types (default: all)
     ///
     /// # Returns
     ///
-    /// List of entity UUIDs
-    fn traverse(&self, start_id: String, direction: String, depth: usize) -> PyResult<Vec<String>> {
+    /// Dict with `nodes` (entity UUIDs in BFS order) and `truncated` (whether a cap was hit)
+    fn traverse(
+        &self,
+        py: Python<'_>,
+        start_id: String,
+        direction: String,
+        depth: usize,
+        max_nodes: Option<usize>,
+        max_nodes_per_level: Option<usize>,
+        rel_types: Option<Vec<String>>,
+    ) -> PyResult<PyObject> {
         let uuid = uuid::Uuid::parse_str(&start_id)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid UUID: {}", e)))?;
 
@@ -709,10 +1012,19 @@ impl PyDatabase {
             )),
         };
 
-        let uuids = self.inner.traverse_bfs(uuid, dir, depth, None)
+        let defaults = crate::graph::TraversalLimits::default();
+        let limits = crate::graph::TraversalLimits {
+            max_nodes: max_nodes.unwrap_or(defaults.max_nodes),
+            max_nodes_per_level: max_nodes_per_level.unwrap_or(defaults.max_nodes_per_level),
+        };
+
+        let result = self.inner.traverse_bfs_with_limits(uuid, dir, depth, rel_types, limits)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Traversal failed: {}", e)))?;
 
-        Ok(uuids.into_iter().map(|u| u.to_string()).collect())
+        let dict = PyDict::new(py);
+        dict.set_item("nodes", result.nodes.iter().map(|u| u.to_string()).collect::<Vec<_>>())?;
+        dict.set_item("truncated", result.truncated)?;
+        Ok(dict.into())
     }
 
     /// Export entities to file.
@@ -723,30 +1035,68 @@ impl PyDatabase {
     /// * `path` - Output file path
     /// * `format` - Export format ("parquet", "csv", "jsonl")
     fn export(&self, table: String, path: String, format: String) -> PyResult<()> {
-        // Get all entities from table
-        let entities = self.inner.list(&self.tenant_id, &table, false, None)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to list entities: {}", e)))?;
-
-        // Export based on format
-        match format.as_str() {
-            "parquet" => {
-                crate::export::ParquetExporter::export(&entities, &path)
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Export failed: {}", e)))?;
-            }
-            "csv" => {
-                crate::export::CsvExporter::export(&entities, &path)
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Export failed: {}", e)))?;
-            }
-            "jsonl" => {
-                crate::export::JsonlExporter::export(&entities, &path)
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Export failed: {}", e)))?;
-            }
-            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+        let format = crate::export::ExportFormat::parse(&format)
+            .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 "format must be 'parquet', 'csv', or 'jsonl'"
+            ))?;
+
+        self.inner.export(&self.tenant_id, &table, &path, format)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Export failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Import entities from a file written by `export`.
+    ///
+    /// Entity IDs and timestamps are preserved; rows of other tables are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Table name
+    /// * `path` - Input file path
+    /// * `format` - Import format ("parquet" or "jsonl")
+    ///
+    /// # Returns
+    ///
+    /// List of imported entity UUIDs
+    fn import_file(&self, table: String, path: String, format: String) -> PyResult<Vec<String>> {
+        let ids = match format.as_str() {
+            "parquet" => self.inner.import_parquet(&self.tenant_id, &table, &path),
+            "jsonl" => self.inner.import_jsonl(&self.tenant_id, &table, &path),
+            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "format must be 'parquet' or 'jsonl'"
             )),
         }
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Import failed: {}", e)))?;
 
-        Ok(())
+        Ok(ids.into_iter().map(|id| id.to_string()).collect())
+    }
+
+    /// Import edges from a CSV or JSONL file.
+    ///
+    /// Rows have `src`, `dst` (entity UUIDs or key values), `rel_type`, and
+    /// optional properties. Rows with unresolvable endpoints are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Input file path
+    /// * `format` - Import format ("csv" or "jsonl")
+    ///
+    /// # Returns
+    ///
+    /// Dict with `created`, `skipped`, and `errors` (list of `(row, reason)`)
+    fn import_edges(&self, py: Python<'_>, path: String, format: String) -> PyResult<PyObject> {
+        let format = crate::export::ExportFormat::parse(&format)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+        let report = self.inner.import_edges(&self.tenant_id, &path, format)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Edge import failed: {}", e)))?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("created", report.created)?;
+        dict.set_item("skipped", report.skipped)?;
+        dict.set_item("errors", report.errors)?;
+        Ok(dict.into())
     }
 
     /// Ingest document file.
@@ -879,8 +1229,11 @@ impl PyDatabase {
 }
 
 /// Helper function to convert Entity to Python dict.
-fn entity_to_pydict(py: Python<'_>, entity: &Entity) -> PyResult<PyObject> {
-    // Convert the entity's properties to a Python dict
-    pythonize::pythonize(py, &entity.properties)
+/// Convert an entity to a Python dict.
+///
+/// With `include_system`, system fields are added as `_id`, `_created_at`,
+/// etc. (see `Entity::to_flat_json`); otherwise only properties are returned.
+fn entity_to_pydict(py: Python<'_>, entity: &Entity, include_system: bool) -> PyResult<PyObject> {
+    pythonize::pythonize(py, &entity.to_flat_json(include_system))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert entity: {}", e)))
 }
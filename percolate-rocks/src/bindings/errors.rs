@@ -1,7 +1,7 @@
 //! Error conversions from Rust to Python.
 
 use pyo3::prelude::*;
-use pyo3::exceptions::{PyValueError, PyIOError, PyRuntimeError};
+use pyo3::exceptions::{PyValueError, PyIOError, PyRuntimeError, PyTimeoutError};
 use crate::types::DatabaseError;
 
 /// Convert DatabaseError to PyErr.
@@ -12,7 +12,10 @@ impl From<DatabaseError> for PyErr {
             DatabaseError::SchemaNotFound(_) => PyValueError::new_err(err.to_string()),
             DatabaseError::ValidationError(_) => PyValueError::new_err(err.to_string()),
             DatabaseError::InvalidKey(_) => PyValueError::new_err(err.to_string()),
+            DatabaseError::DeletePolicyViolation { .. } => PyValueError::new_err(err.to_string()),
+            DatabaseError::EdgeCycle { .. } => PyValueError::new_err(err.to_string()),
             DatabaseError::IoError(_) => PyIOError::new_err(err.to_string()),
+            DatabaseError::Timeout(_) => PyTimeoutError::new_err(err.to_string()),
             _ => PyRuntimeError::new_err(err.to_string()),
         }
     }
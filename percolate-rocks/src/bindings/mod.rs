@@ -10,7 +10,7 @@ pub mod types;
 pub mod errors;
 pub mod async_ops;
 
-pub use database::PyDatabase;
+pub use database::{PyDatabase, PyQueryIter};
 pub use types::*;
 pub use errors::*;
 
@@ -26,6 +26,7 @@ pub use errors::*;
 /// Returns `PyErr` if registration fails
 pub fn register_module(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyDatabase>()?;
+    m.add_class::<PyQueryIter>()?;
     m.add_class::<PyEntity>()?;
     m.add_class::<PyEdge>()?;
     m.add_class::<PySearchResult>()?;
@@ -27,13 +27,68 @@ impl PyEntity {
     /// Get entity properties as dict.
     #[getter]
     fn properties(&self, py: Python) -> PyResult<PyObject> {
-        todo!("Implement PyEntity::properties")
+        to_py(py, &self.inner.properties)
+    }
+
+    /// Get creation timestamp (ISO 8601).
+    #[getter]
+    fn created_at(&self) -> String {
+        self.inner.system.created_at.clone()
+    }
+
+    /// Get last modification timestamp (ISO 8601).
+    #[getter]
+    fn modified_at(&self) -> String {
+        self.inner.system.modified_at.clone()
+    }
+
+    /// Get soft delete timestamp, or None.
+    #[getter]
+    fn deleted_at(&self) -> Option<String> {
+        self.inner.system.deleted_at.clone()
+    }
+
+    /// Get expiry timestamp, or None.
+    #[getter]
+    fn expires_at(&self) -> Option<String> {
+        self.inner.system.expires_at.clone()
+    }
+
+    /// Get inline edges as a list of dicts (`dst`, `rel_type`, `properties`, `created_at`).
+    #[getter]
+    fn edges(&self, py: Python) -> PyResult<PyObject> {
+        to_py(py, &self.inner.system.edges)
     }
 
     /// Check if deleted.
     fn is_deleted(&self) -> bool {
         self.inner.is_deleted()
     }
+
+    /// Convert to the dict returned by untyped `get`.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_system` - Add system fields (`_id`, `_created_at`, ...) (default: true)
+    fn to_dict(&self, py: Python, include_system: Option<bool>) -> PyResult<PyObject> {
+        to_py(py, &self.inner.to_flat_json(include_system.unwrap_or(true)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Entity(id='{}', entity_type='{}')", self.inner.system.id, self.inner.system.entity_type)
+    }
+}
+
+impl From<Entity> for PyEntity {
+    fn from(inner: Entity) -> Self {
+        Self { inner }
+    }
+}
+
+/// Convert a serializable value to a Python object.
+fn to_py<T: serde::Serialize>(py: Python, value: &T) -> PyResult<PyObject> {
+    pythonize::pythonize(py, value)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to convert entity: {}", e)))
 }
 
 /// Python wrapper for Edge.
@@ -0,0 +1,569 @@
+//! Entity change notifications.
+//!
+//! Every successful insert, update, or delete is published as a
+//! `ChangeEvent` on tokio broadcast channels, one per `(tenant, table)`
+//! subscription scope plus one per tenant for all tables. Each subscriber
+//! gets its own copy of every event in its scope.
+//!
+//! Channels are bounded: a subscriber that falls more than
+//! `DEFAULT_CHANGE_CHANNEL_CAPACITY` events behind receives
+//! `RecvError::Lagged` and skips ahead. Publishing never blocks writes.
+//!
+//! Broadcasts only reach subscribers in the writing process;
+//! `watch_subscription` prints them as a live feed. To follow a database
+//! another process is writing, `ChangeTracker` diffs successive scans of the
+//! stored entities instead; `watch` polls it against a secondary instance
+//! (see `Database::open_secondary`) or a shared `StorageProvider` (used by
+//! `rem watch`).
+
+use crate::database::Database;
+use crate::types::{DatabaseError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Events buffered per subscription channel.
+pub const DEFAULT_CHANGE_CHANNEL_CAPACITY: usize = 1024;
+
+/// Time between `watch` polls.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Kind of write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeOp {
+    /// New entity (or upsert of an existing one via insert)
+    Insert,
+    /// Properties changed
+    Update,
+    /// Soft or hard delete
+    Delete,
+}
+
+/// Notification of a successful entity write.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// Kind of write
+    pub op: ChangeOp,
+    /// Table/schema of the entity
+    pub table: String,
+    /// Written entity
+    pub entity_id: uuid::Uuid,
+    /// Time of the write (RFC 3339)
+    pub timestamp: String,
+}
+
+/// Subscription scope: tenant plus optional table.
+type Scope = (String, Option<String>);
+
+/// Registry of change subscription channels.
+#[derive(Debug)]
+pub struct ChangeNotifier {
+    channels: Mutex<HashMap<Scope, broadcast::Sender<ChangeEvent>>>,
+    capacity: usize,
+}
+
+impl Default for ChangeNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHANGE_CHANNEL_CAPACITY)
+    }
+}
+
+impl ChangeNotifier {
+    /// Create notifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Events buffered per subscription channel
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Subscribe to changes in a tenant, optionally limited to one table.
+    pub fn subscribe(&self, tenant_id: &str, table: Option<&str>) -> broadcast::Receiver<ChangeEvent> {
+        let scope = (tenant_id.to_string(), table.map(str::to_string));
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+
+        channels
+            .entry(scope)
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe()
+    }
+
+    /// Publish an event to the table's subscribers and the tenant-wide ones.
+    ///
+    /// Channels whose subscribers have all been dropped are removed.
+    pub fn publish(&self, tenant_id: &str, event: ChangeEvent) {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        if channels.is_empty() {
+            return;
+        }
+
+        for scope in [
+            (tenant_id.to_string(), Some(event.table.clone())),
+            (tenant_id.to_string(), None),
+        ] {
+            if let Some(sender) = channels.get(&scope) {
+                // Err only means no receivers are left
+                if sender.send(event.clone()).is_err() {
+                    channels.remove(&scope);
+                }
+            }
+        }
+    }
+
+    /// Whether any subscription channel is open.
+    pub fn has_subscribers(&self) -> bool {
+        let channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        !channels.is_empty()
+    }
+}
+
+/// Format an event as one line of the watch feed.
+///
+/// `<timestamp>  <op>  <table>  <entity_id>`
+pub fn format_event(event: &ChangeEvent) -> String {
+    let op = match event.op {
+        ChangeOp::Insert => "insert",
+        ChangeOp::Update => "update",
+        ChangeOp::Delete => "delete",
+    };
+    format!("{}  {:<6}  {}  {}", event.timestamp, op, event.table, event.entity_id)
+}
+
+/// Entity fields a scan reads (other fields are skipped).
+#[derive(Deserialize)]
+struct ScanFields {
+    id: uuid::Uuid,
+    entity_type: String,
+    modified_at: String,
+    #[serde(default)]
+    deleted_at: Option<String>,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+/// Entity state remembered between polls.
+#[derive(Debug, Clone, PartialEq)]
+struct SeenEntity {
+    table: String,
+    modified_at: String,
+    /// Soft-deleted or expired
+    gone: bool,
+}
+
+/// Detects entity writes by diffing successive scans of a tenant's entities.
+///
+/// Needs no hooks in the write path, so it works on any handle, including
+/// a secondary instance following another process. Each poll reads the
+/// system timestamps of every entity in scope (properties are skipped), and
+/// writes between two polls collapse into at most one event per entity.
+#[derive(Debug)]
+pub struct ChangeTracker {
+    tenant_id: String,
+    table: Option<String>,
+    seen: HashMap<uuid::Uuid, SeenEntity>,
+}
+
+impl ChangeTracker {
+    /// Start tracking from the database's current state.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - Database to scan
+    /// * `tenant_id` - Tenant to track
+    /// * `table` - Only track this table (optional)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if the scan fails
+    pub fn new(db: &Database, tenant_id: &str, table: Option<&str>) -> Result<Self> {
+        let mut tracker = Self {
+            tenant_id: tenant_id.to_string(),
+            table: table.map(str::to_string),
+            seen: HashMap::new(),
+        };
+        tracker.seen = tracker.scan(db)?;
+        Ok(tracker)
+    }
+
+    /// Report writes since the previous poll.
+    ///
+    /// New entities are inserts, changed `modified_at` is an update (or a
+    /// delete once the entity is soft-deleted or expired), and entities
+    /// that vanished were hard-deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - Database to scan
+    ///
+    /// # Returns
+    ///
+    /// Events ordered by timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if the scan fails
+    pub fn poll(&mut self, db: &Database) -> Result<Vec<ChangeEvent>> {
+        let current = self.scan(db)?;
+        let mut events = Vec::new();
+
+        for (id, now) in &current {
+            let op = match self.seen.get(id) {
+                None if !now.gone => ChangeOp::Insert,
+                Some(before) if before != now && !now.gone => ChangeOp::Update,
+                Some(before) if now.gone && !before.gone => ChangeOp::Delete,
+                _ => continue,
+            };
+            events.push(ChangeEvent {
+                op,
+                table: now.table.clone(),
+                entity_id: *id,
+                timestamp: now.modified_at.clone(),
+            });
+        }
+
+        let removed_at = chrono::Utc::now().to_rfc3339();
+        for (id, before) in &self.seen {
+            if !before.gone && !current.contains_key(id) {
+                events.push(ChangeEvent {
+                    op: ChangeOp::Delete,
+                    table: before.table.clone(),
+                    entity_id: *id,
+                    timestamp: removed_at.clone(),
+                });
+            }
+        }
+
+        events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        self.seen = current;
+
+        Ok(events)
+    }
+
+    fn scan(&self, db: &Database) -> Result<HashMap<uuid::Uuid, SeenEntity>> {
+        use crate::storage::column_families::CF_ENTITIES;
+
+        let provider = db.provider();
+        let prefix = format!("entity:{}:", self.tenant_id);
+        let now = chrono::Utc::now();
+        let mut seen = HashMap::new();
+
+        for item in provider.iterator(CF_ENTITIES, prefix.as_bytes()) {
+            let (_, value) = item?;
+            let fields: ScanFields = serde_json::from_slice(&value)?;
+            if self.table.as_deref().is_some_and(|table| fields.entity_type != table) {
+                continue;
+            }
+
+            let expired = fields.expires_at.as_deref()
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+                .is_some_and(|expires_at| expires_at <= now);
+            seen.insert(fields.id, SeenEntity {
+                table: fields.entity_type,
+                modified_at: fields.modified_at,
+                gone: fields.deleted_at.is_some() || expired,
+            });
+        }
+
+        Ok(seen)
+    }
+}
+
+/// Write change events to `out`, one line each, until `shutdown` resolves.
+///
+/// Polls a `ChangeTracker` every `interval`, first catching up with the
+/// primary when `db` is a secondary instance. Scans run on tokio's blocking
+/// pool. Only writes made after the call are reported. For writes made
+/// through this process's own handle, `watch_subscription` avoids scanning.
+///
+/// # Arguments
+///
+/// * `db` - Database to watch, typically from `Database::open_secondary`
+/// * `tenant_id` - Tenant to watch
+/// * `table` - Only watch this table (optional)
+/// * `interval` - Time between polls (e.g. `DEFAULT_WATCH_INTERVAL`)
+/// * `out` - Output sink (flushed after every poll)
+/// * `shutdown` - Stop signal, e.g. `tokio::signal::ctrl_c()`
+///
+/// # Returns
+///
+/// Number of events written
+///
+/// # Errors
+///
+/// Returns `DatabaseError::StorageError` if catching up or scanning fails,
+/// or `DatabaseError::IoError` if writing to `out` fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let db = Arc::new(Database::open_secondary("./data", "/tmp/rem-watch")?);
+/// let shutdown = async { tokio::signal::ctrl_c().await.ok(); };
+/// watch(db, "default", Some("articles"), DEFAULT_WATCH_INTERVAL, &mut std::io::stdout(), shutdown).await?;
+/// ```
+pub async fn watch<W, F>(
+    db: Arc<Database>,
+    tenant_id: &str,
+    table: Option<&str>,
+    interval: Duration,
+    out: &mut W,
+    shutdown: F,
+) -> Result<usize>
+where
+    W: std::io::Write,
+    F: Future<Output = ()>,
+{
+    let mut tracker = {
+        let db = Arc::clone(&db);
+        let (tenant_id, table) = (tenant_id.to_string(), table.map(str::to_string));
+        blocking(move || {
+            db.catch_up_with_primary()?;
+            ChangeTracker::new(&db, &tenant_id, table.as_deref())
+        })
+        .await??
+    };
+
+    tokio::pin!(shutdown);
+    let mut written = 0;
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        let scan_db = Arc::clone(&db);
+        let (returned, events) = blocking(move || {
+            let events = scan_db.catch_up_with_primary().and_then(|_| tracker.poll(&scan_db));
+            (tracker, events)
+        })
+        .await?;
+        tracker = returned;
+
+        for event in events? {
+            writeln!(out, "{}", format_event(&event))?;
+            written += 1;
+        }
+        out.flush()?;
+    }
+
+    Ok(written)
+}
+
+/// Write events from a `Database::subscribe` receiver to `out`, one line
+/// each, until `shutdown` resolves or the channel closes.
+///
+/// Only sees writes made through the subscribed handle (see module docs).
+/// If the receiver lags, a line with the number of skipped events is
+/// written instead.
+///
+/// # Arguments
+///
+/// * `rx` - Receiver from `Database::subscribe`
+/// * `out` - Output sink (flushed after every event)
+/// * `shutdown` - Stop signal, e.g. `tokio::signal::ctrl_c()`
+///
+/// # Returns
+///
+/// Number of events written
+///
+/// # Errors
+///
+/// Returns an I/O error if writing to `out` fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let rx = db.subscribe("default", Some("articles"));
+/// let shutdown = async { tokio::signal::ctrl_c().await.ok(); };
+/// watch_subscription(rx, &mut std::io::stdout(), shutdown).await?;
+/// ```
+pub async fn watch_subscription<W, F>(
+    mut rx: broadcast::Receiver<ChangeEvent>,
+    out: &mut W,
+    shutdown: F,
+) -> std::io::Result<usize>
+where
+    W: std::io::Write,
+    F: Future<Output = ()>,
+{
+    tokio::pin!(shutdown);
+    let mut written = 0;
+
+    loop {
+        let event = tokio::select! {
+            _ = &mut shutdown => break,
+            received = rx.recv() => received,
+        };
+
+        match event {
+            Ok(event) => {
+                writeln!(out, "{}", format_event(&event))?;
+                written += 1;
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                writeln!(out, "... skipped {} events", skipped)?;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+        out.flush()?;
+    }
+
+    Ok(written)
+}
+
+/// Run blocking storage work on tokio's blocking pool.
+async fn blocking<T, F>(work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| DatabaseError::InternalError(format!("Watch scan panicked: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(table: &str) -> ChangeEvent {
+        ChangeEvent {
+            op: ChangeOp::Insert,
+            table: table.to_string(),
+            entity_id: uuid::Uuid::new_v4(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_publish_scopes() {
+        let notifier = ChangeNotifier::default();
+        let mut articles = notifier.subscribe("tenant1", Some("articles"));
+        let mut all = notifier.subscribe("tenant1", None);
+        let mut other_tenant = notifier.subscribe("tenant2", None);
+
+        notifier.publish("tenant1", event("articles"));
+        notifier.publish("tenant1", event("people"));
+
+        assert_eq!(articles.try_recv().unwrap().table, "articles");
+        assert!(articles.try_recv().is_err());
+        assert_eq!(all.try_recv().unwrap().table, "articles");
+        assert_eq!(all.try_recv().unwrap().table, "people");
+        assert!(other_tenant.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_dropped_subscribers_are_removed() {
+        let notifier = ChangeNotifier::default();
+        drop(notifier.subscribe("tenant1", Some("articles")));
+        assert!(notifier.has_subscribers());
+
+        notifier.publish("tenant1", event("articles"));
+        assert!(!notifier.has_subscribers());
+    }
+
+    #[test]
+    fn test_tracker_reports_writes() {
+        let db = crate::database::Database::open_temp().unwrap();
+        db.register_schema("articles", serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {"title": {"type": "string"}},
+            "required": ["title"]
+        })).unwrap();
+
+        let existing = db.insert("default", "articles", serde_json::json!({"title": "Old"})).unwrap();
+        let mut tracker = ChangeTracker::new(&db, "default", Some("articles")).unwrap();
+        assert!(tracker.poll(&db).unwrap().is_empty());
+
+        let id = db.insert("default", "articles", serde_json::json!({"title": "Hello"})).unwrap();
+        let ops = |events: Vec<ChangeEvent>| events.iter().map(|e| (e.op, e.entity_id)).collect::<Vec<_>>();
+        assert_eq!(ops(tracker.poll(&db).unwrap()), vec![(ChangeOp::Insert, id)]);
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        db.update("default", id, serde_json::json!({"title": "Hello again"})).unwrap();
+        assert_eq!(ops(tracker.poll(&db).unwrap()), vec![(ChangeOp::Update, id)]);
+
+        db.delete("default", id).unwrap();
+        db.hard_delete("default", existing).unwrap();
+        let mut deleted = ops(tracker.poll(&db).unwrap());
+        deleted.sort_by_key(|(_, id)| *id);
+        let mut expected = vec![(ChangeOp::Delete, id), (ChangeOp::Delete, existing)];
+        expected.sort_by_key(|(_, id)| *id);
+        assert_eq!(deleted, expected);
+        assert!(tracker.poll(&db).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_follows_another_handle() {
+        let dir = tempfile::tempdir().unwrap();
+        let secondary_dir = tempfile::tempdir().unwrap();
+
+        let writer = crate::database::Database::open(dir.path()).unwrap();
+        writer.register_schema("articles", serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {"title": {"type": "string"}},
+            "required": ["title"]
+        })).unwrap();
+
+        let reader = Arc::new(crate::database::Database::open_secondary(dir.path(), secondary_dir.path()).unwrap());
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let watcher = tokio::spawn(async move {
+            let mut out = Vec::new();
+            let shutdown = async { stop_rx.await.ok(); };
+            let written = watch(reader, "default", Some("articles"), Duration::from_millis(10), &mut out, shutdown)
+                .await
+                .unwrap();
+            (written, String::from_utf8(out).unwrap())
+        });
+
+        // Let the watcher take its baseline before writing
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let id = writer.insert("default", "articles", serde_json::json!({"title": "Hello"})).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        stop_tx.send(()).unwrap();
+
+        let (written, output) = watcher.await.unwrap();
+        assert_eq!(written, 1);
+        assert!(output.contains("insert"), "{}", output);
+        assert!(output.contains(&id.to_string()), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_watch_stops_on_shutdown() {
+        let db = Arc::new(crate::database::Database::open_temp().unwrap());
+
+        let mut out = Vec::new();
+        let written = watch(db, "tenant1", None, DEFAULT_WATCH_INTERVAL, &mut out, async {}).await.unwrap();
+        assert_eq!(written, 0);
+        assert!(out.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_subscription_prints_events() {
+        let notifier = ChangeNotifier::default();
+        let rx = notifier.subscribe("tenant1", Some("articles"));
+        notifier.publish("tenant1", event("articles"));
+        notifier.publish("tenant1", event("people"));
+        notifier.publish("tenant1", event("articles"));
+
+        // Stops once the sender is gone and buffered events are written
+        drop(notifier);
+        let mut out = Vec::new();
+        let written = watch_subscription(rx, &mut out, std::future::pending()).await.unwrap();
+
+        assert_eq!(written, 2);
+        assert_eq!(String::from_utf8(out).unwrap().lines().filter(|l| l.contains("articles")).count(), 2);
+    }
+}
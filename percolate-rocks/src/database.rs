@@ -3,19 +3,80 @@
 //! This is the main entry point for database operations.
 
 use crate::schema::{SchemaRegistry, register_builtin_schemas};
-use crate::storage::Storage;
+use crate::storage::{BatchOp, Storage, StorageProvider};
+use crate::query::cache::{QueryCache, QueryCacheStats};
+use crate::embeddings::truncate::OverflowPolicy;
+use crate::changes::{ChangeEvent, ChangeNotifier, ChangeOp};
+use crate::otel::{db_span, db_query_span, record_db_metrics, DbOperation};
 use crate::types::{Result, Entity, Edge, DatabaseError};
+use std::collections::HashSet;
 use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Default maximum rows a non-indexed query may scan.
+pub const DEFAULT_MAX_SCAN_ROWS: usize = 100_000;
+
+/// Number of striped commit locks used by `update_with`.
+const COMMIT_LOCK_STRIPES: usize = 64;
+
+/// Number of striped locks serializing `add_edge` cycle checks.
+const EDGE_LOCK_STRIPES: usize = 16;
+
+/// `CF_EDGES` key prefix of persisted edge constraints (edges use `src:`).
+const EDGE_CONSTRAINT_PREFIX: &str = "constraint:";
+
+/// Edges rewritten per write batch during weight decay.
+const EDGE_DECAY_BATCH_SIZE: usize = 1_000;
+
+/// Default entities committed per write batch by `batch_insert`.
+pub const DEFAULT_WRITE_BATCH_SIZE: usize = 10_000;
+
+/// Vectors sampled by `check_embedding_consistency`.
+const EMBEDDING_CHECK_SAMPLE: usize = 1_000;
+
+/// Vectors sampled to fit a dimensionality reduction.
+const PCA_FIT_SAMPLE: usize = 2_000;
+
+/// Vectors whose nearest neighbor `index_health` measures.
+const INDEX_HEALTH_SAMPLE: usize = 200;
 
 /// High-level database with storage and schema registry.
 ///
 /// Thread-safe and optimized for concurrent access.
 pub struct Database {
     storage: Arc<Storage>,
+    /// Backend for entities, key index, and field index (`storage` unless
+    /// opened with `open_with_provider`)
+    provider: Arc<dyn StorageProvider>,
     registry: Arc<RwLock<SchemaRegistry>>,
     wal: Option<Arc<RwLock<crate::replication::WriteAheadLog>>>,
     replication_mode: ReplicationMode,
+    max_scan_rows: usize,
+    query_cache: Option<Arc<Mutex<QueryCache>>>,
+    field_index: Arc<crate::index::FieldIndexer>,
+    embedding_overflow: OverflowPolicy,
+    /// Bytes per stored blob chunk
+    blob_chunk_size: usize,
+    /// Threads used to build HNSW graphs (`None` = one per core)
+    hnsw_build_threads: Option<usize>,
+    /// Entities per RocksDB write batch in bulk inserts
+    write_batch_size: usize,
+    /// Retry policy for read-modify-write conflicts
+    write_retry: crate::storage::WriteRetryPolicy,
+    /// Striped locks serializing commit-time conflict checks
+    commit_locks: Arc<Vec<Mutex<()>>>,
+    /// Bumped by every `register_schema`; writers that validated against an
+    /// older schema re-check under their commit stripes
+    schema_epoch: Arc<AtomicU64>,
+    /// Relationship types on which `add_edge` rejects cycles
+    acyclic_rel_types: Arc<RwLock<HashSet<String>>>,
+    /// Striped locks (by relationship type) holding cycle checks and edge writes together
+    edge_locks: Arc<Vec<Mutex<()>>>,
+    /// Change event subscriptions
+    changes: Arc<ChangeNotifier>,
+    /// Embedding projections by table (see `fit_dimensionality_reduction`)
+    projections: Arc<RwLock<std::collections::HashMap<String, Arc<crate::embeddings::PcaProjection>>>>,
 }
 
 /// Replication mode for the database.
@@ -53,21 +114,12 @@ impl Database {
         // Open storage layer (no encryption by default)
         let storage = Arc::new(Storage::open(path, None)?);
 
-        // Initialize schema registry
-        let mut registry = SchemaRegistry::new();
-
-        // Register builtin schemas (schemas, documents, resources)
-        register_builtin_schemas(&mut registry)?;
-
-        let db = Self {
-            storage,
-            registry: Arc::new(RwLock::new(registry)),
-            wal: None,
-            replication_mode: ReplicationMode::Standalone,
-        };
+        let db = Self::assemble(storage.clone(), storage, None, ReplicationMode::Standalone)?;
 
-        // Load persisted schemas from storage
+        // Load persisted schemas, embedding projections, and edge constraints from storage
         db.load_schemas_from_storage()?;
+        db.load_projections_from_storage()?;
+        db.load_edge_constraints_from_storage()?;
 
         Ok(db)
     }
@@ -96,45 +148,490 @@ impl Database {
         // Open storage layer
         let storage = Arc::new(Storage::open(path, None)?);
 
-        // Initialize schema registry
-        let mut registry = SchemaRegistry::new();
-        register_builtin_schemas(&mut registry)?;
-
         // Initialize WAL for replication
         let wal = crate::replication::WriteAheadLog::new(storage.clone())?;
 
-        let db = Self {
-            storage,
-            registry: Arc::new(RwLock::new(registry)),
-            wal: Some(Arc::new(RwLock::new(wal))),
-            replication_mode: mode,
-        };
+        let db = Self::assemble(storage.clone(), storage, Some(Arc::new(RwLock::new(wal))), mode)?;
 
-        // Load persisted schemas from storage
+        // Load persisted schemas, embedding projections, and edge constraints from storage
         db.load_schemas_from_storage()?;
+        db.load_projections_from_storage()?;
+        db.load_edge_constraints_from_storage()?;
 
         Ok(db)
     }
 
-    /// Open database in memory for testing.
+    /// Open database and check cross-index consistency.
+    ///
+    /// Runs `check_consistency` right after opening, as a recovery step
+    /// after a crash. The scan reads every entity and index entry, so it
+    /// takes time proportional to database size.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Database directory path
+    /// * `repair` - Delete orphaned entries and rebuild missing ones
     ///
     /// # Returns
     ///
-    /// `Database` instance with in-memory backend
-    pub fn open_temp() -> Result<Self> {
-        let storage = Storage::open_temp()?;
+    /// `Database` instance and the consistency report
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if RocksDB fails to open or scan
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (db, report) = Database::open_with_check("./data", true)?;
+    /// if !report.is_consistent() {
+    ///     eprintln!("Repaired {} inconsistencies", report.issues());
+    /// }
+    /// ```
+    pub fn open_with_check<P: AsRef<Path>>(path: P, repair: bool) -> Result<(Self, crate::storage::ConsistencyReport)> {
+        let db = Self::open(path)?;
+        let report = db.check_consistency(repair)?;
+        Ok((db, report))
+    }
+
+    /// Open a read-only view of a database another process has open.
+    ///
+    /// Uses a RocksDB secondary instance, which doesn't take the database
+    /// lock. Writes made by the other process become visible after
+    /// `catch_up_with_primary`. Writes through this handle fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Database directory path
+    /// * `secondary_path` - Scratch directory for the secondary's info logs
+    ///
+    /// # Returns
+    ///
+    /// `Database` instance in `ReplicationMode::Replica`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if RocksDB fails to open
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open_secondary("./data", "/tmp/rem-watch")?;
+    /// db.catch_up_with_primary()?;
+    /// ```
+    pub fn open_secondary<P: AsRef<Path>, S: AsRef<Path>>(path: P, secondary_path: S) -> Result<Self> {
+        let storage = Arc::new(Storage::open_secondary(path, secondary_path)?);
+
+        let db = Self::assemble(storage.clone(), storage, None, ReplicationMode::Replica)?;
+
+        db.load_schemas_from_storage()?;
+        db.load_projections_from_storage()?;
+        db.load_edge_constraints_from_storage()?;
+
+        Ok(db)
+    }
+
+    /// Pick up writes made by the primary since the last catch-up.
+    ///
+    /// No-op unless opened with `open_secondary`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if RocksDB fails
+    pub fn catch_up_with_primary(&self) -> Result<()> {
+        self.storage.catch_up_with_primary()
+    }
+
+    /// Open database with entities stored in another backend.
+    ///
+    /// Entity CRUD, listing, key lookups, and field index queries
+    /// (`CF_ENTITIES`, `CF_KEY_INDEX`, `CF_INDEXES`) go through `provider`,
+    /// e.g. a `PostgresProvider` shared by several nodes, as do embedding
+    /// projections (rewritten together with the entities). Edges, vector
+    /// embeddings, blobs, and vector indexes stay in the local RocksDB at
+    /// `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Local database directory path
+    /// * `provider` - Backend for entities and their indexes
+    ///
+    /// # Returns
+    ///
+    /// `Database` instance with schemas loaded from `provider`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if RocksDB fails to open, or a
+    /// backend error if loading schemas from `provider` fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let provider = Arc::new(PostgresProvider::from_env("acme")?);
+    /// let db = Database::open_with_provider("./data", provider)?;
+    /// ```
+    pub fn open_with_provider<P: AsRef<Path>>(path: P, provider: Arc<dyn StorageProvider>) -> Result<Self> {
+        let storage = Arc::new(Storage::open(path, None)?);
 
+        let db = Self::assemble(storage, provider, None, ReplicationMode::Standalone)?;
+
+        db.load_schemas_from_storage()?;
+        db.load_projections_from_storage()?;
+        db.load_edge_constraints_from_storage()?;
+
+        Ok(db)
+    }
+
+    /// Build a database over opened storage, with builtin schemas registered.
+    fn assemble(
+        storage: Arc<Storage>,
+        provider: Arc<dyn StorageProvider>,
+        wal: Option<Arc<RwLock<crate::replication::WriteAheadLog>>>,
+        replication_mode: ReplicationMode,
+    ) -> Result<Self> {
+        // Register builtin schemas (schemas, documents, resources)
         let mut registry = SchemaRegistry::new();
         register_builtin_schemas(&mut registry)?;
 
         Ok(Self {
-            storage: Arc::new(storage),
+            field_index: Arc::new(crate::index::FieldIndexer::new(provider.clone())),
+            storage,
+            provider,
             registry: Arc::new(RwLock::new(registry)),
-            wal: None,
-            replication_mode: ReplicationMode::Standalone,
+            wal,
+            replication_mode,
+            max_scan_rows: DEFAULT_MAX_SCAN_ROWS,
+            query_cache: None,
+            embedding_overflow: OverflowPolicy::default(),
+            blob_chunk_size: crate::storage::DEFAULT_BLOB_CHUNK_SIZE,
+            hnsw_build_threads: None,
+            write_batch_size: DEFAULT_WRITE_BATCH_SIZE,
+            write_retry: crate::storage::WriteRetryPolicy::default(),
+            commit_locks: Arc::new((0..COMMIT_LOCK_STRIPES).map(|_| Mutex::new(())).collect()),
+            schema_epoch: Arc::new(AtomicU64::new(0)),
+            acyclic_rel_types: Arc::new(RwLock::new(HashSet::new())),
+            edge_locks: Arc::new((0..EDGE_LOCK_STRIPES).map(|_| Mutex::new(())).collect()),
+            changes: Arc::new(ChangeNotifier::default()),
+            projections: Arc::new(RwLock::new(std::collections::HashMap::new())),
         })
     }
 
+    /// Open database in memory for testing.
+    ///
+    /// # Returns
+    ///
+    /// `Database` instance with in-memory backend
+    pub fn open_temp() -> Result<Self> {
+        let storage = Arc::new(Storage::open_temp()?);
+        Self::assemble(storage.clone(), storage, None, ReplicationMode::Standalone)
+    }
+
+    /// Set the maximum rows a non-indexed query may scan.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_rows` - Row limit (default: `DEFAULT_MAX_SCAN_ROWS`)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open("./data")?.with_max_scan_rows(1_000_000);
+    /// ```
+    pub fn with_max_scan_rows(mut self, max_rows: usize) -> Self {
+        self.max_scan_rows = max_rows;
+        self
+    }
+
+    /// Get the configured scan limit.
+    pub fn max_scan_rows(&self) -> usize {
+        self.max_scan_rows
+    }
+
+    /// Enable the in-process query result cache.
+    ///
+    /// SQL and search results are cached by `(tenant, normalized_query)`.
+    /// Any insert, update, or delete on a table invalidates its entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum cached results (LRU eviction)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open("./data")?.with_query_cache(1024);
+    /// ```
+    pub fn with_query_cache(mut self, capacity: usize) -> Self {
+        self.query_cache = Some(Arc::new(Mutex::new(QueryCache::new(capacity))));
+        self
+    }
+
+    /// Set how over-length text is handled before embedding.
+    ///
+    /// Inputs beyond the provider's `max_tokens` are truncated (head by
+    /// default) or rejected. Truncated chunks record the embedded length
+    /// under `embedding_truncation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Overflow policy
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open("./data")?
+    ///     .with_embedding_overflow(OverflowPolicy::Truncate(TruncationStrategy::MiddleDrop));
+    /// ```
+    pub fn with_embedding_overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.embedding_overflow = policy;
+        self
+    }
+
+    /// Get the configured embedding overflow policy.
+    pub fn embedding_overflow(&self) -> OverflowPolicy {
+        self.embedding_overflow
+    }
+
+    /// Set how many times a conflicting update is retried.
+    ///
+    /// `update` and `update_with` commit only if the entity is unchanged
+    /// since it was read; on conflict they re-read and retry with
+    /// exponential backoff, then fail with `DatabaseError::WriteConflict`.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - Retries after the first attempt (default: `DEFAULT_WRITE_RETRIES`)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open("./data")?.with_write_retries(20);
+    /// ```
+    pub fn with_write_retries(mut self, retries: u32) -> Self {
+        self.write_retry.max_retries = retries;
+        self
+    }
+
+    /// Set the full write conflict retry policy (retries and backoff).
+    pub fn with_write_retry_policy(mut self, policy: crate::storage::WriteRetryPolicy) -> Self {
+        self.write_retry = policy;
+        self
+    }
+
+    /// Set when replication WAL writes are fsynced.
+    ///
+    /// No effect unless the database was opened with replication.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Sync policy (default: `WalSyncPolicy::PerWrite`)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open_with_replication("./data", ReplicationMode::Primary)?
+    ///     .with_wal_sync_policy(WalSyncPolicy::Interval(Duration::from_millis(100)));
+    /// ```
+    pub fn with_wal_sync_policy(self, policy: crate::replication::WalSyncPolicy) -> Self {
+        if let Some(ref wal) = self.wal {
+            wal.write()
+                .unwrap_or_else(|e| e.into_inner())
+                .set_sync_policy(policy);
+        }
+        self
+    }
+
+    /// Let compaction drop soft-deleted entities after a grace period.
+    ///
+    /// Purging is opt-in: by default soft-deleted entities are kept (and
+    /// restorable) until `hard_delete`. Tables with
+    /// `DeletePolicy::SoftOnly` are never purged. `vacuum` cleans up after
+    /// the dropped entities (see `storage::expiry`).
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long a soft-deleted entity is kept
+    pub fn with_soft_delete_grace(self, grace: std::time::Duration) -> Self {
+        self.storage.expiry_filter().set_soft_delete_grace(Some(grace));
+        self
+    }
+
+    /// Set the chunk size for blobs written by `put_blob`.
+    ///
+    /// Existing blobs keep the chunk size recorded in their manifest.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk_size` - Bytes per chunk (default: `DEFAULT_BLOB_CHUNK_SIZE`)
+    pub fn with_blob_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.blob_chunk_size = chunk_size;
+        self
+    }
+
+    /// Set the number of threads used to build HNSW indexes.
+    ///
+    /// Applies to the indexes built for `search_by_vector` and
+    /// `build_similarity_graph`. Parallel builds give the same recall as
+    /// serial ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads` - Construction threads; `1` builds serially (default: one per core)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open("./data")?.with_hnsw_build_threads(8);
+    /// ```
+    pub fn with_hnsw_build_threads(mut self, threads: usize) -> Self {
+        self.hnsw_build_threads = Some(threads.max(1));
+        self
+    }
+
+    /// Set how many entities bulk inserts commit per write batch.
+    ///
+    /// `batch_insert` and the importers split large inputs into write
+    /// batches of this size, bounding memory and the time any single
+    /// commit holds up other writers. Each batch is atomic on its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - Entities per write batch (default: `DEFAULT_WRITE_BATCH_SIZE`)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let db = Database::open("./data")?.with_write_batch_size(50_000);
+    /// ```
+    pub fn with_write_batch_size(mut self, batch_size: usize) -> Self {
+        self.write_batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Create an empty HNSW index with the configured build threads.
+    fn new_hnsw_index(&self, dimensions: usize, max_elements: usize, ef_search: usize) -> crate::index::hnsw::HnswIndex {
+        let index = crate::index::hnsw::HnswIndex::new(dimensions, max_elements).with_ef_search(ef_search);
+        match self.hnsw_build_threads {
+            Some(threads) => index.with_build_threads(threads),
+            None => index,
+        }
+    }
+
+    /// Get query cache counters.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the cache is disabled
+    pub fn query_cache_stats(&self) -> Option<QueryCacheStats> {
+        self.query_cache
+            .as_ref()
+            .and_then(|cache| cache.lock().ok().map(|c| c.stats()))
+    }
+
+    /// Look up a cached query result computed under `scan_limit`.
+    fn cached_query(&self, tenant_id: &str, query: &str, scan_limit: Option<usize>) -> Option<serde_json::Value> {
+        let cache = self.query_cache.as_ref()?;
+        cache.lock().ok()?.get(tenant_id, query, scan_limit)
+    }
+
+    /// Store a query result computed under `scan_limit` in the cache, until
+    /// `expires_at` (see `earliest_expiry`).
+    fn cache_query(
+        &self,
+        tenant_id: &str,
+        query: &str,
+        scan_limit: Option<usize>,
+        table: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        result: &serde_json::Value,
+    ) {
+        if let Some(ref cache) = self.query_cache {
+            if let Ok(mut cache) = cache.lock() {
+                cache.put(tenant_id, query, scan_limit, table, expires_at, result.clone());
+            }
+        }
+    }
+
+    /// Drop cached results that read from `table`.
+    fn invalidate_query_cache(&self, table: &str) {
+        if let Some(ref cache) = self.query_cache {
+            if let Ok(mut cache) = cache.lock() {
+                cache.invalidate_table(table);
+            }
+        }
+    }
+
+    /// Write field index entries for an entity's indexed fields.
+    fn write_field_indexes(&self, tenant_id: &str, indexed_fields: &[String], entity: &Entity) -> Result<()> {
+        for field in indexed_fields {
+            if let Some(value) = entity.properties.get(field) {
+                for index_value in index_values(value) {
+                    self.field_index.index_field(
+                        tenant_id,
+                        &entity.system.entity_type,
+                        field,
+                        &index_value,
+                        entity.system.id,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove field index entries for an entity's indexed fields.
+    fn remove_field_indexes(&self, tenant_id: &str, indexed_fields: &[String], entity: &Entity) -> Result<()> {
+        for field in indexed_fields {
+            if let Some(value) = entity.properties.get(field) {
+                for index_value in index_values(value) {
+                    self.field_index.remove_index(tenant_id, field, &index_value, entity.system.id)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add field index changes from `previous` to `entity` to a write batch.
+    ///
+    /// Entries for values the entity no longer has are deleted; entries for
+    /// its current values are (re)written.
+    fn batch_field_indexes(
+        &self,
+        batch: &mut Vec<BatchOp>,
+        tenant_id: &str,
+        indexed_fields: &[String],
+        previous: Option<&Entity>,
+        entity: &Entity,
+    ) {
+        use crate::storage::column_families::CF_INDEXES;
+        use crate::storage::keys::encode_index_key;
+
+        let index_value = serde_json::json!({"type": entity.system.entity_type}).to_string();
+
+        for field in indexed_fields {
+            let new_values = entity.properties.get(field).map(index_values).unwrap_or_default();
+            let old_values = previous
+                .and_then(|p| p.properties.get(field))
+                .map(index_values)
+                .unwrap_or_default();
+
+            for value in old_values.iter().filter(|v| !new_values.contains(v)) {
+                batch.push(BatchOp::Delete {
+                    cf: CF_INDEXES,
+                    key: encode_index_key(tenant_id, field, value, entity.system.id),
+                });
+            }
+            for value in &new_values {
+                batch.push(BatchOp::Put {
+                    cf: CF_INDEXES,
+                    key: encode_index_key(tenant_id, field, value, entity.system.id),
+                    value: index_value.as_bytes().to_vec(),
+                });
+            }
+        }
+    }
+
     /// Register schema from JSON Schema.
     ///
     /// # Arguments
@@ -146,12 +643,16 @@ impl Database {
     ///
     /// Returns `DatabaseError::ValidationError` if schema is invalid
     pub fn register_schema(&self, name: &str, schema: serde_json::Value) -> Result<()> {
+        let _span = db_span(DbOperation::Put, Some("schemas"), None).entered();
+
         // Register in memory
         {
             let mut registry = self.registry.write()
                 .map_err(|e| crate::types::DatabaseError::InternalError(format!("Lock error: {}", e)))?;
 
             registry.register(name, schema.clone())?;
+            self.sync_retained(&registry, name);
+            self.schema_epoch.fetch_add(1, Ordering::AcqRel);
         }
 
         // Persist to storage (unless it's a system schema)
@@ -198,7 +699,7 @@ impl Database {
         Ok(registry.list())
     }
 
-    /// Check if schema exists.
+    /// Get extracted metadata for a schema.
     ///
     /// # Arguments
     ///
@@ -206,24 +707,120 @@ impl Database {
     ///
     /// # Returns
     ///
-    /// `true` if schema is registered
-    pub fn has_schema(&self, name: &str) -> bool {
-        if let Ok(registry) = self.registry.read() {
-            registry.has(name)
-        } else {
-            false
-        }
-    }
-
-    /// Get storage instance (for low-level operations).
+    /// `SchemaInfo` (key_field, indexed_fields, embedding_fields, category, ...)
     ///
-    /// # Returns
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SchemaNotFound` if schema doesn't exist
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let info = db.schema_info("articles")?;
+    /// println!("{} indexes {:?}", info.name, info.indexed_fields);
+    /// ```
+    pub fn schema_info(&self, name: &str) -> Result<crate::schema::SchemaInfo> {
+        let registry = self.registry.read()
+            .map_err(|e| crate::types::DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        registry.info(name)
+    }
+
+    /// Get extracted metadata for all registered schemas.
+    ///
+    /// # Returns
+    ///
+    /// `SchemaInfo` per schema, sorted by name
+    pub fn all_schema_info(&self) -> Result<Vec<crate::schema::SchemaInfo>> {
+        let registry = self.registry.read()
+            .map_err(|e| crate::types::DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        let mut names = registry.list();
+        names.sort();
+        names.iter().map(|name| registry.info(name)).collect()
+    }
+
+    /// Generate Rust types for all non-system schemas.
+    ///
+    /// Writes one `serde` struct per registered schema (plus nested
+    /// `$defs` models) to `out_path`, sorted by schema name. See
+    /// `schema::codegen` for the type mapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `out_path` - Output `.rs` file
+    ///
+    /// # Returns
+    ///
+    /// Names of the generated schema structs
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // build.rs
+    /// let db = Database::open("./data")?;
+    /// db.generate_rust_types(Path::new(&env::var("OUT_DIR")?).join("schemas.rs"))?;
+    /// ```
+    pub fn generate_rust_types<P: AsRef<Path>>(&self, out_path: P) -> Result<Vec<String>> {
+        use crate::schema::codegen;
+        use crate::schema::SchemaCategory;
+
+        let registry = self.registry.read()
+            .map_err(|e| crate::types::DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        let mut names = registry.list();
+        names.sort();
+
+        let mut schemas = Vec::new();
+        for name in names {
+            if registry.get_category(&name)? == SchemaCategory::System {
+                continue;
+            }
+            let schema = registry.get(&name)?.clone();
+            schemas.push((name, schema));
+        }
+        drop(registry);
+
+        std::fs::write(out_path, codegen::generate_rust_types(&schemas))?;
+
+        Ok(schemas.iter().map(|(name, schema)| codegen::struct_name(name, schema)).collect())
+    }
+
+    /// Check if schema exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Schema name
+    ///
+    /// # Returns
+    ///
+    /// `true` if schema is registered
+    pub fn has_schema(&self, name: &str) -> bool {
+        if let Ok(registry) = self.registry.read() {
+            registry.has(name)
+        } else {
+            false
+        }
+    }
+
+    /// Get storage instance (for low-level operations).
+    ///
+    /// # Returns
     ///
     /// Arc reference to storage
     pub fn storage(&self) -> Arc<Storage> {
         Arc::clone(&self.storage)
     }
 
+    /// Get the backend holding entities, key index, and field index.
+    ///
+    /// # Returns
+    ///
+    /// Arc reference to the provider (the RocksDB `storage` by default)
+    pub fn provider(&self) -> Arc<dyn StorageProvider> {
+        Arc::clone(&self.provider)
+    }
+
     /// Get schema registry (for advanced operations).
     ///
     /// # Returns
@@ -233,6 +830,11 @@ impl Database {
         Arc::clone(&self.registry)
     }
 
+    /// Get field index (for `indexed_fields` lookups).
+    pub fn field_index(&self) -> Arc<crate::index::FieldIndexer> {
+        self.field_index.clone()
+    }
+
     /// Insert entity with schema validation and deterministic UUID.
     ///
     /// # Arguments
@@ -257,33 +859,106 @@ impl Database {
     /// - ⏳ Index creation (TODO)
     /// - ⏳ Key index update (TODO)
     pub fn insert(&self, tenant_id: &str, table: &str, data: serde_json::Value) -> Result<uuid::Uuid> {
+        self.insert_entity(tenant_id, table, data, None)
+    }
+
+    /// Insert entity that expires after `ttl`.
+    ///
+    /// Once `ttl` passes the entity is hidden from reads; compaction drops
+    /// it and `vacuum` removes its index entries (see `storage::expiry`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `data` - Entity data
+    /// * `ttl` - Time to live from now
+    ///
+    /// # Returns
+    ///
+    /// Entity UUID (deterministic if key field present)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if schema not found or validation fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let id = db.insert_with_ttl("tenant1", "sessions", json!({"user": "alice"}), Duration::from_secs(3600))?;
+    /// ```
+    pub fn insert_with_ttl(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        data: serde_json::Value,
+        ttl: std::time::Duration,
+    ) -> Result<uuid::Uuid> {
+        self.insert_entity(tenant_id, table, data, Some(ttl))
+    }
+
+    /// Insert implementation; `ttl` of `None` keeps an upserted entity's expiry.
+    fn insert_entity(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        mut data: serde_json::Value,
+        ttl: Option<std::time::Duration>,
+    ) -> Result<uuid::Uuid> {
         use crate::types::{DatabaseError, generate_uuid};
         use crate::schema::{SchemaValidator, PydanticSchemaParser};
 
-        // Get schema
-        let registry = self.registry.read()
-            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+        let _span = db_span(DbOperation::Insert, Some(table), Some(tenant_id)).entered();
 
-        let schema = registry.get(table)?;
+        let (id, key_field_opt, key_case_insensitive, edge_storage_mode, indexed_fields, guard) = loop {
+            let epoch = self.schema_epoch.load(Ordering::Acquire);
 
-        // Validate data against schema
-        let validator = SchemaValidator::new(schema.clone())?;
-        validator.validate(&data)?;
+            // Get schema
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
 
-        // Extract configuration from schema
-        let key_field_opt = PydanticSchemaParser::extract_key_field(schema);
-        let key_field = key_field_opt.as_deref();
-        let edge_storage_mode = PydanticSchemaParser::extract_edge_storage_mode(schema);
+            let schema = registry.get(table)?;
+
+            // Validate data against schema (coercing string values if enabled;
+            // both coercion and projection are no-ops on a retry)
+            let validator = SchemaValidator::new(schema.clone())?
+                .with_coercion(PydanticSchemaParser::extract_coerce_types(schema))
+                .with_content_limit(schema_content_limit(schema)?);
+            let coercions = validator.validate_and_coerce(&mut data)?;
+            if !coercions.is_empty() {
+                tracing::debug!(table, ?coercions, "Coerced string values to schema types");
+            }
+            self.project_embedding(table, &mut data);
+
+            // Extract configuration from schema
+            let key_field_opt = PydanticSchemaParser::extract_key_field(schema);
+            let key_case_insensitive = PydanticSchemaParser::extract_key_case_insensitive(schema);
+            let edge_storage_mode = PydanticSchemaParser::extract_edge_storage_mode(schema);
+            let indexed_fields = PydanticSchemaParser::extract_indexed_fields(schema);
+            drop(registry);
 
-        // Generate deterministic UUID
-        let id = generate_uuid(table, &data, key_field);
+            // Generate deterministic UUID
+            let id = generate_uuid(table, &data, key_field_opt.as_deref());
+
+            // Read the existing entity and write under the commit lock so an
+            // upsert can't interleave with an update of the same entity
+            let guard = self.commit_lock(id);
+
+            // A schema registered since validation (e.g. by `add_field`,
+            // which holds every stripe) may reject the data; validate again
+            if self.schema_epoch.load(Ordering::Acquire) == epoch {
+                break (id, key_field_opt, key_case_insensitive, edge_storage_mode, indexed_fields, guard);
+            }
+        };
+        let key_field = key_field_opt.as_deref();
 
         // Check if entity exists (for edge merging during upsert)
         let entity_key = crate::storage::keys::encode_entity_key(tenant_id, id);
-        let existing_entity_opt = self.storage.get(
+        let existing_entity_opt = self.provider.get(
             crate::storage::column_families::CF_ENTITIES,
             &entity_key,
         )?;
+        let op = if existing_entity_opt.is_some() { ChangeOp::Update } else { ChangeOp::Insert };
 
         // Extract edges from incoming data if present (inline mode)
         let incoming_edges = if edge_storage_mode == "inline" {
@@ -296,11 +971,13 @@ impl Database {
         let mut entity = Entity::new(id, table.to_string(), data);
 
         // Merge edges if entity exists (upsert logic)
-        if let Some(existing_bytes) = existing_entity_opt {
-            let existing_entity: Entity = serde_json::from_slice(&existing_bytes)?;
-
-            // Preserve created_at from existing entity
+        let existing_entity = existing_entity_opt
+            .map(|bytes| serde_json::from_slice::<Entity>(&bytes))
+            .transpose()?;
+        if let Some(existing_entity) = existing_entity.clone() {
+            // Preserve created_at and expiry from existing entity
             entity.system.created_at = existing_entity.system.created_at;
+            entity.system.expires_at = existing_entity.system.expires_at;
 
             // Merge edges (don't override, merge by key)
             if edge_storage_mode == "inline" {
@@ -317,27 +994,35 @@ impl Database {
             }
         }
 
-        // Serialize and store
-        let value = serde_json::to_vec(&entity)?;
-        self.storage.put(
-            crate::storage::column_families::CF_ENTITIES,
-            &entity_key,
-            &value,
-        )?;
+        if let Some(ttl) = ttl {
+            entity.set_ttl(ttl);
+        }
+
+        // Store entity, key index (for reverse lookups) and field indexes atomically
+        let mut batch = vec![BatchOp::Put {
+            cf: crate::storage::column_families::CF_ENTITIES,
+            key: entity_key,
+            value: serde_json::to_vec(&entity)?,
+        }];
 
-        // Update key index for reverse lookups
         if let Some(key_value) = extract_key_value(&entity.properties, key_field) {
-            let index_key = crate::storage::keys::encode_key_index(tenant_id, &key_value, id);
             let index_value = serde_json::json!({"type": table}).to_string();
-            self.storage.put(
-                crate::storage::column_families::CF_KEY_INDEX,
-                &index_key,
-                index_value.as_bytes(),
-            )?;
+            for key_value in key_index_values(key_value, key_case_insensitive) {
+                batch.push(BatchOp::Put {
+                    cf: crate::storage::column_families::CF_KEY_INDEX,
+                    key: crate::storage::keys::encode_key_index(tenant_id, &key_value, id),
+                    value: index_value.as_bytes().to_vec(),
+                });
+            }
         }
 
+        self.batch_field_indexes(&mut batch, tenant_id, &indexed_fields, existing_entity.as_ref(), &entity);
+        self.provider.write_batch(batch)?;
+        drop(guard);
+
+        self.invalidate_query_cache(table);
+
         // TODO: Generate embeddings if configured
-        // TODO: Create field indexes if configured
         // TODO: Handle indexed edge storage mode (write to edges CF)
 
         // Log to WAL if replication enabled
@@ -353,6 +1038,10 @@ impl Database {
             }
         }
 
+        self.notify_change(tenant_id, op, table, id);
+
+        record_db_metrics(None, Some(1));
+
         Ok(id)
     }
 
@@ -371,11 +1060,20 @@ impl Database {
     /// # Errors
     ///
     /// Returns error if schema not found or any validation fails.
-    /// On error, entire batch is rolled back (no partial inserts).
+    /// All rows are validated before anything is written, so a validation
+    /// error inserts nothing. To insert the valid rows anyway, use
+    /// `batch_insert_with_mode`.
+    ///
+    /// # Atomicity
+    ///
+    /// Rows are committed in write batches of `write_batch_size` (see
+    /// `with_write_batch_size`). Each batch is atomic, but the insert as a
+    /// whole is not: if a storage error hits a later batch, earlier batches
+    /// stay written.
     ///
     /// # Performance
     ///
-    /// Uses RocksDB write batch for atomic, efficient bulk inserts.
+    /// Uses RocksDB write batches for efficient bulk inserts.
     /// Significantly faster than individual inserts for large datasets.
     ///
     /// # Example
@@ -395,1547 +1093,8174 @@ impl Database {
         table: &str,
         entities: Vec<serde_json::Value>,
     ) -> Result<Vec<uuid::Uuid>> {
-        use crate::types::{DatabaseError, generate_uuid};
-        use crate::schema::{SchemaValidator, PydanticSchemaParser};
-        use rocksdb::WriteBatch;
-
-        // Get schema once
-        let registry = self.registry.read()
-            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
-
-        let schema = registry.get(table)?;
-        let validator = SchemaValidator::new(schema.clone())?;
-        let key_field_opt = PydanticSchemaParser::extract_key_field(schema);
-        let key_field = key_field_opt.as_deref();
-
-        // Validate all entities first (fail fast before writing)
-        for data in &entities {
-            validator.validate(data)?;
-        }
-
-        // Prepare batch write
-        let mut batch = WriteBatch::default();
-        let mut ids = Vec::with_capacity(entities.len());
-
-        for data in entities {
-            // Generate deterministic UUID
-            let id = generate_uuid(table, &data, key_field);
-            ids.push(id);
-
-            // Create entity with system fields
-            let entity = Entity::new(id, table.to_string(), data.clone());
-
-            // Serialize entity
-            let entity_key = crate::storage::keys::encode_entity_key(tenant_id, id);
-            let entity_value = serde_json::to_vec(&entity)?;
-
-            // Add to batch
-            let cf = self.storage.cf_handle(crate::storage::column_families::CF_ENTITIES);
-            batch.put_cf(&cf, &entity_key, &entity_value);
-
-            // Add key index to batch
-            if let Some(key_value) = extract_key_value(&entity.properties, key_field) {
-                let index_key = crate::storage::keys::encode_key_index(tenant_id, &key_value, id);
-                let index_value = serde_json::json!({"type": table}).to_string();
-                let cf_key_index = self.storage.cf_handle(crate::storage::column_families::CF_KEY_INDEX);
-                batch.put_cf(&cf_key_index, &index_key, index_value.as_bytes());
-            }
-        }
-
-        // Write batch atomically
-        self.storage.db().write(batch)
-            .map_err(|e| DatabaseError::StorageError(e))?;
-
-        Ok(ids)
+        self.batch_insert_with_progress(tenant_id, table, entities, |_| {})
     }
 
-    /// Get entity by ID.
+    /// Batch insert, reporting progress after each committed write batch.
+    ///
+    /// Same behavior as `batch_insert`.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `entity_id` - Entity UUID
+    /// * `table` - Table/schema name
+    /// * `entities` - Vector of entity data objects
+    /// * `on_commit` - Called with the number of rows written so far
     ///
     /// # Returns
     ///
-    /// `Some(Entity)` if found, `None` otherwise
-    pub fn get(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Option<Entity>> {
-        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
-
-        let value = self.storage.get(
-            crate::storage::column_families::CF_ENTITIES,
-            &key,
-        )?;
+    /// Vector of inserted entity UUIDs (in same order as input)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let total = rows.len();
+    /// db.batch_insert_with_progress("tenant1", "person", rows, |written| {
+    ///     println!("{}/{} rows", written, total);
+    /// })?;
+    /// ```
+    pub fn batch_insert_with_progress<F: FnMut(usize)>(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        entities: Vec<serde_json::Value>,
+        mut on_commit: F,
+    ) -> Result<Vec<uuid::Uuid>> {
+        use crate::schema::BatchErrorMode;
 
-        match value {
-            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
-            None => Ok(None),
-        }
+        let report = self.batch_insert_inner(tenant_id, table, entities, BatchErrorMode::FailFast, &mut on_commit)?;
+        Ok(report.ids)
     }
 
-    /// Batch get entities by IDs.
+    /// Batch insert, choosing what happens to rows that fail validation.
+    ///
+    /// - `FailFast`: same as `batch_insert` (an invalid row fails the call
+    ///   and nothing is written)
+    /// - `SkipInvalid`: valid rows are inserted, invalid rows are listed in
+    ///   `skipped`
+    /// - `CollectErrors`: like `SkipInvalid`, with each row's validation
+    ///   error in `errors`
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `entity_ids` - List of entity UUIDs
+    /// * `table` - Table/schema name
+    /// * `entities` - Vector of entity data objects
+    /// * `mode` - Invalid row handling
     ///
     /// # Returns
     ///
-    /// List of entities (Some if found, None if not found, in same order as input)
+    /// `BatchInsertReport` with inserted IDs (input order, invalid rows
+    /// left out) and the skipped row indexes
     ///
-    /// # Performance
+    /// # Errors
     ///
-    /// Uses RocksDB multi_get for efficient bulk retrieval (single operation).
+    /// Returns error if schema not found, a storage write fails, or (under
+    /// `FailFast`) any validation fails
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// let ids = vec![uuid1, uuid2, uuid3];
-    /// let entities = db.get_batch("tenant1", &ids)?;
-    /// // entities[0] corresponds to uuid1, etc.
+    /// let report = db.batch_insert_with_mode("tenant1", "person", rows, BatchErrorMode::CollectErrors)?;
+    /// for (index, error) in &report.errors {
+    ///     eprintln!("row {} skipped: {}", index, error);
+    /// }
     /// ```
-    pub fn get_batch(&self, tenant_id: &str, entity_ids: &[uuid::Uuid]) -> Result<Vec<Option<Entity>>> {
-        let mut entities = Vec::with_capacity(entity_ids.len());
+    pub fn batch_insert_with_mode(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        entities: Vec<serde_json::Value>,
+        mode: crate::schema::BatchErrorMode,
+    ) -> Result<crate::schema::BatchInsertReport> {
+        self.batch_insert_inner(tenant_id, table, entities, mode, &mut |_| {})
+    }
+
+    /// Validate and write a batch under an error mode.
+    fn batch_insert_inner(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        entities: Vec<serde_json::Value>,
+        mode: crate::schema::BatchErrorMode,
+        on_commit: &mut dyn FnMut(usize),
+    ) -> Result<crate::schema::BatchInsertReport> {
+        use crate::types::{DatabaseError, generate_uuid};
+        use crate::schema::{BatchErrorMode, BatchInsertReport, SchemaValidator, PydanticSchemaParser};
 
-        for id in entity_ids {
-            let key = crate::storage::keys::encode_entity_key(tenant_id, *id);
-            let data = self.storage.get(crate::storage::column_families::CF_ENTITIES, &key)?;
+        let _span = db_span(DbOperation::BatchWrite, Some(table), Some(tenant_id)).entered();
 
-            match data {
-                Some(bytes) => {
-                    let entity: Entity = serde_json::from_slice(&bytes)?;
-                    entities.push(Some(entity));
+        // Get schema once
+        let schema_epoch = self.schema_epoch.load(Ordering::Acquire);
+        let registry = self.registry.read()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        let schema = registry.get(table)?;
+        let validator = SchemaValidator::new(schema.clone())?
+            .with_coercion(PydanticSchemaParser::extract_coerce_types(schema))
+            .with_content_limit(schema_content_limit(schema)?);
+        let key_field_opt = PydanticSchemaParser::extract_key_field(schema);
+        let key_field = key_field_opt.as_deref();
+        let key_case_insensitive = PydanticSchemaParser::extract_key_case_insensitive(schema);
+        let indexed_fields = PydanticSchemaParser::extract_indexed_fields(schema);
+        drop(registry);
+
+        // Validate all entities first (fail fast before writing)
+        let mut report = BatchInsertReport::default();
+        let mut valid = Vec::with_capacity(entities.len());
+        let mut coerced = 0;
+        for (index, mut data) in entities.into_iter().enumerate() {
+            match validator.validate_and_coerce(&mut data) {
+                Ok(coercions) => {
+                    coerced += coercions.len();
+                    self.project_embedding(table, &mut data);
+                    valid.push(data);
                 }
-                None => entities.push(None),
+                Err(e) => match mode {
+                    BatchErrorMode::FailFast => return Err(e),
+                    BatchErrorMode::SkipInvalid => report.skipped.push(index),
+                    BatchErrorMode::CollectErrors => {
+                        report.skipped.push(index);
+                        report.errors.push((index, e.to_string()));
+                    }
+                },
             }
         }
+        if coerced > 0 {
+            tracing::debug!(table, coerced, "Coerced string values to schema types");
+        }
+        if !report.skipped.is_empty() {
+            tracing::warn!(table, skipped = report.skipped.len(), "Skipped invalid rows in batch insert");
+        }
 
-        Ok(entities)
+        // Create entities with deterministic UUIDs
+        let entities: Vec<Entity> = valid
+            .into_iter()
+            .map(|data| Entity::new(generate_uuid(table, &data, key_field), table.to_string(), data))
+            .collect();
+
+        self.write_entities_batch(tenant_id, table, schema_epoch, &entities, key_field, key_case_insensitive, &indexed_fields, true, on_commit)?;
+
+        record_db_metrics(None, Some(entities.len()));
+
+        report.ids = entities.iter().map(|e| e.system.id).collect();
+        Ok(report)
     }
 
-    /// Batch lookup entities by key values.
+    /// Validate rows against a table's schema without writing anything.
+    ///
+    /// Applies the same coercion and validation as `batch_insert`, but
+    /// checks every row instead of stopping at the first failure.
     ///
     /// # Arguments
     ///
-    /// * `tenant_id` - Tenant identifier
-    /// * `key_values` - List of key values to lookup
+    /// * `table` - Table/schema name
+    /// * `rows` - Entity data to check
     ///
     /// # Returns
     ///
-    /// List of entity lists (one list per key value, in same order as input).
-    /// Each inner list contains all entities matching that key value.
+    /// `PreflightReport` with the valid count and per-row errors by index
     ///
-    /// # Performance
+    /// # Errors
     ///
-    /// More efficient than individual lookups due to batched index scans.
+    /// Returns `DatabaseError::SchemaNotFound` if the table is not registered
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// let keys = vec!["Alice".to_string(), "Bob".to_string()];
-    /// let results = db.lookup_batch("tenant1", &keys)?;
-    /// // results[0] = all entities with key "Alice"
-    /// // results[1] = all entities with key "Bob"
+    /// let report = db.preflight("person", &rows)?;
+    /// if report.is_valid() {
+    ///     db.batch_insert("tenant1", "person", rows)?;
+    /// } else {
+    ///     for (index, error) in &report.errors {
+    ///         eprintln!("row {}: {}", index, error);
+    ///     }
+    /// }
     /// ```
-    pub fn lookup_batch(&self, tenant_id: &str, key_values: &[String]) -> Result<Vec<Vec<Entity>>> {
-        use std::collections::HashMap;
+    pub fn preflight(
+        &self,
+        table: &str,
+        rows: &[serde_json::Value],
+    ) -> Result<crate::schema::PreflightReport> {
+        use crate::schema::{PreflightReport, SchemaValidator, PydanticSchemaParser};
 
-        // Scan key index for all keys
-        let mut entities_by_key: HashMap<String, Vec<uuid::Uuid>> = HashMap::new();
+        let registry = self.registry.read()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
 
-        for key_value in key_values {
-            let prefix = format!("key:{}:{}:", tenant_id, key_value);
-            let prefix_bytes = prefix.as_bytes();
+        let schema = registry.get(table)?;
+        let validator = SchemaValidator::new(schema.clone())?
+            .with_coercion(PydanticSchemaParser::extract_coerce_types(schema))
+            .with_content_limit(schema_content_limit(schema)?);
+
+        let mut report = PreflightReport::default();
+        for (index, row) in rows.iter().enumerate() {
+            let mut row = row.clone();
+            match validator.validate_and_coerce(&mut row) {
+                Ok(_) => report.valid += 1,
+                Err(e) => report.errors.push((index, e.to_string())),
+            }
+        }
 
-            let cf = self.storage.cf_handle(crate::storage::column_families::CF_KEY_INDEX);
-            let iter = self.storage.db().iterator_cf(&cf, rocksdb::IteratorMode::From(prefix_bytes, rocksdb::Direction::Forward));
+        Ok(report)
+    }
 
-            for item in iter {
-                let (key, _value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+    /// Write entities with their key and field index entries.
+    ///
+    /// Commits one atomic write batch per `write_batch_size` entities,
+    /// calling `on_commit` with the running total after each. With
+    /// `merge_existing`, upserted entities keep their stored `created_at`,
+    /// expiry, and edges as in `insert`; otherwise (imports) they are
+    /// written as given. `schema_epoch` is the schema epoch the entities
+    /// were validated at.
+    #[allow(clippy::too_many_arguments)]
+    fn write_entities_batch(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        schema_epoch: u64,
+        entities: &[Entity],
+        key_field: Option<&str>,
+        key_case_insensitive: bool,
+        indexed_fields: &[String],
+        merge_existing: bool,
+        on_commit: &mut dyn FnMut(usize),
+    ) -> Result<()> {
+        let mut written = 0;
+        for chunk in entities.chunks(self.write_batch_size) {
+            self.write_entities_chunk(tenant_id, table, schema_epoch, chunk, key_field, key_case_insensitive, indexed_fields, merge_existing)?;
+            written += chunk.len();
+            on_commit(written);
+        }
+        Ok(())
+    }
 
-                // Check if key still has our prefix
-                if !key.starts_with(prefix_bytes) {
-                    break;
-                }
+    /// Write one chunk of entities and their index entries in one atomic batch.
+    ///
+    /// Holds the chunk's commit stripes from reading the stored entities
+    /// until the batch is written, so upserts can't interleave with updates.
+    /// If a schema was registered after `schema_epoch`, the chunk is
+    /// validated again under the stripes.
+    #[allow(clippy::too_many_arguments)]
+    fn write_entities_chunk(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        schema_epoch: u64,
+        entities: &[Entity],
+        key_field: Option<&str>,
+        key_case_insensitive: bool,
+        indexed_fields: &[String],
+        merge_existing: bool,
+    ) -> Result<()> {
+        use crate::storage::column_families::{CF_ENTITIES, CF_KEY_INDEX};
+        use std::collections::HashMap;
 
-                // Extract UUID from key: key:{tenant}:{value}:{uuid}
-                let key_str = std::str::from_utf8(&key)
-                    .map_err(|e| crate::types::DatabaseError::InternalError(format!("Invalid UTF-8 in key: {}", e)))?;
+        let keys: Vec<Vec<u8>> = entities
+            .iter()
+            .map(|entity| crate::storage::keys::encode_entity_key(tenant_id, entity.system.id))
+            .collect();
 
-                let parts: Vec<&str> = key_str.split(':').collect();
-                if parts.len() >= 4 {
-                    if let Ok(entity_id) = uuid::Uuid::parse_str(parts[3]) {
-                        entities_by_key.entry(key_value.clone()).or_default().push(entity_id);
-                    }
-                }
+        let guards = self.commit_lock_many(entities.iter().map(|entity| entity.system.id));
+
+        if self.schema_epoch.load(Ordering::Acquire) != schema_epoch {
+            let validator = {
+                let registry = self.registry.read()
+                    .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+                crate::schema::SchemaValidator::new(registry.get(table)?.clone())?
+            };
+            for entity in entities {
+                validator.validate(&entity.properties)?;
             }
         }
 
-        // Fetch entities for each key
-        let mut results = Vec::with_capacity(key_values.len());
-        for key_value in key_values {
-            if let Some(entity_ids) = entities_by_key.get(key_value) {
-                let entities = self.get_batch(tenant_id, entity_ids)?
-                    .into_iter()
-                    .flatten()
-                    .collect();
-                results.push(entities);
-            } else {
-                results.push(Vec::new());
+        let stored = self.provider.multi_get(CF_ENTITIES, &keys)?;
+
+        let mut batch = Vec::new();
+        let mut ops = Vec::with_capacity(entities.len());
+        // Later rows with the same id replace earlier ones in this chunk
+        let mut written: HashMap<uuid::Uuid, Entity> = HashMap::new();
+        let index_value = serde_json::json!({"type": table}).to_string().into_bytes();
+
+        for ((entity, key), stored) in entities.iter().zip(keys).zip(stored) {
+            let id = entity.system.id;
+            let previous = match written.get(&id) {
+                Some(previous) => Some(previous.clone()),
+                None => stored.map(|bytes| serde_json::from_slice::<Entity>(&bytes)).transpose()?,
+            };
+
+            let mut entity = entity.clone();
+            if let Some(previous) = previous.as_ref().filter(|_| merge_existing) {
+                entity.system.created_at = previous.system.created_at.clone();
+                entity.system.expires_at = previous.system.expires_at.clone();
+                entity.system.edges = previous.system.edges.clone();
+            }
+
+            batch.push(BatchOp::Put {
+                cf: CF_ENTITIES,
+                key,
+                value: serde_json::to_vec(&entity)?,
+            });
+
+            // Add key index to batch
+            if let Some(key_value) = extract_key_value(&entity.properties, key_field) {
+                for key_value in key_index_values(key_value, key_case_insensitive) {
+                    batch.push(BatchOp::Put {
+                        cf: CF_KEY_INDEX,
+                        key: crate::storage::keys::encode_key_index(tenant_id, &key_value, id),
+                        value: index_value.clone(),
+                    });
+                }
             }
+
+            // Replace the previous version's field index entries
+            self.batch_field_indexes(&mut batch, tenant_id, indexed_fields, previous.as_ref(), &entity);
+
+            ops.push((id, if previous.is_some() { ChangeOp::Update } else { ChangeOp::Insert }));
+            written.insert(id, entity);
         }
 
-        Ok(results)
+        // Write batch atomically
+        self.provider.write_batch(batch)?;
+        drop(guards);
+
+        self.invalidate_query_cache(table);
+
+        for (entity_id, op) in ops {
+            self.notify_change(tenant_id, op, table, entity_id);
+        }
+
+        Ok(())
     }
 
-    /// Update entity properties.
+    /// Export all live entities of a table.
+    ///
+    /// Parquet and JSONL output can be restored with `import_parquet` and
+    /// `import_jsonl`.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `entity_id` - Entity UUID to update
-    /// * `updates` - JSON object with fields to update (partial or full)
+    /// * `table` - Table/schema name
+    /// * `path` - Output file path
+    /// * `format` - Output format
     ///
     /// # Returns
     ///
-    /// Updated entity
+    /// Number of rows written
     ///
     /// # Errors
     ///
-    /// Returns error if entity not found or validation fails
+    /// Returns `DatabaseError::ExportError` if writing fails or (for Parquet
+    /// and CSV) the table is empty
+    pub fn export<P: AsRef<Path>>(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        path: P,
+        format: crate::export::ExportFormat,
+    ) -> Result<usize> {
+        self.export_query(tenant_id, table, None, None, path, format)
+    }
+
+    /// Export the entities of a table that match a predicate, optionally
+    /// keeping only some columns.
     ///
-    /// # Features
+    /// Filtering uses the field index for equality on an indexed field
+    /// (like `search_filtered`). With `columns`, each row holds just those
+    /// columns in the given order (see `export::projection`); such files are
+    /// not importable. Without `columns` the output matches `export`.
     ///
-    /// - ✅ Partial updates (merge with existing properties)
-    /// - ✅ Schema validation on updated entity
-    /// - ✅ Updates modified_at timestamp
-    /// - ⏳ Re-generate embeddings if embedding fields changed (TODO)
-    /// - ⏳ Update field indexes if indexed fields changed (TODO)
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `predicate` - Rows to keep (`None` = all live rows)
+    /// * `columns` - System fields or properties to write (`None` = full entities)
+    /// * `path` - Output file path
+    /// * `format` - Output format
+    ///
+    /// # Returns
+    ///
+    /// Number of rows written
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ValidationError` if a column is neither a
+    /// system field nor a schema property, or `DatabaseError::ExportError`
+    /// if writing fails
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// // Update single field
-    /// db.update("tenant1", entity_id, json!({"age": 31}))?;
-    ///
-    /// // Update multiple fields
-    /// db.update("tenant1", entity_id, json!({"age": 31, "status": "active"}))?;
+    /// let docs = Predicate::eq("category", json!("docs"));
+    /// db.export_query("tenant1", "articles", Some(&docs), Some(&["id", "title"]), "docs.csv", ExportFormat::Csv)?;
     /// ```
-    pub fn update(&self, tenant_id: &str, entity_id: uuid::Uuid, updates: serde_json::Value) -> Result<Entity> {
-        use crate::types::DatabaseError;
-        use crate::schema::SchemaValidator;
-
-        // Get existing entity
-        let mut entity = self.get(tenant_id, entity_id)?
-            .ok_or_else(|| DatabaseError::EntityNotFound(entity_id))?;
-
-        // Merge updates into properties
-        if let Some(updates_obj) = updates.as_object() {
-            if let Some(props_obj) = entity.properties.as_object_mut() {
-                for (key, value) in updates_obj {
-                    props_obj.insert(key.clone(), value.clone());
+    pub fn export_query<P: AsRef<Path>>(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        predicate: Option<&crate::query::Predicate>,
+        columns: Option<&[&str]>,
+        path: P,
+        format: crate::export::ExportFormat,
+    ) -> Result<usize> {
+        use crate::export::{CsvExporter, ExportFormat, JsonlExporter, ParquetExporter, ProjectedRows};
+
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
+
+        if let Some(columns) = columns {
+            let schema = self.get_schema(table)?;
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+
+            for column in columns {
+                let known = crate::export::projection::SYSTEM_FIELDS.contains(column)
+                    || properties.is_some_and(|p| p.contains_key(*column));
+                if !known {
+                    return Err(DatabaseError::validation(format!(
+                        "Unknown column '{}' for table '{}'",
+                        column, table
+                    )));
                 }
             }
-        } else {
-            // Full replacement if updates is not an object
-            entity.properties = updates;
         }
 
-        // Validate updated entity against schema
-        let registry = self.registry.read()
-            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
-
-        let schema = registry.get(&entity.system.entity_type)?;
-        let validator = SchemaValidator::new(schema.clone())?;
-        validator.validate(&entity.properties)?;
-
-        // Update modified_at timestamp
-        entity.system.modified_at = chrono::Utc::now().to_rfc3339();
-
-        // Serialize and store
-        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
-        let value = serde_json::to_vec(&entity)?;
-
-        self.storage.put(
-            crate::storage::column_families::CF_ENTITIES,
-            &key,
-            &value,
-        )?;
-
-        // TODO: Re-generate embeddings if embedding fields changed
-        // TODO: Update field indexes if indexed fields changed
+        let entities = self.filter_candidates(tenant_id, table, predicate)?;
 
-        // Log to WAL if replication enabled
-        if let Some(ref wal) = self.wal {
-            if self.replication_mode == ReplicationMode::Primary {
-                let op = crate::replication::WalOperation::Update {
-                    tenant_id: tenant_id.to_string(),
-                    entity_id: entity_id.to_string(),
-                    changes: serde_json::to_value(&entity.properties)?,
-                };
-                wal.write()
-                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?
-                    .append(op)?;
+        match columns {
+            Some(columns) => {
+                let rows = ProjectedRows::project(&entities, columns);
+                match format {
+                    ExportFormat::Parquet => ParquetExporter::export_rows(&rows, path)?,
+                    ExportFormat::Csv => CsvExporter::export_rows(&rows, path)?,
+                    ExportFormat::Jsonl => JsonlExporter::export_rows(&rows, path)?,
+                }
             }
+            None => match format {
+                ExportFormat::Parquet => ParquetExporter::export(&entities, path)?,
+                ExportFormat::Csv => CsvExporter::export(&entities, path)?,
+                ExportFormat::Jsonl => JsonlExporter::export(&entities, path)?,
+            },
         }
 
-        Ok(entity)
+        record_db_metrics(Some(entities.len()), None);
+
+        Ok(entities.len())
     }
 
-    /// Delete entity (soft delete by default).
+    /// Import entities from a Parquet file written by `ParquetExporter`.
+    ///
+    /// Entity IDs, timestamps, and properties (including embeddings) are
+    /// restored as exported. Rows of other entity types are skipped, so a
+    /// multi-table export is restored by importing once per table.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `entity_id` - Entity UUID to delete
+    /// * `table` - Table/schema name to import
+    /// * `path` - Parquet file path
     ///
     /// # Returns
     ///
-    /// Deleted entity (with deleted_at set)
+    /// IDs of imported entities
     ///
     /// # Errors
     ///
-    /// Returns error if entity not found
-    ///
-    /// # Note
-    ///
-    /// This is a soft delete - sets `deleted_at` timestamp but keeps the entity in storage.
-    /// For hard delete (permanent removal), use `hard_delete()`.
+    /// Returns `DatabaseError::IngestError` if the file can't be decoded,
+    /// or `DatabaseError::ValidationError` if a row fails schema validation
+    /// (nothing is written in that case)
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// let deleted = db.delete("tenant1", entity_id)?;
-    /// assert!(deleted.is_deleted());
+    /// ParquetExporter::export(&db.list("tenant1", "articles", true, None, None)?, "backup.parquet")?;
+    /// let restored = Database::open("./restore")?;
+    /// restored.import_parquet("tenant1", "articles", "backup.parquet")?;
     /// ```
-    pub fn delete(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Entity> {
-        use crate::types::DatabaseError;
-
-        // Get existing entity
-        let mut entity = self.get(tenant_id, entity_id)?
-            .ok_or_else(|| DatabaseError::EntityNotFound(entity_id))?;
-
-        // Mark as deleted
-        entity.mark_deleted();
-
-        // Serialize and store
-        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
-        let value = serde_json::to_vec(&entity)?;
-
-        self.storage.put(
-            crate::storage::column_families::CF_ENTITIES,
-            &key,
-            &value,
-        )?;
-
-        // Log to WAL if replication enabled
-        if let Some(ref wal) = self.wal {
-            if self.replication_mode == ReplicationMode::Primary {
-                let op = crate::replication::WalOperation::Delete {
-                    tenant_id: tenant_id.to_string(),
-                    entity_id: entity_id.to_string(),
-                };
-                wal.write()
-                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?
-                    .append(op)?;
-            }
-        }
-
-        Ok(entity)
+    pub fn import_parquet<P: AsRef<Path>>(&self, tenant_id: &str, table: &str, path: P) -> Result<Vec<uuid::Uuid>> {
+        let entities = crate::export::ParquetImporter::read(path)?;
+        self.import_entities(tenant_id, table, entities)
     }
 
-    /// Hard delete entity (permanent removal).
+    /// Import entities from a JSONL file written by `JsonlExporter`.
+    ///
+    /// Same behavior as `import_parquet`.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `entity_id` - Entity UUID to delete
+    /// * `table` - Table/schema name to import
+    /// * `path` - JSONL file path
     ///
     /// # Returns
     ///
-    /// Ok if deletion successful
+    /// IDs of imported entities
     ///
     /// # Errors
     ///
-    /// Returns error if entity not found
-    ///
-    /// # Warning
-    ///
-    /// This is a permanent delete - the entity cannot be recovered.
-    /// Consider using `delete()` (soft delete) instead.
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// db.hard_delete("tenant1", entity_id)?;
-    /// assert!(db.get("tenant1", entity_id)?.is_none());
-    /// ```
-    pub fn hard_delete(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<()> {
-        use crate::types::DatabaseError;
+    /// Returns `DatabaseError::IngestError` if a line is not a valid entity,
+    /// or `DatabaseError::ValidationError` if a row fails schema validation
+    pub fn import_jsonl<P: AsRef<Path>>(&self, tenant_id: &str, table: &str, path: P) -> Result<Vec<uuid::Uuid>> {
+        let entities = crate::export::JsonlImporter::read(path)?;
+        self.import_entities(tenant_id, table, entities)
+    }
 
-        // Verify entity exists
-        let entity = self.get(tenant_id, entity_id)?
-            .ok_or_else(|| DatabaseError::EntityNotFound(entity_id))?;
+    /// Validate and write exported entities of `table`, keeping their system fields.
+    fn import_entities(&self, tenant_id: &str, table: &str, entities: Vec<Entity>) -> Result<Vec<uuid::Uuid>> {
+        use crate::types::DatabaseError;
+        use crate::schema::{SchemaValidator, PydanticSchemaParser};
 
-        // Delete from entities CF
-        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
-        self.storage.delete(crate::storage::column_families::CF_ENTITIES, &key)?;
+        let _span = db_span(DbOperation::BatchWrite, Some(table), Some(tenant_id)).entered();
 
-        // Delete from key index if entity has a key value
+        let schema_epoch = self.schema_epoch.load(Ordering::Acquire);
         let registry = self.registry.read()
             .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
 
-        let schema = registry.get(&entity.system.entity_type)?;
-        let key_field_opt = crate::schema::PydanticSchemaParser::extract_key_field(schema);
-        let key_field = key_field_opt.as_deref();
+        let schema = registry.get(table)?;
+        let validator = SchemaValidator::new(schema.clone())?;
+        let key_field_opt = PydanticSchemaParser::extract_key_field(schema);
+        let key_case_insensitive = PydanticSchemaParser::extract_key_case_insensitive(schema);
+        let indexed_fields = PydanticSchemaParser::extract_indexed_fields(schema);
+        drop(registry);
 
-        if let Some(key_value) = extract_key_value(&entity.properties, key_field) {
-            let index_key = crate::storage::keys::encode_key_index(tenant_id, &key_value, entity_id);
-            self.storage.delete(crate::storage::column_families::CF_KEY_INDEX, &index_key)?;
+        let entities: Vec<Entity> = entities
+            .into_iter()
+            .filter(|e| e.system.entity_type == table)
+            .collect();
+
+        // Validate all entities first (fail fast before writing)
+        for entity in &entities {
+            validator.validate(&entity.properties)?;
         }
 
-        // TODO: Delete embeddings from CF_EMBEDDINGS
-        // TODO: Delete field indexes from CF_INDEXES
-        // TODO: Delete edges from CF_EDGES and CF_EDGES_REVERSE
+        self.write_entities_batch(tenant_id, table, schema_epoch, &entities, key_field_opt.as_deref(), key_case_insensitive, &indexed_fields, false, &mut |_| {})?;
 
-        Ok(())
+        record_db_metrics(None, Some(entities.len()));
+
+        Ok(entities.iter().map(|e| e.system.id).collect())
     }
 
-    /// List entities in a table with optional filters.
+    /// Subscribe to entity changes.
+    ///
+    /// Every successful insert, update, and delete in scope is delivered to
+    /// each subscriber. A subscriber that falls more than
+    /// `DEFAULT_CHANGE_CHANNEL_CAPACITY` events behind gets
+    /// `RecvError::Lagged` and resumes from the oldest buffered event.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `table` - Table/schema name to scan
-    /// * `include_deleted` - Include soft-deleted entities (default: false)
-    /// * `limit` - Maximum number of entities to return (optional)
+    /// * `table` - Only this table's changes (`None` = all tables)
     ///
     /// # Returns
     ///
-    /// Vector of entities matching criteria
+    /// Broadcast receiver of `ChangeEvent`s
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// // List all active persons
-    /// let persons = db.list("tenant1", "person", false, None)?;
-    ///
-    /// // List first 10 entities including deleted
-    /// let all = db.list("tenant1", "person", true, Some(10))?;
+    /// let mut changes = db.subscribe("tenant1", Some("articles"));
+    /// tokio::spawn(async move {
+    ///     while let Ok(event) = changes.recv().await {
+    ///         cache.invalidate(event.entity_id);
+    ///     }
+    /// });
     /// ```
-    pub fn list(
-        &self,
-        tenant_id: &str,
-        table: &str,
-        include_deleted: bool,
-        limit: Option<usize>,
-    ) -> Result<Vec<Entity>> {
-        use rocksdb::IteratorMode;
+    pub fn subscribe(&self, tenant_id: &str, table: Option<&str>) -> tokio::sync::broadcast::Receiver<ChangeEvent> {
+        self.changes.subscribe(tenant_id, table)
+    }
 
-        // Scan prefix: entity:{tenant_id}:
-        let prefix = format!("entity:{}:", tenant_id).into_bytes();
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_ENTITIES);
+    /// Publish a change event to subscribers.
+    fn notify_change(&self, tenant_id: &str, op: ChangeOp, table: &str, entity_id: uuid::Uuid) {
+        if !self.changes.has_subscribers() {
+            return;
+        }
 
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
-        );
+        self.changes.publish(tenant_id, ChangeEvent {
+            op,
+            table: table.to_string(),
+            entity_id,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        });
+    }
 
-        let mut entities = Vec::new();
-        let mut count = 0;
-
-        for item in iter {
-            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+    /// Get entity by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity UUID
+    ///
+    /// # Returns
+    ///
+    /// `Some(Entity)` if found, `None` if missing or expired
+    pub fn get(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Option<Entity>> {
+        let _span = db_span(DbOperation::Get, None, Some(tenant_id)).entered();
 
-            // Check if key still matches prefix
-            if !key.starts_with(&prefix) {
-                break;
-            }
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
 
-            // Deserialize entity
-            let entity: Entity = serde_json::from_slice(&value)?;
+        let value = self.provider.get(
+            crate::storage::column_families::CF_ENTITIES,
+            &key,
+        )?;
 
-            // Filter by table type
-            if entity.system.entity_type != table {
-                continue;
+        match value {
+            Some(data) => {
+                let entity: Entity = serde_json::from_slice(&data)?;
+                // Expired entities are hidden until `vacuum` purges them
+                if entity.is_expired() {
+                    record_db_metrics(Some(0), None);
+                    return Ok(None);
+                }
+                record_db_metrics(Some(1), None);
+                Ok(Some(entity))
             }
-
-            // Filter deleted entities unless explicitly included
-            if !include_deleted && entity.is_deleted() {
-                continue;
+            None => {
+                record_db_metrics(Some(0), None);
+                Ok(None)
             }
+        }
+    }
 
-            entities.push(entity);
-            count += 1;
+    /// Batch get entities by IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_ids` - List of entity UUIDs
+    ///
+    /// # Returns
+    ///
+    /// List of entities (Some if found, None if not found or expired, in same order as input)
+    ///
+    /// # Performance
+    ///
+    /// Uses the backend's multi-get for efficient bulk retrieval (a single
+    /// RocksDB `multi_get`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let ids = vec![uuid1, uuid2, uuid3];
+    /// let entities = db.get_batch("tenant1", &ids)?;
+    /// // entities[0] corresponds to uuid1, etc.
+    /// ```
+    pub fn get_batch(&self, tenant_id: &str, entity_ids: &[uuid::Uuid]) -> Result<Vec<Option<Entity>>> {
+        let _span = db_span(DbOperation::Get, None, Some(tenant_id)).entered();
 
-            // Check limit
-            if let Some(max) = limit {
-                if count >= max {
-                    break;
+        let keys: Vec<Vec<u8>> = entity_ids
+            .iter()
+            .map(|id| crate::storage::keys::encode_entity_key(tenant_id, *id))
+            .collect();
+
+        let mut entities = Vec::with_capacity(entity_ids.len());
+
+        for data in self.provider.multi_get(crate::storage::column_families::CF_ENTITIES, &keys)? {
+            match data {
+                Some(bytes) => {
+                    let entity: Entity = serde_json::from_slice(&bytes)?;
+                    entities.push(Some(entity).filter(|e| !e.is_expired()));
                 }
+                None => entities.push(None),
             }
         }
 
+        record_db_metrics(Some(entities.iter().flatten().count()), None);
+
         Ok(entities)
     }
 
-    /// Count entities in a table.
+    /// Batch lookup entities by key values.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `table` - Table/schema name
-    /// * `include_deleted` - Include soft-deleted entities (default: false)
+    /// * `key_values` - List of key values to lookup
     ///
     /// # Returns
     ///
-    /// Number of entities matching criteria
+    /// List of entity lists (one list per key value, in same order as input).
+    /// Each inner list contains all entities matching that key value.
+    ///
+    /// # Performance
+    ///
+    /// More efficient than individual lookups due to batched index scans.
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// let count = db.count("tenant1", "person", false)?;
-    /// println!("Active persons: {}", count);
+    /// let keys = vec!["Alice".to_string(), "Bob".to_string()];
+    /// let results = db.lookup_batch("tenant1", &keys)?;
+    /// // results[0] = all entities with key "Alice"
+    /// // results[1] = all entities with key "Bob"
     /// ```
-    pub fn count(&self, tenant_id: &str, table: &str, include_deleted: bool) -> Result<usize> {
-        use rocksdb::IteratorMode;
+    pub fn lookup_batch(&self, tenant_id: &str, key_values: &[String]) -> Result<Vec<Vec<Entity>>> {
+        use std::collections::HashMap;
 
-        let prefix = format!("entity:{}:", tenant_id).into_bytes();
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_ENTITIES);
+        let _span = db_span(DbOperation::Scan, Some(crate::storage::column_families::CF_KEY_INDEX), Some(tenant_id)).entered();
 
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
-        );
+        // Scan key index for all keys
+        let mut entities_by_key: HashMap<String, Vec<uuid::Uuid>> = HashMap::new();
 
-        let mut count = 0;
+        for key_value in key_values {
+            let prefix = format!("key:{}:{}:", tenant_id, key_value);
+            let prefix_bytes = prefix.as_bytes();
 
-        for item in iter {
-            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+            let iter = self.provider.iterator(crate::storage::column_families::CF_KEY_INDEX, prefix_bytes);
 
-            if !key.starts_with(&prefix) {
-                break;
-            }
+            for item in iter {
+                let (key, _value) = item?;
 
-            let entity: Entity = serde_json::from_slice(&value)?;
+                // Extract UUID from key: key:{tenant}:{value}:{uuid}
+                let key_str = std::str::from_utf8(&key)
+                    .map_err(|e| crate::types::DatabaseError::InternalError(format!("Invalid UTF-8 in key: {}", e)))?;
 
-            if entity.system.entity_type != table {
-                continue;
+                let parts: Vec<&str> = key_str.split(':').collect();
+                if parts.len() >= 4 {
+                    if let Ok(entity_id) = uuid::Uuid::parse_str(parts[3]) {
+                        entities_by_key.entry(key_value.clone()).or_default().push(entity_id);
+                    }
+                }
             }
+        }
 
-            if !include_deleted && entity.is_deleted() {
-                continue;
+        // Fetch entities for each key
+        let mut results = Vec::with_capacity(key_values.len());
+        for key_value in key_values {
+            if let Some(entity_ids) = entities_by_key.get(key_value) {
+                let entities = self.get_batch(tenant_id, entity_ids)?
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                results.push(entities);
+            } else {
+                results.push(Vec::new());
             }
-
-            count += 1;
         }
 
-        Ok(count)
+        record_db_metrics(Some(results.iter().map(Vec::len).sum()), None);
+
+        Ok(results)
     }
 
-    /// Check if entity exists.
+    /// Update entity properties.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `entity_id` - Entity UUID
+    /// * `entity_id` - Entity UUID to update
+    /// * `updates` - JSON object with fields to update (partial or full)
     ///
     /// # Returns
     ///
-    /// `true` if entity exists, `false` otherwise
+    /// Updated entity
     ///
-    /// # Note
+    /// # Errors
     ///
-    /// More efficient than `get()` when you only need to check existence.
+    /// Returns error if entity not found or validation fails
+    ///
+    /// # Features
+    ///
+    /// - ✅ Partial updates (merge with existing properties)
+    /// - ✅ Schema validation on updated entity
+    /// - ✅ Updates modified_at timestamp
+    /// - ⏳ Re-generate embeddings if embedding fields changed (TODO)
+    /// - ⏳ Update field indexes if indexed fields changed (TODO)
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// if db.exists("tenant1", entity_id)? {
-    ///     println!("Entity exists");
-    /// }
+    /// // Update single field
+    /// db.update("tenant1", entity_id, json!({"age": 31}))?;
+    ///
+    /// // Update multiple fields
+    /// db.update("tenant1", entity_id, json!({"age": 31, "status": "active"}))?;
     /// ```
-    pub fn exists(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<bool> {
-        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
-
-        let value = self.storage.get(
-            crate::storage::column_families::CF_ENTITIES,
-            &key,
-        )?;
-
-        Ok(value.is_some())
+    pub fn update(&self, tenant_id: &str, entity_id: uuid::Uuid, updates: serde_json::Value) -> Result<Entity> {
+        self.update_with(tenant_id, entity_id, |entity| {
+            // Merge updates into properties
+            if let Some(updates_obj) = updates.as_object() {
+                if let Some(props_obj) = entity.properties.as_object_mut() {
+                    for (key, value) in updates_obj {
+                        props_obj.insert(key.clone(), value.clone());
+                    }
+                }
+            } else {
+                // Full replacement if updates is not an object
+                entity.properties = updates.clone();
+            }
+            Ok(())
+        })
     }
 
-    /// Add edge between entities.
+    /// Update entity with a read-modify-write closure.
+    ///
+    /// Optimistic concurrency: the closure runs on a fresh read, and the
+    /// result is committed only if the stored entity is still unchanged.
+    /// If another writer got there first, the read and closure are retried
+    /// with backoff (see `with_write_retries`), so concurrent increments or
+    /// merges are never lost. The closure may therefore run more than once.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
-    /// * `src_id` - Source entity UUID
-    /// * `dst_id` - Destination entity UUID
-    /// * `rel_type` - Relationship type (e.g., "authored", "references")
-    /// * `properties` - Optional edge properties
+    /// * `entity_id` - Entity UUID
+    /// * `mutate` - Applies the change to the current entity
     ///
     /// # Returns
     ///
-    /// Created edge
+    /// Updated entity
     ///
     /// # Errors
     ///
-    /// Returns error if entities don't exist
+    /// Returns `DatabaseError::EntityNotFound` if the entity does not exist,
+    /// `DatabaseError::ValidationError` if the result fails schema validation,
+    /// `DatabaseError::WriteConflict` once retries are exhausted, or any error
+    /// returned by `mutate`
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// db.add_edge("tenant1", author_id, article_id, "authored", None)?;
-    /// db.add_edge("tenant1", article1_id, article2_id, "references",
-    ///     Some(json!({"weight": 0.8})))?;
+    /// db.update_with("tenant1", counter_id, |entity| {
+    ///     let count = entity.properties["count"].as_i64().unwrap_or(0);
+    ///     entity.properties["count"] = json!(count + 1);
+    ///     Ok(())
+    /// })?;
     /// ```
-    pub fn add_edge(
-        &self,
-        tenant_id: &str,
-        src_id: uuid::Uuid,
-        dst_id: uuid::Uuid,
-        rel_type: &str,
-        properties: Option<serde_json::Value>,
-    ) -> Result<Edge> {
-        use crate::types::{DatabaseError, Edge};
-
-        // Verify both entities exist
-        if !self.exists(tenant_id, src_id)? {
-            return Err(DatabaseError::EntityNotFound(src_id));
-        }
-        if !self.exists(tenant_id, dst_id)? {
-            return Err(DatabaseError::EntityNotFound(dst_id));
-        }
+    pub fn update_with<F>(&self, tenant_id: &str, entity_id: uuid::Uuid, mut mutate: F) -> Result<Entity>
+    where
+        F: FnMut(&mut Entity) -> Result<()>,
+    {
+        use crate::types::DatabaseError;
+        use crate::schema::SchemaValidator;
 
-        // Create edge
-        let mut edge = Edge::new(src_id, dst_id, rel_type.to_string());
+        let _span = db_span(DbOperation::Update, None, Some(tenant_id)).entered();
 
-        // Add properties if provided
-        if let Some(props) = properties {
-            if let Some(obj) = props.as_object() {
-                for (key, value) in obj {
-                    edge.add_property(key.clone(), value.clone());
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
+        let mut attempts = 0;
+
+        let entity = loop {
+            attempts += 1;
+            let schema_epoch = self.schema_epoch.load(Ordering::Acquire);
+
+            // Read snapshot
+            let snapshot = self.provider.get(crate::storage::column_families::CF_ENTITIES, &key)?
+                .ok_or_else(|| DatabaseError::EntityNotFound(entity_id))?;
+            let previous: Entity = serde_json::from_slice(&snapshot)?;
+
+            let mut entity = previous.clone();
+            mutate(&mut entity)?;
+
+            // Validate updated entity against schema
+            let indexed_fields = {
+                let registry = self.registry.read()
+                    .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+                let schema = registry.get(&entity.system.entity_type)?;
+                let validator = SchemaValidator::new(schema.clone())?
+                    .with_content_limit(schema_content_limit(schema)?);
+                validator.enforce_content_limit(&mut entity.properties)?;
+                validator.validate(&entity.properties)?;
+
+                crate::schema::PydanticSchemaParser::extract_indexed_fields(schema)
+            };
+
+            // Update modified_at timestamp
+            entity.system.modified_at = chrono::Utc::now().to_rfc3339();
+            let value = serde_json::to_vec(&entity)?;
+
+            // Commit only if nobody wrote since the snapshot (every entity
+            // writer of this handle takes the commit lock) and the schema is
+            // unchanged, entity and index entries together
+            let committed = {
+                let _guard = self.commit_lock(entity_id);
+                let current = self.provider.get(crate::storage::column_families::CF_ENTITIES, &key)?;
+
+                if current.as_deref() == Some(snapshot.as_slice())
+                    && self.schema_epoch.load(Ordering::Acquire) == schema_epoch
+                {
+                    let mut batch = vec![BatchOp::Put {
+                        cf: crate::storage::column_families::CF_ENTITIES,
+                        key: key.clone(),
+                        value,
+                    }];
+                    self.batch_field_indexes(&mut batch, tenant_id, &indexed_fields, Some(&previous), &entity);
+                    self.provider.write_batch(batch)?;
+                    true
+                } else {
+                    false
                 }
+            };
+
+            if committed {
+                break entity;
             }
-        }
 
-        // Serialize edge
-        let edge_value = serde_json::to_vec(&edge)?;
+            if attempts > self.write_retry.max_retries {
+                return Err(DatabaseError::WriteConflict { entity_id, attempts });
+            }
+            std::thread::sleep(self.write_retry.backoff(attempts));
+        };
 
-        // Store in forward CF (src → dst)
-        let forward_key = crate::storage::keys::encode_edge_key(src_id, dst_id, rel_type);
-        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
-        self.storage.db().put_cf(&cf_edges, &forward_key, &edge_value)
-            .map_err(|e| DatabaseError::StorageError(e))?;
+        self.invalidate_query_cache(&entity.system.entity_type);
 
-        // Store in reverse CF (dst ← src)
-        let reverse_key = crate::storage::keys::encode_reverse_edge_key(dst_id, src_id, rel_type);
-        let cf_edges_reverse = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
-        self.storage.db().put_cf(&cf_edges_reverse, &reverse_key, &edge_value)
-            .map_err(|e| DatabaseError::StorageError(e))?;
+        // TODO: Re-generate embeddings if embedding fields changed
 
-        Ok(edge)
+        // Log to WAL if replication enabled
+        if let Some(ref wal) = self.wal {
+            if self.replication_mode == ReplicationMode::Primary {
+                let op = crate::replication::WalOperation::Update {
+                    tenant_id: tenant_id.to_string(),
+                    entity_id: entity_id.to_string(),
+                    changes: serde_json::to_value(&entity.properties)?,
+                };
+                wal.write()
+                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?
+                    .append(op)?;
+            }
+        }
+
+        self.notify_change(tenant_id, ChangeOp::Update, &entity.system.entity_type, entity_id);
+
+        record_db_metrics(None, Some(1));
+
+        Ok(entity)
     }
 
-    /// Get outgoing edges from an entity.
+    /// Mark an entity as recently used without changing its data.
+    ///
+    /// Sets `modified_at` to now and, with `ttl`, moves `expires_at` to
+    /// `ttl` from now. Properties are written back untouched, so schema
+    /// validation and index maintenance are skipped.
     ///
     /// # Arguments
     ///
-    /// * `src_id` - Source entity UUID
-    /// * `rel_type` - Optional relationship type filter
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity UUID
+    /// * `ttl` - New time to live from now (`None` keeps the current expiry)
     ///
     /// # Returns
     ///
-    /// Vector of outgoing edges
+    /// The new `modified_at` (RFC 3339)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::EntityNotFound` if the entity does not exist or has expired
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// // Get all outgoing edges
-    /// let edges = db.get_edges(author_id, None)?;
-    ///
-    /// // Get only "authored" edges
-    /// let authored = db.get_edges(author_id, Some("authored"))?;
+    /// // Keep an active session alive for another hour
+    /// db.touch("tenant1", session_id, Some(Duration::from_secs(3600)))?;
     /// ```
-    pub fn get_edges(&self, src_id: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
-        use rocksdb::IteratorMode;
-
-        // Prefix: src:{uuid}:
-        let prefix = format!("src:{}:", src_id).into_bytes();
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
-
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
-        );
-
-        let mut edges = Vec::new();
-
-        for item in iter {
-            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
-
-            if !key.starts_with(&prefix) {
-                break;
-            }
-
-            // Deserialize edge
-            let edge: Edge = serde_json::from_slice(&value)?;
+    pub fn touch(&self, tenant_id: &str, entity_id: uuid::Uuid, ttl: Option<std::time::Duration>) -> Result<String> {
+        let _span = db_span(DbOperation::Update, None, Some(tenant_id)).entered();
 
-            // Filter by rel_type if specified
-            if let Some(rel) = rel_type {
-                if edge.rel_type != rel {
-                    continue;
-                }
+        let entity = self.set_touch_fields(tenant_id, entity_id, |entity| {
+            entity.system.modified_at = chrono::Utc::now().to_rfc3339();
+            if let Some(ttl) = ttl {
+                entity.set_ttl(ttl);
             }
+        })?;
 
-            edges.push(edge);
-        }
+        record_db_metrics(None, Some(1));
 
-        Ok(edges)
+        Ok(entity.system.modified_at)
     }
 
-    /// Get incoming edges to an entity.
-    ///
-    /// # Arguments
-    ///
-    /// * `dst_id` - Destination entity UUID
-    /// * `rel_type` - Optional relationship type filter
+    /// Apply a replicated touch (`WalOperation::Touch`).
     ///
-    /// # Returns
-    ///
-    /// Vector of incoming edges
+    /// Sets the primary's `modified_at` and `expires_at` verbatim instead of
+    /// recomputing them from the local clock.
     ///
-    /// # Example
+    /// # Errors
     ///
-    /// ```rust,ignore
-    /// // Get all incoming edges
-    /// let edges = db.get_incoming_edges(article_id, None)?;
+    /// Returns `DatabaseError::EntityNotFound` if the entity does not exist or has expired
+    pub(crate) fn apply_touch(
+        &self,
+        tenant_id: &str,
+        entity_id: uuid::Uuid,
+        modified_at: String,
+        expires_at: Option<String>,
+    ) -> Result<()> {
+        self.set_touch_fields(tenant_id, entity_id, |entity| {
+            entity.system.modified_at = modified_at;
+            entity.system.expires_at = expires_at;
+        })?;
+        Ok(())
+    }
+
+    /// Rewrite an entity's system fields (shared by `touch` and `apply_touch`).
     ///
-    /// // Get only "references" edges
-    /// let refs = db.get_incoming_edges(article_id, Some("references"))?;
-    /// ```
-    pub fn get_incoming_edges(&self, dst_id: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
-        use rocksdb::IteratorMode;
+    /// Properties and indexes are left alone. Emits an `Update` change event
+    /// and, on a primary, logs a `WalOperation::Touch` carrying the new
+    /// `modified_at`/`expires_at`.
+    fn set_touch_fields(
+        &self,
+        tenant_id: &str,
+        entity_id: uuid::Uuid,
+        set: impl FnOnce(&mut Entity),
+    ) -> Result<Entity> {
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
 
-        // Prefix: dst:{uuid}:
-        let prefix = format!("dst:{}:", dst_id).into_bytes();
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
+        let entity = {
+            // Read and write under the commit lock so concurrent updates can't interleave
+            let _guard = self.commit_lock(entity_id);
 
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
-        );
+            let bytes = self.provider.get(crate::storage::column_families::CF_ENTITIES, &key)?
+                .ok_or(DatabaseError::EntityNotFound(entity_id))?;
+            let mut entity: Entity = serde_json::from_slice(&bytes)?;
 
-        let mut edges = Vec::new();
+            // Expired but not yet compacted away: as missing as it is to `get`
+            if entity.is_expired() {
+                return Err(DatabaseError::EntityNotFound(entity_id));
+            }
 
-        for item in iter {
-            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+            set(&mut entity);
 
-            if !key.starts_with(&prefix) {
-                break;
-            }
+            self.provider.put(
+                crate::storage::column_families::CF_ENTITIES,
+                &key,
+                &serde_json::to_vec(&entity)?,
+            )?;
+            entity
+        };
 
-            // Deserialize edge
-            let edge: Edge = serde_json::from_slice(&value)?;
+        self.invalidate_query_cache(&entity.system.entity_type);
+        self.notify_change(tenant_id, ChangeOp::Update, &entity.system.entity_type, entity_id);
 
-            // Filter by rel_type if specified
-            if let Some(rel) = rel_type {
-                if edge.rel_type != rel {
-                    continue;
-                }
+        // Log to WAL if replication enabled
+        if let Some(ref wal) = self.wal {
+            if self.replication_mode == ReplicationMode::Primary {
+                let op = crate::replication::WalOperation::Touch {
+                    tenant_id: tenant_id.to_string(),
+                    entity_id: entity_id.to_string(),
+                    modified_at: entity.system.modified_at.clone(),
+                    expires_at: entity.system.expires_at.clone(),
+                };
+                wal.write()
+                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?
+                    .append(op)?;
             }
-
-            edges.push(edge);
         }
 
-        Ok(edges)
+        Ok(entity)
     }
 
-    /// Delete edge between entities.
-    ///
-    /// # Arguments
+    /// Get the commit lock stripe for an entity.
     ///
-    /// * `src_id` - Source entity UUID
-    /// * `dst_id` - Destination entity UUID
-    /// * `rel_type` - Relationship type
+    /// Every read-modify-write of a stored entity holds its stripe, so
+    /// `update_with`'s snapshot check can't miss a concurrent write. Never
+    /// take a stripe while holding the schema registry lock.
     ///
-    /// # Returns
+    /// The stripes are in-process mutexes: they serialize writers sharing
+    /// this `Database` (e.g. through an `Arc`), not other processes or handles
+    /// opened on the same storage. RocksDB allows one writable handle per
+    /// path anyway; with a shared `StorageProvider`, writes from other
+    /// nodes are last-writer-wins.
+    fn commit_lock(&self, entity_id: uuid::Uuid) -> std::sync::MutexGuard<'_, ()> {
+        self.commit_locks[self.commit_stripe(entity_id)].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock the commit stripes of several entities.
+    ///
+    /// Stripes are taken in index order, so multi-entity writers can't
+    /// deadlock each other.
+    fn commit_lock_many(&self, entity_ids: impl IntoIterator<Item = uuid::Uuid>) -> Vec<std::sync::MutexGuard<'_, ()>> {
+        let mut stripes: Vec<usize> = entity_ids.into_iter().map(|id| self.commit_stripe(id)).collect();
+        stripes.sort_unstable();
+        stripes.dedup();
+        stripes
+            .into_iter()
+            .map(|stripe| self.commit_locks[stripe].lock().unwrap_or_else(|e| e.into_inner()))
+            .collect()
+    }
+
+    fn commit_stripe(&self, entity_id: uuid::Uuid) -> usize {
+        (entity_id.as_u128() % self.commit_locks.len() as u128) as usize
+    }
+
+    /// Rewrite stored entities under their commit stripes.
     ///
-    /// Ok if edge was deleted or didn't exist
+    /// Each target is re-read under the lock, so a concurrent update made
+    /// since the caller scanned it isn't overwritten. `rewrite` returns
+    /// `false` to leave an entity alone. Changed entities are written in
+    /// one atomic batch, together with `extra`.
     ///
-    /// # Example
+    /// # Returns
     ///
-    /// ```rust,ignore
-    /// db.delete_edge(author_id, article_id, "authored")?;
-    /// ```
-    pub fn delete_edge(&self, src_id: uuid::Uuid, dst_id: uuid::Uuid, rel_type: &str) -> Result<()> {
-        use crate::types::DatabaseError;
+    /// The rewritten entities with their tenants
+    fn rewrite_stored(
+        &self,
+        targets: &[(String, uuid::Uuid)],
+        extra: Vec<BatchOp>,
+        rewrite: &mut dyn FnMut(&mut Entity) -> bool,
+    ) -> Result<Vec<(String, Entity)>> {
+        use crate::storage::column_families::CF_ENTITIES;
 
-        // Delete from forward CF
-        let forward_key = crate::storage::keys::encode_edge_key(src_id, dst_id, rel_type);
-        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
-        self.storage.db().delete_cf(&cf_edges, &forward_key)
-            .map_err(|e| DatabaseError::StorageError(e))?;
+        let keys: Vec<Vec<u8>> = targets
+            .iter()
+            .map(|(tenant_id, entity_id)| crate::storage::keys::encode_entity_key(tenant_id, *entity_id))
+            .collect();
 
-        // Delete from reverse CF
-        let reverse_key = crate::storage::keys::encode_reverse_edge_key(dst_id, src_id, rel_type);
-        let cf_edges_reverse = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
-        self.storage.db().delete_cf(&cf_edges_reverse, &reverse_key)
-            .map_err(|e| DatabaseError::StorageError(e))?;
+        let _guards = self.commit_lock_many(targets.iter().map(|(_, entity_id)| *entity_id));
+        let current = self.provider.multi_get(CF_ENTITIES, &keys)?;
 
-        Ok(())
+        let mut batch = extra;
+        let mut rewritten = Vec::new();
+        for (((tenant_id, _), key), value) in targets.iter().zip(keys).zip(current) {
+            // Purged since the caller read it
+            let Some(value) = value else { continue };
+            let mut entity: Entity = serde_json::from_slice(&value)?;
+            if !rewrite(&mut entity) {
+                continue;
+            }
+
+            batch.push(BatchOp::Put { cf: CF_ENTITIES, key, value: serde_json::to_vec(&entity)? });
+            rewritten.push((tenant_id.clone(), entity));
+        }
+
+        if !batch.is_empty() {
+            self.provider.write_batch(batch)?;
+        }
+
+        Ok(rewritten)
     }
 
-    /// Get entity by key field value (reverse lookup).
+    /// Append tags to an array field of every entity matching a predicate.
+    ///
+    /// Tags already present are skipped, so re-running the same call is a
+    /// no-op. All changed entities (and their field index entries) are
+    /// written in a single atomic batch.
     ///
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant identifier
     /// * `table` - Table/schema name
-    /// * `key_value` - Key field value to lookup
+    /// * `predicate` - Entities to tag
+    /// * `tags` - Tags to append (duplicates are ignored)
+    /// * `field` - Array field to append to (e.g. `tags`, `sentiment_tags`); created if absent
     ///
     /// # Returns
     ///
-    /// `Some(Entity)` if found, `None` otherwise
+    /// Number of entities updated (entities that already had every tag are not counted)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ValidationError` if `field` holds a non-array value
+    /// on a matching entity, or an updated entity fails schema validation
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// // Find person by email
-    /// let person = db.get_by_key("tenant1", "person", "alice@example.com")?;
+    /// let predicate = Predicate::eq("category", serde_json::json!("support"));
+    /// let updated = db.tag_where("tenant1", "resources", &predicate, vec!["triaged".into()], "tags")?;
     /// ```
-    pub fn get_by_key(&self, tenant_id: &str, table: &str, key_value: &str) -> Result<Option<Entity>> {
-        use rocksdb::IteratorMode;
+    pub fn tag_where(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        predicate: &crate::query::Predicate,
+        tags: Vec<String>,
+        field: &str,
+    ) -> Result<usize> {
+        use crate::types::DatabaseError;
+        use crate::schema::{SchemaValidator, PydanticSchemaParser};
 
-        // Scan prefix: key:{tenant_id}:{key_value}:
-        let prefix = format!("key:{}:{}:", tenant_id, key_value).into_bytes();
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_KEY_INDEX);
+        let _span = db_span(DbOperation::BatchWrite, Some(table), Some(tenant_id)).entered();
 
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
-        );
+        let (validator, field_indexed) = {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
 
-        for item in iter {
-            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+            let schema = registry.get(table)?;
+            let indexed_fields = PydanticSchemaParser::extract_indexed_fields(schema);
+            (SchemaValidator::new(schema.clone())?, indexed_fields.iter().any(|f| f == field))
+        };
 
-            // Check if key still matches prefix
-            if !key.starts_with(&prefix) {
-                break;
+        let mut batch = Vec::new();
+        let index_value = serde_json::json!({"type": table}).to_string().into_bytes();
+        let mut updated = Vec::new();
+
+        // Lock the candidates, then re-read them so no concurrent update is lost
+        let candidates: Vec<uuid::Uuid> = self.list(tenant_id, table, false, None, None)?
+            .into_iter()
+            .filter(|entity| predicate.matches(entity))
+            .map(|entity| entity.system.id)
+            .collect();
+        let guards = self.commit_lock_many(candidates.iter().copied());
+
+        for entity_id in candidates {
+            let Some(mut entity) = self.get(tenant_id, entity_id)? else { continue };
+            if entity.is_deleted() || !predicate.matches(&entity) {
+                continue;
             }
 
-            // Parse index value to check table type
-            let index_data: serde_json::Value = serde_json::from_slice(&value)?;
-            if index_data.get("type").and_then(|v| v.as_str()) != Some(table) {
-                continue; // Different table type
+            let previous = entity.properties.get(field).filter(|v| !v.is_null()).cloned();
+            let mut values = match &previous {
+                None => Vec::new(),
+                Some(serde_json::Value::Array(values)) => values.clone(),
+                Some(other) => {
+                    return Err(DatabaseError::ValidationError(format!(
+                        "Cannot tag field '{}' of entity {}: expected array, found {}",
+                        field, entity.system.id, other
+                    )));
+                }
+            };
+
+            let before = values.len();
+            for tag in &tags {
+                let tag = serde_json::Value::String(tag.clone());
+                if !values.contains(&tag) {
+                    values.push(tag);
+                }
+            }
+            if values.len() == before {
+                continue;
             }
 
-            // Extract entity UUID from key
-            let key_str = std::str::from_utf8(&key)
-                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UTF-8: {}", e)))?;
-            let parts: Vec<&str> = key_str.split(':').collect();
-            if parts.len() != 4 {
-                continue; // Invalid key format
+            let new_value = serde_json::Value::Array(values);
+            if let Some(props) = entity.properties.as_object_mut() {
+                props.insert(field.to_string(), new_value.clone());
+            }
+            validator.validate(&entity.properties)?;
+            entity.system.modified_at = chrono::Utc::now().to_rfc3339();
+
+            batch.push(BatchOp::Put {
+                cf: crate::storage::column_families::CF_ENTITIES,
+                key: crate::storage::keys::encode_entity_key(tenant_id, entity.system.id),
+                value: serde_json::to_vec(&entity)?,
+            });
+
+            if field_indexed {
+                // Tags only grow, so existing element entries stay valid
+                let old_values = previous.as_ref().map(index_values).unwrap_or_default();
+                for field_value in index_values(&new_value) {
+                    if !old_values.contains(&field_value) {
+                        batch.push(BatchOp::Put {
+                            cf: crate::storage::column_families::CF_INDEXES,
+                            key: crate::storage::keys::encode_index_key(tenant_id, field, &field_value, entity.system.id),
+                            value: index_value.clone(),
+                        });
+                    }
+                }
             }
 
-            let entity_id = uuid::Uuid::parse_str(parts[3])
-                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UUID: {}", e)))?;
+            updated.push(entity);
+        }
 
-            // Fetch entity by ID
-            return self.get(tenant_id, entity_id);
+        if updated.is_empty() {
+            return Ok(0);
         }
 
-        Ok(None)
+        // Write batch atomically
+        self.provider.write_batch(batch)?;
+        drop(guards);
+
+        self.invalidate_query_cache(table);
+
+        // Log to WAL if replication enabled
+        if let Some(ref wal) = self.wal {
+            if self.replication_mode == ReplicationMode::Primary {
+                let mut wal = wal.write()
+                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?;
+                for entity in &updated {
+                    wal.append(crate::replication::WalOperation::Update {
+                        tenant_id: tenant_id.to_string(),
+                        entity_id: entity.system.id.to_string(),
+                        changes: serde_json::to_value(&entity.properties)?,
+                    })?;
+                }
+            }
+        }
+
+        for entity in &updated {
+            self.notify_change(tenant_id, ChangeOp::Update, table, entity.system.id);
+        }
+
+        record_db_metrics(None, Some(updated.len()));
+
+        Ok(updated.len())
     }
 
-    /// Global lookup by key value across all schemas (anonymous types).
+    /// Add a field to a registered schema without a full migration.
     ///
-    /// This is the core REM pattern: find entities by natural key without knowing the schema.
-    /// Returns ALL entities matching the key_value, regardless of table/schema.
+    /// With no `default` the field is added without touching any entity.
+    /// With a `default`, every existing entity of the schema (in all tenants,
+    /// including soft-deleted ones) that lacks the field is backfilled with
+    /// it, in write batches of `write_batch_size`. A `required` field needs a
+    /// `default`, since existing entities would otherwise be invalid.
+    ///
+    /// All commit stripes are held from the backfill until the new schema is
+    /// registered, and writers that validated against the old schema
+    /// validate again, so no entity without the field is committed once the
+    /// call returns.
     ///
     /// # Arguments
     ///
-    /// * `tenant_id` - Tenant identifier
-    /// * `key_value` - Key field value to lookup
+    /// * `schema` - Schema name
+    /// * `field_name` - Property to add
+    /// * `field_schema` - JSON Schema for the property (e.g. `{"type": "string"}`)
+    /// * `default` - Backfill value for existing entities
+    /// * `required` - Add the field to the schema's `required` list
     ///
     /// # Returns
     ///
-    /// Vector of all matching entities (may be from different schemas)
+    /// Number of entities backfilled
     ///
-    /// # Why Global Lookup?
+    /// # Errors
     ///
-    /// - **Anonymous types**: Users shouldn't need to know schema names
-    /// - **Natural keys**: "Alice" should find Alice regardless of where she's stored
-    /// - **No SQL/GraphQL**: Avoids requiring schema knowledge upfront
+    /// Returns `DatabaseError::SchemaNotFound` if the schema is not registered,
+    /// or `DatabaseError::ValidationError` if the field already exists, the
+    /// default does not satisfy `field_schema`, or `required` is set without
+    /// a default. If a backfill batch fails, earlier batches stay written and
+    /// the schema is left unchanged.
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// // Find all entities with key "Alice" (could be in person, articles, etc.)
-    /// let results = db.lookup_global("tenant1", "Alice")?;
+    /// // Optional: schema-only change
+    /// db.add_field("person", "nickname", json!({"type": "string"}), None, false)?;
     ///
-    /// for entity in results {
-    ///     println!("Found {} in {}", entity.properties["name"], entity.system.entity_type);
-    /// }
+    /// // Required: existing people get status "active"
+    /// let backfilled = db.add_field("person", "status", json!({"type": "string"}), Some(json!("active")), true)?;
     /// ```
-    pub fn lookup_global(&self, tenant_id: &str, key_value: &str) -> Result<Vec<Entity>> {
-        use rocksdb::IteratorMode;
+    pub fn add_field(
+        &self,
+        schema: &str,
+        field_name: &str,
+        field_schema: serde_json::Value,
+        default: Option<serde_json::Value>,
+        required: bool,
+    ) -> Result<usize> {
+        use crate::schema::{SchemaValidator, PydanticSchemaParser};
+        use crate::storage::column_families::CF_ENTITIES;
 
-        // Scan prefix: key:{tenant_id}:{key_value}:
-        let prefix = format!("key:{}:{}:", tenant_id, key_value).into_bytes();
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_KEY_INDEX);
+        let _span = db_span(DbOperation::BatchWrite, Some(schema), None).entered();
 
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
-        );
+        if required && default.is_none() {
+            return Err(DatabaseError::ValidationError(format!(
+                "Required field '{}' needs a default to backfill existing entities",
+                field_name
+            )));
+        }
 
-        let mut entities = Vec::new();
+        let (mut updated_schema, indexed) = {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+            let current = registry.get(schema)?;
+            let indexed = PydanticSchemaParser::extract_indexed_fields(current)
+                .iter()
+                .any(|f| f == field_name);
+            (current.clone(), indexed)
+        };
 
-        for item in iter {
-            let (key, _value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+        let properties = updated_schema.get_mut("properties")
+            .and_then(|p| p.as_object_mut())
+            .ok_or_else(|| DatabaseError::ValidationError(
+                format!("Schema '{}' has no properties object", schema)
+            ))?;
+        if properties.contains_key(field_name) {
+            return Err(DatabaseError::ValidationError(
+                format!("Field '{}' already exists in schema '{}'", field_name, schema)
+            ));
+        }
+        properties.insert(field_name.to_string(), field_schema.clone());
 
-            // Check if key still matches prefix
-            if !key.starts_with(&prefix) {
-                break;
-            }
-
-            // Extract entity UUID from key: key:{tenant}:{value}:{uuid}
-            let key_str = std::str::from_utf8(&key)
-                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UTF-8: {}", e)))?;
-            let parts: Vec<&str> = key_str.split(':').collect();
-            if parts.len() != 4 {
-                continue; // Invalid key format
-            }
+        let Some(default) = default else {
+            SchemaValidator::new(updated_schema.clone())?;
+            self.register_schema(schema, updated_schema)?;
+            return Ok(0);
+        };
 
-            let entity_id = uuid::Uuid::parse_str(parts[3])
-                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UUID: {}", e)))?;
+        SchemaValidator::new(field_schema)?.validate(&default).map_err(|e| {
+            DatabaseError::ValidationError(format!("Default for '{}' is invalid: {}", field_name, e))
+        })?;
 
-            // Fetch entity by ID
-            if let Some(entity) = self.get(tenant_id, entity_id)? {
-                entities.push(entity);
+        if required {
+            match updated_schema.get_mut("required").and_then(|r| r.as_array_mut()) {
+                Some(required) => required.push(serde_json::json!(field_name)),
+                None => updated_schema["required"] = serde_json::json!([field_name]),
             }
         }
+        SchemaValidator::new(updated_schema.clone())?;
 
-        Ok(entities)
-    }
+        // Every entity may change, so hold all commit stripes (in order) until
+        // the new schema is registered; writers that validated against the old
+        // schema see the epoch change and validate again
+        let guards: Vec<_> = self.commit_locks
+            .iter()
+            .map(|lock| lock.lock().unwrap_or_else(|e| e.into_inner()))
+            .collect();
+        let mut batch = Vec::new();
+        let mut pending = Vec::new();
+        let mut backfilled = Vec::new();
 
-    /// Load persisted schemas from storage.
-    ///
-    /// # Returns
-    ///
-    /// Ok if schemas loaded successfully
-    ///
-    /// # Errors
-    ///
-    /// Returns error if schema loading fails
-    ///
-    /// # Note
-    ///
-    /// This is called automatically when opening the database.
-    /// System schemas are not loaded (already registered in-memory).
-    fn load_schemas_from_storage(&self) -> Result<()> {
-        use rocksdb::IteratorMode;
+        for item in self.provider.iterator(CF_ENTITIES, b"entity:") {
+            let (key, value) = item?;
 
-        // Get column family handle
-        let cf = self.storage.cf_handle(crate::storage::column_families::CF_ENTITIES);
+            let mut entity: Entity = serde_json::from_slice(&value)?;
+            if entity.system.entity_type != schema {
+                continue;
+            }
+            let Some(props) = entity.properties.as_object_mut() else { continue };
+            if props.contains_key(field_name) {
+                continue;
+            }
 
-        // Iterate over all entities with prefix "entity:default:"
-        let prefix = b"entity:default:";
-        let iter = self.storage.db().iterator_cf(
-            &cf,
-            IteratorMode::From(prefix, rocksdb::Direction::Forward),
-        );
+            props.insert(field_name.to_string(), default.clone());
+            entity.system.modified_at = chrono::Utc::now().to_rfc3339();
+            let (tenant_id, _) = crate::storage::keys::decode_entity_key(&key)?;
+            batch.push(BatchOp::Put { cf: CF_ENTITIES, key, value: serde_json::to_vec(&entity)? });
 
-        for item in iter {
-            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+            if indexed {
+                self.batch_field_indexes(&mut batch, &tenant_id, &[field_name.to_string()], None, &entity);
+            }
+            pending.push((tenant_id, entity));
 
-            // Check if key still matches prefix
-            if !key.starts_with(prefix) {
-                break;
+            if pending.len() >= self.write_batch_size {
+                self.provider.write_batch(std::mem::take(&mut batch))?;
+                self.log_updates(&pending)?;
+                backfilled.append(&mut pending);
             }
+        }
 
-            // Deserialize entity
-            let entity: Entity = serde_json::from_slice(&value)?;
+        if !pending.is_empty() {
+            self.provider.write_batch(batch)?;
+            self.log_updates(&pending)?;
+            backfilled.append(&mut pending);
+        }
 
-            // Only load schema entities (not other types)
-            if entity.system.entity_type != "schemas" {
-                continue;
-            }
+        self.register_schema(schema, updated_schema)?;
+        drop(guards);
 
-            // Extract schema data
-            let props = &entity.properties;
-            let short_name = props.get("short_name")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| crate::types::DatabaseError::ValidationError(
-                    "Schema entity missing 'short_name'".into()
-                ))?;
+        self.invalidate_query_cache(schema);
 
-            let schema = props.get("schema")
-                .ok_or_else(|| crate::types::DatabaseError::ValidationError(
-                    "Schema entity missing 'schema' field".into()
-                ))?;
+        for (tenant_id, entity) in &backfilled {
+            self.notify_change(tenant_id, ChangeOp::Update, schema, entity.system.id);
+        }
 
-            // Register in memory (skip persistence since it's already persisted)
-            let mut registry = self.registry.write()
-                .map_err(|e| crate::types::DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+        record_db_metrics(None, Some(backfilled.len()));
 
-            registry.register(short_name, schema.clone())?;
+        Ok(backfilled.len())
+    }
+
+    /// Append WAL updates for entities rewritten in bulk (`add_field`,
+    /// `normalize_stored`).
+    fn log_updates(&self, backfilled: &[(String, Entity)]) -> Result<()> {
+        let Some(ref wal) = self.wal else { return Ok(()) };
+        if self.replication_mode != ReplicationMode::Primary {
+            return Ok(());
         }
 
+        let mut wal = wal.write()
+            .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?;
+        for (tenant_id, entity) in backfilled {
+            wal.append(crate::replication::WalOperation::Update {
+                tenant_id: tenant_id.clone(),
+                entity_id: entity.system.id.to_string(),
+                changes: serde_json::to_value(&entity.properties)?,
+            })?;
+        }
         Ok(())
     }
 
-    /// Persist schema to storage (schemas table).
+    /// Delete entity (soft delete by default).
     ///
     /// # Arguments
     ///
-    /// * `name` - Schema name
-    /// * `schema` - JSON Schema
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity UUID to delete
     ///
     /// # Returns
     ///
-    /// Ok if schema persisted successfully
+    /// Deleted entity (with deleted_at set)
     ///
     /// # Errors
     ///
-    /// Returns error if persistence fails
-    fn persist_schema(&self, name: &str, schema: &serde_json::Value) -> Result<()> {
-        use crate::schema::PydanticSchemaParser;
-        use crate::types::generate_uuid;
+    /// Returns error if entity not found
+    ///
+    /// # Note
+    ///
+    /// This is a soft delete - sets `deleted_at` timestamp but keeps the entity in storage.
+    /// For hard delete (permanent removal), use `hard_delete()`.
+    ///
+    /// If the schema's `delete_policy` is `hard_only`, the entity is purged
+    /// as by `hard_delete()` instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let deleted = db.delete("tenant1", entity_id)?;
+    /// assert!(deleted.is_deleted());
+    /// ```
+    pub fn delete(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Entity> {
+        use crate::schema::DeletePolicy;
+        use crate::types::DatabaseError;
 
-        // Extract schema metadata
-        let version = PydanticSchemaParser::extract_version(schema)
-            .ok_or_else(|| crate::types::DatabaseError::ValidationError(
-                "Schema missing 'version' field".into()
-            ))?;
+        let _span = db_span(DbOperation::Delete, None, Some(tenant_id)).entered();
 
-        let description = PydanticSchemaParser::extract_description(schema)
-            .unwrap_or_else(|| "No description".to_string());
+        // Read and write under the commit lock so concurrent updates can't interleave
+        let guard = self.commit_lock(entity_id);
 
-        let category = PydanticSchemaParser::extract_category(schema);
+        // Get existing entity
+        let mut entity = self.get(tenant_id, entity_id)?
+            .ok_or_else(|| DatabaseError::EntityNotFound(entity_id))?;
 
-        // Create schema entity
-        let schema_data = serde_json::json!({
-            "short_name": name,
-            "name": PydanticSchemaParser::extract_fqn(schema).unwrap_or_else(|| name.to_string()),
-            "version": version,
-            "schema": schema,
-            "description": description,
-            "category": category.as_str(),
-        });
+        // Mark as deleted
+        entity.mark_deleted();
 
-        // Generate deterministic UUID (using "name" field)
-        let id = generate_uuid("schemas", &schema_data, Some("name"));
+        if self.delete_policy(&entity.system.entity_type) == DeletePolicy::HardOnly {
+            self.purge_entity(tenant_id, &entity)?;
+        } else {
+            // Serialize and store
+            let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
+            let value = serde_json::to_vec(&entity)?;
+
+            self.provider.put(
+                crate::storage::column_families::CF_ENTITIES,
+                &key,
+                &value,
+            )?;
+            self.invalidate_query_cache(&entity.system.entity_type);
+        }
+        drop(guard);
 
-        // Create entity
-        let entity = Entity::new(id, "schemas".to_string(), schema_data);
+        // Log to WAL if replication enabled (replicas apply the same delete
+        // policy, so a HardOnly delete purges there too)
+        if let Some(ref wal) = self.wal {
+            if self.replication_mode == ReplicationMode::Primary {
+                let op = crate::replication::WalOperation::Delete {
+                    tenant_id: tenant_id.to_string(),
+                    entity_id: entity_id.to_string(),
+                };
+                wal.write()
+                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?
+                    .append(op)?;
+            }
+        }
 
-        // Serialize and store
-        let key = crate::storage::keys::encode_entity_key("default", id);
-        let value = serde_json::to_vec(&entity)?;
+        self.notify_change(tenant_id, ChangeOp::Delete, &entity.system.entity_type, entity_id);
 
-        self.storage.put(
-            crate::storage::column_families::CF_ENTITIES,
-            &key,
-            &value,
-        )?;
+        record_db_metrics(None, Some(1));
 
-        Ok(())
+        Ok(entity)
     }
 
-    /// Breadth-first traversal from starting entity.
+    /// Hard delete entity (permanent removal).
     ///
     /// # Arguments
     ///
-    /// * `start_id` - Starting entity UUID
-    /// * `direction` - Traversal direction (out/in/both)
-    /// * `depth` - Maximum traversal depth
-    /// * `rel_type` - Optional relationship type filter
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity UUID to delete
     ///
     /// # Returns
     ///
-    /// Vector of entity UUIDs in BFS order
+    /// Ok if deletion successful
     ///
     /// # Errors
     ///
-    /// Returns `DatabaseError` if traversal fails
-    pub fn traverse_bfs(
-        &self,
-        start_id: uuid::Uuid,
-        direction: crate::graph::TraversalDirection,
-        depth: usize,
-        rel_type: Option<&str>,
-    ) -> Result<Vec<uuid::Uuid>> {
-        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
-        traversal.bfs(start_id, direction, depth, rel_type)
-    }
-
-    /// Depth-first traversal from starting entity.
-    ///
-    /// # Arguments
-    ///
-    /// * `start_id` - Starting entity UUID
-    /// * `direction` - Traversal direction (out/in/both)
-    /// * `depth` - Maximum traversal depth
-    /// * `rel_type` - Optional relationship type filter
+    /// Returns error if entity not found, or
+    /// `DatabaseError::DeletePolicyViolation` if the schema's `delete_policy`
+    /// is `soft_only`
     ///
-    /// # Returns
+    /// # Warning
     ///
-    /// Vector of entity UUIDs in DFS order
+    /// This is a permanent delete - the entity cannot be recovered.
+    /// Consider using `delete()` (soft delete) instead.
     ///
-    /// # Errors
+    /// # Example
     ///
-    /// Returns `DatabaseError` if traversal fails
-    pub fn traverse_dfs(
-        &self,
-        start_id: uuid::Uuid,
-        direction: crate::graph::TraversalDirection,
-        depth: usize,
-        rel_type: Option<&str>,
-    ) -> Result<Vec<uuid::Uuid>> {
-        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
-        traversal.dfs(start_id, direction, depth, rel_type)
-    }
+    /// ```rust,ignore
+    /// db.hard_delete("tenant1", entity_id)?;
+    /// assert!(db.get("tenant1", entity_id)?.is_none());
+    /// ```
+    pub fn hard_delete(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<()> {
+        use crate::schema::DeletePolicy;
+        use crate::types::DatabaseError;
 
-    /// Find shortest path between two entities.
-    ///
-    /// # Arguments
-    ///
-    /// * `start_id` - Starting entity UUID
-    /// * `end_id` - Target entity UUID
-    /// * `direction` - Traversal direction (out/in/both)
-    /// * `max_depth` - Maximum search depth
-    ///
-    /// # Returns
-    ///
-    /// Vector of entity UUIDs representing path, or empty if no path found
-    ///
-    /// # Errors
-    ///
-    /// Returns `DatabaseError` if search fails
-    pub fn shortest_path(
-        &self,
-        start_id: uuid::Uuid,
-        end_id: uuid::Uuid,
-        direction: crate::graph::TraversalDirection,
-        max_depth: usize,
-    ) -> Result<Vec<uuid::Uuid>> {
-        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
-        traversal.shortest_path(start_id, end_id, direction, max_depth)
+        let _span = db_span(DbOperation::Delete, None, Some(tenant_id)).entered();
+
+        let guard = self.commit_lock(entity_id);
+
+        // Verify entity exists (expired entities can still be purged)
+        let entity = self.get_stored(tenant_id, entity_id)?
+            .ok_or_else(|| DatabaseError::EntityNotFound(entity_id))?;
+
+        let policy = self.delete_policy(&entity.system.entity_type);
+        if policy == DeletePolicy::SoftOnly {
+            return Err(DatabaseError::DeletePolicyViolation {
+                table: entity.system.entity_type.clone(),
+                policy: policy.to_string(),
+                operation: "hard delete".to_string(),
+            });
+        }
+
+        self.purge_entity(tenant_id, &entity)?;
+        drop(guard);
+        self.notify_change(tenant_id, ChangeOp::Delete, &entity.system.entity_type, entity_id);
+
+        record_db_metrics(None, Some(1));
+
+        Ok(())
     }
 
-    /// Execute SQL query.
+    /// Restore a soft-deleted entity.
+    ///
+    /// Clears `deleted_at` and rewrites the entity's key index and field
+    /// index entries, so it shows up again in `get_by_key`, `list`, SQL
+    /// queries, and search (embeddings are stored inline and come back with
+    /// the entity).
     ///
     /// # Arguments
     ///
-    /// * `tenant_id` - Tenant scope
-    /// * `sql` - SQL SELECT statement
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity UUID to restore
     ///
     /// # Returns
     ///
-    /// Query results as JSON array
+    /// Restored entity
     ///
     /// # Errors
     ///
-    /// Returns `DatabaseError` if query fails
+    /// Returns `DatabaseError::EntityNotFound` if the entity doesn't exist
+    /// (including hard-deleted or vacuumed entities), or
+    /// `DatabaseError::ValidationError` if it isn't deleted
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// let results = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30")?;
+    /// for entity in db.list_deleted("tenant1", "person")? {
+    ///     db.restore("tenant1", entity.system.id)?;
+    /// }
     /// ```
-    pub fn query_sql(
-        &self,
-        tenant_id: &str,
-        sql: &str,
-    ) -> Result<serde_json::Value> {
-        // Parse SQL
-        let statement = crate::query::parser::parse_sql(sql)?;
+    pub fn restore(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Entity> {
+        use crate::schema::PydanticSchemaParser;
 
-        // Extract table name
-        let table = crate::query::parser::extract_table_name(&statement)?;
+        let _span = db_span(DbOperation::Update, None, Some(tenant_id)).entered();
+
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
+
+        let entity = {
+            // Read and write under the commit lock so concurrent updates can't interleave
+            let _guard = self.commit_lock(entity_id);
+
+            let bytes = self.provider.get(crate::storage::column_families::CF_ENTITIES, &key)?
+                .ok_or(DatabaseError::EntityNotFound(entity_id))?;
+            let mut entity: Entity = serde_json::from_slice(&bytes)?;
+            if !entity.is_deleted() {
+                return Err(DatabaseError::validation(format!("Entity {} is not deleted", entity_id)));
+            }
+
+            entity.mark_restored();
+            self.provider.put(
+                crate::storage::column_families::CF_ENTITIES,
+                &key,
+                &serde_json::to_vec(&entity)?,
+            )?;
+            entity
+        };
+        let table = entity.system.entity_type.clone();
+
+        {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+            let schema = registry.get(&table)?;
+
+            let key_field = PydanticSchemaParser::extract_key_field(schema);
+            if let Some(key_value) = extract_key_value(&entity.properties, key_field.as_deref()) {
+                let index_value = serde_json::json!({"type": table}).to_string();
+                let case_insensitive = PydanticSchemaParser::extract_key_case_insensitive(schema);
+                for key_value in key_index_values(key_value, case_insensitive) {
+                    let index_key = crate::storage::keys::encode_key_index(tenant_id, &key_value, entity_id);
+                    self.provider.put(
+                        crate::storage::column_families::CF_KEY_INDEX,
+                        &index_key,
+                        index_value.as_bytes(),
+                    )?;
+                }
+            }
+
+            let indexed_fields = PydanticSchemaParser::extract_indexed_fields(schema);
+            self.write_field_indexes(tenant_id, &indexed_fields, &entity)?;
+        }
+
+        self.invalidate_query_cache(&table);
+
+        // Log to WAL if replication enabled
+        if let Some(ref wal) = self.wal {
+            if self.replication_mode == ReplicationMode::Primary {
+                let op = crate::replication::WalOperation::Insert {
+                    tenant_id: tenant_id.to_string(),
+                    entity: serde_json::to_value(&entity)?,
+                };
+                wal.write()
+                    .map_err(|e| DatabaseError::InternalError(format!("WAL lock error: {}", e)))?
+                    .append(op)?;
+            }
+        }
 
-        // Get all entities from table
-        let entities = self.list(tenant_id, &table, false, None)?;
+        self.notify_change(tenant_id, ChangeOp::Update, &table, entity_id);
 
-        // Execute query
-        crate::query::executor::execute_query(&statement, entities)
+        record_db_metrics(None, Some(1));
+
+        Ok(entity)
     }
 
-    /// Semantic search using vector similarity.
+    /// Delete policy of a table (`Either` if the schema is unknown).
+    fn delete_policy(&self, table: &str) -> crate::schema::DeletePolicy {
+        self.registry
+            .read()
+            .ok()
+            .and_then(|registry| registry.get_delete_policy(table).ok())
+            .unwrap_or_default()
+    }
+
+    /// Read an entity as stored, including expired ones.
+    fn get_stored(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Option<Entity>> {
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
+        self.provider.get(crate::storage::column_families::CF_ENTITIES, &key)?
+            .map(|data| serde_json::from_slice(&data).map_err(DatabaseError::from))
+            .transpose()
+    }
+
+    /// Remove an entity and everything attached to it from storage: key and
+    /// field index entries, blob, stored embedding, and incoming and
+    /// outgoing edges.
+    fn purge_entity(&self, tenant_id: &str, entity: &Entity) -> Result<()> {
+        use crate::types::DatabaseError;
+
+        let entity_id = entity.system.id;
+
+        // Delete from entities CF
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
+        self.provider.delete(crate::storage::column_families::CF_ENTITIES, &key)?;
+        self.invalidate_query_cache(&entity.system.entity_type);
+
+        // Delete from key index if entity has a key value
+        let registry = self.registry.read()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        let schema = registry.get(&entity.system.entity_type)?;
+        let key_field_opt = crate::schema::PydanticSchemaParser::extract_key_field(schema);
+        let key_field = key_field_opt.as_deref();
+
+        let key_case_insensitive = crate::schema::PydanticSchemaParser::extract_key_case_insensitive(schema);
+        if let Some(key_value) = extract_key_value(&entity.properties, key_field) {
+            for key_value in key_index_values(key_value, key_case_insensitive) {
+                let index_key = crate::storage::keys::encode_key_index(tenant_id, &key_value, entity_id);
+                self.provider.delete(crate::storage::column_families::CF_KEY_INDEX, &index_key)?;
+            }
+        }
+
+        let indexed_fields = crate::schema::PydanticSchemaParser::extract_indexed_fields(schema);
+        self.remove_field_indexes(tenant_id, &indexed_fields, entity)?;
+        drop(registry);
+
+        crate::storage::blob::delete_blob(&self.storage, tenant_id, entity_id)?;
+
+        let embedding_key = crate::storage::keys::encode_embedding_key(tenant_id, entity_id);
+        self.storage.delete(crate::storage::column_families::CF_EMBEDDINGS, &embedding_key)?;
+
+        let outgoing = self.get_edges(entity_id, None)?;
+        let incoming = self.get_incoming_edges(entity_id, None)?;
+        for edge in outgoing.iter().chain(&incoming) {
+            self.delete_edge(edge.src, edge.dst, &edge.rel_type)?;
+        }
+
+        Ok(())
+    }
+
+    /// Clean up after entities dropped by compaction.
     ///
-    /// # Arguments
+    /// The `CF_ENTITIES` compaction filter drops entities whose
+    /// `expires_at` has passed and, if a grace period was set with
+    /// `with_soft_delete_grace`, soft-deleted entities older than it (see
+    /// `storage::expiry`). It only removes the entity record; `vacuum`
+    /// removes the rest, as `hard_delete` would (indexes, blob, embedding,
+    /// edges). Records still shadowed by a newer version are left alone.
     ///
-    /// * `tenant_id` - Tenant identifier
-    /// * `table` - Schema/table name
-    /// * `query` - Search query text
-    /// * `top_k` - Number of results to return
+    /// Only RocksDB-backed entities are compacted. With another
+    /// `StorageProvider`, expired entities stay stored but hidden from reads.
     ///
     /// # Returns
     ///
-    /// Vector of `(Entity, similarity_score)` tuples, sorted by relevance
+    /// Number of dropped entities cleaned up
     ///
     /// # Errors
     ///
-    /// Returns error if:
-    /// - Schema not found
-    /// - Schema doesn't have embeddings configured
-    /// - Embedding generation fails
-    /// - HNSW index not built
+    /// Returns `DatabaseError::StorageError` if a cleanup fails
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// let results = db.search("tenant1", "articles", "rust programming", 10).await?;
-    /// for (entity, score) in results {
-    ///     println!("Score: {:.4}, Title: {:?}", score, entity.properties.get("title"));
-    /// }
+    /// let db = Database::open("./data")?.with_soft_delete_grace(Duration::from_secs(7 * 24 * 3600));
+    /// db.storage().compact(CF_ENTITIES)?;
+    /// let purged = db.vacuum()?;
     /// ```
-    pub async fn search(
+    pub fn vacuum(&self) -> Result<usize> {
+        use crate::otel::{background_span, record_background_metrics, BackgroundJobType};
+
+        let _span = background_span(BackgroundJobType::Vacuum, "*").entered();
+        let started = std::time::Instant::now();
+
+        let mut seen = HashSet::new();
+        let mut purged = 0;
+        for (key, value) in self.storage.expiry_filter().take_dropped() {
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let entity: Entity = serde_json::from_slice(&value)?;
+            let entity_id = entity.system.id;
+            // Key is entity:{tenant}:{uuid}; tenant IDs may contain ':'
+            let Some(tenant_id) = std::str::from_utf8(&key[b"entity:".len()..])
+                .ok()
+                .and_then(|rest| rest.rsplit_once(':'))
+                .map(|(tenant, _)| tenant.to_string())
+            else {
+                continue;
+            };
+
+            let guard = self.commit_lock(entity_id);
+
+            // A newer version survived compaction; its writer already
+            // replaced the dropped version's index entries
+            if self.get_stored(&tenant_id, entity_id)?.is_some() {
+                continue;
+            }
+
+            self.purge_entity(&tenant_id, &entity)?;
+            drop(guard);
+            self.notify_change(&tenant_id, ChangeOp::Delete, &entity.system.entity_type, entity_id);
+            purged += 1;
+        }
+
+        record_background_metrics(Some(purged), Some(started.elapsed().as_millis() as u64), "success");
+
+        Ok(purged)
+    }
+
+    /// List entities in a table with optional filters.
+    ///
+    /// Without `sort_by`, entities come back in storage key order
+    /// (`entity:{tenant}:{uuid}`, i.e. ascending by the hyphenated entity ID
+    /// string). This order is stable across calls but unrelated to insertion
+    /// time. With `sort_by`, every matching entity is loaded and sorted
+    /// (stable; ties keep key order) before `limit` is applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name to scan
+    /// * `include_deleted` - Include soft-deleted entities (default: false)
+    /// * `limit` - Maximum number of entities to return (optional)
+    /// * `sort_by` - Property or system field (e.g. `created_at`) and direction
+    ///
+    /// # Returns
+    ///
+    /// Vector of entities matching criteria
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // List all active persons
+    /// let persons = db.list("tenant1", "person", false, None, None)?;
+    ///
+    /// // List first 10 entities including deleted
+    /// let all = db.list("tenant1", "person", true, Some(10), None)?;
+    ///
+    /// // Ten most recently created
+    /// let recent = db.list("tenant1", "person", false, Some(10), Some(("created_at", SortOrder::Desc)))?;
+    /// ```
+    pub fn list(
         &self,
         tenant_id: &str,
         table: &str,
-        query: &str,
-        top_k: usize,
-    ) -> Result<Vec<(Entity, f32)>> {
-        use crate::embeddings::provider::ProviderFactory;
-        use crate::schema::PydanticSchemaParser;
+        include_deleted: bool,
+        limit: Option<usize>,
+        sort_by: Option<(&str, crate::query::SortOrder)>,
+    ) -> Result<Vec<Entity>> {
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
 
-        // 1. Get schema and verify it has embedding_fields configured
-        let schema = self.get_schema(table)?;
+        // Scan prefix: entity:{tenant_id}:
+        let prefix = format!("entity:{}:", tenant_id).into_bytes();
+        let iter = self.provider.iterator(crate::storage::column_families::CF_ENTITIES, &prefix);
 
-        let embedding_fields = PydanticSchemaParser::extract_embedding_fields(&schema);
-        if embedding_fields.is_empty() {
-            return Err(DatabaseError::SearchError(
-                format!("Schema '{}' does not have embedding_fields configured", table)
-            ));
-        }
+        let mut entities = Vec::new();
+        let mut count = 0;
+        // Sorting needs every match before the limit can be applied
+        let scan_limit = if sort_by.is_some() { None } else { limit };
 
-        // 2. Determine embedding provider from schema
-        let provider_config = schema
-            .get("json_schema_extra")
-            .and_then(|extra| extra.get("embedding_provider"))
-            .and_then(|p| p.as_str())
-            .unwrap_or("default");
+        for item in iter {
+            let (key, value) = item?;
 
-        // 3. Resolve "default" to actual provider from environment
-        let provider_str = if provider_config == "default" {
-            std::env::var("P8_DEFAULT_EMBEDDING")
-                .unwrap_or_else(|_| "openai:text-embedding-3-small".to_string())
-        } else {
-            provider_config.to_string()
-        };
+            // Check if key still matches prefix
+            if !key.starts_with(&prefix) {
+                break;
+            }
 
-        // 4. Create embedding provider
-        let provider = ProviderFactory::create(&provider_str)?;
+            // Deserialize entity
+            let entity: Entity = serde_json::from_slice(&value)?;
 
-        // 4. Generate embedding for query
-        let query_embedding = provider.embed(query).await?;
-        let dimensions = query_embedding.len();
+            // Filter by table type
+            if entity.system.entity_type != table {
+                continue;
+            }
 
-        // 5. Get HNSW index for this table
-        // For now, we'll build the index on-the-fly from all entities
-        // In production, this should be maintained incrementally
-        let entities = self.list(tenant_id, table, false, None)?;
+            // Filter deleted entities unless explicitly included
+            if !include_deleted && entity.is_deleted() {
+                continue;
+            }
 
-        if entities.is_empty() {
-            return Ok(Vec::new());
-        }
+            // Expired entities are hidden until vacuum purges them
+            if entity.is_expired() {
+                continue;
+            }
 
-        // 6. Build HNSW index from entities with embeddings
-        let mut index = crate::index::hnsw::HnswIndex::new(dimensions, entities.len());
-        let mut entity_vectors = Vec::new();
+            entities.push(entity);
+            count += 1;
 
-        for entity in &entities {
-            // Get embedding from entity properties
-            if let Some(embedding_value) = entity.properties.get("embedding") {
-                if let Some(embedding_array) = embedding_value.as_array() {
-                    let embedding: Vec<f32> = embedding_array
-                        .iter()
-                        .filter_map(|v| v.as_f64().map(|f| f as f32))
-                        .collect();
-
-                    if embedding.len() == dimensions {
-                        entity_vectors.push((entity.system.id, embedding));
-                    }
+            // Check limit
+            if let Some(max) = scan_limit {
+                if count >= max {
+                    break;
                 }
             }
         }
 
-        if entity_vectors.is_empty() {
-            return Err(DatabaseError::SearchError(
-                format!("No entities in '{}' have embeddings. Insert entities with embedding_fields configured.", table)
-            ));
+        if let Some((field, order)) = sort_by {
+            crate::query::sort::sort_entities(&mut entities, field, order);
+            if let Some(max) = limit {
+                entities.truncate(max);
+            }
         }
 
-        // Build the index
-        index.build_from_vectors(entity_vectors).await?;
+        record_db_metrics(Some(entities.len()), None);
 
-        // 7. Search HNSW index for similar vectors
-        let search_results = index.search(&query_embedding, top_k).await?;
+        Ok(entities)
+    }
 
-        // 8. Retrieve entities and return with scores
-        let mut results = Vec::new();
-        for (entity_id, distance) in search_results {
-            if let Some(entity) = self.get(tenant_id, entity_id)? {
-                // Convert distance to similarity score (1 - cosine distance)
-                let similarity = 1.0 - distance;
-                results.push((entity, similarity));
-            }
+    /// List soft-deleted entities in a table.
+    ///
+    /// Entities come back in storage key order, like `list`. Deleted
+    /// entities stay listable until `hard_delete`, or until compaction drops
+    /// them if a grace period is set (see `with_soft_delete_grace`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name to scan
+    ///
+    /// # Returns
+    ///
+    /// Entities with `deleted_at` set
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let trash = db.list_deleted("tenant1", "person")?;
+    /// ```
+    pub fn list_deleted(&self, tenant_id: &str, table: &str) -> Result<Vec<Entity>> {
+        let mut entities = self.list(tenant_id, table, true, None, None)?;
+        entities.retain(Entity::is_deleted);
+        Ok(entities)
+    }
+
+    /// Find live entities whose indexed field holds a value.
+    ///
+    /// For array fields this matches entities containing `value` as an
+    /// element (e.g. every entity tagged `"rust"`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `field` - Indexed field name
+    /// * `value` - Scalar value (or array element) to match
+    ///
+    /// # Returns
+    ///
+    /// Matching entities
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::QueryError` if `field` is not in the schema's
+    /// `indexed_fields`
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let tagged = db.query_by_index("tenant1", "articles", "tags", &json!("rust"))?;
+    /// ```
+    pub fn query_by_index(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        field: &str,
+        value: &serde_json::Value,
+    ) -> Result<Vec<Entity>> {
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
+
+        let indexed = {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+            registry.get_indexed_fields(table)?.iter().any(|f| f == field)
+        };
+        if !indexed {
+            return Err(DatabaseError::QueryError(
+                format!("Field '{}' is not indexed on '{}'", field, table)
+            ));
         }
 
-        Ok(results)
-    }
-}
+        let ids = self.field_index.lookup(tenant_id, table, field, &value_to_string(value))?;
+        let entities: Vec<Entity> = self.get_batch(tenant_id, &ids)?
+            .into_iter()
+            .flatten()
+            .filter(|e| !e.is_deleted())
+            .collect();
 
-/// Implement EdgeProvider trait for Database.
-impl crate::graph::EdgeProvider for Database {
-    fn get_outgoing(&self, node: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
-        self.get_edges(node, rel_type)
-    }
+        record_db_metrics(Some(entities.len()), None);
 
-    fn get_incoming(&self, node: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
-        self.get_incoming_edges(node, rel_type)
+        Ok(entities)
     }
-}
 
-/// Extract inline edges from entity data.
-///
-/// Parses the `edges` array from incoming data and converts to InlineEdge objects.
-///
-/// # Arguments
-///
-/// * `data` - Entity data (JSON value)
-///
-/// # Returns
-///
-/// Vector of InlineEdge objects
-///
-/// # Edge Format
-///
-/// Edges in data should be formatted as:
-/// ```json
-/// {
-///   "edges": [
-///     {
-///       "dst": "uuid-string",
-///       "rel_type": "authored",
-///       "properties": {"since": "2024-01-01"},
-///       "created_at": "2024-01-01T00:00:00Z"
-///     }
-///   ]
-/// }
-/// ```
-fn extract_edges_from_data(data: &serde_json::Value) -> Vec<crate::types::InlineEdge> {
-    use crate::types::InlineEdge;
-    use std::collections::HashMap;
+    /// List one page of live entities in a table.
+    ///
+    /// Pages follow storage (entity ID) order. Pass the ID of the last
+    /// entity of the previous page as `after` to continue.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `after` - Resume after this entity ID (`None` = first page)
+    /// * `page_size` - Maximum entities to return
+    ///
+    /// # Returns
+    ///
+    /// Up to `page_size` entities; fewer means the table is exhausted
+    pub fn list_page(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        after: Option<uuid::Uuid>,
+        page_size: usize,
+    ) -> Result<Vec<Entity>> {
+        use crate::storage::keys::encode_entity_key;
 
-    data.get("edges")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|edge_val| {
-                    // Extract edge fields
-                    let dst_str = edge_val.get("dst")?.as_str()?;
-                    let dst = uuid::Uuid::parse_str(dst_str).ok()?;
-                    let rel_type = edge_val.get("rel_type")?.as_str()?.to_string();
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
 
-                    // Extract properties (optional)
-                    let properties = edge_val.get("properties")
-                        .and_then(|v| v.as_object())
-                        .map(|obj| {
-                            obj.iter()
-                                .map(|(k, v)| (k.clone(), v.clone()))
-                                .collect::<HashMap<String, serde_json::Value>>()
-                        })
-                        .unwrap_or_default();
+        let prefix = format!("entity:{}:", tenant_id).into_bytes();
+        let start = match after {
+            Some(id) => encode_entity_key(tenant_id, id),
+            None => prefix.clone(),
+        };
+        let iter = self.provider.iterator_from(crate::storage::column_families::CF_ENTITIES, &prefix, &start);
 
-                    // Extract or generate created_at
-                    let created_at = edge_val.get("created_at")
-                        .and_then(|v| v.as_str())
-                        .map(String::from)
-                        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+        let mut entities = Vec::with_capacity(page_size);
 
-                    Some(InlineEdge {
-                        dst,
-                        rel_type,
-                        properties,
-                        created_at,
-                    })
-                })
-                .collect()
-        })
-        .unwrap_or_default()
-}
+        for item in iter {
+            let (key, value) = item?;
 
-/// Extract key value from entity data following same priority as generate_uuid.
-///
-/// # Arguments
-///
-/// * `data` - Entity data
-/// * `key_field` - Optional custom key field from schema
-///
-/// # Returns
-///
-/// Key value string if found, None otherwise
-fn extract_key_value(data: &serde_json::Value, key_field: Option<&str>) -> Option<String> {
-    // Priority 1: uri (for resources/documents)
-    if let Some(uri) = data.get("uri").and_then(|v| v.as_str()) {
-        return Some(uri.to_string());
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            // Start key is inclusive; skip the entity we resume after
+            if after.is_some() && *key == *start {
+                continue;
+            }
+
+            let entity: Entity = serde_json::from_slice(&value)?;
+            if entity.system.entity_type != table || entity.is_deleted() || entity.is_expired() {
+                continue;
+            }
+
+            entities.push(entity);
+            if entities.len() >= page_size {
+                break;
+            }
+        }
+
+        record_db_metrics(Some(entities.len()), None);
+
+        Ok(entities)
     }
 
-    // Priority 2: Custom key_field from schema
-    if let Some(field_name) = key_field {
-        if let Some(value) = data.get(field_name) {
-            return Some(value_to_string(value));
+    /// Count entities in a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `include_deleted` - Include soft-deleted entities (default: false)
+    ///
+    /// # Returns
+    ///
+    /// Number of entities matching criteria
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let count = db.count("tenant1", "person", false)?;
+    /// println!("Active persons: {}", count);
+    /// ```
+    pub fn count(&self, tenant_id: &str, table: &str, include_deleted: bool) -> Result<usize> {
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
+
+        let prefix = format!("entity:{}:", tenant_id).into_bytes();
+        let iter = self.provider.iterator(crate::storage::column_families::CF_ENTITIES, &prefix);
+
+        let mut count = 0;
+
+        for item in iter {
+            let (key, value) = item?;
+
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            let entity: Entity = serde_json::from_slice(&value)?;
+
+            if entity.system.entity_type != table {
+                continue;
+            }
+
+            if !include_deleted && entity.is_deleted() {
+                continue;
+            }
+
+            if entity.is_expired() {
+                continue;
+            }
+
+            count += 1;
         }
+
+        record_db_metrics(Some(count), None);
+
+        Ok(count)
     }
 
-    // Priority 3: Generic "key" field
-    if let Some(key_value) = data.get("key") {
-        return Some(value_to_string(key_value));
+    /// Check if entity exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity UUID
+    ///
+    /// # Returns
+    ///
+    /// `true` if entity exists, `false` otherwise
+    ///
+    /// # Note
+    ///
+    /// More efficient than `get()` when you only need to check existence.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if db.exists("tenant1", entity_id)? {
+    ///     println!("Entity exists");
+    /// }
+    /// ```
+    pub fn exists(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<bool> {
+        let _span = db_span(DbOperation::Get, None, Some(tenant_id)).entered();
+
+        let key = crate::storage::keys::encode_entity_key(tenant_id, entity_id);
+
+        let value = self.provider.get(
+            crate::storage::column_families::CF_ENTITIES,
+            &key,
+        )?;
+
+        Ok(value.is_some())
+    }
+
+    /// Attach a binary payload to an entity.
+    ///
+    /// The payload is stored in `CF_BLOBS` in chunks of `blob_chunk_size`
+    /// bytes, replacing any previous blob. It is removed when the entity is
+    /// hard deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Owning entity UUID
+    /// * `bytes` - Payload
+    /// * `content_type` - MIME type (e.g. `image/png`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::EntityNotFound` if the entity does not exist
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let id = db.insert("tenant1", "resources", json!({"name": "logo.png"}))?;
+    /// db.put_blob("tenant1", id, &std::fs::read("logo.png")?, "image/png")?;
+    /// ```
+    pub fn put_blob(&self, tenant_id: &str, entity_id: uuid::Uuid, bytes: &[u8], content_type: &str) -> Result<crate::types::BlobManifest> {
+        self.put_blob_from_reader(tenant_id, entity_id, bytes, content_type)
+    }
+
+    /// Attach a binary payload read from a stream.
+    ///
+    /// Memory use is bounded by the chunk size, so files of 100MB+ can be
+    /// stored without loading them whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Owning entity UUID
+    /// * `reader` - Payload source
+    /// * `content_type` - MIME type
+    ///
+    /// # Returns
+    ///
+    /// Manifest of the stored blob
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::EntityNotFound` if the entity does not exist,
+    /// or `DatabaseError::IoError` if reading fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let file = std::fs::File::open("talk.mp3")?;
+    /// db.put_blob_from_reader("tenant1", id, file, "audio/mpeg")?;
+    /// ```
+    pub fn put_blob_from_reader<R: std::io::Read>(
+        &self,
+        tenant_id: &str,
+        entity_id: uuid::Uuid,
+        reader: R,
+        content_type: &str,
+    ) -> Result<crate::types::BlobManifest> {
+        let _span = db_span(DbOperation::Put, None, Some(tenant_id)).entered();
+
+        if !self.exists(tenant_id, entity_id)? {
+            return Err(DatabaseError::EntityNotFound(entity_id));
+        }
+
+        let manifest = crate::storage::blob::write_blob(
+            &self.storage,
+            tenant_id,
+            entity_id,
+            reader,
+            content_type,
+            self.blob_chunk_size,
+        )?;
+
+        record_db_metrics(None, Some(manifest.chunk_count as usize + 1));
+
+        Ok(manifest)
+    }
+
+    /// Get the binary payload attached to an entity.
+    ///
+    /// Loads the whole payload; use `get_blob_reader` for large blobs.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Owning entity UUID
+    ///
+    /// # Returns
+    ///
+    /// `Some(Blob)` if one is attached, `None` otherwise
+    pub fn get_blob(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Option<crate::types::Blob>> {
+        use std::io::Read;
+
+        let Some(mut reader) = self.get_blob_reader(tenant_id, entity_id)? else {
+            return Ok(None);
+        };
+
+        let mut data = Vec::with_capacity(reader.manifest().total_size as usize);
+        reader.read_to_end(&mut data)?;
+
+        Ok(Some(crate::types::Blob::new(data, reader.manifest().content_type.clone())))
+    }
+
+    /// Get a streaming reader over an entity's blob.
+    ///
+    /// Chunks are loaded one at a time as the reader advances.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Owning entity UUID
+    ///
+    /// # Returns
+    ///
+    /// `Some(BlobReader)` if a blob is attached, `None` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if let Some(mut reader) = db.get_blob_reader("tenant1", id)? {
+    ///     std::io::copy(&mut reader, &mut std::fs::File::create("talk.mp3")?)?;
+    /// }
+    /// ```
+    pub fn get_blob_reader(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Option<crate::storage::BlobReader>> {
+        let _span = db_span(DbOperation::Get, None, Some(tenant_id)).entered();
+
+        crate::storage::BlobReader::open(self.storage.clone(), tenant_id, entity_id)
     }
 
-    // Priority 4: "name" field
-    if let Some(name) = data.get("name").and_then(|v| v.as_str()) {
-        return Some(name.to_string());
-    }
+    /// Get the manifest (size, chunk count, content type) of an entity's blob.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Owning entity UUID
+    ///
+    /// # Returns
+    ///
+    /// `Some(BlobManifest)` if a blob is attached, `None` otherwise
+    pub fn blob_manifest(&self, tenant_id: &str, entity_id: uuid::Uuid) -> Result<Option<crate::types::BlobManifest>> {
+        crate::storage::blob::read_manifest(&self.storage, tenant_id, entity_id)
+    }
+
+    /// Add edge between entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `src_id` - Source entity UUID
+    /// * `dst_id` - Destination entity UUID
+    /// * `rel_type` - Relationship type (e.g., "authored", "references")
+    /// * `properties` - Optional edge properties
+    ///
+    /// # Returns
+    ///
+    /// Created edge
+    ///
+    /// # Errors
+    ///
+    /// Returns error if entities don't exist, or `DatabaseError::EdgeCycle`
+    /// if `rel_type` is acyclic and the edge would close a cycle
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// db.add_edge("tenant1", author_id, article_id, "authored", None)?;
+    /// db.add_edge("tenant1", article1_id, article2_id, "references",
+    ///     Some(json!({"weight": 0.8})))?;
+    /// ```
+    pub fn add_edge(
+        &self,
+        tenant_id: &str,
+        src_id: uuid::Uuid,
+        dst_id: uuid::Uuid,
+        rel_type: &str,
+        properties: Option<serde_json::Value>,
+    ) -> Result<Edge> {
+        use crate::types::{DatabaseError, Edge};
+
+        let _span = db_span(DbOperation::Put, Some(crate::storage::column_families::CF_EDGES), Some(tenant_id)).entered();
+
+        // Verify both entities exist
+        if !self.exists(tenant_id, src_id)? {
+            return Err(DatabaseError::EntityNotFound(src_id));
+        }
+        if !self.exists(tenant_id, dst_id)? {
+            return Err(DatabaseError::EntityNotFound(dst_id));
+        }
+
+        // Hold the relationship type's lock from the cycle check through the
+        // writes, so concurrent `a→b` and `b→a` can't both pass the check
+        let edge_guard = self.is_acyclic(rel_type)?.then(|| self.edge_lock(rel_type));
+
+        if edge_guard.is_some() && (src_id == dst_id || self.reaches(dst_id, src_id, rel_type)?) {
+            return Err(DatabaseError::EdgeCycle {
+                rel_type: rel_type.to_string(),
+                src_id,
+                dst_id,
+            });
+        }
+
+        // Create edge
+        let mut edge = Edge::new(src_id, dst_id, rel_type.to_string());
+
+        // Add properties if provided
+        if let Some(props) = properties {
+            if let Some(obj) = props.as_object() {
+                for (key, value) in obj {
+                    edge.add_property(key.clone(), value.clone());
+                }
+                // A written weight is the new decay base, whatever the key order
+                if obj.contains_key("weight") {
+                    edge.data.properties.remove("base_weight");
+                }
+            }
+        }
+
+        // Serialize edge
+        let edge_value = serde_json::to_vec(&edge)?;
+
+        // Store in forward CF (src → dst)
+        let forward_key = crate::storage::keys::encode_edge_key(src_id, dst_id, rel_type);
+        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
+        self.storage.db().put_cf(&cf_edges, &forward_key, &edge_value)
+            .map_err(|e| DatabaseError::StorageError(e))?;
+
+        // Store in reverse CF (dst ← src)
+        let reverse_key = crate::storage::keys::encode_reverse_edge_key(dst_id, src_id, rel_type);
+        let cf_edges_reverse = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
+        self.storage.db().put_cf(&cf_edges_reverse, &reverse_key, &edge_value)
+            .map_err(|e| DatabaseError::StorageError(e))?;
+
+        record_db_metrics(None, Some(1));
+
+        Ok(edge)
+    }
+
+    /// Import edges from a CSV or JSONL file.
+    ///
+    /// Each endpoint is an entity UUID or a key value looked up with
+    /// `lookup_global`. Rows whose endpoints don't resolve to exactly one
+    /// existing entity are skipped and reported, not treated as errors.
+    /// Edges are written in batches of `write_batch_size`; edges of
+    /// acyclic relationship types go through `add_edge` so cycles are
+    /// still rejected (and counted as skipped).
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `path` - Edge file (see `export::edges` for the row format)
+    /// * `format` - `ExportFormat::Csv` or `ExportFormat::Jsonl`
+    ///
+    /// # Returns
+    ///
+    /// `EdgeImportReport` with created and skipped counts
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IngestError` if the file is malformed
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let report = db.import_edges("tenant1", "citations.csv", ExportFormat::Csv)?;
+    /// println!("{} edges, {} skipped", report.created, report.skipped);
+    /// ```
+    pub fn import_edges<P: AsRef<Path>>(
+        &self,
+        tenant_id: &str,
+        path: P,
+        format: crate::export::ExportFormat,
+    ) -> Result<crate::export::EdgeImportReport> {
+        use crate::export::{EdgeImportReport, EdgeImporter};
+        use rocksdb::WriteBatch;
+        use std::collections::HashMap;
+
+        let _span = db_span(DbOperation::BatchWrite, Some(crate::storage::column_families::CF_EDGES), Some(tenant_id)).entered();
+
+        let rows = EdgeImporter::read(path, format)?;
+
+        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
+        let cf_edges_reverse = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
+
+        let mut report = EdgeImportReport::default();
+        let mut resolved: HashMap<String, std::result::Result<uuid::Uuid, String>> = HashMap::new();
+        let mut batch = WriteBatch::default();
+        let mut pending = 0;
+
+        for (index, row) in rows.into_iter().enumerate() {
+            let mut resolve = |endpoint: &str| -> Result<std::result::Result<uuid::Uuid, String>> {
+                if let Some(id) = resolved.get(endpoint) {
+                    return Ok(id.clone());
+                }
+                let id = self.resolve_edge_endpoint(tenant_id, endpoint)?;
+                resolved.insert(endpoint.to_string(), id.clone());
+                Ok(id)
+            };
+            let (src_id, dst_id) = match (resolve(&row.src)?, resolve(&row.dst)?) {
+                (Ok(src_id), Ok(dst_id)) => (src_id, dst_id),
+                (Err(reason), _) | (_, Err(reason)) => {
+                    report.skipped += 1;
+                    report.errors.push((index, reason));
+                    continue;
+                }
+            };
+
+            if self.is_acyclic(&row.rel_type)? {
+                // Cycle checks read committed edges, so flush first
+                if pending > 0 {
+                    self.storage.db().write(std::mem::take(&mut batch))
+                        .map_err(DatabaseError::StorageError)?;
+                    pending = 0;
+                }
+                match self.add_edge(tenant_id, src_id, dst_id, &row.rel_type, row.properties) {
+                    Ok(_) => report.created += 1,
+                    Err(e @ DatabaseError::EdgeCycle { .. }) => {
+                        report.skipped += 1;
+                        report.errors.push((index, e.to_string()));
+                    }
+                    Err(e) => return Err(e),
+                }
+                continue;
+            }
+
+            let mut edge = Edge::new(src_id, dst_id, row.rel_type.clone());
+            if let Some(serde_json::Value::Object(props)) = row.properties {
+                for (key, value) in props {
+                    edge.add_property(key, value);
+                }
+            }
+            let edge_value = serde_json::to_vec(&edge)?;
+            batch.put_cf(&cf_edges, crate::storage::keys::encode_edge_key(src_id, dst_id, &row.rel_type), &edge_value);
+            batch.put_cf(&cf_edges_reverse, crate::storage::keys::encode_reverse_edge_key(dst_id, src_id, &row.rel_type), &edge_value);
+            report.created += 1;
+            pending += 1;
+
+            if pending >= self.write_batch_size {
+                self.storage.db().write(std::mem::take(&mut batch))
+                    .map_err(DatabaseError::StorageError)?;
+                pending = 0;
+            }
+        }
+
+        if pending > 0 {
+            self.storage.db().write(batch)
+                .map_err(DatabaseError::StorageError)?;
+        }
+
+        record_db_metrics(None, Some(report.created));
+
+        Ok(report)
+    }
+
+    /// Resolve an edge import endpoint (UUID or key value) to an existing entity.
+    ///
+    /// The inner `Err` explains why the endpoint can't be used.
+    fn resolve_edge_endpoint(&self, tenant_id: &str, endpoint: &str) -> Result<std::result::Result<uuid::Uuid, String>> {
+        if let Ok(id) = uuid::Uuid::parse_str(endpoint) {
+            return Ok(if self.exists(tenant_id, id)? {
+                Ok(id)
+            } else {
+                Err(format!("No entity with ID {}", id))
+            });
+        }
+
+        let matches = self.lookup_global(tenant_id, endpoint)?;
+        Ok(match matches.as_slice() {
+            [entity] => Ok(entity.system.id),
+            [] => Err(format!("No entity with key '{}'", endpoint)),
+            _ => Err(format!("Key '{}' matches {} entities", endpoint, matches.len())),
+        })
+    }
+
+    /// Declare whether a relationship type must stay acyclic.
+    ///
+    /// For hierarchical relations (e.g. `parent_of`), `add_edge` then rejects
+    /// any edge from `src` to `dst` when `src` is already reachable from `dst`
+    /// along `rel_type`. Existing edges are not checked. Constraints are
+    /// persisted and reloaded when the database is opened.
+    ///
+    /// # Arguments
+    ///
+    /// * `rel_type` - Relationship type
+    /// * `acyclic` - Reject cycle-closing edges (`false` removes the constraint)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InternalError` if the constraint lock is poisoned,
+    /// or a storage error if the constraint can't be persisted
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// db.set_edge_constraint("parent_of", true)?;
+    /// db.add_edge("tenant1", root, child, "parent_of", None)?;
+    /// assert!(db.add_edge("tenant1", child, root, "parent_of", None).is_err());
+    /// ```
+    pub fn set_edge_constraint(&self, rel_type: &str, acyclic: bool) -> Result<()> {
+        use crate::storage::column_families::CF_EDGES;
+
+        // Persist under the write lock so storage and memory agree
+        let mut acyclic_rel_types = self.acyclic_rel_types.write()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        let key = format!("{}{}", EDGE_CONSTRAINT_PREFIX, rel_type).into_bytes();
+        if acyclic {
+            self.storage.put(CF_EDGES, &key, &serde_json::to_vec(&serde_json::json!({"acyclic": true}))?)?;
+            acyclic_rel_types.insert(rel_type.to_string());
+        } else {
+            self.storage.delete(CF_EDGES, &key)?;
+            acyclic_rel_types.remove(rel_type);
+        }
+        Ok(())
+    }
+
+    /// Check whether a relationship type is constrained to be acyclic.
+    pub fn is_acyclic(&self, rel_type: &str) -> Result<bool> {
+        let acyclic_rel_types = self.acyclic_rel_types.read()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+        Ok(acyclic_rel_types.contains(rel_type))
+    }
+
+    /// Lock the `add_edge` stripe of a relationship type.
+    fn edge_lock(&self, rel_type: &str) -> std::sync::MutexGuard<'_, ()> {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        rel_type.hash(&mut hasher);
+        let stripe = (hasher.finish() % self.edge_locks.len() as u64) as usize;
+        self.edge_locks[stripe].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether `to` is reachable from `from` along outgoing `rel_type` edges.
+    fn reaches(&self, from: uuid::Uuid, to: uuid::Uuid, rel_type: &str) -> Result<bool> {
+        let mut visited = HashSet::from([from]);
+        let mut frontier = vec![from];
+
+        while let Some(node) = frontier.pop() {
+            for edge in self.get_edges(node, Some(rel_type))? {
+                if edge.dst == to {
+                    return Ok(true);
+                }
+                if visited.insert(edge.dst) {
+                    frontier.push(edge.dst);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Get outgoing edges from an entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_id` - Source entity UUID
+    /// * `rel_type` - Optional relationship type filter
+    ///
+    /// # Returns
+    ///
+    /// Vector of outgoing edges
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Get all outgoing edges
+    /// let edges = db.get_edges(author_id, None)?;
+    ///
+    /// // Get only "authored" edges
+    /// let authored = db.get_edges(author_id, Some("authored"))?;
+    /// ```
+    pub fn get_edges(&self, src_id: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
+        use rocksdb::IteratorMode;
+
+        let _span = db_span(DbOperation::Scan, Some(crate::storage::column_families::CF_EDGES), None).entered();
+
+        // Prefix: src:{uuid}:
+        let prefix = format!("src:{}:", src_id).into_bytes();
+        let cf = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
+
+        let iter = self.storage.db().iterator_cf(
+            &cf,
+            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
+        );
+
+        let mut edges = Vec::new();
+
+        for item in iter {
+            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            // Deserialize edge
+            let edge: Edge = serde_json::from_slice(&value)?;
+
+            // Filter by rel_type if specified
+            if let Some(rel) = rel_type {
+                if edge.rel_type != rel {
+                    continue;
+                }
+            }
+
+            edges.push(edge);
+        }
+
+        record_db_metrics(Some(edges.len()), None);
+
+        Ok(edges)
+    }
+
+    /// Get incoming edges to an entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst_id` - Destination entity UUID
+    /// * `rel_type` - Optional relationship type filter
+    ///
+    /// # Returns
+    ///
+    /// Vector of incoming edges
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Get all incoming edges
+    /// let edges = db.get_incoming_edges(article_id, None)?;
+    ///
+    /// // Get only "references" edges
+    /// let refs = db.get_incoming_edges(article_id, Some("references"))?;
+    /// ```
+    pub fn get_incoming_edges(&self, dst_id: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
+        use rocksdb::IteratorMode;
+
+        let _span = db_span(DbOperation::Scan, Some(crate::storage::column_families::CF_EDGES_REVERSE), None).entered();
+
+        // Prefix: dst:{uuid}:
+        let prefix = format!("dst:{}:", dst_id).into_bytes();
+        let cf = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
+
+        let iter = self.storage.db().iterator_cf(
+            &cf,
+            IteratorMode::From(&prefix, rocksdb::Direction::Forward),
+        );
+
+        let mut edges = Vec::new();
+
+        for item in iter {
+            let (key, value) = item.map_err(|e| crate::types::DatabaseError::StorageError(e))?;
+
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            // Deserialize edge
+            let edge: Edge = serde_json::from_slice(&value)?;
+
+            // Filter by rel_type if specified
+            if let Some(rel) = rel_type {
+                if edge.rel_type != rel {
+                    continue;
+                }
+            }
+
+            edges.push(edge);
+        }
+
+        record_db_metrics(Some(edges.len()), None);
+
+        Ok(edges)
+    }
+
+    /// Delete edge between entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `src_id` - Source entity UUID
+    /// * `dst_id` - Destination entity UUID
+    /// * `rel_type` - Relationship type
+    ///
+    /// # Returns
+    ///
+    /// Ok if edge was deleted or didn't exist
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// db.delete_edge(author_id, article_id, "authored")?;
+    /// ```
+    pub fn delete_edge(&self, src_id: uuid::Uuid, dst_id: uuid::Uuid, rel_type: &str) -> Result<()> {
+        use crate::types::DatabaseError;
+
+        let _span = db_span(DbOperation::Delete, Some(crate::storage::column_families::CF_EDGES), None).entered();
+
+        // Delete from forward CF
+        let forward_key = crate::storage::keys::encode_edge_key(src_id, dst_id, rel_type);
+        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
+        self.storage.db().delete_cf(&cf_edges, &forward_key)
+            .map_err(|e| DatabaseError::StorageError(e))?;
+
+        // Delete from reverse CF
+        let reverse_key = crate::storage::keys::encode_reverse_edge_key(dst_id, src_id, rel_type);
+        let cf_edges_reverse = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
+        self.storage.db().delete_cf(&cf_edges_reverse, &reverse_key)
+            .map_err(|e| DatabaseError::StorageError(e))?;
+
+        record_db_metrics(None, Some(1));
+
+        Ok(())
+    }
+
+    /// Recompute edge weights with exponential time decay.
+    ///
+    /// Each edge's `weight` is set to `base_weight * 0.5^(age / half_life)`,
+    /// where age is measured from `created_at`. The undecayed value is kept in
+    /// `base_weight` (seeded from `weight`, default 1.0) so repeated runs do
+    /// not compound; writing `weight` through `add_edge` resets it. Edges are
+    /// streamed and rewritten in batches in both edge CFs.
+    ///
+    /// # Arguments
+    ///
+    /// * `half_life` - Age at which weight halves
+    /// * `rel_type` - Only decay edges of this type (optional)
+    ///
+    /// # Returns
+    ///
+    /// Number of edges updated
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ValidationError` if `half_life` is zero
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Halve relevance of "viewed" edges every week
+    /// let updated = db.decay_edge_weights(Duration::from_secs(7 * 24 * 3600), Some("viewed"))?;
+    /// ```
+    pub fn decay_edge_weights(&self, half_life: std::time::Duration, rel_type: Option<&str>) -> Result<usize> {
+        use crate::otel::{background_span, record_background_metrics, BackgroundJobType};
+        use rocksdb::{IteratorMode, WriteBatch};
+
+        if half_life.is_zero() {
+            return Err(DatabaseError::validation("half_life must be greater than zero"));
+        }
+
+        let _span = background_span(BackgroundJobType::EdgeDecay, rel_type.unwrap_or("*")).entered();
+        let started = std::time::Instant::now();
+
+        let now = chrono::Utc::now();
+        let half_life_secs = half_life.as_secs_f64();
+
+        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
+        let cf_edges_reverse = self.storage.cf_handle(crate::storage::column_families::CF_EDGES_REVERSE);
+
+        // The iterator reads an implicit snapshot, so it never sees its own rewrites
+        let mut batch = WriteBatch::default();
+        let mut updated = 0;
+        // Edge keys start with `src:`; persisted constraints sort before them
+        for item in self.storage.db().iterator_cf(&cf_edges, IteratorMode::From(b"src:", rocksdb::Direction::Forward)) {
+            let (_key, value) = item.map_err(|e| DatabaseError::StorageError(e))?;
+            let mut edge: Edge = serde_json::from_slice(&value)?;
+
+            if rel_type.is_some_and(|rel| edge.rel_type != rel) {
+                continue;
+            }
+
+            let created_at = match chrono::DateTime::parse_from_rfc3339(&edge.data.created_at) {
+                Ok(ts) => ts.with_timezone(&chrono::Utc),
+                Err(_) => continue,
+            };
+
+            let base_weight = edge.data.properties.get("base_weight")
+                .or_else(|| edge.data.properties.get("weight"))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0);
+
+            let age_secs = (now - created_at).num_milliseconds().max(0) as f64 / 1000.0;
+            let weight = base_weight * 0.5f64.powf(age_secs / half_life_secs);
+
+            // Weight first: setting it clears base_weight
+            edge.add_property("weight".to_string(), serde_json::json!(weight));
+            edge.add_property("base_weight".to_string(), serde_json::json!(base_weight));
+
+            let value = serde_json::to_vec(&edge)?;
+            let forward_key = crate::storage::keys::encode_edge_key(edge.src, edge.dst, &edge.rel_type);
+            let reverse_key = crate::storage::keys::encode_reverse_edge_key(edge.dst, edge.src, &edge.rel_type);
+            batch.put_cf(&cf_edges, &forward_key, &value);
+            batch.put_cf(&cf_edges_reverse, &reverse_key, &value);
+            updated += 1;
+
+            if updated % EDGE_DECAY_BATCH_SIZE == 0 {
+                self.storage.db().write(std::mem::take(&mut batch))
+                    .map_err(|e| DatabaseError::StorageError(e))?;
+            }
+        }
+
+        self.storage.db().write(batch)
+            .map_err(|e| DatabaseError::StorageError(e))?;
+
+        record_background_metrics(Some(updated), Some(started.elapsed().as_millis() as u64), "success");
+
+        Ok(updated)
+    }
+
+    /// Get entity by key field value (reverse lookup).
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `key_value` - Key field value to lookup
+    ///
+    /// # Returns
+    ///
+    /// `Some(Entity)` if found, `None` otherwise
+    ///
+    /// Matching is exact unless the schema sets
+    /// `json_schema_extra.key_case_insensitive`, in which case any casing of
+    /// the key matches.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Find person by email
+    /// let person = db.get_by_key("tenant1", "person", "alice@example.com")?;
+    /// ```
+    pub fn get_by_key(&self, tenant_id: &str, table: &str, key_value: &str) -> Result<Option<Entity>> {
+        let _span = db_span(DbOperation::Get, Some(table), Some(tenant_id)).entered();
+
+        let key_value = self.key_lookup_value(table, key_value)?;
+
+        // Scan prefix: key:{tenant_id}:{key_value}:
+        let prefix = format!("key:{}:{}:", tenant_id, key_value).into_bytes();
+        let iter = self.provider.iterator(crate::storage::column_families::CF_KEY_INDEX, &prefix);
+
+        for item in iter {
+            let (key, value) = item?;
+
+            // Check if key still matches prefix
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            // Parse index value to check table type
+            let index_data: serde_json::Value = serde_json::from_slice(&value)?;
+            if index_data.get("type").and_then(|v| v.as_str()) != Some(table) {
+                continue; // Different table type
+            }
+
+            // Extract entity UUID from key
+            let key_str = std::str::from_utf8(&key)
+                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UTF-8: {}", e)))?;
+            let parts: Vec<&str> = key_str.split(':').collect();
+            if parts.len() != 4 {
+                continue; // Invalid key format
+            }
+
+            let entity_id = uuid::Uuid::parse_str(parts[3])
+                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UUID: {}", e)))?;
+
+            // Fetch entity by ID
+            return self.get(tenant_id, entity_id);
+        }
+
+        Ok(None)
+    }
+
+    /// IDs of every entity of `table` indexed under a key value.
+    ///
+    /// Case-insensitive tables index differently-cased keys (separate
+    /// entities) under the same lowercased value, so there may be several.
+    fn key_lookup_ids(&self, tenant_id: &str, table: &str, key_value: &str) -> Result<Vec<uuid::Uuid>> {
+        let key_value = self.key_lookup_value(table, key_value)?;
+        let prefix = format!("key:{}:{}:", tenant_id, key_value).into_bytes();
+
+        let mut ids = Vec::new();
+        for item in self.provider.iterator(crate::storage::column_families::CF_KEY_INDEX, &prefix) {
+            let (key, value) = item?;
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            let index_data: serde_json::Value = serde_json::from_slice(&value)?;
+            if index_data.get("type").and_then(|v| v.as_str()) != Some(table) {
+                continue;
+            }
+
+            // Longer keys sharing the prefix (e.g. "a:b" for "a") don't parse
+            let Some(entity_id) = std::str::from_utf8(&key[prefix.len()..])
+                .ok()
+                .and_then(|rest| uuid::Uuid::parse_str(rest).ok())
+            else {
+                continue;
+            };
+            if !ids.contains(&entity_id) {
+                ids.push(entity_id);
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// Key index value to scan for, lowercased if the table's keys are case-insensitive.
+    fn key_lookup_value(&self, table: &str, key_value: &str) -> Result<String> {
+        let registry = self.registry.read()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        let case_insensitive = registry.get(table)
+            .map(crate::schema::PydanticSchemaParser::extract_key_case_insensitive)
+            .unwrap_or(false);
+
+        Ok(if case_insensitive { key_value.to_lowercase() } else { key_value.to_string() })
+    }
+
+    /// Get entities by key field value for many keys in one table.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `keys` - Key field values to resolve
+    ///
+    /// # Returns
+    ///
+    /// Vector aligned with `keys`: `Some(Entity)` if found, `None` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let users = db.get_by_key_batch("tenant1", "user", &["alice@example.com", "bob@example.com"])?;
+    /// assert_eq!(users.len(), 2);
+    /// ```
+    pub fn get_by_key_batch(&self, tenant_id: &str, table: &str, keys: &[&str]) -> Result<Vec<Option<Entity>>> {
+        let _span = db_span(DbOperation::Get, Some(table), Some(tenant_id)).entered();
+
+        // Resolve each key to at most one entity ID scoped to this table
+        let mut ids: Vec<Option<uuid::Uuid>> = Vec::with_capacity(keys.len());
+
+        for key_value in keys {
+            let key_value = self.key_lookup_value(table, key_value)?;
+            let prefix = format!("key:{}:{}:", tenant_id, key_value).into_bytes();
+            let iter = self.provider.iterator(crate::storage::column_families::CF_KEY_INDEX, &prefix);
+
+            let mut found = None;
+            for item in iter {
+                let (key, value) = item?;
+
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+
+                let index_data: serde_json::Value = serde_json::from_slice(&value)?;
+                if index_data.get("type").and_then(|v| v.as_str()) != Some(table) {
+                    continue;
+                }
+
+                // Entity UUID is the suffix after the prefix
+                let suffix = std::str::from_utf8(&key[prefix.len()..])
+                    .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UTF-8: {}", e)))?;
+                if let Ok(entity_id) = uuid::Uuid::parse_str(suffix) {
+                    found = Some(entity_id);
+                    break;
+                }
+            }
+
+            ids.push(found);
+        }
+
+        // Fetch resolved entities in one pass, preserving alignment
+        let resolved: Vec<uuid::Uuid> = ids.iter().flatten().copied().collect();
+        let mut fetched = self.get_batch(tenant_id, &resolved)?.into_iter();
+
+        let results: Vec<Option<Entity>> = ids
+            .iter()
+            .map(|id| id.and_then(|_| fetched.next().flatten()))
+            .collect();
+
+        record_db_metrics(Some(results.iter().flatten().count()), None);
+
+        Ok(results)
+    }
+
+    /// Get entities by key with edit-distance fallback.
+    ///
+    /// Tries an exact key lookup first. On a miss, scans the table's indexed
+    /// key values and returns those within `max_distance` edits
+    /// (case-insensitive), ranked by distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name
+    /// * `key_value` - Key to look up
+    /// * `max_distance` - Maximum Levenshtein distance for fuzzy matches
+    ///
+    /// # Returns
+    ///
+    /// `(Entity, distance)` pairs sorted by ascending distance
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Typo still resolves
+    /// let matches = db.get_by_key_fuzzy("tenant1", "person", "Alise", 1)?;
+    /// assert_eq!(matches[0].1, 1);
+    /// ```
+    pub fn get_by_key_fuzzy(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        key_value: &str,
+        max_distance: usize,
+    ) -> Result<Vec<(Entity, usize)>> {
+        // 1. Exact match
+        if let Some(entity) = self.get_by_key(tenant_id, table, key_value)? {
+            if !entity.is_deleted() {
+                return Ok(vec![(entity, 0)]);
+            }
+        }
+
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
+
+        // 2. Scan indexed key values for this tenant
+        let results = self.scan_keys_fuzzy(tenant_id, Some(table), key_value, max_distance)?;
+
+        record_db_metrics(Some(results.len()), None);
+
+        Ok(results)
+    }
+
+    /// Scan the key index for keys within `max_distance` edits of `key_value`.
+    ///
+    /// Matching is case-insensitive. `table` restricts matches to one schema;
+    /// `None` matches across all schemas. Deleted entities are skipped.
+    fn scan_keys_fuzzy(
+        &self,
+        tenant_id: &str,
+        table: Option<&str>,
+        key_value: &str,
+        max_distance: usize,
+    ) -> Result<Vec<(Entity, usize)>> {
+        use crate::index::levenshtein;
+
+        let prefix = format!("key:{}:", tenant_id).into_bytes();
+        let iter = self.provider.iterator(crate::storage::column_families::CF_KEY_INDEX, &prefix);
+
+        let query = key_value.to_lowercase();
+        let mut candidates: Vec<(uuid::Uuid, usize)> = Vec::new();
+
+        for item in iter {
+            let (key, value) = item?;
+
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            if let Some(table) = table {
+                let index_data: serde_json::Value = serde_json::from_slice(&value)?;
+                if index_data.get("type").and_then(|v| v.as_str()) != Some(table) {
+                    continue;
+                }
+            }
+
+            // Key format: key:{tenant}:{key_value}:{uuid} (key_value may contain ':')
+            let rest = std::str::from_utf8(&key[prefix.len()..])
+                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UTF-8: {}", e)))?;
+            let (indexed_key, id_str) = match rest.rsplit_once(':') {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let entity_id = match uuid::Uuid::parse_str(id_str) {
+                Ok(id) => id,
+                Err(_) => continue,
+            };
+
+            // Length difference is a lower bound on distance
+            if indexed_key.chars().count().abs_diff(query.chars().count()) > max_distance {
+                continue;
+            }
+
+            let distance = levenshtein(&indexed_key.to_lowercase(), &query);
+            if distance <= max_distance {
+                candidates.push((entity_id, distance));
+            }
+        }
+
+        // An entity can have several index entries (e.g. case-insensitive
+        // keys); keep its closest match
+        candidates.sort_by_key(|(_, distance)| *distance);
+        let mut seen = std::collections::HashSet::new();
+        candidates.retain(|(id, _)| seen.insert(*id));
+
+        let mut results = Vec::with_capacity(candidates.len());
+        for (entity_id, distance) in candidates {
+            if let Some(entity) = self.get(tenant_id, entity_id)? {
+                if !entity.is_deleted() {
+                    results.push((entity, distance));
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Global lookup by key value across all schemas (anonymous types).
+    ///
+    /// This is the core REM pattern: find entities by natural key without knowing the schema.
+    /// Returns ALL entities matching the key_value, regardless of table/schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `key_value` - Key field value to lookup
+    ///
+    /// # Returns
+    ///
+    /// Vector of all matching entities (may be from different schemas)
+    ///
+    /// # Why Global Lookup?
+    ///
+    /// - **Anonymous types**: Users shouldn't need to know schema names
+    /// - **Natural keys**: "Alice" should find Alice regardless of where she's stored
+    /// - **No SQL/GraphQL**: Avoids requiring schema knowledge upfront
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Find all entities with key "Alice" (could be in person, articles, etc.)
+    /// let results = db.lookup_global("tenant1", "Alice")?;
+    ///
+    /// for entity in results {
+    ///     println!("Found {} in {}", entity.properties["name"], entity.system.entity_type);
+    /// }
+    /// ```
+    pub fn lookup_global(&self, tenant_id: &str, key_value: &str) -> Result<Vec<Entity>> {
+        let _span = db_span(DbOperation::Scan, Some(crate::storage::column_families::CF_KEY_INDEX), Some(tenant_id)).entered();
+
+        // Scan prefix: key:{tenant_id}:{key_value}:
+        let prefix = format!("key:{}:{}:", tenant_id, key_value).into_bytes();
+        let iter = self.provider.iterator(crate::storage::column_families::CF_KEY_INDEX, &prefix);
+
+        let mut entities = Vec::new();
+
+        for item in iter {
+            let (key, _value) = item?;
+
+            // Check if key still matches prefix
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            // Extract entity UUID from key: key:{tenant}:{value}:{uuid}
+            let key_str = std::str::from_utf8(&key)
+                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UTF-8: {}", e)))?;
+            let parts: Vec<&str> = key_str.split(':').collect();
+            if parts.len() != 4 {
+                continue; // Invalid key format
+            }
+
+            let entity_id = uuid::Uuid::parse_str(parts[3])
+                .map_err(|e| crate::types::DatabaseError::InvalidKey(format!("Invalid UUID: {}", e)))?;
+
+            // Fetch entity by ID
+            if let Some(entity) = self.get(tenant_id, entity_id)? {
+                entities.push(entity);
+            }
+        }
+
+        record_db_metrics(Some(entities.len()), None);
+
+        Ok(entities)
+    }
+
+    /// Resolve edge destinations to existing entities.
+    ///
+    /// Each unresolved edge's `dst` is matched in order by:
+    ///
+    /// 1. UUID of an existing entity (confidence 1.0)
+    /// 2. Exact global key lookup (confidence 1.0, split across ambiguous matches)
+    /// 3. Fuzzy key match across all schemas, allowing one edit per four
+    ///    characters (confidence `1 - distance / length`)
+    ///
+    /// Edges that still don't match get a placeholder entity `{"name": dst}`
+    /// in `placeholder_table` if one is given (confidence 0.0, edge property
+    /// `placeholder: true`), and are otherwise left unresolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `edges` - Extracted edges; `resolved_id` and `resolution_confidence` are set in place
+    /// * `placeholder_table` - Table to create placeholder entities in
+    ///
+    /// # Returns
+    ///
+    /// Number of edges resolved (including placeholders)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SchemaNotFound` if `placeholder_table` is not registered
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut plan = builder.extract_edges(content, None).await?;
+    /// let resolved = db.resolve_edge_destinations("tenant1", &mut plan.edges, None)?;
+    /// let inline: Vec<InlineEdge> = plan.edges.iter()
+    ///     .filter(|e| e.resolved_id.is_some())
+    ///     .map(|e| e.to_inline_edge())
+    ///     .collect::<Result<_>>()?;
+    /// ```
+    pub fn resolve_edge_destinations(
+        &self,
+        tenant_id: &str,
+        edges: &mut [crate::llm::EdgeSpec],
+        placeholder_table: Option<&str>,
+    ) -> Result<usize> {
+        if let Some(table) = placeholder_table {
+            if !self.has_schema(table) {
+                return Err(DatabaseError::SchemaNotFound(table.to_string()));
+            }
+        }
+
+        let mut resolved = 0;
+
+        for edge in edges.iter_mut() {
+            if edge.resolved_id.is_some() {
+                resolved += 1;
+                continue;
+            }
+
+            let dst = edge.dst.trim();
+            if let Some((id, confidence)) = self.resolve_entity_name(tenant_id, dst)? {
+                edge.resolved_id = Some(id);
+                edge.resolution_confidence = Some(confidence);
+                resolved += 1;
+            } else if let Some(table) = placeholder_table {
+                let id = self.insert(tenant_id, table, serde_json::json!({"name": dst}))?;
+                edge.resolved_id = Some(id);
+                edge.resolution_confidence = Some(0.0);
+                edge.properties.insert("placeholder".to_string(), serde_json::Value::Bool(true));
+                resolved += 1;
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    /// Match a name (or UUID) to one existing entity.
+    ///
+    /// Returns the best match and its confidence, or `None` if nothing matches.
+    fn resolve_entity_name(&self, tenant_id: &str, name: &str) -> Result<Option<(uuid::Uuid, f64)>> {
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        // 1. Existing entity UUID
+        if let Ok(id) = uuid::Uuid::parse_str(name) {
+            if let Some(entity) = self.get(tenant_id, id)? {
+                if !entity.is_deleted() {
+                    return Ok(Some((id, 1.0)));
+                }
+            }
+        }
+
+        // 2. Exact key across all schemas
+        let exact: Vec<Entity> = self.lookup_global(tenant_id, name)?
+            .into_iter()
+            .filter(|e| !e.is_deleted())
+            .collect();
+        if let Some(entity) = exact.first() {
+            return Ok(Some((entity.system.id, 1.0 / exact.len() as f64)));
+        }
+
+        // 3. Fuzzy key across all schemas
+        let length = name.chars().count();
+        let max_distance = (length / 4).max(1);
+        let fuzzy = self.scan_keys_fuzzy(tenant_id, None, name, max_distance)?;
+
+        Ok(fuzzy.first().map(|(entity, distance)| {
+            let confidence = 1.0 - *distance as f64 / length.max(1) as f64;
+            (entity.system.id, confidence.max(0.0))
+        }))
+    }
+
+    /// Extract edges from content and resolve their destinations.
+    ///
+    /// Runs `LlmEdgeBuilder::extract_edges`, then `resolve_edge_destinations`
+    /// on the edges that pass `min_confidence`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `builder` - Edge builder (LLM client)
+    /// * `content` - Document content
+    /// * `context` - Optional context about the document
+    /// * `min_confidence` - Drop edges with `confidence` below this (0.0 keeps all)
+    /// * `placeholder_table` - Table to create placeholder entities in for unmatched destinations
+    ///
+    /// # Returns
+    ///
+    /// `EdgePlan` with `resolved_id` set on each matched edge
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if extraction fails
+    pub async fn extract_and_link(
+        &self,
+        tenant_id: &str,
+        builder: &crate::llm::LlmEdgeBuilder,
+        content: &str,
+        context: Option<&str>,
+        min_confidence: f64,
+        placeholder_table: Option<&str>,
+    ) -> Result<crate::llm::EdgePlan> {
+        // Filter before resolving so low-confidence edges never create placeholders
+        let mut plan = builder.extract_edges(content, context, min_confidence).await?;
+        self.resolve_edge_destinations(tenant_id, &mut plan.edges, placeholder_table)?;
+        Ok(plan)
+    }
+
+    /// Attach an edge plan's edges to a source entity as inline edges.
+    ///
+    /// Destinations are resolved first (`resolve_edge_destinations`), then
+    /// each edge is classified against the source's existing edges: new
+    /// edges are created, edges with an existing (dst, rel_type) are merged
+    /// (properties replaced, as on upsert), and unmatched ones are skipped.
+    ///
+    /// With `dry_run`, the same report is produced without writing anything:
+    /// no edges are attached and no placeholders are created, so edges that
+    /// a real run would give a placeholder are reported as unresolved.
+    /// Resolved ids are still recorded on `plan.edges`, so a reviewed plan
+    /// can be applied afterwards without resolving again.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `src_id` - Entity the edges originate from
+    /// * `plan` - Extracted edge plan; `resolved_id` is set in place
+    /// * `placeholder_table` - Table to create placeholder entities in for unmatched destinations
+    /// * `dry_run` - Report what would change without writing
+    ///
+    /// # Returns
+    ///
+    /// `EdgePlanReport` classifying every edge in the plan
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::EntityNotFound` if the source doesn't exist, or
+    /// `DatabaseError::SchemaNotFound` if `placeholder_table` is not registered
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut plan = builder.extract_edges(content, None, 0.7).await?;
+    /// let preview = db.apply_edge_plan("tenant1", doc_id, &mut plan, None, true)?;
+    /// println!("{} new, {} merged, {} unresolved",
+    ///     preview.created.len(), preview.merged.len(), preview.unresolved.len());
+    ///
+    /// // After review
+    /// db.apply_edge_plan("tenant1", doc_id, &mut plan, None, false)?;
+    /// ```
+    pub fn apply_edge_plan(
+        &self,
+        tenant_id: &str,
+        src_id: uuid::Uuid,
+        plan: &mut crate::llm::EdgePlan,
+        placeholder_table: Option<&str>,
+        dry_run: bool,
+    ) -> Result<crate::llm::EdgePlanReport> {
+        let _span = db_span(DbOperation::BatchWrite, Some(crate::storage::column_families::CF_EDGES), Some(tenant_id)).entered();
+
+        let source = self.get(tenant_id, src_id)?
+            .filter(|e| !e.is_deleted())
+            .ok_or(DatabaseError::EntityNotFound(src_id))?;
+
+        if dry_run {
+            if let Some(table) = placeholder_table {
+                if !self.has_schema(table) {
+                    return Err(DatabaseError::SchemaNotFound(table.to_string()));
+                }
+            }
+            self.resolve_edge_destinations(tenant_id, &mut plan.edges, None)?;
+        } else {
+            self.resolve_edge_destinations(tenant_id, &mut plan.edges, placeholder_table)?;
+        }
+
+        let mut report = crate::llm::EdgePlanReport { dry_run, ..Default::default() };
+        let mut existing: HashSet<(uuid::Uuid, String)> = source.system.edges.iter().map(|e| e.key()).collect();
+        let mut inline = Vec::new();
+
+        for edge in &plan.edges {
+            let Some(dst) = edge.resolved_id else {
+                report.unresolved.push(edge.clone());
+                continue;
+            };
+
+            // Repeats within the plan merge into the first occurrence
+            if existing.insert((dst, edge.rel_type.clone())) {
+                report.created.push(edge.clone());
+            } else {
+                report.merged.push(edge.clone());
+            }
+            inline.push(edge.to_inline_edge()?);
+        }
+
+        if !dry_run && !inline.is_empty() {
+            self.update_with(tenant_id, src_id, |entity| {
+                entity.merge_edges(inline.clone());
+                Ok(())
+            })?;
+        }
+
+        Ok(report)
+    }
+
+    /// Extract time-bounded moments from content.
+    ///
+    /// Runs `LlmMomentBuilder::extract_moments`. Moments whose times are
+    /// missing or ambiguous are omitted, so every returned moment has
+    /// `start_time <= end_time`. Nothing is written: serialize each moment
+    /// (`serde_json::to_value`) and insert it into `moments` to store it.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - Moment builder (LLM client)
+    /// * `content` - Content to classify
+    /// * `context` - Optional context about the content
+    ///
+    /// # Returns
+    ///
+    /// Moment records matching the `moments` schema
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if extraction fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let builder = LlmMomentBuilder::from_env()?;
+    /// for moment in db.extract_moments(&builder, &journal, Some("journal/2024-03-04.md")).await? {
+    ///     db.insert("default", "moments", serde_json::to_value(&moment)?)?;
+    /// }
+    /// ```
+    pub async fn extract_moments(
+        &self,
+        builder: &crate::llm::LlmMomentBuilder,
+        content: &str,
+        context: Option<&str>,
+    ) -> Result<Vec<crate::dreaming::Moment>> {
+        builder.extract_moments(content, context).await
+    }
+
+    /// Load persisted schemas from storage.
+    ///
+    /// # Returns
+    ///
+    /// Ok if schemas loaded successfully
+    ///
+    /// # Errors
+    ///
+    /// Returns error if schema loading fails
+    ///
+    /// # Note
+    ///
+    /// This is called automatically when opening the database.
+    /// System schemas are not loaded (already registered in-memory).
+    fn load_schemas_from_storage(&self) -> Result<()> {
+        // Iterate over all entities with prefix "entity:default:"
+        let prefix = b"entity:default:";
+        let iter = self.provider.iterator(crate::storage::column_families::CF_ENTITIES, prefix);
+
+        for item in iter {
+            let (key, value) = item?;
+
+            // Check if key still matches prefix
+            if !key.starts_with(prefix) {
+                break;
+            }
+
+            // Deserialize entity
+            let entity: Entity = serde_json::from_slice(&value)?;
+
+            // Only load schema entities (not other types)
+            if entity.system.entity_type != "schemas" {
+                continue;
+            }
+
+            // Extract schema data
+            let props = &entity.properties;
+            let short_name = props.get("short_name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| crate::types::DatabaseError::ValidationError(
+                    "Schema entity missing 'short_name'".into()
+                ))?;
+
+            let schema = props.get("schema")
+                .ok_or_else(|| crate::types::DatabaseError::ValidationError(
+                    "Schema entity missing 'schema' field".into()
+                ))?;
+
+            // Register in memory (skip persistence since it's already persisted)
+            let mut registry = self.registry.write()
+                .map_err(|e| crate::types::DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+            registry.register(short_name, schema.clone())?;
+            self.sync_retained(&registry, short_name);
+        }
+
+        Ok(())
+    }
+
+    /// Keep the compaction filter from dropping soft-deleted entities of
+    /// `SoftOnly` tables.
+    fn sync_retained(&self, registry: &SchemaRegistry, name: &str) {
+        let soft_only = registry.get_delete_policy(name)
+            .is_ok_and(|policy| policy == crate::schema::DeletePolicy::SoftOnly);
+        self.storage.expiry_filter().set_retained(name, soft_only);
+    }
+
+    /// Load persisted embedding projections from storage.
+    ///
+    /// Called automatically when opening the database.
+    fn load_projections_from_storage(&self) -> Result<()> {
+        use crate::embeddings::PcaProjection;
+        use crate::storage::column_families::CF_EMBEDDINGS;
+
+        let mut projections = self.projections.write()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        for item in self.provider.iterator(CF_EMBEDDINGS, b"proj:") {
+            let (key, value) = item?;
+            let table = String::from_utf8_lossy(&key["proj:".len()..]).into_owned();
+            let projection: PcaProjection = serde_json::from_slice(&value)?;
+            projections.insert(table, Arc::new(projection));
+        }
+
+        Ok(())
+    }
+
+    /// Load persisted edge constraints (see `set_edge_constraint`).
+    ///
+    /// Called automatically when opening the database.
+    fn load_edge_constraints_from_storage(&self) -> Result<()> {
+        use crate::storage::column_families::CF_EDGES;
+
+        let mut acyclic_rel_types = self.acyclic_rel_types.write()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        for item in self.storage.prefix_iterator(CF_EDGES, EDGE_CONSTRAINT_PREFIX.as_bytes()) {
+            let (key, value) = item?;
+            let constraint: serde_json::Value = serde_json::from_slice(&value)?;
+            if constraint.get("acyclic").and_then(|v| v.as_bool()) == Some(true) {
+                let rel_type = String::from_utf8_lossy(&key[EDGE_CONSTRAINT_PREFIX.len()..]).into_owned();
+                acyclic_rel_types.insert(rel_type);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Persist schema to storage (schemas table).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Schema name
+    /// * `schema` - JSON Schema
+    ///
+    /// # Returns
+    ///
+    /// Ok if schema persisted successfully
+    ///
+    /// # Errors
+    ///
+    /// Returns error if persistence fails
+    fn persist_schema(&self, name: &str, schema: &serde_json::Value) -> Result<()> {
+        use crate::schema::PydanticSchemaParser;
+        use crate::types::generate_uuid;
+
+        // Extract schema metadata
+        let version = PydanticSchemaParser::extract_version(schema)
+            .ok_or_else(|| crate::types::DatabaseError::ValidationError(
+                "Schema missing 'version' field".into()
+            ))?;
+
+        let description = PydanticSchemaParser::extract_description(schema)
+            .unwrap_or_else(|| "No description".to_string());
+
+        let category = PydanticSchemaParser::extract_category(schema);
+
+        // Create schema entity
+        let schema_data = serde_json::json!({
+            "short_name": name,
+            "name": PydanticSchemaParser::extract_fqn(schema).unwrap_or_else(|| name.to_string()),
+            "version": version,
+            "schema": schema,
+            "description": description,
+            "category": category.as_str(),
+        });
+
+        // Generate deterministic UUID (using "name" field)
+        let id = generate_uuid("schemas", &schema_data, Some("name"));
+
+        // Create entity
+        let entity = Entity::new(id, "schemas".to_string(), schema_data);
+
+        // Serialize and store
+        let key = crate::storage::keys::encode_entity_key("default", id);
+        let value = serde_json::to_vec(&entity)?;
+
+        self.provider.put(
+            crate::storage::column_families::CF_ENTITIES,
+            &key,
+            &value,
+        )?;
+
+        Ok(())
+    }
+
+    /// Breadth-first traversal from starting entity.
+    ///
+    /// Capped by the default `TraversalLimits` (`DEFAULT_MAX_TRAVERSAL_NODES`
+    /// nodes, `DEFAULT_MAX_NODES_PER_LEVEL` per depth); check `truncated`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_id` - Starting entity UUID
+    /// * `direction` - Traversal direction (out/in/both)
+    /// * `depth` - Maximum traversal depth
+    /// * `rel_types` - Only follow edges of these types (`None` or empty = all)
+    ///
+    /// # Returns
+    ///
+    /// `TraversalResult` with entity UUIDs in BFS order
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if traversal fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Citation graph only, ignoring authorship and tags
+    /// let cited = db.traverse_bfs(paper_id, TraversalDirection::Out, 3, Some(vec!["cites".to_string()]))?;
+    /// ```
+    pub fn traverse_bfs(
+        &self,
+        start_id: uuid::Uuid,
+        direction: crate::graph::TraversalDirection,
+        depth: usize,
+        rel_types: Option<Vec<String>>,
+    ) -> Result<crate::graph::TraversalResult> {
+        self.traverse_bfs_with_limits(start_id, direction, depth, rel_types, crate::graph::TraversalLimits::default())
+    }
+
+    /// Breadth-first traversal with explicit node caps.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_id` - Starting entity UUID
+    /// * `direction` - Traversal direction (out/in/both)
+    /// * `depth` - Maximum traversal depth
+    /// * `rel_types` - Only follow edges of these types (`None` or empty = all)
+    /// * `limits` - Total and per-depth node caps
+    ///
+    /// # Returns
+    ///
+    /// `TraversalResult` with entity UUIDs in BFS order
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if traversal fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let limits = TraversalLimits { max_nodes: 500, max_nodes_per_level: 100 };
+    /// let result = db.traverse_bfs_with_limits(id, TraversalDirection::Both, 3, None, limits)?;
+    /// if result.truncated {
+    ///     println!("Showing first {} nodes", result.nodes.len());
+    /// }
+    /// ```
+    pub fn traverse_bfs_with_limits(
+        &self,
+        start_id: uuid::Uuid,
+        direction: crate::graph::TraversalDirection,
+        depth: usize,
+        rel_types: Option<Vec<String>>,
+        limits: crate::graph::TraversalLimits,
+    ) -> Result<crate::graph::TraversalResult> {
+        let _span = db_span(DbOperation::Traverse, None, None).entered();
+
+        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
+        let result = traversal.bfs(start_id, direction, depth, rel_types.as_deref(), limits)?;
+
+        if result.truncated {
+            tracing::debug!(nodes = result.nodes.len(), "BFS traversal truncated by limits");
+        }
+
+        Ok(result)
+    }
+
+    /// Depth-first traversal from starting entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_id` - Starting entity UUID
+    /// * `direction` - Traversal direction (out/in/both)
+    /// * `depth` - Maximum traversal depth
+    /// * `rel_type` - Optional relationship type filter
+    ///
+    /// # Returns
+    ///
+    /// Vector of entity UUIDs in DFS order
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if traversal fails
+    pub fn traverse_dfs(
+        &self,
+        start_id: uuid::Uuid,
+        direction: crate::graph::TraversalDirection,
+        depth: usize,
+        rel_type: Option<&str>,
+    ) -> Result<Vec<uuid::Uuid>> {
+        let _span = db_span(DbOperation::Traverse, None, None).entered();
+
+        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
+        traversal.dfs(start_id, direction, depth, rel_type)
+    }
+
+    /// Find shortest path between two entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_id` - Starting entity UUID
+    /// * `end_id` - Target entity UUID
+    /// * `direction` - Traversal direction (out/in/both)
+    /// * `max_depth` - Maximum search depth
+    ///
+    /// # Returns
+    ///
+    /// Vector of entity UUIDs representing path, or empty if no path found
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if search fails
+    pub fn shortest_path(
+        &self,
+        start_id: uuid::Uuid,
+        end_id: uuid::Uuid,
+        direction: crate::graph::TraversalDirection,
+        max_depth: usize,
+    ) -> Result<Vec<uuid::Uuid>> {
+        let _span = db_span(DbOperation::Traverse, None, None).entered();
+
+        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
+        traversal.shortest_path(start_id, end_id, direction, max_depth)
+    }
+
+    /// Find shortest path with a bidirectional BFS.
+    ///
+    /// Same result length as `shortest_path`, but searches from both ends
+    /// and meets in the middle, expanding far fewer nodes on long paths
+    /// through well-connected graphs. The search from `end_id` follows
+    /// edges in reverse, so `direction` keeps its meaning.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_id` - Starting entity UUID
+    /// * `end_id` - Target entity UUID
+    /// * `direction` - Traversal direction (out/in/both)
+    /// * `max_depth` - Maximum path length
+    ///
+    /// # Returns
+    ///
+    /// Vector of entity UUIDs representing path, or empty if no path found
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if search fails
+    pub fn shortest_path_bidirectional(
+        &self,
+        start_id: uuid::Uuid,
+        end_id: uuid::Uuid,
+        direction: crate::graph::TraversalDirection,
+        max_depth: usize,
+    ) -> Result<Vec<uuid::Uuid>> {
+        let _span = db_span(DbOperation::Traverse, None, None).entered();
+
+        let traversal = crate::graph::GraphTraversal::new(self as &dyn crate::graph::EdgeProvider);
+        traversal.bidirectional_shortest_path(start_id, end_id, direction, max_depth)
+    }
+
+    /// Find triangles of mutually connected entities.
+    ///
+    /// Edges of `rel_type` are treated as undirected, so `A→B`, `B→C`,
+    /// `C→A` and `A→B`, `A→C`, `B→C` are both triangles; self-loops are
+    /// ignored. Each triangle is found once per edge by intersecting the
+    /// endpoints' neighbor sets, keeping only the third node ordered after
+    /// both, so every triangle is reported exactly once.
+    ///
+    /// # Arguments
+    ///
+    /// * `rel_type` - Relationship type to analyze
+    ///
+    /// # Returns
+    ///
+    /// Triangles as ascending UUID triples, sorted
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// for [a, b, c] in db.find_triangles("knows")? {
+    ///     println!("{} - {} - {}", a, b, c);
+    /// }
+    /// ```
+    pub fn find_triangles(&self, rel_type: &str) -> Result<Vec<[uuid::Uuid; 3]>> {
+        use rocksdb::IteratorMode;
+        use std::collections::{BTreeSet, HashMap};
+
+        let _span = db_span(DbOperation::Traverse, Some(crate::storage::column_families::CF_EDGES), None).entered();
+
+        let cf_edges = self.storage.cf_handle(crate::storage::column_families::CF_EDGES);
+        let mut adjacency: HashMap<uuid::Uuid, BTreeSet<uuid::Uuid>> = HashMap::new();
+
+        // Edge keys start with `src:`; persisted constraints sort before them
+        for item in self.storage.db().iterator_cf(&cf_edges, IteratorMode::From(b"src:", rocksdb::Direction::Forward)) {
+            let (_key, value) = item.map_err(DatabaseError::StorageError)?;
+            let edge: Edge = serde_json::from_slice(&value)?;
+
+            if edge.rel_type != rel_type || edge.src == edge.dst {
+                continue;
+            }
+            adjacency.entry(edge.src).or_default().insert(edge.dst);
+            adjacency.entry(edge.dst).or_default().insert(edge.src);
+        }
+
+        let mut triangles = Vec::new();
+        for (&u, u_neighbors) in &adjacency {
+            for &v in u_neighbors.range((std::ops::Bound::Excluded(u), std::ops::Bound::Unbounded)) {
+                let v_neighbors = &adjacency[&v];
+                for &w in u_neighbors.range((std::ops::Bound::Excluded(v), std::ops::Bound::Unbounded)) {
+                    if v_neighbors.contains(&w) {
+                        triangles.push([u, v, w]);
+                    }
+                }
+            }
+        }
+        triangles.sort();
+
+        record_db_metrics(Some(triangles.len()), None);
+
+        Ok(triangles)
+    }
+
+    /// Train structural embeddings for the entities of a relationship graph.
+    ///
+    /// Runs random walks over edges of `rel_type` (treated as undirected)
+    /// and trains skip-gram vectors on them (see `graph::embedding`), so
+    /// entities in the same densely linked region get similar vectors. One
+    /// L2-normalized vector per connected entity is stored in
+    /// `CF_GRAPH_EMBEDDINGS`, replacing any earlier run for `rel_type`. These
+    /// sit alongside content embeddings rather than replacing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `rel_type` - Relationship type to walk
+    /// * `dim` - Embedding dimensions
+    /// * `walk_length` - Nodes per walk
+    /// * `num_walks` - Walks started from each entity
+    ///
+    /// # Returns
+    ///
+    /// Number of entities embedded
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` if `dim` or `num_walks` is zero or
+    /// `walk_length` is below 2
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// db.graph_embeddings("cites", 64, 20, 10)?;
+    /// let vector = db.graph_embedding("cites", paper_id)?;
+    /// ```
+    pub fn graph_embeddings(
+        &self,
+        rel_type: &str,
+        dim: usize,
+        walk_length: usize,
+        num_walks: usize,
+    ) -> Result<usize> {
+        let config = crate::graph::WalkEmbeddingConfig::new(dim, walk_length, num_walks);
+        self.graph_embeddings_with_config(rel_type, &config)
+    }
+
+    /// Train structural embeddings with explicit walk and training settings.
+    ///
+    /// Same as `graph_embeddings`, but takes the full `WalkEmbeddingConfig`
+    /// (window, negatives, epochs, learning rate, seed).
+    pub fn graph_embeddings_with_config(
+        &self,
+        rel_type: &str,
+        config: &crate::graph::WalkEmbeddingConfig,
+    ) -> Result<usize> {
+        use crate::graph::embedding::encode_vector;
+        use crate::storage::column_families::{CF_EDGES, CF_GRAPH_EMBEDDINGS};
+        use rocksdb::{IteratorMode, WriteBatch};
+        use std::collections::{BTreeMap, BTreeSet};
+
+        let _span = db_span(DbOperation::Traverse, Some(CF_EDGES), None).entered();
+
+        let cf_edges = self.storage.cf_handle(CF_EDGES);
+        let mut adjacency: BTreeMap<uuid::Uuid, BTreeSet<uuid::Uuid>> = BTreeMap::new();
+
+        // Edge keys start with `src:`; persisted constraints sort before them
+        for item in self.storage.db().iterator_cf(&cf_edges, IteratorMode::From(b"src:", rocksdb::Direction::Forward)) {
+            let (_key, value) = item.map_err(DatabaseError::StorageError)?;
+            let edge: Edge = serde_json::from_slice(&value)?;
+
+            if edge.rel_type != rel_type || edge.src == edge.dst {
+                continue;
+            }
+            adjacency.entry(edge.src).or_default().insert(edge.dst);
+            adjacency.entry(edge.dst).or_default().insert(edge.src);
+        }
+
+        let vectors = crate::graph::embed_graph(&adjacency, config)?;
+
+        // Replace the previous run for this relationship type
+        let cf = self.storage.cf_handle(CF_GRAPH_EMBEDDINGS);
+        let mut batch = WriteBatch::default();
+        let prefix = format!("gemb:{}:", rel_type);
+        for item in self.storage.prefix_iterator(CF_GRAPH_EMBEDDINGS, prefix.as_bytes()) {
+            let (key, _) = item?;
+            batch.delete_cf(&cf, &key);
+        }
+        for (entity_id, vector) in &vectors {
+            let key = crate::storage::keys::encode_graph_embedding_key(rel_type, *entity_id);
+            batch.put_cf(&cf, &key, encode_vector(vector));
+        }
+
+        self.storage.db().write(batch)
+            .map_err(DatabaseError::StorageError)?;
+
+        record_db_metrics(None, Some(vectors.len()));
+
+        Ok(vectors.len())
+    }
+
+    /// Structural embedding of an entity from the last `graph_embeddings` run.
+    ///
+    /// # Arguments
+    ///
+    /// * `rel_type` - Relationship type the embeddings were trained on
+    /// * `entity_id` - Entity UUID
+    ///
+    /// # Returns
+    ///
+    /// The vector, or `None` if the entity had no edges of `rel_type`
+    pub fn graph_embedding(&self, rel_type: &str, entity_id: uuid::Uuid) -> Result<Option<Vec<f32>>> {
+        use crate::storage::column_families::CF_GRAPH_EMBEDDINGS;
+
+        let _span = db_span(DbOperation::Get, Some(CF_GRAPH_EMBEDDINGS), None).entered();
+
+        let key = crate::storage::keys::encode_graph_embedding_key(rel_type, entity_id);
+        self.storage
+            .get(CF_GRAPH_EMBEDDINGS, &key)?
+            .map(|bytes| crate::graph::embedding::decode_vector(&bytes))
+            .transpose()
+    }
+
+    /// Execute SQL query.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant scope
+    /// * `sql` - SQL SELECT statement
+    ///
+    /// # Returns
+    ///
+    /// Query results as JSON array
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if query fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let results = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30")?;
+    /// ```
+    pub fn query_sql(
+        &self,
+        tenant_id: &str,
+        sql: &str,
+    ) -> Result<serde_json::Value> {
+        self.query_sql_with_scan_limit(tenant_id, sql, Some(self.max_scan_rows))
+    }
+
+    /// Execute SQL query with an explicit scan limit.
+    ///
+    /// Equality on the schema key field or on one of its `indexed_fields`
+    /// is served from the index and never counts against the limit (index
+    /// lookups match strings exactly, as key lookups do). A
+    /// query with LIMIT and no ORDER BY or aggregate stops scanning once
+    /// OFFSET + LIMIT rows match. Anything else scans the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `sql` - SQL query string
+    /// * `max_scan_rows` - Row limit for table scans (`None` = unbounded)
+    ///
+    /// # Returns
+    ///
+    /// Query results as JSON array
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ScanLimitExceeded` if a table scan would read
+    /// more than `max_scan_rows` rows
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Deliberate full scan of a large table
+    /// let results = db.query_sql_with_scan_limit("tenant1", "SELECT * FROM logs WHERE level = 'warn'", None)?;
+    /// ```
+    pub fn query_sql_with_scan_limit(
+        &self,
+        tenant_id: &str,
+        sql: &str,
+        max_scan_rows: Option<usize>,
+    ) -> Result<serde_json::Value> {
+        use crate::query::executor::{is_streamable, offset_and_limit};
+        use crate::query::parser::{parse_sql, extract_table_name, extract_equality_predicate};
+
+        // Parse SQL
+        let statement = parse_sql(sql)?;
+
+        // Extract table name
+        let table = extract_table_name(&statement)?;
+
+        let _span = db_query_span(sql, &table, Some(tenant_id)).entered();
+
+        // Keyed on the limit too: an unbounded result must not answer a bounded call
+        if let Some(cached) = self.cached_query(tenant_id, sql, max_scan_rows) {
+            record_db_metrics(Some(cached.as_array().map_or(1, Vec::len)), None);
+            return Ok(cached);
+        }
+
+        // Use key or field index when the WHERE clause pins an indexed field
+        let (key_field, indexed_fields) = {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+            (
+                registry.get_key_field(&table).ok().flatten(),
+                registry.get_indexed_fields(&table).unwrap_or_default(),
+            )
+        };
+        let key_value = key_field
+            .as_deref()
+            .and_then(|field| extract_equality_predicate(&statement, field));
+        let indexed_value = indexed_fields
+            .iter()
+            .find_map(|field| extract_equality_predicate(&statement, field).map(|value| (field, value)));
+        let (offset, limit) = offset_and_limit(&statement);
+
+        let entities = if let Some(key_value) = key_value {
+            // Every entity under the key; the WHERE clause picks the exact match
+            let ids = self.key_lookup_ids(tenant_id, &table, &key_value)?;
+            self.get_batch(tenant_id, &ids)?
+                .into_iter()
+                .flatten()
+                .filter(|entity| !entity.is_deleted())
+                .collect()
+        } else if let Some((field, literal)) = indexed_value {
+            self.query_by_sql_literal(tenant_id, &table, field, literal)?
+        } else if let Some(limit) = limit.filter(|_| is_streamable(&statement)) {
+            self.scan_until_matched(tenant_id, &table, &statement, offset.saturating_add(limit), max_scan_rows)?
+        } else {
+            self.scan_table(tenant_id, &table, max_scan_rows)?
+        };
+
+        // Execute query (re-applies the full WHERE clause)
+        let expires_at = earliest_expiry(&entities);
+        let result = crate::query::executor::execute_query(&statement, entities)?;
+        self.cache_query(tenant_id, sql, max_scan_rows, &table, expires_at, &result);
+        record_db_metrics(Some(result.as_array().map_or(1, Vec::len)), None);
+
+        Ok(result)
+    }
+
+    /// Open a cursor that returns SQL results one page at a time.
+    ///
+    /// Plain SELECTs (WHERE, projection, OFFSET, LIMIT) scan the table lazily,
+    /// `page_size` entities per page, and ignore `max_scan_rows`. Queries
+    /// with ORDER BY or aggregates, and key-field lookups, are executed up
+    /// front via `query_sql` and paged from memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `sql` - SQL query string
+    /// * `page_size` - Entities scanned (or rows returned) per page
+    ///
+    /// # Returns
+    ///
+    /// `SqlCursor`; call `next_page` until it returns `None`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::QueryError` if the SQL is invalid
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut cursor = db.query_sql_cursor("tenant1", "SELECT name FROM person", 100)?;
+    /// while let Some(rows) = cursor.next_page(&db)? {
+    ///     println!("{} rows", rows.len());
+    /// }
+    /// ```
+    pub fn query_sql_cursor(
+        &self,
+        tenant_id: &str,
+        sql: &str,
+        page_size: usize,
+    ) -> Result<crate::query::SqlCursor> {
+        use crate::query::executor::is_streamable;
+        use crate::query::parser::{parse_sql, extract_table_name, extract_equality_predicate};
+        use crate::query::SqlCursor;
+
+        let statement = parse_sql(sql)?;
+        let table = extract_table_name(&statement)?;
+
+        let key_field = {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+            registry.get_key_field(&table).ok().flatten()
+        };
+        let key_lookup = key_field
+            .as_deref()
+            .and_then(|field| extract_equality_predicate(&statement, field))
+            .is_some();
+
+        if key_lookup || !is_streamable(&statement) {
+            let rows = match self.query_sql(tenant_id, sql)? {
+                serde_json::Value::Array(rows) => rows,
+                other => vec![other],
+            };
+            return Ok(SqlCursor::materialized(tenant_id, table, statement, rows, page_size));
+        }
+
+        Ok(SqlCursor::scan(tenant_id, table, statement, page_size))
+    }
+
+    /// Look up entities by an indexed field pinned to a SQL literal.
+    ///
+    /// SQL literals lose their JSON type, so a numeric literal is looked up
+    /// both as written and as a number (`30.0` is indexed as `30`). The
+    /// caller re-applies the WHERE clause to the candidates.
+    fn query_by_sql_literal(&self, tenant_id: &str, table: &str, field: &str, literal: String) -> Result<Vec<Entity>> {
+        let number = serde_json::from_str::<serde_json::Number>(&literal).ok().map(serde_json::Value::Number);
+        let mut entities = self.query_by_index(tenant_id, table, field, &serde_json::Value::String(literal))?;
+
+        if let Some(number) = number {
+            let seen: HashSet<uuid::Uuid> = entities.iter().map(|e| e.system.id).collect();
+            for entity in self.query_by_index(tenant_id, table, field, &number)? {
+                if !seen.contains(&entity.system.id) {
+                    entities.push(entity);
+                }
+            }
+        }
+
+        Ok(entities)
+    }
+
+    /// Scan until `wanted` entities match the WHERE clause, failing past `max_rows`.
+    ///
+    /// Used for streamable queries with a LIMIT, so `LIMIT n` on a large
+    /// table reads only as far as the n-th match.
+    fn scan_until_matched(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        statement: &sqlparser::ast::Statement,
+        wanted: usize,
+        max_rows: Option<usize>,
+    ) -> Result<Vec<Entity>> {
+        use crate::query::cursor::DEFAULT_PAGE_SIZE;
+        use crate::query::executor::matches_where;
+
+        let page_size = wanted.clamp(1, DEFAULT_PAGE_SIZE);
+        let mut matched = Vec::new();
+        let mut scanned = 0usize;
+        let mut after = None;
+
+        while matched.len() < wanted {
+            let page = self.list_page(tenant_id, table, after, page_size)?;
+            let exhausted = page.len() < page_size;
+            after = page.last().map(|e| e.system.id);
+
+            for entity in page {
+                scanned += 1;
+                if let Some(limit) = max_rows.filter(|limit| scanned > *limit) {
+                    return Err(DatabaseError::ScanLimitExceeded {
+                        table: table.to_string(),
+                        limit,
+                    });
+                }
+
+                if matches_where(statement, &entity) {
+                    matched.push(entity);
+                    if matched.len() == wanted {
+                        break;
+                    }
+                }
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+
+        Ok(matched)
+    }
+
+    /// Scan all live entities of a table, failing past `max_rows`.
+    fn scan_table(&self, tenant_id: &str, table: &str, max_rows: Option<usize>) -> Result<Vec<Entity>> {
+        let limit = match max_rows {
+            Some(limit) => limit,
+            None => return self.list(tenant_id, table, false, None, None),
+        };
+
+        // Fetch one extra row to detect overflow without scanning the rest
+        let entities = self.list(tenant_id, table, false, Some(limit.saturating_add(1)), None)?;
+        if entities.len() > limit {
+            return Err(DatabaseError::ScanLimitExceeded {
+                table: table.to_string(),
+                limit,
+            });
+        }
+
+        Ok(entities)
+    }
+
+    /// Semantic search using vector similarity.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    /// * `query` - Search query text
+    /// * `top_k` - Number of results to return
+    ///
+    /// # Returns
+    ///
+    /// `SearchResult`s, best first (`rank` 0-based, scores descending)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - Schema not found
+    /// - Schema doesn't have embeddings configured
+    /// - Embedding generation fails
+    /// - HNSW index not built
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let results = db.search("tenant1", "articles", "rust programming", 10).await?;
+    /// for hit in results {
+    ///     println!("#{} score {:.4}: {:?}", hit.rank, hit.score, hit.entity.properties.get("title"));
+    /// }
+    /// ```
+    pub async fn search(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Vec<crate::types::SearchResult>> {
+        self.search_with_mode(tenant_id, table, query, top_k, crate::index::SearchMode::default()).await
+    }
+
+    /// Semantic search with an explicit recall/latency mode.
+    ///
+    /// Same as `search` (which uses `SearchMode::Balanced`), but `mode`
+    /// selects the HNSW `ef_search` and over-fetch presets.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    /// * `query` - Search query text
+    /// * `top_k` - Number of results to return
+    /// * `mode` - `Fast`, `Balanced`, or `Accurate`
+    ///
+    /// # Returns
+    ///
+    /// `SearchResult`s, best first (`rank` 0-based, scores descending)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let results = db.search_with_mode("tenant1", "articles", "rust", 10, SearchMode::Accurate).await?;
+    /// ```
+    pub async fn search_with_mode(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query: &str,
+        top_k: usize,
+        mode: crate::index::SearchMode,
+    ) -> Result<Vec<crate::types::SearchResult>> {
+        use tracing::Instrument;
+
+        // Instrument the future rather than entering the span, which is not Send
+        let span = db_span(DbOperation::Search, Some(table), Some(tenant_id));
+        let results = self.search_inner(tenant_id, table, query, top_k, mode)
+            .instrument(span.clone())
+            .await?;
+
+        span.in_scope(|| record_db_metrics(Some(results.len()), None));
+        Ok(results)
+    }
+
+    async fn search_inner(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query: &str,
+        top_k: usize,
+        mode: crate::index::SearchMode,
+    ) -> Result<Vec<crate::types::SearchResult>> {
+        // Key on the normalized text: variants that embed identically share
+        // results. Quoting keeps apostrophes in the text from ending the literal
+        let preprocessor = self.embedding_preprocessor(table)?;
+        let cache_key = format!(
+            "SEARCH {} IN {} LIMIT {} MODE {}",
+            crate::query::cache::quote_literal(&preprocessor.apply(query)), table, top_k, mode
+        );
+        if let Some(cached) = self.cached_query(tenant_id, &cache_key, None) {
+            return Ok(serde_json::from_value(cached)?);
+        }
+
+        let query_embedding = self.embed_query(table, query).await?;
+        let results = self.search_by_vector(tenant_id, table, &query_embedding, top_k, None, mode).await?;
+
+        let expires_at = earliest_expiry(results.iter().map(|r| &r.entity));
+        self.cache_query(tenant_id, &cache_key, None, table, expires_at, &serde_json::to_value(&results)?);
+
+        Ok(results)
+    }
+
+    /// Check that indexes and embeddings agree with stored entities.
+    ///
+    /// See `storage::consistency` for what is checked. Entities of
+    /// unregistered schemas (and persisted schema records) are only used for
+    /// orphan detection, since they have no expected index entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `repair` - Delete orphaned entries and rebuild missing ones
+    ///
+    /// # Returns
+    ///
+    /// `ConsistencyReport` with counts of each problem found
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if a scan or repair write fails
+    pub fn check_consistency(&self, repair: bool) -> Result<crate::storage::ConsistencyReport> {
+        use crate::schema::PydanticSchemaParser;
+        use crate::storage::column_families::{CF_EMBEDDINGS, CF_ENTITIES, CF_INDEXES, CF_KEY_INDEX};
+        use crate::storage::consistency::referenced_entity;
+        use crate::storage::ConsistencyReport;
+
+        let _span = db_span(DbOperation::Scan, None, None).entered();
+
+        let mut report = ConsistencyReport { repaired: repair, ..Default::default() };
+        let mut live: HashSet<(String, uuid::Uuid)> = HashSet::new();
+
+        // 1. Every entity has its key and field index entries
+        let registry = self.registry.read()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+
+        for item in self.provider.iterator(CF_ENTITIES, b"entity:") {
+            let (key, value) = item?;
+            let (tenant_id, entity_id) = crate::storage::keys::decode_entity_key(&key)?;
+            let entity: Entity = serde_json::from_slice(&value)?;
+            report.entities += 1;
+            live.insert((tenant_id.clone(), entity_id));
+
+            // Persisted schemas are written without index entries by design
+            if entity.system.entity_type == "schemas" {
+                continue;
+            }
+            let Ok(schema) = registry.get(&entity.system.entity_type) else {
+                continue;
+            };
+            let index_value = serde_json::json!({"type": entity.system.entity_type}).to_string();
+
+            let key_field = PydanticSchemaParser::extract_key_field(schema);
+            if let Some(key_value) = extract_key_value(&entity.properties, key_field.as_deref()) {
+                let case_insensitive = PydanticSchemaParser::extract_key_case_insensitive(schema);
+                for key_value in key_index_values(key_value, case_insensitive) {
+                    let index_key = crate::storage::keys::encode_key_index(&tenant_id, &key_value, entity_id);
+                    if self.provider.get(CF_KEY_INDEX, &index_key)?.is_none() {
+                        report.missing_key_entries += 1;
+                        if repair {
+                            self.provider.put(CF_KEY_INDEX, &index_key, index_value.as_bytes())?;
+                        }
+                    }
+                }
+            }
+
+            for field in PydanticSchemaParser::extract_indexed_fields(schema) {
+                let Some(value) = entity.properties.get(&field) else { continue };
+                for field_value in index_values(value) {
+                    let index_key = crate::storage::keys::encode_index_key(&tenant_id, &field, &field_value, entity_id);
+                    if self.provider.get(CF_INDEXES, &index_key)?.is_none() {
+                        report.missing_index_entries += 1;
+                        if repair {
+                            self.provider.put(CF_INDEXES, &index_key, index_value.as_bytes())?;
+                        }
+                    }
+                }
+            }
+        }
+        drop(registry);
+
+        // 2. Every key index, field index, and embedding entry has its entity
+        for (cf, prefix) in [(CF_KEY_INDEX, "key"), (CF_INDEXES, "idx"), (CF_EMBEDDINGS, "emb")] {
+            // Embeddings stay local; the indexes live with the entities
+            let backend: &dyn StorageProvider = if cf == CF_EMBEDDINGS {
+                self.storage.as_ref()
+            } else {
+                self.provider.as_ref()
+            };
+
+            let mut orphans = Vec::new();
+            for item in backend.iterator(cf, format!("{}:", prefix).as_bytes()) {
+                let (key, _) = item?;
+                let Some(entity_ref) = referenced_entity(&key, prefix) else { continue };
+                if !live.contains(&entity_ref) {
+                    orphans.push(key);
+                }
+            }
+
+            match cf {
+                CF_KEY_INDEX => report.orphaned_key_entries = orphans.len(),
+                CF_INDEXES => report.orphaned_index_entries = orphans.len(),
+                _ => report.orphaned_embeddings = orphans.len(),
+            }
+
+            if repair {
+                for key in orphans {
+                    backend.delete(cf, &key)?;
+                }
+            }
+        }
+
+        if repair && !report.is_consistent() {
+            if let Some(ref cache) = self.query_cache {
+                if let Ok(mut cache) = cache.lock() {
+                    cache.clear();
+                }
+            }
+        }
+
+        if !report.is_consistent() {
+            tracing::warn!(?report, "Consistency check found problems");
+        }
+
+        Ok(report)
+    }
+
+    /// Sample stored embeddings and report whether their norms agree.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    ///
+    /// # Returns
+    ///
+    /// Report classifying the sample as normalized, unnormalized, or mixed
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let report = db.check_embedding_consistency("tenant1", "articles")?;
+    /// if !report.is_consistent() {
+    ///     db.normalize_stored("tenant1", "articles")?;
+    /// }
+    /// ```
+    pub fn check_embedding_consistency(
+        &self,
+        tenant_id: &str,
+        table: &str,
+    ) -> Result<crate::embeddings::EmbeddingConsistencyReport> {
+        use crate::embeddings::EmbeddingConsistencyReport;
+
+        let _span = db_span(DbOperation::Scan, Some(table), Some(tenant_id)).entered();
+
+        let entities = self.list(tenant_id, table, false, Some(EMBEDDING_CHECK_SAMPLE), None)?;
+        let norms = entities
+            .iter()
+            .filter_map(|e| e.get_embedding())
+            .map(|v| crate::index::similarity::norm(&v));
+
+        Ok(EmbeddingConsistencyReport::from_norms(norms))
+    }
+
+    /// Report vector index health metrics for a table.
+    ///
+    /// Builds the same HNSW index search uses and measures what it holds
+    /// (see `index::health`). Nearest-neighbor distance is averaged over up
+    /// to 200 vectors; everything else covers the whole table. Track the
+    /// numbers over time and alert on drift.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    ///
+    /// # Returns
+    ///
+    /// `IndexHealthReport` with missing, mismatched, and indexed counts,
+    /// average nearest-neighbor distance, and median norm
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let health = db.index_health("tenant1", "articles").await?;
+    /// if health.missing_fraction > 0.05 || !health.is_fully_indexed() {
+    ///     tracing::warn!(?health, "Vector index drifting");
+    /// }
+    /// ```
+    pub async fn index_health(&self, tenant_id: &str, table: &str) -> Result<crate::index::IndexHealthReport> {
+        use crate::index::health::{median, IndexHealthReport};
+        use crate::index::SearchMode;
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Scan, Some(table), Some(tenant_id));
+
+        async {
+            let entities = self.list(tenant_id, table, false, None, None)?;
+
+            let mut report = IndexHealthReport { entities: entities.len(), ..Default::default() };
+            let mut vectors = Vec::new();
+            let mut dimension_counts: std::collections::BTreeMap<usize, usize> = std::collections::BTreeMap::new();
+            for entity in &entities {
+                match entity.get_embedding() {
+                    Some(vector) if !vector.is_empty() => {
+                        *dimension_counts.entry(vector.len()).or_default() += 1;
+                        vectors.push((entity.system.id, vector));
+                    }
+                    _ => report.missing_embeddings += 1,
+                }
+            }
+
+            if report.entities > 0 {
+                report.missing_fraction = report.missing_embeddings as f32 / report.entities as f32;
+            }
+            report.median_norm = median(vectors.iter().map(|(_, v)| crate::index::similarity::norm(v)).collect());
+
+            // Most common dimension wins (ties go to the larger one)
+            let Some((&dimensions, _)) = dimension_counts.iter().max_by_key(|(_, count)| **count) else {
+                return Ok(report);
+            };
+            report.dimensions = dimensions;
+
+            let indexable: Vec<(uuid::Uuid, Vec<f32>)> = vectors
+                .into_iter()
+                .filter(|(_, v)| v.len() == dimensions)
+                .collect();
+            let sample: Vec<(uuid::Uuid, Vec<f32>)> = indexable.iter().take(INDEX_HEALTH_SAMPLE).cloned().collect();
+
+            let mut index = self.new_hnsw_index(dimensions, indexable.len(), SearchMode::Accurate.ef_search_for(2));
+            index.build_from_vectors(indexable).await?;
+            report.indexed = index.num_nodes();
+            report.index_mismatch = (report.entities - report.missing_embeddings).saturating_sub(report.indexed);
+
+            if report.indexed >= 2 {
+                let mut total = 0.0;
+                let mut measured = 0;
+                for (id, vector) in &sample {
+                    let neighbors = index.search(vector, 2).await?;
+                    if let Some((_, distance)) = neighbors.into_iter().find(|(neighbor, _)| neighbor != id) {
+                        total += distance;
+                        measured += 1;
+                    }
+                }
+                if measured > 0 {
+                    report.avg_nn_distance = Some(total / measured as f32);
+                }
+            }
+
+            Ok(report)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// L2-normalize every stored embedding in a table.
+    ///
+    /// Vectors already within tolerance and soft-deleted entities are left
+    /// untouched. Rewrites are committed in batches of `write_batch_size`.
+    /// Vector search builds its index from stored vectors, so the next
+    /// search sees the new values.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    ///
+    /// # Returns
+    ///
+    /// Number of entities rewritten
+    pub fn normalize_stored(&self, tenant_id: &str, table: &str) -> Result<usize> {
+        use crate::embeddings::norms::{is_unit, l2_normalize};
+        use crate::query::cursor::DEFAULT_PAGE_SIZE;
+
+        let _span = db_span(DbOperation::BatchWrite, Some(table), Some(tenant_id)).entered();
+
+        // Re-checked under the commit stripes, so concurrent updates aren't lost
+        let mut normalize = |entity: &mut Entity| {
+            if entity.is_deleted() {
+                return false;
+            }
+            let Some(mut vector) = entity.get_embedding() else { return false };
+            let norm = l2_normalize(&mut vector);
+            if norm == 0.0 || is_unit(norm) {
+                return false;
+            }
+            if let Some(props) = entity.properties.as_object_mut() {
+                props.insert("embedding".to_string(), serde_json::json!(vector));
+            }
+            true
+        };
+        let mut targets = Vec::new();
+        let mut updated = Vec::new();
+        let mut after = None;
+
+        loop {
+            let page = self.list_page(tenant_id, table, after, DEFAULT_PAGE_SIZE)?;
+            let exhausted = page.len() < DEFAULT_PAGE_SIZE;
+            after = page.last().map(|e| e.system.id);
+
+            for mut entity in page {
+                if !normalize(&mut entity) {
+                    continue;
+                }
+                targets.push((tenant_id.to_string(), entity.system.id));
+
+                if targets.len() >= self.write_batch_size {
+                    let rewritten = self.rewrite_stored(&std::mem::take(&mut targets), Vec::new(), &mut normalize)?;
+                    self.log_updates(&rewritten)?;
+                    updated.extend(rewritten);
+                }
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+
+        let rewritten = self.rewrite_stored(&targets, Vec::new(), &mut normalize)?;
+        self.log_updates(&rewritten)?;
+        updated.extend(rewritten);
+        self.invalidate_query_cache(table);
+
+        for (_, entity) in &updated {
+            self.notify_change(tenant_id, ChangeOp::Update, table, entity.system.id);
+        }
+
+        record_db_metrics(None, Some(updated.len()));
+
+        Ok(updated.len())
+    }
+
+    /// Reduce a table's embeddings to fewer dimensions with PCA.
+    ///
+    /// Fits a `PcaProjection` over a uniform sample of up to 2,000 stored
+    /// vectors (all tenants) and rewrites every stored vector of the fitted
+    /// dimension. From then on, inserted vectors of that dimension are
+    /// projected before storage and `search_by_vector` projects full-size
+    /// queries, so callers keep embedding with the original model. See
+    /// `embeddings::projection`.
+    ///
+    /// The projection is recorded as pending before the rewrite and only
+    /// activated in the rewrite's final batch. If the rewrite is interrupted,
+    /// calling this again with the same `target_dim` resumes it with the
+    /// pending projection (the report's `sampled` is then 0).
+    ///
+    /// Reduction is one-way: the original vectors are not kept, and a table
+    /// can only be reduced once.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Schema/table name
+    /// * `target_dim` - Dimensions to keep
+    ///
+    /// # Returns
+    ///
+    /// `DimensionReductionReport` with the variance kept and vectors rewritten
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` if the table is already reduced,
+    /// `target_dim` is out of range, or differs from an interrupted
+    /// reduction's, `DatabaseError::SearchError` if no
+    /// entity has an embedding
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let report = db.fit_dimensionality_reduction("articles", 256)?;
+    /// println!("{} -> {} dims, {:.1}% variance kept", report.source_dim, report.target_dim, report.explained_variance * 100.0);
+    /// ```
+    pub fn fit_dimensionality_reduction(
+        &self,
+        table: &str,
+        target_dim: usize,
+    ) -> Result<crate::embeddings::DimensionReductionReport> {
+        use crate::embeddings::{DimensionReductionReport, PcaProjection};
+        use crate::storage::column_families::{CF_EMBEDDINGS, CF_ENTITIES};
+
+        let _span = db_span(DbOperation::BatchWrite, Some(table), None).entered();
+
+        if self.dimensionality_reduction(table).is_some() {
+            return Err(DatabaseError::ConfigError(format!(
+                "Embeddings of '{}' are already reduced",
+                table
+            )));
+        }
+
+        let pending_key = crate::storage::keys::encode_pending_projection_key(table);
+        let (projection, sampled) = match self.provider.get(CF_EMBEDDINGS, &pending_key)? {
+            // Resume an interrupted rewrite with the projection it started with
+            Some(value) => {
+                let projection: PcaProjection = serde_json::from_slice(&value)?;
+                if projection.target_dim() != target_dim {
+                    return Err(DatabaseError::ConfigError(format!(
+                        "Interrupted reduction of '{}' targets {} dims; resume it with that target",
+                        table,
+                        projection.target_dim()
+                    )));
+                }
+                (projection, 0)
+            }
+            None => {
+                let samples = self.sample_embeddings(table)?;
+                let projection = PcaProjection::fit(&samples, target_dim)?;
+                self.provider.put(CF_EMBEDDINGS, &pending_key, &serde_json::to_vec(&projection)?)?;
+                (projection, samples.len())
+            }
+        };
+
+        // Rewrite stored vectors (soft-deleted ones too, so restores match)
+        let mut project = |entity: &mut Entity| {
+            if entity.system.entity_type != table {
+                return false;
+            }
+            let Some(vector) = entity.get_embedding() else { return false };
+            if vector.len() != projection.source_dim() {
+                return false;
+            }
+            if let Some(props) = entity.properties.as_object_mut() {
+                props.insert("embedding".to_string(), serde_json::json!(projection.project(&vector)));
+            }
+            true
+        };
+        let mut targets = Vec::new();
+        let mut updated = Vec::new();
+
+        for item in self.provider.iterator(CF_ENTITIES, b"entity:") {
+            let (key, value) = item?;
+            let entity: Entity = serde_json::from_slice(&value)?;
+            if entity.system.entity_type != table
+                || !entity.get_embedding().is_some_and(|vector| vector.len() == projection.source_dim())
+            {
+                continue;
+            }
+
+            let (tenant_id, _) = crate::storage::keys::decode_entity_key(&key)?;
+            targets.push((tenant_id, entity.system.id));
+
+            if targets.len() >= self.write_batch_size {
+                updated.extend(self.rewrite_stored(&std::mem::take(&mut targets), Vec::new(), &mut project)?);
+            }
+        }
+
+        // Activate the projection atomically with the last rewrites
+        let activate = vec![
+            BatchOp::Put {
+                cf: CF_EMBEDDINGS,
+                key: crate::storage::keys::encode_projection_key(table),
+                value: serde_json::to_vec(&projection)?,
+            },
+            BatchOp::Delete { cf: CF_EMBEDDINGS, key: pending_key },
+        ];
+        updated.extend(self.rewrite_stored(&targets, activate, &mut project)?);
+
+        let report = DimensionReductionReport {
+            source_dim: projection.source_dim(),
+            target_dim: projection.target_dim(),
+            sampled,
+            explained_variance: projection.explained_variance(),
+            rewritten: updated.len(),
+        };
+
+        self.projections.write()
+            .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?
+            .insert(table.to_string(), Arc::new(projection));
+        self.invalidate_query_cache(table);
+
+        for (tenant_id, entity) in &updated {
+            self.notify_change(tenant_id, ChangeOp::Update, table, entity.system.id);
+        }
+
+        record_db_metrics(None, Some(updated.len()));
+
+        Ok(report)
+    }
+
+    /// Embedding projection fitted for a table, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Schema/table name
+    ///
+    /// # Returns
+    ///
+    /// The projection applied to the table's embeddings, or `None`
+    pub fn dimensionality_reduction(&self, table: &str) -> Option<Arc<crate::embeddings::PcaProjection>> {
+        self.projections.read().ok()?.get(table).cloned()
+    }
+
+    /// Reservoir-sample up to `PCA_FIT_SAMPLE` live embeddings of a table.
+    ///
+    /// Only vectors of the first dimension seen are kept, so a partly
+    /// re-embedded table fits on one model's vectors.
+    fn sample_embeddings(&self, table: &str) -> Result<Vec<Vec<f32>>> {
+        use crate::storage::column_families::CF_ENTITIES;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let mut samples: Vec<Vec<f32>> = Vec::new();
+        let mut seen = 0usize;
+
+        for item in self.provider.iterator(CF_ENTITIES, b"entity:") {
+            let (_, value) = item?;
+            let entity: Entity = serde_json::from_slice(&value)?;
+            if entity.system.entity_type != table || entity.is_deleted() {
+                continue;
+            }
+            let Some(vector) = entity.get_embedding() else { continue };
+            if samples.first().is_some_and(|first| first.len() != vector.len()) {
+                continue;
+            }
+
+            seen += 1;
+            if samples.len() < PCA_FIT_SAMPLE {
+                samples.push(vector);
+            } else {
+                let slot = rng.gen_range(0..seen);
+                if slot < PCA_FIT_SAMPLE {
+                    samples[slot] = vector;
+                }
+            }
+        }
+
+        if samples.is_empty() {
+            return Err(DatabaseError::SearchError(
+                format!("No entities in '{}' have embeddings to fit a projection on", table)
+            ));
+        }
+
+        Ok(samples)
+    }
+
+    /// Ingest files through a bounded embedding queue.
+    ///
+    /// Chunks are produced onto a queue of at most `queue_depth` entries and
+    /// embedded by `workers` concurrent tasks using the schema's configured
+    /// provider. The producer blocks while the queue is full, so large
+    /// ingests never hold more than `queue_depth` pending chunks in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `paths` - Files to ingest
+    /// * `schema` - Schema/table name for the chunk entities
+    /// * `workers` - Number of embedding workers
+    /// * `queue_depth` - Maximum chunks waiting for embedding
+    ///
+    /// # Returns
+    ///
+    /// `IngestStats` with files read, chunks stored, and peak queue depth
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IngestError` if a file cannot be read,
+    /// `DatabaseError::EmbeddingError` if embedding fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let stats = db.ingest_pipelined("tenant1", &paths, "resources", 4, 64).await?;
+    /// println!("{} chunks from {} files", stats.chunks, stats.files);
+    /// ```
+    pub async fn ingest_pipelined<P: AsRef<Path>>(
+        &self,
+        tenant_id: &str,
+        paths: &[P],
+        schema: &str,
+        workers: usize,
+        queue_depth: usize,
+    ) -> Result<crate::ingest::IngestStats> {
+        let provider = self.embedding_provider(schema)?;
+        self.ingest_pipelined_with_provider(tenant_id, paths, schema, provider.into(), workers, queue_depth)
+            .await
+    }
+
+    /// Insert an entity, embedding its `embedding_fields` first.
+    ///
+    /// The fields are combined according to the schema's
+    /// `embedding_pooling` (see `embeddings::pooling`) and the result is
+    /// stored as `embedding`. Entities with none of the fields set are
+    /// inserted without one.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Table/schema name (must configure `embedding_fields`)
+    /// * `data` - Entity data
+    ///
+    /// # Returns
+    ///
+    /// UUID of inserted entity
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SearchError` if the schema has no embedding
+    /// fields, `DatabaseError::ConfigError` for an unknown pooling strategy,
+    /// or the provider's error
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let id = db.insert_embedded("tenant1", "articles", json!({"title": "Rust", "content": "..."})).await?;
+    /// ```
+    pub async fn insert_embedded(&self, tenant_id: &str, table: &str, data: serde_json::Value) -> Result<uuid::Uuid> {
+        let provider = self.embedding_provider(table)?;
+        self.insert_embedded_with_provider(tenant_id, table, data, &*provider).await
+    }
+
+    /// Insert an entity, embedding it with an explicit provider.
+    ///
+    /// Same as `insert_embedded`, but embeds with `provider` instead of the
+    /// one configured on the schema.
+    pub async fn insert_embedded_with_provider(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        mut data: serde_json::Value,
+        provider: &dyn crate::embeddings::EmbeddingProvider,
+    ) -> Result<uuid::Uuid> {
+        use crate::embeddings::{embed_fields, PoolingStrategy};
+        use crate::schema::PydanticSchemaParser;
+
+        let schema = self.get_schema(table)?;
+        let fields = PydanticSchemaParser::extract_embedding_fields(&schema);
+        let strategy = PydanticSchemaParser::extract_embedding_pooling(&schema)
+            .map(|s| PoolingStrategy::parse(&s))
+            .transpose()?
+            .unwrap_or_default();
+        let weights = PydanticSchemaParser::extract_embedding_field_weights(&schema);
+
+        if let Some(embedding) = embed_fields(provider, &data, &fields, strategy, &weights, self.embedding_overflow).await? {
+            if let Some(obj) = data.as_object_mut() {
+                obj.insert("embedding".to_string(), serde_json::json!(embedding));
+            }
+        }
+
+        self.insert(tenant_id, table, data)
+    }
+
+    /// Ingest files through a bounded embedding queue with an explicit provider.
+    ///
+    /// Same as `ingest_pipelined`, but embeds with `provider` instead of the
+    /// one configured on the schema.
+    pub async fn ingest_pipelined_with_provider<P: AsRef<Path>>(
+        &self,
+        tenant_id: &str,
+        paths: &[P],
+        schema: &str,
+        provider: Arc<dyn crate::embeddings::EmbeddingProvider>,
+        workers: usize,
+        queue_depth: usize,
+    ) -> Result<crate::ingest::IngestStats> {
+        use crate::ingest::{IngestPipeline, IngestStats};
+        use tracing::Instrument;
+
+        if !self.has_schema(schema) {
+            return Err(DatabaseError::SchemaNotFound(schema.to_string()));
+        }
+
+        let span = db_span(DbOperation::Insert, Some(schema), Some(tenant_id));
+
+        async {
+            let paths = paths.iter().map(|p| p.as_ref().to_path_buf()).collect();
+            let mut pipeline = IngestPipeline::spawn(paths, provider, self.embedding_overflow, workers, queue_depth);
+
+            // Store on this task; workers only embed
+            let mut chunks = 0;
+            let mut stored = Ok(());
+            while let Some(embedded) = pipeline.results.recv().await {
+                stored = embedded.and_then(|chunk| self.insert(tenant_id, schema, chunk.to_properties()).map(|_| ()));
+                if stored.is_err() {
+                    break;
+                }
+                chunks += 1;
+            }
+
+            // Join the tasks and record what was stored even if a chunk failed
+            let max_queue_depth = pipeline.gauge.max_depth();
+            let finished = pipeline.finish().await;
+
+            record_db_metrics(None, Some(chunks));
+
+            stored?;
+            let files = finished?;
+
+            Ok(IngestStats { files, chunks, max_queue_depth })
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Semantic search restricted to entities matching a predicate.
+    ///
+    /// Equality on one of the schema's `indexed_fields` is pushed down to
+    /// `CF_INDEXES`, so only matching entities are loaded and indexed.
+    /// Any other predicate falls back to filtering the scanned table.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    /// * `query` - Search query text
+    /// * `top_k` - Number of results to return
+    /// * `filter` - Predicate candidates must satisfy
+    ///
+    /// # Returns
+    ///
+    /// `SearchResult`s, best first (`rank` 0-based, scores descending)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let filter = Predicate::eq("category", json!("tutorial"));
+    /// let results = db.search_filtered("tenant1", "articles", "rust async", 10, &filter).await?;
+    /// ```
+    pub async fn search_filtered(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query: &str,
+        top_k: usize,
+        filter: &crate::query::Predicate,
+    ) -> Result<Vec<crate::types::SearchResult>> {
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Search, Some(table), Some(tenant_id));
+        async {
+            let query_embedding = self.embed_query(table, query).await?;
+            self.search_by_vector(tenant_id, table, &query_embedding, top_k, Some(filter), crate::index::SearchMode::default()).await
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Semantic search with a precomputed query vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    /// * `query_embedding` - Query vector (stored or, for a reduced table, original dimensions)
+    /// * `top_k` - Number of results to return
+    /// * `filter` - Optional predicate candidates must satisfy
+    /// * `mode` - Recall/latency preset (see `SearchMode`)
+    ///
+    /// # Returns
+    ///
+    /// `SearchResult`s, best first (`rank` 0-based, scores descending)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SearchError` if no candidate has an embedding
+    pub async fn search_by_vector(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query_embedding: &[f32],
+        top_k: usize,
+        filter: Option<&crate::query::Predicate>,
+        mode: crate::index::SearchMode,
+    ) -> Result<Vec<crate::types::SearchResult>> {
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Search, Some(table), Some(tenant_id));
+
+        async {
+            // Full-size queries against a reduced table are projected like stored vectors
+            let projected;
+            let query_embedding = match self.dimensionality_reduction(table) {
+                Some(projection) if query_embedding.len() == projection.source_dim() => {
+                    projected = projection.project(query_embedding);
+                    projected.as_slice()
+                }
+                _ => query_embedding,
+            };
+            let dimensions = query_embedding.len();
+
+            // 1. Collect candidates (index pushdown when possible)
+            // For now, we build the HNSW index on-the-fly from candidates
+            // In production, this should be maintained incrementally
+            let entities = self.filter_candidates(tenant_id, table, filter)?;
+
+            if entities.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            // 2. Build HNSW index from entities with embeddings
+            let mut index = self.new_hnsw_index(dimensions, entities.len(), mode.ef_search_for(top_k));
+            let mut entity_vectors = Vec::new();
+
+            for entity in &entities {
+                // Get embedding from entity properties
+                if let Some(embedding_value) = entity.properties.get("embedding") {
+                    if let Some(embedding_array) = embedding_value.as_array() {
+                        let embedding: Vec<f32> = embedding_array
+                            .iter()
+                            .filter_map(|v| v.as_f64().map(|f| f as f32))
+                            .collect();
+
+                        if embedding.len() == dimensions {
+                            entity_vectors.push((entity.system.id, embedding));
+                        }
+                    }
+                }
+            }
+
+            if entity_vectors.is_empty() {
+                return Err(DatabaseError::SearchError(
+                    format!("No entities in '{}' have embeddings. Insert entities with embedding_fields configured.", table)
+                ));
+            }
+
+            // Build the index
+            index.build_from_vectors(entity_vectors).await?;
+
+            // 3. Search HNSW index for similar vectors
+            let search_results = index.search(query_embedding, top_k).await?;
+
+            // 4. Return candidates with scores
+            let by_id: std::collections::HashMap<uuid::Uuid, Entity> = entities
+                .into_iter()
+                .map(|e| (e.system.id, e))
+                .collect();
+
+            let hits = search_results
+                .into_iter()
+                .filter_map(|(entity_id, distance)| by_id.get(&entity_id).map(|e| (e.clone(), distance)));
+
+            // HNSW measures cosine distance
+            Ok(crate::types::SearchResult::from_distances(hits, crate::types::DistanceMetric::Cosine))
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Find entities similar to an existing one.
+    ///
+    /// Uses the entity's stored embedding as the query vector and searches
+    /// its own table. The entity itself is never returned. Neighbors below
+    /// `min_similarity` are dropped, so an outlier gets fewer than `top_k`
+    /// results (possibly none) instead of weakly related ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `entity_id` - Entity to find neighbors of
+    /// * `top_k` - Maximum number of results
+    /// * `min_similarity` - Similarity floor in `[0, 1]` (0.0 keeps every neighbor)
+    ///
+    /// # Returns
+    ///
+    /// `SearchResult`s, best first (`rank` 0-based, scores descending)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::EntityNotFound` if the entity doesn't exist,
+    /// `DatabaseError::SearchError` if it has no embedding, or
+    /// `DatabaseError::ConfigError` if `min_similarity` is outside `[0, 1]`
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// for hit in db.more_like_this("tenant1", article_id, 5, 0.8).await? {
+    ///     println!("{} {:.3}", hit.entity.properties["title"], hit.score);
+    /// }
+    /// ```
+    pub async fn more_like_this(
+        &self,
+        tenant_id: &str,
+        entity_id: uuid::Uuid,
+        top_k: usize,
+        min_similarity: f32,
+    ) -> Result<Vec<crate::types::SearchResult>> {
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Search, None, Some(tenant_id));
+
+        async {
+            check_min_similarity(min_similarity)?;
+
+            let entity = self.get(tenant_id, entity_id)?
+                .ok_or(DatabaseError::EntityNotFound(entity_id))?;
+            let table = entity.system.entity_type.clone();
+
+            let embedding = entity.get_embedding()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| DatabaseError::SearchError(format!(
+                    "Entity {} in '{}' has no embedding to search with",
+                    entity_id, table
+                )))?;
+
+            // One extra candidate: the entity finds itself first
+            let results = self
+                .search_by_vector(tenant_id, &table, &embedding, top_k + 1, None, crate::index::SearchMode::default())
+                .await?;
+
+            Ok(results
+                .into_iter()
+                .filter(|hit| hit.entity.system.id != entity_id && hit.score >= min_similarity)
+                .take(top_k)
+                .enumerate()
+                .map(|(rank, hit)| crate::types::SearchResult { rank, ..hit })
+                .collect())
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Link each entity to its nearest neighbors with similarity edges.
+    ///
+    /// Builds one HNSW index over the table's embeddings, then for every
+    /// entity adds up to `max_neighbors` `rel_type` edges to neighbors whose
+    /// similarity is at least `min_similarity`, so outliers get no edges
+    /// rather than spurious ones. The similarity is stored as the
+    /// edge `weight`. Neighborhoods are not symmetric, so A → B does not
+    /// imply B → A. Rebuilding overwrites existing edges with fresh weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name
+    /// * `min_similarity` - Similarity floor in `[0, 1]` for an edge
+    /// * `max_neighbors` - Maximum edges per entity
+    /// * `rel_type` - Relationship type of created edges (e.g. `"similar_to"`)
+    ///
+    /// # Returns
+    ///
+    /// Number of edges written
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SearchError` if no entity has an embedding,
+    /// `DatabaseError::ConfigError` if `min_similarity` is outside `[0, 1]`, or
+    /// `DatabaseError::EdgeCycle` if `rel_type` is declared acyclic
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let edges = db.build_similarity_graph("tenant1", "articles", 0.9, 5, "similar_to").await?;
+    /// let similar = db.get_edges(article_id, Some("similar_to"))?;
+    /// ```
+    pub async fn build_similarity_graph(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        min_similarity: f32,
+        max_neighbors: usize,
+        rel_type: &str,
+    ) -> Result<usize> {
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::BatchWrite, Some(table), Some(tenant_id));
+
+        async {
+            check_min_similarity(min_similarity)?;
+
+            let entities = self.list(tenant_id, table, false, None, None)?;
+            if entities.is_empty() || max_neighbors == 0 {
+                return Ok(0);
+            }
+
+            let mut entity_vectors: Vec<(uuid::Uuid, Vec<f32>)> = entities
+                .iter()
+                .filter_map(|e| e.get_embedding().map(|v| (e.system.id, v)))
+                .filter(|(_, v)| !v.is_empty())
+                .collect();
+
+            let Some(dimensions) = entity_vectors.first().map(|(_, v)| v.len()) else {
+                return Err(DatabaseError::SearchError(
+                    format!("No entities in '{}' have embeddings. Insert entities with embedding_fields configured.", table)
+                ));
+            };
+            entity_vectors.retain(|(_, v)| v.len() == dimensions);
+
+            // One extra candidate: each entity finds itself first
+            let k = max_neighbors + 1;
+            let mut index = self.new_hnsw_index(
+                dimensions,
+                entity_vectors.len(),
+                crate::index::SearchMode::default().ef_search_for(k),
+            );
+            index.build_from_vectors(entity_vectors.clone()).await?;
+
+            let mut created = 0;
+            for (src_id, vector) in &entity_vectors {
+                let neighbors = index.search(vector, k).await?;
+
+                for (dst_id, distance) in neighbors
+                    .into_iter()
+                    .filter(|(dst_id, _)| dst_id != src_id)
+                    .take(max_neighbors)
+                {
+                    let similarity = crate::types::DistanceMetric::Cosine.to_score(distance);
+                    if similarity < min_similarity {
+                        continue;
+                    }
+
+                    self.add_edge(tenant_id, *src_id, dst_id, rel_type, Some(serde_json::json!({"weight": similarity})))?;
+                    created += 1;
+                }
+            }
+
+            Ok(created)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Answer a batch of natural language questions.
+    ///
+    /// Identical questions are planned and executed once. Up to
+    /// `max_concurrency` distinct questions are planned at a time; plans
+    /// then execute in question order. A question that fails to plan or
+    /// execute gets an answer with `error` set instead of failing the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `planner` - Query planner (usually `LlmQueryBuilder::from_env()?`)
+    /// * `tenant_id` - Tenant identifier
+    /// * `questions` - Natural language questions
+    /// * `schema_hint` - Schema to query (`None` = offer every registered schema)
+    /// * `max_concurrency` - Questions planned concurrently (see `DEFAULT_ASK_CONCURRENCY`)
+    ///
+    /// # Returns
+    ///
+    /// One answer per question, in input order
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InternalError` if a planning task panics
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let planner = Arc::new(LlmQueryBuilder::from_env()?);
+    /// let answers = db.ask_batch(planner, "default", questions, Some("articles"), DEFAULT_ASK_CONCURRENCY).await?;
+    /// for answer in &answers {
+    ///     println!("{}: {} result(s)", answer.question, answer.results.len());
+    /// }
+    /// ```
+    pub async fn ask_batch(
+        &self,
+        planner: Arc<dyn crate::llm::QueryPlanner>,
+        tenant_id: &str,
+        questions: Vec<String>,
+        schema_hint: Option<&str>,
+        max_concurrency: usize,
+    ) -> Result<Vec<crate::llm::AskAnswer>> {
+        use crate::llm::{execute_plan, AskAnswer, QueryPlan};
+        use std::collections::HashMap;
+        use tokio::sync::Semaphore;
+        use tokio::task::JoinSet;
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Query, None, Some(tenant_id));
+
+        async {
+            let schema_context = match schema_hint {
+                Some(schema) => format!("Schema: {}", schema),
+                None => format!("Available schemas: {}", self.list_schemas()?.join(", ")),
+            };
+
+            // Distinct questions in first-seen order
+            let mut slots: HashMap<&str, usize> = HashMap::new();
+            let mut distinct: Vec<&str> = Vec::new();
+            for question in &questions {
+                slots.entry(question.as_str()).or_insert_with(|| {
+                    distinct.push(question.as_str());
+                    distinct.len() - 1
+                });
+            }
+
+            let semaphore = Arc::new(Semaphore::new(max_concurrency.max(1)));
+            let mut tasks = JoinSet::new();
+            for (slot, question) in distinct.iter().enumerate() {
+                let planner = Arc::clone(&planner);
+                let semaphore = Arc::clone(&semaphore);
+                let question = question.to_string();
+                let schema_context = schema_context.clone();
+                tasks.spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    (slot, planner.plan_query(&question, &schema_context).await)
+                });
+            }
+
+            let mut plans: Vec<Option<Result<QueryPlan>>> = (0..distinct.len()).map(|_| None).collect();
+            while let Some(joined) = tasks.join_next().await {
+                let (slot, plan) = joined
+                    .map_err(|e| DatabaseError::InternalError(format!("Planning task failed: {}", e)))?;
+                plans[slot] = Some(plan);
+            }
+
+            let mut answers = Vec::with_capacity(distinct.len());
+            for (question, plan) in distinct.iter().zip(plans) {
+                let mut answer = AskAnswer {
+                    question: question.to_string(),
+                    plan: None,
+                    results: Vec::new(),
+                    error: None,
+                };
+                match plan.expect("every planning task reports its slot") {
+                    Ok(plan) => {
+                        match execute_plan(self, tenant_id, &plan).await {
+                            Ok(results) => answer.results = results,
+                            Err(e) => answer.error = Some(e.to_string()),
+                        }
+                        answer.plan = Some(plan);
+                    }
+                    Err(e) => answer.error = Some(e.to_string()),
+                }
+                answers.push(answer);
+            }
+
+            Ok(questions.iter().map(|q| answers[slots[q.as_str()]].clone()).collect())
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Plan a question and replay the plan stage by stage.
+    ///
+    /// Backs `rem ask --explain`. See `llm::explain_plan` for how stages
+    /// and fallbacks are executed.
+    ///
+    /// # Arguments
+    ///
+    /// * `planner` - Query planner (usually `LlmQueryBuilder::from_env()?`)
+    /// * `tenant_id` - Tenant identifier
+    /// * `question` - Natural language question
+    /// * `schema_context` - Schema information passed to the planner
+    ///
+    /// # Returns
+    ///
+    /// `PlanExplanation` with the plan and one `StageReport` per executed stage
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if planning fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let planner = LlmQueryBuilder::from_env()?;
+    /// let explanation = db.explain_ask(&planner, "default", "recent rust articles", "Available schemas: articles").await?;
+    /// print!("{}", explanation);
+    /// ```
+    pub async fn explain_ask(
+        &self,
+        planner: &dyn crate::llm::QueryPlanner,
+        tenant_id: &str,
+        question: &str,
+        schema_context: &str,
+    ) -> Result<crate::llm::PlanExplanation> {
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Query, None, Some(tenant_id));
+
+        async {
+            let plan = planner.plan_query(question, schema_context).await?;
+            crate::llm::explain_plan(self, tenant_id, &plan).await
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Answer a question from the entities most similar to it.
+    ///
+    /// Searches `table` for the `top_k` best matches, numbers them into a
+    /// context prompt (see `llm::build_context`), and has `generator` write
+    /// an answer grounded in that context. If nothing is retrieved the
+    /// generator is not called and the answer is empty. Call
+    /// `RagAnswer::verify` to check the answer against its sources.
+    ///
+    /// With `neighbor_chunks` > 0, each retrieved chunk is expanded with the
+    /// chunks of the same `uri` up to that many `chunk_ordinal`s before and
+    /// after it, so text split across chunk boundaries stays together. Each
+    /// hit's chunks are added in document order; chunks already included by
+    /// an earlier hit are not repeated.
+    ///
+    /// # Arguments
+    ///
+    /// * `generator` - Answer generator (usually `LlmQueryBuilder::from_env()?`)
+    /// * `tenant_id` - Tenant identifier
+    /// * `question` - Natural language question
+    /// * `table` - Schema/table to retrieve from
+    /// * `top_k` - Number of chunks to retrieve
+    /// * `neighbor_chunks` - Adjacent chunks added on each side of a hit (0 = none)
+    ///
+    /// # Returns
+    ///
+    /// Answer with the source entity IDs in context order
+    ///
+    /// # Errors
+    ///
+    /// Returns search errors (e.g. no embeddings configured) or
+    /// `DatabaseError::LlmError` if generation fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let llm = LlmQueryBuilder::from_env()?;
+    /// let rag = db.rag_answer(&llm, "default", "How does ownership work?", "resources", 5, 1).await?;
+    /// println!("{}\nSources: {:?}", rag.answer, rag.sources);
+    /// ```
+    pub async fn rag_answer(
+        &self,
+        generator: &dyn crate::llm::AnswerGenerator,
+        tenant_id: &str,
+        question: &str,
+        table: &str,
+        top_k: usize,
+        neighbor_chunks: usize,
+    ) -> Result<crate::llm::RagAnswer> {
+        let provider = self.embedding_provider(table)?;
+        self.rag_answer_with_provider(generator, &*provider, tenant_id, question, table, top_k, neighbor_chunks).await
+    }
+
+    /// Answer a question from retrieved entities with an explicit embedding provider.
+    ///
+    /// Same as `rag_answer`, but embeds the question with `provider` instead
+    /// of the one configured on the schema.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn rag_answer_with_provider(
+        &self,
+        generator: &dyn crate::llm::AnswerGenerator,
+        provider: &dyn crate::embeddings::EmbeddingProvider,
+        tenant_id: &str,
+        question: &str,
+        table: &str,
+        top_k: usize,
+        neighbor_chunks: usize,
+    ) -> Result<crate::llm::RagAnswer> {
+        use crate::embeddings::truncate::embed_within_limit;
+        use tracing::Instrument;
+
+        let span = db_span(DbOperation::Search, Some(table), Some(tenant_id));
+
+        async {
+            let (query_embedding, _) = embed_within_limit(provider, question, self.embedding_overflow).await?;
+            let results = self
+                .search_by_vector(tenant_id, table, &query_embedding, top_k, None, crate::index::SearchMode::default())
+                .await?;
+
+            let hits: Vec<Entity> = results.into_iter().map(|hit| hit.entity).collect();
+            let chunks = self.expand_chunk_neighbors(tenant_id, table, hits, neighbor_chunks)?;
+
+            let sources: Vec<uuid::Uuid> = chunks.iter().map(|chunk| chunk.system.id).collect();
+            let context = crate::llm::build_context(&chunks);
+            let answer = if chunks.is_empty() {
+                String::new()
+            } else {
+                generator.generate_answer(question, &context).await?
+            };
+
+            Ok(crate::llm::RagAnswer {
+                question: question.to_string(),
+                answer,
+                sources,
+                context,
+                grounding: None,
+            })
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Add each chunk's neighbors (same `uri`, `chunk_ordinal` within `window`).
+    ///
+    /// Neighbors are fetched by their deterministic ID; entities without
+    /// `uri` stand alone. Each entity appears once, at its first position.
+    fn expand_chunk_neighbors(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        hits: Vec<Entity>,
+        window: usize,
+    ) -> Result<Vec<Entity>> {
+        if window == 0 {
+            return Ok(hits);
+        }
+
+        let mut seen = HashSet::new();
+        let mut expanded = Vec::new();
+
+        for hit in hits {
+            let Some(uri) = hit.properties.get("uri").and_then(|v| v.as_str()).map(str::to_string) else {
+                if seen.insert(hit.system.id) {
+                    expanded.push(hit);
+                }
+                continue;
+            };
+            let ordinal = hit.properties.get("chunk_ordinal").and_then(|v| v.as_u64()).unwrap_or(0);
+
+            let first = ordinal.saturating_sub(window as u64);
+            for neighbor_ordinal in first..=ordinal + window as u64 {
+                let chunk = if neighbor_ordinal == ordinal {
+                    Some(hit.clone())
+                } else {
+                    let id = crate::types::generate_uuid(
+                        table,
+                        &serde_json::json!({"uri": uri, "chunk_ordinal": neighbor_ordinal}),
+                        None,
+                    );
+                    self.get(tenant_id, id)?.filter(|e| !e.is_deleted())
+                };
+
+                if let Some(chunk) = chunk {
+                    if seen.insert(chunk.system.id) {
+                        expanded.push(chunk);
+                    }
+                }
+            }
+        }
+
+        Ok(expanded)
+    }
+
+    /// Load live entities of a table that satisfy `filter`.
+    ///
+    /// Uses the field index for equality on an indexed field, otherwise
+    /// scans the table and filters in memory.
+    fn filter_candidates(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        filter: Option<&crate::query::Predicate>,
+    ) -> Result<Vec<Entity>> {
+        let filter = match filter {
+            Some(f) => f,
+            None => return self.list(tenant_id, table, false, None, None),
+        };
+
+        let indexed = {
+            let registry = self.registry.read()
+                .map_err(|e| DatabaseError::InternalError(format!("Lock error: {}", e)))?;
+            registry.get_indexed_fields(table)?.contains(&filter.field)
+        };
+
+        if indexed && filter.is_equality() {
+            let ids = self.field_index.lookup(
+                tenant_id,
+                table,
+                &filter.field,
+                &value_to_string(&filter.value),
+            )?;
+
+            // Re-check the predicate: index values are stringified
+            return Ok(self.get_batch(tenant_id, &ids)?
+                .into_iter()
+                .flatten()
+                .filter(|e| !e.is_deleted() && filter.matches(e))
+                .collect());
+        }
+
+        Ok(self.list(tenant_id, table, false, None, None)?
+            .into_iter()
+            .filter(|e| filter.matches(e))
+            .collect())
+    }
+
+    /// Project an `embedding` property through the table's projection.
+    ///
+    /// Vectors that don't have the projection's source dimension (including
+    /// already reduced ones) are left as they are.
+    fn project_embedding(&self, table: &str, data: &mut serde_json::Value) {
+        let Some(projection) = self.dimensionality_reduction(table) else { return };
+        let Some(vector) = data.get("embedding").and_then(|v| v.as_array()) else { return };
+        if vector.len() != projection.source_dim() {
+            return;
+        }
+
+        let vector: Vec<f32> = vector.iter().filter_map(|v| v.as_f64().map(|f| f as f32)).collect();
+        if vector.len() == projection.source_dim() {
+            data["embedding"] = serde_json::json!(projection.project(&vector));
+        }
+    }
+
+    /// Embed search text with the schema's configured provider.
+    async fn embed_query(&self, table: &str, query: &str) -> Result<Vec<f32>> {
+        use crate::embeddings::truncate::embed_within_limit;
+
+        let provider = self.embedding_provider(table)?;
+        let (embedding, _) = embed_within_limit(&*provider, query, self.embedding_overflow).await?;
+        Ok(embedding)
+    }
+
+    /// Resolve the embedding provider configured on a schema.
+    fn embedding_provider(&self, table: &str) -> Result<Box<dyn crate::embeddings::EmbeddingProvider>> {
+        use crate::embeddings::provider::ProviderFactory;
+        use crate::schema::PydanticSchemaParser;
+
+        // 1. Get schema and verify it has embedding_fields configured
+        let schema = self.get_schema(table)?;
+
+        let embedding_fields = PydanticSchemaParser::extract_embedding_fields(&schema);
+        if embedding_fields.is_empty() {
+            return Err(DatabaseError::SearchError(
+                format!("Schema '{}' does not have embedding_fields configured", table)
+            ));
+        }
+
+        // 2. Determine embedding provider from schema
+        let provider_config = schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("embedding_provider"))
+            .and_then(|p| p.as_str())
+            .unwrap_or("default");
+
+        // 3. Resolve "default" to actual provider from environment
+        let provider_str = if provider_config == "default" {
+            std::env::var("P8_DEFAULT_EMBEDDING")
+                .unwrap_or_else(|_| "openai:text-embedding-3-small".to_string())
+        } else {
+            provider_config.to_string()
+        };
+
+        // 4. Create embedding provider, wrapped if the schema normalizes input
+        let provider = ProviderFactory::create(&provider_str)?;
+        let preprocessor = self.embedding_preprocessor(table)?;
+        if preprocessor.is_noop() {
+            return Ok(provider);
+        }
+        Ok(Box::new(crate::embeddings::PreprocessingEmbedder::new(provider, preprocessor)))
+    }
+
+    /// Resolve the embedding input preprocessing configured on a schema.
+    fn embedding_preprocessor(&self, table: &str) -> Result<crate::embeddings::TextPreprocessor> {
+        use crate::schema::PydanticSchemaParser;
+
+        let schema = self.get_schema(table)?;
+        crate::embeddings::TextPreprocessor::from_steps(
+            &PydanticSchemaParser::extract_embedding_preprocess(&schema),
+        )
+    }
+}
+
+/// Implement EdgeProvider trait for Database.
+impl crate::graph::EdgeProvider for Database {
+    fn get_outgoing(&self, node: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
+        self.get_edges(node, rel_type)
+    }
+
+    fn get_incoming(&self, node: uuid::Uuid, rel_type: Option<&str>) -> Result<Vec<Edge>> {
+        self.get_incoming_edges(node, rel_type)
+    }
+}
+
+/// Extract inline edges from entity data.
+///
+/// Parses the `edges` array from incoming data and converts to InlineEdge objects.
+///
+/// # Arguments
+///
+/// * `data` - Entity data (JSON value)
+///
+/// # Returns
+///
+/// Vector of InlineEdge objects
+///
+/// # Edge Format
+///
+/// Edges in data should be formatted as:
+/// ```json
+/// {
+///   "edges": [
+///     {
+///       "dst": "uuid-string",
+///       "rel_type": "authored",
+///       "properties": {"since": "2024-01-01"},
+///       "created_at": "2024-01-01T00:00:00Z"
+///     }
+///   ]
+/// }
+/// ```
+fn extract_edges_from_data(data: &serde_json::Value) -> Vec<crate::types::InlineEdge> {
+    use crate::types::InlineEdge;
+    use std::collections::HashMap;
+
+    data.get("edges")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|edge_val| {
+                    // Extract edge fields
+                    let dst_str = edge_val.get("dst")?.as_str()?;
+                    let dst = uuid::Uuid::parse_str(dst_str).ok()?;
+                    let rel_type = edge_val.get("rel_type")?.as_str()?.to_string();
+
+                    // Extract properties (optional)
+                    let properties = edge_val.get("properties")
+                        .and_then(|v| v.as_object())
+                        .map(|obj| {
+                            obj.iter()
+                                .map(|(k, v)| (k.clone(), v.clone()))
+                                .collect::<HashMap<String, serde_json::Value>>()
+                        })
+                        .unwrap_or_default();
+
+                    // Extract or generate created_at
+                    let created_at = edge_val.get("created_at")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+
+                    Some(InlineEdge {
+                        dst,
+                        rel_type,
+                        properties,
+                        created_at,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Extract key value from entity data following same priority as generate_uuid.
+///
+/// # Arguments
+///
+/// * `data` - Entity data
+/// * `key_field` - Optional custom key field from schema
+///
+/// # Returns
+///
+/// Key value string if found, None otherwise
+fn extract_key_value(data: &serde_json::Value, key_field: Option<&str>) -> Option<String> {
+    // Priority 1: uri (for resources/documents)
+    if let Some(uri) = data.get("uri").and_then(|v| v.as_str()) {
+        return Some(uri.to_string());
+    }
+
+    // Priority 2: Custom key_field from schema
+    if let Some(field_name) = key_field {
+        if let Some(value) = data.get(field_name) {
+            return Some(value_to_string(value));
+        }
+    }
+
+    // Priority 3: Generic "key" field
+    if let Some(key_value) = data.get("key") {
+        return Some(value_to_string(key_value));
+    }
+
+    // Priority 4: "name" field
+    if let Some(name) = data.get("name").and_then(|v| v.as_str()) {
+        return Some(name.to_string());
+    }
+
+    // Priority 5: No key field (random UUID case)
+    None
+}
+
+/// Field index values for a property.
+///
+/// Arrays are multi-value: one entry per distinct non-null element, so
+/// `tags: ["rust", "db"]` is found by a lookup for either tag. Scalars
+/// give a single entry and null gives none.
+fn index_values(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(items) => {
+            let mut values: Vec<String> = Vec::with_capacity(items.len());
+            for item in items.iter().filter(|v| !v.is_null()) {
+                let value = value_to_string(item);
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+            values
+        }
+        scalar => vec![value_to_string(scalar)],
+    }
+}
+
+/// Content limit configured on a schema (`max_content_chars`), if any.
+fn schema_content_limit(schema: &serde_json::Value) -> Result<Option<crate::schema::ContentLimit>> {
+    use crate::schema::{ContentLimit, ContentOverflow, PydanticSchemaParser};
+
+    let (max_chars, overflow) = PydanticSchemaParser::extract_content_limit(schema);
+    let Some(max_chars) = max_chars else {
+        return Ok(None);
+    };
+    let overflow = overflow
+        .map(|o| ContentOverflow::parse(&o))
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(Some(ContentLimit { max_chars, overflow }))
+}
+
+/// Validate the similarity floor shared by the neighbor-finding APIs.
+fn check_min_similarity(min_similarity: f32) -> Result<()> {
+    if !(0.0..=1.0).contains(&min_similarity) {
+        return Err(DatabaseError::ConfigError(format!(
+            "min_similarity must be in [0, 1], got {}",
+            min_similarity
+        )));
+    }
+    Ok(())
+}
+
+/// Earliest `expires_at` among entities, after which results built from
+/// them are stale.
+fn earliest_expiry<'a>(entities: impl IntoIterator<Item = &'a Entity>) -> Option<chrono::DateTime<chrono::Utc>> {
+    entities
+        .into_iter()
+        .filter_map(|entity| entity.system.expires_at.as_deref())
+        .filter_map(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.with_timezone(&chrono::Utc))
+        .min()
+}
+
+/// Key index entries for a key value.
+///
+/// Case-insensitive schemas also index the lowercased form (if different),
+/// which `get_by_key` scans; the original stays indexed for exact lookups.
+fn key_index_values(key_value: String, case_insensitive: bool) -> Vec<String> {
+    let lowered = key_value.to_lowercase();
+    if case_insensitive && lowered != key_value {
+        vec![key_value, lowered]
+    } else {
+        vec![key_value]
+    }
+}
+
+/// Convert JSON value to string for key indexing.
+///
+/// Numbers use the same canonical form as UUID generation so `1` and `1.0`
+/// index under the same key.
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => crate::types::canonical_number(n),
+        serde_json::Value::Bool(b) => b.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_open_temp() {
+        let db = Database::open_temp().unwrap();
+
+        // Should have builtin schemas
+        assert!(db.has_schema("schemas"));
+        assert!(db.has_schema("documents"));
+        assert!(db.has_schema("resources"));
+    }
+
+    #[test]
+    fn test_list_schemas() {
+        let db = Database::open_temp().unwrap();
+        let schemas = db.list_schemas().unwrap();
+
+        assert_eq!(schemas.len(), 3);
+        assert!(schemas.contains(&"schemas".to_string()));
+        assert!(schemas.contains(&"documents".to_string()));
+        assert!(schemas.contains(&"resources".to_string()));
+    }
+
+    #[test]
+    fn test_register_schema() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "description": "Test article schema",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {
+                "title": {
+                    "type": "string",
+                    "description": "Article title"
+                }
+            },
+            "required": ["title"]
+        });
+
+        db.register_schema("articles", schema).unwrap();
+        assert!(db.has_schema("articles"));
+    }
+
+    #[test]
+    fn test_get_by_key_with_name_field() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema without explicit key_field
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entity with name field
+        let data = serde_json::json!({"name": "Alice", "age": 30});
+        let id = db.insert("tenant1", "person", data).unwrap();
+
+        // Lookup by name
+        let entity = db.get_by_key("tenant1", "person", "Alice").unwrap();
+        assert!(entity.is_some());
+
+        let entity = entity.unwrap();
+        assert_eq!(entity.system.id, id);
+        assert_eq!(entity.properties.get("name").unwrap(), "Alice");
+    }
+
+    #[test]
+    fn test_get_by_key_with_custom_key_field() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema with custom key_field
+        let schema = serde_json::json!({
+            "title": "User",
+            "version": "1.0.0",
+            "short_name": "user",
+            "json_schema_extra": {
+                "key_field": "email"
+            },
+            "properties": {
+                "email": {"type": "string"},
+                "username": {"type": "string"}
+            },
+            "required": ["email", "username"]
+        });
+
+        db.register_schema("user", schema).unwrap();
+
+        // Insert entity
+        let data = serde_json::json!({
+            "email": "alice@example.com",
+            "username": "alice"
+        });
+        let id = db.insert("tenant1", "user", data).unwrap();
+
+        // Lookup by email
+        let entity = db.get_by_key("tenant1", "user", "alice@example.com").unwrap();
+        assert!(entity.is_some());
+
+        let entity = entity.unwrap();
+        assert_eq!(entity.system.id, id);
+        assert_eq!(entity.properties.get("email").unwrap(), "alice@example.com");
+    }
+
+    #[test]
+    fn test_open_with_check_repairs_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        let orphan_id = uuid::Uuid::new_v4();
+
+        {
+            let db = Database::open(dir.path()).unwrap();
+            db.register_schema("person", serde_json::json!({
+                "title": "Person",
+                "version": "1.0.0",
+                "short_name": "person",
+                "json_schema_extra": {"indexed_fields": ["city"]},
+                "properties": {"name": {"type": "string"}, "city": {"type": "string"}},
+                "required": ["name"]
+            })).unwrap();
+            let alice = db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "city": "Paris"})).unwrap();
+
+            // Simulate a crash between writes: an index entry without its
+            // entity, and an entity without its field index entry
+            let orphan_key = crate::storage::keys::encode_key_index("tenant1", "Ghost", orphan_id);
+            db.storage.put(crate::storage::column_families::CF_KEY_INDEX, &orphan_key, br#"{"type":"person"}"#).unwrap();
+            let city_key = crate::storage::keys::encode_index_key("tenant1", "city", "Paris", alice);
+            db.storage.delete(crate::storage::column_families::CF_INDEXES, &city_key).unwrap();
+
+            let report = db.check_consistency(false).unwrap();
+            assert_eq!(report.orphaned_key_entries, 1);
+            assert_eq!(report.missing_index_entries, 1);
+            assert!(!report.repaired);
+        }
+
+        let (db, report) = Database::open_with_check(dir.path(), true).unwrap();
+        // Alice and the persisted person schema
+        assert_eq!(report.entities, 2);
+        assert_eq!(report.orphaned_key_entries, 1);
+        assert_eq!(report.missing_index_entries, 1);
+        assert!(report.repaired);
+
+        assert!(db.lookup_global("tenant1", "Ghost").unwrap().is_empty());
+        let orphan_key = crate::storage::keys::encode_key_index("tenant1", "Ghost", orphan_id);
+        assert!(db.storage.get(crate::storage::column_families::CF_KEY_INDEX, &orphan_key).unwrap().is_none());
+        assert_eq!(db.query_by_index("tenant1", "person", "city", &serde_json::json!("Paris")).unwrap().len(), 1);
+
+        assert!(db.check_consistency(false).unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_touch() {
+        use crate::replication::WalOperation;
+
+        let path = std::env::temp_dir().join(format!("rem-db-test-{}", uuid::Uuid::new_v4()));
+        let db = Database::open_with_replication(&path, ReplicationMode::Primary).unwrap();
+        db.register_schema("sessions", serde_json::json!({
+            "title": "Session",
+            "version": "1.0.0",
+            "short_name": "sessions",
+            "properties": {"user": {"type": "string"}, "visits": {"type": "integer"}},
+            "required": ["user"]
+        })).unwrap();
+
+        let id = db.insert_with_ttl("tenant1", "sessions", serde_json::json!({"user": "alice", "visits": 3}), std::time::Duration::from_secs(60)).unwrap();
+        let before = db.get("tenant1", id).unwrap().unwrap();
+        let mut changes = db.subscribe("tenant1", Some("sessions"));
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let touched_at = db.touch("tenant1", id, None).unwrap();
+        let after = db.get("tenant1", id).unwrap().unwrap();
+
+        let parse = |ts: &str| chrono::DateTime::parse_from_rfc3339(ts).unwrap();
+        assert_eq!(after.system.modified_at, touched_at);
+        assert!(parse(&after.system.modified_at) > parse(&before.system.modified_at));
+        assert_eq!(after.properties, before.properties);
+        assert_eq!(after.system.created_at, before.system.created_at);
+        assert_eq!(after.system.expires_at, before.system.expires_at);
+
+        // Resetting the TTL pushes expiry out
+        db.touch("tenant1", id, Some(std::time::Duration::from_secs(3600))).unwrap();
+        let extended = db.get("tenant1", id).unwrap().unwrap();
+        assert!(parse(extended.system.expires_at.as_deref().unwrap()) > parse(before.system.expires_at.as_deref().unwrap()));
+
+        // Subscribers see both touches
+        for _ in 0..2 {
+            let event = changes.try_recv().unwrap();
+            assert_eq!(event.op, ChangeOp::Update);
+            assert_eq!(event.entity_id, id);
+        }
+        assert!(changes.try_recv().is_err());
+
+        // The new system fields are replicated
+        let wal = db.wal.as_ref().unwrap().read().unwrap().get_entries_after(0, usize::MAX).unwrap();
+        match &wal.last().unwrap().op {
+            WalOperation::Touch { entity_id, modified_at, expires_at, .. } => {
+                assert_eq!(*entity_id, id.to_string());
+                assert_eq!(*modified_at, extended.system.modified_at);
+                assert_eq!(*expires_at, extended.system.expires_at);
+            }
+            other => panic!("expected Touch, got {:?}", other),
+        }
+
+        assert!(matches!(db.touch("tenant1", uuid::Uuid::new_v4(), None), Err(DatabaseError::EntityNotFound(_))));
+
+        // Expired (but not yet compacted) entities are missing, not resurrected
+        let expired = db.insert_with_ttl("tenant1", "sessions", serde_json::json!({"user": "bob"}), std::time::Duration::from_millis(1)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(matches!(db.touch("tenant1", expired, None), Err(DatabaseError::EntityNotFound(_))));
+        assert!(matches!(db.touch("tenant1", expired, Some(std::time::Duration::from_secs(3600))), Err(DatabaseError::EntityNotFound(_))));
+        assert!(db.get("tenant1", expired).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_max_content_chars() {
+        let db = Database::open_temp().unwrap();
+
+        for (name, overflow) in [("strict", "reject"), ("clipped", "truncate")] {
+            db.register_schema(name, serde_json::json!({
+                "title": name,
+                "version": "1.0.0",
+                "short_name": name,
+                "json_schema_extra": {"max_content_chars": 10, "content_overflow": overflow},
+                "properties": {"name": {"type": "string"}, "content": {"type": "string"}},
+                "required": ["name"]
+            })).unwrap();
+        }
+        let long = "x".repeat(25);
+
+        let err = db.insert("tenant1", "strict", serde_json::json!({"name": "a", "content": long})).unwrap_err();
+        assert!(matches!(err, DatabaseError::ValidationError(_)));
+        assert!(err.to_string().contains("'content'") && err.to_string().contains("10"), "{}", err);
+        assert_eq!(db.count("tenant1", "strict", false).unwrap(), 0);
+
+        let id = db.insert("tenant1", "strict", serde_json::json!({"name": "a", "content": "short"})).unwrap();
+        assert!(db.update("tenant1", id, serde_json::json!({"content": long})).is_err());
+        assert_eq!(db.get("tenant1", id).unwrap().unwrap().properties["content"], "short");
+
+        let id = db.insert("tenant1", "clipped", serde_json::json!({"name": "a", "content": long})).unwrap();
+        assert_eq!(db.get("tenant1", id).unwrap().unwrap().properties["content"], "x".repeat(10));
+
+        let updated = db.update("tenant1", id, serde_json::json!({"content": "y".repeat(25)})).unwrap();
+        assert_eq!(updated.properties["content"], "y".repeat(10));
+    }
+
+    #[test]
+    fn test_batch_insert_commits_in_chunks() {
+        let db = Database::open_temp().unwrap().with_write_batch_size(10_000);
+
+        db.register_schema("item", serde_json::json!({
+            "title": "Item",
+            "version": "1.0.0",
+            "short_name": "item",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        })).unwrap();
+
+        let rows: Vec<serde_json::Value> = (0..25_000)
+            .map(|i| serde_json::json!({"name": format!("item-{}", i)}))
+            .collect();
+
+        let mut commits = Vec::new();
+        let ids = db
+            .batch_insert_with_progress("tenant1", "item", rows, |written| commits.push(written))
+            .unwrap();
+
+        assert_eq!(commits, vec![10_000, 20_000, 25_000]);
+        assert_eq!(ids.len(), 25_000);
+        assert_eq!(db.count("tenant1", "item", false).unwrap(), 25_000);
+        assert!(db.get_by_key("tenant1", "item", "item-24999").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_get_by_key_case_insensitive() {
+        let db = Database::open_temp().unwrap();
+
+        for (name, case_insensitive) in [("folded", true), ("exact", false)] {
+            db.register_schema(name, serde_json::json!({
+                "title": name,
+                "version": "1.0.0",
+                "short_name": name,
+                "json_schema_extra": {"key_case_insensitive": case_insensitive},
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            })).unwrap();
+        }
+
+        let folded_id = db.insert("tenant1", "folded", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "exact", serde_json::json!({"name": "Alice"})).unwrap();
+
+        for key in ["alice", "ALICE", "Alice"] {
+            let entity = db.get_by_key("tenant1", "folded", key).unwrap().unwrap();
+            assert_eq!(entity.system.id, folded_id);
+            // Stored value and deterministic ID keep the original casing
+            assert_eq!(entity.properties["name"], "Alice");
+        }
+        assert_eq!(db.get_by_key_batch("tenant1", "folded", &["aLiCe"]).unwrap()[0].as_ref().unwrap().system.id, folded_id);
+
+        assert!(db.get_by_key("tenant1", "exact", "alice").unwrap().is_none());
+        assert!(db.get_by_key("tenant1", "exact", "Alice").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_get_by_key_not_found() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Lookup non-existent key
+        let entity = db.get_by_key("tenant1", "person", "NonExistent").unwrap();
+        assert!(entity.is_none());
+    }
+
+    #[test]
+    fn test_get_by_key_tenant_isolation() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert same name in different tenants
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant2", "person", serde_json::json!({"name": "Alice"})).unwrap();
+
+        // Each tenant should only see their own entity
+        let entity1 = db.get_by_key("tenant1", "person", "Alice").unwrap();
+        let entity2 = db.get_by_key("tenant2", "person", "Alice").unwrap();
+
+        assert!(entity1.is_some());
+        assert!(entity2.is_some());
+
+        // UUIDs are the same (deterministic based on entity data alone)
+        // but they are stored at different keys: entity:tenant1:{uuid} vs entity:tenant2:{uuid}
+        assert_eq!(entity1.as_ref().unwrap().system.id, entity2.as_ref().unwrap().system.id);
+
+        // Verify tenant1 cannot see tenant2's entity by direct get
+        let id = entity1.unwrap().system.id;
+        let from_tenant1 = db.get("tenant1", id).unwrap();
+        let from_tenant2 = db.get("tenant2", id).unwrap();
+
+        assert!(from_tenant1.is_some());
+        assert!(from_tenant2.is_some()); // Both exist because same UUID stored under different tenant keys
+    }
+
+    #[test]
+    fn test_update_partial() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"},
+                "status": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entity
+        let data = serde_json::json!({"name": "Alice", "age": 30, "status": "active"});
+        let id = db.insert("tenant1", "person", data).unwrap();
+
+        // Partial update - only update age
+        let updates = serde_json::json!({"age": 31});
+        let updated = db.update("tenant1", id, updates).unwrap();
+
+        assert_eq!(updated.properties.get("name").unwrap(), "Alice");
+        assert_eq!(updated.properties.get("age").unwrap(), 31);
+        assert_eq!(updated.properties.get("status").unwrap(), "active");
+    }
+
+    #[test]
+    fn test_update_validation() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema with required field
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"}
+            },
+            "required": ["name", "age"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert valid entity
+        let data = serde_json::json!({"name": "Alice", "age": 30});
+        let id = db.insert("tenant1", "person", data).unwrap();
+
+        // Try to update with invalid data (age as string)
+        let updates = serde_json::json!({"age": "thirty"});
+        let result = db.update("tenant1", id, updates);
+
+        assert!(result.is_err()); // Should fail validation
+    }
+
+    #[test]
+    fn test_update_not_found() {
+        let db = Database::open_temp().unwrap();
+
+        let updates = serde_json::json!({"age": 31});
+        let result = db.update("tenant1", uuid::Uuid::new_v4(), updates);
+
+        assert!(result.is_err()); // Entity not found
+    }
+
+    #[test]
+    fn test_soft_delete() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entity
+        let data = serde_json::json!({"name": "Alice"});
+        let id = db.insert("tenant1", "person", data).unwrap();
+
+        // Soft delete
+        let deleted = db.delete("tenant1", id).unwrap();
+
+        assert!(deleted.is_deleted());
+        assert!(deleted.system.deleted_at.is_some());
+
+        // Entity still exists in storage (soft deleted)
+        let entity = db.get("tenant1", id).unwrap();
+        assert!(entity.is_some());
+        assert!(entity.unwrap().is_deleted());
+    }
+
+    #[tokio::test]
+    async fn test_list_deleted_and_restore() {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
+            "version": "1.0.0",
+            "short_name": "point",
+            "properties": {
+                "name": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["name"],
+            "json_schema_extra": {"key_field": "name", "indexed_fields": ["name"]}
+        })).unwrap();
+
+        let mut ids = std::collections::HashMap::new();
+        for (name, angle) in [("a", 0.0_f64), ("b", 0.5), ("c", 1.0)] {
+            let id = db.insert("tenant1", "point", serde_json::json!({
+                "name": name,
+                "embedding": [angle.cos(), angle.sin()]
+            })).unwrap();
+            ids.insert(name, id);
+        }
+
+        db.delete("tenant1", ids["a"]).unwrap();
+        db.delete("tenant1", ids["b"]).unwrap();
+
+        let mut deleted: Vec<uuid::Uuid> = db.list_deleted("tenant1", "point").unwrap()
+            .iter()
+            .map(|e| e.system.id)
+            .collect();
+        deleted.sort();
+        let mut expected = vec![ids["a"], ids["b"]];
+        expected.sort();
+        assert_eq!(deleted, expected);
+
+        let restored = db.restore("tenant1", ids["a"]).unwrap();
+        assert!(!restored.is_deleted());
+        assert_eq!(db.list_deleted("tenant1", "point").unwrap().len(), 1);
+
+        // Back in lists, key lookups, index queries, SQL, and search
+        assert_eq!(db.list("tenant1", "point", false, None, None).unwrap().len(), 2);
+        assert_eq!(db.get_by_key("tenant1", "point", "a").unwrap().unwrap().system.id, ids["a"]);
+        assert_eq!(db.query_by_index("tenant1", "point", "name", &serde_json::json!("a")).unwrap().len(), 1);
+        let rows = db.query_sql("tenant1", "SELECT name FROM point WHERE name = 'a'").unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 1);
+
+        let results = db.search_by_vector("tenant1", "point", &[1.0, 0.0], 1, None, crate::index::SearchMode::default())
+            .await
+            .unwrap();
+        assert_eq!(results[0].entity.system.id, ids["a"]);
+
+        assert!(matches!(db.restore("tenant1", ids["c"]), Err(DatabaseError::ValidationError(_))));
+        assert!(matches!(
+            db.restore("tenant1", uuid::Uuid::new_v4()),
+            Err(DatabaseError::EntityNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_hard_delete() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entity
+        let data = serde_json::json!({"name": "Alice"});
+        let id = db.insert("tenant1", "person", data).unwrap();
+
+        // Hard delete
+        db.hard_delete("tenant1", id).unwrap();
+
+        // Entity no longer exists
+        let entity = db.get("tenant1", id).unwrap();
+        assert!(entity.is_none());
+    }
+
+    #[test]
+    fn test_hard_delete_removes_key_index() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema with key_field
+        let schema = serde_json::json!({
+            "title": "User",
+            "version": "1.0.0",
+            "short_name": "user",
+            "json_schema_extra": {
+                "key_field": "email"
+            },
+            "properties": {
+                "email": {"type": "string"},
+                "name": {"type": "string"}
+            },
+            "required": ["email", "name"]
+        });
+
+        db.register_schema("user", schema).unwrap();
+
+        // Insert entity
+        let data = serde_json::json!({"email": "alice@example.com", "name": "Alice"});
+        let id = db.insert("tenant1", "user", data).unwrap();
+
+        // Verify key lookup works
+        let entity = db.get_by_key("tenant1", "user", "alice@example.com").unwrap();
+        assert!(entity.is_some());
+
+        // Hard delete
+        db.hard_delete("tenant1", id).unwrap();
+
+        // Key lookup should return None
+        let entity = db.get_by_key("tenant1", "user", "alice@example.com").unwrap();
+        assert!(entity.is_none());
+    }
+
+    #[test]
+    fn test_delete_policy() {
+        use crate::replication::WalOperation;
+        use crate::schema::DeletePolicy;
+
+        let path = std::env::temp_dir().join(format!("rem-db-test-{}", uuid::Uuid::new_v4()));
+        let db = Database::open_with_replication(&path, ReplicationMode::Primary).unwrap();
+
+        let schema = |name: &str, policy: Option<&str>| {
+            let mut schema = serde_json::json!({
+                "title": name,
+                "version": "1.0.0",
+                "short_name": name,
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            });
+            if let Some(policy) = policy {
+                schema["json_schema_extra"] = serde_json::json!({"delete_policy": policy});
+            }
+            schema
+        };
+        db.register_schema("audit", schema("audit", Some("soft_only"))).unwrap();
+        db.register_schema("cache", schema("cache", Some("HardOnly"))).unwrap();
+        db.register_schema("note", schema("note", None)).unwrap();
+        assert_eq!(db.schema_info("audit").unwrap().delete_policy, DeletePolicy::SoftOnly);
+        assert_eq!(db.schema_info("note").unwrap().delete_policy, DeletePolicy::Either);
+
+        // SoftOnly: soft delete keeps the entity, hard delete is refused
+        let id = db.insert("tenant1", "audit", serde_json::json!({"name": "login"})).unwrap();
+        match db.hard_delete("tenant1", id) {
+            Err(DatabaseError::DeletePolicyViolation { table, policy, .. }) => {
+                assert_eq!(table, "audit");
+                assert_eq!(policy, "soft_only");
+            }
+            other => panic!("expected DeletePolicyViolation, got {:?}", other),
+        }
+        assert!(!db.get("tenant1", id).unwrap().unwrap().is_deleted());
+        assert!(db.delete("tenant1", id).unwrap().is_deleted());
+        assert!(db.get("tenant1", id).unwrap().unwrap().is_deleted());
+        assert!(db.hard_delete("tenant1", id).is_err());
+
+        // HardOnly: soft delete purges the entity and its key index entry
+        let id = db.insert("tenant1", "cache", serde_json::json!({"name": "page"})).unwrap();
+        assert!(db.delete("tenant1", id).unwrap().is_deleted());
+        assert!(db.get("tenant1", id).unwrap().is_none());
+        assert!(db.get_by_key("tenant1", "cache", "page").unwrap().is_none());
+
+        // ...and is replicated like any delete
+        let wal = db.wal.as_ref().unwrap().read().unwrap().get_entries_after(0, usize::MAX).unwrap();
+        assert!(matches!(
+            &wal.last().unwrap().op,
+            WalOperation::Delete { entity_id, .. } if *entity_id == id.to_string()
+        ));
+
+        // Either: both operations behave as requested
+        let soft = db.insert("tenant1", "note", serde_json::json!({"name": "a"})).unwrap();
+        let hard = db.insert("tenant1", "note", serde_json::json!({"name": "b"})).unwrap();
+        db.delete("tenant1", soft).unwrap();
+        db.hard_delete("tenant1", hard).unwrap();
+        assert!(db.get("tenant1", soft).unwrap().unwrap().is_deleted());
+        assert!(db.get("tenant1", hard).unwrap().is_none());
+
+        // Unknown policy is rejected at registration
+        assert!(matches!(
+            db.register_schema("bad", schema("bad", Some("sometimes"))),
+            Err(DatabaseError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_subscribe_change_events() {
+        use crate::changes::ChangeOp;
+
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {"title": {"type": "string"}},
+            "required": ["title"]
+        });
+        db.register_schema("articles", schema).unwrap();
+
+        let mut articles = db.subscribe("tenant1", Some("articles"));
+        let mut all = db.subscribe("tenant1", None);
+        let mut other = db.subscribe("tenant1", Some("people"));
+
+        let id = db.insert("tenant1", "articles", serde_json::json!({"title": "Hello"})).unwrap();
+        db.delete("tenant1", id).unwrap();
+
+        for rx in [&mut articles, &mut all] {
+            let inserted = rx.try_recv().unwrap();
+            assert_eq!(inserted.op, ChangeOp::Insert);
+            assert_eq!(inserted.table, "articles");
+            assert_eq!(inserted.entity_id, id);
+
+            let deleted = rx.try_recv().unwrap();
+            assert_eq!(deleted.op, ChangeOp::Delete);
+            assert_eq!(deleted.entity_id, id);
+
+            assert!(rx.try_recv().is_err());
+        }
+        assert!(other.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_concurrent_update_with_no_lost_updates() {
+        let db = Arc::new(Database::open_temp().unwrap().with_write_retries(1000));
+
+        let schema = serde_json::json!({
+            "title": "Counter",
+            "version": "1.0.0",
+            "short_name": "counters",
+            "properties": {
+                "name": {"type": "string"},
+                "count": {"type": "integer"}
+            },
+            "required": ["name"]
+        });
+        db.register_schema("counters", schema).unwrap();
+        let id = db.insert("tenant1", "counters", serde_json::json!({"name": "hits", "count": 0})).unwrap();
+
+        let threads = 8;
+        let increments = 25;
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..increments {
+                        db.update_with("tenant1", id, |entity| {
+                            let count = entity.properties["count"].as_i64().unwrap();
+                            entity.properties["count"] = serde_json::json!(count + 1);
+                            Ok(())
+                        }).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let entity = db.get("tenant1", id).unwrap().unwrap();
+        assert_eq!(entity.properties["count"], serde_json::json!(threads * increments));
+    }
+
+    #[test]
+    fn test_tag_where_and_update_with_no_lost_updates() {
+        let db = Arc::new(Database::open_temp().unwrap().with_write_retries(1000));
+
+        let schema = serde_json::json!({
+            "title": "Counter",
+            "version": "1.0.0",
+            "short_name": "counters",
+            "properties": {
+                "name": {"type": "string"},
+                "count": {"type": "integer"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["name"],
+            "json_schema_extra": {"indexed_fields": ["tags"]}
+        });
+        db.register_schema("counters", schema).unwrap();
+        let id = db.insert("tenant1", "counters", serde_json::json!({"name": "hits", "count": 0})).unwrap();
+
+        let counter = {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                for _ in 0..50 {
+                    db.update_with("tenant1", id, |entity| {
+                        let count = entity.properties["count"].as_i64().unwrap();
+                        entity.properties["count"] = serde_json::json!(count + 1);
+                        Ok(())
+                    }).unwrap();
+                }
+            })
+        };
+        let tagger = {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                let predicate = crate::query::Predicate::eq("name", serde_json::json!("hits"));
+                for i in 0..50 {
+                    db.tag_where("tenant1", "counters", &predicate, vec![format!("t{}", i)], "tags").unwrap();
+                }
+            })
+        };
+        counter.join().unwrap();
+        tagger.join().unwrap();
+
+        let entity = db.get("tenant1", id).unwrap().unwrap();
+        assert_eq!(entity.properties["count"], serde_json::json!(50));
+        assert_eq!(entity.properties["tags"].as_array().unwrap().len(), 50);
+        assert_eq!(db.query_by_index("tenant1", "counters", "tags", &serde_json::json!("t49")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_update_with_conflict_retry() {
+        let schema = serde_json::json!({
+            "title": "Counter",
+            "version": "1.0.0",
+            "short_name": "counters",
+            "properties": {
+                "name": {"type": "string"},
+                "count": {"type": "integer"}
+            },
+            "required": ["name"]
+        });
+
+        for (retries, expect_conflict) in [(0, true), (1, false)] {
+            let db = Database::open_temp().unwrap().with_write_retries(retries);
+            db.register_schema("counters", schema.clone()).unwrap();
+            let id = db.insert("tenant1", "counters", serde_json::json!({"name": "hits", "count": 0})).unwrap();
+
+            // First attempt races with a concurrent write; later attempts don't
+            let mut calls = 0;
+            let result = db.update_with("tenant1", id, |entity| {
+                calls += 1;
+                if calls == 1 {
+                    db.update("tenant1", id, serde_json::json!({"count": 10})).unwrap();
+                }
+                let count = entity.properties["count"].as_i64().unwrap();
+                entity.properties["count"] = serde_json::json!(count + 1);
+                Ok(())
+            });
+
+            let count = db.get("tenant1", id).unwrap().unwrap().properties["count"].clone();
+            if expect_conflict {
+                assert!(matches!(result, Err(DatabaseError::WriteConflict { attempts: 1, .. })));
+                assert_eq!(count, serde_json::json!(10));
+            } else {
+                assert_eq!(result.unwrap().properties["count"], serde_json::json!(11));
+                assert_eq!(count, serde_json::json!(11));
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_rust_types() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {
+                "title": {"type": "string"},
+                "views": {"type": "integer"}
+            },
+            "required": ["title"]
+        });
+        db.register_schema("articles", schema).unwrap();
+
+        let out = std::env::temp_dir().join(format!("rem-types-{}.rs", uuid::Uuid::new_v4()));
+        let generated = db.generate_rust_types(&out).unwrap();
+        assert_eq!(generated, vec!["Article".to_string()]);
+
+        let code = std::fs::read_to_string(&out).unwrap();
+        std::fs::remove_file(&out).unwrap();
+
+        // Built-in system schemas are skipped
+        let file = syn::parse_file(&code).unwrap();
+        let structs: Vec<String> = file.items.iter()
+            .filter_map(|item| match item {
+                syn::Item::Struct(s) => Some(s.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(structs, vec!["Article".to_string()]);
+        assert!(code.contains("pub title: String,"));
+        assert!(code.contains("pub views: Option<i64>,"));
+    }
+
+    #[test]
+    fn test_tag_where() {
+        use crate::query::Predicate;
+
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Resource",
+            "version": "1.0.0",
+            "short_name": "resources",
+            "properties": {
+                "name": {"type": "string"},
+                "category": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["name"]
+        });
+        db.register_schema("resources", schema).unwrap();
+
+        let a = db.insert("tenant1", "resources", serde_json::json!({
+            "name": "a", "category": "support", "tags": ["urgent"]
+        })).unwrap();
+        let b = db.insert("tenant1", "resources", serde_json::json!({
+            "name": "b", "category": "support"
+        })).unwrap();
+        let c = db.insert("tenant1", "resources", serde_json::json!({
+            "name": "c", "category": "sales", "tags": ["lead"]
+        })).unwrap();
+
+        let predicate = Predicate::eq("category", serde_json::json!("support"));
+        let tags = vec!["urgent".to_string(), "triaged".to_string(), "triaged".to_string()];
+        let updated = db.tag_where("tenant1", "resources", &predicate, tags.clone(), "tags").unwrap();
+        assert_eq!(updated, 2);
+
+        let tags_of = |id| db.get("tenant1", id).unwrap().unwrap().properties["tags"].clone();
+        assert_eq!(tags_of(a), serde_json::json!(["urgent", "triaged"]));
+        assert_eq!(tags_of(b), serde_json::json!(["urgent", "triaged"]));
+        assert_eq!(tags_of(c), serde_json::json!(["lead"]));
+
+        // Re-running changes nothing
+        assert_eq!(db.tag_where("tenant1", "resources", &predicate, tags, "tags").unwrap(), 0);
+
+        // Non-array field is rejected
+        assert!(matches!(
+            db.tag_where("tenant1", "resources", &predicate, vec!["x".to_string()], "category"),
+            Err(DatabaseError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_entities() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert multiple entities
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+
+        // List all persons
+        let entities = db.list("tenant1", "person", false, None, None).unwrap();
+        assert_eq!(entities.len(), 3);
+
+        let names: Vec<&str> = entities
+            .iter()
+            .map(|e| e.properties.get("name").unwrap().as_str().unwrap())
+            .collect();
+
+        assert!(names.contains(&"Alice"));
+        assert!(names.contains(&"Bob"));
+        assert!(names.contains(&"Charlie"));
+    }
+
+    #[test]
+    fn test_list_with_limit() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert multiple entities
+        for i in 0..10 {
+            db.insert("tenant1", "person", serde_json::json!({"name": format!("Person {}", i)})).unwrap();
+        }
+
+        // List with limit
+        let entities = db.list("tenant1", "person", false, Some(5), None).unwrap();
+        assert_eq!(entities.len(), 5);
+    }
+
+    #[test]
+    fn test_blob_round_trip() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Resource",
+            "version": "1.0.0",
+            "short_name": "resources",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+        db.register_schema("resources", schema).unwrap();
+
+        let id = db.insert("tenant1", "resources", serde_json::json!({"name": "logo.png"})).unwrap();
+        assert!(db.get_blob("tenant1", id).unwrap().is_none());
+
+        let bytes: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        db.put_blob("tenant1", id, &bytes, "image/png").unwrap();
+
+        let blob = db.get_blob("tenant1", id).unwrap().unwrap();
+        assert_eq!(blob.data, bytes);
+        assert_eq!(blob.content_type, "image/png");
+
+        // Entity JSON is untouched and the blob is tenant-scoped
+        assert_eq!(db.get("tenant1", id).unwrap().unwrap().properties, serde_json::json!({"name": "logo.png"}));
+        assert!(db.get_blob("tenant2", id).unwrap().is_none());
+
+        // Blob requires an existing entity
+        assert!(matches!(
+            db.put_blob("tenant1", uuid::Uuid::new_v4(), &bytes, "image/png"),
+            Err(DatabaseError::EntityNotFound(_))
+        ));
+
+        db.hard_delete("tenant1", id).unwrap();
+        assert!(db.get_blob("tenant1", id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunked_blob() {
+        use std::io::Read;
+
+        let db = Database::open_temp().unwrap().with_blob_chunk_size(1024 * 1024);
+
+        let schema = serde_json::json!({
+            "title": "Resource",
+            "version": "1.0.0",
+            "short_name": "resources",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+        db.register_schema("resources", schema).unwrap();
+        let id = db.insert("tenant1", "resources", serde_json::json!({"name": "talk.mp3"})).unwrap();
+
+        let bytes: Vec<u8> = (0..10 * 1024 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+        let manifest = db.put_blob("tenant1", id, &bytes, "audio/mpeg").unwrap();
+        assert_eq!(manifest.chunk_count, 10);
+        assert_eq!(manifest.total_size, bytes.len() as u64);
+        assert_eq!(db.blob_manifest("tenant1", id).unwrap(), Some(manifest));
+
+        let mut reader = db.get_blob_reader("tenant1", id).unwrap().unwrap();
+        let mut streamed = Vec::new();
+        reader.read_to_end(&mut streamed).unwrap();
+        assert!(streamed == bytes);
+
+        // Hard delete removes every chunk
+        db.hard_delete("tenant1", id).unwrap();
+        let storage = db.storage();
+        for idx in 0..10 {
+            let key = crate::storage::keys::encode_blob_chunk_key("tenant1", id, idx);
+            assert!(storage.get(crate::storage::column_families::CF_BLOBS, &key).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_list_ordering() {
+        use crate::query::SortOrder;
+
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
+            "required": ["name"]
+        });
+        db.register_schema("person", schema).unwrap();
+
+        let ages = [42, 7, 19, 88, 33, 19];
+        for (i, age) in ages.iter().enumerate() {
+            db.insert("tenant1", "person", serde_json::json!({"name": format!("Person {}", i), "age": age})).unwrap();
+        }
+
+        // Unsorted: key order (ascending ID string), identical across calls
+        let ids = |entities: &[Entity]| entities.iter().map(|e| e.system.id.to_string()).collect::<Vec<_>>();
+        let first = ids(&db.list("tenant1", "person", false, None, None).unwrap());
+        let second = ids(&db.list("tenant1", "person", false, None, None).unwrap());
+        assert_eq!(first, second);
+        let mut by_key = first.clone();
+        by_key.sort();
+        assert_eq!(first, by_key);
+
+        // Sorted by property, limit applied after sorting
+        let age_of = |entities: Vec<Entity>| entities.iter().map(|e| e.properties["age"].as_i64().unwrap()).collect::<Vec<_>>();
+        let asc = db.list("tenant1", "person", false, Some(3), Some(("age", SortOrder::Asc))).unwrap();
+        assert_eq!(age_of(asc), vec![7, 19, 19]);
+        let desc = db.list("tenant1", "person", false, None, Some(("age", SortOrder::Desc))).unwrap();
+        assert_eq!(age_of(desc), vec![88, 42, 33, 19, 19, 7]);
+
+        // Sorted by system field
+        let created = db.list("tenant1", "person", false, None, Some(("created_at", SortOrder::Asc))).unwrap();
+        assert!(created.windows(2).all(|w| w[0].system.created_at <= w[1].system.created_at));
+    }
+
+    #[test]
+    fn test_list_excludes_deleted() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities
+        let id1 = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Soft delete one entity
+        db.delete("tenant1", id1).unwrap();
+
+        // List without deleted entities
+        let entities = db.list("tenant1", "person", false, None, None).unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].properties.get("name").unwrap(), "Bob");
+
+        // List with deleted entities
+        let all_entities = db.list("tenant1", "person", true, None, None).unwrap();
+        assert_eq!(all_entities.len(), 2);
+    }
+
+    #[test]
+    fn test_list_tenant_isolation() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities in different tenants
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        db.insert("tenant2", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+
+        // List for tenant1
+        let entities1 = db.list("tenant1", "person", false, None, None).unwrap();
+        assert_eq!(entities1.len(), 2);
+
+        // List for tenant2
+        let entities2 = db.list("tenant2", "person", false, None, None).unwrap();
+        assert_eq!(entities2.len(), 1);
+    }
+
+    #[test]
+    fn test_list_filters_by_table() {
+        let db = Database::open_temp().unwrap();
+
+        // Register multiple schemas
+        let person_schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+
+        let project_schema = serde_json::json!({
+            "title": "Project",
+            "version": "1.0.0",
+            "short_name": "project",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+
+        db.register_schema("person", person_schema).unwrap();
+        db.register_schema("project", project_schema).unwrap();
+
+        // Insert entities in different tables
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        db.insert("tenant1", "project", serde_json::json!({"name": "Project X"})).unwrap();
+
+        // List only persons
+        let persons = db.list("tenant1", "person", false, None, None).unwrap();
+        assert_eq!(persons.len(), 2);
+
+        // List only projects
+        let projects = db.list("tenant1", "project", false, None, None).unwrap();
+        assert_eq!(projects.len(), 1);
+    }
+
+    #[test]
+    fn test_batch_insert() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Batch insert
+        let people = vec![
+            serde_json::json!({"name": "Alice", "age": 30}),
+            serde_json::json!({"name": "Bob", "age": 25}),
+            serde_json::json!({"name": "Charlie", "age": 35}),
+        ];
+
+        let ids = db.batch_insert("tenant1", "person", people).unwrap();
+        assert_eq!(ids.len(), 3);
+
+        // Verify all entities were inserted
+        let entities = db.list("tenant1", "person", false, None, None).unwrap();
+        assert_eq!(entities.len(), 3);
+
+        // Verify IDs match
+        for id in &ids {
+            assert!(db.exists("tenant1", *id).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_preflight_reports_invalid_rows() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"}
+            },
+            "required": ["name", "age"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        let rows = vec![
+            serde_json::json!({"name": "Alice", "age": 30}),
+            serde_json::json!({"name": "Bob"}),                 // Missing "age"
+            serde_json::json!({"name": "Charlie", "age": 35}),
+            serde_json::json!({"name": "Dana", "age": "old"}),  // Wrong type
+        ];
+
+        let report = db.preflight("person", &rows).unwrap();
+        assert_eq!(report.valid, 2);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        // Nothing is written
+        assert!(db.list("tenant1", "person", false, None, None).unwrap().is_empty());
+
+        assert!(db.preflight("missing", &rows).is_err());
+    }
+
+    #[test]
+    fn test_batch_insert_with_mode() {
+        use crate::schema::BatchErrorMode;
+
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"}
+            },
+            "required": ["name", "age"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        let rows = vec![
+            serde_json::json!({"name": "Alice", "age": 30}),
+            serde_json::json!({"name": "Bob"}),  // Missing "age"
+            serde_json::json!({"name": "Charlie", "age": 35}),
+        ];
+
+        // Fail fast writes nothing
+        assert!(db.batch_insert_with_mode("tenant1", "person", rows.clone(), BatchErrorMode::FailFast).is_err());
+        assert!(db.list("tenant1", "person", false, None, None).unwrap().is_empty());
+
+        // Skip invalid writes the valid rows and reports the skipped one
+        let report = db.batch_insert_with_mode("tenant1", "person", rows.clone(), BatchErrorMode::SkipInvalid).unwrap();
+        assert_eq!(report.ids.len(), 2);
+        assert_eq!(report.skipped, vec![1]);
+        assert!(report.errors.is_empty());
+        assert_eq!(db.list("tenant1", "person", false, None, None).unwrap().len(), 2);
+
+        // Collect errors also records why
+        let report = db.batch_insert_with_mode("tenant2", "person", rows, BatchErrorMode::CollectErrors).unwrap();
+        assert_eq!(report.ids.len(), 2);
+        assert_eq!(report.skipped, vec![1]);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, 1);
+        assert!(report.errors[0].1.contains("age"));
+        assert_eq!(db.list("tenant2", "person", false, None, None).unwrap().len(), 2);
+
+        assert_eq!(BatchErrorMode::parse("collect_errors").unwrap(), BatchErrorMode::CollectErrors);
+        assert!(BatchErrorMode::parse("ignore").is_err());
+    }
+
+    #[test]
+    fn test_batch_insert_validation_failure() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema with strict validation
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"}
+            },
+            "required": ["name", "age"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Batch with one invalid entity (missing required field)
+        let people = vec![
+            serde_json::json!({"name": "Alice", "age": 30}),
+            serde_json::json!({"name": "Bob"}),  // Missing required "age"
+            serde_json::json!({"name": "Charlie", "age": 35}),
+        ];
+
+        let result = db.batch_insert("tenant1", "person", people);
+        assert!(result.is_err());
+
+        // No entities should be inserted (atomic rollback)
+        let entities = db.list("tenant1", "person", false, None, None).unwrap();
+        assert_eq!(entities.len(), 0);
+    }
+
+    #[test]
+    fn test_add_optional_field_skips_rewrite() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+        db.register_schema("person", schema).unwrap();
+
+        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let before = db.get("tenant1", id).unwrap().unwrap();
+
+        let backfilled = db.add_field("person", "nickname", serde_json::json!({"type": "string"}), None, false).unwrap();
+        assert_eq!(backfilled, 0);
+
+        let after = db.get("tenant1", id).unwrap().unwrap();
+        assert_eq!(after.system.modified_at, before.system.modified_at);
+        assert!(after.properties.get("nickname").is_none());
+        assert!(db.get_schema("person").unwrap()["properties"].get("nickname").is_some());
+
+        // Still optional: new rows may omit it
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Adding the same field twice is rejected
+        assert!(db.add_field("person", "nickname", serde_json::json!({"type": "string"}), None, false).is_err());
+
+        // A required field needs a default for existing rows
+        assert!(db.add_field("person", "status", serde_json::json!({"type": "string"}), None, true).is_err());
+        assert!(db.get_schema("person").unwrap()["properties"].get("status").is_none());
+    }
+
+    #[test]
+    fn test_add_required_field_backfills_default() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+        db.register_schema("person", schema).unwrap();
+
+        // Backfill spans several write batches
+        let db = db.with_write_batch_size(2);
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Dave"})).unwrap();
+        db.insert("tenant2", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Default must satisfy the field schema
+        let bad = db.add_field("person", "status", serde_json::json!({"type": "string"}), Some(serde_json::json!(1)), true);
+        assert!(bad.is_err());
+
+        let backfilled = db.add_field(
+            "person",
+            "status",
+            serde_json::json!({"type": "string"}),
+            Some(serde_json::json!("active")),
+            true,
+        ).unwrap();
+        assert_eq!(backfilled, 3);
+
+        for (tenant, count) in [("tenant1", 2), ("tenant2", 1)] {
+            let people = db.list(tenant, "person", false, None, None).unwrap();
+            assert_eq!(people.len(), count);
+            assert!(people.iter().all(|person| person.properties["status"] == "active"));
+        }
+
+        // Now required for new rows, including batches
+        assert!(db.insert("tenant1", "person", serde_json::json!({"name": "Carol"})).is_err());
+        assert!(db.batch_insert("tenant1", "person", vec![serde_json::json!({"name": "Carol"})]).is_err());
+
+        // A default without `required` backfills but stays optional
+        assert_eq!(db.add_field("person", "team", serde_json::json!({"type": "string"}), Some(serde_json::json!("core")), false).unwrap(), 3);
+        db.insert("tenant1", "person", serde_json::json!({"name": "Erin", "status": "new"})).unwrap();
+    }
+
+    #[test]
+    fn test_query_by_index_array_field() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {
+                "name": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["name"],
+            "json_schema_extra": {"indexed_fields": ["tags"]}
+        });
+        db.register_schema("articles", schema).unwrap();
+
+        let a = db.insert("tenant1", "articles", serde_json::json!({"name": "A", "tags": ["rust", "db"]})).unwrap();
+        let b = db.insert("tenant1", "articles", serde_json::json!({"name": "B", "tags": ["rust", "python"]})).unwrap();
+        let c = db.batch_insert("tenant1", "articles", vec![
+            serde_json::json!({"name": "C", "tags": ["python", "python"]}),
+        ]).unwrap()[0];
+
+        let tagged = |tag: &str| {
+            let mut ids: Vec<uuid::Uuid> = db
+                .query_by_index("tenant1", "articles", "tags", &serde_json::json!(tag))
+                .unwrap()
+                .iter()
+                .map(|e| e.system.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let sorted = |mut ids: Vec<uuid::Uuid>| { ids.sort(); ids };
+
+        assert_eq!(tagged("rust"), sorted(vec![a, b]));
+        assert_eq!(tagged("python"), sorted(vec![b, c]));
+        assert_eq!(tagged("db"), vec![a]);
+        assert!(tagged("go").is_empty());
+
+        // Dropping a tag removes only that element's entry
+        db.update("tenant1", b, serde_json::json!({"tags": ["python"]})).unwrap();
+        assert_eq!(tagged("rust"), vec![a]);
+        assert_eq!(tagged("python"), sorted(vec![b, c]));
+
+        db.delete("tenant1", a).unwrap();
+        assert!(tagged("rust").is_empty());
+
+        assert!(db.query_by_index("tenant1", "articles", "name", &serde_json::json!("A")).is_err());
+    }
+
+    #[test]
+    fn test_insert_coerces_string_values() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"},
+                "active": {"type": "boolean"}
+            },
+            "required": ["name"],
+            "json_schema_extra": {"coerce_types": true}
+        });
+        db.register_schema("person", schema).unwrap();
+
+        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": "30", "active": "true"})).unwrap();
+        let entity = db.get("tenant1", id).unwrap().unwrap();
+        assert_eq!(entity.properties["age"], 30);
+        assert_eq!(entity.properties["active"], true);
+
+        let ids = db.batch_insert("tenant1", "person", vec![serde_json::json!({"name": "Bob", "age": "25"})]).unwrap();
+        assert_eq!(db.get("tenant1", ids[0]).unwrap().unwrap().properties["age"], 25);
+
+        // Unparseable strings are still rejected
+        assert!(db.insert("tenant1", "person", serde_json::json!({"name": "Carol", "age": "abc"})).is_err());
+    }
+
+    #[test]
+    fn test_import_parquet_round_trip() {
+        use crate::export::{JsonlExporter, ParquetExporter};
+        use tempfile::tempdir;
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "article",
+            "json_schema_extra": {"key_field": "slug"},
+            "properties": {
+                "slug": {"type": "string"},
+                "title": {"type": "string"},
+                "views": {"type": "integer"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["slug", "title"]
+        });
+
+        let source = Database::open_temp().unwrap();
+        source.register_schema("article", schema.clone()).unwrap();
+        source.insert("tenant1", "article", serde_json::json!({
+            "slug": "rust-db", "title": "Rust DB", "views": 10, "tags": ["rust"], "embedding": [0.5, -0.25]
+        })).unwrap();
+        source.insert("tenant1", "article", serde_json::json!({
+            "slug": "graphs", "title": "Graphs", "tags": []
+        })).unwrap();
+        let deleted = source.insert("tenant1", "article", serde_json::json!({
+            "slug": "old", "title": "Old", "views": 0
+        })).unwrap();
+        source.delete("tenant1", deleted).unwrap();
+
+        let exported = source.list("tenant1", "article", true, None, None).unwrap();
+        let dir = tempdir().unwrap();
+        let parquet_path = dir.path().join("articles.parquet");
+        let jsonl_path = dir.path().join("articles.jsonl");
+        ParquetExporter::export(&exported, &parquet_path).unwrap();
+        JsonlExporter::export(&exported, &jsonl_path).unwrap();
+
+        for format in ["parquet", "jsonl"] {
+            let target = Database::open_temp().unwrap();
+            target.register_schema("article", schema.clone()).unwrap();
+
+            let ids = match format {
+                "parquet" => target.import_parquet("tenant1", "article", &parquet_path).unwrap(),
+                _ => target.import_jsonl("tenant1", "article", &jsonl_path).unwrap(),
+            };
+            assert_eq!(ids.len(), 3);
+
+            let imported = target.list("tenant1", "article", true, None, None).unwrap();
+            assert_eq!(imported.len(), exported.len());
+            for original in &exported {
+                let restored = target.get("tenant1", original.system.id).unwrap().unwrap();
+                assert_eq!(
+                    serde_json::to_value(original).unwrap(),
+                    serde_json::to_value(&restored).unwrap(),
+                    "{} import differs",
+                    format
+                );
+            }
+
+            // Key index is rebuilt
+            assert!(target.get_by_key("tenant1", "article", "graphs").unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn test_batch_insert_deterministic_uuids() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema with key_field
+        let schema = serde_json::json!({
+            "title": "User",
+            "version": "1.0.0",
+            "short_name": "user",
+            "json_schema_extra": {
+                "key_field": "email"
+            },
+            "properties": {
+                "email": {"type": "string"},
+                "name": {"type": "string"}
+            },
+            "required": ["email", "name"]
+        });
+
+        db.register_schema("user", schema).unwrap();
+
+        // Batch insert
+        let users = vec![
+            serde_json::json!({"email": "alice@example.com", "name": "Alice"}),
+            serde_json::json!({"email": "bob@example.com", "name": "Bob"}),
+        ];
+
+        let ids = db.batch_insert("tenant1", "user", users).unwrap();
+
+        // Insert same data again
+        let users2 = vec![
+            serde_json::json!({"email": "alice@example.com", "name": "Alice"}),
+            serde_json::json!({"email": "bob@example.com", "name": "Bob"}),
+        ];
+
+        let ids2 = db.batch_insert("tenant1", "user", users2).unwrap();
+
+        // Same data should generate same UUIDs
+        assert_eq!(ids, ids2);
+    }
+
+    #[test]
+    fn test_count() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Initially zero
+        assert_eq!(db.count("tenant1", "person", false).unwrap(), 0);
+
+        // Insert entities
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+
+        // Count should be 3
+        assert_eq!(db.count("tenant1", "person", false).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_count_excludes_deleted() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities
+        let id1 = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Soft delete one
+        db.delete("tenant1", id1).unwrap();
+
+        // Count without deleted
+        assert_eq!(db.count("tenant1", "person", false).unwrap(), 1);
+
+        // Count with deleted
+        assert_eq!(db.count("tenant1", "person", true).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_exists() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entity
+        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+
+        // Should exist
+        assert!(db.exists("tenant1", id).unwrap());
+
+        // Non-existent UUID
+        assert!(!db.exists("tenant1", uuid::Uuid::new_v4()).unwrap());
+
+        // Hard delete
+        db.hard_delete("tenant1", id).unwrap();
+
+        // Should not exist
+        assert!(!db.exists("tenant1", id).unwrap());
+    }
+
+    #[test]
+    fn test_exists_with_soft_delete() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entity
+        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+
+        // Soft delete
+        db.delete("tenant1", id).unwrap();
+
+        // Should still exist (soft deleted)
+        assert!(db.exists("tenant1", id).unwrap());
+    }
+
+    #[test]
+    fn test_add_edge() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Add edge
+        let edge = db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
+
+        assert_eq!(edge.src, alice_id);
+        assert_eq!(edge.dst, bob_id);
+        assert_eq!(edge.rel_type, "knows");
+    }
+
+    #[test]
+    fn test_add_edge_with_properties() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Add edge with properties
+        let props = serde_json::json!({"weight": 0.8, "since": "2020-01-01"});
+        let edge = db.add_edge("tenant1", alice_id, bob_id, "knows", Some(props)).unwrap();
+
+        assert_eq!(edge.data.properties.get("weight").unwrap(), &serde_json::json!(0.8));
+        assert_eq!(edge.data.properties.get("since").unwrap(), "2020-01-01");
+    }
+
+    #[test]
+    fn test_add_edge_nonexistent_entity() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert one entity
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+
+        // Try to add edge to nonexistent entity
+        let result = db.add_edge("tenant1", alice_id, uuid::Uuid::new_v4(), "knows", None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_edges() {
+        let db = Database::open_temp().unwrap();
+
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
+
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        let charlie_id = db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+
+        // Add edges
+        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
+        db.add_edge("tenant1", alice_id, charlie_id, "knows", None).unwrap();
+        db.add_edge("tenant1", alice_id, bob_id, "likes", None).unwrap();
+
+        // Get all outgoing edges
+        let edges = db.get_edges(alice_id, None).unwrap();
+        assert_eq!(edges.len(), 3);
 
-    // Priority 5: No key field (random UUID case)
-    None
-}
+        // Get only "knows" edges
+        let knows_edges = db.get_edges(alice_id, Some("knows")).unwrap();
+        assert_eq!(knows_edges.len(), 2);
 
-/// Convert JSON value to string for key indexing.
-fn value_to_string(value: &serde_json::Value) -> String {
-    match value {
-        serde_json::Value::String(s) => s.clone(),
-        serde_json::Value::Number(n) => n.to_string(),
-        serde_json::Value::Bool(b) => b.to_string(),
-        _ => value.to_string(),
+        // Get only "likes" edges
+        let likes_edges = db.get_edges(alice_id, Some("likes")).unwrap();
+        assert_eq!(likes_edges.len(), 1);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_database_open_temp() {
+    fn test_get_incoming_edges() {
         let db = Database::open_temp().unwrap();
 
-        // Should have builtin schemas
-        assert!(db.has_schema("schemas"));
-        assert!(db.has_schema("documents"));
-        assert!(db.has_schema("resources"));
-    }
+        // Register schema
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        });
 
-    #[test]
-    fn test_list_schemas() {
-        let db = Database::open_temp().unwrap();
-        let schemas = db.list_schemas().unwrap();
+        db.register_schema("person", schema).unwrap();
 
-        assert_eq!(schemas.len(), 3);
-        assert!(schemas.contains(&"schemas".to_string()));
-        assert!(schemas.contains(&"documents".to_string()));
-        assert!(schemas.contains(&"resources".to_string()));
+        // Insert entities
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        let charlie_id = db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+
+        // Add edges (multiple entities pointing to Bob)
+        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
+        db.add_edge("tenant1", charlie_id, bob_id, "knows", None).unwrap();
+
+        // Get incoming edges to Bob
+        let edges = db.get_incoming_edges(bob_id, None).unwrap();
+        assert_eq!(edges.len(), 2);
+
+        // Check sources
+        let sources: Vec<uuid::Uuid> = edges.iter().map(|e| e.src).collect();
+        assert!(sources.contains(&alice_id));
+        assert!(sources.contains(&charlie_id));
     }
 
     #[test]
-    fn test_register_schema() {
+    fn test_delete_edge() {
         let db = Database::open_temp().unwrap();
 
+        // Register schema
         let schema = serde_json::json!({
-            "title": "Article",
-            "description": "Test article schema",
+            "title": "Person",
             "version": "1.0.0",
-            "short_name": "articles",
-            "properties": {
-                "title": {
-                    "type": "string",
-                    "description": "Article title"
-                }
-            },
-            "required": ["title"]
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
         });
 
-        db.register_schema("articles", schema).unwrap();
-        assert!(db.has_schema("articles"));
+        db.register_schema("person", schema).unwrap();
+
+        // Insert entities
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Add edge
+        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
+
+        // Verify edge exists
+        let edges = db.get_edges(alice_id, None).unwrap();
+        assert_eq!(edges.len(), 1);
+
+        // Delete edge
+        db.delete_edge(alice_id, bob_id, "knows").unwrap();
+
+        // Verify edge is gone
+        let edges = db.get_edges(alice_id, None).unwrap();
+        assert_eq!(edges.len(), 0);
+
+        // Also gone from reverse index
+        let incoming = db.get_incoming_edges(bob_id, None).unwrap();
+        assert_eq!(incoming.len(), 0);
     }
 
     #[test]
-    fn test_get_by_key_with_name_field() {
+    fn test_bidirectional_edges() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema without explicit key_field
+        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entity with name field
-        let data = serde_json::json!({"name": "Alice", "age": 30});
-        let id = db.insert("tenant1", "person", data).unwrap();
+        // Insert entities
+        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
 
-        // Lookup by name
-        let entity = db.get_by_key("tenant1", "person", "Alice").unwrap();
-        assert!(entity.is_some());
+        // Add edge
+        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
 
-        let entity = entity.unwrap();
-        assert_eq!(entity.system.id, id);
-        assert_eq!(entity.properties.get("name").unwrap(), "Alice");
+        // Query from both directions
+        let outgoing = db.get_edges(alice_id, None).unwrap();
+        let incoming = db.get_incoming_edges(bob_id, None).unwrap();
+
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(incoming.len(), 1);
+
+        // Both should have same data
+        assert_eq!(outgoing[0].src, incoming[0].src);
+        assert_eq!(outgoing[0].dst, incoming[0].dst);
+        assert_eq!(outgoing[0].rel_type, incoming[0].rel_type);
     }
 
     #[test]
-    fn test_get_by_key_with_custom_key_field() {
+    fn test_acyclic_edge_constraint() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema with custom key_field
         let schema = serde_json::json!({
-            "title": "User",
+            "title": "Folder",
             "version": "1.0.0",
-            "short_name": "user",
-            "json_schema_extra": {
-                "key_field": "email"
-            },
-            "properties": {
-                "email": {"type": "string"},
-                "username": {"type": "string"}
-            },
-            "required": ["email", "username"]
+            "short_name": "folder",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
         });
+        db.register_schema("folder", schema).unwrap();
+
+        let root = db.insert("tenant1", "folder", serde_json::json!({"name": "root"})).unwrap();
+        let docs = db.insert("tenant1", "folder", serde_json::json!({"name": "docs"})).unwrap();
+        let specs = db.insert("tenant1", "folder", serde_json::json!({"name": "specs"})).unwrap();
+        let archive = db.insert("tenant1", "folder", serde_json::json!({"name": "archive"})).unwrap();
+
+        db.set_edge_constraint("parent_of", true).unwrap();
+        assert!(db.is_acyclic("parent_of").unwrap());
+
+        // Valid hierarchy edges
+        db.add_edge("tenant1", root, docs, "parent_of", None).unwrap();
+        db.add_edge("tenant1", docs, specs, "parent_of", None).unwrap();
+        db.add_edge("tenant1", root, specs, "parent_of", None).unwrap();
+        db.add_edge("tenant1", root, archive, "parent_of", None).unwrap();
+
+        // Closing a cycle (specs -> root) is rejected, as is a self-loop
+        match db.add_edge("tenant1", specs, root, "parent_of", None) {
+            Err(DatabaseError::EdgeCycle { rel_type, src_id, dst_id }) => {
+                assert_eq!(rel_type, "parent_of");
+                assert_eq!(src_id, specs);
+                assert_eq!(dst_id, root);
+            }
+            other => panic!("expected EdgeCycle, got {:?}", other),
+        }
+        assert!(db.add_edge("tenant1", docs, docs, "parent_of", None).is_err());
+        assert!(db.get_edges(specs, Some("parent_of")).unwrap().is_empty());
 
-        db.register_schema("user", schema).unwrap();
+        // Other relationship types are unconstrained
+        db.add_edge("tenant1", specs, root, "links_to", None).unwrap();
 
-        // Insert entity
-        let data = serde_json::json!({
-            "email": "alice@example.com",
-            "username": "alice"
-        });
-        let id = db.insert("tenant1", "user", data).unwrap();
+        // Removing the constraint allows the cycle
+        db.set_edge_constraint("parent_of", false).unwrap();
+        db.add_edge("tenant1", specs, root, "parent_of", None).unwrap();
+    }
 
-        // Lookup by email
-        let entity = db.get_by_key("tenant1", "user", "alice@example.com").unwrap();
-        assert!(entity.is_some());
+    #[test]
+    fn test_acyclic_edge_constraint_concurrent_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
 
-        let entity = entity.unwrap();
-        assert_eq!(entity.system.id, id);
-        assert_eq!(entity.properties.get("email").unwrap(), "alice@example.com");
+        {
+            let db = Arc::new(Database::open(dir.path()).unwrap());
+            db.register_schema("folder", serde_json::json!({
+                "title": "Folder",
+                "version": "1.0.0",
+                "short_name": "folder",
+                "properties": {"name": {"type": "string"}},
+                "required": ["name"]
+            })).unwrap();
+            db.set_edge_constraint("parent_of", true).unwrap();
+
+            // Racing a→b and b→a: exactly one edge of each pair is committed
+            for round in 0..20 {
+                let a = db.insert("tenant1", "folder", serde_json::json!({"name": format!("a{}", round)})).unwrap();
+                let b = db.insert("tenant1", "folder", serde_json::json!({"name": format!("b{}", round)})).unwrap();
+                let barrier = Arc::new(std::sync::Barrier::new(2));
+
+                let handles: Vec<_> = [(a, b), (b, a)]
+                    .into_iter()
+                    .map(|(src, dst)| {
+                        let db = Arc::clone(&db);
+                        let barrier = Arc::clone(&barrier);
+                        std::thread::spawn(move || {
+                            barrier.wait();
+                            db.add_edge("tenant1", src, dst, "parent_of", None).is_ok()
+                        })
+                    })
+                    .collect();
+                let added = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+
+                assert_eq!(added, 1);
+                let edges = db.get_edges(a, Some("parent_of")).unwrap().len() + db.get_edges(b, Some("parent_of")).unwrap().len();
+                assert_eq!(edges, 1);
+            }
+        }
+
+        // The constraint survives a reopen, and edge scans skip the stored constraint
+        let db = Database::open(dir.path()).unwrap();
+        assert!(db.is_acyclic("parent_of").unwrap());
+
+        let root = db.insert("tenant1", "folder", serde_json::json!({"name": "root"})).unwrap();
+        let child = db.insert("tenant1", "folder", serde_json::json!({"name": "child"})).unwrap();
+        db.add_edge("tenant1", root, child, "parent_of", None).unwrap();
+        assert!(matches!(db.add_edge("tenant1", child, root, "parent_of", None), Err(DatabaseError::EdgeCycle { .. })));
+        db.decay_edge_weights(std::time::Duration::from_secs(3600), None).unwrap();
+
+        // Removing it is persisted too
+        db.set_edge_constraint("parent_of", false).unwrap();
+        drop(db);
+        let db = Database::open(dir.path()).unwrap();
+        assert!(!db.is_acyclic("parent_of").unwrap());
     }
 
     #[test]
-    fn test_get_by_key_not_found() {
+    fn test_traverse_bfs() {
         let db = Database::open_temp().unwrap();
 
         // Register schema
@@ -1943,21 +9268,86 @@ mod tests {
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Lookup non-existent key
-        let entity = db.get_by_key("tenant1", "person", "NonExistent").unwrap();
-        assert!(entity.is_none());
+        // Create a graph: A -> B -> C -> D
+        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
+        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
+        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
+        let d = db.insert("tenant1", "person", serde_json::json!({"name": "D"})).unwrap();
+
+        db.add_edge("tenant1", a, b, "knows", None).unwrap();
+        db.add_edge("tenant1", b, c, "knows", None).unwrap();
+        db.add_edge("tenant1", c, d, "knows", None).unwrap();
+
+        // BFS from A with depth 2 should find A, B, C
+        let result = db.traverse_bfs(a, crate::graph::TraversalDirection::Out, 2, None).unwrap().nodes;
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], a);
+        assert_eq!(result[1], b);
+        assert_eq!(result[2], c);
+
+        // BFS from A with depth 3 should find all
+        let result = db.traverse_bfs(a, crate::graph::TraversalDirection::Out, 3, None).unwrap().nodes;
+        assert_eq!(result.len(), 4);
     }
 
     #[test]
-    fn test_get_by_key_tenant_isolation() {
+    fn test_traverse_bfs_limits() {
+        use crate::graph::{TraversalDirection, TraversalLimits};
+
+        let db = Database::open_temp().unwrap();
+        db.register_schema("person", serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        })).unwrap();
+
+        // Dense graph: a hub linked to 10 nodes, each linked to every other
+        let hub = db.insert("tenant1", "person", serde_json::json!({"name": "hub"})).unwrap();
+        let spokes: Vec<_> = (0..10)
+            .map(|i| db.insert("tenant1", "person", serde_json::json!({"name": format!("n{}", i)})).unwrap())
+            .collect();
+        for &spoke in &spokes {
+            db.add_edge("tenant1", hub, spoke, "knows", None).unwrap();
+            for &other in &spokes {
+                if other != spoke {
+                    db.add_edge("tenant1", spoke, other, "knows", None).unwrap();
+                }
+            }
+        }
+
+        // Default limits leave small graphs untouched
+        let full = db.traverse_bfs(hub, TraversalDirection::Out, 3, None).unwrap();
+        assert_eq!(full.nodes.len(), 11);
+        assert!(!full.truncated);
+
+        // Total cap
+        let limits = TraversalLimits { max_nodes: 5, ..TraversalLimits::default() };
+        let capped = db.traverse_bfs_with_limits(hub, TraversalDirection::Out, 3, None, limits).unwrap();
+        assert_eq!(capped.nodes.len(), 5);
+        assert_eq!(capped.nodes[0], hub);
+        assert!(capped.truncated);
+
+        // Per-level cap bounds the hub's fan-out
+        let limits = TraversalLimits { max_nodes_per_level: 3, ..TraversalLimits::default() };
+        let fanout = db.traverse_bfs_with_limits(hub, TraversalDirection::Out, 1, None, limits).unwrap();
+        assert_eq!(fanout.nodes.len(), 4);
+        assert!(fanout.truncated);
+
+        // Exactly at the cap is not truncation
+        let limits = TraversalLimits { max_nodes: 11, ..TraversalLimits::default() };
+        assert!(!db.traverse_bfs_with_limits(hub, TraversalDirection::Out, 3, None, limits).unwrap().truncated);
+    }
+
+    #[test]
+    fn test_traverse_dfs() {
         let db = Database::open_temp().unwrap();
 
         // Register schema
@@ -1965,40 +9355,29 @@ mod tests {
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert same name in different tenants
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant2", "person", serde_json::json!({"name": "Alice"})).unwrap();
-
-        // Each tenant should only see their own entity
-        let entity1 = db.get_by_key("tenant1", "person", "Alice").unwrap();
-        let entity2 = db.get_by_key("tenant2", "person", "Alice").unwrap();
-
-        assert!(entity1.is_some());
-        assert!(entity2.is_some());
-
-        // UUIDs are the same (deterministic based on entity data alone)
-        // but they are stored at different keys: entity:tenant1:{uuid} vs entity:tenant2:{uuid}
-        assert_eq!(entity1.as_ref().unwrap().system.id, entity2.as_ref().unwrap().system.id);
+        // Create a graph: A -> B, A -> C
+        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
+        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
+        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
 
-        // Verify tenant1 cannot see tenant2's entity by direct get
-        let id = entity1.unwrap().system.id;
-        let from_tenant1 = db.get("tenant1", id).unwrap();
-        let from_tenant2 = db.get("tenant2", id).unwrap();
+        db.add_edge("tenant1", a, b, "knows", None).unwrap();
+        db.add_edge("tenant1", a, c, "knows", None).unwrap();
 
-        assert!(from_tenant1.is_some());
-        assert!(from_tenant2.is_some()); // Both exist because same UUID stored under different tenant keys
+        // DFS from A should find all 3
+        let result = db.traverse_dfs(a, crate::graph::TraversalDirection::Out, 2, None).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], a);
+        // B and C order depends on edge insertion order
     }
 
     #[test]
-    fn test_update_partial() {
+    fn test_traverse_incoming() {
         let db = Database::open_temp().unwrap();
 
         // Register schema
@@ -2006,169 +9385,189 @@ mod tests {
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"},
-                "status": {"type": "string"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entity
-        let data = serde_json::json!({"name": "Alice", "age": 30, "status": "active"});
-        let id = db.insert("tenant1", "person", data).unwrap();
+        // Create a graph: A -> C, B -> C
+        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
+        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
+        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
 
-        // Partial update - only update age
-        let updates = serde_json::json!({"age": 31});
-        let updated = db.update("tenant1", id, updates).unwrap();
+        db.add_edge("tenant1", a, c, "knows", None).unwrap();
+        db.add_edge("tenant1", b, c, "knows", None).unwrap();
 
-        assert_eq!(updated.properties.get("name").unwrap(), "Alice");
-        assert_eq!(updated.properties.get("age").unwrap(), 31);
-        assert_eq!(updated.properties.get("status").unwrap(), "active");
+        // Traverse incoming from C should find C, A, B
+        let result = db.traverse_bfs(c, crate::graph::TraversalDirection::In, 2, None).unwrap().nodes;
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], c);
+        assert!(result.contains(&a));
+        assert!(result.contains(&b));
     }
 
     #[test]
-    fn test_update_validation() {
+    fn test_shortest_path() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema with required field
+        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"}
-            },
-            "required": ["name", "age"]
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert valid entity
-        let data = serde_json::json!({"name": "Alice", "age": 30});
-        let id = db.insert("tenant1", "person", data).unwrap();
-
-        // Try to update with invalid data (age as string)
-        let updates = serde_json::json!({"age": "thirty"});
-        let result = db.update("tenant1", id, updates);
-
-        assert!(result.is_err()); // Should fail validation
-    }
+        // Create a graph: A -> B -> C -> D
+        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
+        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
+        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
+        let d = db.insert("tenant1", "person", serde_json::json!({"name": "D"})).unwrap();
 
-    #[test]
-    fn test_update_not_found() {
-        let db = Database::open_temp().unwrap();
+        db.add_edge("tenant1", a, b, "knows", None).unwrap();
+        db.add_edge("tenant1", b, c, "knows", None).unwrap();
+        db.add_edge("tenant1", c, d, "knows", None).unwrap();
 
-        let updates = serde_json::json!({"age": 31});
-        let result = db.update("tenant1", uuid::Uuid::new_v4(), updates);
+        // Find path from A to D
+        let path = db.shortest_path(a, d, crate::graph::TraversalDirection::Out, 5).unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path[0], a);
+        assert_eq!(path[1], b);
+        assert_eq!(path[2], c);
+        assert_eq!(path[3], d);
 
-        assert!(result.is_err()); // Entity not found
+        // No path exists (wrong direction)
+        let path = db.shortest_path(d, a, crate::graph::TraversalDirection::Out, 5).unwrap();
+        assert_eq!(path.len(), 0);
     }
 
     #[test]
-    fn test_soft_delete() {
+    fn test_find_triangles() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entity
-        let data = serde_json::json!({"name": "Alice"});
-        let id = db.insert("tenant1", "person", data).unwrap();
+        let ids: Vec<uuid::Uuid> = ["A", "B", "C", "D", "E"]
+            .iter()
+            .map(|name| db.insert("tenant1", "person", serde_json::json!({"name": name})).unwrap())
+            .collect();
+        let (a, b, c, d, e) = (ids[0], ids[1], ids[2], ids[3], ids[4]);
 
-        // Soft delete
-        let deleted = db.delete("tenant1", id).unwrap();
+        // Triangle A-B-C with mixed directions, plus a reverse duplicate
+        db.add_edge("tenant1", a, b, "knows", None).unwrap();
+        db.add_edge("tenant1", b, c, "knows", None).unwrap();
+        db.add_edge("tenant1", a, c, "knows", None).unwrap();
+        db.add_edge("tenant1", c, a, "knows", None).unwrap();
 
-        assert!(deleted.is_deleted());
-        assert!(deleted.system.deleted_at.is_some());
+        // Non-triangle edges: a path off the triangle and another type
+        db.add_edge("tenant1", c, d, "knows", None).unwrap();
+        db.add_edge("tenant1", d, e, "knows", None).unwrap();
+        db.add_edge("tenant1", e, a, "likes", None).unwrap();
+        db.add_edge("tenant1", d, a, "likes", None).unwrap();
 
-        // Entity still exists in storage (soft deleted)
-        let entity = db.get("tenant1", id).unwrap();
-        assert!(entity.is_some());
-        assert!(entity.unwrap().is_deleted());
+        let triangles = db.find_triangles("knows").unwrap();
+        let mut expected = [a, b, c];
+        expected.sort();
+        assert_eq!(triangles, vec![expected]);
+
+        // A-D-E only closes across "likes" and "knows", never within one type
+        assert!(db.find_triangles("likes").unwrap().is_empty());
     }
 
     #[test]
-    fn test_hard_delete() {
+    fn test_graph_embeddings_separate_communities() {
+        use crate::index::similarity::cosine_similarity;
+
         let db = Database::open_temp().unwrap();
 
-        // Register schema
-        let schema = serde_json::json!({
+        db.register_schema("person", serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
-        });
+        })).unwrap();
 
-        db.register_schema("person", schema).unwrap();
+        let ids: Vec<uuid::Uuid> = (0..12)
+            .map(|i| db.insert("tenant1", "person", serde_json::json!({"name": format!("p{}", i)})).unwrap())
+            .collect();
+        let (left, right) = ids.split_at(6);
 
-        // Insert entity
-        let data = serde_json::json!({"name": "Alice"});
-        let id = db.insert("tenant1", "person", data).unwrap();
+        // Two cliques joined by a single bridge
+        for community in [left, right] {
+            for (i, &a) in community.iter().enumerate() {
+                for &b in &community[i + 1..] {
+                    db.add_edge("tenant1", a, b, "knows", None).unwrap();
+                }
+            }
+        }
+        db.add_edge("tenant1", left[0], right[0], "knows", None).unwrap();
 
-        // Hard delete
-        db.hard_delete("tenant1", id).unwrap();
+        assert!(db.graph_embeddings("knows", 16, 1, 10).is_err());
+        assert_eq!(db.graph_embeddings("knows", 16, 20, 20).unwrap(), 12);
 
-        // Entity no longer exists
-        let entity = db.get("tenant1", id).unwrap();
-        assert!(entity.is_none());
+        let vectors: Vec<Vec<f32>> = ids
+            .iter()
+            .map(|id| db.graph_embedding("knows", *id).unwrap().unwrap())
+            .collect();
+        assert!(vectors.iter().all(|v| v.len() == 16));
+
+        let (mut within, mut across) = (Vec::new(), Vec::new());
+        for (i, a) in vectors.iter().enumerate() {
+            for (j, b) in vectors.iter().enumerate().skip(i + 1) {
+                let similarity = cosine_similarity(a, b);
+                if (i < 6) == (j < 6) {
+                    within.push(similarity);
+                } else {
+                    across.push(similarity);
+                }
+            }
+        }
+        let mean = |xs: &[f32]| xs.iter().sum::<f32>() / xs.len() as f32;
+        assert!(mean(&within) > mean(&across) + 0.3, "within {} across {}", mean(&within), mean(&across));
+
+        // Untouched relationship types have no vectors
+        assert!(db.graph_embedding("likes", ids[0]).unwrap().is_none());
     }
 
     #[test]
-    fn test_hard_delete_removes_key_index() {
+    fn test_shortest_path_not_found() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema with key_field
+        // Register schema
         let schema = serde_json::json!({
-            "title": "User",
+            "title": "Person",
             "version": "1.0.0",
-            "short_name": "user",
-            "json_schema_extra": {
-                "key_field": "email"
-            },
-            "properties": {
-                "email": {"type": "string"},
-                "name": {"type": "string"}
-            },
-            "required": ["email", "name"]
+            "short_name": "person",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
         });
 
-        db.register_schema("user", schema).unwrap();
-
-        // Insert entity
-        let data = serde_json::json!({"email": "alice@example.com", "name": "Alice"});
-        let id = db.insert("tenant1", "user", data).unwrap();
-
-        // Verify key lookup works
-        let entity = db.get_by_key("tenant1", "user", "alice@example.com").unwrap();
-        assert!(entity.is_some());
+        db.register_schema("person", schema).unwrap();
 
-        // Hard delete
-        db.hard_delete("tenant1", id).unwrap();
+        // Create two disconnected nodes
+        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
+        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
 
-        // Key lookup should return None
-        let entity = db.get_by_key("tenant1", "user", "alice@example.com").unwrap();
-        assert!(entity.is_none());
+        // No path should exist
+        let path = db.shortest_path(a, b, crate::graph::TraversalDirection::Out, 5).unwrap();
+        assert_eq!(path.len(), 0);
     }
 
     #[test]
-    fn test_list_entities() {
+    fn test_traverse_with_rel_type_filter() {
         let db = Database::open_temp().unwrap();
 
         // Register schema
@@ -2176,35 +9575,70 @@ mod tests {
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert multiple entities
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+        // Create a graph with different relationship types
+        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
+        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
+        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
 
-        // List all persons
-        let entities = db.list("tenant1", "person", false, None).unwrap();
-        assert_eq!(entities.len(), 3);
+        db.add_edge("tenant1", a, b, "knows", None).unwrap();
+        db.add_edge("tenant1", a, c, "works_with", None).unwrap();
 
-        let names: Vec<&str> = entities
-            .iter()
-            .map(|e| e.properties.get("name").unwrap().as_str().unwrap())
-            .collect();
+        // Only follow "knows" edges
+        let result = db.traverse_bfs(a, crate::graph::TraversalDirection::Out, 2, Some(vec!["knows".to_string()])).unwrap().nodes;
+        assert_eq!(result.len(), 2); // A and B only
+        assert!(result.contains(&a));
+        assert!(result.contains(&b));
+        assert!(!result.contains(&c));
+    }
 
-        assert!(names.contains(&"Alice"));
-        assert!(names.contains(&"Bob"));
-        assert!(names.contains(&"Charlie"));
+    #[test]
+    fn test_traverse_with_multiple_rel_types() {
+        use crate::graph::TraversalDirection;
+
+        let db = Database::open_temp().unwrap();
+        db.register_schema("doc", serde_json::json!({
+            "title": "Doc",
+            "version": "1.0.0",
+            "short_name": "doc",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        })).unwrap();
+
+        let insert = |name: &str| db.insert("tenant1", "doc", serde_json::json!({"name": name})).unwrap();
+        let (paper, cited, cited_twice, spec, author, tag) =
+            (insert("paper"), insert("cited"), insert("cited-twice"), insert("spec"), insert("author"), insert("tag"));
+
+        // paper -cites-> cited -cites-> cited_twice -references-> spec,
+        // with authorship and tags hanging off the citation chain
+        db.add_edge("tenant1", paper, cited, "cites", None).unwrap();
+        db.add_edge("tenant1", cited, cited_twice, "cites", None).unwrap();
+        db.add_edge("tenant1", cited_twice, spec, "references", None).unwrap();
+        db.add_edge("tenant1", paper, author, "authored_by", None).unwrap();
+        db.add_edge("tenant1", cited, tag, "tagged", None).unwrap();
+
+        let follow = |types: &[&str]| {
+            let types = types.iter().map(|t| t.to_string()).collect();
+            db.traverse_bfs(paper, TraversalDirection::Out, 3, Some(types)).unwrap().nodes
+        };
+
+        assert_eq!(follow(&["cites"]), vec![paper, cited, cited_twice]);
+        assert_eq!(follow(&["cites", "references"]), vec![paper, cited, cited_twice, spec]);
+
+        // Empty list and None both follow everything
+        assert_eq!(follow(&[]).len(), 6);
+        assert_eq!(db.traverse_bfs(paper, TraversalDirection::Out, 3, None).unwrap().nodes.len(), 6);
     }
 
+    // SQL Query Tests
+
     #[test]
-    fn test_list_with_limit() {
+    fn test_query_sql_select_all() {
         let db = Database::open_temp().unwrap();
 
         // Register schema
@@ -2213,131 +9647,136 @@ mod tests {
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert multiple entities
-        for i in 0..10 {
-            db.insert("tenant1", "person", serde_json::json!({"name": format!("Person {}", i)})).unwrap();
-        }
+        // Insert test data
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
 
-        // List with limit
-        let entities = db.list("tenant1", "person", false, Some(5)).unwrap();
-        assert_eq!(entities.len(), 5);
+        // Query all
+        let result = db.query_sql("tenant1", "SELECT * FROM person").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 3);
     }
 
     #[test]
-    fn test_list_excludes_deleted() {
+    fn test_query_sql_where_comparison() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let id1 = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
 
-        // Soft delete one entity
-        db.delete("tenant1", id1).unwrap();
+        // Test greater than
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], "Charlie");
 
-        // List without deleted entities
-        let entities = db.list("tenant1", "person", false, None).unwrap();
-        assert_eq!(entities.len(), 1);
-        assert_eq!(entities[0].properties.get("name").unwrap(), "Bob");
+        // Test less than
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age < 30").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], "Bob");
 
-        // List with deleted entities
-        let all_entities = db.list("tenant1", "person", true, None).unwrap();
-        assert_eq!(all_entities.len(), 2);
+        // Test equality
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age = 30").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], "Alice");
     }
 
     #[test]
-    fn test_list_tenant_isolation() {
+    fn test_query_sql_where_string_equality() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "role": {"type": "string"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities in different tenants
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-        db.insert("tenant2", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "role": "engineer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "role": "designer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "role": "engineer"})).unwrap();
 
-        // List for tenant1
-        let entities1 = db.list("tenant1", "person", false, None).unwrap();
-        assert_eq!(entities1.len(), 2);
+        // Test string equality (case-insensitive)
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE role = 'engineer'").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 2);
 
-        // List for tenant2
-        let entities2 = db.list("tenant2", "person", false, None).unwrap();
-        assert_eq!(entities2.len(), 1);
+        // Case insensitive
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE role = 'ENGINEER'").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 2);
     }
 
     #[test]
-    fn test_list_filters_by_table() {
+    fn test_query_sql_where_and_or() {
         let db = Database::open_temp().unwrap();
 
-        // Register multiple schemas
-        let person_schema = serde_json::json!({
+        let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {"name": {"type": "string"}},
-            "required": ["name"]
-        });
-
-        let project_schema = serde_json::json!({
-            "title": "Project",
-            "version": "1.0.0",
-            "short_name": "project",
-            "properties": {"name": {"type": "string"}},
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "number"},
+                "role": {"type": "string"}
+            },
             "required": ["name"]
         });
 
-        db.register_schema("person", person_schema).unwrap();
-        db.register_schema("project", project_schema).unwrap();
+        db.register_schema("person", schema).unwrap();
 
-        // Insert entities in different tables
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-        db.insert("tenant1", "project", serde_json::json!({"name": "Project X"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30, "role": "engineer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25, "role": "designer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35, "role": "engineer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Diana", "age": 28, "role": "manager"})).unwrap();
 
-        // List only persons
-        let persons = db.list("tenant1", "person", false, None).unwrap();
-        assert_eq!(persons.len(), 2);
+        // Test AND
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30 AND role = 'engineer'").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], "Charlie");
 
-        // List only projects
-        let projects = db.list("tenant1", "project", false, None).unwrap();
-        assert_eq!(projects.len(), 1);
+        // Test OR
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age < 26 OR role = 'manager'").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 2); // Bob and Diana
     }
 
     #[test]
-    fn test_batch_insert() {
+    fn test_query_sql_order_by() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2351,31 +9790,29 @@ mod tests {
 
         db.register_schema("person", schema).unwrap();
 
-        // Batch insert
-        let people = vec![
-            serde_json::json!({"name": "Alice", "age": 30}),
-            serde_json::json!({"name": "Bob", "age": 25}),
-            serde_json::json!({"name": "Charlie", "age": 35}),
-        ];
-
-        let ids = db.batch_insert("tenant1", "person", people).unwrap();
-        assert_eq!(ids.len(), 3);
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
 
-        // Verify all entities were inserted
-        let entities = db.list("tenant1", "person", false, None).unwrap();
-        assert_eq!(entities.len(), 3);
+        // Test ORDER BY ASC (default)
+        let result = db.query_sql("tenant1", "SELECT * FROM person ORDER BY age ASC").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows[0]["name"], "Bob");
+        assert_eq!(rows[1]["name"], "Alice");
+        assert_eq!(rows[2]["name"], "Charlie");
 
-        // Verify IDs match
-        for id in &ids {
-            assert!(db.exists("tenant1", *id).unwrap());
-        }
+        // Test ORDER BY DESC
+        let result = db.query_sql("tenant1", "SELECT * FROM person ORDER BY age DESC").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows[0]["name"], "Charlie");
+        assert_eq!(rows[1]["name"], "Alice");
+        assert_eq!(rows[2]["name"], "Bob");
     }
 
     #[test]
-    fn test_batch_insert_validation_failure() {
+    fn test_query_sql_limit() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema with strict validation
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2384,192 +9821,169 @@ mod tests {
                 "name": {"type": "string"},
                 "age": {"type": "number"}
             },
-            "required": ["name", "age"]
+            "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Batch with one invalid entity (missing required field)
-        let people = vec![
-            serde_json::json!({"name": "Alice", "age": 30}),
-            serde_json::json!({"name": "Bob"}),  // Missing required "age"
-            serde_json::json!({"name": "Charlie", "age": 35}),
-        ];
-
-        let result = db.batch_insert("tenant1", "person", people);
-        assert!(result.is_err());
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Diana", "age": 28})).unwrap();
 
-        // No entities should be inserted (atomic rollback)
-        let entities = db.list("tenant1", "person", false, None).unwrap();
-        assert_eq!(entities.len(), 0);
+        // Test LIMIT
+        let result = db.query_sql("tenant1", "SELECT * FROM person LIMIT 2").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 2);
     }
 
     #[test]
-    fn test_batch_insert_deterministic_uuids() {
+    fn test_query_sql_count() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema with key_field
         let schema = serde_json::json!({
-            "title": "User",
+            "title": "Person",
             "version": "1.0.0",
-            "short_name": "user",
-            "json_schema_extra": {
-                "key_field": "email"
-            },
+            "short_name": "person",
             "properties": {
-                "email": {"type": "string"},
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"}
             },
-            "required": ["email", "name"]
+            "required": ["name"]
         });
 
-        db.register_schema("user", schema).unwrap();
-
-        // Batch insert
-        let users = vec![
-            serde_json::json!({"email": "alice@example.com", "name": "Alice"}),
-            serde_json::json!({"email": "bob@example.com", "name": "Bob"}),
-        ];
-
-        let ids = db.batch_insert("tenant1", "user", users).unwrap();
-
-        // Insert same data again
-        let users2 = vec![
-            serde_json::json!({"email": "alice@example.com", "name": "Alice"}),
-            serde_json::json!({"email": "bob@example.com", "name": "Bob"}),
-        ];
+        db.register_schema("person", schema).unwrap();
 
-        let ids2 = db.batch_insert("tenant1", "user", users2).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
 
-        // Same data should generate same UUIDs
-        assert_eq!(ids, ids2);
+        // Test COUNT(*)
+        let result = db.query_sql("tenant1", "SELECT COUNT(*) FROM person").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["count"], 3);
     }
 
     #[test]
-    fn test_count() {
+    fn test_query_sql_avg() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Initially zero
-        assert_eq!(db.count("tenant1", "person", false).unwrap(), 0);
-
-        // Insert entities
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 20})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 40})).unwrap();
 
-        // Count should be 3
-        assert_eq!(db.count("tenant1", "person", false).unwrap(), 3);
+        // Test AVG
+        let result = db.query_sql("tenant1", "SELECT AVG(age) FROM person").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["avg"], 30.0);
     }
 
     #[test]
-    fn test_count_excludes_deleted() {
+    fn test_query_sql_min_max() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let id1 = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-
-        // Soft delete one
-        db.delete("tenant1", id1).unwrap();
-
-        // Count without deleted
-        assert_eq!(db.count("tenant1", "person", false).unwrap(), 1);
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
 
-        // Count with deleted
-        assert_eq!(db.count("tenant1", "person", true).unwrap(), 2);
+        // Test MIN and MAX
+        let result = db.query_sql("tenant1", "SELECT MIN(age), MAX(age) FROM person").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["min"], 25.0);
+        assert_eq!(rows[0]["max"], 35.0);
     }
 
     #[test]
-    fn test_exists() {
+    fn test_query_sql_sum() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entity
-        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-
-        // Should exist
-        assert!(db.exists("tenant1", id).unwrap());
-
-        // Non-existent UUID
-        assert!(!db.exists("tenant1", uuid::Uuid::new_v4()).unwrap());
-
-        // Hard delete
-        db.hard_delete("tenant1", id).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
 
-        // Should not exist
-        assert!(!db.exists("tenant1", id).unwrap());
+        // Test SUM
+        let result = db.query_sql("tenant1", "SELECT SUM(age) FROM person").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["sum"], 90.0);
     }
 
     #[test]
-    fn test_exists_with_soft_delete() {
+    fn test_query_sql_aggregate_with_where() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {
-                "name": {"type": "string"}
+                "name": {"type": "string"},
+                "age": {"type": "number"},
+                "role": {"type": "string"}
             },
             "required": ["name"]
         });
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entity
-        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-
-        // Soft delete
-        db.delete("tenant1", id).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30, "role": "engineer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25, "role": "designer"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35, "role": "engineer"})).unwrap();
 
-        // Should still exist (soft deleted)
-        assert!(db.exists("tenant1", id).unwrap());
+        // Test aggregate with WHERE filter
+        let result = db.query_sql("tenant1", "SELECT COUNT(*), AVG(age) FROM person WHERE role = 'engineer'").unwrap();
+        let rows = result.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["count"], 2);
+        assert_eq!(rows[0]["avg"], 32.5);
     }
 
     #[test]
-    fn test_add_edge() {
+    fn test_query_sql_invalid_syntax() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2580,23 +9994,15 @@ mod tests {
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-
-        // Add edge
-        let edge = db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
-
-        assert_eq!(edge.src, alice_id);
-        assert_eq!(edge.dst, bob_id);
-        assert_eq!(edge.rel_type, "knows");
+        // Invalid SQL should return error
+        let result = db.query_sql("tenant1", "INVALID SQL QUERY");
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_add_edge_with_properties() {
+    fn test_query_sql_no_joins() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2607,156 +10013,339 @@ mod tests {
 
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-
-        // Add edge with properties
-        let props = serde_json::json!({"weight": 0.8, "since": "2020-01-01"});
-        let edge = db.add_edge("tenant1", alice_id, bob_id, "knows", Some(props)).unwrap();
-
-        assert_eq!(edge.data.properties.get("weight").unwrap(), &serde_json::json!(0.8));
-        assert_eq!(edge.data.properties.get("since").unwrap(), "2020-01-01");
+        // JOIN should be rejected
+        let result = db.query_sql("tenant1", "SELECT * FROM person JOIN company ON person.company_id = company.id");
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_add_edge_nonexistent_entity() {
-        let db = Database::open_temp().unwrap();
+    fn test_query_sql_cursor_streams_pages() {
+        // Scan limit well below the table size: streaming must not hit it
+        let db = Database::open_temp().unwrap().with_max_scan_rows(50);
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {"name": {"type": "string"}},
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
 
-        // Insert one entity
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        for i in 0..250 {
+            db.insert("tenant1", "person", serde_json::json!({
+                "name": format!("Person{}", i),
+                "age": i
+            })).unwrap();
+        }
 
-        // Try to add edge to nonexistent entity
-        let result = db.add_edge("tenant1", alice_id, uuid::Uuid::new_v4(), "knows", None);
-        assert!(result.is_err());
+        let mut cursor = db.query_sql_cursor("tenant1", "SELECT name FROM person", 100).unwrap();
+        assert!(cursor.is_streaming());
+
+        // First page is fetched alone; the rest stays in storage
+        let first = cursor.next_page(&db).unwrap().unwrap();
+        assert_eq!(first.len(), 100);
+        assert!(!cursor.is_exhausted());
+        assert!(first[0].get("age").is_none());
+
+        let mut names: std::collections::HashSet<String> = first
+            .iter()
+            .map(|row| row["name"].as_str().unwrap().to_string())
+            .collect();
+        let mut pages = 1;
+        while let Some(page) = cursor.next_page(&db).unwrap() {
+            pages += 1;
+            names.extend(page.iter().map(|row| row["name"].as_str().unwrap().to_string()));
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(names.len(), 250);
+        assert!(cursor.next_page(&db).unwrap().is_none());
+
+        // WHERE, OFFSET, and LIMIT apply across pages
+        let mut cursor = db
+            .query_sql_cursor("tenant1", "SELECT * FROM person WHERE age >= 200 LIMIT 30 OFFSET 10", 16)
+            .unwrap();
+        let mut rows = Vec::new();
+        while let Some(page) = cursor.next_page(&db).unwrap() {
+            rows.extend(page);
+        }
+        assert_eq!(rows.len(), 30);
+        assert!(rows.iter().all(|row| row["age"].as_i64().unwrap() >= 200));
+
+        // ORDER BY needs the full result, so it is materialized (and scan-limited)
+        let result = db.query_sql_cursor("tenant1", "SELECT * FROM person ORDER BY age", 100);
+        assert!(matches!(result, Err(DatabaseError::ScanLimitExceeded { .. })));
     }
 
     #[test]
-    fn test_get_edges() {
-        let db = Database::open_temp().unwrap();
+    fn test_query_sql_scan_limit() {
+        let db = Database::open_temp().unwrap().with_max_scan_rows(10);
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {"name": {"type": "string"}},
-            "required": ["name"]
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
+            "required": ["name"],
+            "json_schema_extra": {
+                "key_field": "name"
+            }
         });
-
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-        let charlie_id = db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+        for i in 0..15 {
+            db.insert("tenant1", "person", serde_json::json!({
+                "name": format!("Person{}", i),
+                "age": 20 + i
+            })).unwrap();
+        }
 
-        // Add edges
-        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
-        db.add_edge("tenant1", alice_id, charlie_id, "knows", None).unwrap();
-        db.add_edge("tenant1", alice_id, bob_id, "likes", None).unwrap();
+        // Non-indexed predicate scans the whole table
+        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30");
+        match result {
+            Err(DatabaseError::ScanLimitExceeded { table, limit }) => {
+                assert_eq!(table, "person");
+                assert_eq!(limit, 10);
+            }
+            other => panic!("expected ScanLimitExceeded, got {:?}", other),
+        }
+        let message = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("index"));
+
+        // Key field equality uses the key index
+        let results = db.query_sql("tenant1", "SELECT * FROM person WHERE name = 'Person3'").unwrap();
+        let rows = results.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["age"], 23);
+
+        // Explicit override allows the large scan
+        let results = db.query_sql_with_scan_limit(
+            "tenant1",
+            "SELECT * FROM person WHERE age > 30",
+            None,
+        ).unwrap();
+        assert_eq!(results.as_array().unwrap().len(), 4);
+    }
 
-        // Get all outgoing edges
-        let edges = db.get_edges(alice_id, None).unwrap();
-        assert_eq!(edges.len(), 3);
+    #[test]
+    fn test_query_sql_index_and_limit_pushdown() {
+        let db = Database::open_temp().unwrap().with_max_scan_rows(10);
 
-        // Get only "knows" edges
-        let knows_edges = db.get_edges(alice_id, Some("knows")).unwrap();
-        assert_eq!(knows_edges.len(), 2);
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"},
+                "city": {"type": "string"}
+            },
+            "required": ["name"],
+            "json_schema_extra": {"indexed_fields": ["city", "age"]}
+        });
+        db.register_schema("person", schema).unwrap();
 
-        // Get only "likes" edges
-        let likes_edges = db.get_edges(alice_id, Some("likes")).unwrap();
-        assert_eq!(likes_edges.len(), 1);
+        for i in 0..30 {
+            db.insert("tenant1", "person", serde_json::json!({
+                "name": format!("Person{}", i),
+                "age": 20 + i % 5,
+                "city": if i < 3 { "Oslo" } else { "Lima" }
+            })).unwrap();
+        }
+
+        // Indexed equality reads only the matching rows
+        let rows = db.query_sql("tenant1", "SELECT name FROM person WHERE city = 'Oslo'").unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 3);
+        let rows = db.query_sql("tenant1", "SELECT * FROM person WHERE age = 21.0 AND city = 'Oslo'").unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 1);
+        let rows = db.query_sql("tenant1", "SELECT * FROM person WHERE age = 21.0").unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 6);
+
+        // LIMIT without ORDER BY stops scanning early
+        let rows = db.query_sql("tenant1", "SELECT * FROM person LIMIT 5").unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 5);
+        let rows = db.query_sql("tenant1", "SELECT * FROM person WHERE age >= 20 LIMIT 3 OFFSET 2").unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 3);
+
+        // A LIMIT past the cap, or ORDER BY, still needs the full scan
+        assert!(matches!(
+            db.query_sql("tenant1", "SELECT * FROM person LIMIT 20"),
+            Err(DatabaseError::ScanLimitExceeded { .. })
+        ));
+        assert!(matches!(
+            db.query_sql("tenant1", "SELECT * FROM person ORDER BY age LIMIT 5"),
+            Err(DatabaseError::ScanLimitExceeded { .. })
+        ));
     }
 
     #[test]
-    fn test_get_incoming_edges() {
+    fn test_query_sql_key_lookup() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
-        let schema = serde_json::json!({
+        db.register_schema("person", serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
             "properties": {"name": {"type": "string"}},
+            "required": ["name"],
+            "json_schema_extra": {"key_field": "name", "key_case_insensitive": true}
+        })).unwrap();
+
+        let upper = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let lower = db.insert("tenant1", "person", serde_json::json!({"name": "alice"})).unwrap();
+        let names = |sql: &str| db.query_sql("tenant1", sql).unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        // Both casings share an index value; each query finds its own entity
+        assert_eq!(names("SELECT name FROM person WHERE name = 'Alice'"), vec!["Alice"]);
+        assert_eq!(names("SELECT name FROM person WHERE name = 'alice'"), vec!["alice"]);
+
+        // Soft-deleted entities are not returned
+        db.delete("tenant1", upper).unwrap();
+        assert!(names("SELECT name FROM person WHERE name = 'Alice'").is_empty());
+        db.delete("tenant1", lower).unwrap();
+        assert!(names("SELECT name FROM person WHERE name = 'alice'").is_empty());
+    }
+
+    #[test]
+    fn test_query_cache_respects_scan_limit() {
+        let db = Database::open_temp().unwrap().with_max_scan_rows(10).with_query_cache(16);
+
+        let schema = serde_json::json!({
+            "title": "Person",
+            "version": "1.0.0",
+            "short_name": "person",
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-        let charlie_id = db.insert("tenant1", "person", serde_json::json!({"name": "Charlie"})).unwrap();
+        for i in 0..15 {
+            db.insert("tenant1", "person", serde_json::json!({"name": format!("Person{}", i), "age": 20 + i})).unwrap();
+        }
 
-        // Add edges (multiple entities pointing to Bob)
-        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
-        db.add_edge("tenant1", charlie_id, bob_id, "knows", None).unwrap();
+        // An unbounded result is cached, but doesn't answer a bounded call
+        let sql = "SELECT * FROM person WHERE age > 30";
+        assert_eq!(db.query_sql_with_scan_limit("tenant1", sql, None).unwrap().as_array().unwrap().len(), 4);
+        assert!(matches!(db.query_sql("tenant1", sql), Err(DatabaseError::ScanLimitExceeded { .. })));
+        assert_eq!(db.query_cache_stats().unwrap().hits, 0);
 
-        // Get incoming edges to Bob
-        let edges = db.get_incoming_edges(bob_id, None).unwrap();
-        assert_eq!(edges.len(), 2);
+        // Same limit hits the cache
+        db.query_sql_with_scan_limit("tenant1", sql, None).unwrap();
+        assert_eq!(db.query_cache_stats().unwrap().hits, 1);
+    }
 
-        // Check sources
-        let sources: Vec<uuid::Uuid> = edges.iter().map(|e| e.src).collect();
-        assert!(sources.contains(&alice_id));
-        assert!(sources.contains(&charlie_id));
+    #[test]
+    fn test_query_cache_expires_with_entities() {
+        let db = Database::open_temp().unwrap().with_query_cache(16);
+
+        db.register_schema("sessions", serde_json::json!({
+            "title": "Session",
+            "version": "1.0.0",
+            "short_name": "sessions",
+            "properties": {"user": {"type": "string"}},
+            "required": ["user"]
+        })).unwrap();
+
+        db.insert("tenant1", "sessions", serde_json::json!({"user": "alice"})).unwrap();
+        db.insert_with_ttl("tenant1", "sessions", serde_json::json!({"user": "bob"}), std::time::Duration::from_millis(200)).unwrap();
+
+        let count = || db.query_sql("tenant1", "SELECT COUNT(*) FROM sessions").unwrap()[0]["count"].clone();
+        assert_eq!(count(), 2);
+        assert_eq!(count(), 2);
+        assert_eq!(db.query_cache_stats().unwrap().hits, 1);
+
+        // Once bob's session expires the cached count is stale
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        assert_eq!(count(), 1);
+        assert_eq!(db.query_cache_stats().unwrap().hits, 1);
     }
 
     #[test]
-    fn test_delete_edge() {
-        let db = Database::open_temp().unwrap();
+    fn test_query_cache_invalidation() {
+        let db = Database::open_temp().unwrap().with_query_cache(16);
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {"name": {"type": "string"}},
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"}
+            },
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-
-        // Add edge
-        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 35})).unwrap();
 
-        // Verify edge exists
-        let edges = db.get_edges(alice_id, None).unwrap();
-        assert_eq!(edges.len(), 1);
+        let sql = "SELECT * FROM person WHERE age > 30";
+        let first = db.query_sql("tenant1", sql).unwrap();
+        assert_eq!(first.as_array().unwrap().len(), 1);
 
-        // Delete edge
-        db.delete_edge(alice_id, bob_id, "knows").unwrap();
+        // Same query (modulo whitespace) served from cache
+        let second = db.query_sql("tenant1", "SELECT *  FROM person WHERE age > 30;").unwrap();
+        assert_eq!(first, second);
+        let stats = db.query_cache_stats().unwrap();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
 
-        // Verify edge is gone
-        let edges = db.get_edges(alice_id, None).unwrap();
-        assert_eq!(edges.len(), 0);
+        // Write to the table invalidates the cached result
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 40})).unwrap();
+        assert_eq!(db.query_cache_stats().unwrap().entries, 0);
 
-        // Also gone from reverse index
-        let incoming = db.get_incoming_edges(bob_id, None).unwrap();
-        assert_eq!(incoming.len(), 0);
+        let third = db.query_sql("tenant1", sql).unwrap();
+        assert_eq!(third.as_array().unwrap().len(), 2);
+        assert_eq!(db.query_cache_stats().unwrap().hits, 1);
     }
 
     #[test]
-    fn test_bidirectional_edges() {
-        let db = Database::open_temp().unwrap();
+    fn test_insert_emits_span() {
+        use std::collections::HashMap;
+        use std::sync::Mutex;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id};
+        use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
 
-        // Register schema
+        type Captured = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+        struct CaptureLayer(Captured);
+        struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+        impl Visit for FieldVisitor<'_> {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.insert(field.name().to_string(), value.to_string());
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.insert(field.name().to_string(), format!("{:?}", value));
+            }
+        }
+
+        impl<S: tracing::Subscriber> Layer<S> for CaptureLayer {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+                let mut fields = HashMap::new();
+                attrs.record(&mut FieldVisitor(&mut fields));
+                self.0.lock().unwrap().push(fields);
+            }
+        }
+
+        let db = Database::open_temp().unwrap();
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2764,71 +10353,126 @@ mod tests {
             "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
 
-        // Insert entities
-        let alice_id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
-        let bob_id = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
-
-        // Add edge
-        db.add_edge("tenant1", alice_id, bob_id, "knows", None).unwrap();
+        let captured: Captured = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer(captured.clone()));
 
-        // Query from both directions
-        let outgoing = db.get_edges(alice_id, None).unwrap();
-        let incoming = db.get_incoming_edges(bob_id, None).unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        });
 
-        assert_eq!(outgoing.len(), 1);
-        assert_eq!(incoming.len(), 1);
+        let spans = captured.lock().unwrap();
+        let insert_span = spans
+            .iter()
+            .find(|fields| fields.get("db.operation.name").map(String::as_str) == Some("insert"))
+            .expect("insert span not captured");
 
-        // Both should have same data
-        assert_eq!(outgoing[0].src, incoming[0].src);
-        assert_eq!(outgoing[0].dst, incoming[0].dst);
-        assert_eq!(outgoing[0].rel_type, incoming[0].rel_type);
+        assert_eq!(insert_span.get("db.collection.name").map(String::as_str), Some("person"));
+        assert_eq!(insert_span.get("db.namespace").map(String::as_str), Some("tenant1"));
     }
 
     #[test]
-    fn test_traverse_bfs() {
+    fn test_get_by_key_batch() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
-            "title": "Person",
+            "title": "User",
             "version": "1.0.0",
-            "short_name": "person",
-            "properties": {"name": {"type": "string"}},
-            "required": ["name"]
+            "short_name": "user",
+            "json_schema_extra": {
+                "key_field": "email"
+            },
+            "properties": {
+                "email": {"type": "string"},
+                "username": {"type": "string"}
+            },
+            "required": ["email", "username"]
         });
+        db.register_schema("user", schema).unwrap();
 
-        db.register_schema("person", schema).unwrap();
-
-        // Create a graph: A -> B -> C -> D
-        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
-        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
-        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
-        let d = db.insert("tenant1", "person", serde_json::json!({"name": "D"})).unwrap();
+        let alice = db.insert("tenant1", "user", serde_json::json!({
+            "email": "alice@example.com", "username": "alice"
+        })).unwrap();
+        let bob = db.insert("tenant1", "user", serde_json::json!({
+            "email": "bob@example.com", "username": "bob"
+        })).unwrap();
+        let carol = db.insert("tenant1", "user", serde_json::json!({
+            "email": "carol@example.com", "username": "carol"
+        })).unwrap();
+
+        let results = db.get_by_key_batch("tenant1", "user", &[
+            "bob@example.com",
+            "missing@example.com",
+            "alice@example.com",
+            "nobody@example.com",
+            "carol@example.com",
+        ]).unwrap();
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap().system.id, bob);
+        assert!(results[1].is_none());
+        assert_eq!(results[2].as_ref().unwrap().system.id, alice);
+        assert!(results[3].is_none());
+        assert_eq!(results[4].as_ref().unwrap().system.id, carol);
+
+        // Scoped to table: other schemas never match
+        let results = db.get_by_key_batch("tenant1", "person", &["alice@example.com"]).unwrap();
+        assert!(results[0].is_none());
+    }
 
-        db.add_edge("tenant1", a, b, "knows", None).unwrap();
-        db.add_edge("tenant1", b, c, "knows", None).unwrap();
-        db.add_edge("tenant1", c, d, "knows", None).unwrap();
+    #[test]
+    fn test_schema_info() {
+        use crate::schema::SchemaCategory;
 
-        // BFS from A with depth 2 should find A, B, C
-        let result = db.traverse_bfs(a, crate::graph::TraversalDirection::Out, 2, None).unwrap();
-        assert_eq!(result.len(), 3);
-        assert_eq!(result[0], a);
-        assert_eq!(result[1], b);
-        assert_eq!(result[2], c);
+        let db = Database::open_temp().unwrap();
 
-        // BFS from A with depth 3 should find all
-        let result = db.traverse_bfs(a, crate::graph::TraversalDirection::Out, 3, None).unwrap();
-        assert_eq!(result.len(), 4);
+        let schema = serde_json::json!({
+            "title": "Article",
+            "description": "Published articles",
+            "version": "1.2.0",
+            "short_name": "article",
+            "properties": {
+                "title": {"type": "string"},
+                "content": {"type": "string"},
+                "category": {"type": "string"}
+            },
+            "required": ["title"],
+            "json_schema_extra": {
+                "key_field": "title",
+                "indexed_fields": ["category"],
+                "embedding_fields": ["content"],
+                "embedding_provider": "default",
+                "category": "public"
+            }
+        });
+        db.register_schema("article", schema).unwrap();
+
+        let info = db.schema_info("article").unwrap();
+        assert_eq!(info.name, "article");
+        assert_eq!(info.version, "1.2.0");
+        assert_eq!(info.short_name.as_deref(), Some("article"));
+        assert_eq!(info.description.as_deref(), Some("Published articles"));
+        assert_eq!(info.category, SchemaCategory::Public);
+        assert_eq!(info.key_field.as_deref(), Some("title"));
+        assert_eq!(info.indexed_fields, vec!["category"]);
+        assert_eq!(info.embedding_fields, vec!["content"]);
+        assert_eq!(info.embedding_provider.as_deref(), Some("default"));
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["category"], "public");
+
+        let all = db.all_schema_info().unwrap();
+        assert!(all.iter().any(|i| i == &info));
+        assert!(all.windows(2).all(|w| w[0].name <= w[1].name));
+
+        assert!(matches!(db.schema_info("missing"), Err(DatabaseError::SchemaNotFound(_))));
     }
 
     #[test]
-    fn test_traverse_dfs() {
+    fn test_get_by_key_fuzzy() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2836,29 +10480,53 @@ mod tests {
             "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
 
-        // Create a graph: A -> B, A -> C
-        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
-        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
-        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
-
-        db.add_edge("tenant1", a, b, "knows", None).unwrap();
-        db.add_edge("tenant1", a, c, "knows", None).unwrap();
+        let alice = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
 
-        // DFS from A should find all 3
-        let result = db.traverse_dfs(a, crate::graph::TraversalDirection::Out, 2, None).unwrap();
-        assert_eq!(result.len(), 3);
-        assert_eq!(result[0], a);
-        // B and C order depends on edge insertion order
+        // Exact match returns distance 0
+        let matches = db.get_by_key_fuzzy("tenant1", "person", "Alice", 2).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.system.id, alice);
+        assert_eq!(matches[0].1, 0);
+
+        // One-character typo matches at distance 1
+        let matches = db.get_by_key_fuzzy("tenant1", "person", "Alise", 1).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0.system.id, alice);
+        assert_eq!(matches[0].1, 1);
+
+        // Distant string returns nothing
+        let matches = db.get_by_key_fuzzy("tenant1", "person", "Zachary", 2).unwrap();
+        assert!(matches.is_empty());
+
+        // Case-insensitive keys index each entity twice; each is returned once
+        db.register_schema("member", serde_json::json!({
+            "title": "Member",
+            "version": "1.0.0",
+            "short_name": "member",
+            "json_schema_extra": {"key_field": "name", "key_case_insensitive": true},
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        })).unwrap();
+        let alice = db.insert("tenant1", "member", serde_json::json!({"name": "Alice"})).unwrap();
+        let alicf = db.insert("tenant1", "member", serde_json::json!({"name": "Alicf"})).unwrap();
+
+        let matches = db.get_by_key_fuzzy("tenant1", "member", "Alicd", 1).unwrap();
+        let mut ids: Vec<_> = matches.iter().map(|(entity, _)| entity.system.id).collect();
+        ids.sort();
+        let mut expected = vec![alice, alicf];
+        expected.sort();
+        assert_eq!(ids, expected);
     }
 
     #[test]
-    fn test_traverse_incoming() {
+    fn test_resolve_edge_destinations() {
+        use crate::llm::LlmEdgeBuilder;
+
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2866,30 +10534,52 @@ mod tests {
             "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
-
-        // Create a graph: A -> C, B -> C
-        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
-        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
-        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
-
-        db.add_edge("tenant1", a, c, "knows", None).unwrap();
-        db.add_edge("tenant1", b, c, "knows", None).unwrap();
-
-        // Traverse incoming from C should find C, A, B
-        let result = db.traverse_bfs(c, crate::graph::TraversalDirection::In, 2, None).unwrap();
-        assert_eq!(result.len(), 3);
-        assert_eq!(result[0], c);
-        assert!(result.contains(&a));
-        assert!(result.contains(&b));
+        let alice = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+
+        // Canned LLM output with name destinations
+        let response = r#"{
+            "edges": [
+                {"dst": "Alice", "rel_type": "authored_by", "properties": {"confidence": 0.9}, "created_at": "2024-01-15T10:00:00Z"},
+                {"dst": "Unknown Corp", "rel_type": "funded_by", "properties": {"confidence": 0.8}, "created_at": "2024-01-15T10:00:00Z"}
+            ],
+            "summary": {"total_edges": 2, "relationship_types": ["authored_by", "funded_by"], "avg_confidence": 0.85}
+        }"#;
+        let mut plan = LlmEdgeBuilder::parse_edge_plan(response).unwrap();
+
+        let resolved = db.resolve_edge_destinations("tenant1", &mut plan.edges, None).unwrap();
+        assert_eq!(resolved, 1);
+
+        assert_eq!(plan.edges[0].resolved_id, Some(alice));
+        assert_eq!(plan.edges[0].resolution_confidence, Some(1.0));
+        assert_eq!(plan.edges[0].to_inline_edge().unwrap().dst, alice);
+
+        assert_eq!(plan.edges[1].resolved_id, None);
+        assert!(plan.edges[1].to_inline_edge().is_err());
+
+        // Typo resolves fuzzily with reduced confidence
+        let mut plan = LlmEdgeBuilder::parse_edge_plan(response).unwrap();
+        plan.edges[0].dst = "Alise".to_string();
+        db.resolve_edge_destinations("tenant1", &mut plan.edges, None).unwrap();
+        assert_eq!(plan.edges[0].resolved_id, Some(alice));
+        assert!(plan.edges[0].resolution_confidence.unwrap() < 1.0);
+
+        // Placeholder table creates an entity for the unmatched destination
+        let resolved = db.resolve_edge_destinations("tenant1", &mut plan.edges, Some("person")).unwrap();
+        assert_eq!(resolved, 2);
+        let placeholder = plan.edges[1].resolved_id.unwrap();
+        assert_eq!(plan.edges[1].resolution_confidence, Some(0.0));
+        assert_eq!(plan.edges[1].properties["placeholder"], true);
+        assert_eq!(db.get("tenant1", placeholder).unwrap().unwrap().properties["name"], "Unknown Corp");
     }
 
     #[test]
-    fn test_shortest_path() {
+    fn test_apply_edge_plan_dry_run() {
+        use crate::llm::LlmEdgeBuilder;
+        use crate::types::InlineEdge;
+
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
@@ -2897,467 +10587,1246 @@ mod tests {
             "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
+        let doc = db.insert("tenant1", "person", serde_json::json!({"name": "Design Doc"})).unwrap();
+        let alice = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+
+        // Pre-existing edge doc -> Alice
+        db.update_with("tenant1", doc, |entity| {
+            entity.add_edge(InlineEdge::new(alice, "authored_by".to_string()));
+            Ok(())
+        }).unwrap();
+        let before = db.get("tenant1", doc).unwrap().unwrap();
+
+        let response = r#"{
+            "edges": [
+                {"dst": "Alice", "rel_type": "authored_by", "properties": {"confidence": 0.9}, "created_at": "2024-01-15T10:00:00Z"},
+                {"dst": "Bob", "rel_type": "reviewed_by", "properties": {"confidence": 0.8}, "created_at": "2024-01-15T10:00:00Z"},
+                {"dst": "Unknown Corp", "rel_type": "funded_by", "properties": {"confidence": 0.8}, "created_at": "2024-01-15T10:00:00Z"}
+            ],
+            "summary": {"total_edges": 3, "relationship_types": ["authored_by", "reviewed_by", "funded_by"], "avg_confidence": 0.83}
+        }"#;
+        let mut plan = LlmEdgeBuilder::parse_edge_plan(response).unwrap();
+
+        let report = db.apply_edge_plan("tenant1", doc, &mut plan, Some("person"), true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.merged.iter().map(|e| e.resolved_id).collect::<Vec<_>>(), vec![Some(alice)]);
+        assert_eq!(report.created.iter().map(|e| e.resolved_id).collect::<Vec<_>>(), vec![Some(bob)]);
+        assert_eq!(report.unresolved.iter().map(|e| e.dst.as_str()).collect::<Vec<_>>(), vec!["Unknown Corp"]);
+
+        // Nothing written: no new edges, no placeholder entity
+        let after = db.get("tenant1", doc).unwrap().unwrap();
+        assert_eq!(after.system.edges.len(), 1);
+        assert_eq!(after.system.modified_at, before.system.modified_at);
+        assert_eq!(db.list("tenant1", "person", false, None, None).unwrap().len(), 3);
+
+        // Applying for real writes what the dry run reported
+        let report = db.apply_edge_plan("tenant1", doc, &mut plan, None, false).unwrap();
+        assert!(!report.dry_run);
+        assert_eq!((report.created.len(), report.merged.len(), report.unresolved.len()), (1, 1, 1));
+        let edges = db.get("tenant1", doc).unwrap().unwrap().system.edges;
+        assert_eq!(edges.len(), 2);
+        assert!(edges.iter().any(|e| e.dst == bob && e.rel_type == "reviewed_by"));
+    }
 
-        // Create a graph: A -> B -> C -> D
-        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
-        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
-        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
-        let d = db.insert("tenant1", "person", serde_json::json!({"name": "D"})).unwrap();
+    #[tokio::test]
+    async fn test_search_result_ranking() {
+        use crate::index::SearchMode;
 
-        db.add_edge("tenant1", a, b, "knows", None).unwrap();
-        db.add_edge("tenant1", b, c, "knows", None).unwrap();
-        db.add_edge("tenant1", c, d, "knows", None).unwrap();
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
+            "version": "1.0.0",
+            "short_name": "point",
+            "properties": {
+                "name": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["name"]
+        })).unwrap();
+
+        // Inserted out of similarity order
+        for (name, angle) in [("far", 1.2f64), ("near", 0.1), ("mid", 0.6), ("exact", 0.0)] {
+            db.insert("tenant1", "point", serde_json::json!({
+                "name": name,
+                "embedding": [angle.cos(), angle.sin()]
+            })).unwrap();
+        }
 
-        // Find path from A to D
-        let path = db.shortest_path(a, d, crate::graph::TraversalDirection::Out, 5).unwrap();
-        assert_eq!(path.len(), 4);
-        assert_eq!(path[0], a);
-        assert_eq!(path[1], b);
-        assert_eq!(path[2], c);
-        assert_eq!(path[3], d);
+        let results = db.search_by_vector("tenant1", "point", &[1.0, 0.0], 4, None, SearchMode::default()).await.unwrap();
 
-        // No path exists (wrong direction)
-        let path = db.shortest_path(d, a, crate::graph::TraversalDirection::Out, 5).unwrap();
-        assert_eq!(path.len(), 0);
+        let names: Vec<&str> = results.iter().map(|r| r.entity.properties["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["exact", "near", "mid", "far"]);
+        assert_eq!(results.iter().map(|r| r.rank).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        // Tuple shim for older callers
+        let (entity, score): (Entity, f32) = results[0].clone().into();
+        assert_eq!(entity.properties["name"], "exact");
+        assert!((score - 1.0).abs() < 1e-4);
+    }
+
+    #[tokio::test]
+    async fn test_fit_dimensionality_reduction() {
+        use crate::index::SearchMode;
+        use rand::{Rng, SeedableRng};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+
+        // 128-dim vectors on a 16-dim subspace
+        let basis: Vec<Vec<f64>> = (0..16)
+            .map(|_| (0..128).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+        let vectors: Vec<Vec<f64>> = (0..200)
+            .map(|_| {
+                let weights: Vec<f64> = (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                (0..128).map(|i| basis.iter().zip(&weights).map(|(b, w)| b[i] * w).sum()).collect()
+            })
+            .collect();
+
+        {
+            let db = Database::open(dir.path()).unwrap();
+            db.register_schema("point", serde_json::json!({
+                "title": "Point",
+                "version": "1.0.0",
+                "short_name": "point",
+                "properties": {
+                    "name": {"type": "string"},
+                    "embedding": {"type": "array", "items": {"type": "number"}}
+                },
+                "required": ["name"]
+            })).unwrap();
+
+            let rows = vectors
+                .iter()
+                .enumerate()
+                .map(|(i, v)| serde_json::json!({"name": format!("p{}", i), "embedding": v}))
+                .collect();
+            db.batch_insert("tenant1", "point", rows).unwrap();
+
+            assert!(db.fit_dimensionality_reduction("point", 128).is_err());
+
+            let report = db.fit_dimensionality_reduction("point", 32).unwrap();
+            assert_eq!((report.source_dim, report.target_dim), (128, 32));
+            assert_eq!((report.sampled, report.rewritten), (200, 200));
+            assert!(report.explained_variance > 0.99);
+
+            // Already reduced
+            assert!(db.fit_dimensionality_reduction("point", 16).is_err());
+
+            let stored = db.list("tenant1", "point", false, None, None).unwrap();
+            assert!(stored.iter().all(|e| e.get_embedding().unwrap().len() == 32));
+        }
+
+        // The projection survives a reopen and applies to new inserts and queries
+        let db = Database::open(dir.path()).unwrap();
+        assert_eq!(db.dimensionality_reduction("point").unwrap().target_dim(), 32);
+
+        let id = db.insert("tenant1", "point", serde_json::json!({"name": "new", "embedding": vectors[7]})).unwrap();
+        assert_eq!(db.get("tenant1", id).unwrap().unwrap().get_embedding().unwrap().len(), 32);
+
+        let query: Vec<f32> = vectors[42].iter().map(|x| *x as f32).collect();
+        let results = db.search_by_vector("tenant1", "point", &query, 1, None, SearchMode::Accurate).await.unwrap();
+        assert_eq!(results[0].entity.properties["name"], "p42");
     }
 
     #[test]
-    fn test_shortest_path_not_found() {
-        let db = Database::open_temp().unwrap();
+    fn test_fit_dimensionality_reduction_resumes() {
+        use crate::embeddings::PcaProjection;
+        use crate::storage::column_families::{CF_EMBEDDINGS, CF_ENTITIES};
+        use crate::storage::keys::{encode_entity_key, encode_pending_projection_key};
 
-        // Register schema
-        let schema = serde_json::json!({
-            "title": "Person",
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
             "version": "1.0.0",
-            "short_name": "person",
-            "properties": {"name": {"type": "string"}},
+            "short_name": "point",
+            "properties": {
+                "name": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
             "required": ["name"]
-        });
+        })).unwrap();
 
-        db.register_schema("person", schema).unwrap();
+        let vectors: Vec<Vec<f32>> = (0..10)
+            .map(|i| vec![i as f32, 2.0 * i as f32, 1.0, (i % 3) as f32])
+            .collect();
+        let ids: Vec<uuid::Uuid> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| db.insert("tenant1", "point", serde_json::json!({"name": format!("p{}", i), "embedding": v})).unwrap())
+            .collect();
 
-        // Create two disconnected nodes
-        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
-        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
+        // Simulate a rewrite that stopped after the first entity
+        let projection = PcaProjection::fit(&vectors, 2).unwrap();
+        db.provider.put(CF_EMBEDDINGS, &encode_pending_projection_key("point"), &serde_json::to_vec(&projection).unwrap()).unwrap();
+        let mut first = db.get("tenant1", ids[0]).unwrap().unwrap();
+        first.properties["embedding"] = serde_json::json!(projection.project(&vectors[0]));
+        db.storage.put(CF_ENTITIES, &encode_entity_key("tenant1", ids[0]), &serde_json::to_vec(&first).unwrap()).unwrap();
+
+        // Not active until the rewrite finishes
+        assert!(db.dimensionality_reduction("point").is_none());
+        assert!(matches!(
+            db.fit_dimensionality_reduction("point", 3),
+            Err(DatabaseError::ConfigError(_))
+        ));
+
+        let report = db.fit_dimensionality_reduction("point", 2).unwrap();
+        assert_eq!((report.sampled, report.rewritten), (0, 9));
+        assert_eq!(db.dimensionality_reduction("point").unwrap().target_dim(), 2);
+        assert!(db.provider.get(CF_EMBEDDINGS, &encode_pending_projection_key("point")).unwrap().is_none());
+
+        let stored = db.list("tenant1", "point", false, None, None).unwrap();
+        assert!(stored.iter().all(|e| e.get_embedding().unwrap().len() == 2));
+    }
 
-        // No path should exist
-        let path = db.shortest_path(a, b, crate::graph::TraversalDirection::Out, 5).unwrap();
-        assert_eq!(path.len(), 0);
+    #[tokio::test]
+    async fn test_index_health() {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
+            "version": "1.0.0",
+            "short_name": "point",
+            "properties": {
+                "name": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["name"]
+        })).unwrap();
+
+        let empty = db.index_health("tenant1", "point").await.unwrap();
+        assert_eq!(empty.entities, 0);
+        assert_eq!(empty.avg_nn_distance, None);
+
+        for (i, angle) in [0.0f64, 0.1, 0.2, 0.3, 0.4].iter().enumerate() {
+            db.insert("tenant1", "point", serde_json::json!({
+                "name": format!("p{}", i),
+                "embedding": [angle.cos(), angle.sin()]
+            })).unwrap();
+        }
+        // One without an embedding, one from a different model
+        db.insert("tenant1", "point", serde_json::json!({"name": "bare"})).unwrap();
+        db.insert("tenant1", "point", serde_json::json!({"name": "wide", "embedding": [0.0, 0.0, 2.0]})).unwrap();
+
+        let health = db.index_health("tenant1", "point").await.unwrap();
+        assert_eq!(health.entities, 7);
+        assert_eq!(health.missing_embeddings, 1);
+        assert!((health.missing_fraction - 1.0 / 7.0).abs() < 1e-6);
+        assert_eq!(health.dimensions, 2);
+        assert_eq!(health.indexed, 5);
+        assert_eq!(health.index_mismatch, 1);
+        assert!(!health.is_fully_indexed());
+        assert!((health.median_norm - 1.0).abs() < 1e-4);
+
+        // Neighbors are 0.1 rad apart: 1 - cos(0.1)
+        let expected = 1.0 - 0.1f32.cos();
+        let distance = health.avg_nn_distance.unwrap();
+        assert!((distance - expected).abs() < 1e-3, "avg nn distance {}", distance);
     }
 
-    #[test]
-    fn test_traverse_with_rel_type_filter() {
+    #[tokio::test]
+    async fn test_search_filter_pushdown() {
+        use crate::index::SearchMode;
+        use crate::query::Predicate;
+
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
-            "title": "Person",
+            "title": "Article",
             "version": "1.0.0",
-            "short_name": "person",
-            "properties": {"name": {"type": "string"}},
-            "required": ["name"]
+            "short_name": "article",
+            "properties": {
+                "title": {"type": "string"},
+                "category": {"type": "string"},
+                "views": {"type": "integer"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["title"],
+            "json_schema_extra": {
+                "key_field": "title",
+                "indexed_fields": ["category"]
+            }
         });
+        db.register_schema("article", schema).unwrap();
+
+        // Most articles are "news"; only two are "tutorial"
+        for i in 0..20 {
+            let category = if i == 3 || i == 7 { "tutorial" } else { "news" };
+            db.insert("tenant1", "article", serde_json::json!({
+                "title": format!("Article {}", i),
+                "category": category,
+                "views": i,
+                "embedding": [1.0, i as f64 / 20.0, 0.0]
+            })).unwrap();
+        }
 
-        db.register_schema("person", schema).unwrap();
+        let query = [1.0f32, 0.0, 0.0];
 
-        // Create a graph with different relationship types
-        let a = db.insert("tenant1", "person", serde_json::json!({"name": "A"})).unwrap();
-        let b = db.insert("tenant1", "person", serde_json::json!({"name": "B"})).unwrap();
-        let c = db.insert("tenant1", "person", serde_json::json!({"name": "C"})).unwrap();
+        // Indexed equality: consults the field index
+        let before = db.field_index().lookup_count();
+        let filter = Predicate::eq("category", serde_json::json!("tutorial"));
+        let results = db.search_by_vector("tenant1", "article", &query, 5, Some(&filter), SearchMode::default()).await.unwrap();
 
-        db.add_edge("tenant1", a, b, "knows", None).unwrap();
-        db.add_edge("tenant1", a, c, "works_with", None).unwrap();
+        assert_eq!(db.field_index().lookup_count(), before + 1);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.entity.properties["category"] == "tutorial"));
+        assert_eq!(results[0].entity.properties["title"], "Article 3");
 
-        // Only follow "knows" edges
-        let result = db.traverse_bfs(a, crate::graph::TraversalDirection::Out, 2, Some("knows")).unwrap();
-        assert_eq!(result.len(), 2); // A and B only
-        assert!(result.contains(&a));
-        assert!(result.contains(&b));
-        assert!(!result.contains(&c));
+        // Non-indexed predicate falls back to post-filtering
+        let filter = Predicate::new("views", crate::query::CompareOp::Gte, serde_json::json!(18));
+        let results = db.search_by_vector("tenant1", "article", &query, 5, Some(&filter), SearchMode::default()).await.unwrap();
+
+        assert_eq!(db.field_index().lookup_count(), before + 1);
+        assert_eq!(results.len(), 2);
     }
 
-    // SQL Query Tests
+    #[tokio::test]
+    async fn test_search_mode_recall() {
+        use crate::index::SearchMode;
+        use std::collections::HashSet;
 
-    #[test]
-    fn test_query_sql_select_all() {
         let db = Database::open_temp().unwrap();
 
-        // Register schema
         let schema = serde_json::json!({
-            "title": "Person",
+            "title": "Point",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "point",
             "properties": {
                 "name": {"type": "string"},
-                "age": {"type": "number"}
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
             "required": ["name"]
         });
+        db.register_schema("point", schema).unwrap();
+
+        // Deterministic pseudo-random vectors (LCG), high enough dimension
+        // that a narrow candidate list misses true neighbors
+        let mut state: u64 = 42;
+        let mut next = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) as f64 / (1u64 << 31) as f64) * 2.0 - 1.0
+        };
+        let dims = 32;
+        let vectors: Vec<Vec<f32>> = (0..2000)
+            .map(|_| (0..dims).map(|_| next() as f32).collect())
+            .collect();
 
-        db.register_schema("person", schema).unwrap();
+        let rows = vectors.iter().enumerate()
+            .map(|(i, v)| serde_json::json!({"name": format!("p{}", i), "embedding": v}))
+            .collect();
+        db.batch_insert("tenant1", "point", rows).unwrap();
+
+        // Ground truth by exhaustive scan
+        let stored = db.list("tenant1", "point", false, None, None).unwrap();
+        let exact_top = |query: &[f32]| -> HashSet<uuid::Uuid> {
+            let mut scored: Vec<_> = stored.iter()
+                .map(|e| (e.system.id, crate::index::similarity::cosine_similarity(query, &e.get_embedding().unwrap())))
+                .collect();
+            scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            scored.into_iter().take(10).map(|(id, _)| id).collect()
+        };
 
-        // Insert test data
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
+        let queries: Vec<Vec<f32>> = (0..20)
+            .map(|_| (0..dims).map(|_| next() as f32).collect())
+            .collect();
 
-        // Query all
-        let result = db.query_sql("tenant1", "SELECT * FROM person").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 3);
+        let mut recall = std::collections::HashMap::new();
+        for mode in [SearchMode::Fast, SearchMode::Accurate] {
+            let mut hits = 0;
+            for query in &queries {
+                let truth = exact_top(query);
+                let results = db.search_by_vector("tenant1", "point", query, 10, None, mode).await.unwrap();
+                assert_eq!(results.len(), 10);
+                hits += results.iter().filter(|r| truth.contains(&r.entity.system.id)).count();
+            }
+            recall.insert(mode, hits as f64 / (queries.len() * 10) as f64);
+        }
+
+        assert!(
+            recall[&SearchMode::Accurate] > recall[&SearchMode::Fast],
+            "recall@10: accurate {} vs fast {}",
+            recall[&SearchMode::Accurate],
+            recall[&SearchMode::Fast]
+        );
+        assert!(recall[&SearchMode::Accurate] >= 0.9);
     }
 
     #[test]
-    fn test_query_sql_where_comparison() {
+    fn test_decay_edge_weights() {
         let db = Database::open_temp().unwrap();
 
         let schema = serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
         });
-
         db.register_schema("person", schema).unwrap();
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
+        let alice = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
+        let bob = db.insert("tenant1", "person", serde_json::json!({"name": "Bob"})).unwrap();
+        let carol = db.insert("tenant1", "person", serde_json::json!({"name": "Carol"})).unwrap();
 
-        // Test greater than
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["name"], "Charlie");
+        db.add_edge("tenant1", alice, bob, "knows", Some(serde_json::json!({"weight": 1.0}))).unwrap();
+        let mut old_edge = db.add_edge("tenant1", alice, carol, "knows", Some(serde_json::json!({"weight": 1.0}))).unwrap();
 
-        // Test less than
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age < 30").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["name"], "Bob");
+        // Backdate one edge by 30 days
+        old_edge.data.created_at = (chrono::Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        let value = serde_json::to_vec(&old_edge).unwrap();
+        db.storage().put(
+            crate::storage::column_families::CF_EDGES,
+            &crate::storage::keys::encode_edge_key(alice, carol, "knows"),
+            &value,
+        ).unwrap();
+        db.storage().put(
+            crate::storage::column_families::CF_EDGES_REVERSE,
+            &crate::storage::keys::encode_reverse_edge_key(carol, alice, "knows"),
+            &value,
+        ).unwrap();
+
+        let half_life = std::time::Duration::from_secs(10 * 24 * 3600);
+        assert_eq!(db.decay_edge_weights(half_life, Some("knows")).unwrap(), 2);
+
+        let weight_to = |dst: uuid::Uuid| -> f64 {
+            db.get_edges(alice, Some("knows")).unwrap()
+                .into_iter()
+                .find(|e| e.dst == dst)
+                .unwrap()
+                .data.properties["weight"]
+                .as_f64()
+                .unwrap()
+        };
 
-        // Test equality
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age = 30").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["name"], "Alice");
+        let recent = weight_to(bob);
+        let old = weight_to(carol);
+        assert!(recent > 0.99);
+        // Three half-lives: 1.0 -> 0.125
+        assert!((old - 0.125).abs() < 0.01, "old weight {}", old);
+
+        // Re-running does not compound the decay
+        db.decay_edge_weights(half_life, Some("knows")).unwrap();
+        assert!((weight_to(carol) - 0.125).abs() < 0.01);
+
+        // Incoming view is kept in sync
+        let incoming = db.get_incoming_edges(carol, Some("knows")).unwrap();
+        assert!((incoming[0].data.properties["weight"].as_f64().unwrap() - 0.125).abs() < 0.01);
+
+        // Writing a new weight (even alongside the old props) resets the base
+        let mut props = db.get_edges(alice, Some("knows")).unwrap()
+            .into_iter()
+            .find(|e| e.dst == bob)
+            .unwrap()
+            .data.properties;
+        assert!(props.contains_key("base_weight"));
+        props.insert("weight".to_string(), serde_json::json!(0.5));
+        db.add_edge("tenant1", alice, bob, "knows", Some(serde_json::json!(props))).unwrap();
+        db.decay_edge_weights(half_life, Some("knows")).unwrap();
+        assert!((weight_to(bob) - 0.5).abs() < 0.01);
     }
 
     #[test]
-    fn test_query_sql_where_string_equality() {
+    fn test_embedding_consistency_and_normalize() {
+        use crate::embeddings::NormState;
+
         let db = Database::open_temp().unwrap();
 
         let schema = serde_json::json!({
-            "title": "Person",
+            "title": "Article",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "article",
             "properties": {
-                "name": {"type": "string"},
-                "role": {"type": "string"}
+                "title": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
-            "required": ["name"]
+            "required": ["title"]
         });
+        db.register_schema("article", schema).unwrap();
 
-        db.register_schema("person", schema).unwrap();
+        // One unit vector, two raw vectors (as after a model switch)
+        db.insert("tenant1", "article", serde_json::json!({"title": "a", "embedding": [1.0, 0.0, 0.0]})).unwrap();
+        db.insert("tenant1", "article", serde_json::json!({"title": "b", "embedding": [3.0, 4.0, 0.0]})).unwrap();
+        db.insert("tenant1", "article", serde_json::json!({"title": "c", "embedding": [0.0, 0.0, 2.0]})).unwrap();
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "role": "engineer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "role": "designer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "role": "engineer"})).unwrap();
+        let report = db.check_embedding_consistency("tenant1", "article").unwrap();
+        assert_eq!(report.sampled, 3);
+        assert_eq!(report.state, NormState::Mixed);
+        assert!(!report.is_consistent());
 
-        // Test string equality (case-insensitive)
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE role = 'engineer'").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 2);
+        assert_eq!(db.normalize_stored("tenant1", "article").unwrap(), 2);
 
-        // Case insensitive
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE role = 'ENGINEER'").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 2);
+        let report = db.check_embedding_consistency("tenant1", "article").unwrap();
+        assert_eq!(report.state, NormState::Normalized);
+        assert_eq!(report.normalized, 3);
+
+        // Idempotent once uniform
+        assert_eq!(db.normalize_stored("tenant1", "article").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_normalize_stored_batches_and_skips_deleted() {
+        use crate::replication::WalOperation;
+
+        let path = std::env::temp_dir().join(format!("rem-db-test-{}", uuid::Uuid::new_v4()));
+        let db = Database::open_with_replication(&path, ReplicationMode::Primary).unwrap().with_write_batch_size(2);
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "article",
+            "properties": {
+                "title": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["title"]
+        });
+        db.register_schema("article", schema).unwrap();
+
+        for i in 0..5 {
+            db.insert("tenant1", "article", serde_json::json!({"title": format!("a{}", i), "embedding": [3.0, 4.0]})).unwrap();
+        }
+        let deleted = db.insert("tenant1", "article", serde_json::json!({"title": "gone", "embedding": [3.0, 4.0]})).unwrap();
+        db.delete("tenant1", deleted).unwrap();
+
+        let logged = db.wal.as_ref().unwrap().read().unwrap().current_position();
+        assert_eq!(db.normalize_stored("tenant1", "article").unwrap(), 5);
+
+        // Rewrites are replicated
+        let wal = db.wal.as_ref().unwrap().read().unwrap().get_entries_after(logged, usize::MAX).unwrap();
+        assert_eq!(wal.len(), 5);
+        assert!(wal.iter().all(|entry| matches!(&entry.op, WalOperation::Update { changes, .. } if changes["embedding"].is_array())));
+
+        let raw: Entity = serde_json::from_slice(
+            &db.storage.get(
+                crate::storage::column_families::CF_ENTITIES,
+                &crate::storage::keys::encode_entity_key("tenant1", deleted),
+            ).unwrap().unwrap()
+        ).unwrap();
+        assert_eq!(raw.get_embedding().unwrap(), vec![3.0, 4.0]);
+    }
+
+    /// Deterministic embedder that yields so workers interleave.
+    struct SlowEmbedder;
+
+    #[async_trait::async_trait]
+    impl crate::embeddings::EmbeddingProvider for SlowEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            Ok(vec![text.len() as f32, 1.0, 0.0])
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::with_capacity(texts.len());
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+    }
+
+    /// Embeds each known word to a fixed vector.
+    struct WordEmbedder;
+
+    #[async_trait::async_trait]
+    impl crate::embeddings::EmbeddingProvider for WordEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            Ok(match text {
+                "ownership" => vec![2.0, 0.0, 0.0],
+                "borrowing" => vec![0.0, 1.0, 1.0],
+                _ => vec![0.0, 0.0, 0.0],
+            })
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::with_capacity(texts.len());
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_embedded_mean_pooling() {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("note", serde_json::json!({
+            "title": "Note",
+            "version": "1.0.0",
+            "short_name": "note",
+            "properties": {
+                "title": {"type": "string"},
+                "body": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["title"],
+            "json_schema_extra": {
+                "embedding_fields": ["title", "body"],
+                "embedding_pooling": "mean"
+            }
+        })).unwrap();
+
+        let id = db
+            .insert_embedded_with_provider("tenant1", "note", serde_json::json!({"title": "ownership", "body": "borrowing"}), &WordEmbedder)
+            .await
+            .unwrap();
+
+        // Normalized mean of [2, 0, 0] and [0, 1, 1]
+        let mut expected = vec![1.0_f32, 0.5, 0.5];
+        crate::embeddings::norms::l2_normalize(&mut expected);
+
+        let stored = db.get("tenant1", id).unwrap().unwrap().get_embedding().unwrap();
+        assert_eq!(stored.len(), 3);
+        for (got, want) in stored.iter().zip(&expected) {
+            assert!((got - want).abs() < 1e-6, "{:?} != {:?}", stored, expected);
+        }
     }
 
-    #[test]
-    fn test_query_sql_where_and_or() {
+    #[tokio::test]
+    async fn test_ingest_pipelined() {
+        use tempfile::tempdir;
+
         let db = Database::open_temp().unwrap();
 
         let schema = serde_json::json!({
-            "title": "Person",
+            "title": "Resource",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "resource",
             "properties": {
                 "name": {"type": "string"},
-                "age": {"type": "number"},
-                "role": {"type": "string"}
+                "content": {"type": "string"},
+                "uri": {"type": "string"},
+                "chunk_ordinal": {"type": "integer"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
-            "required": ["name"]
+            "required": ["content"]
         });
+        db.register_schema("resource", schema).unwrap();
+
+        // Paragraphs near the chunk size so each becomes its own chunk
+        let dir = tempdir().unwrap();
+        let paths: Vec<_> = (0..3)
+            .map(|f| {
+                let path = dir.path().join(format!("doc{}.txt", f));
+                let text = (0..5)
+                    .map(|p| format!("{}-{} ", f, p).repeat(300))
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                std::fs::write(&path, text).unwrap();
+                path
+            })
+            .collect();
 
-        db.register_schema("person", schema).unwrap();
-
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30, "role": "engineer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25, "role": "designer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35, "role": "engineer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Diana", "age": 28, "role": "manager"})).unwrap();
-
-        // Test AND
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age > 30 AND role = 'engineer'").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["name"], "Charlie");
-
-        // Test OR
-        let result = db.query_sql("tenant1", "SELECT * FROM person WHERE age < 26 OR role = 'manager'").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 2); // Bob and Diana
+        let queue_depth = 2;
+        let stats = db
+            .ingest_pipelined_with_provider("tenant1", &paths, "resource", Arc::new(SlowEmbedder), 2, queue_depth)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.files, 3);
+        assert_eq!(stats.chunks, 15);
+        assert!(stats.max_queue_depth >= 1);
+        assert!(stats.max_queue_depth <= queue_depth);
+
+        let stored = db.list("tenant1", "resource", false, None, None).unwrap();
+        assert_eq!(stored.len(), 15);
+        assert!(stored.iter().all(|e| e.get_embedding().map(|v| v.len()) == Some(3)));
+
+        // Re-ingesting upserts by uri + chunk_ordinal
+        db.ingest_pipelined_with_provider("tenant1", &paths, "resource", Arc::new(SlowEmbedder), 2, queue_depth)
+            .await
+            .unwrap();
+        assert_eq!(db.count("tenant1", "resource", false).unwrap(), 15);
     }
 
     #[test]
-    fn test_query_sql_order_by() {
+    fn test_expired_entities_hidden_on_read() {
         let db = Database::open_temp().unwrap();
+        db.register_schema("sessions", serde_json::json!({
+            "title": "Session",
+            "version": "1.0.0",
+            "short_name": "sessions",
+            "properties": {"user": {"type": "string"}},
+            "required": ["user"]
+        })).unwrap();
+
+        let expired = db.insert_with_ttl("tenant1", "sessions", serde_json::json!({"user": "alice"}), std::time::Duration::ZERO).unwrap();
+        let live = db.insert_with_ttl("tenant1", "sessions", serde_json::json!({"user": "bob"}), std::time::Duration::from_secs(3600)).unwrap();
+
+        assert!(db.get("tenant1", expired).unwrap().is_none());
+        assert!(db.get("tenant1", live).unwrap().is_some());
+        assert!(db.get_batch("tenant1", &[expired, live]).unwrap()[0].is_none());
+        assert_eq!(db.list("tenant1", "sessions", true, None, None).unwrap().len(), 1);
+        assert_eq!(db.count("tenant1", "sessions", true).unwrap(), 1);
+    }
 
-        let schema = serde_json::json!({
-            "title": "Person",
+    #[test]
+    fn test_compaction_drops_expired_entities() {
+        use crate::storage::column_families::{CF_ENTITIES, CF_KEY_INDEX};
+
+        let schema = |name: &str, policy: &str| serde_json::json!({
+            "title": name,
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": name,
             "properties": {
                 "name": {"type": "string"},
-                "age": {"type": "number"}
+                "city": {"type": "string"}
             },
-            "required": ["name"]
+            "required": ["name"],
+            "json_schema_extra": {
+                "key_field": "name",
+                "indexed_fields": ["city"],
+                "delete_policy": policy
+            }
         });
 
-        db.register_schema("person", schema).unwrap();
-
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
-
-        // Test ORDER BY ASC (default)
-        let result = db.query_sql("tenant1", "SELECT * FROM person ORDER BY age ASC").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows[0]["name"], "Bob");
-        assert_eq!(rows[1]["name"], "Alice");
-        assert_eq!(rows[2]["name"], "Charlie");
-
-        // Test ORDER BY DESC
-        let result = db.query_sql("tenant1", "SELECT * FROM person ORDER BY age DESC").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows[0]["name"], "Charlie");
-        assert_eq!(rows[1]["name"], "Alice");
-        assert_eq!(rows[2]["name"], "Bob");
+        let db = Database::open_temp().unwrap();
+        db.register_schema("person", schema("person", "either")).unwrap();
+        db.register_schema("audit", schema("audit", "soft_only")).unwrap();
+
+        let expired = db.insert_with_ttl("tenant1", "person", serde_json::json!({"name": "alice", "city": "Paris"}), std::time::Duration::ZERO).unwrap();
+        let deleted = db.insert("tenant1", "person", serde_json::json!({"name": "bob", "city": "Paris"})).unwrap();
+        let kept = db.insert("tenant1", "person", serde_json::json!({"name": "carol", "city": "Paris"})).unwrap();
+        let audit = db.insert("tenant1", "audit", serde_json::json!({"name": "entry"})).unwrap();
+        db.add_edge("tenant1", deleted, kept, "knows", None).unwrap();
+        db.add_edge("tenant1", kept, deleted, "knows", None).unwrap();
+        db.delete("tenant1", deleted).unwrap();
+        db.delete("tenant1", audit).unwrap();
+
+        let stored = |db: &Database, id| db.storage().get(CF_ENTITIES, &crate::storage::keys::encode_entity_key("tenant1", id)).unwrap();
+        let key_entries = |db: &Database, name: &str| db.storage()
+            .prefix_iterator(CF_KEY_INDEX, format!("key:tenant1:{}:", name).as_bytes())
+            .count();
+
+        // Compaction drops the entity record; vacuum removes the rest
+        assert!(stored(&db, expired).is_some());
+        db.storage().compact(CF_ENTITIES).unwrap();
+        assert!(stored(&db, expired).is_none());
+        assert_eq!(key_entries(&db, "alice"), 1);
+        assert_eq!(db.vacuum().unwrap(), 1);
+        assert_eq!(key_entries(&db, "alice"), 0);
+        assert_eq!(db.vacuum().unwrap(), 0);
+
+        // Soft-delete purging is opt-in
+        assert!(db.get("tenant1", deleted).unwrap().unwrap().is_deleted());
+
+        let db = db.with_soft_delete_grace(std::time::Duration::ZERO);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        db.storage().compact(CF_ENTITIES).unwrap();
+        assert_eq!(db.vacuum().unwrap(), 1);
+
+        assert!(stored(&db, deleted).is_none());
+        assert_eq!(key_entries(&db, "bob"), 0);
+        assert!(db.get_edges(kept, None).unwrap().is_empty());
+        assert!(db.get_incoming_edges(kept, None).unwrap().is_empty());
+        assert_eq!(db.field_index.lookup("tenant1", "person", "city", "Paris").unwrap(), vec![kept]);
+
+        // Soft-only tables are never purged
+        assert!(stored(&db, audit).is_some());
+        assert!(db.get("tenant1", audit).unwrap().unwrap().is_deleted());
     }
 
     #[test]
-    fn test_query_sql_limit() {
-        let db = Database::open_temp().unwrap();
+    fn test_open_with_provider_routes_crud() {
+        use crate::storage::column_families::{CF_ENTITIES, CF_KEY_INDEX};
 
-        let schema = serde_json::json!({
+        let provider = Arc::new(Storage::open_temp().unwrap());
+        let local = std::env::temp_dir().join(format!("rem-db-test-{}", uuid::Uuid::new_v4()));
+        let db = Database::open_with_provider(&local, provider.clone()).unwrap();
+
+        db.register_schema("person", serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"}
-            },
+            "properties": {"name": {"type": "string"}},
             "required": ["name"]
-        });
-
-        db.register_schema("person", schema).unwrap();
+        })).unwrap();
+        let id = db.insert("tenant1", "person", serde_json::json!({"name": "Alice"})).unwrap();
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Diana", "age": 28})).unwrap();
+        assert_eq!(db.get("tenant1", id).unwrap().unwrap().system.id, id);
+        assert_eq!(db.get_by_key("tenant1", "person", "Alice").unwrap().unwrap().system.id, id);
+        assert_eq!(db.list("tenant1", "person", false, None, None).unwrap().len(), 1);
+        assert_eq!(db.count("tenant1", "person", false).unwrap(), 1);
 
-        // Test LIMIT
-        let result = db.query_sql("tenant1", "SELECT * FROM person LIMIT 2").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 2);
+        // Entities and their keys live in the provider, not the local RocksDB
+        let entity_key = crate::storage::keys::encode_entity_key("tenant1", id);
+        assert!(provider.get(CF_ENTITIES, &entity_key).unwrap().is_some());
+        assert!(db.storage().get(CF_ENTITIES, &entity_key).unwrap().is_none());
+        assert!(provider.iterator(CF_KEY_INDEX, b"key:tenant1:").next().is_some());
+        assert!(db.storage().prefix_iterator(CF_KEY_INDEX, b"key:").next().is_none());
+
+        // Schemas persisted through the provider are loaded on reopen
+        drop(db);
+        let db = Database::open_with_provider(&local, provider).unwrap();
+        assert_eq!(db.get_by_key("tenant1", "person", "Alice").unwrap().unwrap().system.id, id);
     }
 
     #[test]
-    fn test_query_sql_count() {
+    fn test_import_edges_jsonl() {
         let db = Database::open_temp().unwrap();
+        db.register_schema("paper", serde_json::json!({
+            "title": "Paper",
+            "version": "1.0.0",
+            "short_name": "paper",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        })).unwrap();
+
+        let a = db.insert("tenant1", "paper", serde_json::json!({"name": "paper-a"})).unwrap();
+        let b = db.insert("tenant1", "paper", serde_json::json!({"name": "paper-b"})).unwrap();
+        let c = db.insert("tenant1", "paper", serde_json::json!({"name": "paper-c"})).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("citations.jsonl");
+        let lines = [
+            serde_json::json!({"src": a.to_string(), "dst": "paper-b", "rel_type": "cites", "properties": {"weight": 0.8}}),
+            serde_json::json!({"src": "paper-b", "dst": c.to_string(), "rel_type": "cites"}),
+            serde_json::json!({"src": "paper-c", "dst": "paper-z", "rel_type": "cites"}),
+        ];
+        std::fs::write(&path, lines.iter().map(|l| l.to_string()).collect::<Vec<_>>().join("\n")).unwrap();
+
+        let report = db.import_edges("tenant1", &path, crate::export::ExportFormat::Jsonl).unwrap();
+        assert_eq!(report.created, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.errors[0].0, 2);
+
+        let from_a = db.get_edges(a, Some("cites")).unwrap();
+        assert_eq!(from_a.len(), 1);
+        assert_eq!(from_a[0].dst, b);
+        assert_eq!(from_a[0].data.properties["weight"], 0.8);
+        assert_eq!(db.get_edges(b, Some("cites")).unwrap()[0].dst, c);
+        assert!(db.get_edges(c, Some("cites")).unwrap().is_empty());
+    }
 
-        let schema = serde_json::json!({
-            "title": "Person",
+    #[tokio::test]
+    async fn test_search_scores_are_similarities() {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "point",
             "properties": {
                 "name": {"type": "string"},
-                "age": {"type": "number"}
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
             "required": ["name"]
-        });
-
-        db.register_schema("person", schema).unwrap();
+        })).unwrap();
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
+        for (name, embedding) in [("exact", [1.0, 0.0]), ("orthogonal", [0.0, 1.0]), ("opposite", [-1.0, 0.0])] {
+            db.insert("tenant1", "point", serde_json::json!({"name": name, "embedding": embedding})).unwrap();
+        }
 
-        // Test COUNT(*)
-        let result = db.query_sql("tenant1", "SELECT COUNT(*) FROM person").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["count"], 3);
+        let results = db
+            .search_by_vector("tenant1", "point", &[2.0, 0.0], 3, None, crate::index::SearchMode::default())
+            .await
+            .unwrap();
+        let score = |name: &str| results.iter().find(|r| r.entity.properties["name"] == name).unwrap();
+
+        assert!((score("exact").score - 1.0).abs() < 1e-5);
+        assert!(score("orthogonal").score.abs() < 1e-5);
+        assert_eq!(score("opposite").score, 0.0);
+        assert!((score("opposite").raw_distance.unwrap() - 2.0).abs() < 1e-5);
+        assert!(results.iter().all(|r| (0.0..=1.0).contains(&r.score)));
     }
 
-    #[test]
-    fn test_query_sql_avg() {
+    #[tokio::test]
+    async fn test_more_like_this() {
         let db = Database::open_temp().unwrap();
-
-        let schema = serde_json::json!({
-            "title": "Person",
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "point",
             "properties": {
                 "name": {"type": "string"},
-                "age": {"type": "number"}
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
             "required": ["name"]
-        });
+        })).unwrap();
+
+        let mut ids = std::collections::HashMap::new();
+        for (name, angle) in [("source", 0.0_f64), ("near", 0.1), ("mid", 0.5), ("far", 1.5)] {
+            let id = db.insert("tenant1", "point", serde_json::json!({
+                "name": name,
+                "embedding": [angle.cos(), angle.sin()]
+            })).unwrap();
+            ids.insert(name, id);
+        }
+        let bare = db.insert("tenant1", "point", serde_json::json!({"name": "bare"})).unwrap();
+
+        let results = db.more_like_this("tenant1", ids["source"], 2, 0.0).await.unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.entity.properties["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["near", "mid"]);
+        assert_eq!(results.iter().map(|r| r.rank).collect::<Vec<_>>(), vec![0, 1]);
+
+        // Asking for more than exist still leaves the source out
+        let results = db.more_like_this("tenant1", ids["source"], 10, 0.0).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.entity.system.id != ids["source"]));
+
+        assert!(matches!(
+            db.more_like_this("tenant1", bare, 2, 0.0).await,
+            Err(DatabaseError::SearchError(_))
+        ));
+        assert!(matches!(
+            db.more_like_this("tenant1", uuid::Uuid::new_v4(), 2, 0.0).await,
+            Err(DatabaseError::EntityNotFound(_))
+        ));
+    }
 
-        db.register_schema("person", schema).unwrap();
+    #[tokio::test]
+    async fn test_build_similarity_graph() {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
+            "version": "1.0.0",
+            "short_name": "point",
+            "properties": {
+                "name": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["name"],
+            "json_schema_extra": {"key_field": "name"}
+        })).unwrap();
+
+        // Two tight clusters pointing in orthogonal directions
+        let points = [
+            ("a1", [1.0, 0.0, 0.0]),
+            ("a2", [0.99, 0.1, 0.0]),
+            ("b1", [0.0, 0.0, 1.0]),
+            ("b2", [0.0, 0.1, 0.99]),
+        ];
+        let mut ids = std::collections::HashMap::new();
+        for (name, embedding) in points {
+            let id = db.insert("tenant1", "point", serde_json::json!({"name": name, "embedding": embedding})).unwrap();
+            ids.insert(name, id);
+        }
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 20})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 40})).unwrap();
+        let created = db.build_similarity_graph("tenant1", "point", 0.9, 3, "similar_to").await.unwrap();
+        assert_eq!(created, 4);
 
-        // Test AVG
-        let result = db.query_sql("tenant1", "SELECT AVG(age) FROM person").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["avg"], 30.0);
+        let neighbors = |name: &str| -> Vec<uuid::Uuid> {
+            db.get_edges(ids[name], Some("similar_to")).unwrap().iter().map(|e| e.dst).collect()
+        };
+        assert_eq!(neighbors("a1"), vec![ids["a2"]]);
+        assert_eq!(neighbors("a2"), vec![ids["a1"]]);
+        assert_eq!(neighbors("b1"), vec![ids["b2"]]);
+        assert_eq!(neighbors("b2"), vec![ids["b1"]]);
+
+        let edge = &db.get_edges(ids["a1"], Some("similar_to")).unwrap()[0];
+        let weight = edge.data.properties["weight"].as_f64().unwrap();
+        assert!(weight > 0.9 && weight <= 1.0, "{}", weight);
     }
 
-    #[test]
-    fn test_query_sql_min_max() {
+    #[tokio::test]
+    async fn test_min_similarity_drops_outlier_neighbors() {
         let db = Database::open_temp().unwrap();
-
-        let schema = serde_json::json!({
-            "title": "Person",
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "point",
             "properties": {
                 "name": {"type": "string"},
-                "age": {"type": "number"}
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
             "required": ["name"]
-        });
+        })).unwrap();
+
+        // Tight cluster around angle 0, one outlier at a right angle
+        let mut ids = std::collections::HashMap::new();
+        for (name, angle) in [("c1", 0.0_f64), ("c2", 0.05), ("c3", 0.1), ("outlier", 1.6)] {
+            let id = db.insert("tenant1", "point", serde_json::json!({
+                "name": name,
+                "embedding": [angle.cos(), angle.sin()]
+            })).unwrap();
+            ids.insert(name, id);
+        }
 
-        db.register_schema("person", schema).unwrap();
+        // Without a floor the outlier still gets top_k neighbors
+        assert_eq!(db.more_like_this("tenant1", ids["outlier"], 2, 0.0).await.unwrap().len(), 2);
+        assert!(db.more_like_this("tenant1", ids["outlier"], 2, 0.8).await.unwrap().is_empty());
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
+        let results = db.more_like_this("tenant1", ids["c1"], 5, 0.8).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.score >= 0.8 && r.entity.system.id != ids["outlier"]));
 
-        // Test MIN and MAX
-        let result = db.query_sql("tenant1", "SELECT MIN(age), MAX(age) FROM person").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["min"], 25.0);
-        assert_eq!(rows[0]["max"], 35.0);
+        // Cluster fully connected, outlier isolated
+        let created = db.build_similarity_graph("tenant1", "point", 0.8, 5, "similar_to").await.unwrap();
+        assert_eq!(created, 6);
+        assert!(db.get_edges(ids["outlier"], Some("similar_to")).unwrap().is_empty());
+        for name in ["c1", "c2", "c3"] {
+            assert_eq!(db.get_edges(ids[name], Some("similar_to")).unwrap().len(), 2);
+        }
+
+        assert!(matches!(
+            db.more_like_this("tenant1", ids["c1"], 2, 1.5).await,
+            Err(DatabaseError::ConfigError(_))
+        ));
     }
 
     #[test]
-    fn test_query_sql_sum() {
-        let db = Database::open_temp().unwrap();
+    fn test_export_query() {
+        use crate::export::ExportFormat;
+        use crate::query::Predicate;
+        use parquet::file::reader::{FileReader, SerializedFileReader};
 
-        let schema = serde_json::json!({
-            "title": "Person",
+        let db = Database::open_temp().unwrap();
+        db.register_schema("articles", serde_json::json!({
+            "title": "Article",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "articles",
             "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"}
+                "title": {"type": "string"},
+                "category": {"type": "string"},
+                "body": {"type": "string"}
             },
-            "required": ["name"]
-        });
+            "required": ["title"],
+            "json_schema_extra": {"key_field": "title"}
+        })).unwrap();
+
+        for (title, category) in [("Install", "docs"), ("Release", "news"), ("Configure", "docs"), ("Roadmap", "blog")] {
+            db.insert("tenant1", "articles", serde_json::json!({
+                "title": title,
+                "category": category,
+                "body": "..."
+            })).unwrap();
+        }
 
-        db.register_schema("person", schema).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let docs = Predicate::eq("category", serde_json::json!("docs"));
+        let columns = ["title", "category"];
+
+        let path = dir.path().join("docs.csv");
+        let written = db.export_query("tenant1", "articles", Some(&docs), Some(&columns), &path, ExportFormat::Csv).unwrap();
+        assert_eq!(written, 2);
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "title,category");
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1..].iter().all(|line| line.ends_with(",docs")));
+
+        let path = dir.path().join("docs.jsonl");
+        db.export_query("tenant1", "articles", Some(&docs), Some(&columns), &path, ExportFormat::Jsonl).unwrap();
+        let rows: Vec<serde_json::Value> = std::fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        for row in &rows {
+            let keys: Vec<&String> = row.as_object().unwrap().keys().collect();
+            assert_eq!(keys, vec!["category", "title"]);
+        }
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35})).unwrap();
+        let path = dir.path().join("docs.parquet");
+        db.export_query("tenant1", "articles", Some(&docs), Some(&columns), &path, ExportFormat::Parquet).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+        let names: Vec<&str> = metadata.file_metadata().schema_descr().columns().iter().map(|c| c.name()).collect();
+        assert_eq!(names, vec!["title", "category"]);
 
-        // Test SUM
-        let result = db.query_sql("tenant1", "SELECT SUM(age) FROM person").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["sum"], 90.0);
+        let err = db.export_query("tenant1", "articles", None, Some(&["author"]), dir.path().join("x.csv"), ExportFormat::Csv);
+        assert!(matches!(err, Err(DatabaseError::ValidationError(_))));
     }
 
-    #[test]
-    fn test_query_sql_aggregate_with_where() {
+    #[tokio::test]
+    async fn test_ask_batch_dedupes_questions() {
+        use crate::llm::{LlmQueryBuilder, QueryPlan, QueryPlanner};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Plans a title lookup for the question, counting calls.
+        struct MockPlanner {
+            calls: AtomicUsize,
+        }
+
+        #[async_trait::async_trait]
+        impl QueryPlanner for MockPlanner {
+            async fn plan_query(&self, question: &str, _schema_context: &str) -> Result<QueryPlan> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                LlmQueryBuilder::parse_plan(&serde_json::json!({
+                    "query_type": "sql",
+                    "confidence": 0.9,
+                    "primary_query": {
+                        "dialect": "rem_sql",
+                        "query_string": format!("SELECT * FROM articles WHERE title = '{}'", question),
+                        "parameters": {"schema": "articles"}
+                    },
+                    "execution_mode": "single_pass",
+                    "reasoning": "Title filter"
+                }).to_string())
+            }
+        }
+
         let db = Database::open_temp().unwrap();
+        db.register_schema("articles", serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "articles",
+            "properties": {"title": {"type": "string"}},
+            "required": ["title"]
+        })).unwrap();
+        for title in ["Rust", "Go"] {
+            db.insert("default", "articles", serde_json::json!({"title": title})).unwrap();
+        }
 
-        let schema = serde_json::json!({
-            "title": "Person",
+        let planner = Arc::new(MockPlanner { calls: AtomicUsize::new(0) });
+        let questions = vec!["Rust".to_string(), "Go".to_string(), "Rust".to_string()];
+        let answers = db.ask_batch(planner.clone(), "default", questions, Some("articles"), 2).await.unwrap();
+
+        assert_eq!(planner.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(answers.len(), 3);
+        for (answer, title) in answers.iter().zip(["Rust", "Go", "Rust"]) {
+            assert_eq!(answer.question, title);
+            assert!(answer.error.is_none());
+            assert_eq!(answer.results.len(), 1);
+            assert_eq!(answer.results[0]["title"], title);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rag_answer_cites_retrieved_sources() {
+        use crate::llm::AnswerGenerator;
+        use std::sync::Mutex;
+
+        /// Records the context and answers citing the first source.
+        struct MockGenerator {
+            contexts: Mutex<Vec<String>>,
+        }
+
+        #[async_trait::async_trait]
+        impl AnswerGenerator for MockGenerator {
+            async fn generate_answer(&self, question: &str, context: &str) -> Result<String> {
+                self.contexts.lock().unwrap().push(context.to_string());
+                Ok(format!("{}: see [1]", question))
+            }
+        }
+
+        let db = Database::open_temp().unwrap();
+        db.register_schema("note", serde_json::json!({
+            "title": "Note",
             "version": "1.0.0",
-            "short_name": "person",
+            "short_name": "note",
             "properties": {
-                "name": {"type": "string"},
-                "age": {"type": "number"},
-                "role": {"type": "string"}
+                "content": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
             },
-            "required": ["name"]
-        });
+            "required": ["content"]
+        })).unwrap();
+
+        let mut ids = std::collections::HashMap::new();
+        for (content, embedding) in [
+            ("Ownership moves values", [1.0, 0.0, 0.0]),
+            ("Borrowing lends references", [0.0, 1.0, 1.0]),
+            ("Lifetimes bound references", [0.0, 0.0, 1.0]),
+        ] {
+            let id = db.insert("tenant1", "note", serde_json::json!({"content": content, "embedding": embedding})).unwrap();
+            ids.insert(content, id);
+        }
 
-        db.register_schema("person", schema).unwrap();
+        let generator = MockGenerator { contexts: Mutex::new(Vec::new()) };
+        let rag = db.rag_answer_with_provider(&generator, &WordEmbedder, "tenant1", "borrowing", "note", 2, 0).await.unwrap();
 
-        db.insert("tenant1", "person", serde_json::json!({"name": "Alice", "age": 30, "role": "engineer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Bob", "age": 25, "role": "designer"})).unwrap();
-        db.insert("tenant1", "person", serde_json::json!({"name": "Charlie", "age": 35, "role": "engineer"})).unwrap();
+        assert_eq!(rag.answer, "borrowing: see [1]");
+        assert_eq!(rag.sources, vec![ids["Borrowing lends references"], ids["Lifetimes bound references"]]);
 
-        // Test aggregate with WHERE filter
-        let result = db.query_sql("tenant1", "SELECT COUNT(*), AVG(age) FROM person WHERE role = 'engineer'").unwrap();
-        let rows = result.as_array().unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0]["count"], 2);
-        assert_eq!(rows[0]["avg"], 32.5);
+        let contexts = generator.contexts.lock().unwrap();
+        assert_eq!(contexts.len(), 1);
+        assert!(contexts[0].starts_with(&format!("[1] ({})\nBorrowing lends references", rag.sources[0])));
+        assert!(contexts[0].contains(&format!("[2] ({})\nLifetimes bound references", rag.sources[1])));
+        assert!(!contexts[0].contains("Ownership"));
     }
 
-    #[test]
-    fn test_query_sql_invalid_syntax() {
-        let db = Database::open_temp().unwrap();
+    #[tokio::test]
+    async fn test_rag_answer_expands_neighbor_chunks() {
+        use crate::llm::AnswerGenerator;
 
-        let schema = serde_json::json!({
-            "title": "Person",
+        /// Answers with the context it was given.
+        struct EchoGenerator;
+
+        #[async_trait::async_trait]
+        impl AnswerGenerator for EchoGenerator {
+            async fn generate_answer(&self, _question: &str, context: &str) -> Result<String> {
+                Ok(context.to_string())
+            }
+        }
+
+        let db = Database::open_temp().unwrap();
+        db.register_schema("chunk", serde_json::json!({
+            "title": "Chunk",
             "version": "1.0.0",
-            "short_name": "person",
-            "properties": {"name": {"type": "string"}},
-            "required": ["name"]
-        });
+            "short_name": "chunk",
+            "properties": {
+                "content": {"type": "string"},
+                "uri": {"type": "string"},
+                "chunk_ordinal": {"type": "integer"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["content", "uri", "chunk_ordinal"]
+        })).unwrap();
+
+        // The answer starts in chunk 0 and finishes in chunk 1
+        let mut ids = Vec::new();
+        for (ordinal, content, embedding) in [
+            (0, "Ownership moves a value", [1.0, 0.0, 0.0]),
+            (1, "to its new owner and invalidates the old binding", [0.8, 0.6, 0.0]),
+            (2, "Appendix", [0.0, 0.0, 1.0]),
+        ] {
+            ids.push(db.insert("tenant1", "chunk", serde_json::json!({
+                "content": content,
+                "uri": "docs/ownership.md",
+                "chunk_ordinal": ordinal,
+                "embedding": embedding
+            })).unwrap());
+        }
 
-        db.register_schema("person", schema).unwrap();
+        let rag = db.rag_answer_with_provider(&EchoGenerator, &WordEmbedder, "tenant1", "ownership", "chunk", 1, 0).await.unwrap();
+        assert_eq!(rag.sources, vec![ids[0]]);
+        assert!(!rag.answer.contains("new owner"));
 
-        // Invalid SQL should return error
-        let result = db.query_sql("tenant1", "INVALID SQL QUERY");
-        assert!(result.is_err());
+        let rag = db.rag_answer_with_provider(&EchoGenerator, &WordEmbedder, "tenant1", "ownership", "chunk", 1, 1).await.unwrap();
+        assert_eq!(rag.sources, vec![ids[0], ids[1]]);
+        assert!(rag.answer.contains("[1] (") && rag.answer.contains("Ownership moves a value"));
+        assert!(rag.answer.contains("[2] (") && rag.answer.contains("to its new owner"));
+
+        // Overlapping windows: chunk 1 is both a hit and chunk 0's neighbor
+        let rag = db.rag_answer_with_provider(&EchoGenerator, &WordEmbedder, "tenant1", "ownership", "chunk", 2, 1).await.unwrap();
+        assert_eq!(rag.sources, vec![ids[0], ids[1], ids[2]]);
     }
 
     #[test]
-    fn test_query_sql_no_joins() {
+    fn test_batch_upsert_merges_existing() {
         let db = Database::open_temp().unwrap();
-
-        let schema = serde_json::json!({
+        db.register_schema("person", serde_json::json!({
             "title": "Person",
             "version": "1.0.0",
             "short_name": "person",
-            "properties": {"name": {"type": "string"}},
+            "json_schema_extra": {"key_field": "name", "indexed_fields": ["city"]},
+            "properties": {"name": {"type": "string"}, "city": {"type": "string"}},
             "required": ["name"]
-        });
-
-        db.register_schema("person", schema).unwrap();
-
-        // JOIN should be rejected
-        let result = db.query_sql("tenant1", "SELECT * FROM person JOIN company ON person.company_id = company.id");
-        assert!(result.is_err());
+        })).unwrap();
+
+        let id = db.insert_with_ttl("tenant1", "person", serde_json::json!({"name": "Alice", "city": "Paris"}), std::time::Duration::from_secs(3600)).unwrap();
+        let original = db.get("tenant1", id).unwrap().unwrap();
+
+        let ids = db.batch_insert("tenant1", "person", vec![serde_json::json!({"name": "Alice", "city": "Lyon"})]).unwrap();
+        assert_eq!(ids, vec![id]);
+
+        // Upserts keep creation time and expiry, and replace field index entries
+        let upserted = db.get("tenant1", id).unwrap().unwrap();
+        assert_eq!(upserted.properties["city"], "Lyon");
+        assert_eq!(upserted.system.created_at, original.system.created_at);
+        assert_eq!(upserted.system.expires_at, original.system.expires_at);
+        assert!(db.field_index.lookup("tenant1", "person", "city", "Paris").unwrap().is_empty());
+        assert_eq!(db.field_index.lookup("tenant1", "person", "city", "Lyon").unwrap(), vec![id]);
+        assert!(db.check_consistency(false).unwrap().is_consistent());
     }
 }
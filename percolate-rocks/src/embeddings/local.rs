@@ -1,16 +1,21 @@
 //! Local embedding models using embed-anything.
 
 use crate::types::{Result, DatabaseError};
-use crate::embeddings::provider::EmbeddingProvider;
+use crate::embeddings::provider::{embed_in_batches, EmbeddingProvider};
 use async_trait::async_trait;
 use embed_anything::embeddings::embed::{Embedder, EmbedderBuilder};
 use std::sync::Arc;
 
+/// Texts per model call; bounds peak memory on large batches.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 256;
+
 /// Local embedding model provider using embed-anything.
 pub struct LocalEmbedder {
     embedder: Arc<Embedder>,
     model_name: String,
     dimensions: usize,
+    max_tokens: usize,
+    max_batch_size: usize,
 }
 
 impl LocalEmbedder {
@@ -48,19 +53,19 @@ impl LocalEmbedder {
     /// - `BAAI/bge-base-en-v1.5` (768 dims) - Best quality, slower
     /// - `jinaai/jina-embeddings-v2-small-en` (512 dims) - Good balance
     pub fn new(model_id: &str) -> Result<Self> {
-        // Determine dimensions and architecture based on model
-        let (dimensions, architecture) = match model_id {
-            "sentence-transformers/all-MiniLM-L6-v2" => (384, "bert"),
-            "BAAI/bge-small-en-v1.5" => (384, "bert"),
-            "BAAI/bge-base-en-v1.5" => (768, "bert"),
-            "jinaai/jina-embeddings-v2-small-en" => (512, "jina"),
-            "jinaai/jina-embeddings-v2-base-en" => (768, "jina"),
+        // Determine dimensions, architecture, and context window based on model
+        let (dimensions, architecture, max_tokens) = match model_id {
+            "sentence-transformers/all-MiniLM-L6-v2" => (384, "bert", 512),
+            "BAAI/bge-small-en-v1.5" => (384, "bert", 512),
+            "BAAI/bge-base-en-v1.5" => (768, "bert", 512),
+            "jinaai/jina-embeddings-v2-small-en" => (512, "jina", 8192),
+            "jinaai/jina-embeddings-v2-base-en" => (768, "jina", 8192),
             _ => {
                 // Try to infer from model name
                 if model_id.contains("jina") {
-                    (512, "jina")
+                    (512, "jina", 8192)
                 } else {
-                    (384, "bert") // Default
+                    (384, "bert", 512) // Default
                 }
             }
         };
@@ -80,13 +85,55 @@ impl LocalEmbedder {
             embedder: Arc::new(embedder),
             model_name: model_id.to_string(),
             dimensions,
+            max_tokens,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
         })
     }
+
+    /// Set maximum texts per model call.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_batch_size` - Texts per call (default: `DEFAULT_MAX_BATCH_SIZE`)
+    ///
+    /// # Returns
+    ///
+    /// Updated `LocalEmbedder`
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Embed one sub-batch with the model.
+    async fn embed_chunk(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        crate::otel::record_embedding_calls(1);
+
+        // Convert Vec<String> to Vec<&str>
+        let text_refs: Vec<&str> = texts.iter().map(|s| s.as_str()).collect();
+        let embedder = self.embedder.clone();
+
+        // embed_query returns Vec<EmbedData>, extract embeddings
+        let results = embed_anything::embed_query(&text_refs, &embedder, None)
+            .await
+            .map_err(|e| DatabaseError::EmbeddingError(format!("Batch embedding failed: {:?}", e)))?;
+
+        // Extract embeddings from each EmbedData
+        results.into_iter().map(|embed_data| {
+            match embed_data.embedding {
+                embed_anything::embeddings::embed::EmbeddingResult::DenseVector(vec) => Ok(vec),
+                embed_anything::embeddings::embed::EmbeddingResult::MultiVector(_) => {
+                    Err(DatabaseError::EmbeddingError("Expected DenseVector, got MultiVector".to_string()))
+                }
+            }
+        }).collect()
+    }
 }
 
 #[async_trait]
 impl EmbeddingProvider for LocalEmbedder {
     async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        crate::otel::record_embedding_calls(1);
+
         // embed_query takes &[&str]
         let texts = [text];
         let embedder = self.embedder.clone();
@@ -110,29 +157,20 @@ impl EmbeddingProvider for LocalEmbedder {
     }
 
     async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
-        // Convert Vec<String> to Vec<&str>
-        let text_refs: Vec<&str> = texts.iter().map(|s| s.as_str()).collect();
-        let embedder = self.embedder.clone();
-
-        // embed_query returns Vec<EmbedData>, extract embeddings
-        let results = embed_anything::embed_query(&text_refs, &embedder, None)
-            .await
-            .map_err(|e| DatabaseError::EmbeddingError(format!("Batch embedding failed: {:?}", e)))?;
-
-        // Extract embeddings from each EmbedData
-        results.into_iter().map(|embed_data| {
-            match embed_data.embedding {
-                embed_anything::embeddings::embed::EmbeddingResult::DenseVector(vec) => Ok(vec),
-                embed_anything::embeddings::embed::EmbeddingResult::MultiVector(_) => {
-                    Err(DatabaseError::EmbeddingError("Expected DenseVector, got MultiVector".to_string()))
-                }
-            }
-        }).collect()
+        embed_in_batches(texts, self.max_batch_size, None, move |batch| self.embed_chunk(batch)).await
     }
 
     fn dimensions(&self) -> usize {
         self.dimensions
     }
+
+    fn max_tokens(&self) -> Option<usize> {
+        Some(self.max_tokens)
+    }
+
+    fn max_batch_size(&self) -> Option<usize> {
+        Some(self.max_batch_size)
+    }
 }
 
 #[cfg(test)]
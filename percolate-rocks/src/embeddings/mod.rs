@@ -6,8 +6,18 @@ pub mod provider;
 pub mod local;
 pub mod openai;
 pub mod batch;
+pub mod norms;
+pub mod truncate;
+pub mod preprocess;
+pub mod pooling;
+pub mod projection;
 
 pub use provider::{EmbeddingProvider, ProviderFactory};
 pub use local::LocalEmbedder;
 pub use openai::OpenAIEmbedder;
 pub use batch::BatchEmbedder;
+pub use norms::{EmbeddingConsistencyReport, NormState};
+pub use truncate::{OverflowPolicy, Tokenizer, TruncationStrategy};
+pub use preprocess::{PreprocessingEmbedder, TextPreprocessor};
+pub use pooling::{embed_fields, PoolingStrategy};
+pub use projection::{DimensionReductionReport, PcaProjection};
@@ -0,0 +1,139 @@
+//! Embedding norm consistency checks.
+//!
+//! Providers differ on whether they return unit vectors. A table that mixes
+//! normalized and raw vectors (e.g. after a model switch) ranks unevenly, so
+//! these helpers classify stored norms and L2-normalize vectors in place.
+
+use serde::{Deserialize, Serialize};
+
+/// Allowed deviation from 1.0 for a vector to count as normalized.
+pub const NORM_TOLERANCE: f32 = 1e-3;
+
+/// Normalization state of a set of stored vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NormState {
+    /// No vectors found
+    Empty,
+    /// All vectors have unit norm
+    Normalized,
+    /// No vector has unit norm
+    Unnormalized,
+    /// Some vectors normalized, some not
+    Mixed,
+}
+
+/// Result of sampling stored embedding norms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingConsistencyReport {
+    /// Vectors inspected
+    pub sampled: usize,
+    /// Vectors with norm within `NORM_TOLERANCE` of 1.0
+    pub normalized: usize,
+    /// Vectors outside tolerance
+    pub unnormalized: usize,
+    /// Smallest norm seen
+    pub min_norm: f32,
+    /// Largest norm seen
+    pub max_norm: f32,
+    /// Overall classification
+    pub state: NormState,
+}
+
+impl EmbeddingConsistencyReport {
+    /// Build report from vector norms.
+    ///
+    /// # Arguments
+    ///
+    /// * `norms` - L2 norm of each sampled vector
+    pub fn from_norms(norms: impl IntoIterator<Item = f32>) -> Self {
+        let mut report = Self {
+            sampled: 0,
+            normalized: 0,
+            unnormalized: 0,
+            min_norm: f32::INFINITY,
+            max_norm: 0.0,
+            state: NormState::Empty,
+        };
+
+        for n in norms {
+            report.sampled += 1;
+            report.min_norm = report.min_norm.min(n);
+            report.max_norm = report.max_norm.max(n);
+            if is_unit(n) {
+                report.normalized += 1;
+            } else {
+                report.unnormalized += 1;
+            }
+        }
+
+        if report.sampled == 0 {
+            report.min_norm = 0.0;
+        }
+
+        report.state = match (report.normalized, report.unnormalized) {
+            (0, 0) => NormState::Empty,
+            (_, 0) => NormState::Normalized,
+            (0, _) => NormState::Unnormalized,
+            _ => NormState::Mixed,
+        };
+
+        report
+    }
+
+    /// `true` unless normalized and unnormalized vectors are mixed.
+    pub fn is_consistent(&self) -> bool {
+        self.state != NormState::Mixed
+    }
+}
+
+/// Check whether a norm is within tolerance of 1.0.
+pub fn is_unit(norm: f32) -> bool {
+    (norm - 1.0).abs() <= NORM_TOLERANCE
+}
+
+/// L2-normalize a vector in place.
+///
+/// Zero vectors are left unchanged.
+///
+/// # Returns
+///
+/// Norm before normalization
+pub fn l2_normalize(vector: &mut [f32]) -> f32 {
+    let norm = crate::index::similarity::norm(vector);
+    if norm > 0.0 {
+        for x in vector.iter_mut() {
+            *x /= norm;
+        }
+    }
+    norm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_states() {
+        assert_eq!(EmbeddingConsistencyReport::from_norms(vec![]).state, NormState::Empty);
+        assert_eq!(EmbeddingConsistencyReport::from_norms(vec![1.0, 1.0005]).state, NormState::Normalized);
+        assert_eq!(EmbeddingConsistencyReport::from_norms(vec![3.0, 0.5]).state, NormState::Unnormalized);
+
+        let mixed = EmbeddingConsistencyReport::from_norms(vec![1.0, 5.0]);
+        assert_eq!(mixed.state, NormState::Mixed);
+        assert!(!mixed.is_consistent());
+        assert_eq!(mixed.min_norm, 1.0);
+        assert_eq!(mixed.max_norm, 5.0);
+    }
+
+    #[test]
+    fn test_l2_normalize() {
+        let mut v = vec![3.0, 4.0];
+        assert_eq!(l2_normalize(&mut v), 5.0);
+        assert!((v[0] - 0.6).abs() < 1e-6);
+        assert!((v[1] - 0.8).abs() < 1e-6);
+
+        let mut zero = vec![0.0, 0.0];
+        l2_normalize(&mut zero);
+        assert_eq!(zero, vec![0.0, 0.0]);
+    }
+}
@@ -1,10 +1,23 @@
 //! OpenAI embedding API client.
 
 use crate::types::{Result, DatabaseError};
-use crate::embeddings::provider::EmbeddingProvider;
+use crate::embeddings::provider::{embed_in_batches, EmbeddingProvider};
+use crate::embeddings::truncate::Tokenizer;
+use crate::http::{with_deadline, DEFAULT_REQUEST_TIMEOUT};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// OpenAI API base URL.
+const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
+
+/// Maximum inputs per embeddings request accepted by the API.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 2048;
+
+/// Maximum total tokens per embeddings request accepted by the API.
+pub const MAX_BATCH_TOKENS: usize = 300_000;
 
 /// OpenAI API embedding request.
 #[derive(Debug, Serialize)]
@@ -29,7 +42,12 @@ pub struct OpenAIEmbedder {
     api_key: String,
     model: String,
     dimensions: usize,
+    max_batch_size: usize,
+    tokenizer: Tokenizer,
     client: Client,
+    base_url: String,
+    timeout: Duration,
+    cancel: Option<CancellationToken>,
 }
 
 impl OpenAIEmbedder {
@@ -51,24 +69,102 @@ impl OpenAIEmbedder {
             "text-embedding-ada-002" => 1536,
             _ => 1536,  // Default to 1536
         };
+        let tokenizer = Tokenizer::for_model(&model);
 
         Self {
             api_key,
             model,
             dimensions,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            tokenizer,
             client: Client::new(),
+            base_url: OPENAI_API_BASE.to_string(),
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            cancel: None,
         }
     }
 
+    /// Set maximum texts per request.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_batch_size` - Texts per request (default: `DEFAULT_MAX_BATCH_SIZE`)
+    ///
+    /// # Returns
+    ///
+    /// Updated `OpenAIEmbedder`
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Set the per-request timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum time per API request (default: `DEFAULT_REQUEST_TIMEOUT`)
+    ///
+    /// # Returns
+    ///
+    /// Updated `OpenAIEmbedder`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Abort in-flight and future requests when `token` is cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Cancellation token
+    ///
+    /// # Returns
+    ///
+    /// Updated `OpenAIEmbedder`
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Set the API base URL (for proxies and OpenAI-compatible servers).
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - Base URL including version (default: `https://api.openai.com/v1`)
+    ///
+    /// # Returns
+    ///
+    /// Updated `OpenAIEmbedder`
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Call OpenAI embeddings API.
+    ///
+    /// Fails with `DatabaseError::Timeout` or `DatabaseError::Cancelled`
+    /// if the request outlives the timeout or the cancellation token.
     async fn call_api(&self, input: serde_json::Value) -> Result<Vec<Vec<f32>>> {
+        // Each request counts, so a split `embed_batch` counts once per sub-batch
+        crate::otel::record_embedding_calls(1);
+        with_deadline(
+            self.timeout,
+            self.cancel.as_ref(),
+            "OpenAI embeddings request",
+            self.send_request(input),
+        )
+        .await
+    }
+
+    /// Send one embeddings request and parse the response.
+    async fn send_request(&self, input: serde_json::Value) -> Result<Vec<Vec<f32>>> {
         let request = EmbeddingRequest {
             model: self.model.clone(),
             input,
         };
 
         let response = self.client
-            .post("https://api.openai.com/v1/embeddings")
+            .post(format!("{}/embeddings", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request)
@@ -109,12 +205,140 @@ impl EmbeddingProvider for OpenAIEmbedder {
             return Ok(Vec::new());
         }
 
-        // OpenAI supports batch embeddings (up to ~2048 texts per request)
-        // For simplicity, send all at once (caller should handle chunking if needed)
-        self.call_api(serde_json::json!(texts)).await
+        // Split into requests within the API's input count and token caps
+        embed_in_batches(texts, self.max_batch_size, Some(MAX_BATCH_TOKENS), move |batch| {
+            self.call_api(serde_json::json!(batch))
+        })
+        .await
     }
 
     fn dimensions(&self) -> usize {
         self.dimensions
     }
+
+    fn max_tokens(&self) -> Option<usize> {
+        // All current OpenAI embedding models share this input limit
+        Some(8191)
+    }
+
+    fn tokenizer(&self) -> Tokenizer {
+        self.tokenizer.clone()
+    }
+
+    fn max_batch_size(&self) -> Option<usize> {
+        Some(self.max_batch_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Server that accepts connections and never responds.
+    async fn hanging_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        format!("http://{}/v1", addr)
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let embedder = OpenAIEmbedder::new("test-key".to_string(), "text-embedding-3-small".to_string())
+            .with_base_url(hanging_server().await)
+            .with_timeout(Duration::from_millis(200));
+
+        let started = Instant::now();
+        let result = embedder.embed("hello").await;
+
+        assert!(matches!(result, Err(DatabaseError::Timeout(_))), "got {:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_request_cancellation() {
+        let token = CancellationToken::new();
+        let embedder = OpenAIEmbedder::new("test-key".to_string(), "text-embedding-3-small".to_string())
+            .with_base_url(hanging_server().await)
+            .with_cancellation(token.clone());
+
+        let cancel = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            token.cancel();
+        });
+
+        let started = Instant::now();
+        let result = embedder.embed_batch(&["a".to_string(), "b".to_string()]).await;
+        cancel.await.unwrap();
+
+        assert!(matches!(result, Err(DatabaseError::Cancelled(_))), "got {:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    /// Embeddings server answering one `[0.5, 0.5]` vector per input.
+    async fn embedding_server() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+
+                // Headers, then Content-Length bytes of body
+                let (head_len, body_len) = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    assert!(n > 0, "connection closed before headers");
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|value| value.trim().parse().ok())
+                            .unwrap_or(0);
+                        break (end + 4, length);
+                    }
+                };
+                while buf.len() < head_len + body_len {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+
+                let body: serde_json::Value = serde_json::from_slice(&buf[head_len..head_len + body_len]).unwrap();
+                let inputs = body["input"].as_array().map_or(1, |inputs| inputs.len());
+                let data: Vec<_> = (0..inputs).map(|_| serde_json::json!({"embedding": [0.5, 0.5]})).collect();
+                let reply = serde_json::json!({ "data": data }).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    reply.len(),
+                    reply
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        format!("http://{}/v1", addr)
+    }
+
+    #[tokio::test]
+    async fn test_each_request_counts_as_embedding_call() {
+        let embedder = OpenAIEmbedder::new("test-key".to_string(), "text-embedding-3-small".to_string())
+            .with_base_url(embedding_server().await)
+            .with_max_batch_size(2);
+        let before = crate::otel::metrics().embedding_calls();
+
+        assert_eq!(embedder.embed("a").await.unwrap(), vec![0.5, 0.5]);
+        let texts: Vec<String> = (0..5).map(|i| i.to_string()).collect();
+        assert_eq!(embedder.embed_batch(&texts).await.unwrap().len(), 5);
+
+        // One single request plus three sub-batches (other tests may add more)
+        assert!(crate::otel::metrics().embedding_calls() - before >= 4);
+    }
 }
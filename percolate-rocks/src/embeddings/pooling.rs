@@ -0,0 +1,216 @@
+//! Combine several embedding fields into one vector.
+//!
+//! A schema with more than one `embedding_field` can embed them:
+//!
+//! - `concat` (default): join the field texts and embed once
+//! - `mean`: embed each field separately and average the vectors
+//! - `weighted`: like `mean`, scaled by `embedding_field_weights`
+//!
+//! Concatenation lets a long field drown out a short one; pooling keeps
+//! each field's signal. Pooled vectors are L2-normalized.
+//!
+//! ```python
+//! model_config = ConfigDict(
+//!     json_schema_extra={
+//!         "embedding_fields": ["title", "content"],
+//!         "embedding_pooling": "weighted",
+//!         "embedding_field_weights": {"title": 2.0, "content": 1.0}
+//!     }
+//! )
+//! ```
+
+use crate::embeddings::norms::l2_normalize;
+use crate::embeddings::truncate::{embed_within_limit, OverflowPolicy};
+use crate::embeddings::EmbeddingProvider;
+use crate::types::{DatabaseError, Result};
+use std::collections::HashMap;
+
+/// How multiple embedding fields become one vector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PoolingStrategy {
+    /// Embed the joined field texts once
+    #[default]
+    Concat,
+    /// Average per-field embeddings
+    Mean,
+    /// Weighted average of per-field embeddings
+    Weighted,
+}
+
+impl PoolingStrategy {
+    /// Parse strategy from string (`concat`, `mean`, `weighted`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "concat" => Ok(Self::Concat),
+            "mean" => Ok(Self::Mean),
+            "weighted" => Ok(Self::Weighted),
+            other => Err(DatabaseError::ConfigError(format!(
+                "Unknown embedding pooling '{}' (expected concat, mean, or weighted)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Embed an entity's embedding fields.
+///
+/// Missing and null fields are skipped; non-string values are embedded as
+/// their JSON text. Under `Weighted`, fields without a configured weight
+/// count as 1.0.
+///
+/// # Arguments
+///
+/// * `provider` - Embedding provider
+/// * `properties` - Entity properties
+/// * `fields` - Fields to embed, in order
+/// * `strategy` - Pooling strategy
+/// * `weights` - Per-field weights (used by `Weighted`)
+/// * `overflow` - What to do with text over the model's token limit
+///
+/// # Returns
+///
+/// The embedding, or `None` if no field has a value
+///
+/// # Errors
+///
+/// Returns `DatabaseError::ConfigError` if the weights of present fields
+/// sum to zero, or the provider's error
+pub async fn embed_fields(
+    provider: &dyn EmbeddingProvider,
+    properties: &serde_json::Value,
+    fields: &[String],
+    strategy: PoolingStrategy,
+    weights: &HashMap<String, f32>,
+    overflow: OverflowPolicy,
+) -> Result<Option<Vec<f32>>> {
+    let texts: Vec<(&str, String)> = fields
+        .iter()
+        .filter_map(|field| {
+            let text = match properties.get(field)? {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            Some((field.as_str(), text))
+        })
+        .collect();
+
+    if texts.is_empty() {
+        return Ok(None);
+    }
+
+    if strategy == PoolingStrategy::Concat {
+        let joined = texts.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>().join("\n\n");
+        let (embedding, _) = embed_within_limit(provider, &joined, overflow).await?;
+        return Ok(Some(embedding));
+    }
+
+    let mut pooled: Vec<f32> = Vec::new();
+    let mut total_weight = 0.0;
+
+    for (field, text) in &texts {
+        let weight = match strategy {
+            PoolingStrategy::Weighted => weights.get(*field).copied().unwrap_or(1.0),
+            _ => 1.0,
+        };
+
+        let (embedding, _) = embed_within_limit(provider, text, overflow).await?;
+        if pooled.is_empty() {
+            pooled = vec![0.0; embedding.len()];
+        }
+        for (sum, x) in pooled.iter_mut().zip(&embedding) {
+            *sum += weight * x;
+        }
+        total_weight += weight;
+    }
+
+    if total_weight <= 0.0 {
+        return Err(DatabaseError::ConfigError(
+            "Embedding field weights must sum to a positive value".to_string(),
+        ));
+    }
+
+    for x in pooled.iter_mut() {
+        *x /= total_weight;
+    }
+    l2_normalize(&mut pooled);
+
+    Ok(Some(pooled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    /// Embeds known words to fixed vectors; anything else to its length.
+    struct WordEmbedder;
+
+    #[async_trait]
+    impl EmbeddingProvider for WordEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            Ok(match text {
+                "north" => vec![0.0, 1.0],
+                "east" => vec![3.0, 0.0],
+                other => vec![other.len() as f32, 0.0],
+            })
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::with_capacity(texts.len());
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+    }
+
+    fn fields() -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(PoolingStrategy::parse("Mean").unwrap(), PoolingStrategy::Mean);
+        assert!(PoolingStrategy::parse("max").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concat_embeds_joined_text() {
+        let props = json!({"a": "north", "b": "east"});
+        let embedding = embed_fields(&WordEmbedder, &props, &fields(), PoolingStrategy::Concat, &HashMap::new(), OverflowPolicy::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(embedding, vec!["north\n\neast".len() as f32, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_pooling() {
+        let props = json!({"a": "north", "b": "east", "c": null});
+        let weights = HashMap::from([("a".to_string(), 3.0)]);
+
+        // (3 * [0, 1] + 1 * [3, 0]) / 4 = [0.75, 0.75], normalized
+        let embedding = embed_fields(&WordEmbedder, &props, &fields(), PoolingStrategy::Weighted, &weights, OverflowPolicy::default())
+            .await
+            .unwrap()
+            .unwrap();
+        let expected = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((embedding[0] - expected).abs() < 1e-6 && (embedding[1] - expected).abs() < 1e-6);
+
+        let zero = HashMap::from([("a".to_string(), 0.0), ("b".to_string(), 0.0)]);
+        assert!(embed_fields(&WordEmbedder, &props, &fields(), PoolingStrategy::Weighted, &zero, OverflowPolicy::default()).await.is_err());
+
+        let empty = embed_fields(&WordEmbedder, &json!({}), &fields(), PoolingStrategy::Mean, &HashMap::new(), OverflowPolicy::default()).await.unwrap();
+        assert!(empty.is_none());
+    }
+}
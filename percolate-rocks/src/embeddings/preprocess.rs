@@ -0,0 +1,277 @@
+//! Normalize text before embedding.
+//!
+//! Inputs that differ only in case or whitespace embed to slightly
+//! different vectors. A `TextPreprocessor` removes those differences, and
+//! `PreprocessingEmbedder` applies it inside the provider so documents and
+//! queries always go through the same steps.
+//!
+//! # Steps
+//!
+//! Applied in this order, each opt-in:
+//!
+//! - `control`: drop control characters (tabs and newlines count as whitespace)
+//! - `whitespace`: collapse runs of whitespace to a single space
+//! - `trim`: remove leading and trailing whitespace
+//! - `lowercase`: Unicode lowercase
+//!
+//! Configured per schema alongside the provider:
+//!
+//! ```python
+//! model_config = ConfigDict(
+//!     json_schema_extra={
+//!         "embedding_provider": "local:all-MiniLM-L6-v2",
+//!         "embedding_preprocess": ["control", "whitespace", "trim", "lowercase"]
+//!     }
+//! )
+//! ```
+
+use crate::embeddings::{EmbeddingProvider, Tokenizer};
+use crate::types::{DatabaseError, Result};
+use async_trait::async_trait;
+use std::borrow::Cow;
+
+/// Text normalization applied before embedding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextPreprocessor {
+    /// Lowercase the text
+    pub lowercase: bool,
+    /// Trim leading and trailing whitespace
+    pub trim: bool,
+    /// Collapse internal whitespace runs to one space
+    pub collapse_whitespace: bool,
+    /// Drop non-whitespace control characters
+    pub strip_control: bool,
+}
+
+impl TextPreprocessor {
+    /// Preprocessor with every step enabled.
+    pub fn all() -> Self {
+        Self {
+            lowercase: true,
+            trim: true,
+            collapse_whitespace: true,
+            strip_control: true,
+        }
+    }
+
+    /// Build a preprocessor from step names.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - Any of `lowercase`, `trim`, `whitespace`, `control`, or `all`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown step names
+    pub fn from_steps<S: AsRef<str>>(steps: &[S]) -> Result<Self> {
+        let mut preprocessor = Self::default();
+        for step in steps {
+            match step.as_ref().to_ascii_lowercase().as_str() {
+                "lowercase" => preprocessor.lowercase = true,
+                "trim" => preprocessor.trim = true,
+                "whitespace" => preprocessor.collapse_whitespace = true,
+                "control" => preprocessor.strip_control = true,
+                "all" => preprocessor = Self::all(),
+                other => {
+                    return Err(DatabaseError::ConfigError(format!(
+                        "Unknown embedding preprocess step '{}' (expected lowercase, trim, whitespace, control, or all)",
+                        other
+                    )));
+                }
+            }
+        }
+        Ok(preprocessor)
+    }
+
+    /// Whether no step is enabled.
+    pub fn is_noop(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Normalize text.
+    ///
+    /// The result is also the text to key caches on, since equal outputs
+    /// embed identically.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Input text
+    ///
+    /// # Returns
+    ///
+    /// Normalized text (borrowed when nothing changed)
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.is_noop() {
+            return Cow::Borrowed(text);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut pending_space = false;
+
+        for c in text.chars() {
+            if self.strip_control && c.is_control() && !c.is_whitespace() {
+                continue;
+            }
+            if self.collapse_whitespace && c.is_whitespace() {
+                pending_space = true;
+                continue;
+            }
+            if pending_space {
+                out.push(' ');
+                pending_space = false;
+            }
+            out.push(c);
+        }
+        if pending_space {
+            out.push(' ');
+        }
+
+        if self.trim {
+            let trimmed = out.trim();
+            if trimmed.len() != out.len() {
+                out = trimmed.to_string();
+            }
+        }
+        if self.lowercase {
+            out = out.to_lowercase();
+        }
+
+        if out == text {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(out)
+        }
+    }
+}
+
+/// Embedding provider that preprocesses every input.
+pub struct PreprocessingEmbedder {
+    inner: Box<dyn EmbeddingProvider>,
+    preprocessor: TextPreprocessor,
+}
+
+impl PreprocessingEmbedder {
+    /// Wrap a provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - Provider that receives normalized text
+    /// * `preprocessor` - Steps to apply
+    ///
+    /// # Returns
+    ///
+    /// New `PreprocessingEmbedder`
+    pub fn new(inner: Box<dyn EmbeddingProvider>, preprocessor: TextPreprocessor) -> Self {
+        Self { inner, preprocessor }
+    }
+
+    /// Get the configured preprocessor.
+    pub fn preprocessor(&self) -> TextPreprocessor {
+        self.preprocessor
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for PreprocessingEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.inner.embed(&self.preprocessor.apply(text)).await
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let texts: Vec<String> = texts
+            .iter()
+            .map(|t| self.preprocessor.apply(t).into_owned())
+            .collect();
+        self.inner.embed_batch(&texts).await
+    }
+
+    fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    fn max_tokens(&self) -> Option<usize> {
+        self.inner.max_tokens()
+    }
+
+    fn tokenizer(&self) -> Tokenizer {
+        self.inner.tokenizer()
+    }
+
+    fn max_batch_size(&self) -> Option<usize> {
+        self.inner.max_batch_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Embeds the BLAKE3 hash of the exact input, so any difference shows.
+    struct HashEmbedder;
+
+    #[async_trait]
+    impl EmbeddingProvider for HashEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let hash = blake3::hash(text.as_bytes());
+            Ok(hash.as_bytes()[..4].iter().map(|b| *b as f32).collect())
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::with_capacity(texts.len());
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+
+        fn dimensions(&self) -> usize {
+            4
+        }
+    }
+
+    #[test]
+    fn test_apply_all_steps() {
+        let p = TextPreprocessor::all();
+        assert_eq!(p.apply("  Hello,\t\tWORLD \n\u{0007}again  "), "hello, world again");
+        assert_eq!(p.apply("already normal"), "already normal");
+        assert!(matches!(p.apply("already normal"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_default_is_noop() {
+        let p = TextPreprocessor::default();
+        assert!(p.is_noop());
+        assert_eq!(p.apply("  Mixed  Case "), "  Mixed  Case ");
+    }
+
+    #[test]
+    fn test_from_steps() {
+        let p = TextPreprocessor::from_steps(&["trim", "Lowercase"]).unwrap();
+        assert!(p.trim && p.lowercase);
+        assert!(!p.collapse_whitespace && !p.strip_control);
+        assert_eq!(p.apply("  A  B "), "a  b");
+
+        assert_eq!(TextPreprocessor::from_steps(&["all"]).unwrap(), TextPreprocessor::all());
+        assert!(TextPreprocessor::from_steps(&["stem"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_whitespace_and_case_variants_embed_identically() {
+        let a = "Rust   ownership\nrules";
+        let b = "  rust ownership RULES ";
+
+        // Raw inputs embed differently
+        assert_ne!(HashEmbedder.embed(a).await.unwrap(), HashEmbedder.embed(b).await.unwrap());
+
+        let preprocessor = TextPreprocessor::all();
+        let embedder = PreprocessingEmbedder::new(Box::new(HashEmbedder), preprocessor);
+
+        // Same cache key, same embedding
+        assert_eq!(preprocessor.apply(a), preprocessor.apply(b));
+        assert_eq!(embedder.embed(a).await.unwrap(), embedder.embed(b).await.unwrap());
+
+        let batch = embedder.embed_batch(&[a.to_string(), b.to_string()]).await.unwrap();
+        assert_eq!(batch[0], batch[1]);
+        assert_eq!(batch[0], embedder.embed(a).await.unwrap());
+    }
+}
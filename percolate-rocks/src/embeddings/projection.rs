@@ -0,0 +1,329 @@
+//! PCA dimensionality reduction for stored embeddings.
+//!
+//! Large embeddings (1536 dims for OpenAI) dominate storage and index
+//! size, and many tables keep nearly all of their signal in far fewer
+//! dimensions. A `PcaProjection` is fit over a sample of a table's vectors
+//! and maps each vector onto its top principal components:
+//!
+//! ```text
+//! projected = components · (vector - mean)
+//! ```
+//!
+//! Stored vectors, newly inserted vectors, and search queries all go
+//! through the same projection, so similarity is compared in one space.
+//! Components are found by subspace iteration on the sample covariance,
+//! which costs `O(d² · k)` per iteration for `d` source and `k` target
+//! dimensions; fitting is a one-off maintenance step.
+
+use crate::types::{DatabaseError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Maximum subspace iterations when fitting.
+const MAX_ITERATIONS: usize = 100;
+
+/// Change in captured variance (relative) below which fitting stops.
+const CONVERGENCE_TOLERANCE: f64 = 1e-9;
+
+/// Outcome of fitting and applying a projection to a table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DimensionReductionReport {
+    /// Dimension of the original vectors
+    pub source_dim: usize,
+    /// Dimension of the stored vectors after reduction
+    pub target_dim: usize,
+    /// Vectors the projection was fit on
+    pub sampled: usize,
+    /// Fraction of the sample's variance kept (0.0 to 1.0)
+    pub explained_variance: f32,
+    /// Stored vectors rewritten
+    pub rewritten: usize,
+}
+
+/// Linear projection onto the top principal components of a sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PcaProjection {
+    /// Per-dimension mean of the sample
+    mean: Vec<f32>,
+    /// Unit principal components (`target_dim` rows of `source_dim`),
+    /// highest variance first
+    components: Vec<Vec<f32>>,
+    /// Fraction of the sample's variance the components capture
+    explained_variance: f32,
+}
+
+impl PcaProjection {
+    /// Fit a projection over sample vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Vectors of equal length
+    /// * `target_dim` - Dimensions to keep
+    ///
+    /// # Returns
+    ///
+    /// Projection from the sample dimension down to `target_dim`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` if `target_dim` is zero or not
+    /// below the sample dimension, or there are fewer samples than
+    /// `target_dim`; `DatabaseError::ValidationError` if the samples differ
+    /// in length
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pca = PcaProjection::fit(&vectors, 256)?;
+    /// let reduced = pca.project(&vectors[0]);
+    /// ```
+    pub fn fit(samples: &[Vec<f32>], target_dim: usize) -> Result<Self> {
+        let source_dim = samples.first().map(|s| s.len()).unwrap_or(0);
+        if target_dim == 0 || target_dim >= source_dim {
+            return Err(DatabaseError::ConfigError(format!(
+                "Target dimension must be between 1 and {} (got {})",
+                source_dim.saturating_sub(1),
+                target_dim
+            )));
+        }
+        if samples.len() < target_dim {
+            return Err(DatabaseError::ConfigError(format!(
+                "Need at least {} sample vectors to fit {} dimensions (got {})",
+                target_dim,
+                target_dim,
+                samples.len()
+            )));
+        }
+        if let Some(index) = samples.iter().position(|s| s.len() != source_dim) {
+            return Err(DatabaseError::validation(format!(
+                "Sample {} has {} dimensions, expected {}",
+                index,
+                samples[index].len(),
+                source_dim
+            )));
+        }
+
+        let n = samples.len() as f64;
+        let mut mean = vec![0.0f64; source_dim];
+        for sample in samples {
+            for (m, x) in mean.iter_mut().zip(sample) {
+                *m += *x as f64 / n;
+            }
+        }
+
+        // Sample covariance
+        let mut covariance = vec![vec![0.0f64; source_dim]; source_dim];
+        let mut centered = vec![0.0f64; source_dim];
+        for sample in samples {
+            for (c, (x, m)) in centered.iter_mut().zip(sample.iter().zip(&mean)) {
+                *c = *x as f64 - m;
+            }
+            for (row, ci) in covariance.iter_mut().zip(&centered) {
+                for (cell, cj) in row.iter_mut().zip(&centered) {
+                    *cell += ci * cj / n;
+                }
+            }
+        }
+        let total_variance: f64 = covariance.iter().enumerate().map(|(i, row)| row[i]).sum();
+
+        // Subspace iteration from a fixed start, so fits are reproducible
+        let mut basis: Vec<Vec<f64>> = (0..target_dim)
+            .map(|k| {
+                (0..source_dim)
+                    .map(|i| if i == k { 1.0 } else { ((i * 31 + k * 17) % 13) as f64 / 130.0 })
+                    .collect()
+            })
+            .collect();
+        orthonormalize(&mut basis);
+
+        let mut captured = 0.0;
+        for _ in 0..MAX_ITERATIONS {
+            let mut next: Vec<Vec<f64>> = basis.iter().map(|v| multiply(&covariance, v)).collect();
+            orthonormalize(&mut next);
+            basis = next;
+
+            let previous = captured;
+            captured = basis.iter().map(|v| rayleigh(&covariance, v)).sum::<f64>();
+            if (captured - previous).abs() <= CONVERGENCE_TOLERANCE * total_variance.max(f64::MIN_POSITIVE) {
+                break;
+            }
+        }
+
+        // Highest variance first
+        let mut ranked: Vec<(f64, Vec<f64>)> = basis
+            .into_iter()
+            .map(|v| (rayleigh(&covariance, &v), v))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let explained_variance = if total_variance > 0.0 {
+            (captured / total_variance).min(1.0) as f32
+        } else {
+            0.0
+        };
+
+        Ok(Self {
+            mean: mean.iter().map(|m| *m as f32).collect(),
+            components: ranked
+                .into_iter()
+                .map(|(_, v)| v.iter().map(|x| *x as f32).collect())
+                .collect(),
+            explained_variance,
+        })
+    }
+
+    /// Project a vector into the reduced space.
+    ///
+    /// # Arguments
+    ///
+    /// * `vector` - Vector of `source_dim` length
+    ///
+    /// # Returns
+    ///
+    /// Vector of `target_dim` length
+    pub fn project(&self, vector: &[f32]) -> Vec<f32> {
+        self.components
+            .iter()
+            .map(|component| {
+                component
+                    .iter()
+                    .zip(vector.iter().zip(&self.mean))
+                    .map(|(c, (x, m))| c * (x - m))
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Dimension of vectors the projection accepts.
+    pub fn source_dim(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// Dimension of projected vectors.
+    pub fn target_dim(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Fraction of the fitted sample's variance kept (0.0 to 1.0).
+    pub fn explained_variance(&self) -> f32 {
+        self.explained_variance
+    }
+}
+
+/// Matrix-vector product.
+fn multiply(matrix: &[Vec<f64>], v: &[f64]) -> Vec<f64> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+/// `vᵀ M v` for a unit vector `v`.
+fn rayleigh(matrix: &[Vec<f64>], v: &[f64]) -> f64 {
+    multiply(matrix, v).iter().zip(v).map(|(a, b)| a * b).sum()
+}
+
+/// Modified Gram-Schmidt in place.
+///
+/// A vector that collapses (the covariance has lower rank than the basis)
+/// is replaced by a unit vector along the first axis left orthogonal.
+fn orthonormalize(basis: &mut [Vec<f64>]) {
+    for k in 0..basis.len() {
+        let (done, rest) = basis.split_at_mut(k);
+        let v = &mut rest[0];
+
+        for axis in 0..=v.len() {
+            for u in done.iter() {
+                let projection: f64 = u.iter().zip(v.iter()).map(|(a, b)| a * b).sum();
+                for (x, y) in v.iter_mut().zip(u) {
+                    *x -= projection * y;
+                }
+            }
+
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 1e-12 {
+                for x in v.iter_mut() {
+                    *x /= norm;
+                }
+                break;
+            }
+            if axis < v.len() {
+                v.fill(0.0);
+                v[axis] = 1.0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::similarity::cosine_distance;
+    use rand::{Rng, SeedableRng};
+
+    /// Vectors on a low-rank subspace with a little isotropic noise.
+    fn low_rank_vectors(n: usize, dims: usize, rank: usize) -> Vec<Vec<f32>> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let basis: Vec<Vec<f32>> = (0..rank)
+            .map(|_| (0..dims).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+
+        (0..n)
+            .map(|_| {
+                let weights: Vec<f32> = (0..rank).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                (0..dims)
+                    .map(|i| {
+                        let signal: f32 = basis.iter().zip(&weights).map(|(b, w)| b[i] * w).sum();
+                        signal + rng.gen_range(-0.05..0.05)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Indexes of the `k` nearest vectors by cosine distance.
+    fn nearest(query: &[f32], vectors: &[Vec<f32>], k: usize) -> Vec<usize> {
+        let mut ranked: Vec<(usize, f32)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i, cosine_distance(query, v)))
+            .collect();
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+        ranked.into_iter().take(k).map(|(i, _)| i).collect()
+    }
+
+    #[test]
+    fn test_pca_preserves_nearest_neighbors() {
+        let vectors = low_rank_vectors(500, 128, 16);
+
+        let pca = PcaProjection::fit(&vectors, 32).unwrap();
+        assert_eq!((pca.source_dim(), pca.target_dim()), (128, 32));
+        assert!(pca.explained_variance() > 0.95, "explained {}", pca.explained_variance());
+
+        let projected: Vec<Vec<f32>> = vectors.iter().map(|v| pca.project(v)).collect();
+        assert_eq!(projected[0].len(), 32);
+
+        let queries = 50;
+        let mut hits = 0;
+        for q in 0..queries {
+            let exact = nearest(&vectors[q], &vectors, 10);
+            let reduced = nearest(&projected[q], &projected, 10);
+            hits += reduced.iter().filter(|i| exact.contains(i)).count();
+        }
+        let recall = hits as f32 / (queries * 10) as f32;
+        assert!(recall > 0.8, "recall@10 {}", recall);
+    }
+
+    #[test]
+    fn test_fit_rejects_bad_dimensions() {
+        let vectors = low_rank_vectors(8, 4, 2);
+
+        assert!(PcaProjection::fit(&vectors, 0).is_err());
+        assert!(PcaProjection::fit(&vectors, 4).is_err());
+        assert!(PcaProjection::fit(&vectors[..1], 2).is_err());
+        assert!(PcaProjection::fit(&[vec![1.0, 2.0, 3.0], vec![1.0, 2.0]], 1).is_err());
+
+        // Rank-deficient samples still yield orthonormal components
+        let pca = PcaProjection::fit(&vectors, 3).unwrap();
+        assert_eq!(pca.target_dim(), 3);
+    }
+}
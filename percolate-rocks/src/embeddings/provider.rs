@@ -1,7 +1,9 @@
 //! Embedding provider trait and factory.
 
-use crate::types::Result;
+use crate::embeddings::truncate::Tokenizer;
+use crate::types::{DatabaseError, Result};
 use async_trait::async_trait;
+use std::future::Future;
 
 /// Embedding provider trait.
 #[async_trait]
@@ -42,6 +44,120 @@ pub trait EmbeddingProvider: Send + Sync {
     ///
     /// Vector dimension
     fn dimensions(&self) -> usize;
+
+    /// Get model context window in tokens.
+    ///
+    /// Inputs longer than this are truncated or rejected before embedding
+    /// (see `embeddings::truncate`).
+    ///
+    /// # Returns
+    ///
+    /// Maximum input tokens, or `None` if unbounded
+    fn max_tokens(&self) -> Option<usize> {
+        None
+    }
+
+    /// Get the tokenizer `max_tokens` is measured in.
+    ///
+    /// Used to count and cut over-length input. The default,
+    /// `Tokenizer::Estimate` (~4 characters per token), is a fallback for
+    /// models whose tokenizer isn't available; it undercounts code, CJK,
+    /// and numeric text.
+    ///
+    /// # Returns
+    ///
+    /// Tokenizer matching the model
+    fn tokenizer(&self) -> Tokenizer {
+        Tokenizer::Estimate
+    }
+
+    /// Get maximum texts per embedding request.
+    ///
+    /// `embed_batch` splits larger batches into sub-requests of at most
+    /// this many texts (see `embed_in_batches`).
+    ///
+    /// # Returns
+    ///
+    /// Maximum batch size, or `None` if unbounded
+    fn max_batch_size(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Embed texts in sub-batches that respect provider request limits.
+///
+/// Texts are grouped in order into batches of at most `max_batch_size`
+/// texts and, if set, at most `max_batch_tokens` estimated tokens (a single
+/// text over the token budget is sent on its own). `embed` is called once
+/// per batch and the results are concatenated in input order.
+///
+/// # Arguments
+///
+/// * `texts` - Input texts
+/// * `max_batch_size` - Maximum texts per request (minimum 1)
+/// * `max_batch_tokens` - Maximum estimated tokens per request (`None` = unbounded)
+/// * `embed` - Backend call for one batch
+///
+/// # Returns
+///
+/// One embedding per input text, in order
+///
+/// # Errors
+///
+/// Returns `DatabaseError::EmbeddingError` if a backend call fails or returns
+/// the wrong number of embeddings
+///
+/// # Example
+///
+/// ```rust,ignore
+/// async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+///     embed_in_batches(texts, self.max_batch_size, None, move |batch| self.call_api(batch)).await
+/// }
+/// ```
+pub async fn embed_in_batches<'a, F, Fut>(
+    texts: &'a [String],
+    max_batch_size: usize,
+    max_batch_tokens: Option<usize>,
+    mut embed: F,
+) -> Result<Vec<Vec<f32>>>
+where
+    F: FnMut(&'a [String]) -> Fut,
+    Fut: Future<Output = Result<Vec<Vec<f32>>>>,
+{
+    use crate::ingest::TextChunker;
+
+    let max_batch_size = max_batch_size.max(1);
+    let mut embeddings = Vec::with_capacity(texts.len());
+    let mut start = 0;
+
+    while start < texts.len() {
+        let mut end = start;
+        let mut tokens = 0;
+        while end < texts.len() && end - start < max_batch_size {
+            let text_tokens = TextChunker::estimate_tokens(&texts[end]);
+            if let Some(budget) = max_batch_tokens {
+                if end > start && tokens + text_tokens > budget {
+                    break;
+                }
+            }
+            tokens += text_tokens;
+            end += 1;
+        }
+
+        let batch = &texts[start..end];
+        let batch_embeddings = embed(batch).await?;
+        if batch_embeddings.len() != batch.len() {
+            return Err(DatabaseError::EmbeddingError(format!(
+                "Embedding backend returned {} embeddings for {} texts",
+                batch_embeddings.len(),
+                batch.len()
+            )));
+        }
+        embeddings.extend(batch_embeddings);
+        start = end;
+    }
+
+    Ok(embeddings)
 }
 
 /// Factory for creating embedding providers.
@@ -80,7 +196,6 @@ impl ProviderFactory {
     pub fn create(config: &str) -> Result<Box<dyn EmbeddingProvider>> {
         use crate::embeddings::local::LocalEmbedder;
         use crate::embeddings::openai::OpenAIEmbedder;
-        use crate::types::DatabaseError;
 
         let parts: Vec<&str> = config.split(':').collect();
 
@@ -130,3 +245,72 @@ impl ProviderFactory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Backend that records each request and refuses oversized batches.
+    struct MockBackend {
+        max_batch_size: usize,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for MockBackend {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            Ok(self.embed_batch(&[text.to_string()]).await?.remove(0))
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            embed_in_batches(texts, self.max_batch_size, None, |batch| {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                let result = if batch.len() > self.max_batch_size {
+                    Err(DatabaseError::EmbeddingError("400: too many inputs".to_string()))
+                } else {
+                    Ok(batch.iter().map(|t| vec![t.parse::<f32>().unwrap()]).collect())
+                };
+                std::future::ready(result)
+            })
+            .await
+        }
+
+        fn dimensions(&self) -> usize {
+            1
+        }
+
+        fn max_batch_size(&self) -> Option<usize> {
+            Some(self.max_batch_size)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embed_batch_splits_oversized_batches() {
+        let backend = MockBackend { max_batch_size: 4, calls: AtomicUsize::new(0) };
+        let texts: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+
+        let embeddings = backend.embed_batch(&texts).await.unwrap();
+
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+        let order: Vec<f32> = embeddings.iter().map(|e| e[0]).collect();
+        assert_eq!(order, (0..10).map(|i| i as f32).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_embed_in_batches_token_budget() {
+        let texts = vec!["a".repeat(40), "b".repeat(40), "c".repeat(40), "d".repeat(100)];
+        let mut sizes = Vec::new();
+
+        // 10 tokens each for the first three, 25 for the last
+        let embeddings = embed_in_batches(&texts, 100, Some(20), |batch| {
+            sizes.push(batch.len());
+            std::future::ready(Ok(batch.iter().map(|_| vec![0.0]).collect()))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(embeddings.len(), 4);
+        assert_eq!(sizes, vec![2, 1, 1]);
+    }
+}
@@ -0,0 +1,466 @@
+//! Fit embedding input to the model's context window.
+//!
+//! Providers report their limit via `EmbeddingProvider::max_tokens`. Inputs
+//! over the limit are either truncated or rejected, per `OverflowPolicy`.
+//!
+//! # Truncation strategies
+//!
+//! - `Head`: keep the beginning, drop the end (default; titles and leads
+//!   carry most of the meaning in typical documents)
+//! - `Tail`: keep the end, drop the beginning
+//! - `MiddleDrop`: keep both ends, drop the middle
+//!
+//! # Token counting
+//!
+//! Inputs are counted and cut with the provider's `Tokenizer`. Providers
+//! with a known BPE vocabulary (OpenAI) count exact tokens, so code, CJK,
+//! and numeric text that run well over one token per 4 characters still
+//! fit. Providers without one fall back to `Tokenizer::Estimate` (~4
+//! characters per token, `TextChunker::estimate_tokens`), which can
+//! undercount such text. Cuts always fall on `char` boundaries.
+
+use crate::embeddings::EmbeddingProvider;
+use crate::ingest::TextChunker;
+use crate::types::{DatabaseError, Result};
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+use tiktoken_rs::CoreBPE;
+
+/// Characters per estimated token.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Separator inserted where `MiddleDrop` removes text.
+const MIDDLE_SEPARATOR: &str = " ... ";
+
+/// Which part of an over-length input to keep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TruncationStrategy {
+    /// Keep the beginning
+    #[default]
+    Head,
+    /// Keep the end
+    Tail,
+    /// Keep both ends, drop the middle
+    MiddleDrop,
+}
+
+impl TruncationStrategy {
+    /// Get strategy name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Head => "head",
+            Self::Tail => "tail",
+            Self::MiddleDrop => "middle",
+        }
+    }
+}
+
+/// What to do with input over the model's context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Truncate with the given strategy
+    Truncate(TruncationStrategy),
+    /// Fail with `DatabaseError::EmbeddingError`
+    Reject,
+}
+
+impl Default for OverflowPolicy {
+    fn default() -> Self {
+        Self::Truncate(TruncationStrategy::Head)
+    }
+}
+
+impl OverflowPolicy {
+    /// Parse policy from config string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - One of `head`, `tail`, `middle`, `reject`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "head" => Ok(Self::Truncate(TruncationStrategy::Head)),
+            "tail" => Ok(Self::Truncate(TruncationStrategy::Tail)),
+            "middle" => Ok(Self::Truncate(TruncationStrategy::MiddleDrop)),
+            "reject" => Ok(Self::Reject),
+            other => Err(DatabaseError::ConfigError(format!(
+                "Unknown embedding overflow policy '{}' (expected head, tail, middle, or reject)",
+                other
+            ))),
+        }
+    }
+}
+
+/// How input is counted and cut against a model's token limit.
+#[derive(Clone, Default)]
+pub enum Tokenizer {
+    /// Exact BPE tokens (tiktoken vocabulary)
+    Bpe(Arc<CoreBPE>),
+    /// ~4 characters per token, for models whose tokenizer isn't available
+    #[default]
+    Estimate,
+}
+
+impl std::fmt::Debug for Tokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bpe(_) => f.write_str("Bpe"),
+            Self::Estimate => f.write_str("Estimate"),
+        }
+    }
+}
+
+impl Tokenizer {
+    /// Tokenizer for an OpenAI model name.
+    ///
+    /// # Returns
+    ///
+    /// The model's BPE vocabulary, or `Estimate` for unknown models
+    pub fn for_model(model: &str) -> Self {
+        use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer as Vocabulary};
+
+        match get_tokenizer(model) {
+            Some(Vocabulary::Cl100kBase) => Self::cl100k(),
+            Some(vocabulary) => tiktoken_rs::get_bpe_from_tokenizer(vocabulary)
+                .map(|bpe| Self::Bpe(Arc::new(bpe)))
+                .unwrap_or_default(),
+            None => Self::Estimate,
+        }
+    }
+
+    /// `cl100k_base`, shared by all current OpenAI embedding models.
+    ///
+    /// The vocabulary is loaded once per process.
+    pub fn cl100k() -> Self {
+        static CL100K: OnceLock<Option<Arc<CoreBPE>>> = OnceLock::new();
+        CL100K
+            .get_or_init(|| tiktoken_rs::cl100k_base().ok().map(Arc::new))
+            .clone()
+            .map(Self::Bpe)
+            .unwrap_or_default()
+    }
+
+    /// Count tokens in text.
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Self::Bpe(bpe) => bpe.encode_ordinary(text).len(),
+            Self::Estimate => TextChunker::estimate_tokens(text),
+        }
+    }
+
+    /// Cut text to roughly `budget` tokens with a strategy.
+    ///
+    /// BPE cuts land on token boundaries (moved inward to the nearest
+    /// `char` boundary); re-tokenizing the joined result can still differ
+    /// by a token at the seams, which `fit_to_limit` checks for.
+    fn cut(&self, text: &str, budget: usize, strategy: TruncationStrategy) -> String {
+        match self {
+            Self::Bpe(bpe) => {
+                // Tokens decode back to the exact input bytes, so their
+                // lengths give each token's byte offset
+                let tokens = bpe.encode_ordinary(text);
+                let mut offsets = Vec::with_capacity(tokens.len() + 1);
+                let mut offset = 0;
+                offsets.push(0);
+                for token in &tokens {
+                    offset = (offset + bpe._decode_native(&[*token]).len()).min(text.len());
+                    offsets.push(offset);
+                }
+
+                let floor = |mut i: usize| {
+                    while !text.is_char_boundary(i) {
+                        i -= 1;
+                    }
+                    i
+                };
+                let ceil = |mut i: usize| {
+                    while !text.is_char_boundary(i) {
+                        i += 1;
+                    }
+                    i
+                };
+                let head_end = |n: usize| floor(offsets[n.min(tokens.len())]);
+                let tail_start = |n: usize| ceil(offsets[tokens.len() - n.min(tokens.len())]);
+
+                match strategy {
+                    TruncationStrategy::Head => text[..head_end(budget)].to_string(),
+                    TruncationStrategy::Tail => text[tail_start(budget)..].to_string(),
+                    TruncationStrategy::MiddleDrop => {
+                        let keep = budget.saturating_sub(self.count(MIDDLE_SEPARATOR));
+                        let head = keep.div_ceil(2);
+                        let tail = keep - head;
+                        format!("{}{}{}", &text[..head_end(head)], MIDDLE_SEPARATOR, &text[tail_start(tail)..])
+                    }
+                }
+            }
+            Self::Estimate => {
+                let budget = budget * CHARS_PER_TOKEN;
+                let char_count = text.chars().count();
+
+                match strategy {
+                    TruncationStrategy::Head => text.chars().take(budget).collect(),
+                    TruncationStrategy::Tail => text.chars().skip(char_count.saturating_sub(budget)).collect(),
+                    TruncationStrategy::MiddleDrop => {
+                        let keep = budget.saturating_sub(MIDDLE_SEPARATOR.len());
+                        let head = keep.div_ceil(2);
+                        let tail = keep - head;
+
+                        let mut fitted: String = text.chars().take(head).collect();
+                        fitted.push_str(MIDDLE_SEPARATOR);
+                        fitted.extend(text.chars().skip(char_count.saturating_sub(tail)));
+                        fitted
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Record of a truncated embedding input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Truncation {
+    /// Tokens before truncation (per the provider's `Tokenizer`)
+    pub original_tokens: usize,
+    /// Tokens actually embedded
+    pub embedded_tokens: usize,
+    /// Strategy applied
+    pub strategy: TruncationStrategy,
+}
+
+impl Truncation {
+    /// Entity metadata describing this truncation.
+    pub fn to_metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "original_tokens": self.original_tokens,
+            "embedded_tokens": self.embedded_tokens,
+            "strategy": self.strategy.as_str(),
+        })
+    }
+}
+
+/// Fit text to a token limit.
+///
+/// # Arguments
+///
+/// * `text` - Input text
+/// * `max_tokens` - Model limit (`None` leaves text unchanged)
+/// * `tokenizer` - How tokens are counted and cut
+/// * `policy` - Overflow handling
+///
+/// # Returns
+///
+/// Text to embed, plus a `Truncation` record if it was shortened
+///
+/// # Errors
+///
+/// Returns `DatabaseError::EmbeddingError` if over the limit and `policy` is `Reject`
+pub fn fit_to_limit<'a>(
+    text: &'a str,
+    max_tokens: Option<usize>,
+    tokenizer: &Tokenizer,
+    policy: OverflowPolicy,
+) -> Result<(Cow<'a, str>, Option<Truncation>)> {
+    let original_tokens = tokenizer.count(text);
+
+    let max_tokens = match max_tokens {
+        Some(max) if original_tokens > max => max,
+        _ => return Ok((Cow::Borrowed(text), None)),
+    };
+
+    let strategy = match policy {
+        OverflowPolicy::Truncate(strategy) => strategy,
+        OverflowPolicy::Reject => {
+            return Err(DatabaseError::EmbeddingError(format!(
+                "Input of {} tokens exceeds model limit of {} tokens",
+                original_tokens, max_tokens
+            )));
+        }
+    };
+
+    // Shrink the budget until the re-tokenized result fits
+    let mut budget = max_tokens;
+    let (fitted, embedded_tokens) = loop {
+        let fitted = tokenizer.cut(text, budget, strategy);
+        let tokens = tokenizer.count(&fitted);
+        if tokens <= max_tokens || budget == 0 {
+            break (fitted, tokens);
+        }
+        budget = budget.saturating_sub(tokens - max_tokens);
+    };
+
+    let truncation = Truncation {
+        original_tokens,
+        embedded_tokens,
+        strategy,
+    };
+
+    Ok((Cow::Owned(fitted), Some(truncation)))
+}
+
+/// Embed text, fitting it to the provider's context window first.
+///
+/// # Arguments
+///
+/// * `provider` - Embedding provider
+/// * `text` - Input text
+/// * `policy` - Overflow handling
+///
+/// # Returns
+///
+/// Embedding, plus a `Truncation` record if the input was shortened
+///
+/// # Errors
+///
+/// Returns `DatabaseError::EmbeddingError` if the input is rejected or embedding fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (embedding, truncation) = embed_within_limit(&*provider, &text, OverflowPolicy::default()).await?;
+/// if let Some(t) = truncation {
+///     props.insert("embedding_truncation".into(), t.to_metadata());
+/// }
+/// ```
+pub async fn embed_within_limit(
+    provider: &dyn EmbeddingProvider,
+    text: &str,
+    policy: OverflowPolicy,
+) -> Result<(Vec<f32>, Option<Truncation>)> {
+    let (fitted, truncation) = fit_to_limit(text, provider.max_tokens(), &provider.tokenizer(), policy)?;
+    let embedding = provider.embed(&fitted).await?;
+    Ok((embedding, truncation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Provider that fails on inputs over its limit, like a real model API.
+    struct LimitedEmbedder {
+        max_tokens: usize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for LimitedEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let tokens = TextChunker::estimate_tokens(text);
+            if tokens > self.max_tokens {
+                return Err(DatabaseError::EmbeddingError(format!("{} tokens over limit", tokens)));
+            }
+            Ok(vec![tokens as f32])
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::with_capacity(texts.len());
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+
+        fn dimensions(&self) -> usize {
+            1
+        }
+
+        fn max_tokens(&self) -> Option<usize> {
+            Some(self.max_tokens)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_over_length_input_is_truncated() {
+        let provider = LimitedEmbedder { max_tokens: 10 };
+        let text = "x".repeat(1000);
+
+        // Embedding directly fails
+        assert!(provider.embed(&text).await.is_err());
+
+        let (embedding, truncation) = embed_within_limit(&provider, &text, OverflowPolicy::default())
+            .await
+            .unwrap();
+
+        assert_eq!(embedding, vec![10.0]);
+        let truncation = truncation.unwrap();
+        assert_eq!(truncation.original_tokens, 250);
+        assert_eq!(truncation.embedded_tokens, 10);
+        assert_eq!(truncation.to_metadata()["strategy"], "head");
+    }
+
+    #[tokio::test]
+    async fn test_reject_policy() {
+        let provider = LimitedEmbedder { max_tokens: 10 };
+        let text = "x".repeat(1000);
+
+        let result = embed_within_limit(&provider, &text, OverflowPolicy::Reject).await;
+        assert!(matches!(result, Err(DatabaseError::EmbeddingError(_))));
+
+        // Short input passes untouched
+        let (_, truncation) = embed_within_limit(&provider, "short", OverflowPolicy::Reject).await.unwrap();
+        assert!(truncation.is_none());
+    }
+
+    #[test]
+    fn test_strategies() {
+        let text = "abcdefghijklmnopqrstuvwxyz0123456789";
+        let fit = |strategy| {
+            fit_to_limit(text, Some(4), &Tokenizer::Estimate, OverflowPolicy::Truncate(strategy))
+                .unwrap()
+                .0
+                .into_owned()
+        };
+
+        assert_eq!(fit(TruncationStrategy::Head), "abcdefghijklmnop");
+        assert_eq!(fit(TruncationStrategy::Tail), "uvwxyz0123456789");
+        assert_eq!(fit(TruncationStrategy::MiddleDrop), "abcdef ... 56789");
+    }
+
+    #[test]
+    fn test_multibyte_boundaries() {
+        let text = "é".repeat(100);
+        let (fitted, _) = fit_to_limit(&text, Some(2), &Tokenizer::Estimate, OverflowPolicy::default()).unwrap();
+        assert_eq!(fitted.chars().count(), 8);
+
+        let (fitted, _) = fit_to_limit(&text, Some(2), &Tokenizer::cl100k(), OverflowPolicy::default()).unwrap();
+        assert!(!fitted.is_empty() && fitted.chars().all(|c| c == 'é'));
+    }
+
+    #[test]
+    fn test_bpe_fits_dense_text() {
+        let bpe = Tokenizer::cl100k();
+        assert!(matches!(bpe, Tokenizer::Bpe(_)));
+
+        // CJK and digits run well over one token per 4 characters
+        let text = "数据库索引向量检索".repeat(100) + &"3141592653 ".repeat(100);
+        let estimated = Tokenizer::Estimate.count(&text);
+        assert!(bpe.count(&text) > estimated);
+
+        // The 4-chars-per-token cut overflows the real limit; the BPE cut fits
+        let (by_estimate, _) = fit_to_limit(&text, Some(100), &Tokenizer::Estimate, OverflowPolicy::default()).unwrap();
+        assert!(bpe.count(&by_estimate) > 100);
+
+        for strategy in [TruncationStrategy::Head, TruncationStrategy::Tail, TruncationStrategy::MiddleDrop] {
+            let (fitted, truncation) = fit_to_limit(&text, Some(100), &bpe, OverflowPolicy::Truncate(strategy)).unwrap();
+            let truncation = truncation.unwrap();
+            assert!(bpe.count(&fitted) <= 100, "{:?}", strategy);
+            assert_eq!(truncation.embedded_tokens, bpe.count(&fitted));
+            assert_eq!(truncation.original_tokens, bpe.count(&text));
+        }
+    }
+
+    #[test]
+    fn test_for_model() {
+        assert!(matches!(Tokenizer::for_model("text-embedding-3-small"), Tokenizer::Bpe(_)));
+        assert!(matches!(Tokenizer::for_model("all-MiniLM-L6-v2"), Tokenizer::Estimate));
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(OverflowPolicy::parse("middle").unwrap(), OverflowPolicy::Truncate(TruncationStrategy::MiddleDrop));
+        assert_eq!(OverflowPolicy::parse("REJECT").unwrap(), OverflowPolicy::Reject);
+        assert!(OverflowPolicy::parse("sideways").is_err());
+    }
+}
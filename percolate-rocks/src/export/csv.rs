@@ -1,5 +1,6 @@
 //! CSV export for spreadsheets.
 
+use super::ProjectedRows;
 use crate::types::{Result, Entity, DatabaseError};
 use std::collections::BTreeSet;
 use std::fs::File;
@@ -90,11 +91,7 @@ impl CsvExporter {
                         // Get from properties
                         entity.properties
                             .get(field)
-                            .map(|v| match v {
-                                serde_json::Value::String(s) => s.clone(),
-                                serde_json::Value::Null => String::new(),
-                                other => other.to_string(),
-                            })
+                            .map(cell)
                             .unwrap_or_default()
                     }
                 };
@@ -110,6 +107,47 @@ impl CsvExporter {
 
         Ok(())
     }
+
+    /// Export projected rows with the columns as header.
+    ///
+    /// Unlike `export`, an empty result still writes the header.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Projected rows
+    /// * `path` - Output file path
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ExportError` if export fails
+    pub fn export_rows<P: AsRef<Path>>(rows: &ProjectedRows, path: P) -> Result<()> {
+        let file = File::create(path.as_ref())
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to create file: {}", e)))?;
+
+        let mut writer = csv::Writer::from_writer(file);
+
+        writer.write_record(&rows.columns)
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to write header: {}", e)))?;
+
+        for row in &rows.rows {
+            writer.write_record(row.iter().map(cell))
+                .map_err(|e| DatabaseError::ExportError(format!("Failed to write row: {}", e)))?;
+        }
+
+        writer.flush()
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to flush: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Format a value as a CSV cell (strings unquoted, null empty).
+fn cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,185 @@
+//! Edge import from CSV and JSONL.
+//!
+//! Each row names a source, a destination, and a relationship type:
+//!
+//! ```text
+//! src,dst,rel_type,weight
+//! 2f1c...,paper-b,cites,0.8
+//! ```
+//!
+//! ```text
+//! {"src": "paper-a", "dst": "paper-b", "rel_type": "cites", "properties": {"weight": 0.8}}
+//! ```
+//!
+//! Endpoints are entity UUIDs or key values, resolved by
+//! `Database::import_edges`. In CSV, columns other than `src`, `dst`, and
+//! `rel_type` become edge properties (values that parse as JSON keep their
+//! type, anything else is a string); a `properties` column holding a JSON
+//! object is merged in. JSONL rows carry an optional `properties` object.
+
+use super::ExportFormat;
+use crate::types::{DatabaseError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// One edge to import, with unresolved endpoints.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EdgeRow {
+    /// Source entity UUID or key value
+    pub src: String,
+    /// Destination entity UUID or key value
+    pub dst: String,
+    /// Relationship type
+    pub rel_type: String,
+    /// Edge properties
+    #[serde(default)]
+    pub properties: Option<serde_json::Value>,
+}
+
+/// Outcome of an edge import.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EdgeImportReport {
+    /// Edges written
+    pub created: usize,
+    /// Rows not imported
+    pub skipped: usize,
+    /// Reason for each skipped row, by 0-based row index
+    pub errors: Vec<(usize, String)>,
+}
+
+/// Edge file reader.
+pub struct EdgeImporter;
+
+impl EdgeImporter {
+    /// Read edge rows from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Input file path
+    /// * `format` - `Csv` or `Jsonl`
+    ///
+    /// # Returns
+    ///
+    /// Rows in file order
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IngestError` if the file can't be read or a
+    /// row lacks `src`, `dst`, or `rel_type`, or `DatabaseError::ConfigError`
+    /// for Parquet
+    pub fn read<P: AsRef<Path>>(path: P, format: ExportFormat) -> Result<Vec<EdgeRow>> {
+        match format {
+            ExportFormat::Csv => Self::read_csv(path),
+            ExportFormat::Jsonl => Self::read_jsonl(path),
+            ExportFormat::Parquet => Err(DatabaseError::ConfigError(
+                "Edge import supports csv and jsonl".to_string(),
+            )),
+        }
+    }
+
+    fn read_jsonl<P: AsRef<Path>>(path: P) -> Result<Vec<EdgeRow>> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to open file: {}", e)))?;
+
+        let mut rows = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line
+                .map_err(|e| DatabaseError::IngestError(format!("Failed to read line {}: {}", number + 1, e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let row = serde_json::from_str(&line)
+                .map_err(|e| DatabaseError::IngestError(format!("Invalid edge on line {}: {}", number + 1, e)))?;
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    fn read_csv<P: AsRef<Path>>(path: P) -> Result<Vec<EdgeRow>> {
+        let mut reader = csv::Reader::from_path(path.as_ref())
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to open file: {}", e)))?;
+
+        let headers = reader.headers()
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to read CSV header: {}", e)))?
+            .clone();
+        let column = |name: &str| {
+            headers.iter().position(|h| h == name).ok_or_else(|| {
+                DatabaseError::IngestError(format!("CSV header is missing the '{}' column", name))
+            })
+        };
+        let (src, dst, rel_type) = (column("src")?, column("dst")?, column("rel_type")?);
+
+        let mut rows = Vec::new();
+        for (number, record) in reader.records().enumerate() {
+            let record = record
+                .map_err(|e| DatabaseError::IngestError(format!("Failed to read CSV row {}: {}", number + 1, e)))?;
+
+            let mut properties = serde_json::Map::new();
+            for (index, (name, value)) in headers.iter().zip(record.iter()).enumerate() {
+                if index == src || index == dst || index == rel_type || value.is_empty() {
+                    continue;
+                }
+                let parsed = serde_json::from_str(value)
+                    .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                match (name, parsed) {
+                    ("properties", serde_json::Value::Object(map)) => properties.extend(map),
+                    (name, parsed) => {
+                        properties.insert(name.to_string(), parsed);
+                    }
+                }
+            }
+
+            rows.push(EdgeRow {
+                src: record.get(src).unwrap_or_default().to_string(),
+                dst: record.get(dst).unwrap_or_default().to_string(),
+                rel_type: record.get(rel_type).unwrap_or_default().to_string(),
+                properties: (!properties.is_empty()).then_some(serde_json::Value::Object(properties)),
+            });
+        }
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_csv_edges() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("edges.csv");
+        fs::write(&path, "src,dst,rel_type,weight,note\npaper-a,paper-b,cites,0.8,seminal\npaper-b,paper-c,cites,,\n").unwrap();
+
+        let rows = EdgeImporter::read(&path, ExportFormat::Csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].src, "paper-a");
+        assert_eq!(rows[0].properties, Some(json!({"weight": 0.8, "note": "seminal"})));
+        assert_eq!(rows[1].properties, None);
+
+        fs::write(&path, "from,to,rel_type\na,b,cites\n").unwrap();
+        assert!(EdgeImporter::read(&path, ExportFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn test_read_jsonl_edges() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("edges.jsonl");
+        fs::write(&path, "{\"src\": \"a\", \"dst\": \"b\", \"rel_type\": \"cites\", \"properties\": {\"weight\": 1}}\n\n{\"src\": \"b\", \"dst\": \"c\", \"rel_type\": \"cites\"}\n").unwrap();
+
+        let rows = EdgeImporter::read(&path, ExportFormat::Jsonl).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].properties, Some(json!({"weight": 1})));
+        assert_eq!(rows[1].properties, None);
+
+        fs::write(&path, "{\"src\": \"a\", \"rel_type\": \"cites\"}\n").unwrap();
+        assert!(EdgeImporter::read(&path, ExportFormat::Jsonl).is_err());
+    }
+}
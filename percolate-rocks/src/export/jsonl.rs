@@ -1,8 +1,9 @@
 //! JSONL export for streaming/batch processing.
 
+use super::ProjectedRows;
 use crate::types::{Result, Entity, DatabaseError};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
 /// JSONL exporter.
@@ -60,6 +61,77 @@ impl JsonlExporter {
 
         Ok(())
     }
+
+    /// Export projected rows, one JSON object per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Projected rows
+    /// * `path` - Output file path
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ExportError` if export fails
+    pub fn export_rows<P: AsRef<Path>>(rows: &ProjectedRows, path: P) -> Result<()> {
+        let file = File::create(path.as_ref())
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to create file: {}", e)))?;
+
+        let mut writer = BufWriter::new(file);
+
+        for row in &rows.rows {
+            let object: serde_json::Map<String, serde_json::Value> = rows.columns
+                .iter()
+                .cloned()
+                .zip(row.iter().cloned())
+                .collect();
+
+            writeln!(writer, "{}", serde_json::Value::Object(object))
+                .map_err(|e| DatabaseError::ExportError(format!("Failed to write: {}", e)))?;
+        }
+
+        writer.flush()
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to flush: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// JSONL importer for files written by `JsonlExporter`.
+pub struct JsonlImporter;
+
+impl JsonlImporter {
+    /// Read entities from a JSONL file (one entity per line).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Input file path
+    ///
+    /// # Returns
+    ///
+    /// Entities in file order
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IngestError` if the file can't be read or a line is not an entity
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Entity>> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to open file: {}", e)))?;
+
+        let mut entities = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line
+                .map_err(|e| DatabaseError::IngestError(format!("Failed to read line {}: {}", number + 1, e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entity = serde_json::from_str(&line)
+                .map_err(|e| DatabaseError::IngestError(format!("Invalid entity on line {}: {}", number + 1, e)))?;
+            entities.push(entity);
+        }
+
+        Ok(entities)
+    }
 }
 
 #[cfg(test)]
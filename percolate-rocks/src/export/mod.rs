@@ -1,11 +1,67 @@
 //! Export operations to analytics formats.
 //!
-//! Supports Parquet, CSV, and JSONL export formats.
+//! Supports Parquet, CSV, and JSONL export formats. Parquet and JSONL
+//! files can be imported back (see `Database::import_parquet`).
+//! Filtered, column-projected exports go through `projection`. Edge lists
+//! in CSV or JSONL are read by `edges` (see `Database::import_edges`).
 
 pub mod parquet;
 pub mod csv;
 pub mod jsonl;
+pub mod projection;
+pub mod edges;
 
-pub use self::parquet::ParquetExporter;
+pub use self::parquet::{ParquetExporter, ParquetImporter};
 pub use self::csv::CsvExporter;
-pub use self::jsonl::JsonlExporter;
+pub use self::jsonl::{JsonlExporter, JsonlImporter};
+pub use self::projection::ProjectedRows;
+pub use self::edges::{EdgeImportReport, EdgeImporter, EdgeRow};
+
+use crate::types::{DatabaseError, Result};
+
+/// Export file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Columnar, ZSTD-compressed
+    Parquet,
+
+    /// Comma-separated values with header
+    Csv,
+
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// Parse format from string (`parquet`, `csv`, `jsonl`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "parquet" => Ok(Self::Parquet),
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::Jsonl),
+            other => Err(DatabaseError::ConfigError(format!(
+                "Unknown export format '{}' (expected parquet, csv, or jsonl)",
+                other
+            ))),
+        }
+    }
+
+    /// Get format name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -1,17 +1,145 @@
-//! Parquet export with ZSTD compression.
+//! Parquet export with ZSTD compression, and the matching importer.
+//!
+//! # File layout
+//!
+//! System columns (Utf8): `id`, `entity_type`, `created_at`, `modified_at`,
+//! `deleted_at`, and `edges` (inline edges as JSON, null when empty).
+//!
+//! Each property becomes one column:
+//! - Utf8: every value is a string
+//! - Utf8 with field metadata `rem:json = "true"`: other values, JSON-encoded
+//! - FixedSizeList<Float32>: `embedding*` fields whose values are all
+//!   same-length vectors of f32-representable numbers
+//!
+//! Values that don't fit their column (explicit nulls in string and
+//! embedding columns, properties named like a system column) go to the
+//! `_overflow` column as a JSON object. `ParquetImporter` reverses all of
+//! this, so export → import reproduces entities exactly.
 
+use super::ProjectedRows;
 use crate::types::{Result, Entity, DatabaseError};
-use arrow::array::{ArrayRef, StringArray};
+use arrow::array::{Array, ArrayRef, FixedSizeListArray, FixedSizeListBuilder, Float32Array, Float32Builder, StringArray};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
 
+/// System field columns.
+const SYSTEM_COLUMNS: [&str; 6] = ["id", "entity_type", "created_at", "modified_at", "deleted_at", "edges"];
+
+/// Column holding properties that don't fit their typed column.
+const OVERFLOW_COLUMN: &str = "_overflow";
+
+/// Field metadata key marking a JSON-encoded Utf8 column.
+const JSON_METADATA_KEY: &str = "rem:json";
+
+/// Storage type chosen for a property column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnKind {
+    /// Plain strings
+    Text,
+    /// JSON-encoded values
+    Json,
+    /// Fixed-size f32 vectors of this dimension
+    Embedding(i32),
+}
+
+impl ColumnKind {
+    /// Pick the column type for a property from all its non-null values.
+    fn infer<'a>(name: &str, values: impl Iterator<Item = &'a serde_json::Value>) -> Self {
+        let values: Vec<_> = values.filter(|v| !v.is_null()).collect();
+
+        if name.starts_with("embedding") {
+            if let Some(dim) = values.first().and_then(|v| v.as_array()).map(|a| a.len()) {
+                if dim > 0 && values.iter().all(|v| as_f32_vector(v).is_some_and(|vec| vec.len() == dim)) {
+                    return Self::Embedding(dim as i32);
+                }
+            }
+        }
+
+        if values.iter().all(|v| v.is_string()) {
+            Self::Text
+        } else {
+            Self::Json
+        }
+    }
+
+    /// Whether `value` can be stored in this column (else it overflows).
+    fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::Text => value.is_string(),
+            Self::Json => true,
+            Self::Embedding(_) => !value.is_null(),
+        }
+    }
+
+    /// Arrow field for a column of this kind.
+    fn field(&self, name: &str) -> Field {
+        match self {
+            Self::Text => Field::new(name, DataType::Utf8, true),
+            Self::Json => Field::new(name, DataType::Utf8, true).with_metadata(HashMap::from([
+                (JSON_METADATA_KEY.to_string(), "true".to_string()),
+            ])),
+            Self::Embedding(dim) => Field::new(
+                name,
+                DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), *dim),
+                true,
+            ),
+        }
+    }
+
+    /// Build the Arrow array for a column (`None` cells are null).
+    fn array(&self, cells: &[Option<&serde_json::Value>]) -> ArrayRef {
+        match self {
+            Self::Text => Arc::new(StringArray::from(
+                cells.iter().map(|v| v.and_then(|v| v.as_str())).collect::<Vec<_>>(),
+            )),
+            Self::Json => Arc::new(StringArray::from(
+                cells.iter().map(|v| v.map(|v| v.to_string())).collect::<Vec<_>>(),
+            )),
+            Self::Embedding(dim) => {
+                let mut builder = FixedSizeListBuilder::new(Float32Builder::new(), *dim);
+                for cell in cells {
+                    match cell.and_then(as_f32_vector) {
+                        Some(vector) => {
+                            builder.values().append_slice(&vector);
+                            builder.append(true);
+                        }
+                        None => {
+                            builder.values().append_nulls(*dim as usize);
+                            builder.append(false);
+                        }
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+        }
+    }
+}
+
+/// Whether a property name collides with a system or overflow column.
+fn is_reserved(name: &str) -> bool {
+    SYSTEM_COLUMNS.contains(&name) || name == OVERFLOW_COLUMN
+}
+
+/// Numeric array as f32s, if every element converts without loss.
+fn as_f32_vector(value: &serde_json::Value) -> Option<Vec<f32>> {
+    value.as_array()?
+        .iter()
+        .map(|v| {
+            let f = v.as_f64()?;
+            let narrowed = f as f32;
+            (f64::from(narrowed) == f).then_some(narrowed)
+        })
+        .collect()
+}
+
 /// Parquet exporter for analytics.
 pub struct ParquetExporter;
 
@@ -55,36 +183,37 @@ impl ParquetExporter {
             return Err(DatabaseError::ExportError("No entities to export".to_string()));
         }
 
-        // Collect all unique field names
-        let mut all_fields = BTreeSet::new();
-
-        // System fields
-        all_fields.insert("id".to_string());
-        all_fields.insert("entity_type".to_string());
-        all_fields.insert("created_at".to_string());
-        all_fields.insert("modified_at".to_string());
-        all_fields.insert("deleted_at".to_string());
-
-        // Collect property fields
+        // Collect property columns (sorted) and their types
+        let mut names = BTreeSet::new();
         for entity in entities {
             if let Some(obj) = entity.properties.as_object() {
                 for key in obj.keys() {
-                    // Skip embedding fields (large arrays)
-                    if !key.starts_with("embedding") {
-                        all_fields.insert(key.clone());
+                    if !is_reserved(key) {
+                        names.insert(key.clone());
                     }
                 }
             }
         }
 
-        let fields: Vec<String> = all_fields.into_iter().collect();
+        let columns: Vec<(String, ColumnKind)> = names
+            .into_iter()
+            .map(|name| {
+                let kind = ColumnKind::infer(&name, entities.iter().filter_map(|e| e.properties.get(&name)));
+                (name, kind)
+            })
+            .collect();
 
-        // Build Arrow schema (all fields as nullable strings)
-        let arrow_fields: Vec<Field> = fields
+        // Build Arrow schema
+        let mut arrow_fields: Vec<Field> = SYSTEM_COLUMNS
             .iter()
-            .map(|name| Field::new(name, DataType::Utf8, true))
+            .map(|name| Field::new(*name, DataType::Utf8, true))
             .collect();
 
+        for (name, kind) in &columns {
+            arrow_fields.push(kind.field(name));
+        }
+        arrow_fields.push(Field::new(OVERFLOW_COLUMN, DataType::Utf8, true));
+
         let schema = Arc::new(Schema::new(arrow_fields));
 
         // Create file
@@ -102,30 +231,61 @@ impl ParquetExporter {
 
         // Convert entities to Arrow arrays and write in batches
         for chunk in entities.chunks(row_group_size) {
-            let mut columns: Vec<ArrayRef> = Vec::new();
+            let mut arrays: Vec<ArrayRef> = Vec::new();
 
-            for field in &fields {
+            for name in SYSTEM_COLUMNS {
                 let values: Vec<Option<String>> = chunk
                     .iter()
-                    .map(|entity| match field.as_str() {
-                        "id" => Some(entity.system.id.to_string()),
-                        "entity_type" => Some(entity.system.entity_type.clone()),
-                        "created_at" => Some(entity.system.created_at.clone()),
-                        "modified_at" => Some(entity.system.modified_at.clone()),
-                        "deleted_at" => entity.system.deleted_at.clone(),
-                        _ => entity.properties.get(field).map(|v| match v {
-                            serde_json::Value::String(s) => s.clone(),
-                            serde_json::Value::Null => String::new(),
-                            other => other.to_string(),
-                        }),
+                    .map(|entity| match name {
+                        "id" => Ok(Some(entity.system.id.to_string())),
+                        "entity_type" => Ok(Some(entity.system.entity_type.clone())),
+                        "created_at" => Ok(Some(entity.system.created_at.clone())),
+                        "modified_at" => Ok(Some(entity.system.modified_at.clone())),
+                        "deleted_at" => Ok(entity.system.deleted_at.clone()),
+                        _ if entity.system.edges.is_empty() => Ok(None),
+                        _ => serde_json::to_string(&entity.system.edges).map(Some),
+                    })
+                    .collect::<std::result::Result<_, _>>()?;
+
+                arrays.push(Arc::new(StringArray::from(values)) as ArrayRef);
+            }
+
+            let mut overflow: Vec<serde_json::Map<String, serde_json::Value>> = vec![serde_json::Map::new(); chunk.len()];
+
+            // Properties named like a system column always overflow
+            for (map, entity) in overflow.iter_mut().zip(chunk) {
+                if let Some(obj) = entity.properties.as_object() {
+                    for (key, value) in obj.iter().filter(|(key, _)| is_reserved(key)) {
+                        map.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+
+            for (name, kind) in &columns {
+                let cells: Vec<Option<&serde_json::Value>> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(row, entity)| {
+                        let value = entity.properties.get(name)?;
+                        if kind.accepts(value) {
+                            Some(value)
+                        } else {
+                            overflow[row].insert(name.clone(), value.clone());
+                            None
+                        }
                     })
                     .collect();
 
-                let array = StringArray::from(values);
-                columns.push(Arc::new(array) as ArrayRef);
+                arrays.push(kind.array(&cells));
             }
 
-            let batch = RecordBatch::try_new(schema.clone(), columns)
+            let overflow_values: Vec<Option<String>> = overflow
+                .into_iter()
+                .map(|map| (!map.is_empty()).then(|| serde_json::Value::Object(map).to_string()))
+                .collect();
+            arrays.push(Arc::new(StringArray::from(overflow_values)) as ArrayRef);
+
+            let batch = RecordBatch::try_new(schema.clone(), arrays)
                 .map_err(|e| DatabaseError::ExportError(format!("Failed to create batch: {}", e)))?;
 
             writer
@@ -139,6 +299,176 @@ impl ParquetExporter {
 
         Ok(())
     }
+
+    /// Export projected rows, one column per selected field.
+    ///
+    /// Column types are inferred as for properties. Values that don't fit
+    /// their column are written as null (there is no `_overflow` column),
+    /// and an empty result still writes the schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - Projected rows
+    /// * `path` - Output file path
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ExportError` if export fails
+    pub fn export_rows<P: AsRef<Path>>(rows: &ProjectedRows, path: P) -> Result<()> {
+        let kinds: Vec<ColumnKind> = rows.columns
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ColumnKind::infer(name, rows.column(index)))
+            .collect();
+
+        let schema = Arc::new(Schema::new(
+            rows.columns.iter().zip(&kinds).map(|(name, kind)| kind.field(name)).collect::<Vec<_>>(),
+        ));
+
+        let file = File::create(path.as_ref())
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to create file: {}", e)))?;
+
+        let props = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(Default::default()))
+            .build();
+
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(props))
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to create writer: {}", e)))?;
+
+        let arrays: Vec<ArrayRef> = kinds
+            .iter()
+            .enumerate()
+            .map(|(index, kind)| {
+                let cells: Vec<Option<&serde_json::Value>> = rows.column(index)
+                    .map(|value| kind.accepts(value).then_some(value))
+                    .collect();
+                kind.array(&cells)
+            })
+            .collect();
+
+        let batch = RecordBatch::try_new(schema, arrays)
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to create batch: {}", e)))?;
+
+        writer
+            .write(&batch)
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to write batch: {}", e)))?;
+
+        writer
+            .close()
+            .map_err(|e| DatabaseError::ExportError(format!("Failed to close writer: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Parquet importer for files written by `ParquetExporter`.
+pub struct ParquetImporter;
+
+impl ParquetImporter {
+    /// Read entities from a Parquet file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Input file path
+    ///
+    /// # Returns
+    ///
+    /// Entities with system fields and properties as exported
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IngestError` if the file can't be read or a
+    /// column has an unsupported type or malformed value
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Entity>> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to open file: {}", e)))?;
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .and_then(|builder| builder.build())
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to create reader: {}", e)))?;
+
+        let mut entities = Vec::new();
+        for batch in reader {
+            let batch = batch
+                .map_err(|e| DatabaseError::IngestError(format!("Failed to read batch: {}", e)))?;
+            entities.extend(Self::decode_batch(&batch)?);
+        }
+
+        Ok(entities)
+    }
+
+    /// Rebuild entities from one record batch.
+    fn decode_batch(batch: &RecordBatch) -> Result<Vec<Entity>> {
+        let schema = batch.schema();
+        let mut records: Vec<serde_json::Map<String, serde_json::Value>> = vec![serde_json::Map::new(); batch.num_rows()];
+        let mut properties: Vec<serde_json::Map<String, serde_json::Value>> = vec![serde_json::Map::new(); batch.num_rows()];
+
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            let name = field.name().as_str();
+            let is_json = field.metadata().get(JSON_METADATA_KEY).is_some_and(|v| v == "true");
+
+            match field.data_type() {
+                DataType::Utf8 => {
+                    let strings = column.as_any().downcast_ref::<StringArray>()
+                        .ok_or_else(|| DatabaseError::IngestError(format!("Column '{}' is not a string array", name)))?;
+
+                    for row in (0..strings.len()).filter(|&row| strings.is_valid(row)) {
+                        let raw = strings.value(row);
+                        if SYSTEM_COLUMNS.contains(&name) {
+                            let value = if name == "edges" { parse_json(name, raw)? } else { raw.into() };
+                            records[row].insert(name.to_string(), value);
+                        } else if name == OVERFLOW_COLUMN {
+                            match parse_json(name, raw)? {
+                                serde_json::Value::Object(map) => properties[row].extend(map),
+                                _ => {
+                                    return Err(DatabaseError::IngestError(format!(
+                                        "Column '{}' row {} is not a JSON object", name, row
+                                    )));
+                                }
+                            }
+                        } else if is_json {
+                            properties[row].insert(name.to_string(), parse_json(name, raw)?);
+                        } else {
+                            properties[row].insert(name.to_string(), raw.into());
+                        }
+                    }
+                }
+                DataType::FixedSizeList(item, _) if item.data_type() == &DataType::Float32 => {
+                    let lists = column.as_any().downcast_ref::<FixedSizeListArray>()
+                        .ok_or_else(|| DatabaseError::IngestError(format!("Column '{}' is not a list array", name)))?;
+
+                    for row in (0..lists.len()).filter(|&row| lists.is_valid(row)) {
+                        let values = lists.value(row);
+                        let floats = values.as_any().downcast_ref::<Float32Array>()
+                            .ok_or_else(|| DatabaseError::IngestError(format!("Column '{}' is not a float list", name)))?;
+                        let vector: Vec<f32> = floats.iter().map(|f| f.unwrap_or_default()).collect();
+                        properties[row].insert(name.to_string(), serde_json::json!(vector));
+                    }
+                }
+                other => {
+                    return Err(DatabaseError::IngestError(format!(
+                        "Unsupported type {} for column '{}'", other, name
+                    )));
+                }
+            }
+        }
+
+        records
+            .into_iter()
+            .zip(properties)
+            .map(|(mut record, props)| {
+                record.insert("properties".to_string(), serde_json::Value::Object(props));
+                serde_json::from_value(serde_json::Value::Object(record))
+                    .map_err(|e| DatabaseError::IngestError(format!("Invalid entity row: {}", e)))
+            })
+            .collect()
+    }
+}
+
+/// Parse a JSON-encoded cell.
+fn parse_json(column: &str, raw: &str) -> Result<serde_json::Value> {
+    serde_json::from_str(raw)
+        .map_err(|e| DatabaseError::IngestError(format!("Invalid JSON in column '{}': {}", column, e)))
 }
 
 #[cfg(test)]
@@ -176,4 +506,37 @@ mod tests {
         let result = ParquetExporter::export(&entities, &path);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("round_trip.parquet");
+
+        let mut deleted = Entity::new(Uuid::new_v4(), "articles".to_string(), json!({
+            "title": "Article 2",
+            "views": "n/a",
+            "embedding": null,
+            "id": "external-42"
+        }));
+        deleted.mark_deleted();
+
+        let entities = vec![
+            Entity::new(Uuid::new_v4(), "articles".to_string(), json!({
+                "title": "Article 1",
+                "views": 100,
+                "tags": ["rust", "db"],
+                "embedding": [0.5, -0.25, 1.0],
+                "embedding_alt": [0.1, 0.2]
+            })),
+            deleted,
+        ];
+
+        ParquetExporter::export_with_options(&entities, &path, 1).unwrap();
+        let imported = ParquetImporter::read(&path).unwrap();
+
+        assert_eq!(imported.len(), entities.len());
+        for (original, restored) in entities.iter().zip(&imported) {
+            assert_eq!(serde_json::to_value(original).unwrap(), serde_json::to_value(restored).unwrap());
+        }
+    }
 }
@@ -0,0 +1,86 @@
+//! Column projection for filtered exports.
+//!
+//! `Database::export_query` projects matching entities onto the requested
+//! columns before handing them to an exporter's `export_rows`. A column is
+//! either a system field (`id`, `entity_type`, `created_at`,
+//! `modified_at`, `deleted_at`) or a property; missing values become null.
+//!
+//! Projected files hold only the selected columns, so unlike full exports
+//! they cannot be imported back.
+
+use crate::types::Entity;
+
+/// System fields selectable as columns.
+pub const SYSTEM_FIELDS: [&str; 5] = ["id", "entity_type", "created_at", "modified_at", "deleted_at"];
+
+/// Entities projected onto a fixed set of columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectedRows {
+    /// Column names, in output order
+    pub columns: Vec<String>,
+    /// One value per column for each row
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+impl ProjectedRows {
+    /// Project entities onto columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - Entities to project
+    /// * `columns` - Column names, in output order
+    pub fn project(entities: &[Entity], columns: &[&str]) -> Self {
+        Self {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: entities
+                .iter()
+                .map(|entity| columns.iter().map(|c| column_value(entity, c)).collect())
+                .collect(),
+        }
+    }
+
+    /// Values of one column across all rows.
+    pub fn column(&self, index: usize) -> impl Iterator<Item = &serde_json::Value> {
+        self.rows.iter().map(move |row| &row[index])
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// Get a column value (system field, then property).
+fn column_value(entity: &Entity, column: &str) -> serde_json::Value {
+    let system = &entity.system;
+    let value = match column {
+        "id" => Some(system.id.to_string()),
+        "entity_type" => Some(system.entity_type.clone()),
+        "created_at" => Some(system.created_at.clone()),
+        "modified_at" => Some(system.modified_at.clone()),
+        "deleted_at" => system.deleted_at.clone(),
+        _ => return entity.properties.get(column).cloned().unwrap_or(serde_json::Value::Null),
+    };
+    value.map_or(serde_json::Value::Null, serde_json::Value::String)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_project() {
+        let entity = Entity::new(uuid::Uuid::new_v4(), "articles".to_string(), json!({"title": "Rust", "views": 3}));
+        let projected = ProjectedRows::project(std::slice::from_ref(&entity), &["views", "id", "missing", "deleted_at"]);
+
+        assert_eq!(projected.columns, vec!["views", "id", "missing", "deleted_at"]);
+        assert_eq!(projected.rows, vec![vec![json!(3), json!(entity.system.id.to_string()), json!(null), json!(null)]]);
+        assert_eq!(projected.column(0).collect::<Vec<_>>(), vec![&json!(3)]);
+    }
+}
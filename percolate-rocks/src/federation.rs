@@ -0,0 +1,358 @@
+//! Queries spanning several databases.
+//!
+//! A `Federation` holds named `Database` handles (e.g. one per shard or
+//! per tenant file) and fans each call out to all of them:
+//!
+//! - `search` / `search_by_vector`: members are queried concurrently, hits
+//!   are merged by score and re-ranked, keeping the global `top_k`
+//! - `query`: SQL rows are concatenated in member order
+//! - `lookup`: entities are concatenated and deduplicated by ID (the first
+//!   member holding an entity wins)
+//!
+//! Every result carries the name of the member it came from. A failing
+//! member fails the whole call, with the member name in the error.
+
+use crate::database::Database;
+use crate::index::SearchMode;
+use crate::types::{DatabaseError, Entity, Result, SearchResult};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// Key added to SQL rows naming their source database.
+pub const SOURCE_FIELD: &str = "_source";
+
+/// Search hit attributed to a member database.
+#[derive(Debug, Clone, Serialize)]
+pub struct FederatedSearchResult {
+    /// Member the hit came from
+    pub source: String,
+    /// Hit, with `rank` across the whole federation
+    pub result: SearchResult,
+}
+
+/// Entity attributed to a member database.
+#[derive(Debug, Clone, Serialize)]
+pub struct FederatedEntity {
+    /// Member the entity came from
+    pub source: String,
+    pub entity: Entity,
+}
+
+/// Named set of databases queried together.
+#[derive(Default, Clone)]
+pub struct Federation {
+    members: Vec<(String, Arc<Database>)>,
+}
+
+impl Federation {
+    /// Create an empty federation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a member database.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Source name attached to this member's results
+    /// * `db` - Database handle
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` if `name` is already a member
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut federation = Federation::new();
+    /// federation.add("eu", Arc::new(Database::open("./eu")?))?;
+    /// federation.add("us", Arc::new(Database::open("./us")?))?;
+    /// ```
+    pub fn add(&mut self, name: &str, db: Arc<Database>) -> Result<()> {
+        if self.members.iter().any(|(member, _)| member == name) {
+            return Err(DatabaseError::ConfigError(format!(
+                "Federation already has a member named '{}'",
+                name
+            )));
+        }
+        self.members.push((name.to_string(), db));
+        Ok(())
+    }
+
+    /// Member names, in the order they were added.
+    pub fn members(&self) -> Vec<&str> {
+        self.members.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Semantic search across all members.
+    ///
+    /// Each member embeds `query` with its own schema's provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name (must exist in every member)
+    /// * `query` - Search text
+    /// * `top_k` - Results to return in total
+    /// * `mode` - Recall/latency preset
+    ///
+    /// # Returns
+    ///
+    /// Best `top_k` hits across members, best first
+    ///
+    /// # Errors
+    ///
+    /// Returns the first member error, prefixed with the member name
+    pub async fn search(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query: &str,
+        top_k: usize,
+        mode: SearchMode,
+    ) -> Result<Vec<FederatedSearchResult>> {
+        let query = query.to_string();
+        self.fan_out_search(tenant_id, table, top_k, move |db, tenant_id, table| {
+            let query = query.clone();
+            async move { db.search_with_mode(&tenant_id, &table, &query, top_k, mode).await }
+        })
+        .await
+    }
+
+    /// Vector search across all members.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `table` - Schema/table name (must exist in every member)
+    /// * `query_embedding` - Query vector
+    /// * `top_k` - Results to return in total
+    /// * `mode` - Recall/latency preset
+    ///
+    /// # Returns
+    ///
+    /// Best `top_k` hits across members, best first
+    ///
+    /// # Errors
+    ///
+    /// Returns the first member error, prefixed with the member name
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// for hit in federation.search_by_vector("default", "articles", &embedding, 10, SearchMode::default()).await? {
+    ///     println!("[{}] {} {:.3}", hit.source, hit.result.entity.system.id, hit.result.score);
+    /// }
+    /// ```
+    pub async fn search_by_vector(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        query_embedding: &[f32],
+        top_k: usize,
+        mode: SearchMode,
+    ) -> Result<Vec<FederatedSearchResult>> {
+        let query_embedding = Arc::new(query_embedding.to_vec());
+        self.fan_out_search(tenant_id, table, top_k, move |db, tenant_id, table| {
+            let query_embedding = Arc::clone(&query_embedding);
+            async move {
+                db.search_by_vector(&tenant_id, &table, &query_embedding, top_k, None, mode).await
+            }
+        })
+        .await
+    }
+
+    /// Run a search on every member concurrently, then merge and re-rank.
+    async fn fan_out_search<F, Fut>(
+        &self,
+        tenant_id: &str,
+        table: &str,
+        top_k: usize,
+        search: F,
+    ) -> Result<Vec<FederatedSearchResult>>
+    where
+        F: Fn(Arc<Database>, String, String) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<SearchResult>>> + Send + 'static,
+    {
+        let mut tasks = JoinSet::new();
+        for (slot, (_, db)) in self.members.iter().enumerate() {
+            let search = search(Arc::clone(db), tenant_id.to_string(), table.to_string());
+            tasks.spawn(async move { (slot, search.await) });
+        }
+
+        let mut per_member: Vec<Vec<SearchResult>> = vec![Vec::new(); self.members.len()];
+        while let Some(joined) = tasks.join_next().await {
+            let (slot, results) = joined
+                .map_err(|e| DatabaseError::InternalError(format!("Federated search task failed: {}", e)))?;
+            per_member[slot] = results.map_err(|e| self.member_error(slot, e))?;
+        }
+
+        // Ties keep member order, then each member's own ranking
+        let mut merged: Vec<(usize, SearchResult)> = per_member
+            .into_iter()
+            .enumerate()
+            .flat_map(|(slot, results)| results.into_iter().map(move |r| (slot, r)))
+            .collect();
+        merged.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+        merged.truncate(top_k);
+
+        Ok(merged
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (slot, mut result))| {
+                result.rank = rank;
+                FederatedSearchResult { source: self.members[slot].0.clone(), result }
+            })
+            .collect())
+    }
+
+    /// Run SQL on every member.
+    ///
+    /// Rows are returned in member order, each with a `_source` key. Rows
+    /// are not re-sorted or re-aggregated: `ORDER BY`, `LIMIT`, and
+    /// aggregates apply per member.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `sql` - SQL SELECT statement
+    ///
+    /// # Returns
+    ///
+    /// Concatenated rows
+    ///
+    /// # Errors
+    ///
+    /// Returns the first member error, prefixed with the member name
+    pub fn query(&self, tenant_id: &str, sql: &str) -> Result<Vec<serde_json::Value>> {
+        let mut rows = Vec::new();
+
+        for (slot, (name, db)) in self.members.iter().enumerate() {
+            let result = db.query_sql(tenant_id, sql).map_err(|e| self.member_error(slot, e))?;
+            let member_rows = match result {
+                serde_json::Value::Array(rows) => rows,
+                other => vec![other],
+            };
+
+            for row in member_rows {
+                let mut row = match row {
+                    serde_json::Value::Object(map) => map,
+                    other => {
+                        let mut map = serde_json::Map::new();
+                        map.insert("value".to_string(), other);
+                        map
+                    }
+                };
+                row.insert(SOURCE_FIELD.to_string(), serde_json::json!(name));
+                rows.push(serde_json::Value::Object(row));
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Global key lookup on every member.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `key_value` - Key field value
+    ///
+    /// # Returns
+    ///
+    /// Matching entities, deduplicated by ID (first member wins)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first member error, prefixed with the member name
+    pub fn lookup(&self, tenant_id: &str, key_value: &str) -> Result<Vec<FederatedEntity>> {
+        let mut seen = HashSet::new();
+        let mut entities = Vec::new();
+
+        for (slot, (name, db)) in self.members.iter().enumerate() {
+            let found = db.lookup_global(tenant_id, key_value).map_err(|e| self.member_error(slot, e))?;
+            for entity in found {
+                if seen.insert(entity.system.id) {
+                    entities.push(FederatedEntity { source: name.clone(), entity });
+                }
+            }
+        }
+
+        Ok(entities)
+    }
+
+    /// Attach the member name to an error.
+    fn member_error(&self, slot: usize, error: DatabaseError) -> DatabaseError {
+        DatabaseError::QueryError(format!("Federation member '{}': {}", self.members[slot].0, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point_db(points: &[(&str, f64)]) -> Arc<Database> {
+        let db = Database::open_temp().unwrap();
+        db.register_schema("point", serde_json::json!({
+            "title": "Point",
+            "version": "1.0.0",
+            "short_name": "point",
+            "properties": {
+                "name": {"type": "string"},
+                "embedding": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["name"]
+        })).unwrap();
+
+        for (name, angle) in points {
+            db.insert("tenant1", "point", serde_json::json!({
+                "name": name,
+                "embedding": [angle.cos(), angle.sin()]
+            })).unwrap();
+        }
+        Arc::new(db)
+    }
+
+    #[tokio::test]
+    async fn test_federated_search_merges_and_ranks() {
+        let mut federation = Federation::new();
+        federation.add("east", point_db(&[("exact", 0.0), ("mid", 0.6)])).unwrap();
+        federation.add("west", point_db(&[("near", 0.1), ("far", 1.2)])).unwrap();
+        assert!(federation.add("east", point_db(&[])).is_err());
+
+        let results = federation
+            .search_by_vector("tenant1", "point", &[1.0, 0.0], 3, SearchMode::default())
+            .await
+            .unwrap();
+
+        let hits: Vec<(&str, &str)> = results
+            .iter()
+            .map(|r| (r.source.as_str(), r.result.entity.properties["name"].as_str().unwrap()))
+            .collect();
+        assert_eq!(hits, vec![("east", "exact"), ("west", "near"), ("east", "mid")]);
+        assert_eq!(results.iter().map(|r| r.result.rank).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(results.windows(2).all(|pair| pair[0].result.score >= pair[1].result.score));
+    }
+
+    #[test]
+    fn test_federated_query_and_lookup() {
+        let mut federation = Federation::new();
+        federation.add("east", point_db(&[("alpha", 0.0)])).unwrap();
+        federation.add("west", point_db(&[("alpha", 0.0), ("beta", 0.5)])).unwrap();
+
+        let rows = federation.query("tenant1", "SELECT name FROM point").unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows.iter().filter(|r| r[SOURCE_FIELD] == "west").count(), 2);
+
+        // Same deterministic ID in both members: reported once, from the first
+        let found = federation.lookup("tenant1", "alpha").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].source, "east");
+
+        let found = federation.lookup("tenant1", "beta").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].source, "west");
+    }
+}
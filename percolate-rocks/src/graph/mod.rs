@@ -4,6 +4,11 @@
 
 pub mod edges;
 pub mod traversal;
+pub mod embedding;
 
 pub use edges::EdgeManager;
-pub use traversal::{GraphTraversal, TraversalDirection, EdgeProvider};
+pub use embedding::{embed_graph, WalkEmbeddingConfig};
+pub use traversal::{
+    GraphTraversal, TraversalDirection, TraversalLimits, TraversalResult, EdgeProvider,
+    DEFAULT_MAX_NODES_PER_LEVEL, DEFAULT_MAX_TRAVERSAL_NODES,
+};
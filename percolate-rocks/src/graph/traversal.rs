@@ -5,6 +5,12 @@ use crate::types::Edge;
 use uuid::Uuid;
 use std::collections::{VecDeque, HashSet, HashMap};
 
+/// Default maximum nodes returned by a BFS traversal.
+pub const DEFAULT_MAX_TRAVERSAL_NODES: usize = 10_000;
+
+/// Default maximum nodes discovered at any one BFS depth.
+pub const DEFAULT_MAX_NODES_PER_LEVEL: usize = 1_000;
+
 /// Traversal direction.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TraversalDirection {
@@ -16,6 +22,57 @@ pub enum TraversalDirection {
     Both,
 }
 
+impl TraversalDirection {
+    /// Direction that walks the same edges backwards.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Out => Self::In,
+            Self::In => Self::Out,
+            Self::Both => Self::Both,
+        }
+    }
+}
+
+/// Size limits for a BFS traversal.
+///
+/// Hub nodes in dense graphs can pull in most of the graph within a couple
+/// of hops; these caps bound the result (and memory) regardless of depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraversalLimits {
+    /// Maximum nodes returned, including the start node
+    pub max_nodes: usize,
+    /// Maximum nodes discovered at each depth (bounds fan-out)
+    pub max_nodes_per_level: usize,
+}
+
+impl Default for TraversalLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: DEFAULT_MAX_TRAVERSAL_NODES,
+            max_nodes_per_level: DEFAULT_MAX_NODES_PER_LEVEL,
+        }
+    }
+}
+
+impl TraversalLimits {
+    /// No limits (the whole reachable subgraph within `depth`).
+    pub fn unbounded() -> Self {
+        Self {
+            max_nodes: usize::MAX,
+            max_nodes_per_level: usize::MAX,
+        }
+    }
+}
+
+/// Nodes reached by a BFS traversal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraversalResult {
+    /// Entity UUIDs in BFS order, starting with the start node
+    pub nodes: Vec<Uuid>,
+    /// Whether a limit stopped nodes from being added
+    pub truncated: bool,
+}
+
 /// Edge provider trait for traversal.
 ///
 /// This allows traversal to work with any edge source.
@@ -37,16 +94,22 @@ impl<'a> GraphTraversal<'a> {
 
     /// Breadth-first search from starting entity.
     ///
+    /// Stops adding nodes once `limits.max_nodes` is reached, and skips
+    /// nodes beyond `limits.max_nodes_per_level` at any depth; either sets
+    /// `truncated`. Nodes are visited in BFS order, so a truncated result
+    /// keeps the nodes closest to `start`.
+    ///
     /// # Arguments
     ///
     /// * `start` - Starting entity UUID
     /// * `direction` - Traversal direction
     /// * `depth` - Maximum traversal depth
-    /// * `rel_type` - Optional relationship type filter
+    /// * `rel_types` - Only follow edges of these types (`None` or empty = all)
+    /// * `limits` - Node caps
     ///
     /// # Returns
     ///
-    /// Vector of entity UUIDs in BFS order
+    /// `TraversalResult` with entity UUIDs in BFS order
     ///
     /// # Errors
     ///
@@ -56,35 +119,55 @@ impl<'a> GraphTraversal<'a> {
         start: Uuid,
         direction: TraversalDirection,
         depth: usize,
-        rel_type: Option<&str>,
-    ) -> Result<Vec<Uuid>> {
+        rel_types: Option<&[String]>,
+        limits: TraversalLimits,
+    ) -> Result<TraversalResult> {
         let mut visited = HashSet::new();
         let mut result = Vec::new();
         let mut queue = VecDeque::new();
+        // Nodes discovered per depth (index = depth)
+        let mut level_counts = vec![1];
+        let mut truncated = false;
 
         // Start with (node, current_depth)
         queue.push_back((start, 0));
         visited.insert(start);
         result.push(start);
 
-        while let Some((node, current_depth)) = queue.pop_front() {
+        'traversal: while let Some((node, current_depth)) = queue.pop_front() {
             if current_depth >= depth {
                 continue;
             }
 
             // Get neighbors based on direction
-            let neighbors = self.get_neighbors(node, direction, rel_type)?;
+            let neighbors = self.get_neighbors_of_types(node, direction, rel_types)?;
 
             for neighbor in neighbors {
-                if !visited.contains(&neighbor) {
-                    visited.insert(neighbor);
-                    result.push(neighbor);
-                    queue.push_back((neighbor, current_depth + 1));
+                if visited.contains(&neighbor) {
+                    continue;
                 }
+                if result.len() >= limits.max_nodes {
+                    truncated = true;
+                    break 'traversal;
+                }
+
+                let next_depth = current_depth + 1;
+                if level_counts.len() <= next_depth {
+                    level_counts.push(0);
+                }
+                if level_counts[next_depth] >= limits.max_nodes_per_level {
+                    truncated = true;
+                    continue;
+                }
+
+                level_counts[next_depth] += 1;
+                visited.insert(neighbor);
+                result.push(neighbor);
+                queue.push_back((neighbor, next_depth));
             }
         }
 
-        Ok(result)
+        Ok(TraversalResult { nodes: result, truncated })
     }
 
     /// Depth-first search from starting entity.
@@ -224,6 +307,122 @@ impl<'a> GraphTraversal<'a> {
         Ok(Vec::new())
     }
 
+    /// Find shortest path with a bidirectional BFS.
+    ///
+    /// Searches forward from `start` (following `direction`) and backward
+    /// from `end` (following `direction.reversed()`), one whole level at a
+    /// time on whichever side has the smaller frontier, until the searches
+    /// meet. For a path of length `d` with branching factor `b` this
+    /// expands about `2 * b^(d/2)` nodes instead of `b^d`. Returns the same
+    /// path length as `shortest_path` (the path itself may differ if
+    /// several are equally short).
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Starting entity UUID
+    /// * `end` - Target entity UUID
+    /// * `direction` - Traversal direction, from `start` towards `end`
+    /// * `max_depth` - Maximum path length in edges
+    ///
+    /// # Returns
+    ///
+    /// Vector of entity UUIDs representing path, or empty if no path found
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::GraphError` if search fails
+    pub fn bidirectional_shortest_path(
+        &self,
+        start: Uuid,
+        end: Uuid,
+        direction: TraversalDirection,
+        max_depth: usize,
+    ) -> Result<Vec<Uuid>> {
+        if start == end {
+            return Ok(vec![start]);
+        }
+
+        // Node -> the node it was reached from (None for the root)
+        let mut forward: HashMap<Uuid, Option<Uuid>> = HashMap::from([(start, None)]);
+        let mut backward: HashMap<Uuid, Option<Uuid>> = HashMap::from([(end, None)]);
+        let mut forward_frontier = vec![start];
+        let mut backward_frontier = vec![end];
+        let mut searched_depth = 0;
+
+        while searched_depth < max_depth && !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+            let expand_forward = forward_frontier.len() <= backward_frontier.len();
+            let (frontier, visited, other, dir) = if expand_forward {
+                (&mut forward_frontier, &mut forward, &backward, direction)
+            } else {
+                (&mut backward_frontier, &mut backward, &forward, direction.reversed())
+            };
+
+            let mut next = Vec::new();
+            let mut meeting = None;
+
+            'level: for &node in frontier.iter() {
+                for neighbor in self.get_neighbors(node, dir, None)? {
+                    if visited.contains_key(&neighbor) {
+                        continue;
+                    }
+                    visited.insert(neighbor, Some(node));
+                    if other.contains_key(&neighbor) {
+                        meeting = Some(neighbor);
+                        break 'level;
+                    }
+                    next.push(neighbor);
+                }
+            }
+
+            if let Some(meeting) = meeting {
+                // start .. meeting, then meeting .. end
+                let mut path = Self::walk_parents(&forward, meeting);
+                path.reverse();
+                path.extend(Self::walk_parents(&backward, meeting).into_iter().skip(1));
+                return Ok(path);
+            }
+
+            *frontier = next;
+            searched_depth += 1;
+        }
+
+        // No path found
+        Ok(Vec::new())
+    }
+
+    /// Follow parent links from `node` back to its search root.
+    fn walk_parents(parents: &HashMap<Uuid, Option<Uuid>>, node: Uuid) -> Vec<Uuid> {
+        let mut path = vec![node];
+        let mut current = node;
+        while let Some(&Some(parent)) = parents.get(&current) {
+            path.push(parent);
+            current = parent;
+        }
+        path
+    }
+
+    /// Get neighbors reached by edges of any of `rel_types`.
+    ///
+    /// One lookup per type, so each uses the edge index. Neighbors linked
+    /// by several types appear more than once.
+    fn get_neighbors_of_types(
+        &self,
+        node: Uuid,
+        direction: TraversalDirection,
+        rel_types: Option<&[String]>,
+    ) -> Result<Vec<Uuid>> {
+        match rel_types {
+            None | Some([]) => self.get_neighbors(node, direction, None),
+            Some(types) => {
+                let mut neighbors = Vec::new();
+                for rel_type in types {
+                    neighbors.extend(self.get_neighbors(node, direction, Some(rel_type))?);
+                }
+                Ok(neighbors)
+            }
+        }
+    }
+
     /// Get neighbors of a node based on direction.
     fn get_neighbors(
         &self,
@@ -254,3 +453,78 @@ impl<'a> GraphTraversal<'a> {
         Ok(neighbors)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// In-memory graph that counts neighbor lookups.
+    struct CountingGraph {
+        edges: Vec<(Uuid, Uuid)>,
+        lookups: Cell<usize>,
+    }
+
+    impl CountingGraph {
+        fn edge(&self, src: Uuid, dst: Uuid) -> Edge {
+            Edge::new(src, dst, "links".to_string())
+        }
+    }
+
+    impl EdgeProvider for CountingGraph {
+        fn get_outgoing(&self, node: Uuid, _rel_type: Option<&str>) -> Result<Vec<Edge>> {
+            self.lookups.set(self.lookups.get() + 1);
+            Ok(self.edges.iter().filter(|(src, _)| *src == node).map(|&(src, dst)| self.edge(src, dst)).collect())
+        }
+
+        fn get_incoming(&self, node: Uuid, _rel_type: Option<&str>) -> Result<Vec<Edge>> {
+            self.lookups.set(self.lookups.get() + 1);
+            Ok(self.edges.iter().filter(|(_, dst)| *dst == node).map(|&(src, dst)| self.edge(src, dst)).collect())
+        }
+    }
+
+    #[test]
+    fn test_bidirectional_shortest_path() {
+        // Directed chain of 12 nodes, each with 4 dead-end side branches
+        let chain: Vec<Uuid> = (0..12).map(|_| Uuid::new_v4()).collect();
+        let mut edges = Vec::new();
+        for pair in chain.windows(2) {
+            edges.push((pair[0], pair[1]));
+        }
+        for &node in &chain {
+            for _ in 0..4 {
+                edges.push((node, Uuid::new_v4()));
+            }
+        }
+        let graph = CountingGraph { edges, lookups: Cell::new(0) };
+        let traversal = GraphTraversal::new(&graph);
+        let (first, last) = (chain[0], chain[11]);
+
+        let unidirectional = traversal.shortest_path(first, last, TraversalDirection::Out, 20).unwrap();
+        let unidirectional_lookups = graph.lookups.replace(0);
+
+        let bidirectional = traversal.bidirectional_shortest_path(first, last, TraversalDirection::Out, 20).unwrap();
+        let bidirectional_lookups = graph.lookups.replace(0);
+
+        assert_eq!(unidirectional, chain);
+        assert_eq!(bidirectional, chain);
+        assert!(
+            bidirectional_lookups < unidirectional_lookups,
+            "bidirectional expanded {} nodes, unidirectional {}",
+            bidirectional_lookups,
+            unidirectional_lookups
+        );
+
+        // The backward search follows reverse edges: no path against them
+        assert!(traversal.bidirectional_shortest_path(last, first, TraversalDirection::Out, 20).unwrap().is_empty());
+        assert_eq!(
+            traversal.bidirectional_shortest_path(last, first, TraversalDirection::In, 20).unwrap(),
+            chain.iter().rev().copied().collect::<Vec<_>>()
+        );
+
+        // Path of 11 edges exceeds a depth of 10
+        assert!(traversal.bidirectional_shortest_path(first, last, TraversalDirection::Out, 10).unwrap().is_empty());
+        assert_eq!(traversal.bidirectional_shortest_path(first, last, TraversalDirection::Out, 11).unwrap().len(), 12);
+        assert_eq!(traversal.bidirectional_shortest_path(first, first, TraversalDirection::Out, 0).unwrap(), vec![first]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Timeouts and cancellation for outbound API calls.
+//!
+//! Embedding providers and `LlmQueryBuilder` run each request (send plus
+//! reading the body) under `with_deadline`, so a hung connection surfaces
+//! as `DatabaseError::Timeout` instead of stalling ingestion, and a
+//! `CancellationToken` can abort in-flight requests (e.g. on shutdown).
+
+use crate::types::{DatabaseError, Result};
+use std::future::Future;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Default timeout for one API request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run a request with a timeout and optional cancellation.
+///
+/// # Arguments
+///
+/// * `timeout` - Maximum time for the whole request
+/// * `cancel` - Token that aborts the request when cancelled
+/// * `what` - Request description for error messages
+/// * `request` - Request future
+///
+/// # Errors
+///
+/// Returns `DatabaseError::Timeout` if `timeout` elapses,
+/// `DatabaseError::Cancelled` if `cancel` fires first, or the request's own error
+pub(crate) async fn with_deadline<T, F>(
+    timeout: Duration,
+    cancel: Option<&CancellationToken>,
+    what: &str,
+    request: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let timed = async {
+        tokio::time::timeout(timeout, request)
+            .await
+            .map_err(|_| DatabaseError::Timeout(format!("{} after {:?}", what, timeout)))?
+    };
+
+    match cancel {
+        Some(token) => tokio::select! {
+            _ = token.cancelled() => Err(DatabaseError::Cancelled(what.to_string())),
+            result = timed => result,
+        },
+        None => timed.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_deadline() {
+        let ok = with_deadline(Duration::from_secs(1), None, "fast", async { Ok(1) }).await;
+        assert_eq!(ok.unwrap(), 1);
+
+        let slow = with_deadline(Duration::from_millis(20), None, "slow", async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        })
+        .await;
+        assert!(matches!(slow, Err(DatabaseError::Timeout(_))));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let cancelled = with_deadline(Duration::from_secs(5), Some(&token), "cancelled", std::future::pending::<Result<()>>()).await;
+        assert!(matches!(cancelled, Err(DatabaseError::Cancelled(_))));
+    }
+}
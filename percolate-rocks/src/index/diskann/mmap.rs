@@ -554,13 +554,16 @@ impl MmapIndex {
     }
 
     /// Get UUID for a node (zero-copy from mmap).
-    fn uuid(&self, node_id: u32) -> uuid::Uuid {
+    pub fn uuid(&self, node_id: u32) -> uuid::Uuid {
         unsafe { *self.uuids_ptr.add(node_id as usize) }
     }
 }
 
-// MmapIndex is Send but not Sync (single-threaded mmap access)
+// The raw pointers point into the owned, read-only mmap, and no method
+// mutates through them, so the index can be moved and shared across threads
+// (the tiered refresh task rebuilds the cold tier off the caller's thread).
 unsafe impl Send for MmapIndex {}
+unsafe impl Sync for MmapIndex {}
 
 #[cfg(test)]
 mod tests {
@@ -1,14 +1,21 @@
 //! Field indexing for SQL predicate evaluation.
 //!
 //! Indexes specified fields for 10-50x speedup on WHERE clauses.
+//!
+//! Entries live in `CF_INDEXES` as `idx:{tenant}:{field}:{value}:{uuid}`
+//! with value `{"type": entity_type}`, mirroring the key index layout.
+//! Array fields are multi-value: each distinct element gets its own entry.
 
 use crate::types::Result;
-use crate::storage::Storage;
+use crate::storage::{column_families::CF_INDEXES, keys::encode_index_key, StorageProvider};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Field indexer for fast SQL predicates.
 pub struct FieldIndexer {
-    storage: Storage,
+    storage: Arc<dyn StorageProvider>,
+    lookups: AtomicU64,
 }
 
 impl FieldIndexer {
@@ -16,13 +23,16 @@ impl FieldIndexer {
     ///
     /// # Arguments
     ///
-    /// * `storage` - Storage instance
+    /// * `storage` - Storage backend holding `CF_INDEXES`
     ///
     /// # Returns
     ///
     /// New `FieldIndexer`
-    pub fn new(storage: Storage) -> Self {
-        todo!("Implement FieldIndexer::new")
+    pub fn new(storage: Arc<dyn StorageProvider>) -> Self {
+        Self {
+            storage,
+            lookups: AtomicU64::new(0),
+        }
     }
 
     /// Index field value for entity.
@@ -30,6 +40,7 @@ impl FieldIndexer {
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant scope
+    /// * `entity_type` - Schema name of the entity
     /// * `field_name` - Field name
     /// * `field_value` - Field value
     /// * `entity_id` - Entity UUID
@@ -40,11 +51,14 @@ impl FieldIndexer {
     pub fn index_field(
         &self,
         tenant_id: &str,
+        entity_type: &str,
         field_name: &str,
         field_value: &str,
         entity_id: Uuid,
     ) -> Result<()> {
-        todo!("Implement FieldIndexer::index_field")
+        let key = encode_index_key(tenant_id, field_name, field_value, entity_id);
+        let value = serde_json::json!({"type": entity_type}).to_string();
+        self.storage.put(CF_INDEXES, &key, value.as_bytes())
     }
 
     /// Lookup entities by field value.
@@ -52,6 +66,7 @@ impl FieldIndexer {
     /// # Arguments
     ///
     /// * `tenant_id` - Tenant scope
+    /// * `entity_type` - Schema name to restrict results to
     /// * `field_name` - Field name
     /// * `field_value` - Field value
     ///
@@ -65,10 +80,33 @@ impl FieldIndexer {
     pub fn lookup(
         &self,
         tenant_id: &str,
+        entity_type: &str,
         field_name: &str,
         field_value: &str,
     ) -> Result<Vec<Uuid>> {
-        todo!("Implement FieldIndexer::lookup")
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+
+        let prefix = format!("idx:{}:{}:{}:", tenant_id, field_name, field_value).into_bytes();
+        let mut ids = Vec::new();
+
+        for item in self.storage.iterator(CF_INDEXES, &prefix) {
+            let (key, value) = item?;
+
+            let index_data: serde_json::Value = serde_json::from_slice(&value)?;
+            if index_data.get("type").and_then(|v| v.as_str()) != Some(entity_type) {
+                continue;
+            }
+
+            // Suffix after the prefix is the entity UUID
+            if let Some(id) = std::str::from_utf8(&key[prefix.len()..])
+                .ok()
+                .and_then(|s| Uuid::parse_str(s).ok())
+            {
+                ids.push(id);
+            }
+        }
+
+        Ok(ids)
     }
 
     /// Remove field index for entity.
@@ -90,6 +128,14 @@ impl FieldIndexer {
         field_value: &str,
         entity_id: Uuid,
     ) -> Result<()> {
-        todo!("Implement FieldIndexer::remove_index")
+        let key = encode_index_key(tenant_id, field_name, field_value, entity_id);
+        self.storage.delete(CF_INDEXES, &key)
+    }
+
+    /// Number of index lookups served since creation.
+    ///
+    /// Lets callers confirm a query was answered from the index.
+    pub fn lookup_count(&self) -> u64 {
+        self.lookups.load(Ordering::Relaxed)
     }
 }
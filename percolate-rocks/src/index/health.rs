@@ -0,0 +1,76 @@
+//! Vector index health metrics for drift detection.
+//!
+//! Search quality degrades quietly when the embedding model or the data
+//! changes: vectors go missing, dimensions stop matching the index, norms
+//! shift, or neighbors spread out. `Database::index_health` summarizes a
+//! table so operators can track these numbers over time and alert on
+//! change:
+//!
+//! - **Missing embeddings**: entities search can never return
+//! - **Index mismatch**: embeddings whose dimension differs from the
+//!   table's dominant one, which the index skips
+//! - **Average nearest-neighbor distance**: cosine distance from sampled
+//!   vectors to their closest neighbor; a jump suggests a model change
+//! - **Median norm**: shifts when a provider or normalization changes
+
+use serde::{Deserialize, Serialize};
+
+/// Vector health of one table.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexHealthReport {
+    /// Live entities scanned
+    pub entities: usize,
+    /// Entities without an embedding (or with an empty one)
+    pub missing_embeddings: usize,
+    /// `missing_embeddings / entities` (0.0 for an empty table)
+    pub missing_fraction: f32,
+    /// Dominant embedding dimension (0 if none)
+    pub dimensions: usize,
+    /// Vectors the index holds
+    pub indexed: usize,
+    /// Embeddings left out of the index (wrong dimension)
+    pub index_mismatch: usize,
+    /// Mean cosine distance from sampled vectors to their nearest neighbor
+    /// (`None` with fewer than two indexed vectors)
+    pub avg_nn_distance: Option<f32>,
+    /// Median L2 norm of all embeddings (0.0 if none)
+    pub median_norm: f32,
+}
+
+impl IndexHealthReport {
+    /// Whether every live entity is embedded and indexed.
+    pub fn is_fully_indexed(&self) -> bool {
+        self.missing_embeddings == 0 && self.index_mismatch == 0
+    }
+}
+
+/// Median of a list of values.
+///
+/// # Returns
+///
+/// The middle value (mean of the two middle values for even lengths), or
+/// 0.0 for an empty list
+pub fn median(mut values: Vec<f32>) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(vec![]), 0.0);
+        assert_eq!(median(vec![3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(vec![4.0, 1.0, 2.0, 3.0]), 2.5);
+    }
+}
@@ -38,19 +38,19 @@ struct VectorPoint(Vec<f32>);
 
 impl Point for VectorPoint {
     fn distance(&self, other: &Self) -> f32 {
-        // Cosine distance
-        let dot: f32 = self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum();
-        let norm_a: f32 = self.0.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_b: f32 = other.0.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-        if norm_a == 0.0 || norm_b == 0.0 {
-            return 1.0;
-        }
-
-        1.0 - (dot / (norm_a * norm_b))
+        super::similarity::cosine_distance(&self.0, &other.0)
     }
 }
 
+/// On-disk layout written by `HnswIndex::save_to`.
+#[derive(Deserialize)]
+struct HnswFile {
+    dimensions: usize,
+    ef_search: usize,
+    idx_to_id: HashMap<usize, Uuid>,
+    hnsw: Option<Hnsw<VectorPoint>>,
+}
+
 /// Index loading state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IndexState {
@@ -99,6 +99,12 @@ pub struct HnswIndex {
 
     /// Next available index
     next_idx: Arc<RwLock<usize>>,
+
+    /// Candidate list size explored at query time
+    ef_search: usize,
+
+    /// Worker threads for graph construction (`None` = rayon global pool)
+    build_threads: Option<usize>,
 }
 
 impl HnswIndex {
@@ -121,9 +127,49 @@ impl HnswIndex {
             id_to_idx: Arc::new(RwLock::new(HashMap::new())),
             idx_to_id: Arc::new(RwLock::new(HashMap::new())),
             next_idx: Arc::new(RwLock::new(0)),
+            ef_search: crate::index::SearchMode::default().ef_search(),
+            build_threads: None,
         }
     }
 
+    /// Set the query-time candidate list size.
+    ///
+    /// instant-distance fixes `ef_search` when the graph is built, so this
+    /// must be set before `build_from_vectors`. Graph construction is
+    /// unaffected: indexes that differ only in `ef_search` share the same
+    /// structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `ef_search` - Candidates explored per query (see `SearchMode::ef_search_for`)
+    ///
+    /// # Returns
+    ///
+    /// Updated `HnswIndex`
+    pub fn with_ef_search(mut self, ef_search: usize) -> Self {
+        self.ef_search = ef_search.max(1);
+        self
+    }
+
+    /// Set the number of threads used to build the graph.
+    ///
+    /// Nodes within each layer are inserted concurrently, with per-node
+    /// locks on the neighbor lists (instant-distance's construction). `1`
+    /// builds serially. Without this, construction uses the rayon global
+    /// pool (one thread per core).
+    ///
+    /// # Arguments
+    ///
+    /// * `threads` - Construction threads (minimum 1)
+    ///
+    /// # Returns
+    ///
+    /// Updated `HnswIndex`
+    pub fn with_build_threads(mut self, threads: usize) -> Self {
+        self.build_threads = Some(threads.max(1));
+        self
+    }
+
     /// Create new HNSW index with persistence.
     ///
     /// # Arguments
@@ -140,7 +186,10 @@ impl HnswIndex {
         dimensions: usize,
         max_elements: usize,
     ) -> Self {
-        todo!("Implement HnswIndex::new_with_path")
+        let mut index = Self::new(dimensions, max_elements);
+        index.path = path.as_ref().to_path_buf();
+        index.state = Arc::new(RwLock::new(IndexState::NotLoaded));
+        index
     }
 
     /// Load index from disk synchronously.
@@ -157,7 +206,23 @@ impl HnswIndex {
     ///
     /// Returns `DatabaseError::IoError` if load fails
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        todo!("Implement HnswIndex::load")
+        let bytes = std::fs::read(path.as_ref())?;
+        let file: HnswFile = bincode::deserialize(&bytes)?;
+
+        let id_to_idx: HashMap<Uuid, usize> = file.idx_to_id.iter().map(|(idx, id)| (*id, *idx)).collect();
+        let num_points = id_to_idx.len();
+
+        Ok(Self {
+            state: Arc::new(RwLock::new(IndexState::Ready)),
+            path: path.as_ref().to_path_buf(),
+            dimensions: file.dimensions,
+            inner: Arc::new(RwLock::new(file.hnsw)),
+            id_to_idx: Arc::new(RwLock::new(id_to_idx)),
+            idx_to_id: Arc::new(RwLock::new(file.idx_to_id)),
+            next_idx: Arc::new(RwLock::new(num_points)),
+            ef_search: file.ef_search,
+            build_threads: None,
+        })
     }
 
     /// Load index from disk asynchronously.
@@ -251,7 +316,7 @@ impl HnswIndex {
         if inner.is_none() {
             // Build index with first vector
             let points = vec![point];
-            let (hnsw, _point_ids) = Builder::default().build_hnsw(points);
+            let (hnsw, _point_ids) = Builder::default().ef_search(self.ef_search).build_hnsw(points);
             *inner = Some(hnsw);
         } else {
             // TODO: instant-distance doesn't support incremental insertion
@@ -344,8 +409,31 @@ impl HnswIndex {
             points.push(VectorPoint(vec));
         }
 
-        // Build HNSW index
-        let (hnsw, _point_ids) = Builder::default().build_hnsw(points);
+        // Build HNSW index, on a dedicated pool if the thread count is configured
+        let builder = Builder::default().ef_search(self.ef_search);
+        let (hnsw, point_ids) = match self.build_threads {
+            Some(threads) => {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|e| DatabaseError::SearchError(format!("Failed to start build pool: {}", e)))?;
+                pool.install(|| builder.build_hnsw(points))
+            }
+            None => builder.build_hnsw(points),
+        };
+
+        // Builder reorders points; remap input order to the assigned PointIds
+        let idx_to_id_map: HashMap<usize, Uuid> = point_ids
+            .iter()
+            .enumerate()
+            .filter_map(|(input_idx, pid)| {
+                idx_to_id_map.get(&input_idx).map(|id| (pid.into_inner() as usize, *id))
+            })
+            .collect();
+        let id_to_idx_map: HashMap<Uuid, usize> = idx_to_id_map
+            .iter()
+            .map(|(idx, id)| (*id, *idx))
+            .collect();
 
         // Store everything
         let num_points = id_to_idx_map.len();
@@ -385,7 +473,36 @@ impl HnswIndex {
     ///
     /// Prefer `save_async()` for non-blocking saves
     pub fn save(&self) -> Result<()> {
-        todo!("Implement HnswIndex::save")
+        self.save_to(&self.path)
+    }
+
+    /// Save index to a given file.
+    ///
+    /// The graph is serialized as built, so `load` restores it without
+    /// re-running construction. The file is written next to `path` and
+    /// renamed into place, so a crash never leaves a partial index.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Index file path
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IoError` if the write fails, or
+    /// `DatabaseError::SearchError` if the index is being rebuilt
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let busy = |_| DatabaseError::SearchError("Index is being rebuilt - retry save".to_string());
+        let inner = self.inner.try_read().map_err(busy)?;
+        let idx_to_id = self.idx_to_id.try_read().map_err(busy)?;
+
+        let bytes = bincode::serialize(&(self.dimensions, self.ef_search, &*idx_to_id, &*inner))?;
+
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        Ok(())
     }
 
     /// Save index to file asynchronously.
@@ -463,6 +580,77 @@ mod tests {
         assert!(ids.contains(&id3));
     }
 
+    /// Clustered unit vectors: nearest neighbors are well defined.
+    fn clustered_vectors(n: usize, dims: usize) -> Vec<(Uuid, Vec<f32>)> {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let centers: Vec<Vec<f32>> = (0..16)
+            .map(|_| (0..dims).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+
+        (0..n)
+            .map(|i| {
+                let center = &centers[i % centers.len()];
+                let vector = center.iter().map(|c| c + rng.gen_range(-0.2..0.2)).collect();
+                (Uuid::new_v4(), vector)
+            })
+            .collect()
+    }
+
+    /// Mean top-k overlap with exact cosine neighbors.
+    async fn recall(index: &HnswIndex, vectors: &[(Uuid, Vec<f32>)], queries: usize, k: usize) -> f32 {
+        let mut hits = 0;
+        for (_, query) in vectors.iter().take(queries) {
+            let mut exact: Vec<(Uuid, f32)> = vectors
+                .iter()
+                .map(|(id, v)| (*id, crate::index::similarity::cosine_distance(query, v)))
+                .collect();
+            exact.sort_by(|a, b| a.1.total_cmp(&b.1));
+            let exact: Vec<Uuid> = exact.iter().take(k).map(|(id, _)| *id).collect();
+
+            let found = index.search(query, k).await.unwrap();
+            hits += found.iter().filter(|(id, _)| exact.contains(id)).count();
+        }
+        hits as f32 / (queries * k) as f32
+    }
+
+    #[tokio::test]
+    async fn test_parallel_build_matches_serial() {
+        let vectors = clustered_vectors(4_000, 32);
+
+        let mut serial = HnswIndex::new(32, vectors.len()).with_build_threads(1);
+        let started = std::time::Instant::now();
+        serial.build_from_vectors(vectors.clone()).await.unwrap();
+        let serial_elapsed = started.elapsed();
+
+        let mut parallel = HnswIndex::new(32, vectors.len()).with_build_threads(4);
+        let started = std::time::Instant::now();
+        parallel.build_from_vectors(vectors.clone()).await.unwrap();
+        let parallel_elapsed = started.elapsed();
+
+        assert_eq!(parallel.num_nodes(), serial.num_nodes());
+
+        // Insertion order differs, so the graphs may too; recall must not
+        let serial_recall = recall(&serial, &vectors, 100, 10).await;
+        let parallel_recall = recall(&parallel, &vectors, 100, 10).await;
+        assert!(serial_recall > 0.9, "serial recall {}", serial_recall);
+        assert!(
+            parallel_recall >= serial_recall - 0.02,
+            "parallel recall {} vs serial {}",
+            parallel_recall,
+            serial_recall
+        );
+
+        // Margin for pool startup and noisy single-core runners
+        assert!(
+            parallel_elapsed <= serial_elapsed.mul_f64(1.5),
+            "parallel {:?} vs serial {:?}",
+            parallel_elapsed,
+            serial_elapsed
+        );
+    }
+
     #[tokio::test]
     async fn test_hnsw_dimension_validation() {
         let mut index = HnswIndex::new(384, 1000);
@@ -574,10 +574,52 @@ impl FuzzyKeyIndex {
     }
 }
 
+/// Levenshtein edit distance between two strings (by `char`).
+///
+/// # Arguments
+///
+/// * `a` - First string
+/// * `b` - Second string
+///
+/// # Returns
+///
+/// Minimum number of single-character insertions, deletions, or substitutions
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(levenshtein("alice", "alise"), 1);
+/// ```
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b_chars.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("alice", "alice"), 0);
+        assert_eq!(levenshtein("alice", "alise"), 1);
+        assert_eq!(levenshtein("alice", "alic"), 1);
+        assert_eq!(levenshtein("", "bob"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
     #[test]
     fn test_exact_lookup() {
         todo!("Test exact key match")
@@ -6,6 +6,9 @@
 //! - **BM25** keyword search for full-text retrieval (best-match ranking)
 //! - **Fuzzy key lookup** with BM25 fallback (exact → prefix → fuzzy)
 //! - Field indexes for SQL predicates
+//! - SIMD cosine similarity kernels for candidate ranking
+//! - Search modes trading recall for latency
+//! - Vector health metrics for drift detection
 //! - Reverse key index for global lookups
 
 pub mod hnsw;
@@ -15,11 +18,17 @@ pub mod bm25;
 pub mod fields;
 pub mod keys;
 pub mod keys_fuzzy;
+pub mod similarity;
+pub mod search_mode;
+pub mod health;
 
 pub use hnsw::HnswIndex;
 pub use diskann::DiskANNIndex;
-pub use tiered::{TieredIndex, TieredSearchConfig};
+pub use tiered::{RefreshStats, ScoreOrder, TieredIndex, TieredSearchConfig};
 pub use bm25::BM25Index;
 pub use fields::FieldIndexer;
 pub use keys::KeyIndex;
-pub use keys_fuzzy::{FuzzyKeyIndex, LookupResult, MatchType};
+pub use keys_fuzzy::{levenshtein, FuzzyKeyIndex, LookupResult, MatchType};
+pub use similarity::{cosine_similarity, cosine_similarity_batch};
+pub use search_mode::SearchMode;
+pub use health::IndexHealthReport;
@@ -0,0 +1,101 @@
+//! Recall/latency presets for vector search.
+//!
+//! Each mode maps to an HNSW `ef_search` (candidate list size explored at
+//! the base layer) and an over-fetch factor. The effective list size is
+//! `max(ef_search, top_k * over_fetch)`, so small modes still return
+//! `top_k` results and large `top_k` values keep their headroom.
+//!
+//! | Mode       | ef_search | over-fetch |
+//! |------------|-----------|------------|
+//! | `fast`     | 16        | 1×         |
+//! | `balanced` | 100       | 2×         |
+//! | `accurate` | 400       | 4×         |
+
+use crate::types::{DatabaseError, Result};
+
+/// Vector search recall/latency tradeoff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SearchMode {
+    /// Lowest latency, lower recall
+    Fast,
+
+    /// Default tradeoff
+    #[default]
+    Balanced,
+
+    /// Highest recall, slower queries
+    Accurate,
+}
+
+impl SearchMode {
+    /// Parse mode from string (`fast`, `balanced`, `accurate`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "balanced" => Ok(Self::Balanced),
+            "accurate" => Ok(Self::Accurate),
+            other => Err(DatabaseError::ConfigError(format!(
+                "Unknown search mode '{}' (expected fast, balanced, or accurate)",
+                other
+            ))),
+        }
+    }
+
+    /// Get mode name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Balanced => "balanced",
+            Self::Accurate => "accurate",
+        }
+    }
+
+    /// Preset HNSW `ef_search`.
+    pub fn ef_search(&self) -> usize {
+        match self {
+            Self::Fast => 16,
+            Self::Balanced => 100,
+            Self::Accurate => 400,
+        }
+    }
+
+    /// Candidates fetched per requested result.
+    pub fn over_fetch(&self) -> usize {
+        match self {
+            Self::Fast => 1,
+            Self::Balanced => 2,
+            Self::Accurate => 4,
+        }
+    }
+
+    /// Effective `ef_search` for a query returning `top_k` results.
+    pub fn ef_search_for(&self, top_k: usize) -> usize {
+        self.ef_search().max(top_k.saturating_mul(self.over_fetch()))
+    }
+}
+
+impl std::fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_presets() {
+        assert_eq!(SearchMode::parse("Accurate").unwrap(), SearchMode::Accurate);
+        assert!(SearchMode::parse("thorough").is_err());
+
+        assert_eq!(SearchMode::Fast.ef_search_for(10), 16);
+        assert_eq!(SearchMode::Fast.ef_search_for(50), 50);
+        assert_eq!(SearchMode::Accurate.ef_search_for(200), 800);
+        assert!(SearchMode::Fast.ef_search_for(10) < SearchMode::Accurate.ef_search_for(10));
+    }
+}
@@ -0,0 +1,263 @@
+//! Vector similarity kernels.
+//!
+//! Cosine similarity is the hot loop when ranking candidates. This module
+//! provides a SIMD path (AVX + FMA on x86_64, selected at runtime) with a
+//! portable scalar fallback for every other target.
+//!
+//! # Batch scoring
+//!
+//! `cosine_similarity_batch` computes the query norm once and reuses it for
+//! every candidate, so only one dot product and one norm are computed per
+//! candidate.
+
+/// Dot product of two vectors.
+///
+/// Vectors of different length are truncated to the shorter one.
+#[inline]
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let len = a.len().min(b.len());
+    let (a, b) = (&a[..len], &b[..len]);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if simd_available() {
+            // SAFETY: AVX and FMA support verified at runtime above.
+            return unsafe { x86::dot_avx(a, b) };
+        }
+    }
+
+    dot_scalar(a, b)
+}
+
+/// Euclidean (L2) norm of a vector.
+#[inline]
+pub fn norm(a: &[f32]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+/// Cosine similarity of two vectors in `[-1.0, 1.0]`.
+///
+/// Returns `0.0` if either vector has zero norm.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let sim = cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]);
+/// assert!((sim - 1.0).abs() < 1e-6);
+/// ```
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let norm_a = norm(a);
+    let norm_b = norm(b);
+    cosine_from_parts(dot(a, b), norm_a, norm_b)
+}
+
+/// Cosine similarity of one query against many candidates.
+///
+/// The query norm is computed once and amortized across all candidates.
+///
+/// # Arguments
+///
+/// * `query` - Query vector
+/// * `candidates` - Candidate vectors to score
+///
+/// # Returns
+///
+/// Similarity per candidate, in input order
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let scores = cosine_similarity_batch(&query, &candidates);
+/// assert_eq!(scores.len(), candidates.len());
+/// ```
+pub fn cosine_similarity_batch<V: AsRef<[f32]>>(query: &[f32], candidates: &[V]) -> Vec<f32> {
+    let query_norm = norm(query);
+
+    candidates
+        .iter()
+        .map(|c| {
+            let c = c.as_ref();
+            cosine_from_parts(dot(query, c), query_norm, norm(c))
+        })
+        .collect()
+}
+
+/// Cosine distance (`1.0 - similarity`), as used by the HNSW index.
+///
+/// Returns `1.0` if either vector has zero norm.
+#[inline]
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    1.0 - cosine_similarity(a, b)
+}
+
+#[inline]
+fn cosine_from_parts(dot: f32, norm_a: f32, norm_b: f32) -> f32 {
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Scalar dot product (reference implementation and fallback).
+#[inline]
+pub(crate) fn dot_scalar(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Whether the SIMD path is used on this machine.
+#[cfg(target_arch = "x86_64")]
+#[inline]
+pub fn simd_available() -> bool {
+    is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma")
+}
+
+/// Whether the SIMD path is used on this machine.
+#[cfg(not(target_arch = "x86_64"))]
+#[inline]
+pub fn simd_available() -> bool {
+    false
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// AVX dot product with two independent accumulators.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure AVX and FMA are available and `a.len() == b.len()`.
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn dot_avx(a: &[f32], b: &[f32]) -> f32 {
+        debug_assert_eq!(a.len(), b.len());
+
+        let len = a.len();
+        let pa = a.as_ptr();
+        let pb = b.as_ptr();
+
+        let mut acc0 = _mm256_setzero_ps();
+        let mut acc1 = _mm256_setzero_ps();
+        let mut i = 0;
+
+        while i + 16 <= len {
+            acc0 = _mm256_fmadd_ps(_mm256_loadu_ps(pa.add(i)), _mm256_loadu_ps(pb.add(i)), acc0);
+            acc1 = _mm256_fmadd_ps(
+                _mm256_loadu_ps(pa.add(i + 8)),
+                _mm256_loadu_ps(pb.add(i + 8)),
+                acc1,
+            );
+            i += 16;
+        }
+        while i + 8 <= len {
+            acc0 = _mm256_fmadd_ps(_mm256_loadu_ps(pa.add(i)), _mm256_loadu_ps(pb.add(i)), acc0);
+            i += 8;
+        }
+
+        let acc = _mm256_add_ps(acc0, acc1);
+        let mut lanes = [0.0f32; 8];
+        _mm256_storeu_ps(lanes.as_mut_ptr(), acc);
+        let mut sum: f32 = lanes.iter().sum();
+
+        while i < len {
+            sum += *pa.add(i) * *pb.add(i);
+            i += 1;
+        }
+
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random vector (LCG) so tests need no extra deps.
+    fn vector(seed: u64, dim: usize) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (0..dim)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                ((state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    fn cosine_scalar(a: &[f32], b: &[f32]) -> f32 {
+        let n_a = dot_scalar(a, a).sqrt();
+        let n_b = dot_scalar(b, b).sqrt();
+        cosine_from_parts(dot_scalar(a, b), n_a, n_b)
+    }
+
+    #[test]
+    fn test_identical_and_orthogonal() {
+        assert!((cosine_similarity(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_zero_vector() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_distance(&[0.0, 0.0], &[1.0, 0.0]), 1.0);
+    }
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        // Odd dimensions exercise the remainder loop
+        for dim in [1, 7, 8, 15, 16, 17, 33, 384, 768, 1536] {
+            for seed in 0..10 {
+                let a = vector(seed, dim);
+                let b = vector(seed + 1000, dim);
+
+                let simd = dot(&a, &b);
+                let scalar = dot_scalar(&a, &b);
+                let tolerance = 1e-4 * (1.0 + scalar.abs());
+                assert!(
+                    (simd - scalar).abs() <= tolerance,
+                    "dim={} seed={} simd={} scalar={}",
+                    dim, seed, simd, scalar
+                );
+
+                let sim = cosine_similarity(&a, &b);
+                assert!((sim - cosine_scalar(&a, &b)).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_matches_pairwise() {
+        let query = vector(1, 384);
+        let candidates: Vec<Vec<f32>> = (0..100).map(|i| vector(i + 10, 384)).collect();
+
+        let batch = cosine_similarity_batch(&query, &candidates);
+
+        assert_eq!(batch.len(), candidates.len());
+        for (score, candidate) in batch.iter().zip(&candidates) {
+            assert!((score - cosine_similarity(&query, candidate)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_batch_10k_vectors() {
+        let dim = 384;
+        let query = vector(42, dim);
+        let candidates: Vec<Vec<f32>> = (0..10_000).map(|i| vector(i, dim)).collect();
+
+        let batch = cosine_similarity_batch(&query, &candidates);
+        let scalar: Vec<f32> = candidates.iter().map(|c| cosine_scalar(&query, c)).collect();
+
+        assert_eq!(batch.len(), 10_000);
+        for (b, s) in batch.iter().zip(&scalar) {
+            assert!((b - s).abs() < 1e-5);
+        }
+
+        // The query's own vector is the best match
+        let best = batch
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i);
+        assert_eq!(best, Some(42));
+        assert!((batch[42] - 1.0).abs() < 1e-5);
+    }
+}
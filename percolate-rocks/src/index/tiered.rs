@@ -20,6 +20,8 @@
 //!     max_hot_vectors: 100_000,
 //!     auto_refresh: true,
 //!     refresh_interval_secs: 3600,
+//!     max_hot_memory_bytes: Some(256 * 1024 * 1024),
+//!     score_order: ScoreOrder::AscendingDistance,
 //! };
 //!
 //! let mut index = TieredIndex::new(config, dimensions);
@@ -28,11 +30,54 @@
 //! // Search queries recent data (HNSW) + historical (DiskANN)
 //! let results = index.search(&query_vector, 10).await?;
 //! ```
+//!
+//! # Memory Budget
+//!
+//! Age alone doesn't bound the hot tier: a burst of recent data can exceed
+//! the RAM budget. With `max_hot_memory_bytes` set, `build` and every
+//! refresh keep only the newest vectors that fit (estimated with
+//! `hot_vector_bytes`) in HNSW and demote the rest to the cold tier, which
+//! is rebuilt to include them, so they stay searchable.
+//!
+//! # Score Convention
+//!
+//! The tiers measure closeness differently: HNSW returns cosine distance,
+//! DiskANN returns L2 distance. Cold vectors are L2-normalized when the
+//! DiskANN index is built and queries before it is searched, so L2 order
+//! is cosine order and `search` can convert cold L2 distances to cosine
+//! distance exactly (`d² / 2`). Both tiers then share one scale, reported
+//! in the configured `ScoreOrder`:
+//!
+//! | `ScoreOrder` | Score | Best first |
+//! |--------------|-------|------------|
+//! | `AscendingDistance` (default) | cosine distance | smallest |
+//! | `DescendingSimilarity` | `1 - distance` | largest |
+//!
+//! # Refresh Monitoring
+//!
+//! Each hot index refresh (manual or background) is logged and recorded;
+//! `refresh_stats()` reports when the last one ran, how many vectors it
+//! loaded, and how long it took.
+//!
+//! # Persistence
+//!
+//! `save(dir)` writes both tiers and the hot/cold cutoff to a directory;
+//! `load(dir, config)` restores them without rebuilding either index:
+//!
+//! | File | Contents |
+//! |------|----------|
+//! | `tiered.json` | Dimensions and cutoff |
+//! | `hot.hnsw` | Serialized HNSW graph and UUID mapping |
+//! | `cold.diskann` | DiskANN index (memory-mapped in place on load) |
 
+use crate::embeddings::norms::l2_normalize;
 use crate::index::hnsw::HnswIndex;
 use crate::index::diskann::{DiskANNIndex, MmapIndex};
 use crate::types::{DatabaseError, Result};
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
@@ -52,6 +97,12 @@ pub struct TieredSearchConfig {
 
     /// Refresh interval (seconds)
     pub refresh_interval_secs: u64,
+
+    /// Estimated memory cap for the hot HNSW tier (`None` = age only)
+    pub max_hot_memory_bytes: Option<usize>,
+
+    /// Score convention of search results (and merge direction)
+    pub score_order: ScoreOrder,
 }
 
 impl Default for TieredSearchConfig {
@@ -61,10 +112,98 @@ impl Default for TieredSearchConfig {
             max_hot_vectors: 100_000,
             auto_refresh: true,
             refresh_interval_secs: 3600,
+            max_hot_memory_bytes: None,
+            score_order: ScoreOrder::default(),
         }
     }
 }
 
+/// Direction in which tiered search scores rank.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScoreOrder {
+    /// Scores are distances: smaller is better
+    #[default]
+    AscendingDistance,
+    /// Scores are similarities: larger is better
+    DescendingSimilarity,
+}
+
+impl ScoreOrder {
+    /// Express a cosine distance as a score in this convention.
+    pub fn from_cosine_distance(self, distance: f32) -> f32 {
+        match self {
+            Self::AscendingDistance => distance,
+            Self::DescendingSimilarity => 1.0 - distance,
+        }
+    }
+
+    /// Order two scores best first.
+    fn compare(self, a: f32, b: f32) -> std::cmp::Ordering {
+        match self {
+            Self::AscendingDistance => a.total_cmp(&b),
+            Self::DescendingSimilarity => b.total_cmp(&a),
+        }
+    }
+}
+
+/// Cosine distance of unit vectors `d` apart in L2 (`d² = 2 - 2cos`).
+fn l2_to_cosine_distance(distance: f32) -> f32 {
+    distance * distance / 2.0
+}
+
+/// Estimated HNSW memory per vector beyond its components: layer-0
+/// neighbor links (2 × M=32 `u32`s), upper layers, and the UUID maps.
+const HNSW_NODE_OVERHEAD_BYTES: usize = 320;
+
+/// Estimated hot tier memory for one vector.
+///
+/// # Arguments
+///
+/// * `dimensions` - Vector dimensionality
+///
+/// # Returns
+///
+/// Bytes used by the vector and its HNSW bookkeeping
+pub fn hot_vector_bytes(dimensions: usize) -> usize {
+    dimensions * std::mem::size_of::<f32>() + HNSW_NODE_OVERHEAD_BYTES
+}
+
+/// Outcome of the most recent hot index refresh.
+///
+/// Lets operators see how stale the hot tier is (see `TieredIndex::refresh_stats`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RefreshStats {
+    /// When the last refresh finished (`None` = never refreshed)
+    pub last_refresh_time: Option<DateTime<Utc>>,
+
+    /// Vectors loaded into the hot index by the last refresh
+    pub last_refresh_count: usize,
+
+    /// Time spent rebuilding the hot index in the last refresh
+    pub last_refresh_duration: std::time::Duration,
+
+    /// Refreshes completed since the index was created
+    pub refresh_count: u64,
+}
+
+/// Manifest file written by `TieredIndex::save`.
+const MANIFEST_FILE: &str = "tiered.json";
+
+/// Hot tier file written by `TieredIndex::save`.
+const HOT_FILE: &str = "hot.hnsw";
+
+/// Cold tier file written by `TieredIndex::save`.
+const COLD_FILE: &str = "cold.diskann";
+
+/// Persisted tiered index metadata.
+#[derive(Debug, Serialize, Deserialize)]
+struct TieredManifest {
+    dimensions: usize,
+    cutoff: DateTime<Utc>,
+    hot: bool,
+    cold: bool,
+}
+
 /// Tiered vector index with HNSW (hot) + DiskANN (cold).
 ///
 /// Automatically partitions vectors by age:
@@ -85,7 +224,7 @@ pub struct TieredIndex {
     cold: Arc<RwLock<Option<MmapIndex>>>,
 
     /// Cold index path on disk
-    cold_path: Option<std::path::PathBuf>,
+    cold_path: Arc<RwLock<Option<PathBuf>>>,
 
     /// Vector dimensionality
     dimensions: usize,
@@ -98,6 +237,9 @@ pub struct TieredIndex {
 
     /// Cutoff timestamp for hot/cold boundary
     cutoff: Arc<RwLock<DateTime<Utc>>>,
+
+    /// Last hot index refresh
+    refresh_stats: Arc<RwLock<RefreshStats>>,
 }
 
 impl TieredIndex {
@@ -117,16 +259,20 @@ impl TieredIndex {
         Self {
             hot: Arc::new(RwLock::new(None)),
             cold: Arc::new(RwLock::new(None)),
-            cold_path: None,
+            cold_path: Arc::new(RwLock::new(None)),
             dimensions,
             config,
             refresh_task: None,
             cutoff: Arc::new(RwLock::new(cutoff)),
+            refresh_stats: Arc::new(RwLock::new(RefreshStats::default())),
         }
     }
 
     /// Build tiered index from vectors with timestamps.
     ///
+    /// Vectors newer than the cutoff go to the hot tier, up to
+    /// `max_hot_memory_bytes` (newest first); everything else goes cold.
+    ///
     /// # Arguments
     ///
     /// * `vectors` - Vector of (id, embedding, created_at) tuples
@@ -155,13 +301,11 @@ impl TieredIndex {
             .into_iter()
             .partition(|(_, _, created_at)| *created_at >= cutoff);
 
-        // Build HNSW index for hot data
-        if !hot_vectors.is_empty() {
-            let hot_data: Vec<_> = hot_vectors
-                .into_iter()
-                .map(|(id, vec, _)| (id, vec))
-                .collect();
+        // Recent vectors over the memory budget go cold too
+        let (hot_data, demoted) = split_by_budget(hot_vectors, self.dimensions, self.config.max_hot_memory_bytes);
 
+        // Build HNSW index for hot data
+        if !hot_data.is_empty() {
             let mut hnsw = HnswIndex::new(self.dimensions, hot_data.len());
             hnsw.build_from_vectors(hot_data).await?;
 
@@ -169,30 +313,17 @@ impl TieredIndex {
         }
 
         // Build DiskANN index for cold data
-        if !cold_vectors.is_empty() {
-            // Extract UUIDs and vectors for DiskANN build
-            let cold_data: Vec<(Uuid, Vec<f32>)> = cold_vectors
-                .into_iter()
-                .map(|(id, vec, _)| (id, vec))
-                .collect();
-
-            // Also extract just vectors for save (needed separately)
-            let cold_vecs: Vec<Vec<f32>> = cold_data.iter().map(|(_, v)| v.clone()).collect();
-
-            // Build DiskANN with default params
-            use crate::index::diskann::BuildParams;
-            let params = BuildParams::default();
-            let diskann = DiskANNIndex::build(cold_data, params)?;
-
-            // Save to disk (temp path for now - should be configurable)
-            let cold_path = std::env::temp_dir().join(format!("tiered_cold_{}.diskann", Uuid::new_v4()));
-            diskann.save(cold_path.to_str().unwrap(), &cold_vecs)?;
+        let cold_data: Vec<(Uuid, Vec<f32>)> = cold_vectors
+            .into_iter()
+            .map(|(id, vec, _)| (id, vec))
+            .chain(demoted)
+            .collect();
 
-            // Load as memory-mapped index for search
-            let mmap_index = MmapIndex::load(cold_path.to_str().unwrap())?;
+        if !cold_data.is_empty() {
+            let (mmap_index, cold_path) = build_cold(cold_data)?;
 
             *self.cold.write().await = Some(mmap_index);
-            self.cold_path = Some(cold_path);
+            *self.cold_path.write().await = Some(cold_path);
         }
 
         Ok(())
@@ -207,7 +338,8 @@ impl TieredIndex {
     ///
     /// # Returns
     ///
-    /// Vector of `(entity_id, distance)` tuples, sorted by distance
+    /// Vector of `(entity_id, score)` tuples, best first, with scores in the
+    /// configured `score_order`
     ///
     /// # Errors
     ///
@@ -230,7 +362,9 @@ impl TieredIndex {
             )));
         }
 
-        // Search hot index (HNSW)
+        let order = self.config.score_order;
+
+        // Search hot index (HNSW, cosine distance)
         let hot_results = {
             let hot = self.hot.read().await;
             match hot.as_ref() {
@@ -244,49 +378,171 @@ impl TieredIndex {
             let cold = self.cold.read().await;
             match cold.as_ref() {
                 Some(mmap) => {
+                    // Cold vectors are stored unit-length; match them
+                    let mut unit_query = query.to_vec();
+                    l2_normalize(&mut unit_query);
+
                     // Use larger search list for DiskANN (better recall)
                     let search_list_size = top_k * 2;
-                    mmap.search(query, top_k, search_list_size)?
+                    mmap.search(&unit_query, top_k, search_list_size)?
                 },
                 None => vec![],
             }
         };
 
+        // Put both tiers on the cosine scale, in the configured convention
+        let hot_results = hot_results
+            .into_iter()
+            .map(|(id, distance)| (id, order.from_cosine_distance(distance)))
+            .collect();
+        let cold_results = cold_results
+            .into_iter()
+            .map(|(id, distance)| (id, order.from_cosine_distance(l2_to_cosine_distance(distance))))
+            .collect();
+
         // Merge and re-rank by score
-        let merged = merge_results(hot_results, cold_results, top_k);
+        let merged = merge_results(hot_results, cold_results, top_k, order);
 
         Ok(merged)
     }
 
     /// Refresh hot index with recent data.
     ///
-    /// Rebuilds HNSW index with vectors newer than cutoff.
+    /// Rebuilds HNSW index with vectors newer than cutoff. If they exceed
+    /// `max_hot_memory_bytes`, the oldest are demoted to the cold tier first.
     ///
     /// # Arguments
     ///
-    /// * `recent_vectors` - Recent vectors to index
+    /// * `recent_vectors` - Recent vectors to index, as (id, embedding, created_at)
     ///
     /// # Errors
     ///
     /// Returns `DatabaseError::SearchError` if refresh fails
-    pub async fn refresh_hot_index(&mut self, recent_vectors: Vec<(Uuid, Vec<f32>)>) -> Result<()> {
+    pub async fn refresh_hot_index(&mut self, recent_vectors: Vec<(Uuid, Vec<f32>, DateTime<Utc>)>) -> Result<()> {
         if recent_vectors.is_empty() {
             return Ok(());
         }
 
-        // Rebuild HNSW index
-        let mut hnsw = HnswIndex::new(self.dimensions, recent_vectors.len());
-        hnsw.build_from_vectors(recent_vectors).await?;
+        let recent_vectors = rebalance(
+            &self.cold,
+            &self.cold_path,
+            self.dimensions,
+            self.config.max_hot_memory_bytes,
+            recent_vectors,
+        ).await?;
+
+        rebuild_hot(
+            &self.hot,
+            &self.cutoff,
+            &self.refresh_stats,
+            self.dimensions,
+            self.config.hot_data_days,
+            recent_vectors,
+        ).await
+    }
 
-        // Atomic swap
-        *self.hot.write().await = Some(hnsw);
+    /// Save both tiers to a directory.
+    ///
+    /// The cold DiskANN file is copied into `dir`, so the saved index
+    /// doesn't depend on the temp file written by `build`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Target directory (created if missing)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IoError` if writing fails, or
+    /// `DatabaseError::SearchError` if the hot index is mid-rebuild
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// index.save("/var/lib/rem/tiered").await?;
+    /// let index = TieredIndex::load("/var/lib/rem/tiered", config)?;
+    /// ```
+    pub async fn save<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let hot = self.hot.read().await;
+        if let Some(hnsw) = hot.as_ref() {
+            hnsw.save_to(dir.join(HOT_FILE))?;
+        }
 
-        // Update cutoff timestamp
-        *self.cutoff.write().await = Utc::now() - Duration::days(self.config.hot_data_days as i64);
+        let cold_path = self.cold_path.read().await;
+        let cold_target = dir.join(COLD_FILE);
+        if let Some(cold_path) = cold_path.as_ref() {
+            // Re-saving a loaded index into its own directory needs no copy
+            if cold_path.canonicalize()? != cold_target.canonicalize().unwrap_or_default() {
+                std::fs::copy(cold_path, &cold_target)?;
+            }
+        }
+
+        let manifest = TieredManifest {
+            dimensions: self.dimensions,
+            cutoff: *self.cutoff.read().await,
+            hot: hot.is_some(),
+            cold: cold_path.is_some(),
+        };
+        std::fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
 
         Ok(())
     }
 
+    /// Load a tiered index saved with `save`.
+    ///
+    /// The cutoff is re-derived against the current time: the later of
+    /// the saved cutoff and `now - hot_data_days`. Vectors that aged past
+    /// the window while the index was on disk stay in the hot tier until
+    /// the next refresh, but `get_cutoff` no longer reports them as hot.
+    /// If `hot_data_days` grew since the save, the saved cutoff wins: the
+    /// hot tier holds nothing older than it.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory written by `save`
+    /// * `config` - Tiered search configuration
+    ///
+    /// # Returns
+    ///
+    /// `TieredIndex` with both tiers ready for search
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IoError` if a file is missing or unreadable
+    pub fn load<P: AsRef<Path>>(dir: P, config: TieredSearchConfig) -> Result<Self> {
+        let dir = dir.as_ref();
+        let manifest: TieredManifest = serde_json::from_slice(&std::fs::read(dir.join(MANIFEST_FILE))?)?;
+
+        let mut index = Self::new(config, manifest.dimensions);
+
+        let window_cutoff = Utc::now() - Duration::days(index.config.hot_data_days as i64);
+        index.cutoff = Arc::new(RwLock::new(manifest.cutoff.max(window_cutoff)));
+
+        if manifest.hot {
+            index.hot = Arc::new(RwLock::new(Some(HnswIndex::load(dir.join(HOT_FILE))?)));
+        }
+
+        if manifest.cold {
+            let cold_path = dir.join(COLD_FILE);
+            let path_str = cold_path.to_str().ok_or_else(|| {
+                DatabaseError::SearchError(format!("Invalid cold index path: {}", cold_path.display()))
+            })?;
+            index.cold = Arc::new(RwLock::new(Some(MmapIndex::load(path_str)?)));
+            index.cold_path = Arc::new(RwLock::new(Some(cold_path)));
+        }
+
+        Ok(index)
+    }
+
+    /// Get statistics of the most recent hot index refresh.
+    ///
+    /// Covers both `refresh_hot_index` and the background refresh task.
+    pub async fn refresh_stats(&self) -> RefreshStats {
+        self.refresh_stats.read().await.clone()
+    }
+
     /// Get current hot/cold boundary timestamp.
     pub async fn get_cutoff(&self) -> DateTime<Utc> {
         *self.cutoff.read().await
@@ -301,6 +557,13 @@ impl TieredIndex {
         }
     }
 
+    /// Get estimated hot index memory (bytes).
+    ///
+    /// Stays within `max_hot_memory_bytes` when a budget is configured.
+    pub fn hot_memory_bytes(&self) -> usize {
+        self.hot_size() * hot_vector_bytes(self.dimensions)
+    }
+
     /// Get cold index size (number of vectors).
     pub async fn cold_size(&self) -> usize {
         let cold = self.cold.read().await;
@@ -314,7 +577,8 @@ impl TieredIndex {
     ///
     /// # Arguments
     ///
-    /// * `refresh_callback` - Async function that returns recent vectors
+    /// * `refresh_callback` - Async function that returns recent vectors as
+    ///   (id, embedding, created_at)
     ///
     /// # Example
     ///
@@ -327,7 +591,7 @@ impl TieredIndex {
     pub async fn start_refresh_task<F, Fut>(&mut self, refresh_callback: F)
     where
         F: Fn() -> Fut + Send + 'static,
-        Fut: std::future::Future<Output = Result<Vec<(Uuid, Vec<f32>)>>> + Send,
+        Fut: std::future::Future<Output = Result<Vec<(Uuid, Vec<f32>, DateTime<Utc>)>>> + Send,
     {
         if !self.config.auto_refresh {
             return;
@@ -335,9 +599,13 @@ impl TieredIndex {
 
         let interval_secs = self.config.refresh_interval_secs;
         let hot = Arc::clone(&self.hot);
+        let cold = Arc::clone(&self.cold);
+        let cold_path = Arc::clone(&self.cold_path);
         let cutoff = Arc::clone(&self.cutoff);
+        let refresh_stats = Arc::clone(&self.refresh_stats);
         let dimensions = self.dimensions;
         let hot_data_days = self.config.hot_data_days;
+        let max_hot_memory_bytes = self.config.max_hot_memory_bytes;
 
         let handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(
@@ -354,18 +622,20 @@ impl TieredIndex {
                             continue;
                         }
 
-                        // Rebuild HNSW index
-                        let mut hnsw = HnswIndex::new(dimensions, recent_vectors.len());
-                        if let Err(e) = hnsw.build_from_vectors(recent_vectors).await {
+                        let rebuilt = match rebalance(&cold, &cold_path, dimensions, max_hot_memory_bytes, recent_vectors).await {
+                            Ok(hot_vectors) => rebuild_hot(
+                                &hot,
+                                &cutoff,
+                                &refresh_stats,
+                                dimensions,
+                                hot_data_days,
+                                hot_vectors,
+                            ).await,
+                            Err(e) => Err(e),
+                        };
+                        if let Err(e) = rebuilt {
                             eprintln!("Failed to refresh hot index: {}", e);
-                            continue;
                         }
-
-                        // Atomic swap
-                        *hot.write().await = Some(hnsw);
-
-                        // Update cutoff timestamp
-                        *cutoff.write().await = Utc::now() - Duration::days(hot_data_days as i64);
                     }
                     Err(e) => {
                         eprintln!("Refresh callback failed: {}", e);
@@ -393,58 +663,182 @@ impl Drop for TieredIndex {
     }
 }
 
+/// Rebuild the hot index from recent vectors and swap it in.
+///
+/// Advances the cutoff and records the refresh in `stats`.
+async fn rebuild_hot(
+    hot: &RwLock<Option<HnswIndex>>,
+    cutoff: &RwLock<DateTime<Utc>>,
+    stats: &RwLock<RefreshStats>,
+    dimensions: usize,
+    hot_data_days: u32,
+    recent_vectors: Vec<(Uuid, Vec<f32>)>,
+) -> Result<()> {
+    let started = std::time::Instant::now();
+    let count = recent_vectors.len();
+
+    // Rebuild HNSW index
+    let mut hnsw = HnswIndex::new(dimensions, count);
+    hnsw.build_from_vectors(recent_vectors).await?;
+
+    // Atomic swap
+    *hot.write().await = Some(hnsw);
+
+    // Update cutoff timestamp
+    let new_cutoff = Utc::now() - Duration::days(hot_data_days as i64);
+    *cutoff.write().await = new_cutoff;
+
+    let duration = started.elapsed();
+    let mut stats = stats.write().await;
+    stats.last_refresh_time = Some(Utc::now());
+    stats.last_refresh_count = count;
+    stats.last_refresh_duration = duration;
+    stats.refresh_count += 1;
+
+    tracing::info!(
+        vectors = count,
+        duration_ms = duration.as_millis() as u64,
+        cutoff = %new_cutoff,
+        refreshes = stats.refresh_count,
+        "Refreshed tiered hot index"
+    );
+
+    Ok(())
+}
+
+/// Vectors with their entity ids.
+type IdVectors = Vec<(Uuid, Vec<f32>)>;
+
+/// Split recent vectors into those that fit the hot memory budget and the rest.
+///
+/// The newest vectors are kept hot; with no budget, all of them are.
+fn split_by_budget(
+    mut vectors: Vec<(Uuid, Vec<f32>, DateTime<Utc>)>,
+    dimensions: usize,
+    max_hot_memory_bytes: Option<usize>,
+) -> (IdVectors, IdVectors) {
+    let capacity = max_hot_memory_bytes
+        .map(|budget| budget / hot_vector_bytes(dimensions))
+        .unwrap_or(usize::MAX);
+
+    if vectors.len() > capacity {
+        vectors.sort_by_key(|(_, _, created_at)| std::cmp::Reverse(*created_at));
+    }
+
+    let mut hot: IdVectors = vectors.into_iter().map(|(id, vec, _)| (id, vec)).collect();
+    let demoted = hot.split_off(capacity.min(hot.len()));
+    (hot, demoted)
+}
+
+/// Build a cold DiskANN index and memory-map it.
+///
+/// Vectors are L2-normalized first, so DiskANN's L2 distance ranks by
+/// cosine and converts with `l2_to_cosine_distance`. `max_degree` is
+/// capped below the vector count so small cold tiers (e.g. just demoted
+/// vectors) still build.
+fn build_cold(data: IdVectors) -> Result<(MmapIndex, PathBuf)> {
+    use crate::index::diskann::BuildParams;
+
+    let data: IdVectors = data
+        .into_iter()
+        .map(|(id, mut vector)| {
+            l2_normalize(&mut vector);
+            (id, vector)
+        })
+        .collect();
+
+    let vectors: Vec<Vec<f32>> = data.iter().map(|(_, v)| v.clone()).collect();
+
+    let defaults = BuildParams::default();
+    let params = BuildParams {
+        max_degree: defaults.max_degree.min(data.len().saturating_sub(1)).max(1),
+        ..defaults
+    };
+    let diskann = DiskANNIndex::build(data, params)?;
+
+    // Save to disk (temp path for now - should be configurable)
+    let cold_path = std::env::temp_dir().join(format!("tiered_cold_{}.diskann", Uuid::new_v4()));
+    let path_str = cold_path.to_str().ok_or_else(|| {
+        DatabaseError::SearchError(format!("Invalid cold index path: {}", cold_path.display()))
+    })?;
+    diskann.save(path_str, &vectors)?;
+
+    // Load as memory-mapped index for search
+    Ok((MmapIndex::load(path_str)?, cold_path))
+}
+
+/// Enforce the hot memory budget before a refresh.
+///
+/// Vectors that don't fit are merged into the cold tier (replacing any
+/// earlier copy with the same id), which is rebuilt and swapped in.
+///
+/// # Returns
+///
+/// Vectors to rebuild the hot index with
+async fn rebalance(
+    cold: &RwLock<Option<MmapIndex>>,
+    cold_path: &RwLock<Option<PathBuf>>,
+    dimensions: usize,
+    max_hot_memory_bytes: Option<usize>,
+    recent_vectors: Vec<(Uuid, Vec<f32>, DateTime<Utc>)>,
+) -> Result<IdVectors> {
+    let (hot_vectors, demoted) = split_by_budget(recent_vectors, dimensions, max_hot_memory_bytes);
+    if demoted.is_empty() {
+        return Ok(hot_vectors);
+    }
+
+    let demoted_count = demoted.len();
+    let demoted_ids: HashSet<Uuid> = demoted.iter().map(|(id, _)| *id).collect();
+
+    let mut cold_data: IdVectors = match cold.read().await.as_ref() {
+        Some(mmap) => (0..mmap.graph().num_nodes as u32)
+            .map(|node| (mmap.uuid(node), mmap.vector(node).to_vec()))
+            .filter(|(id, _)| !demoted_ids.contains(id))
+            .collect(),
+        None => Vec::new(),
+    };
+    cold_data.extend(demoted);
+    let cold_count = cold_data.len();
+
+    let (mmap_index, path) = build_cold(cold_data)?;
+    *cold.write().await = Some(mmap_index);
+    *cold_path.write().await = Some(path);
+
+    tracing::info!(
+        demoted = demoted_count,
+        hot = hot_vectors.len(),
+        cold = cold_count,
+        "Demoted hot vectors over the memory budget to the cold tier"
+    );
+
+    Ok(hot_vectors)
+}
+
 /// Merge results from hot and cold indexes.
 ///
-/// Combines results and selects top K by score.
+/// Combines results and selects top K by score. Both tiers must already
+/// report scores in the same convention.
 ///
 /// # Arguments
 ///
 /// * `hot` - Results from HNSW (hot) index
 /// * `cold` - Results from DiskANN (cold) index
 /// * `top_k` - Number of results to return
+/// * `order` - Whether smaller (distance) or larger (similarity) is better
 ///
 /// # Returns
 ///
-/// Top K results sorted by score (ascending distance)
+/// Top K results, best first
 fn merge_results(
     hot: Vec<(Uuid, f32)>,
     cold: Vec<(Uuid, f32)>,
     top_k: usize,
+    order: ScoreOrder,
 ) -> Vec<(Uuid, f32)> {
-    use std::collections::BinaryHeap;
-    use std::cmp::Ordering;
-
-    #[derive(PartialEq)]
-    struct OrderedResult(Uuid, f32);
-
-    impl Eq for OrderedResult {}
-
-    impl Ord for OrderedResult {
-        fn cmp(&self, other: &Self) -> Ordering {
-            // Normal ordering for ascending sort (smaller distance = better)
-            self.1.partial_cmp(&other.1).unwrap_or(Ordering::Equal)
-        }
-    }
-
-    impl PartialOrd for OrderedResult {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            Some(self.cmp(other))
-        }
-    }
-
-    let mut heap = BinaryHeap::new();
-
-    // Add all results to heap
-    for (id, score) in hot.into_iter().chain(cold.into_iter()) {
-        heap.push(OrderedResult(id, score));
-    }
-
-    // Take top K
-    heap.into_sorted_vec()
-        .into_iter()
-        .take(top_k)
-        .map(|OrderedResult(id, score)| (id, score))
-        .collect()
+    let mut merged: Vec<(Uuid, f32)> = hot.into_iter().chain(cold).collect();
+    merged.sort_by(|a, b| order.compare(a.1, b.1));
+    merged.truncate(top_k);
+    merged
 }
 
 #[cfg(test)]
@@ -463,7 +857,7 @@ mod tests {
             (Uuid::new_v4(), 0.4),
         ];
 
-        let merged = merge_results(hot, cold, 3);
+        let merged = merge_results(hot, cold, 3, ScoreOrder::AscendingDistance);
 
         assert_eq!(merged.len(), 3);
         assert_eq!(merged[0].1, 0.1);  // Best score
@@ -471,6 +865,29 @@ mod tests {
         assert_eq!(merged[2].1, 0.3);
     }
 
+    #[test]
+    fn test_merge_results_descending_similarity() {
+        let best = Uuid::new_v4();
+        let hot = vec![(Uuid::new_v4(), 0.7), (Uuid::new_v4(), 0.2)];
+        let cold = vec![(best, 0.95), (Uuid::new_v4(), 0.5)];
+
+        let merged = merge_results(hot, cold, 3, ScoreOrder::DescendingSimilarity);
+
+        assert_eq!(merged[0], (best, 0.95));
+        assert_eq!(merged.iter().map(|r| r.1).collect::<Vec<_>>(), vec![0.95, 0.7, 0.5]);
+    }
+
+    #[test]
+    fn test_tiers_share_score_scale() {
+        // Unit vectors at 60°: cosine distance 0.5, L2 distance 1.0
+        assert!((l2_to_cosine_distance(1.0) - 0.5).abs() < 1e-6);
+
+        let order = ScoreOrder::DescendingSimilarity;
+        assert_eq!(order.from_cosine_distance(0.5), 0.5);
+        assert_eq!(order.from_cosine_distance(0.0), 1.0);
+        assert_eq!(ScoreOrder::AscendingDistance.from_cosine_distance(0.25), 0.25);
+    }
+
     #[test]
     fn test_merge_results_empty_hot() {
         let hot = vec![];
@@ -479,7 +896,7 @@ mod tests {
             (Uuid::new_v4(), 0.4),
         ];
 
-        let merged = merge_results(hot, cold, 5);
+        let merged = merge_results(hot, cold, 5, ScoreOrder::AscendingDistance);
 
         assert_eq!(merged.len(), 2);
     }
@@ -492,11 +909,42 @@ mod tests {
         ];
         let cold = vec![];
 
-        let merged = merge_results(hot, cold, 5);
+        let merged = merge_results(hot, cold, 5, ScoreOrder::AscendingDistance);
 
         assert_eq!(merged.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_cold_tier_ranks_by_cosine() {
+        let config = TieredSearchConfig {
+            auto_refresh: false,
+            ..TieredSearchConfig::default()
+        };
+        let mut index = TieredIndex::new(config, 3);
+
+        // Same direction as the query but long (far in L2), versus a short
+        // vector that is close in L2 but 45° off
+        let now = Utc::now();
+        let aligned = Uuid::new_v4();
+        let decoy = Uuid::new_v4();
+        let mut vectors = vec![
+            (aligned, vec![10.0, 0.0, 0.0], now - Duration::days(60)),
+            (decoy, vec![0.7, 0.7, 0.0], now - Duration::days(60)),
+        ];
+        for i in 0..68 {
+            let angle = i as f32 * 0.09;
+            vectors.push((Uuid::new_v4(), vec![0.0, angle.sin(), angle.cos()], now - Duration::days(90)));
+        }
+        index.build(vectors).await.unwrap();
+        assert_eq!(index.cold_size().await, 70);
+
+        let results = index.search(&[2.0, 0.0, 0.0], 2).await.unwrap();
+        assert_eq!(results[0].0, aligned);
+        assert!(results[0].1 < 0.01, "{:?}", results);
+        assert_eq!(results[1].0, decoy);
+        assert!((results[1].1 - (1.0 - std::f32::consts::FRAC_1_SQRT_2)).abs() < 0.01, "{:?}", results);
+    }
+
     #[tokio::test]
     async fn test_tiered_index_creation() {
         let config = TieredSearchConfig::default();
@@ -515,6 +963,8 @@ mod tests {
             max_hot_vectors: 100,
             auto_refresh: true,
             refresh_interval_secs: 1,  // 1 second for fast test
+            max_hot_memory_bytes: None,
+            score_order: ScoreOrder::AscendingDistance,
         };
 
         let mut index = TieredIndex::new(config, 3);
@@ -531,8 +981,8 @@ mod tests {
 
                 // Return sample vectors
                 Ok(vec![
-                    (Uuid::new_v4(), vec![1.0, 0.0, 0.0]),
-                    (Uuid::new_v4(), vec![0.0, 1.0, 0.0]),
+                    (Uuid::new_v4(), vec![1.0, 0.0, 0.0], Utc::now()),
+                    (Uuid::new_v4(), vec![0.0, 1.0, 0.0], Utc::now()),
                 ])
             }
         }).await;
@@ -551,6 +1001,46 @@ mod tests {
         index.stop_refresh_task();
     }
 
+    #[tokio::test]
+    async fn test_refresh_stats() {
+        let config = TieredSearchConfig {
+            hot_data_days: 30,
+            max_hot_vectors: 100,
+            auto_refresh: true,
+            refresh_interval_secs: 1,
+            max_hot_memory_bytes: None,
+            score_order: ScoreOrder::AscendingDistance,
+        };
+
+        let mut index = TieredIndex::new(config, 3);
+        assert_eq!(index.refresh_stats().await, RefreshStats::default());
+
+        let started = Utc::now();
+        index.start_refresh_task(|| async {
+            Ok(vec![
+                (Uuid::new_v4(), vec![1.0, 0.0, 0.0], Utc::now()),
+                (Uuid::new_v4(), vec![0.0, 1.0, 0.0], Utc::now()),
+                (Uuid::new_v4(), vec![0.0, 0.0, 1.0], Utc::now()),
+            ])
+        }).await;
+
+        // First tick fires immediately, second after 1s
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        index.stop_refresh_task();
+
+        let stats = index.refresh_stats().await;
+        assert!(stats.refresh_count >= 2, "Expected at least 2 refreshes, got {}", stats.refresh_count);
+        assert_eq!(stats.last_refresh_count, 3);
+        let last_refresh = stats.last_refresh_time.unwrap();
+        assert!(last_refresh >= started && last_refresh <= Utc::now());
+
+        // Manual refreshes are tracked too
+        index.refresh_hot_index(vec![(Uuid::new_v4(), vec![1.0, 1.0, 0.0], Utc::now())]).await.unwrap();
+        let stats = index.refresh_stats().await;
+        assert_eq!(stats.last_refresh_count, 1);
+        assert!(stats.last_refresh_time.unwrap() >= last_refresh);
+    }
+
     #[tokio::test]
     async fn test_refresh_task_disabled() {
         let config = TieredSearchConfig {
@@ -581,6 +1071,8 @@ mod tests {
             max_hot_vectors: 100,
             auto_refresh: false,
             refresh_interval_secs: 3600,
+            max_hot_memory_bytes: None,
+            score_order: ScoreOrder::AscendingDistance,
         };
 
         let mut index = TieredIndex::new(config, 3);
@@ -686,6 +1178,8 @@ mod tests {
             max_hot_vectors: 100,
             auto_refresh: false,
             refresh_interval_secs: 3600,
+            max_hot_memory_bytes: None,
+            score_order: ScoreOrder::AscendingDistance,
         };
 
         let mut index = TieredIndex::new(config, 3);
@@ -743,4 +1237,111 @@ mod tests {
         println!("✅ Cold index UUID persistence test passed!");
         println!("   - Found exact match (uuid1): {:?}", found_uuid1);
     }
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let config = TieredSearchConfig {
+            hot_data_days: 30,
+            max_hot_vectors: 100,
+            auto_refresh: false,
+            refresh_interval_secs: 3600,
+            max_hot_memory_bytes: None,
+            score_order: ScoreOrder::AscendingDistance,
+        };
+
+        let now = Utc::now();
+        let vectors: Vec<_> = (0..80)
+            .map(|i| {
+                let vec = vec![(i as f32 * 0.1).sin(), (i as f32 * 0.2).cos(), (i as f32 * 0.3).sin()];
+                // 10 hot, 70 cold (DiskANN needs at least 65)
+                let created_at = if i < 10 { now - Duration::days(i) } else { now - Duration::days(60 + i) };
+                (Uuid::new_v4(), vec, created_at)
+            })
+            .collect();
+
+        let mut index = TieredIndex::new(config.clone(), 3);
+        index.build(vectors.clone()).await.unwrap();
+        let saved_cutoff = index.get_cutoff().await;
+
+        let dir = tempfile::tempdir().unwrap();
+        index.save(dir.path()).await.unwrap();
+
+        let loaded = TieredIndex::load(dir.path(), config.clone()).unwrap();
+        assert_eq!(loaded.hot_size(), 10);
+        assert_eq!(loaded.hot_size(), index.hot_size());
+        assert_eq!(loaded.cold_size().await, index.cold_size().await);
+
+        for (_, query, _) in vectors.iter().step_by(7) {
+            assert_eq!(
+                loaded.search(query, 5).await.unwrap(),
+                index.search(query, 5).await.unwrap()
+            );
+        }
+
+        // A wider window keeps the saved cutoff
+        let wider = TieredSearchConfig { hot_data_days: 365, ..config.clone() };
+        assert_eq!(TieredIndex::load(dir.path(), wider).unwrap().get_cutoff().await, saved_cutoff);
+
+        // A stale saved cutoff moves up to now - hot_data_days
+        let manifest_path = dir.path().join(MANIFEST_FILE);
+        let mut manifest: TieredManifest = serde_json::from_slice(&std::fs::read(&manifest_path).unwrap()).unwrap();
+        manifest.cutoff = now - Duration::days(90);
+        std::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+
+        let before_load = Utc::now();
+        let cutoff = TieredIndex::load(dir.path(), config).unwrap().get_cutoff().await;
+        assert!(cutoff >= before_load - Duration::days(30));
+        assert!(cutoff <= Utc::now() - Duration::days(30));
+    }
+
+    #[tokio::test]
+    async fn test_hot_memory_budget() {
+        let budget = 10 * hot_vector_bytes(3);
+        let config = TieredSearchConfig {
+            hot_data_days: 30,
+            max_hot_vectors: 100,
+            auto_refresh: false,
+            refresh_interval_secs: 3600,
+            max_hot_memory_bytes: Some(budget),
+            score_order: ScoreOrder::AscendingDistance,
+        };
+
+        // Well-spread unit vectors, all recent (index = minutes old)
+        let now = Utc::now();
+        let point = |i: usize| {
+            let (a, b) = (i as f32 * 0.37, (i as f32 * 0.13).sin());
+            vec![a.cos() * b.cos(), a.sin() * b.cos(), b.sin()]
+        };
+        let vectors: Vec<_> = (0..80)
+            .map(|i| (Uuid::new_v4(), point(i), now - Duration::minutes(i as i64)))
+            .collect();
+
+        let mut index = TieredIndex::new(config, 3);
+        index.build(vectors.clone()).await.unwrap();
+
+        // Only the newest fit; the rest were demoted despite being recent
+        assert_eq!(index.hot_size(), 10);
+        assert!(index.hot_memory_bytes() <= budget);
+        assert_eq!(index.cold_size().await, 70);
+
+        let (oldest, query, _) = &vectors[79];
+        let results = index.search(query, 5).await.unwrap();
+        assert!(results.iter().any(|(id, _)| id == oldest), "demoted vector not searchable");
+
+        // Refresh with a burst of 30 newer vectors: 20 are demoted
+        let burst: Vec<_> = (80..110)
+            .map(|i| (Uuid::new_v4(), point(i), Utc::now() + Duration::seconds(i as i64)))
+            .collect();
+        index.refresh_hot_index(burst.clone()).await.unwrap();
+
+        assert_eq!(index.hot_size(), 10);
+        assert!(index.hot_memory_bytes() <= budget);
+        assert_eq!(index.cold_size().await, 90);
+
+        // Oldest of the burst went cold, newest stayed hot
+        for (id, query, _) in [&burst[0], &burst[29]] {
+            let results = index.search(query, 5).await.unwrap();
+            assert!(results.iter().any(|(hit, _)| hit == id), "vector lost after rebalance");
+        }
+    }
 }
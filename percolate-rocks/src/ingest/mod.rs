@@ -2,8 +2,10 @@
 
 pub mod chunker;
 pub mod pdf;
+pub mod pipeline;
 pub mod text;
 
 pub use chunker::{Chunker, ChunkStrategy};
 pub use pdf::PdfParser;
+pub use pipeline::{IngestPipeline, IngestStats};
 pub use text::TextChunker;
@@ -0,0 +1,241 @@
+//! Pipelined ingestion with a bounded embedding queue.
+//!
+//! A producer reads files and chunks them onto a bounded channel; a pool of
+//! workers drains the channel, embeds each chunk, and hands the result back
+//! to the caller for storage. When workers fall behind, the producer blocks
+//! on the full channel, so memory stays bounded by `queue_depth`.
+//!
+//! ```text
+//! files → [producer] → chunk queue (bounded) → [worker × N] → embedded queue → insert
+//! ```
+
+use crate::embeddings::truncate::{embed_within_limit, OverflowPolicy, Truncation};
+use crate::embeddings::EmbeddingProvider;
+use crate::ingest::TextChunker;
+use crate::types::{DatabaseError, Result};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+/// Maximum chunk size in characters.
+pub const DEFAULT_CHUNK_SIZE: usize = 2000;
+
+/// Chunk awaiting embedding.
+#[derive(Debug, Clone)]
+pub struct ChunkJob {
+    /// Source file path
+    pub path: PathBuf,
+    /// Chunk position within the file
+    pub ordinal: usize,
+    /// Chunk text
+    pub content: String,
+}
+
+/// Chunk with its embedding, ready to store.
+#[derive(Debug, Clone)]
+pub struct EmbeddedChunk {
+    pub job: ChunkJob,
+    pub embedding: Vec<f32>,
+    /// Set if the content was cut to fit the model's context window
+    pub truncation: Option<Truncation>,
+}
+
+impl EmbeddedChunk {
+    /// Build entity properties for this chunk.
+    ///
+    /// Mirrors the single-file ingest layout (`uri` + `chunk_ordinal` give a
+    /// deterministic UUID, so re-ingesting a file upserts its chunks).
+    /// Truncated chunks carry `embedding_truncation` metadata; `content`
+    /// is always stored in full.
+    pub fn to_properties(&self) -> serde_json::Value {
+        let file_name = self.job.path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+
+        let mut props = serde_json::json!({
+            "name": format!("{} (chunk {})", file_name, self.job.ordinal),
+            "content": self.job.content,
+            "uri": format!("file://{}", self.job.path.display()),
+            "chunk_ordinal": self.job.ordinal,
+            "embedding": self.embedding,
+        });
+
+        if let Some(truncation) = &self.truncation {
+            props["embedding_truncation"] = truncation.to_metadata();
+        }
+
+        props
+    }
+}
+
+/// Ingestion statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IngestStats {
+    /// Files read
+    pub files: usize,
+    /// Chunks embedded and stored
+    pub chunks: usize,
+    /// Highest chunk queue occupancy observed
+    pub max_queue_depth: usize,
+}
+
+/// Chunk queue occupancy tracker.
+///
+/// Occupancy is read from the channel itself (`max_capacity - capacity`)
+/// after every send, so it counts queued chunks plus reserved slots.
+#[derive(Debug, Default)]
+pub struct QueueGauge {
+    max_depth: AtomicUsize,
+    enqueued: AtomicUsize,
+}
+
+impl QueueGauge {
+    fn observe(&self, tx: &mpsc::Sender<ChunkJob>) {
+        let depth = tx.max_capacity() - tx.capacity();
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+        self.enqueued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Highest occupancy observed.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    /// Total chunks enqueued.
+    pub fn enqueued(&self) -> usize {
+        self.enqueued.load(Ordering::Relaxed)
+    }
+}
+
+/// Running pipeline: producer and worker tasks plus the embedded-chunk stream.
+pub struct IngestPipeline {
+    /// Embedded chunks in completion order
+    pub results: mpsc::Receiver<Result<EmbeddedChunk>>,
+    /// Queue occupancy tracker
+    pub gauge: Arc<QueueGauge>,
+    producer: JoinHandle<Result<usize>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl IngestPipeline {
+    /// Spawn producer and embedding workers.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Files to ingest
+    /// * `provider` - Embedding provider shared by all workers
+    /// * `overflow` - Handling of chunks over the provider's token limit
+    /// * `workers` - Number of embedding workers (minimum 1)
+    /// * `queue_depth` - Chunk queue bound (minimum 1)
+    ///
+    /// # Returns
+    ///
+    /// Running `IngestPipeline`; drain `results` then call `finish`
+    pub fn spawn(
+        paths: Vec<PathBuf>,
+        provider: Arc<dyn EmbeddingProvider>,
+        overflow: OverflowPolicy,
+        workers: usize,
+        queue_depth: usize,
+    ) -> Self {
+        let queue_depth = queue_depth.max(1);
+        let (chunk_tx, chunk_rx) = mpsc::channel::<ChunkJob>(queue_depth);
+        let (result_tx, results) = mpsc::channel(queue_depth);
+        let gauge = Arc::new(QueueGauge::default());
+
+        let producer = tokio::spawn(produce(paths, chunk_tx, gauge.clone()));
+
+        let chunk_rx = Arc::new(Mutex::new(chunk_rx));
+        let workers = (0..workers.max(1))
+            .map(|_| tokio::spawn(embed_worker(chunk_rx.clone(), result_tx.clone(), provider.clone(), overflow)))
+            .collect();
+
+        Self { results, gauge, producer, workers }
+    }
+
+    /// Wait for all tasks to exit.
+    ///
+    /// # Returns
+    ///
+    /// Number of files read
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::IngestError` if a file could not be read or a task panicked
+    pub async fn finish(self) -> Result<usize> {
+        // Dropping the receiver unblocks workers if the caller stopped early
+        drop(self.results);
+
+        for worker in self.workers {
+            worker.await
+                .map_err(|e| DatabaseError::IngestError(format!("Embedding worker failed: {}", e)))?;
+        }
+
+        self.producer.await
+            .map_err(|e| DatabaseError::IngestError(format!("Ingest producer failed: {}", e)))?
+    }
+}
+
+/// Read and chunk each file, blocking while the queue is full.
+///
+/// Markdown files (`.md`, `.markdown`) are chunked by section.
+async fn produce(
+    paths: Vec<PathBuf>,
+    tx: mpsc::Sender<ChunkJob>,
+    gauge: Arc<QueueGauge>,
+) -> Result<usize> {
+    let mut files = 0;
+
+    for path in paths {
+        let content = tokio::fs::read_to_string(&path).await
+            .map_err(|e| DatabaseError::IngestError(format!("Failed to read {}: {}", path.display(), e)))?;
+        files += 1;
+
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"));
+        let chunks = if is_markdown {
+            TextChunker::split_markdown(&content, DEFAULT_CHUNK_SIZE)
+        } else {
+            TextChunker::split_paragraphs(&content, DEFAULT_CHUNK_SIZE)
+        };
+
+        for (ordinal, chunk) in chunks.into_iter().enumerate() {
+            let job = ChunkJob { path: path.clone(), ordinal, content: chunk };
+            if tx.send(job).await.is_err() {
+                // Workers are gone (consumer stopped early)
+                return Ok(files);
+            }
+            gauge.observe(&tx);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Embed chunks until the queue closes.
+async fn embed_worker(
+    rx: Arc<Mutex<mpsc::Receiver<ChunkJob>>>,
+    tx: mpsc::Sender<Result<EmbeddedChunk>>,
+    provider: Arc<dyn EmbeddingProvider>,
+    overflow: OverflowPolicy,
+) {
+    loop {
+        // Hold the lock only for the receive, not the embedding call
+        let job = match rx.lock().await.recv().await {
+            Some(job) => job,
+            None => return,
+        };
+
+        let result = embed_within_limit(&*provider, &job.content, overflow).await
+            .map(|(embedding, truncation)| EmbeddedChunk { job, embedding, truncation });
+
+        if tx.send(result).await.is_err() {
+            return;
+        }
+    }
+}
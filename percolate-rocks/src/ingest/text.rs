@@ -7,6 +7,10 @@ pub struct TextChunker;
 impl TextChunker {
     /// Split text into chunks at paragraph boundaries.
     ///
+    /// A paragraph longer than `max_size` is split at sentence ends, and a
+    /// sentence longer than `max_size` at the last whitespace that fits (or
+    /// mid-word if there is none), so no chunk exceeds `max_size`.
+    ///
     /// # Arguments
     ///
     /// * `text` - Input text
@@ -16,7 +20,92 @@ impl TextChunker {
     ///
     /// Vector of text chunks
     pub fn split_paragraphs(text: &str, max_size: usize) -> Vec<String> {
-        todo!("Implement TextChunker::split_paragraphs")
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+
+        for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+            if paragraph.len() > max_size {
+                if !current.is_empty() {
+                    chunks.push(std::mem::take(&mut current));
+                }
+                chunks.extend(split_oversized(paragraph, max_size));
+                continue;
+            }
+
+            // Start a new chunk if this paragraph would push us past max_size
+            if !current.is_empty() && current.len() + paragraph.len() + 2 > max_size {
+                chunks.push(std::mem::take(&mut current));
+            }
+
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(paragraph);
+        }
+
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+
+        chunks
+    }
+
+    /// Split markdown into chunks that follow its structure.
+    ///
+    /// Headings (`#` to `######`) always start a new chunk, so a chunk never
+    /// spans two sections. A section longer than `max_size` is packed by
+    /// paragraph like `split_paragraphs` (oversized paragraphs are split
+    /// the same way), with each fenced code block
+    /// (```` ``` ```` or `~~~`) kept whole: a fence is never split, even if
+    /// it alone exceeds `max_size`. `#` lines inside fences are not headings.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Markdown text
+    /// * `max_size` - Maximum chunk size (characters) within a section
+    ///
+    /// # Returns
+    ///
+    /// Vector of text chunks, in document order
+    pub fn split_markdown(text: &str, max_size: usize) -> Vec<String> {
+        let mut chunks = Vec::new();
+
+        for section in markdown_sections(text) {
+            let section = section.trim();
+            if section.is_empty() {
+                continue;
+            }
+            if section.len() <= max_size {
+                chunks.push(section.to_string());
+                continue;
+            }
+
+            let mut current = String::new();
+            for block in markdown_blocks(section) {
+                if block.len() > max_size && fence_marker(&block).is_none() {
+                    if !current.is_empty() {
+                        chunks.push(std::mem::take(&mut current));
+                    }
+                    chunks.extend(split_oversized(&block, max_size));
+                    continue;
+                }
+
+                if !current.is_empty() && current.len() + block.len() + 2 > max_size {
+                    chunks.push(std::mem::take(&mut current));
+                }
+
+                if !current.is_empty() {
+                    current.push_str("\n\n");
+                }
+                current.push_str(&block);
+            }
+
+            if !current.is_empty() {
+                chunks.push(current);
+            }
+        }
+
+        chunks
     }
 
     /// Split text with overlap for context preservation.
@@ -44,6 +133,246 @@ impl TextChunker {
     ///
     /// Estimated token count
     pub fn estimate_tokens(text: &str) -> usize {
-        todo!("Implement TextChunker::estimate_tokens")
+        // ~4 characters per token for English text
+        text.chars().count().div_ceil(4)
+    }
+}
+
+/// Split a paragraph longer than `max_size` into pieces that fit.
+///
+/// Whole sentences are packed together where they fit; longer sentences
+/// are cut by `hard_split`.
+fn split_oversized(paragraph: &str, max_size: usize) -> Vec<String> {
+    let max_size = max_size.max(1);
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for sentence in sentences(paragraph) {
+        for part in hard_split(sentence, max_size) {
+            if !current.is_empty() && current.len() + part.len() + 1 > max_size {
+                pieces.push(std::mem::take(&mut current));
+            }
+
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(part);
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// Sentences of a paragraph, ending at `.`, `!` or `?` followed by whitespace.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let at_end = matches!(c, '.' | '!' | '?')
+            && chars.peek().is_some_and(|(_, next)| next.is_whitespace());
+        if at_end {
+            let end = i + c.len_utf8();
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// Cut text into pieces of at most `max_size` bytes.
+///
+/// Cuts at the last whitespace before the limit, or at the last character
+/// boundary if there is none.
+fn hard_split(text: &str, max_size: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+
+    while rest.len() > max_size {
+        let mut cut = max_size;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        if cut == 0 {
+            // A single character wider than max_size
+            cut = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        } else if !rest[cut..].starts_with(char::is_whitespace) {
+            if let Some(space) = rest[..cut].rfind(char::is_whitespace).filter(|&i| i > 0) {
+                cut = space;
+            }
+        }
+
+        let piece = rest[..cut].trim_end();
+        if !piece.is_empty() {
+            pieces.push(piece);
+        }
+        rest = rest[cut..].trim_start();
+    }
+
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+/// Fence marker opening a fenced code block on this line, if any.
+fn fence_marker(line: &str) -> Option<&'static str> {
+    let line = line.trim_start();
+    if line.starts_with("```") {
+        Some("```")
+    } else if line.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Whether a line is an ATX heading (`# Title` through `###### Title`).
+fn is_heading(line: &str) -> bool {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&level) && matches!(line[level..].chars().next(), None | Some(' ' | '\t'))
+}
+
+/// Split markdown at headings outside code fences.
+fn markdown_sections(text: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    let mut fence: Option<&str> = None;
+
+    for line in text.lines() {
+        match fence {
+            Some(marker) => {
+                if line.trim_start().starts_with(marker) {
+                    fence = None;
+                }
+            }
+            None => {
+                if is_heading(line) && !current.trim().is_empty() {
+                    sections.push(std::mem::take(&mut current));
+                }
+                fence = fence_marker(line);
+            }
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+
+    if !current.trim().is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+/// Split a section into paragraphs and whole fenced code blocks.
+fn markdown_blocks(section: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current = String::new();
+    let mut fence: Option<&str> = None;
+
+    let mut flush = |current: &mut String| {
+        let block = current.trim_end();
+        if !block.is_empty() {
+            blocks.push(block.to_string());
+        }
+        current.clear();
+    };
+
+    for line in section.lines() {
+        match fence {
+            Some(marker) => {
+                current.push_str(line);
+                current.push('\n');
+                if line.trim_start().starts_with(marker) {
+                    fence = None;
+                    flush(&mut current);
+                }
+            }
+            None if line.trim().is_empty() => flush(&mut current),
+            None => {
+                if let Some(marker) = fence_marker(line) {
+                    flush(&mut current);
+                    fence = Some(marker);
+                }
+                current.push_str(line);
+                current.push('\n');
+            }
+        }
+    }
+
+    flush(&mut current);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_paragraphs() {
+        let text = "alpha\n\nbeta\n\n\n\ngamma";
+
+        assert_eq!(TextChunker::split_paragraphs(text, 100), vec!["alpha\n\nbeta\n\ngamma"]);
+        assert_eq!(TextChunker::split_paragraphs(text, 12), vec!["alpha\n\nbeta", "gamma"]);
+        assert!(TextChunker::split_paragraphs("  \n\n ", 100).is_empty());
+    }
+
+    #[test]
+    fn test_split_paragraphs_oversized() {
+        let text = "intro\n\nOne two three. Four five six! Seven eight nine ten eleven twelve.";
+        let chunks = TextChunker::split_paragraphs(text, 20);
+        assert_eq!(
+            chunks,
+            vec!["intro", "One two three.", "Four five six!", "Seven eight nine ten", "eleven twelve."]
+        );
+
+        // No whitespace: cut at the limit
+        let chunks = TextChunker::split_paragraphs(&"x".repeat(45), 20);
+        assert_eq!(chunks.iter().map(|c| c.len()).collect::<Vec<_>>(), vec![20, 20, 5]);
+
+        // Multi-byte text is cut on character boundaries
+        let chunks = TextChunker::split_paragraphs(&"é".repeat(15), 7);
+        assert!(chunks.iter().all(|c| c.len() <= 7));
+        assert_eq!(chunks.concat(), "é".repeat(15));
+    }
+
+    #[test]
+    fn test_split_markdown() {
+        let text = "# Intro\n\nShort intro.\n\n\
+## Setup\n\nInstall the toolchain first.\n\n\
+```bash\ncargo build\n\n# not a heading\ncargo test\n```\n\nThen run it.\n\n\
+## Usage\n\nCall the API.\n";
+
+        let chunks = TextChunker::split_markdown(text, 1000);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with("# Intro"));
+        assert!(chunks[1].starts_with("## Setup") && chunks[1].contains("# not a heading"));
+        assert!(chunks[2].starts_with("## Usage"));
+
+        // Oversized section: packed by paragraph, fence kept whole
+        let fence = "```bash\ncargo build\n\n# not a heading\ncargo test\n```";
+        let chunks = TextChunker::split_markdown(text, 40);
+        assert!(chunks.contains(&fence.to_string()), "{:?}", chunks);
+        assert!(chunks.iter().filter(|c| c.contains("```")).all(|c| c.matches("```").count() == 2));
+        assert_eq!(chunks.iter().filter(|c| c.starts_with('#')).count(), 3);
+        assert_eq!(chunks.last().unwrap(), "## Usage\n\nCall the API.");
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(TextChunker::estimate_tokens(""), 0);
+        assert_eq!(TextChunker::estimate_tokens("abcd"), 1);
+        assert_eq!(TextChunker::estimate_tokens("abcde"), 2);
     }
 }
@@ -50,10 +50,17 @@ pub mod crypto;
 pub mod dreaming;
 pub mod otel;
 pub mod agents;  // Lightweight agent runtime for background indexing
+pub mod http;  // Timeouts and cancellation for embedding/LLM API calls
 
 // High-level database API
 pub mod database;
 
+// Queries fanned out across several databases
+pub mod federation;
+
+// Entity change notifications (pub/sub)
+pub mod changes;
+
 // Database administration operations
 pub mod admin;
 
@@ -0,0 +1,129 @@
+//! Planning and executing natural language questions.
+//!
+//! `Database::ask_batch` answers many questions at once (e.g. evaluating a
+//! test set). Identical questions are planned once, and planning (the LLM
+//! call) runs with bounded concurrency. Plans then execute against the
+//! database in question order.
+//!
+//! Planning goes through the `QueryPlanner` trait so tests and callers can
+//! substitute a planner that doesn't call an LLM.
+
+use crate::database::Database;
+use crate::llm::planner::{FallbackTrigger, QueryPlan};
+use crate::llm::query_builder::LlmQueryBuilder;
+use crate::types::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Default number of questions planned concurrently by `ask_batch`.
+pub const DEFAULT_ASK_CONCURRENCY: usize = 4;
+
+/// Turns a natural language question into a query plan.
+#[async_trait]
+pub trait QueryPlanner: Send + Sync {
+    /// Plan a question.
+    ///
+    /// # Arguments
+    ///
+    /// * `question` - Natural language question
+    /// * `schema_context` - Schema information
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if planning fails
+    async fn plan_query(&self, question: &str, schema_context: &str) -> Result<QueryPlan>;
+}
+
+#[async_trait]
+impl QueryPlanner for LlmQueryBuilder {
+    async fn plan_query(&self, question: &str, schema_context: &str) -> Result<QueryPlan> {
+        LlmQueryBuilder::plan_query(self, question, schema_context).await
+    }
+}
+
+/// Answer to one question of a batch.
+#[derive(Debug, Clone)]
+pub struct AskAnswer {
+    /// Question as asked
+    pub question: String,
+    /// Plan used (`None` if planning failed)
+    pub plan: Option<QueryPlan>,
+    /// Results of the last executed stage
+    pub results: Vec<Value>,
+    /// Planning or execution error
+    pub error: Option<String>,
+}
+
+/// Execute a plan, running fallbacks as their triggers fire.
+///
+/// Stages follow `explain_plan`: execution stops at the first stage that
+/// returns results, and the last stage's outcome is returned.
+///
+/// # Errors
+///
+/// Returns the last stage's error if it failed
+pub async fn execute_plan(db: &Database, tenant_id: &str, plan: &QueryPlan) -> Result<Vec<Value>> {
+    let mut outcome = execute_query(db, tenant_id, &plan.primary_query.query_string).await;
+
+    for fallback in &plan.fallback_queries {
+        let fires = match (&outcome, &fallback.trigger) {
+            (Err(_), FallbackTrigger::Error) => true,
+            (Ok(results), FallbackTrigger::NoResults | FallbackTrigger::LowQuality) => results.is_empty(),
+            _ => false,
+        };
+        if fires {
+            outcome = execute_query(db, tenant_id, &fallback.query.query_string).await;
+            if outcome.as_ref().is_ok_and(|results| !results.is_empty()) {
+                break;
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Run an extended REM query and collect its results as JSON.
+pub(crate) async fn execute_query(db: &Database, tenant_id: &str, query: &str) -> Result<Vec<Value>> {
+    use crate::graph::TraversalDirection;
+    use crate::query::{parse_extended_query, ExtendedQuery, TraverseDirection};
+    use crate::types::DatabaseError;
+
+    match parse_extended_query(query)? {
+        ExtendedQuery::KeyLookup(lookup) => {
+            let mut results = Vec::new();
+            for key in &lookup.keys {
+                for entity in db.lookup_global(tenant_id, key)? {
+                    results.push(serde_json::to_value(&entity)?);
+                }
+            }
+            Ok(results)
+        }
+        ExtendedQuery::Search(search) => {
+            db.search(tenant_id, &search.table, &search.query, search.limit)
+                .await?
+                .iter()
+                .map(|hit| Ok(serde_json::to_value(hit)?))
+                .collect()
+        }
+        ExtendedQuery::Traverse(traverse) => {
+            let start_id = uuid::Uuid::parse_str(&traverse.start_uuid)
+                .map_err(|e| DatabaseError::QueryError(format!("Invalid start UUID: {}", e)))?;
+            let direction = match traverse.direction {
+                TraverseDirection::Out => TraversalDirection::Out,
+                TraverseDirection::In => TraversalDirection::In,
+                TraverseDirection::Both => TraversalDirection::Both,
+            };
+            Ok(db.traverse_bfs(start_id, direction, traverse.depth, traverse.rel_type.clone().map(|t| vec![t]))?
+                .nodes
+                .into_iter()
+                .map(|id| Value::String(id.to_string()))
+                .collect())
+        }
+        ExtendedQuery::Sql(sql) => {
+            match db.query_sql(tenant_id, &sql)? {
+                Value::Array(rows) => Ok(rows),
+                _ => Ok(Vec::new()),
+            }
+        }
+    }
+}
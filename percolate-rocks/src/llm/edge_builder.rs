@@ -4,7 +4,7 @@
 //! This is the E (Entities/relationships) in REM indexing.
 
 use crate::types::{Result, DatabaseError, InlineEdge};
-use crate::llm::query_builder::LlmQueryBuilder;
+use crate::llm::query_builder::{LlmOperation, LlmQueryBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
@@ -19,10 +19,54 @@ pub struct EdgePlan {
     pub summary: EdgeSummary,
 }
 
+impl EdgePlan {
+    /// Drop edges below a confidence threshold.
+    ///
+    /// Edges without a `confidence` property count as 0.0. The summary is
+    /// recomputed over the remaining edges and `filtered_edges` is
+    /// incremented by the number dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_confidence` - Minimum `confidence` property to keep an edge
+    ///
+    /// # Returns
+    ///
+    /// Number of edges dropped
+    pub fn filter_by_confidence(&mut self, min_confidence: f64) -> usize {
+        let before = self.edges.len();
+        self.edges.retain(|edge| edge.confidence().unwrap_or(0.0) >= min_confidence);
+        let filtered = before - self.edges.len();
+
+        if filtered > 0 {
+            let mut relationship_types: Vec<String> = Vec::new();
+            for edge in &self.edges {
+                if !relationship_types.contains(&edge.rel_type) {
+                    relationship_types.push(edge.rel_type.clone());
+                }
+            }
+
+            let confidences: Vec<f64> = self.edges.iter().filter_map(EdgeSpec::confidence).collect();
+            let avg_confidence = if confidences.is_empty() {
+                0.0
+            } else {
+                confidences.iter().sum::<f64>() / confidences.len() as f64
+            };
+
+            self.summary.total_edges = self.edges.len();
+            self.summary.relationship_types = relationship_types;
+            self.summary.avg_confidence = avg_confidence;
+            self.summary.filtered_edges += filtered;
+        }
+
+        filtered
+    }
+}
+
 /// Edge specification (before converting to InlineEdge).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeSpec {
-    /// Destination entity UUID
+    /// Destination entity UUID, or its name/key if the UUID is unknown
     pub dst: String,
 
     /// Relationship type
@@ -34,23 +78,41 @@ pub struct EdgeSpec {
 
     /// ISO 8601 timestamp
     pub created_at: String,
+
+    /// Existing entity `dst` resolved to (see `Database::resolve_edge_destinations`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_id: Option<uuid::Uuid>,
+
+    /// Confidence of the resolution (1.0 exact, lower for fuzzy matches)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution_confidence: Option<f64>,
 }
 
 impl EdgeSpec {
+    /// Model confidence from the `confidence` property.
+    pub fn confidence(&self) -> Option<f64> {
+        self.properties.get("confidence").and_then(|v| v.as_f64())
+    }
+
     /// Convert to InlineEdge with validation.
     ///
+    /// Uses `resolved_id` when set, otherwise `dst` must be a UUID.
+    ///
     /// # Returns
     ///
-    /// `Result<InlineEdge>` if dst is valid UUID
+    /// `Result<InlineEdge>` if dst is resolved or a valid UUID
     ///
     /// # Errors
     ///
-    /// Returns `DatabaseError::ValidationError` if dst is not valid UUID
+    /// Returns `DatabaseError::ValidationError` if dst is unresolved and not a valid UUID
     pub fn to_inline_edge(&self) -> Result<InlineEdge> {
-        let dst_uuid = uuid::Uuid::parse_str(&self.dst)
-            .map_err(|e| DatabaseError::ValidationError(
-                format!("Invalid UUID in edge dst: {}", e)
-            ))?;
+        let dst_uuid = match self.resolved_id {
+            Some(id) => id,
+            None => uuid::Uuid::parse_str(&self.dst)
+                .map_err(|e| DatabaseError::ValidationError(
+                    format!("Invalid UUID in edge dst: {}", e)
+                ))?,
+        };
 
         Ok(InlineEdge {
             dst: dst_uuid,
@@ -72,6 +134,28 @@ pub struct EdgeSummary {
 
     /// Average confidence score across all edges
     pub avg_confidence: f64,
+
+    /// Edges dropped for falling below `min_confidence`
+    #[serde(default)]
+    pub filtered_edges: usize,
+}
+
+/// What applying an `EdgePlan` to a source entity did (or would do).
+///
+/// See `Database::apply_edge_plan`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EdgePlanReport {
+    /// Nothing was written
+    pub dry_run: bool,
+
+    /// Edges new to the source entity
+    pub created: Vec<EdgeSpec>,
+
+    /// Edges whose (dst, rel_type) already exists; their properties are replaced
+    pub merged: Vec<EdgeSpec>,
+
+    /// Edges whose destination matched no entity
+    pub unresolved: Vec<EdgeSpec>,
 }
 
 /// LLM-powered edge builder.
@@ -98,15 +182,29 @@ impl LlmEdgeBuilder {
 
     /// Create from environment variables.
     ///
-    /// Uses `P8_DEFAULT_LLM` for model (default: "gpt-4-turbo")
+    /// Uses `P8_EDGE_LLM` for model, falling back to `P8_DEFAULT_LLM`
+    /// (default: "gpt-4-turbo")
     /// Uses `ANTHROPIC_API_KEY` or `OPENAI_API_KEY` based on model
     ///
     /// # Errors
     ///
     /// Returns error if API key not found in environment
     pub fn from_env() -> Result<Self> {
+        Self::from_env_with_model(None)
+    }
+
+    /// Create from environment variables, optionally forcing a model.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Model overriding `P8_EDGE_LLM`/`P8_DEFAULT_LLM` (blank is ignored)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model's API key is not found in environment
+    pub fn from_env_with_model(model: Option<&str>) -> Result<Self> {
         Ok(Self {
-            query_builder: LlmQueryBuilder::from_env()?,
+            query_builder: LlmQueryBuilder::from_env_with_model(LlmOperation::Edge, model)?,
         })
     }
 
@@ -116,10 +214,11 @@ impl LlmEdgeBuilder {
     ///
     /// * `content` - Document content (text, markdown, etc.)
     /// * `context` - Optional context about the document (file name, type, etc.)
+    /// * `min_confidence` - Drop edges with `confidence` below this (0.0 keeps all)
     ///
     /// # Returns
     ///
-    /// `EdgePlan` with extracted edges and summary
+    /// `EdgePlan` with extracted edges and summary (`filtered_edges` counts dropped edges)
     ///
     /// # Errors
     ///
@@ -131,7 +230,8 @@ impl LlmEdgeBuilder {
     /// let builder = LlmEdgeBuilder::from_env()?;
     /// let plan = builder.extract_edges(
     ///     "This document references Design Doc 001...",
-    ///     Some("architecture/rem-database.md")
+    ///     Some("architecture/rem-database.md"),
+    ///     0.7,
     /// ).await?;
     ///
     /// for edge in plan.edges {
@@ -142,6 +242,7 @@ impl LlmEdgeBuilder {
         &self,
         content: &str,
         context: Option<&str>,
+        min_confidence: f64,
     ) -> Result<EdgePlan> {
         // Build system prompt
         let system_prompt = Self::build_system_prompt();
@@ -152,29 +253,38 @@ impl LlmEdgeBuilder {
         // Call LLM
         let response = self.call_llm(&system_prompt, &user_prompt).await?;
 
-        // Parse response as EdgePlan
-        let plan: EdgePlan = serde_json::from_str(&response)
-            .map_err(|e| DatabaseError::LlmError(
-                format!("Failed to parse edge plan: {}\nResponse: {}", e, response)
-            ))?;
-
-        // Validate edges
-        for edge in &plan.edges {
-            // Validate UUID format
-            uuid::Uuid::parse_str(&edge.dst)
-                .map_err(|e| DatabaseError::ValidationError(
-                    format!("Invalid UUID in edge dst: {}", e)
-                ))?;
-        }
+        let mut plan = Self::parse_edge_plan(&response)?;
+        plan.filter_by_confidence(min_confidence);
 
         Ok(plan)
     }
 
+    /// Parse an edge plan from raw LLM output.
+    ///
+    /// Edge `dst` values may be UUIDs or entity names; names are matched to
+    /// entities by `Database::resolve_edge_destinations`.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - LLM response text (JSON, optionally in a markdown fence)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if the JSON is malformed
+    pub fn parse_edge_plan(response: &str) -> Result<EdgePlan> {
+        serde_json::from_str(&LlmQueryBuilder::strip_markdown(response))
+            .map_err(|e| DatabaseError::LlmError(
+                format!("Failed to parse edge plan: {}\nResponse: {}", e, response)
+            ))
+    }
+
     /// Call LLM API (delegates to query_builder).
     async fn call_llm(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
-        // Delegate to query_builder's call_llm method
+        // Delegate to query_builder, constrained to the EdgePlan schema
         // This handles OpenAI, Anthropic, Cerebras providers
-        self.query_builder.call_llm(system_prompt, user_prompt).await
+        self.query_builder
+            .call_llm_with_schema(system_prompt, user_prompt, "edge_plan", Self::get_edge_plan_schema())
+            .await
     }
 
     /// Build system prompt for edge extraction.
@@ -191,7 +301,7 @@ Your role is to analyze content and extract semantic relationships in the form o
    - Determine relationship types based on context
 
 2. **Edge Generation:**
-   - Create edges with dst UUID, rel_type, and properties
+   - Create edges with dst (UUID if given in the content, otherwise the target's name), rel_type, and properties
    - Add confidence scores based on clarity of relationship
    - Include context in edge properties where helpful
 
@@ -210,7 +320,7 @@ Your role is to analyze content and extract semantic relationships in the form o
 
 **Important Notes:**
 - Only generate edges for relationships you can clearly identify
-- Use placeholder UUIDs in format 12345678-1234-5678-9abc-XXXXXXXXXXXX
+- For dst, use the target's UUID only if it appears in the content; otherwise use its exact name or identifier (e.g. "Design Doc 001")
 - Be conservative with confidence scores (0.0-1.0)
 - Group related edges by relationship type
 
@@ -219,7 +329,7 @@ Return a JSON object matching this schema:
 {
   "edges": [
     {
-      "dst": "12345678-1234-5678-9abc-123456789001",
+      "dst": "Design Doc 001",
       "rel_type": "references",
       "properties": {
         "confidence": 0.95,
@@ -252,7 +362,7 @@ Return ONLY valid JSON. No markdown code blocks or explanations."#.to_string()
 {content}
 ```
 
-Analyze the content and identify all clear, explicit relationships. Generate edges with valid UUIDs (use placeholder format), relationship types, and confidence scores.
+Analyze the content and identify all clear, explicit relationships. Generate edges with dst (UUID or target name), relationship types, and confidence scores.
 
 Return JSON only."#,
             context_str = context_str,
@@ -261,7 +371,11 @@ Return JSON only."#,
     }
 
     /// Get JSON Schema for EdgePlan struct.
-    fn get_edge_plan_schema() -> serde_json::Value {
+    ///
+    /// Strict-mode compatible: every object lists all of its properties as
+    /// required and disallows additional properties, so providers with
+    /// structured output can enforce it.
+    pub fn get_edge_plan_schema() -> serde_json::Value {
         json!({
             "type": "object",
             "properties": {
@@ -272,7 +386,7 @@ Return JSON only."#,
                         "properties": {
                             "dst": {
                                 "type": "string",
-                                "pattern": "^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$"
+                                "description": "Target UUID, or target name/key if the UUID is unknown"
                             },
                             "rel_type": {
                                 "type": "string",
@@ -284,14 +398,26 @@ Return JSON only."#,
                             },
                             "properties": {
                                 "type": "object",
-                                "additionalProperties": true
+                                "properties": {
+                                    "confidence": {
+                                        "type": "number",
+                                        "description": "Confidence in the relationship, 0.0-1.0"
+                                    },
+                                    "context": {
+                                        "type": "string",
+                                        "description": "Text supporting the relationship"
+                                    }
+                                },
+                                "required": ["confidence", "context"],
+                                "additionalProperties": false
                             },
                             "created_at": {
                                 "type": "string",
-                                "format": "date-time"
+                                "description": "ISO 8601 timestamp"
                             }
                         },
-                        "required": ["dst", "rel_type", "created_at"]
+                        "required": ["dst", "rel_type", "properties", "created_at"],
+                        "additionalProperties": false
                     }
                 },
                 "summary": {
@@ -302,16 +428,14 @@ Return JSON only."#,
                             "type": "array",
                             "items": {"type": "string"}
                         },
-                        "avg_confidence": {
-                            "type": "number",
-                            "minimum": 0,
-                            "maximum": 1
-                        }
+                        "avg_confidence": {"type": "number"}
                     },
-                    "required": ["total_edges", "relationship_types", "avg_confidence"]
+                    "required": ["total_edges", "relationship_types", "avg_confidence"],
+                    "additionalProperties": false
                 }
             },
-            "required": ["edges", "summary"]
+            "required": ["edges", "summary"],
+            "additionalProperties": false
         })
     }
 }
@@ -320,6 +444,26 @@ Return JSON only."#,
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_per_operation_model_from_env() {
+        std::env::set_var("OPENAI_API_KEY", "test-key");
+        std::env::set_var("P8_DEFAULT_LLM", "gpt-4o");
+        std::env::set_var("P8_QUERY_LLM", "gpt-4o-mini");
+        std::env::remove_var("P8_EDGE_LLM");
+
+        // Query planning uses its override, edge extraction the default
+        assert_eq!(LlmQueryBuilder::from_env().unwrap().model(), "gpt-4o-mini");
+        assert_eq!(LlmEdgeBuilder::from_env().unwrap().query_builder.model(), "gpt-4o");
+
+        std::env::set_var("P8_EDGE_LLM", "gpt-4.1");
+        assert_eq!(LlmEdgeBuilder::from_env().unwrap().query_builder.model(), "gpt-4.1");
+
+        for var in ["P8_DEFAULT_LLM", "P8_QUERY_LLM", "P8_EDGE_LLM"] {
+            std::env::remove_var(var);
+        }
+        assert_eq!(LlmOperation::Query.model(), crate::llm::DEFAULT_LLM_MODEL);
+    }
+
     #[test]
     fn test_edge_spec_to_inline_edge() {
         let spec = EdgeSpec {
@@ -327,6 +471,8 @@ mod tests {
             rel_type: "references".to_string(),
             properties: HashMap::new(),
             created_at: "2024-01-15T10:00:00Z".to_string(),
+            resolved_id: None,
+            resolution_confidence: None,
         };
 
         let inline_edge = spec.to_inline_edge().unwrap();
@@ -340,11 +486,108 @@ mod tests {
             rel_type: "references".to_string(),
             properties: HashMap::new(),
             created_at: "2024-01-15T10:00:00Z".to_string(),
+            resolved_id: None,
+            resolution_confidence: None,
         };
 
         assert!(spec.to_inline_edge().is_err());
     }
 
+    #[test]
+    fn test_parse_edge_plan_with_name_dst() {
+        let response = r#"```json
+{
+  "edges": [
+    {"dst": "Design Doc 001", "rel_type": "references", "created_at": "2024-01-15T10:00:00Z"}
+  ],
+  "summary": {"total_edges": 1, "relationship_types": ["references"], "avg_confidence": 0.9}
+}
+```"#;
+
+        let mut plan = LlmEdgeBuilder::parse_edge_plan(response).unwrap();
+        assert_eq!(plan.edges[0].dst, "Design Doc 001");
+        assert!(plan.edges[0].resolved_id.is_none());
+        assert!(plan.edges[0].to_inline_edge().is_err());
+
+        // Resolved edges convert via resolved_id
+        let id = uuid::Uuid::new_v4();
+        plan.edges[0].resolved_id = Some(id);
+        assert_eq!(plan.edges[0].to_inline_edge().unwrap().dst, id);
+    }
+
+    #[test]
+    fn test_filter_by_confidence() {
+        // Canned LLM output with mixed confidence
+        let response = r#"{
+  "edges": [
+    {"dst": "Design Doc 001", "rel_type": "references", "properties": {"confidence": 0.95}, "created_at": "2024-01-15T10:00:00Z"},
+    {"dst": "Alice", "rel_type": "authored_by", "properties": {"confidence": 0.8}, "created_at": "2024-01-15T10:00:00Z"},
+    {"dst": "Kafka", "rel_type": "depends_on", "properties": {"confidence": 0.4}, "created_at": "2024-01-15T10:00:00Z"},
+    {"dst": "Something", "rel_type": "related_to", "created_at": "2024-01-15T10:00:00Z"}
+  ],
+  "summary": {"total_edges": 4, "relationship_types": ["references", "authored_by", "depends_on", "related_to"], "avg_confidence": 0.72}
+}"#;
+
+        let mut plan = LlmEdgeBuilder::parse_edge_plan(response).unwrap();
+        assert_eq!(plan.summary.filtered_edges, 0);
+
+        let filtered = plan.filter_by_confidence(0.7);
+
+        assert_eq!(filtered, 2);
+        let dsts: Vec<&str> = plan.edges.iter().map(|e| e.dst.as_str()).collect();
+        assert_eq!(dsts, vec!["Design Doc 001", "Alice"]);
+        assert_eq!(plan.summary.total_edges, 2);
+        assert_eq!(plan.summary.filtered_edges, 2);
+        assert_eq!(plan.summary.relationship_types, vec!["references", "authored_by"]);
+        assert!((plan.summary.avg_confidence - 0.875).abs() < 1e-9);
+
+        // Zero threshold keeps everything
+        assert_eq!(plan.filter_by_confidence(0.0), 0);
+        assert_eq!(plan.edges.len(), 2);
+    }
+
+    /// Assert every object schema requires all its properties and forbids extras.
+    fn assert_strict(schema: &serde_json::Value, path: &str) {
+        if let Some(props) = schema.get("properties").and_then(|p| p.as_object()) {
+            assert_eq!(schema["additionalProperties"], false, "{} allows additional properties", path);
+            let required: Vec<&str> = schema["required"]
+                .as_array()
+                .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            for (name, prop) in props {
+                assert!(required.contains(&name.as_str()), "{}.{} not required", path, name);
+                assert_strict(prop, &format!("{}.{}", path, name));
+            }
+        }
+        if let Some(items) = schema.get("items") {
+            assert_strict(items, &format!("{}[]", path));
+        }
+    }
+
+    #[test]
+    fn test_edge_plan_schema_is_strict() {
+        assert_strict(&LlmEdgeBuilder::get_edge_plan_schema(), "$");
+    }
+
+    #[test]
+    fn test_parse_structured_response() {
+        // Canned response as returned under the strict edge_plan schema
+        let response = r#"{"edges":[{"dst":"Design Doc 001","rel_type":"references","properties":{"confidence":0.95,"context":"influenced by Design Doc 001"},"created_at":"2024-01-15T10:00:00Z"},{"dst":"550e8400-e29b-41d4-a716-446655440000","rel_type":"depends_on","properties":{"confidence":0.8,"context":"requires the storage layer"},"created_at":"2024-01-15T10:00:00Z"}],"summary":{"total_edges":2,"relationship_types":["references","depends_on"],"avg_confidence":0.875}}"#;
+
+        let plan = LlmEdgeBuilder::parse_edge_plan(response).unwrap();
+
+        assert_eq!(plan.edges.len(), 2);
+        assert_eq!(plan.edges[0].dst, "Design Doc 001");
+        assert_eq!(plan.edges[0].rel_type, "references");
+        assert_eq!(plan.edges[0].confidence(), Some(0.95));
+        assert_eq!(plan.edges[0].properties["context"], "influenced by Design Doc 001");
+        assert_eq!(plan.edges[1].rel_type, "depends_on");
+        assert_eq!(plan.edges[1].to_inline_edge().unwrap().dst.to_string(), "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(plan.summary.total_edges, 2);
+        assert_eq!(plan.summary.relationship_types, vec!["references", "depends_on"]);
+        assert_eq!(plan.summary.filtered_edges, 0);
+    }
+
     #[test]
     fn test_system_prompt_generation() {
         let prompt = LlmEdgeBuilder::build_system_prompt();
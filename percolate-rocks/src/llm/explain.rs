@@ -0,0 +1,281 @@
+//! Step-by-step replay of a query plan.
+//!
+//! Runs the primary query, then each fallback whose trigger fires, and
+//! records the query and result count per stage. Used by `rem ask --explain`
+//! (via `Database::explain_ask`) to make LLM planning transparent without
+//! dumping the full plan JSON.
+//!
+//! Stages stop at the first one that returns results. `no_results` and
+//! `low_quality` fallbacks fire on an empty stage; `error` fallbacks fire
+//! on a failed stage.
+
+use crate::database::Database;
+use crate::llm::planner::{FallbackTrigger, QueryPlan};
+use crate::types::Result;
+use std::fmt;
+
+/// One executed stage of a plan.
+#[derive(Debug, Clone)]
+pub struct StageReport {
+    /// Stage number (1-based)
+    pub stage: usize,
+    /// `primary` or the fallback trigger that fired
+    pub label: String,
+    /// Query text executed
+    pub query: String,
+    /// Result count (`None` if the query failed)
+    pub result_count: Option<usize>,
+    /// Error message if the query failed
+    pub error: Option<String>,
+}
+
+/// Query plan plus per-stage execution results.
+#[derive(Debug, Clone)]
+pub struct PlanExplanation {
+    pub plan: QueryPlan,
+    pub stages: Vec<StageReport>,
+}
+
+impl PlanExplanation {
+    /// Results from the last executed stage.
+    pub fn final_result_count(&self) -> usize {
+        self.stages.last().and_then(|s| s.result_count).unwrap_or(0)
+    }
+}
+
+/// Serde name of an enum value (e.g. `multi_stage`).
+fn serde_name<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl fmt::Display for PlanExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plan = &self.plan;
+
+        writeln!(f, "Query Plan")?;
+        writeln!(f, "  Type:       {}", serde_name(&plan.query_type))?;
+        writeln!(f, "  Confidence: {:.2}", plan.confidence)?;
+        writeln!(f, "  Mode:       {}", serde_name(&plan.execution_mode))?;
+        writeln!(f, "  Primary:    {}", plan.primary_query.query_string)?;
+        for (i, fallback) in plan.fallback_queries.iter().enumerate() {
+            writeln!(
+                f,
+                "  Fallback {}: {} (on {}, confidence {:.2})",
+                i + 1,
+                fallback.query.query_string,
+                serde_name(&fallback.trigger),
+                fallback.confidence
+            )?;
+        }
+        writeln!(f, "  Reasoning:  {}", plan.reasoning)?;
+        if let Some(explanation) = &plan.explanation {
+            writeln!(f, "  Explanation: {}", explanation)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Execution")?;
+        for stage in &self.stages {
+            match (&stage.result_count, &stage.error) {
+                (Some(count), _) => writeln!(
+                    f,
+                    "  Stage {} [{}]: {} -> {} result(s)",
+                    stage.stage, stage.label, stage.query, count
+                )?,
+                (None, Some(error)) => writeln!(
+                    f,
+                    "  Stage {} [{}]: {} -> error: {}",
+                    stage.stage, stage.label, stage.query, error
+                )?,
+                (None, None) => writeln!(f, "  Stage {} [{}]: {}", stage.stage, stage.label, stage.query)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Execute a plan stage by stage, recording each stage's result count.
+///
+/// Query errors are recorded on the stage rather than returned, so a failing
+/// primary query still shows its fallbacks.
+///
+/// # Arguments
+///
+/// * `db` - Database to run queries against
+/// * `tenant_id` - Tenant scope
+/// * `plan` - Plan to replay
+///
+/// # Returns
+///
+/// `PlanExplanation` with one `StageReport` per executed stage
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let plan = builder.plan_query(question, &schema_context).await?;
+/// let explanation = explain_plan(&db, "default", &plan).await?;
+/// println!("{}", explanation);
+/// ```
+pub async fn explain_plan(db: &Database, tenant_id: &str, plan: &QueryPlan) -> Result<PlanExplanation> {
+    let mut stages = Vec::new();
+
+    let primary = run_stage(db, tenant_id, 1, "primary".to_string(), &plan.primary_query.query_string).await;
+    let mut last_failed = primary.error.is_some();
+    let mut last_empty = primary.result_count == Some(0);
+    stages.push(primary);
+
+    for fallback in &plan.fallback_queries {
+        let fires = match fallback.trigger {
+            FallbackTrigger::Error => last_failed,
+            FallbackTrigger::NoResults | FallbackTrigger::LowQuality => last_empty,
+        };
+        if !fires {
+            continue;
+        }
+
+        let label = format!("fallback: {}", serde_name(&fallback.trigger));
+        let stage = run_stage(db, tenant_id, stages.len() + 1, label, &fallback.query.query_string).await;
+        last_failed = stage.error.is_some();
+        last_empty = stage.result_count == Some(0);
+        stages.push(stage);
+
+        if !last_failed && !last_empty {
+            break;
+        }
+    }
+
+    Ok(PlanExplanation {
+        plan: plan.clone(),
+        stages,
+    })
+}
+
+async fn run_stage(db: &Database, tenant_id: &str, stage: usize, label: String, query: &str) -> StageReport {
+    let (result_count, error) = match count_results(db, tenant_id, query).await {
+        Ok(count) => (Some(count), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    StageReport {
+        stage,
+        label,
+        query: query.to_string(),
+        result_count,
+        error,
+    }
+}
+
+/// Run an extended REM query and count its results.
+async fn count_results(db: &Database, tenant_id: &str, query: &str) -> Result<usize> {
+    Ok(crate::llm::ask::execute_query(db, tenant_id, query).await?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LlmQueryBuilder, QueryPlanner};
+    use std::sync::Mutex;
+
+    /// Returns a fixed plan and records the schema context it was given.
+    struct MockPlanner {
+        plan: serde_json::Value,
+        contexts: Mutex<Vec<String>>,
+    }
+
+    impl MockPlanner {
+        fn new(plan: serde_json::Value) -> Self {
+            Self { plan, contexts: Mutex::new(Vec::new()) }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl QueryPlanner for MockPlanner {
+        async fn plan_query(&self, _question: &str, schema_context: &str) -> Result<QueryPlan> {
+            self.contexts.lock().unwrap().push(schema_context.to_string());
+            LlmQueryBuilder::parse_plan(&self.plan.to_string())
+        }
+    }
+
+    /// Title lookup with a fallback to all articles.
+    fn title_then_all(primary: &str) -> serde_json::Value {
+        serde_json::json!({
+            "query_type": "sql",
+            "confidence": 0.7,
+            "primary_query": {
+                "dialect": "rem_sql",
+                "query_string": primary,
+                "parameters": {"schema": "article"}
+            },
+            "fallback_queries": [
+                {
+                    "query": {
+                        "dialect": "rem_sql",
+                        "query_string": "SELECT * FROM article",
+                        "parameters": {"schema": "article"}
+                    },
+                    "trigger": "no_results",
+                    "confidence": 0.5,
+                    "reasoning": "Broaden to all articles"
+                }
+            ],
+            "execution_mode": "multi_stage",
+            "reasoning": "Title filter first, then all articles",
+            "next_steps": []
+        })
+    }
+
+    #[tokio::test]
+    async fn test_explain_plan_stages() {
+        let db = Database::open_temp().unwrap();
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "article",
+            "properties": {
+                "title": {"type": "string"}
+            },
+            "required": ["title"]
+        });
+        db.register_schema("article", schema).unwrap();
+        for i in 0..3 {
+            db.insert("default", "article", serde_json::json!({"title": format!("Article {}", i)})).unwrap();
+        }
+
+        let planner = MockPlanner::new(title_then_all("SELECT * FROM article WHERE title = 'Missing'"));
+        let explanation = db
+            .explain_ask(&planner, "default", "articles titled Missing", "Available schemas: article")
+            .await
+            .unwrap();
+
+        assert_eq!(*planner.contexts.lock().unwrap(), vec!["Available schemas: article".to_string()]);
+        assert_eq!(explanation.stages.len(), 2);
+        assert_eq!(explanation.stages[0].result_count, Some(0));
+        assert_eq!(explanation.stages[1].result_count, Some(3));
+        assert_eq!(explanation.final_result_count(), 3);
+
+        let output = explanation.to_string();
+        assert!(output.contains("Type:       sql"));
+        assert!(output.contains("Mode:       multi_stage"));
+        assert!(output.contains("Stage 1 [primary]: SELECT * FROM article WHERE title = 'Missing' -> 0 result(s)"));
+        assert!(output.contains("Stage 2 [fallback: no_results]: SELECT * FROM article -> 3 result(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_explain_records_stage_error() {
+        let db = Database::open_temp().unwrap();
+
+        let mut plan = title_then_all("SELEC * FROM article");
+        plan["fallback_queries"] = serde_json::json!([]);
+        let planner = MockPlanner::new(plan);
+
+        let explanation = db.explain_ask(&planner, "default", "anything", "").await.unwrap();
+
+        assert_eq!(explanation.stages.len(), 1);
+        assert!(explanation.stages[0].error.is_some());
+        assert!(explanation.to_string().contains("-> error:"));
+    }
+}
@@ -1,9 +1,17 @@
-//! LLM-powered natural language query builder and edge extraction.
+//! LLM-powered natural language query builder, edge extraction, moment extraction, and RAG answers.
 
 pub mod query_builder;
 pub mod planner;
 pub mod edge_builder;
+pub mod moment_builder;
+pub mod explain;
+pub mod ask;
+pub mod rag;
 
-pub use query_builder::LlmQueryBuilder;
+pub use query_builder::{LlmOperation, LlmQueryBuilder, DEFAULT_LLM_MODEL};
 pub use planner::{QueryPlan, QueryType, QueryResult};
-pub use edge_builder::{LlmEdgeBuilder, EdgePlan, EdgeSpec, EdgeSummary};
+pub use edge_builder::{LlmEdgeBuilder, EdgePlan, EdgePlanReport, EdgeSpec, EdgeSummary};
+pub use moment_builder::LlmMomentBuilder;
+pub use explain::{explain_plan, PlanExplanation, StageReport};
+pub use ask::{execute_plan, AskAnswer, QueryPlanner, DEFAULT_ASK_CONCURRENCY};
+pub use rag::{build_context, split_sentences, AnswerGenerator, Grounding, GroundingVerifier, RagAnswer, SentenceSupport};
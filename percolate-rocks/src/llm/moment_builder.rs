@@ -0,0 +1,348 @@
+//! LLM-powered moment extraction for REM indexing.
+//!
+//! Classifies content into time-bounded moments (what happened, when, and
+//! what kind of activity it was). This is the M (Moments) in REM indexing.
+//!
+//! Only moments with explicit, well-formed times are kept: the model is
+//! told to return `null` for times it can't determine, and moments with a
+//! missing or unparseable time, or an end before the start, are dropped
+//! rather than guessed.
+
+use crate::dreaming::types::{Moment, MomentType};
+use crate::llm::query_builder::{LlmOperation, LlmQueryBuilder};
+use crate::types::{DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Moment as returned by the LLM, before time validation.
+#[derive(Debug, Clone, Deserialize)]
+struct MomentCandidate {
+    name: String,
+    #[serde(default)]
+    summary: String,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    #[serde(default)]
+    moment_type: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    emotion_tags: Vec<String>,
+    #[serde(default)]
+    people: Vec<String>,
+}
+
+impl MomentCandidate {
+    /// Convert to a `Moment`, or `None` if its time range is unusable.
+    fn into_moment(self) -> Option<Moment> {
+        let start_time = parse_time(self.start_time.as_deref())?;
+        let end_time = parse_time(self.end_time.as_deref())?;
+        if end_time < start_time {
+            return None;
+        }
+
+        let moment_type = self
+            .moment_type
+            .and_then(|t| serde_json::from_value(serde_json::Value::String(t)).ok())
+            .unwrap_or(MomentType::Other);
+
+        Some(Moment {
+            name: self.name,
+            summary: self.summary,
+            start_time,
+            end_time,
+            moment_type,
+            tags: self.tags,
+            emotion_tags: self.emotion_tags,
+            people: self.people,
+            resource_ids: Vec::new(),
+            session_ids: Vec::new(),
+            metadata: json!({}),
+        })
+    }
+}
+
+/// Parse an RFC 3339 timestamp, treating blanks and garbage as missing.
+fn parse_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?.trim())
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+#[derive(Debug, Deserialize)]
+struct MomentResponse {
+    moments: Vec<MomentCandidate>,
+}
+
+/// LLM-powered moment builder.
+pub struct LlmMomentBuilder {
+    query_builder: LlmQueryBuilder,
+}
+
+impl LlmMomentBuilder {
+    /// Create new moment builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - API key (OpenAI or Anthropic)
+    /// * `model` - LLM model name
+    ///
+    /// # Returns
+    ///
+    /// New `LlmMomentBuilder`
+    pub fn new(api_key: String, model: String) -> Self {
+        Self {
+            query_builder: LlmQueryBuilder::new(api_key, model),
+        }
+    }
+
+    /// Create from environment variables.
+    ///
+    /// Uses `P8_DEFAULT_LLM` for model (default: "gpt-4-turbo") and the
+    /// same API key variables as `LlmQueryBuilder::from_env`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if API key not found in environment
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            query_builder: LlmQueryBuilder::from_env_for(LlmOperation::General)?,
+        })
+    }
+
+    /// Extract time-bounded moments from content.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Content to classify (notes, transcript, log, etc.)
+    /// * `context` - Optional context (source, author, date of writing)
+    ///
+    /// # Returns
+    ///
+    /// Moments with valid time ranges, ready to insert into `moments`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if generation or parsing fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let builder = LlmMomentBuilder::from_env()?;
+    /// let moments = builder.extract_moments(
+    ///     "Standup ran 9:00-9:15 on 2024-03-04, then paired on DiskANN until noon.",
+    ///     Some("journal/2024-03-04.md"),
+    /// ).await?;
+    /// ```
+    pub async fn extract_moments(&self, content: &str, context: Option<&str>) -> Result<Vec<Moment>> {
+        let system_prompt = Self::build_system_prompt();
+        let user_prompt = Self::build_user_prompt(content, context);
+
+        let response = self
+            .query_builder
+            .call_llm_with_schema(&system_prompt, &user_prompt, "moments", Self::get_moments_schema())
+            .await?;
+
+        Self::parse_moments(&response)
+    }
+
+    /// Parse moments from raw LLM output.
+    ///
+    /// Moments whose start or end time is missing, not RFC 3339, or out of
+    /// order are dropped. An unknown `moment_type` becomes `other`.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - LLM response text (JSON, optionally in a markdown fence)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if the JSON is malformed
+    pub fn parse_moments(response: &str) -> Result<Vec<Moment>> {
+        let parsed: MomentResponse = serde_json::from_str(&LlmQueryBuilder::strip_markdown(response))
+            .map_err(|e| DatabaseError::LlmError(
+                format!("Failed to parse moments: {}\nResponse: {}", e, response)
+            ))?;
+
+        let candidates = parsed.moments.len();
+        let moments: Vec<Moment> = parsed
+            .moments
+            .into_iter()
+            .filter_map(MomentCandidate::into_moment)
+            .collect();
+
+        if moments.len() < candidates {
+            tracing::debug!(
+                dropped = candidates - moments.len(),
+                "Dropped moments without a valid time range"
+            );
+        }
+
+        Ok(moments)
+    }
+
+    /// Build system prompt for moment extraction.
+    fn build_system_prompt() -> String {
+        r#"You are a moment extraction specialist that classifies content into time-bounded moments.
+
+A moment is a period of activity with a start time, an end time, and a type.
+
+**Moment Types:**
+- work_session: Focused work on a task
+- learning: Reading, studying, researching
+- planning: Planning, prioritising, scheduling
+- communication: Meetings, calls, conversations
+- reflection: Journaling, retrospectives
+- creation: Writing, designing, building
+- other: Anything else
+
+**Times:**
+- Use ISO 8601 / RFC 3339 timestamps with a timezone (e.g. "2024-03-04T09:00:00Z")
+- Only use times stated in, or directly derivable from, the content and context
+- If a start or end time is ambiguous or missing, set it to null. Never guess.
+
+**Output Format:**
+{
+  "moments": [
+    {
+      "name": "Morning standup",
+      "summary": "Daily standup covering the release blockers.",
+      "start_time": "2024-03-04T09:00:00Z",
+      "end_time": "2024-03-04T09:15:00Z",
+      "moment_type": "communication",
+      "tags": ["standup", "release"],
+      "emotion_tags": ["focused"],
+      "people": ["Alice"]
+    }
+  ]
+}
+
+Return ONLY valid JSON. No markdown code blocks or explanations."#.to_string()
+    }
+
+    /// Build user prompt with content.
+    fn build_user_prompt(content: &str, context: Option<&str>) -> String {
+        let context_str = context
+            .map(|c| format!("\n\n**Context:** {}\n", c))
+            .unwrap_or_default();
+
+        format!(
+            r#"Extract moments from the following content:{context_str}
+
+**Content:**
+```
+{content}
+```
+
+Identify each distinct, time-bounded activity. Use null for any time you cannot determine.
+
+Return JSON only."#,
+            context_str = context_str,
+            content = content
+        )
+    }
+
+    /// Get JSON Schema for the moments response.
+    ///
+    /// Strict-mode compatible; times are nullable so the model can decline
+    /// to give them.
+    pub fn get_moments_schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "moments": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": {"type": "string"},
+                            "summary": {"type": "string"},
+                            "start_time": {
+                                "type": ["string", "null"],
+                                "description": "RFC 3339 start time, or null if not stated"
+                            },
+                            "end_time": {
+                                "type": ["string", "null"],
+                                "description": "RFC 3339 end time, or null if not stated"
+                            },
+                            "moment_type": {
+                                "type": "string",
+                                "enum": [
+                                    "work_session", "learning", "planning", "communication",
+                                    "reflection", "creation", "other"
+                                ]
+                            },
+                            "tags": {"type": "array", "items": {"type": "string"}},
+                            "emotion_tags": {"type": "array", "items": {"type": "string"}},
+                            "people": {"type": "array", "items": {"type": "string"}}
+                        },
+                        "required": [
+                            "name", "summary", "start_time", "end_time",
+                            "moment_type", "tags", "emotion_tags", "people"
+                        ],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["moments"],
+            "additionalProperties": false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_moments() {
+        // Canned LLM output: two usable moments, one with a missing end
+        // time, one ending before it starts
+        let response = r#"```json
+{
+  "moments": [
+    {"name": "Morning standup", "summary": "Release blockers.", "start_time": "2024-03-04T09:00:00Z", "end_time": "2024-03-04T09:15:00Z", "moment_type": "communication", "tags": ["standup"], "emotion_tags": [], "people": ["Alice"]},
+    {"name": "DiskANN pairing", "summary": "Paired on graph pruning.", "start_time": "2024-03-04T10:00:00+01:00", "end_time": "2024-03-04T12:00:00+01:00", "moment_type": "work_session", "tags": ["diskann"], "emotion_tags": ["focused"], "people": []},
+    {"name": "Lunch", "summary": "", "start_time": "2024-03-04T12:30:00Z", "end_time": null, "moment_type": "other", "tags": [], "emotion_tags": [], "people": []},
+    {"name": "Backwards", "summary": "", "start_time": "2024-03-04T15:00:00Z", "end_time": "2024-03-04T14:00:00Z", "moment_type": "planning", "tags": [], "emotion_tags": [], "people": []}
+  ]
+}
+```"#;
+
+        let moments = LlmMomentBuilder::parse_moments(response).unwrap();
+
+        assert_eq!(moments.len(), 2);
+        assert!(moments.iter().all(|m| m.start_time < m.end_time));
+
+        assert_eq!(moments[0].name, "Morning standup");
+        assert_eq!(moments[0].moment_type, MomentType::Communication);
+        assert_eq!(moments[0].people, vec!["Alice"]);
+
+        // Offsets are normalised to UTC
+        assert_eq!(moments[1].moment_type, MomentType::WorkSession);
+        assert_eq!(moments[1].start_time.to_rfc3339(), "2024-03-04T09:00:00+00:00");
+
+        // Records serialize to `moments` schema properties
+        let properties = serde_json::to_value(&moments[1]).unwrap();
+        assert_eq!(properties["moment_type"], "work_session");
+        assert_eq!(properties["tags"], json!(["diskann"]));
+        assert!(properties["end_time"].is_string());
+    }
+
+    #[test]
+    fn test_parse_moments_unknown_type() {
+        let response = r#"{"moments": [{"name": "Nap", "start_time": "2024-03-04T13:00:00Z", "end_time": "2024-03-04T13:20:00Z", "moment_type": "sleeping"}]}"#;
+
+        let moments = LlmMomentBuilder::parse_moments(response).unwrap();
+        assert_eq!(moments[0].moment_type, MomentType::Other);
+        assert!(LlmMomentBuilder::parse_moments("not json").is_err());
+    }
+
+    #[test]
+    fn test_user_prompt_generation() {
+        let prompt = LlmMomentBuilder::build_user_prompt("Standup at 9", Some("journal/2024-03-04.md"));
+        assert!(prompt.contains("Standup at 9"));
+        assert!(prompt.contains("journal/2024-03-04.md"));
+    }
+}
@@ -5,6 +5,9 @@ use crate::llm::planner::{QueryPlan, QueryType, Query, QueryDialect, ExecutionMo
 use serde::Deserialize;
 use serde_json::json;
 use reqwest::Client;
+use crate::http::{with_deadline, DEFAULT_REQUEST_TIMEOUT};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 /// LLM provider type.
 #[derive(Debug, Clone)]
@@ -14,12 +17,56 @@ pub enum LlmProvider {
     Cerebras,
 }
 
+/// Default model when no `P8_*_LLM` variable is set.
+pub const DEFAULT_LLM_MODEL: &str = "gpt-4-turbo";
+
+/// Operation an LLM is used for, each with its own model override.
+///
+/// Lets a cheap, fast model plan queries while a stronger one extracts
+/// edges. Unset overrides fall back to `P8_DEFAULT_LLM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmOperation {
+    /// Anything without a dedicated override (uses `P8_DEFAULT_LLM`)
+    General,
+    /// Query planning (`P8_QUERY_LLM`)
+    Query,
+    /// Edge extraction (`P8_EDGE_LLM`)
+    Edge,
+}
+
+impl LlmOperation {
+    /// Environment variable overriding this operation's model.
+    pub fn env_var(&self) -> Option<&'static str> {
+        match self {
+            LlmOperation::General => None,
+            LlmOperation::Query => Some("P8_QUERY_LLM"),
+            LlmOperation::Edge => Some("P8_EDGE_LLM"),
+        }
+    }
+
+    /// Resolve the model for this operation from the environment.
+    ///
+    /// # Returns
+    ///
+    /// The operation's override, else `P8_DEFAULT_LLM`, else `DEFAULT_LLM_MODEL`
+    pub fn model(&self) -> String {
+        self.env_var()
+            .and_then(|var| std::env::var(var).ok())
+            .or_else(|| std::env::var("P8_DEFAULT_LLM").ok())
+            .filter(|model| !model.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_LLM_MODEL.to_string())
+    }
+}
+
 /// LLM-powered query builder.
 pub struct LlmQueryBuilder {
     api_key: String,
     model: String,
     provider: LlmProvider,
     client: Client,
+    base_url: Option<String>,
+    timeout: Duration,
+    cancel: Option<CancellationToken>,
 }
 
 /// OpenAI API response for structured output.
@@ -56,7 +103,7 @@ impl LlmQueryBuilder {
     /// - ```json ... ```
     /// - ```JSON ... ```
     /// - ``` ... ```
-    fn strip_markdown(text: &str) -> String {
+    pub(crate) fn strip_markdown(text: &str) -> String {
         let text = text.trim();
 
         // Check for ```json ... ``` or ```JSON ... ```
@@ -100,20 +147,127 @@ impl LlmQueryBuilder {
             model,
             provider,
             client: Client::new(),
+            base_url: None,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            cancel: None,
         }
     }
 
-    /// Create from environment variables.
+    /// Set the per-request timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum time per API call (default: `DEFAULT_REQUEST_TIMEOUT`)
+    ///
+    /// # Returns
+    ///
+    /// Updated `LlmQueryBuilder`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Abort in-flight and future API calls when `token` is cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Cancellation token
+    ///
+    /// # Returns
+    ///
+    /// Updated `LlmQueryBuilder`
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Override the provider's API origin (for proxies and gateways).
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - Origin replacing e.g. `https://api.openai.com` (paths are kept)
+    ///
+    /// # Returns
     ///
-    /// Uses `P8_DEFAULT_LLM` for model (default: "gpt-4-turbo")
+    /// Updated `LlmQueryBuilder`
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Get the model name.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Endpoint URL for `path` on the provider's origin (or the override).
+    fn endpoint(&self, default_origin: &str, path: &str) -> String {
+        format!("{}{}", self.base_url.as_deref().unwrap_or(default_origin), path)
+    }
+
+    /// Create from environment variables for query planning.
+    ///
+    /// Uses `P8_QUERY_LLM` for model, falling back to `P8_DEFAULT_LLM`
+    /// (default: "gpt-4-turbo")
     /// Uses `CEREBRAS_API_KEY`, `OPENAI_API_KEY`, or `ANTHROPIC_API_KEY` based on model
     ///
     /// # Errors
     ///
     /// Returns error if API key not found in environment
     pub fn from_env() -> Result<Self> {
-        let model = std::env::var("P8_DEFAULT_LLM")
-            .unwrap_or_else(|_| "gpt-4-turbo".to_string());
+        Self::from_env_for(LlmOperation::Query)
+    }
+
+    /// Create from environment variables for a given operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - Operation whose model override to use (see `LlmOperation::model`)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if API key not found in environment
+    pub fn from_env_for(operation: LlmOperation) -> Result<Self> {
+        Self::for_model(operation.model())
+    }
+
+    /// Create from environment variables, optionally forcing a model.
+    ///
+    /// Lets one request use a different model (e.g. a stronger one for a
+    /// hard question) without changing the environment.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - Operation whose model to use when `model` is `None`
+    /// * `model` - Model overriding the environment for this builder (blank is ignored)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the model's API key is not found in environment
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let builder = LlmQueryBuilder::from_env_with_model(LlmOperation::Query, Some("claude-3-5-sonnet-20241022"))?;
+    /// ```
+    pub fn from_env_with_model(operation: LlmOperation, model: Option<&str>) -> Result<Self> {
+        match model.map(str::trim).filter(|model| !model.is_empty()) {
+            Some(model) => Self::for_model(model),
+            None => Self::from_env_for(operation),
+        }
+    }
+
+    /// Create for a model, reading its provider's API key from the environment.
+    ///
+    /// Uses `ANTHROPIC_API_KEY` for `claude*`/`anthropic*` models,
+    /// `CEREBRAS_API_KEY` for `cerebras*`/`llama*`/`qwen*`, and
+    /// `OPENAI_API_KEY` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if API key not found in environment
+    pub fn for_model(model: impl Into<String>) -> Result<Self> {
+        let model = model.into();
 
         let api_key = if model.starts_with("claude") || model.starts_with("anthropic") {
             std::env::var("ANTHROPIC_API_KEY")
@@ -157,17 +311,116 @@ impl LlmQueryBuilder {
     ///
     /// Public method to allow reuse by other LLM-powered modules.
     pub async fn call_llm(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        let response_format = match self.provider {
+            LlmProvider::Cerebras => Self::json_schema_format("query_plan", Self::get_query_plan_schema()),
+            _ => json!({"type": "json_object"}),
+        };
+
+        self.call_provider(system_prompt, user_prompt, response_format).await
+    }
+
+    /// Call LLM API constrained to a strict JSON schema.
+    ///
+    /// OpenAI and Cerebras enforce the schema via `response_format`
+    /// (`json_schema`, `strict: true`), so the response always parses.
+    /// Anthropic has no equivalent mode; the schema is appended to the
+    /// system prompt instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `system_prompt` - System prompt
+    /// * `user_prompt` - User prompt
+    /// * `schema_name` - Schema name reported to the provider
+    /// * `schema` - Strict-mode JSON Schema (all properties required, no additional properties)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if the API call fails
+    pub async fn call_llm_with_schema(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        schema_name: &str,
+        schema: serde_json::Value,
+    ) -> Result<String> {
+        if let LlmProvider::Anthropic = self.provider {
+            let system_prompt = format!(
+                "{}\n\nRespond with JSON matching this schema:\n{}",
+                system_prompt,
+                serde_json::to_string_pretty(&schema)?
+            );
+            return self.call_provider(&system_prompt, user_prompt, serde_json::Value::Null).await;
+        }
+
+        let response_format = Self::json_schema_format(schema_name, schema);
+        self.call_provider(system_prompt, user_prompt, response_format).await
+    }
+
+    /// Dispatch to the configured provider.
+    ///
+    /// `response_format` is sent as-is to OpenAI-compatible providers and
+    /// ignored for Anthropic. The call fails with `DatabaseError::Timeout`
+    /// or `DatabaseError::Cancelled` if it outlives the timeout or the
+    /// cancellation token.
+    async fn call_provider(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: serde_json::Value,
+    ) -> Result<String> {
+        with_deadline(
+            self.timeout,
+            self.cancel.as_ref(),
+            "LLM request",
+            self.dispatch(system_prompt, user_prompt, response_format),
+        )
+        .await
+    }
+
+    /// Call the configured provider's API.
+    async fn dispatch(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: serde_json::Value,
+    ) -> Result<String> {
         match self.provider {
-            LlmProvider::OpenAI => self.call_openai(system_prompt, user_prompt).await,
-            LlmProvider::Anthropic => self.call_anthropic(system_prompt, user_prompt).await,
-            LlmProvider::Cerebras => self.call_cerebras(system_prompt, user_prompt).await,
+            LlmProvider::OpenAI => {
+                crate::otel::record_llm_call("openai");
+                self.call_openai(system_prompt, user_prompt, response_format).await
+            }
+            LlmProvider::Anthropic => {
+                crate::otel::record_llm_call("anthropic");
+                self.call_anthropic(system_prompt, user_prompt).await
+            }
+            LlmProvider::Cerebras => {
+                crate::otel::record_llm_call("cerebras");
+                self.call_cerebras(system_prompt, user_prompt, response_format).await
+            }
         }
     }
 
+    /// Strict `json_schema` response format for OpenAI-compatible APIs.
+    fn json_schema_format(name: &str, schema: serde_json::Value) -> serde_json::Value {
+        json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": name,
+                "strict": true,
+                "schema": schema
+            }
+        })
+    }
+
     /// Call OpenAI API.
-    async fn call_openai(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+    async fn call_openai(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: serde_json::Value,
+    ) -> Result<String> {
         let response = self.client
-            .post("https://api.openai.com/v1/chat/completions")
+            .post(self.endpoint("https://api.openai.com", "/v1/chat/completions"))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&json!({
@@ -176,7 +429,7 @@ impl LlmQueryBuilder {
                     {"role": "system", "content": system_prompt},
                     {"role": "user", "content": user_prompt}
                 ],
-                "response_format": {"type": "json_object"},
+                "response_format": response_format,
                 "temperature": 0.1
             }))
             .send()
@@ -202,7 +455,7 @@ impl LlmQueryBuilder {
     /// Call Anthropic API.
     async fn call_anthropic(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
         let response = self.client
-            .post("https://api.anthropic.com/v1/messages")
+            .post(self.endpoint("https://api.anthropic.com", "/v1/messages"))
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
@@ -405,9 +658,14 @@ impl LlmQueryBuilder {
     }
 
     /// Call Cerebras API (OpenAI-compatible) with strict JSON schema.
-    async fn call_cerebras(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+    async fn call_cerebras(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: serde_json::Value,
+    ) -> Result<String> {
         let response = self.client
-            .post("https://api.cerebras.ai/v1/chat/completions")
+            .post(self.endpoint("https://api.cerebras.ai", "/v1/chat/completions"))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&json!({
@@ -416,14 +674,7 @@ impl LlmQueryBuilder {
                     {"role": "system", "content": system_prompt},
                     {"role": "user", "content": user_prompt}
                 ],
-                "response_format": {
-                    "type": "json_schema",
-                    "json_schema": {
-                        "name": "query_plan",
-                        "strict": true,
-                        "schema": Self::get_query_plan_schema()
-                    }
-                },
+                "response_format": response_format,
                 "temperature": 0.1,
                 "max_tokens": 4096
             }))
@@ -557,9 +808,25 @@ OUTPUT: Valid JSON only, no markdown, no explanation outside JSON."#;
         );
 
         let response = self.call_llm(system_prompt, &user_prompt).await?;
+        Self::parse_plan(&response)
+    }
 
+    /// Parse and validate a query plan from raw LLM output.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - LLM response text (JSON, optionally in a markdown fence)
+    ///
+    /// # Returns
+    ///
+    /// Validated `QueryPlan`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if the JSON is malformed or the plan is invalid
+    pub fn parse_plan(response: &str) -> Result<QueryPlan> {
         // Parse JSON response
-        let plan: QueryPlan = serde_json::from_str(&response)
+        let plan: QueryPlan = serde_json::from_str(&Self::strip_markdown(response))
             .map_err(|e| DatabaseError::LlmError(format!("Failed to parse query plan: {}", e)))?;
 
         // Validate plan
@@ -677,4 +944,116 @@ mod tests {
         assert!(!LlmQueryBuilder::is_entity_lookup(""));
         assert!(!LlmQueryBuilder::is_entity_lookup("a b"));
     }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        // Server that accepts connections and never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let builder = LlmQueryBuilder::new("test-key".to_string(), "gpt-4-turbo".to_string())
+            .with_base_url(format!("http://{}", addr))
+            .with_timeout(Duration::from_millis(200));
+
+        let started = std::time::Instant::now();
+        let result = builder.call_llm("system", "user").await;
+
+        assert!(matches!(result, Err(DatabaseError::Timeout(_))), "got {:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    /// Chat completion server answering `{}`, recording each request's
+    /// `Authorization` header and JSON body.
+    async fn recording_server() -> (String, std::sync::Arc<std::sync::Mutex<Vec<(String, serde_json::Value)>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+
+                // Headers, then Content-Length bytes of body
+                let (head_len, body_len) = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    assert!(n > 0, "connection closed before headers");
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8_lossy(&buf[..end]).to_lowercase();
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|value| value.trim().parse().ok())
+                            .unwrap_or(0);
+                        break (end + 4, length);
+                    }
+                };
+                while buf.len() < head_len + body_len {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+
+                let head = String::from_utf8_lossy(&buf[..head_len]).to_string();
+                let authorization = head
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("authorization").then(|| value.trim().to_string())
+                    })
+                    .unwrap_or_default();
+                let body = serde_json::from_slice(&buf[head_len..head_len + body_len]).unwrap();
+                recorded.lock().unwrap().push((authorization, body));
+
+                let reply = json!({"choices": [{"message": {"content": "{}"}}]}).to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    reply.len(),
+                    reply
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn test_model_override_selects_model_and_key() {
+        std::env::set_var("OPENAI_API_KEY", "test-key");
+        std::env::set_var("CEREBRAS_API_KEY", "cerebras-key");
+
+        let (url, requests) = recording_server().await;
+
+        // Blank overrides fall back to the environment
+        let fallback = LlmQueryBuilder::from_env_with_model(LlmOperation::Query, Some("  ")).unwrap();
+        assert!(!fallback.model().trim().is_empty());
+
+        let builder = LlmQueryBuilder::from_env_with_model(LlmOperation::Query, Some("gpt-4o"))
+            .unwrap()
+            .with_base_url(&url);
+        assert_eq!(builder.model(), "gpt-4o");
+        builder.call_llm("system", "user").await.unwrap();
+
+        let builder = LlmQueryBuilder::from_env_with_model(LlmOperation::Query, Some("cerebras:llama3.1-8b"))
+            .unwrap()
+            .with_base_url(&url);
+        builder.call_llm("system", "user").await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, "Bearer test-key");
+        assert_eq!(requests[0].1["model"], "gpt-4o");
+        assert_eq!(requests[1].0, "Bearer cerebras-key");
+        assert_eq!(requests[1].1["model"], "llama3.1-8b");
+    }
 }
@@ -0,0 +1,393 @@
+//! Retrieval-augmented answers.
+//!
+//! `Database::rag_answer` searches a table for the entities closest to a
+//! question, numbers them into a context block, and asks an LLM to answer
+//! from that context only. The answer comes back with the IDs of the
+//! retrieved entities, in rank order, so callers can cite them:
+//!
+//! ```text
+//! question → [search top_k] → [± neighbor chunks] → [1] ... [2] ... → LLM → answer + source IDs
+//! ```
+//!
+//! `RagAnswer::verify` optionally checks the answer against its sources: a
+//! second LLM pass marks which sources support each sentence, giving a
+//! per-sentence support map and the fraction of sentences that are
+//! grounded. Answers below a threshold are flagged as likely hallucinated.
+//!
+//! Generation and verification go through the `AnswerGenerator` and
+//! `GroundingVerifier` traits so tests and callers can substitute
+//! implementations that don't call an LLM.
+
+use crate::llm::query_builder::LlmQueryBuilder;
+use crate::types::{DatabaseError, Entity, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Maximum characters of each source included in the context.
+pub const MAX_SOURCE_CHARS: usize = 2000;
+
+/// System prompt for grounded answer synthesis.
+const RAG_SYSTEM_PROMPT: &str = "You answer questions using only the numbered sources provided. \
+Cite the sources you use by number, e.g. [1]. If the sources don't contain the answer, say so \
+instead of guessing.";
+
+/// System prompt for grounding verification.
+const VERIFY_SYSTEM_PROMPT: &str = "You check whether statements are supported by numbered sources. \
+For each numbered statement, list the numbers of the sources that directly support it. Use an \
+empty list if no source supports it. Do not use outside knowledge.";
+
+/// Writes an answer to a question from retrieved context.
+#[async_trait]
+pub trait AnswerGenerator: Send + Sync {
+    /// Answer a question.
+    ///
+    /// # Arguments
+    ///
+    /// * `question` - Question as asked
+    /// * `context` - Numbered sources (see `build_context`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if generation fails
+    async fn generate_answer(&self, question: &str, context: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl AnswerGenerator for LlmQueryBuilder {
+    async fn generate_answer(&self, question: &str, context: &str) -> Result<String> {
+        let user_prompt = format!("Sources:\n\n{}\n\nQuestion: {}", context, question);
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {"answer": {"type": "string"}},
+            "required": ["answer"],
+            "additionalProperties": false
+        });
+
+        let response = self.call_llm_with_schema(RAG_SYSTEM_PROMPT, &user_prompt, "rag_answer", schema).await?;
+        let parsed: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| DatabaseError::LlmError(format!("Invalid answer response: {}", e)))?;
+        parsed["answer"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| DatabaseError::LlmError("Answer response has no 'answer' field".to_string()))
+    }
+}
+
+/// Marks which sources support each sentence of an answer.
+#[async_trait]
+pub trait GroundingVerifier: Send + Sync {
+    /// Find the supporting sources of each sentence.
+    ///
+    /// # Arguments
+    ///
+    /// * `sentences` - Answer sentences
+    /// * `context` - Numbered sources the answer was generated from
+    ///
+    /// # Returns
+    ///
+    /// One list per sentence of 1-based source numbers (`[n]` in `context`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if verification fails
+    async fn supporting_sources(&self, sentences: &[String], context: &str) -> Result<Vec<Vec<usize>>>;
+}
+
+#[async_trait]
+impl GroundingVerifier for LlmQueryBuilder {
+    async fn supporting_sources(&self, sentences: &[String], context: &str) -> Result<Vec<Vec<usize>>> {
+        let statements = sentences
+            .iter()
+            .enumerate()
+            .map(|(i, sentence)| format!("{}. {}", i + 1, sentence))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let user_prompt = format!("Sources:\n\n{}\n\nStatements:\n{}", context, statements);
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "statements": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "statement": {"type": "integer"},
+                            "sources": {"type": "array", "items": {"type": "integer"}}
+                        },
+                        "required": ["statement", "sources"],
+                        "additionalProperties": false
+                    }
+                }
+            },
+            "required": ["statements"],
+            "additionalProperties": false
+        });
+
+        let response = self.call_llm_with_schema(VERIFY_SYSTEM_PROMPT, &user_prompt, "grounding", schema).await?;
+        let parsed: serde_json::Value = serde_json::from_str(&response)
+            .map_err(|e| DatabaseError::LlmError(format!("Invalid grounding response: {}", e)))?;
+
+        // Statements the model skipped count as unsupported
+        let mut support = vec![Vec::new(); sentences.len()];
+        for item in parsed["statements"].as_array().into_iter().flatten() {
+            let Some(statement) = item["statement"].as_u64().map(|n| n as usize) else { continue };
+            if let Some(slot) = statement.checked_sub(1).and_then(|i| support.get_mut(i)) {
+                *slot = item["sources"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|n| n.as_u64().map(|n| n as usize))
+                    .collect();
+            }
+        }
+        Ok(support)
+    }
+}
+
+/// Answer synthesized from search results.
+#[derive(Debug, Clone, Serialize)]
+pub struct RagAnswer {
+    /// Question as asked
+    pub question: String,
+    /// Generated answer (empty if nothing was retrieved)
+    pub answer: String,
+    /// Retrieved entity IDs, best first; source `[n]` is `sources[n - 1]`
+    pub sources: Vec<Uuid>,
+    /// Numbered sources the answer was generated from
+    #[serde(skip)]
+    pub context: String,
+    /// Verification result, once `verify` has run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grounding: Option<Grounding>,
+}
+
+/// Sources supporting one answer sentence.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SentenceSupport {
+    pub sentence: String,
+    /// Supporting entity IDs (empty if unsupported)
+    pub sources: Vec<Uuid>,
+}
+
+/// How well an answer is supported by its sources.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Grounding {
+    /// Support map, one entry per answer sentence
+    pub sentences: Vec<SentenceSupport>,
+    /// Fraction of sentences with at least one supporting source
+    pub grounded_fraction: f32,
+    /// Whether `grounded_fraction` is below the verification threshold
+    pub flagged: bool,
+}
+
+impl RagAnswer {
+    /// Check which sources support each sentence of the answer.
+    ///
+    /// Sets `grounding`. Source numbers the verifier reports outside
+    /// `1..=sources.len()` are ignored. An empty answer is fully grounded.
+    ///
+    /// # Arguments
+    ///
+    /// * `verifier` - Grounding verifier (usually the `LlmQueryBuilder` used to answer)
+    /// * `min_grounded_fraction` - Flag the answer below this fraction (e.g. 0.8)
+    ///
+    /// # Returns
+    ///
+    /// The grounding result
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::LlmError` if verification fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut rag = db.rag_answer(&llm, "default", question, "articles", 5).await?;
+    /// if rag.verify(&llm, 0.8).await?.flagged {
+    ///     eprintln!("Answer is poorly grounded");
+    /// }
+    /// ```
+    pub async fn verify(
+        &mut self,
+        verifier: &dyn GroundingVerifier,
+        min_grounded_fraction: f32,
+    ) -> Result<&Grounding> {
+        let sentences = split_sentences(&self.answer);
+        let support = if sentences.is_empty() {
+            Vec::new()
+        } else {
+            verifier.supporting_sources(&sentences, &self.context).await?
+        };
+
+        let sentences: Vec<SentenceSupport> = sentences
+            .into_iter()
+            .enumerate()
+            .map(|(i, sentence)| {
+                let mut sources: Vec<Uuid> = support
+                    .get(i)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|&n| n.checked_sub(1).and_then(|index| self.sources.get(index)).copied())
+                    .collect();
+                sources.dedup();
+                SentenceSupport { sentence, sources }
+            })
+            .collect();
+
+        let grounded_fraction = if sentences.is_empty() {
+            1.0
+        } else {
+            sentences.iter().filter(|s| !s.sources.is_empty()).count() as f32 / sentences.len() as f32
+        };
+
+        Ok(self.grounding.insert(Grounding {
+            sentences,
+            grounded_fraction,
+            flagged: grounded_fraction < min_grounded_fraction,
+        }))
+    }
+}
+
+/// Split an answer into sentences.
+///
+/// Breaks after `.`, `!`, or `?` followed by whitespace (trailing citation
+/// markers like `[1]` stay with their sentence).
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let at_break = matches!(c, '.' | '!' | '?')
+            && !chars.peek().is_some_and(|&(_, next)| !next.is_whitespace());
+        if at_break {
+            let end = i + c.len_utf8();
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+/// Number retrieved entities into a context block.
+///
+/// Each source is `[n] (id)` followed by its text: the `content` property
+/// if it is a string, otherwise the properties as JSON (without the
+/// embedding), cut to `MAX_SOURCE_CHARS`.
+///
+/// # Example
+///
+/// ```text
+/// [1] (2f1c...)
+/// Ownership moves values between bindings.
+///
+/// [2] (9a0b...)
+/// {"title":"Borrowing"}
+/// ```
+pub fn build_context(entities: &[Entity]) -> String {
+    entities
+        .iter()
+        .enumerate()
+        .map(|(i, entity)| format!("[{}] ({})\n{}", i + 1, entity.system.id, source_text(entity)))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Text of an entity as shown to the LLM.
+fn source_text(entity: &Entity) -> String {
+    let text = match entity.properties.get("content") {
+        Some(serde_json::Value::String(content)) => content.clone(),
+        _ => {
+            let mut properties = entity.properties.clone();
+            if let Some(obj) = properties.as_object_mut() {
+                obj.remove("embedding");
+            }
+            properties.to_string()
+        }
+    };
+
+    match text.char_indices().nth(MAX_SOURCE_CHARS) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_context() {
+        let with_content = Entity::new(Uuid::new_v4(), "note".to_string(), json!({"content": "Ownership", "embedding": [1.0]}));
+        let without_content = Entity::new(Uuid::new_v4(), "note".to_string(), json!({"title": "Borrowing", "embedding": [1.0]}));
+        let long = Entity::new(Uuid::new_v4(), "note".to_string(), json!({"content": "x".repeat(MAX_SOURCE_CHARS + 10)}));
+
+        let context = build_context(&[with_content.clone(), without_content.clone(), long]);
+
+        assert!(context.starts_with(&format!("[1] ({})\nOwnership\n\n", with_content.system.id)));
+        assert!(context.contains(&format!("[2] ({})\n{{\"title\":\"Borrowing\"}}", without_content.system.id)));
+        assert!(context.ends_with(&format!("\n{}", "x".repeat(MAX_SOURCE_CHARS))));
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Ownership moves values [1]. Is 2.5 a version? Yes! trailing"),
+            vec!["Ownership moves values [1].", "Is 2.5 a version?", "Yes!", "trailing"]
+        );
+        assert!(split_sentences("  ").is_empty());
+    }
+
+    /// Supports the first sentence with source 1, then sources 2 and 9 (out of range).
+    struct MockVerifier;
+
+    #[async_trait]
+    impl GroundingVerifier for MockVerifier {
+        async fn supporting_sources(&self, sentences: &[String], context: &str) -> Result<Vec<Vec<usize>>> {
+            assert!(context.starts_with("[1]"));
+            Ok(sentences
+                .iter()
+                .enumerate()
+                .map(|(i, _)| match i {
+                    0 => vec![1],
+                    1 => vec![2, 9],
+                    _ => vec![],
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_builds_support_map() {
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut rag = RagAnswer {
+            question: "What is ownership?".to_string(),
+            answer: "Ownership moves values [1]. Borrowing lends them [2]. It was invented in 1850.".to_string(),
+            sources: vec![first, second],
+            context: "[1] (a)\nOwnership\n\n[2] (b)\nBorrowing".to_string(),
+            grounding: None,
+        };
+
+        let grounding = rag.verify(&MockVerifier, 0.8).await.unwrap().clone();
+
+        assert_eq!(grounding.sentences.len(), 3);
+        assert_eq!(grounding.sentences[0].sources, vec![first]);
+        assert_eq!(grounding.sentences[1].sources, vec![second]);
+        assert!(grounding.sentences[2].sources.is_empty());
+        assert!((grounding.grounded_fraction - 2.0 / 3.0).abs() < 1e-6);
+        assert!(grounding.flagged);
+        assert_eq!(rag.grounding.as_ref(), Some(&grounding));
+
+        assert!(!rag.verify(&MockVerifier, 0.5).await.unwrap().flagged);
+    }
+}
@@ -24,6 +24,10 @@ pub enum BackgroundJobType {
     FieldIndexRebuild,
     /// Key index rebuild
     KeyIndexRebuild,
+    /// Edge weight decay/recompute
+    EdgeDecay,
+    /// Purge of expired and soft-deleted entities
+    Vacuum,
 }
 
 impl BackgroundJobType {
@@ -38,6 +42,8 @@ impl BackgroundJobType {
             Self::Compaction => "db.compact",
             Self::FieldIndexRebuild => "index.field.rebuild",
             Self::KeyIndexRebuild => "index.key.rebuild",
+            Self::EdgeDecay => "graph.edge_decay",
+            Self::Vacuum => "db.vacuum",
         }
     }
 }
@@ -67,6 +73,9 @@ pub fn background_span(job_type: BackgroundJobType, target: &str) -> Span {
         otel.kind = "internal",
         job.type = job_type.as_str(),
         job.target = target,
+        job.batch_size = tracing::field::Empty,
+        job.duration_ms = tracing::field::Empty,
+        job.status = tracing::field::Empty,
     )
 }
 
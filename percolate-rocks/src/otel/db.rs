@@ -2,7 +2,7 @@
 //!
 //! Implements OpenTelemetry semantic conventions for RocksDB operations.
 
-use tracing::{span, Level, Span};
+use tracing::{field, span, Level, Span};
 
 /// Database operation types (maps to `db.operation.name`).
 #[derive(Debug, Clone, Copy)]
@@ -19,6 +19,16 @@ pub enum DbOperation {
     BatchWrite,
     /// Compaction
     Compact,
+    /// Insert or upsert entity (schema-validated)
+    Insert,
+    /// Partial entity update
+    Update,
+    /// SQL query
+    Query,
+    /// Semantic vector search
+    Search,
+    /// Graph traversal
+    Traverse,
 }
 
 impl DbOperation {
@@ -31,6 +41,22 @@ impl DbOperation {
             Self::Scan => "scan",
             Self::BatchWrite => "batch_write",
             Self::Compact => "compact",
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Query => "query",
+            Self::Search => "search",
+            Self::Traverse => "traverse",
+        }
+    }
+
+    /// Span level for this operation.
+    ///
+    /// Per-key reads and writes and raw scans run many times per request,
+    /// so they trace at DEBUG; the rest trace at INFO.
+    pub fn level(&self) -> Level {
+        match self {
+            Self::Get | Self::Put | Self::Delete | Self::Scan => Level::DEBUG,
+            _ => Level::INFO,
         }
     }
 }
@@ -65,24 +91,30 @@ pub fn db_span(
         operation.as_str().to_string()
     };
 
-    let span = span!(
-        Level::INFO,
-        "db",
-        otel.name = %span_name,
-        otel.kind = "client",
-        db.system.name = "rocksdb",
-        db.operation.name = operation.as_str(),
-    );
-
-    // Add optional attributes
-    if let Some(coll) = collection {
-        span.record("db.collection.name", coll);
-    }
-    if let Some(ns) = namespace {
-        span.record("db.namespace", ns);
+    // Fields must be declared up front; `Span::record` ignores unknown fields
+    macro_rules! db_span_at {
+        ($level:expr) => {
+            span!(
+                $level,
+                "db",
+                otel.name = %span_name,
+                otel.kind = "client",
+                db.system.name = "rocksdb",
+                db.operation.name = operation.as_str(),
+                db.collection.name = collection,
+                db.namespace = namespace,
+                db.response.returned_rows = field::Empty,
+                db.response.affected_rows = field::Empty,
+            )
+        };
     }
 
-    span
+    // `span!` needs a constant level
+    if operation.level() == Level::DEBUG {
+        db_span_at!(Level::DEBUG)
+    } else {
+        db_span_at!(Level::INFO)
+    }
 }
 
 /// Create database query span (for SQL-like queries).
@@ -112,7 +144,7 @@ pub fn db_query_span(
     collection: &str,
     namespace: Option<&str>,
 ) -> Span {
-    let span = span!(
+    span!(
         Level::INFO,
         "db.query",
         otel.name = format!("query {}", collection),
@@ -121,13 +153,9 @@ pub fn db_query_span(
         db.operation.name = "query",
         db.collection.name = collection,
         db.query.text = query_text,
-    );
-
-    if let Some(ns) = namespace {
-        span.record("db.namespace", ns);
-    }
-
-    span
+        db.namespace = namespace,
+        db.response.returned_rows = field::Empty,
+    )
 }
 
 /// Record database operation metrics in span.
@@ -165,6 +193,15 @@ mod tests {
         assert_eq!(DbOperation::Get.as_str(), "get");
         assert_eq!(DbOperation::Put.as_str(), "put");
         assert_eq!(DbOperation::Scan.as_str(), "scan");
+        assert_eq!(DbOperation::Insert.as_str(), "insert");
+    }
+
+    #[test]
+    fn test_db_operation_levels() {
+        assert_eq!(DbOperation::Get.level(), Level::DEBUG);
+        assert_eq!(DbOperation::Scan.level(), Level::DEBUG);
+        assert_eq!(DbOperation::Search.level(), Level::INFO);
+        assert_eq!(DbOperation::BatchWrite.level(), Level::INFO);
     }
 
     #[test]
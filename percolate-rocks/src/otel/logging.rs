@@ -0,0 +1,59 @@
+//! Log subscriber setup.
+//!
+//! Verbosity comes from `P8_LOG`, falling back to `RUST_LOG`, then `warn`.
+//! Both accept `EnvFilter` directives, e.g. `P8_LOG=percolate_rocks=debug`.
+//! Set `P8_LOG_FORMAT=json` for structured output.
+//!
+//! The filter applies to log output only; `MetricsLayer` sees every span.
+
+use crate::otel::metrics::{metrics, MetricsLayer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// Default filter when neither `P8_LOG` nor `RUST_LOG` is set.
+const DEFAULT_FILTER: &str = "warn";
+
+/// Build the log filter from environment.
+///
+/// # Returns
+///
+/// `EnvFilter` from `P8_LOG`, `RUST_LOG`, or the default
+pub fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_env("P8_LOG")
+        .or_else(|_| EnvFilter::try_from_default_env())
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER))
+}
+
+/// Install a global stderr subscriber for `tracing` spans and events.
+///
+/// Also installs `MetricsLayer` feeding the process-wide `metrics()` registry.
+///
+/// Safe to call more than once; later calls are no-ops.
+///
+/// # Returns
+///
+/// `true` if this call installed the subscriber
+///
+/// # Example
+///
+/// ```rust,ignore
+/// percolate_rocks::otel::init_logging();
+/// ```
+pub fn init_logging() -> bool {
+    let json = std::env::var("P8_LOG_FORMAT")
+        .map(|f| f.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    let fmt_layer = fmt::layer().with_writer(std::io::stderr);
+    let fmt_layer = if json {
+        fmt_layer.json().boxed()
+    } else {
+        fmt_layer.boxed()
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(env_filter()))
+        .with(MetricsLayer::new(metrics()))
+        .try_init()
+        .is_ok()
+}
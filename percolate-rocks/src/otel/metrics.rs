@@ -0,0 +1,289 @@
+//! In-process operation metrics.
+//!
+//! `MetricsLayer` is a `tracing` layer that times every `db` / `db.query`
+//! span (see `db_span`) and feeds a `MetricsRegistry`: a counter and a
+//! latency histogram per `db.operation.name`. Embedding calls are counted
+//! by the providers themselves (one per backend request, via
+//! `record_embedding_calls`); LLM calls via `record_llm_call`.
+//!
+//! The registry renders Prometheus text format, so the node can be scraped
+//! without an OTLP collector.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Latency histogram bucket upper bounds (seconds).
+pub const LATENCY_BUCKETS: [f64; 10] = [0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// Counter and latency histogram for one operation.
+#[derive(Debug, Clone, Default)]
+struct OperationStats {
+    count: u64,
+    sum_seconds: f64,
+    /// Per-bucket (non-cumulative) counts; last slot is `+Inf`
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+}
+
+impl OperationStats {
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|le| seconds <= *le)
+            .unwrap_or(LATENCY_BUCKETS.len());
+
+        self.count += 1;
+        self.sum_seconds += seconds;
+        self.buckets[bucket] += 1;
+    }
+}
+
+/// Process-wide operation counters and latency histograms.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    operations: Mutex<BTreeMap<String, OperationStats>>,
+    embedding_calls: AtomicU64,
+    llm_calls: Mutex<BTreeMap<String, u64>>,
+}
+
+impl MetricsRegistry {
+    /// Create empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one completed database operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - Operation name (`db.operation.name`)
+    /// * `elapsed` - Operation latency
+    pub fn record_operation(&self, operation: &str, elapsed: Duration) {
+        if let Ok(mut ops) = self.operations.lock() {
+            ops.entry(operation.to_string()).or_default().observe(elapsed);
+        }
+    }
+
+    /// Record embedding provider calls.
+    pub fn record_embedding_calls(&self, calls: u64) {
+        self.embedding_calls.fetch_add(calls, Ordering::Relaxed);
+    }
+
+    /// Record one LLM call.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - LLM provider name (e.g. `openai`)
+    pub fn record_llm_call(&self, provider: &str) {
+        if let Ok(mut calls) = self.llm_calls.lock() {
+            *calls.entry(provider.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Number of completed operations with this name.
+    pub fn operation_count(&self, operation: &str) -> u64 {
+        self.operations
+            .lock()
+            .ok()
+            .and_then(|ops| ops.get(operation).map(|s| s.count))
+            .unwrap_or(0)
+    }
+
+    /// Number of embedding provider calls.
+    pub fn embedding_calls(&self) -> u64 {
+        self.embedding_calls.load(Ordering::Relaxed)
+    }
+
+    /// Append metrics in Prometheus text exposition format.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Buffer to append to
+    pub fn render_prometheus(&self, out: &mut String) {
+        let ops = self.operations.lock().map(|o| o.clone()).unwrap_or_default();
+
+        let _ = writeln!(out, "# HELP rem_db_operations_total Database operations completed.");
+        let _ = writeln!(out, "# TYPE rem_db_operations_total counter");
+        for (op, stats) in &ops {
+            let _ = writeln!(out, "rem_db_operations_total{{operation=\"{}\"}} {}", escape_label(op), stats.count);
+        }
+
+        let _ = writeln!(out, "# HELP rem_db_operation_duration_seconds Database operation latency.");
+        let _ = writeln!(out, "# TYPE rem_db_operation_duration_seconds histogram");
+        for (op, stats) in &ops {
+            let op = escape_label(op);
+            let mut cumulative = 0;
+            for (i, le) in LATENCY_BUCKETS.iter().enumerate() {
+                cumulative += stats.buckets[i];
+                let _ = writeln!(
+                    out,
+                    "rem_db_operation_duration_seconds_bucket{{operation=\"{}\",le=\"{}\"}} {}",
+                    op, le, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "rem_db_operation_duration_seconds_bucket{{operation=\"{}\",le=\"+Inf\"}} {}",
+                op, stats.count
+            );
+            let _ = writeln!(out, "rem_db_operation_duration_seconds_sum{{operation=\"{}\"}} {}", op, stats.sum_seconds);
+            let _ = writeln!(out, "rem_db_operation_duration_seconds_count{{operation=\"{}\"}} {}", op, stats.count);
+        }
+
+        let _ = writeln!(out, "# HELP rem_embedding_calls_total Embedding provider calls.");
+        let _ = writeln!(out, "# TYPE rem_embedding_calls_total counter");
+        let _ = writeln!(out, "rem_embedding_calls_total {}", self.embedding_calls());
+
+        let _ = writeln!(out, "# HELP rem_llm_calls_total LLM API calls.");
+        let _ = writeln!(out, "# TYPE rem_llm_calls_total counter");
+        if let Ok(calls) = self.llm_calls.lock() {
+            for (provider, count) in calls.iter() {
+                let _ = writeln!(out, "rem_llm_calls_total{{provider=\"{}\"}} {}", escape_label(provider), count);
+            }
+        }
+    }
+}
+
+/// Escape a Prometheus label value.
+pub(crate) fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Get the process-wide registry.
+///
+/// `init_logging` installs a `MetricsLayer` feeding this registry.
+pub fn metrics() -> Arc<MetricsRegistry> {
+    static GLOBAL: OnceLock<Arc<MetricsRegistry>> = OnceLock::new();
+    GLOBAL.get_or_init(|| Arc::new(MetricsRegistry::new())).clone()
+}
+
+/// Record embedding provider calls in the process-wide registry.
+pub fn record_embedding_calls(calls: u64) {
+    metrics().record_embedding_calls(calls);
+}
+
+/// Record one LLM call in the process-wide registry.
+pub fn record_llm_call(provider: &str) {
+    metrics().record_llm_call(provider);
+}
+
+/// Tracing layer timing database spans into a `MetricsRegistry`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(MetricsLayer::new(metrics()))
+///     .init();
+/// ```
+pub struct MetricsLayer {
+    registry: Arc<MetricsRegistry>,
+}
+
+impl MetricsLayer {
+    /// Create layer feeding `registry`.
+    pub fn new(registry: Arc<MetricsRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+/// Timing state stored in span extensions.
+struct SpanTiming {
+    operation: String,
+    start: Instant,
+}
+
+/// Extracts `db.operation.name` from span attributes.
+#[derive(Default)]
+struct OperationVisitor(Option<String>);
+
+impl Visit for OperationVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "db.operation.name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl<S> Layer<S> for MetricsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        if name != "db" && name != "db.query" {
+            return;
+        }
+
+        let mut visitor = OperationVisitor::default();
+        attrs.record(&mut visitor);
+
+        if let (Some(operation), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SpanTiming {
+                operation,
+                start: Instant::now(),
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(timing) = span.extensions().get::<SpanTiming>() {
+                self.registry.record_operation(&timing.operation, timing.start.elapsed());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::otel::{db_span, DbOperation};
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_layer_counts_db_spans() {
+        let registry = Arc::new(MetricsRegistry::new());
+        let subscriber = tracing_subscriber::registry().with(MetricsLayer::new(registry.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..3 {
+                let _span = db_span(DbOperation::Insert, Some("articles"), None).entered();
+            }
+            let _span = db_span(DbOperation::Get, Some("articles"), None).entered();
+        });
+
+        assert_eq!(registry.operation_count("insert"), 3);
+        assert_eq!(registry.operation_count("get"), 1);
+        assert_eq!(registry.operation_count("delete"), 0);
+    }
+
+    #[test]
+    fn test_render_histogram() {
+        let registry = MetricsRegistry::new();
+        registry.record_operation("get", Duration::from_micros(100));
+        registry.record_operation("get", Duration::from_secs(10));
+        registry.record_llm_call("openai");
+
+        let mut out = String::new();
+        registry.render_prometheus(&mut out);
+
+        assert!(out.contains("rem_db_operations_total{operation=\"get\"} 2"));
+        assert!(out.contains("rem_db_operation_duration_seconds_bucket{operation=\"get\",le=\"0.0005\"} 1"));
+        assert!(out.contains("rem_db_operation_duration_seconds_bucket{operation=\"get\",le=\"5\"} 1"));
+        assert!(out.contains("rem_db_operation_duration_seconds_bucket{operation=\"get\",le=\"+Inf\"} 2"));
+        assert!(out.contains("rem_llm_calls_total{provider=\"openai\"} 1"));
+    }
+}
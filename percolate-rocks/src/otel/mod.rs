@@ -26,6 +26,17 @@
 //! - Use `INTERNAL` span kind
 //! - Custom attributes: `job.type`, `job.status`, `job.batch_size`
 //!
+//! # Verbosity
+//!
+//! `init_logging()` installs a stderr subscriber filtered by `P8_LOG`
+//! (or `RUST_LOG`).
+//!
+//! # Metrics
+//!
+//! `init_logging()` also installs `MetricsLayer`, which times `db` spans
+//! into the process-wide `metrics()` registry. `serve_metrics` exposes it
+//! at `/metrics` in Prometheus text format.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -41,7 +52,13 @@
 pub mod db;
 pub mod background;
 pub mod context;
+pub mod logging;
+pub mod metrics;
+pub mod prometheus;
 
 pub use db::{db_span, db_query_span, record_db_metrics, DbOperation};
 pub use background::{background_span, record_background_metrics, BackgroundJobType};
 pub use context::{attach_trace_context, extract_trace_context, TraceContext};
+pub use logging::{env_filter, init_logging};
+pub use metrics::{metrics, record_embedding_calls, record_llm_call, MetricsLayer, MetricsRegistry};
+pub use prometheus::{render_metrics, serve_metrics};
@@ -0,0 +1,217 @@
+//! Prometheus `/metrics` endpoint.
+//!
+//! Serves `MetricsRegistry` counters plus point-in-time database gauges
+//! (query cache hit rate, column family key estimates) over plain HTTP/1.1.
+//! Only `GET /metrics` is answered; everything else gets `404`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:9090").await?;
+//! serve_metrics(listener, Arc::new(db), metrics()).await?;
+//! ```
+
+use crate::database::Database;
+use crate::otel::metrics::{escape_label, MetricsRegistry};
+use crate::storage::column_families::{all_column_families, CF_INDEXES, CF_KEY_INDEX};
+use crate::types::Result;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Prometheus text exposition content type.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Largest request head accepted.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Render registry counters and database gauges in Prometheus text format.
+///
+/// # Arguments
+///
+/// * `db` - Database to read gauges from
+/// * `registry` - Operation counters and histograms
+///
+/// # Returns
+///
+/// Prometheus text exposition body
+pub fn render_metrics(db: &Database, registry: &MetricsRegistry) -> String {
+    let mut out = String::new();
+    registry.render_prometheus(&mut out);
+
+    if let Some(stats) = db.query_cache_stats() {
+        let lookups = stats.hits + stats.misses;
+        let hit_ratio = if lookups == 0 { 0.0 } else { stats.hits as f64 / lookups as f64 };
+
+        let _ = writeln!(out, "# HELP rem_query_cache_hits_total Query cache hits.");
+        let _ = writeln!(out, "# TYPE rem_query_cache_hits_total counter");
+        let _ = writeln!(out, "rem_query_cache_hits_total {}", stats.hits);
+        let _ = writeln!(out, "# HELP rem_query_cache_misses_total Query cache misses.");
+        let _ = writeln!(out, "# TYPE rem_query_cache_misses_total counter");
+        let _ = writeln!(out, "rem_query_cache_misses_total {}", stats.misses);
+        let _ = writeln!(out, "# HELP rem_query_cache_hit_ratio Query cache hits over lookups.");
+        let _ = writeln!(out, "# TYPE rem_query_cache_hit_ratio gauge");
+        let _ = writeln!(out, "rem_query_cache_hit_ratio {}", hit_ratio);
+        let _ = writeln!(out, "# HELP rem_query_cache_entries Cached query results.");
+        let _ = writeln!(out, "# TYPE rem_query_cache_entries gauge");
+        let _ = writeln!(out, "rem_query_cache_entries {}", stats.entries);
+    }
+
+    let storage = db.storage();
+    let estimate = |cf: &str| {
+        storage.db()
+            .property_int_value_cf(&storage.cf_handle(cf), "rocksdb.estimate-num-keys")
+            .ok()
+            .flatten()
+            .unwrap_or(0)
+    };
+
+    let _ = writeln!(out, "# HELP rem_index_entries Estimated index entries.");
+    let _ = writeln!(out, "# TYPE rem_index_entries gauge");
+    let _ = writeln!(out, "rem_index_entries{{index=\"field\"}} {}", estimate(CF_INDEXES));
+    let _ = writeln!(out, "rem_index_entries{{index=\"key\"}} {}", estimate(CF_KEY_INDEX));
+
+    let _ = writeln!(out, "# HELP rem_field_index_lookups_total Field index lookups served.");
+    let _ = writeln!(out, "# TYPE rem_field_index_lookups_total counter");
+    let _ = writeln!(out, "rem_field_index_lookups_total {}", db.field_index().lookup_count());
+
+    let _ = writeln!(out, "# HELP rem_column_family_keys Estimated keys per column family.");
+    let _ = writeln!(out, "# TYPE rem_column_family_keys gauge");
+    for cf in all_column_families() {
+        let _ = writeln!(out, "rem_column_family_keys{{cf=\"{}\"}} {}", escape_label(cf), estimate(cf));
+    }
+
+    out
+}
+
+/// Serve `GET /metrics` until the listener fails.
+///
+/// # Arguments
+///
+/// * `listener` - Bound TCP listener
+/// * `db` - Database to read gauges from
+/// * `registry` - Operation counters and histograms
+///
+/// # Errors
+///
+/// Returns `DatabaseError::IoError` if accepting a connection fails
+pub async fn serve_metrics(
+    listener: TcpListener,
+    db: Arc<Database>,
+    registry: Arc<MetricsRegistry>,
+) -> Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let db = db.clone();
+        let registry = registry.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &db, &registry).await {
+                tracing::debug!(%peer, error = %e, "metrics request failed");
+            }
+        });
+    }
+}
+
+/// Answer a single request and close the connection.
+async fn handle_connection(
+    mut stream: TcpStream,
+    db: &Database,
+    registry: &MetricsRegistry,
+) -> Result<()> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+
+    // Read the request head; the body (if any) is ignored
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&buf);
+    let mut request_line = head.lines().next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("");
+    let path = request_line.next().unwrap_or("").split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => ("200 OK", CONTENT_TYPE, render_metrics(db, registry)),
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::otel::MetricsLayer;
+    use tracing_subscriber::prelude::*;
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let db = Arc::new(Database::open_temp().unwrap().with_query_cache(8));
+        let registry = Arc::new(MetricsRegistry::new());
+
+        let schema = serde_json::json!({
+            "title": "Article",
+            "version": "1.0.0",
+            "short_name": "article",
+            "properties": {
+                "title": {"type": "string"}
+            },
+            "required": ["title"]
+        });
+
+        // Route this test's spans to its own registry
+        let subscriber = tracing_subscriber::registry().with(MetricsLayer::new(registry.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            db.register_schema("article", schema).unwrap();
+            for i in 0..3 {
+                db.insert("tenant1", "article", serde_json::json!({"title": format!("a{}", i)})).unwrap();
+            }
+            db.count("tenant1", "article", false).unwrap();
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_metrics(listener, db.clone(), registry.clone()));
+
+        let response = get(addr, "/metrics").await;
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains(CONTENT_TYPE));
+        assert!(response.contains("rem_db_operations_total{operation=\"insert\"} 3"));
+        assert!(response.contains("rem_db_operation_duration_seconds_count{operation=\"insert\"} 3"));
+        assert!(response.contains("rem_db_operation_duration_seconds_bucket{operation=\"insert\",le=\"+Inf\"} 3"));
+        assert!(response.contains("rem_embedding_calls_total"));
+        assert!(response.contains("rem_query_cache_hit_ratio"));
+        assert!(response.contains("rem_index_entries{index=\"key\"}"));
+        assert!(response.contains("rem_column_family_keys{cf=\"entities\"}"));
+
+        assert!(get(addr, "/other").await.starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Bounded query result cache.
+//!
+//! Caches query results keyed by `(tenant, normalized_query, scan_limit)`.
+//! The scan limit is part of the key because the same SQL can succeed
+//! unbounded and fail bounded. Each entry records the table it read from so
+//! a write to that table drops every cached result that could have
+//! observed it. Results built from entities with a TTL also record the
+//! earliest `expires_at` among them and miss from then on, since expiry
+//! changes what a query sees without any write.
+//!
+//! Eviction is least-recently-used once `capacity` entries are held.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Cache key: tenant, normalized query text, and scan limit.
+type CacheKey = (String, String, Option<usize>);
+
+/// Cached query result.
+struct CacheEntry {
+    table: String,
+    expires_at: Option<DateTime<Utc>>,
+    result: serde_json::Value,
+    last_used: u64,
+}
+
+/// Cache hit/miss counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryCacheStats {
+    /// Lookups served from cache
+    pub hits: u64,
+    /// Lookups that missed
+    pub misses: u64,
+    /// Entries currently cached
+    pub entries: usize,
+}
+
+/// LRU cache of query results with per-table invalidation.
+pub struct QueryCache {
+    capacity: usize,
+    entries: HashMap<CacheKey, CacheEntry>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl QueryCache {
+    /// Create cache holding at most `capacity` results.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum cached results (0 disables caching)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Look up a cached result.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `query` - Query text (normalized internally)
+    /// * `scan_limit` - Scan limit the query runs under (`None` = unbounded)
+    ///
+    /// # Returns
+    ///
+    /// Cached result if present
+    pub fn get(&mut self, tenant_id: &str, query: &str, scan_limit: Option<usize>) -> Option<serde_json::Value> {
+        self.tick += 1;
+        let key = (tenant_id.to_string(), normalize_query(query), scan_limit);
+
+        let expired = self.entries
+            .get(&key)
+            .and_then(|entry| entry.expires_at)
+            .is_some_and(|expires_at| expires_at <= Utc::now());
+        if expired {
+            self.entries.remove(&key);
+        }
+
+        match self.entries.get_mut(&key) {
+            Some(entry) => {
+                entry.last_used = self.tick;
+                self.hits += 1;
+                Some(entry.result.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store a result, evicting the least recently used entry if full.
+    ///
+    /// # Arguments
+    ///
+    /// * `tenant_id` - Tenant identifier
+    /// * `query` - Query text (normalized internally)
+    /// * `scan_limit` - Scan limit the result was computed under
+    /// * `table` - Table the query read from
+    /// * `expires_at` - Earliest expiry among the entities read (`None` if none expire)
+    /// * `result` - Query result
+    pub fn put(
+        &mut self,
+        tenant_id: &str,
+        query: &str,
+        scan_limit: Option<usize>,
+        table: &str,
+        expires_at: Option<DateTime<Utc>>,
+        result: serde_json::Value,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+        let key = (tenant_id.to_string(), normalize_query(query), scan_limit);
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let oldest = self.entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(key, CacheEntry {
+            table: table.to_string(),
+            expires_at,
+            result,
+            last_used: self.tick,
+        });
+    }
+
+    /// Drop all cached results that read from `table`.
+    ///
+    /// # Returns
+    ///
+    /// Number of entries removed
+    pub fn invalidate_table(&mut self, table: &str) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.table != table);
+        before - self.entries.len()
+    }
+
+    /// Drop all cached results.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Get hit/miss counters.
+    pub fn stats(&self) -> QueryCacheStats {
+        QueryCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+        }
+    }
+
+    /// Get configured capacity.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Normalize query text for cache keys.
+///
+/// Trims, drops a trailing `;`, and collapses whitespace runs outside
+/// quoted literals so formatting differences share one entry. Quotes inside
+/// a literal must be doubled (`'it''s'`), as in SQL; see `quote_literal`.
+pub fn normalize_query(query: &str) -> String {
+    let trimmed = query.trim().trim_end_matches(';').trim_end();
+    let mut out = String::with_capacity(trimmed.len());
+    let mut quote: Option<char> = None;
+    let mut pending_space = false;
+
+    for c in trimmed.chars() {
+        match quote {
+            Some(q) => {
+                out.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space {
+                    out.push(' ');
+                    pending_space = false;
+                }
+                if c == '\'' || c == '"' {
+                    quote = Some(c);
+                }
+                out.push(c);
+            }
+        }
+    }
+
+    out
+}
+
+/// Quote text as a single-quoted literal, doubling embedded quotes.
+///
+/// Use when building cache keys from free text, so `normalize_query`
+/// keeps the whole text inside the literal.
+pub fn quote_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!(
+            normalize_query("  SELECT *\n  FROM person   WHERE name = 'a  b' ; "),
+            "SELECT * FROM person WHERE name = 'a  b'"
+        );
+
+        // Doubled quotes stay inside the literal
+        let key = |text| format!("SEARCH {} IN docs", quote_literal(text));
+        assert_eq!(normalize_query(&key("it's  here")), "SEARCH 'it''s  here' IN docs");
+        assert_ne!(normalize_query(&key("it's  here")), normalize_query(&key("it's here")));
+    }
+
+    #[test]
+    fn test_scan_limit_is_part_of_key() {
+        let mut cache = QueryCache::new(4);
+        cache.put("t1", "SELECT * FROM logs", None, "logs", None, json!([1, 2, 3]));
+
+        assert!(cache.get("t1", "SELECT * FROM logs", Some(2)).is_none());
+        assert_eq!(cache.get("t1", "SELECT * FROM logs", None), Some(json!([1, 2, 3])));
+    }
+
+    #[test]
+    fn test_hit_and_miss() {
+        let mut cache = QueryCache::new(4);
+        assert!(cache.get("t1", "SELECT * FROM person", None).is_none());
+
+        cache.put("t1", "SELECT * FROM person", None, "person", None, json!([1]));
+        assert_eq!(cache.get("t1", "SELECT *  FROM person", None), Some(json!([1])));
+
+        // Tenant is part of the key
+        assert!(cache.get("t2", "SELECT * FROM person", None).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 2);
+    }
+
+    #[test]
+    fn test_invalidate_table() {
+        let mut cache = QueryCache::new(4);
+        cache.put("t1", "SELECT * FROM person", None, "person", None, json!([]));
+        cache.put("t1", "SELECT * FROM company", None, "company", None, json!([]));
+
+        assert_eq!(cache.invalidate_table("person"), 1);
+        assert!(cache.get("t1", "SELECT * FROM person", None).is_none());
+        assert!(cache.get("t1", "SELECT * FROM company", None).is_some());
+    }
+
+    #[test]
+    fn test_expired_entry_misses() {
+        let mut cache = QueryCache::new(4);
+        let now = Utc::now();
+        cache.put("t1", "SELECT * FROM sessions", None, "sessions", Some(now - chrono::Duration::seconds(1)), json!([1]));
+        cache.put("t1", "SELECT * FROM users", None, "users", Some(now + chrono::Duration::hours(1)), json!([2]));
+
+        assert!(cache.get("t1", "SELECT * FROM sessions", None).is_none());
+        assert_eq!(cache.stats().entries, 1);
+        assert_eq!(cache.get("t1", "SELECT * FROM users", None), Some(json!([2])));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = QueryCache::new(2);
+        cache.put("t1", "q1", None, "a", None, json!(1));
+        cache.put("t1", "q2", None, "a", None, json!(2));
+
+        // Touch q1 so q2 is least recently used
+        cache.get("t1", "q1", None);
+        cache.put("t1", "q3", None, "a", None, json!(3));
+
+        assert!(cache.get("t1", "q1", None).is_some());
+        assert!(cache.get("t1", "q2", None).is_none());
+        assert!(cache.get("t1", "q3", None).is_some());
+    }
+}
@@ -0,0 +1,165 @@
+//! Cursor-paginated SQL execution.
+//!
+//! `SqlCursor` runs a SELECT one page at a time: each call to `next_page`
+//! scans the next `page_size` entities of the table (resuming after the
+//! last entity ID seen), applies WHERE and projection, and returns the
+//! matching rows. Memory stays bounded by the page size regardless of
+//! table size, and no scan limit applies.
+//!
+//! Queries that need the whole result set (ORDER BY, aggregates) or that
+//! are served from the key index run once through `Database::query_sql`
+//! and are then handed out page by page.
+
+use crate::database::Database;
+use crate::query::executor::{execute_page, offset_and_limit};
+use crate::types::Result;
+use sqlparser::ast::Statement;
+
+/// Default rows per page.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Where the next page comes from.
+#[derive(Debug)]
+enum CursorSource {
+    /// Resume the table scan after this entity
+    Scan { after: Option<uuid::Uuid> },
+    /// Rows of an already-executed query
+    Materialized(std::vec::IntoIter<serde_json::Value>),
+}
+
+/// Paginated SQL query in progress.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut cursor = db.query_sql_cursor("tenant1", "SELECT * FROM logs WHERE level = 'warn'", 500)?;
+/// while let Some(rows) = cursor.next_page(&db)? {
+///     for row in rows {
+///         process(row);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SqlCursor {
+    tenant_id: String,
+    table: String,
+    statement: Statement,
+    page_size: usize,
+    source: CursorSource,
+    /// OFFSET rows still to skip
+    skip: usize,
+    /// LIMIT rows still to return
+    remaining: Option<usize>,
+    exhausted: bool,
+}
+
+impl SqlCursor {
+    /// Cursor that scans `table` page by page.
+    pub(crate) fn scan(tenant_id: &str, table: String, statement: Statement, page_size: usize) -> Self {
+        let (skip, remaining) = offset_and_limit(&statement);
+
+        Self {
+            tenant_id: tenant_id.to_string(),
+            table,
+            statement,
+            page_size: page_size.max(1),
+            source: CursorSource::Scan { after: None },
+            skip,
+            remaining,
+            exhausted: false,
+        }
+    }
+
+    /// Cursor over rows that were already computed.
+    pub(crate) fn materialized(
+        tenant_id: &str,
+        table: String,
+        statement: Statement,
+        rows: Vec<serde_json::Value>,
+        page_size: usize,
+    ) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            table,
+            statement,
+            page_size: page_size.max(1),
+            source: CursorSource::Materialized(rows.into_iter()),
+            // Already applied by the executor
+            skip: 0,
+            remaining: None,
+            exhausted: false,
+        }
+    }
+
+    /// Table being queried.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Whether rows are read lazily from storage (false if materialized up front).
+    pub fn is_streaming(&self) -> bool {
+        matches!(self.source, CursorSource::Scan { .. })
+    }
+
+    /// Whether all rows have been returned.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Fetch the next page of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - Database the cursor was opened on
+    ///
+    /// # Returns
+    ///
+    /// Next non-empty page, or `None` once the query is exhausted
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError` if the storage scan fails
+    pub fn next_page(&mut self, db: &Database) -> Result<Option<Vec<serde_json::Value>>> {
+        loop {
+            if self.exhausted || self.remaining == Some(0) {
+                self.exhausted = true;
+                return Ok(None);
+            }
+
+            let after = match &mut self.source {
+                CursorSource::Materialized(rows) => {
+                    let page: Vec<_> = rows.by_ref().take(self.page_size).collect();
+                    if page.is_empty() {
+                        self.exhausted = true;
+                        return Ok(None);
+                    }
+                    return Ok(Some(page));
+                }
+                CursorSource::Scan { after } => *after,
+            };
+
+            let entities = db.list_page(&self.tenant_id, &self.table, after, self.page_size)?;
+            if entities.len() < self.page_size {
+                self.exhausted = true;
+            }
+            if let Some(last) = entities.last() {
+                self.source = CursorSource::Scan { after: Some(last.system.id) };
+            }
+
+            let mut rows = execute_page(&self.statement, entities)?;
+
+            let skipped = self.skip.min(rows.len());
+            rows.drain(..skipped);
+            self.skip -= skipped;
+
+            if let Some(remaining) = self.remaining {
+                rows.truncate(remaining);
+                self.remaining = Some(remaining - rows.len());
+            }
+
+            if !rows.is_empty() {
+                return Ok(Some(rows));
+            }
+        }
+    }
+}
@@ -64,6 +64,73 @@ pub fn execute_query(statement: &Statement, entities: Vec<Entity>) -> Result<ser
     Err(DatabaseError::QueryError("Invalid query structure".to_string()))
 }
 
+/// Whether a query can be executed page by page.
+///
+/// Aggregates and ORDER BY need the full result set; WHERE, projection,
+/// OFFSET, and LIMIT apply row by row.
+pub fn is_streamable(statement: &Statement) -> bool {
+    if let Statement::Query(query) = statement {
+        if let sqlparser::ast::SetExpr::Select(select) = query.body.as_ref() {
+            let has_agg = select.projection.iter().any(|item| {
+                matches!(item, SelectItem::UnnamedExpr(Expr::Function(_)))
+            });
+            return !has_agg && query.order_by.is_none();
+        }
+    }
+
+    false
+}
+
+/// Apply WHERE and projection to one page of entities.
+///
+/// OFFSET and LIMIT are not applied; callers track them across pages
+/// (see `offset_and_limit`).
+pub fn execute_page(statement: &Statement, entities: Vec<Entity>) -> Result<Vec<serde_json::Value>> {
+    if let Statement::Query(query) = statement {
+        if let sqlparser::ast::SetExpr::Select(select) = query.body.as_ref() {
+            let filtered = match select.selection {
+                Some(ref selection) => entities.into_iter()
+                    .filter(|entity| evaluate_where(selection, entity))
+                    .collect(),
+                None => entities,
+            };
+
+            return project_fields(select, filtered);
+        }
+    }
+
+    Err(DatabaseError::QueryError("Invalid query structure".to_string()))
+}
+
+/// Whether an entity satisfies a query's WHERE clause (true if there is none).
+pub fn matches_where(statement: &Statement, entity: &Entity) -> bool {
+    if let Statement::Query(query) = statement {
+        if let sqlparser::ast::SetExpr::Select(select) = query.body.as_ref() {
+            return select.selection.as_ref().map_or(true, |selection| evaluate_where(selection, entity));
+        }
+    }
+
+    false
+}
+
+/// OFFSET and LIMIT of a query (`0` and `None` if absent).
+pub fn offset_and_limit(statement: &Statement) -> (usize, Option<usize>) {
+    let Statement::Query(query) = statement else {
+        return (0, None);
+    };
+
+    let offset = match query.offset {
+        Some(sqlparser::ast::Offset { value: Expr::Value(Value::Number(ref n, _)), .. }) => n.parse().unwrap_or(0),
+        _ => 0,
+    };
+    let limit = match query.limit {
+        Some(Expr::Value(Value::Number(ref n, _))) => n.parse().ok(),
+        _ => None,
+    };
+
+    (offset, limit)
+}
+
 /// Evaluate WHERE condition.
 fn evaluate_where(expr: &Expr, entity: &Entity) -> bool {
     match expr {
@@ -291,7 +358,7 @@ fn apply_order_by(entities: &mut [Entity], order_by: &sqlparser::ast::OrderBy) -
 }
 
 /// Compare values for sorting.
-fn compare_for_sort(a: &serde_json::Value, b: &serde_json::Value) -> std::cmp::Ordering {
+pub(crate) fn compare_for_sort(a: &serde_json::Value, b: &serde_json::Value) -> std::cmp::Ordering {
     use serde_json::Value;
 
     match (a, b) {
@@ -6,13 +6,23 @@
 //! - `LOOKUP 'key1', 'key2'` - Key-based entity lookup
 //! - `TRAVERSE FROM 'uuid' DEPTH n DIRECTION dir` - Graph traversal
 //! - `SEARCH 'query' IN table` - Semantic search
+//!
+//! Results can be cached in-process (see `cache::QueryCache`) or streamed
+//! page by page (see `cursor::SqlCursor`).
 
 pub mod parser;
 pub mod executor;
 pub mod predicates;
 pub mod planner;
 pub mod extended;
+pub mod cache;
+pub mod cursor;
+pub mod sort;
 
 pub use extended::{
     parse_extended_query, ExtendedQuery, KeyLookupQuery, TraverseQuery, SearchQuery, TraverseDirection
 };
+pub use cache::{QueryCache, QueryCacheStats};
+pub use cursor::SqlCursor;
+pub use predicates::{CompareOp, Predicate};
+pub use sort::SortOrder;
@@ -104,6 +104,57 @@ pub fn has_aggregates(statement: &Statement) -> bool {
     false
 }
 
+/// Extract the literal of a top-level `field = 'value'` predicate.
+///
+/// Only equality conjuncts reachable through `AND` are considered, since an
+/// `OR` branch could match rows the equality alone would not.
+///
+/// # Arguments
+///
+/// * `statement` - Parsed SELECT statement
+/// * `field` - Field name to look for
+///
+/// # Returns
+///
+/// Literal value as string if the WHERE clause pins `field` to a single value
+pub fn extract_equality_predicate(statement: &Statement, field: &str) -> Option<String> {
+    if let Statement::Query(query) = statement {
+        if let sqlparser::ast::SetExpr::Select(select) = query.body.as_ref() {
+            return select.selection.as_ref().and_then(|expr| find_equality(expr, field));
+        }
+    }
+    None
+}
+
+fn find_equality(expr: &Expr, field: &str) -> Option<String> {
+    use sqlparser::ast::{BinaryOperator, Value};
+
+    match expr {
+        Expr::Nested(inner) => find_equality(inner, field),
+        Expr::BinaryOp { left, op: BinaryOperator::And, right } => {
+            find_equality(left, field).or_else(|| find_equality(right, field))
+        }
+        Expr::BinaryOp { left, op: BinaryOperator::Eq, right } => {
+            let name = match left.as_ref() {
+                Expr::Identifier(ident) => ident.value.as_str(),
+                Expr::CompoundIdentifier(parts) => parts.last()?.value.as_str(),
+                _ => return None,
+            };
+            if name != field {
+                return None;
+            }
+            match right.as_ref() {
+                Expr::Value(Value::SingleQuotedString(s)) | Expr::Value(Value::DoubleQuotedString(s)) => {
+                    Some(s.clone())
+                }
+                Expr::Value(Value::Number(n, _)) => Some(n.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +208,17 @@ mod tests {
         let table = extract_table_name(&stmt).unwrap();
         assert_eq!(table, "person");
     }
+
+    #[test]
+    fn test_extract_equality_predicate() {
+        let stmt = parse_sql("SELECT * FROM person WHERE name = 'Alice' AND age > 30").unwrap();
+        assert_eq!(extract_equality_predicate(&stmt, "name"), Some("Alice".to_string()));
+        assert_eq!(extract_equality_predicate(&stmt, "age"), None);
+
+        let stmt = parse_sql("SELECT * FROM person WHERE name = 'Alice' OR age > 30").unwrap();
+        assert_eq!(extract_equality_predicate(&stmt, "name"), None);
+
+        let stmt = parse_sql("SELECT * FROM person").unwrap();
+        assert_eq!(extract_equality_predicate(&stmt, "name"), None);
+    }
 }
@@ -1,6 +1,91 @@
 //! Predicate evaluation for WHERE clauses.
+//!
+//! `Predicate` is the structured form used by filtered search; equality on
+//! an indexed field can be answered from `CF_INDEXES` instead of a scan.
 
 use crate::types::{Result, Entity};
+use serde::{Deserialize, Serialize};
+
+/// Comparison operator for a field predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    /// `=`
+    Eq,
+    /// `!=`
+    NotEq,
+    /// `<`
+    Lt,
+    /// `<=`
+    Lte,
+    /// `>`
+    Gt,
+    /// `>=`
+    Gte,
+}
+
+impl CompareOp {
+    /// Get SQL operator symbol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::NotEq => "!=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+            Self::Gt => ">",
+            Self::Gte => ">=",
+        }
+    }
+}
+
+/// Single-field predicate on entity properties.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Predicate {
+    /// Property name
+    pub field: String,
+    /// Comparison operator
+    pub op: CompareOp,
+    /// Value to compare against
+    pub value: serde_json::Value,
+}
+
+impl Predicate {
+    /// Create predicate.
+    pub fn new(field: impl Into<String>, op: CompareOp, value: serde_json::Value) -> Self {
+        Self {
+            field: field.into(),
+            op,
+            value,
+        }
+    }
+
+    /// Create equality predicate.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let filter = Predicate::eq("category", json!("tutorial"));
+    /// ```
+    pub fn eq(field: impl Into<String>, value: serde_json::Value) -> Self {
+        Self::new(field, CompareOp::Eq, value)
+    }
+
+    /// Check whether this predicate can be answered by a field index lookup.
+    pub fn is_equality(&self) -> bool {
+        self.op == CompareOp::Eq
+    }
+
+    /// Test predicate against entity properties.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the field is missing
+    pub fn matches(&self, entity: &Entity) -> bool {
+        match entity.properties.get(&self.field) {
+            Some(actual) => PredicateEvaluator::compare(actual, self.op.as_str(), &self.value),
+            None => false,
+        }
+    }
+}
 
 /// Predicate evaluator for filtering entities.
 pub struct PredicateEvaluator;
@@ -36,6 +121,64 @@ impl PredicateEvaluator {
     ///
     /// Comparison result
     pub fn compare(left: &serde_json::Value, op: &str, right: &serde_json::Value) -> bool {
-        todo!("Implement PredicateEvaluator::compare")
+        use serde_json::Value;
+        use std::cmp::Ordering;
+
+        let ordering = match (left, right) {
+            // Numbers that don't fit an f64 are incomparable, not zero
+            (Value::Number(a), Value::Number(b)) => match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => None,
+            },
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            _ if left == right => Some(Ordering::Equal),
+            _ => None,
+        };
+
+        match (op, ordering) {
+            ("=", Some(o)) => o == Ordering::Equal,
+            ("!=", Some(o)) => o != Ordering::Equal,
+            ("!=", None) => true,
+            ("<", Some(o)) => o == Ordering::Less,
+            ("<=", Some(o)) => o != Ordering::Greater,
+            (">", Some(o)) => o == Ordering::Greater,
+            (">=", Some(o)) => o != Ordering::Less,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare() {
+        assert!(PredicateEvaluator::compare(&json!(5), ">", &json!(3)));
+        assert!(PredicateEvaluator::compare(&json!(3.0), "=", &json!(3)));
+        assert!(PredicateEvaluator::compare(&json!("a"), "<", &json!("b")));
+        assert!(PredicateEvaluator::compare(&json!("a"), "!=", &json!(1)));
+        assert!(!PredicateEvaluator::compare(&json!("a"), "=", &json!(1)));
+
+        // Non-numeric values never satisfy a range filter
+        assert!(!PredicateEvaluator::compare(&json!("abc"), "<", &json!(5)));
+        assert!(!PredicateEvaluator::compare(&json!(null), ">=", &json!(0)));
+        assert!(!PredicateEvaluator::compare(&json!([1]), "<=", &json!(0)));
+    }
+
+    #[test]
+    fn test_predicate_matches() {
+        let entity = Entity::new(
+            uuid::Uuid::new_v4(),
+            "article".to_string(),
+            json!({"category": "tutorial", "views": 10}),
+        );
+
+        assert!(Predicate::eq("category", json!("tutorial")).matches(&entity));
+        assert!(!Predicate::eq("category", json!("news")).matches(&entity));
+        assert!(Predicate::new("views", CompareOp::Gte, json!(10)).matches(&entity));
+        assert!(!Predicate::eq("missing", json!(1)).matches(&entity));
     }
 }
@@ -0,0 +1,120 @@
+//! Sorting entities by a field.
+//!
+//! Used by `Database::list` when `sort_by` is given. The field is looked up
+//! in properties first, then in system fields (`id`, `entity_type`,
+//! `created_at`, `modified_at`, `deleted_at`). Entities without the field
+//! sort first in ascending order. The sort is stable, so ties keep storage
+//! key order.
+
+use crate::query::executor::compare_for_sort;
+use crate::types::Entity;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+/// Sort direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Smallest first
+    #[default]
+    Asc,
+
+    /// Largest first
+    Desc,
+}
+
+impl SortOrder {
+    /// Parse order from string (`asc`/`ascending`, `desc`/`descending`).
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Order string
+    ///
+    /// # Returns
+    ///
+    /// Parsed order or None if invalid
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "asc" | "ascending" => Some(Self::Asc),
+            "desc" | "descending" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    /// Convert order to string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Asc => "asc",
+            Self::Desc => "desc",
+        }
+    }
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Get a field value for sorting (property, then system field).
+fn sort_value<'a>(entity: &'a Entity, field: &str) -> Option<Cow<'a, serde_json::Value>> {
+    if let Some(value) = entity.properties.get(field) {
+        return Some(Cow::Borrowed(value));
+    }
+
+    let system = &entity.system;
+    let value = match field {
+        "id" => system.id.to_string(),
+        "entity_type" => system.entity_type.clone(),
+        "created_at" => system.created_at.clone(),
+        "modified_at" => system.modified_at.clone(),
+        "deleted_at" => system.deleted_at.clone()?,
+        _ => return None,
+    };
+    Some(Cow::Owned(serde_json::Value::String(value)))
+}
+
+/// Sort entities in place by a field.
+///
+/// # Arguments
+///
+/// * `entities` - Entities to sort
+/// * `field` - Property or system field name
+/// * `order` - Sort direction
+pub fn sort_entities(entities: &mut [Entity], field: &str, order: SortOrder) {
+    entities.sort_by(|a, b| {
+        let cmp = match (sort_value(a, field), sort_value(b, field)) {
+            (Some(av), Some(bv)) => compare_for_sort(&av, &bv),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+
+        match order {
+            SortOrder::Asc => cmp,
+            SortOrder::Desc => cmp.reverse(),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_entities_stable() {
+        let entity = |rank: i64| Entity::new(uuid::Uuid::new_v4(), "item".to_string(), serde_json::json!({"rank": rank}));
+        let mut entities = vec![entity(2), entity(1), entity(2), Entity::new(uuid::Uuid::new_v4(), "item".to_string(), serde_json::json!({}))];
+        let tied = (entities[0].system.id, entities[2].system.id);
+
+        sort_entities(&mut entities, "rank", SortOrder::Desc);
+        assert_eq!(entities[0].system.id, tied.0);
+        assert_eq!(entities[1].system.id, tied.1);
+        assert_eq!(entities[2].properties["rank"], 1);
+        assert!(entities[3].properties.get("rank").is_none());
+
+        assert_eq!(SortOrder::from_str("DESC"), Some(SortOrder::Desc));
+        assert_eq!(SortOrder::from_str("sideways"), None);
+    }
+}
@@ -8,7 +8,7 @@ pub mod replica;
 pub mod protocol;
 pub mod sync;
 
-pub use wal::{WriteAheadLog, WalEntry, WalOperation};
+pub use wal::{WriteAheadLog, WalEntry, WalOperation, WalSyncPolicy};
 pub use primary::PrimaryNode;
 pub use replica::ReplicaNode;
 pub use sync::SyncStateMachine;
@@ -38,6 +38,14 @@ pub fn to_proto_op(op: &WalOperation) -> pb::WalOperationProto {
                 entity_id: entity_id.clone(),
             })
         }
+        WalOperation::Touch { tenant_id, entity_id, modified_at, expires_at } => {
+            Operation::Touch(pb::TouchOp {
+                tenant_id: tenant_id.clone(),
+                entity_id: entity_id.clone(),
+                modified_at: modified_at.clone(),
+                expires_at: expires_at.clone().unwrap_or_default(),
+            })
+        }
     };
 
     pb::WalOperationProto {
@@ -74,6 +82,14 @@ pub fn from_proto_op(proto: pb::WalOperationProto) -> Result<WalOperation, Strin
                 entity_id: op.entity_id,
             })
         }
+        Some(Operation::Touch(op)) => {
+            Ok(WalOperation::Touch {
+                tenant_id: op.tenant_id,
+                entity_id: op.entity_id,
+                modified_at: op.modified_at,
+                expires_at: Some(op.expires_at).filter(|expires_at| !expires_at.is_empty()),
+            })
+        }
         None => Err("Missing operation in protobuf".to_string()),
     }
 }
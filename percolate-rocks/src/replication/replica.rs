@@ -198,6 +198,12 @@ impl ReplicaNode {
                         .map_err(|e| DatabaseError::ReplicationError(format!("Invalid UUID: {}", e)))?;
                     self.database.delete(&tenant_id, uuid)?;
                 }
+                WalOperation::Touch { tenant_id, entity_id, modified_at, expires_at } => {
+                    // Parse UUID from string
+                    let uuid = uuid::Uuid::parse_str(&entity_id)
+                        .map_err(|e| DatabaseError::ReplicationError(format!("Invalid UUID: {}", e)))?;
+                    self.database.apply_touch(&tenant_id, uuid, modified_at, expires_at)?;
+                }
             }
 
             // Update last sync time
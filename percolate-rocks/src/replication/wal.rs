@@ -3,6 +3,20 @@
 //! WAL stores all write operations for replication and crash recovery.
 //! Entries are stored in RocksDB WAL column family with sequence numbers as keys.
 //!
+//! # Sync Policy
+//!
+//! `WalSyncPolicy` controls when appended entries are fsynced to disk.
+//! A process crash never loses appended entries (they are in the OS page
+//! cache); the data-loss window below applies to power loss or OS crash:
+//!
+//! | Policy           | fsync                                   | Loss window                          |
+//! |------------------|-----------------------------------------|--------------------------------------|
+//! | `PerWrite`       | every `append` (default)                | none                                 |
+//! | `Interval(d)`    | first `append` at least `d` after the last sync | up to `d` of writes, plus any tail written since the last append |
+//! | `OnBatch`        | end of `append_batch`, or explicit `sync` | single appends since the last batch/sync |
+//!
+//! Unsynced entries are also synced when the WAL is dropped.
+//!
 //! # Encryption (TODO)
 //!
 //! **Current**: WAL entries stored unencrypted
@@ -25,6 +39,21 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// When WAL entries are fsynced to disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WalSyncPolicy {
+    /// fsync every append; nothing acknowledged is lost
+    #[default]
+    PerWrite,
+
+    /// fsync at most once per interval; up to one interval of writes at risk
+    Interval(Duration),
+
+    /// fsync once per `append_batch` (or explicit `sync`)
+    OnBatch,
+}
 
 /// WAL entry representing a database operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +82,13 @@ pub enum WalOperation {
         tenant_id: String,
         entity_id: String,
     },
+    /// System-field refresh from `Database::touch` (properties unchanged).
+    Touch {
+        tenant_id: String,
+        entity_id: String,
+        modified_at: String,
+        expires_at: Option<String>,
+    },
 }
 
 /// Write-ahead log for durability and replication.
@@ -77,6 +113,11 @@ pub enum WalOperation {
 pub struct WriteAheadLog {
     storage: Arc<Storage>,
     current_seq: Arc<AtomicU64>,
+    sync_policy: WalSyncPolicy,
+    /// Last time the WAL was fsynced
+    last_sync: Instant,
+    /// Entries appended since the last fsync
+    unsynced: u64,
 }
 
 impl WriteAheadLog {
@@ -109,9 +150,102 @@ impl WriteAheadLog {
         Ok(Self {
             storage,
             current_seq: Arc::new(AtomicU64::new(current_seq)),
+            sync_policy: WalSyncPolicy::default(),
+            last_sync: Instant::now(),
+            unsynced: 0,
         })
     }
 
+    /// Set the sync policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - When to fsync appended entries (default: `PerWrite`)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let wal = WriteAheadLog::new(storage)?
+    ///     .with_sync_policy(WalSyncPolicy::Interval(Duration::from_millis(100)));
+    /// ```
+    pub fn with_sync_policy(mut self, policy: WalSyncPolicy) -> Self {
+        self.set_sync_policy(policy);
+        self
+    }
+
+    /// Change the sync policy, syncing any pending entries first.
+    pub fn set_sync_policy(&mut self, policy: WalSyncPolicy) {
+        if self.unsynced > 0 {
+            // Best effort: a failed sync leaves the entries pending
+            let _ = self.sync();
+        }
+        self.sync_policy = policy;
+    }
+
+    /// Get the sync policy.
+    pub fn sync_policy(&self) -> WalSyncPolicy {
+        self.sync_policy
+    }
+
+    /// Number of appended entries not yet fsynced.
+    pub fn unsynced_entries(&self) -> u64 {
+        self.unsynced
+    }
+
+    /// fsync all appended entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if the fsync fails
+    pub fn sync(&mut self) -> Result<()> {
+        self.storage.db().flush_wal(true)?;
+        self.last_sync = Instant::now();
+        self.unsynced = 0;
+        Ok(())
+    }
+
+    /// Write entries and fsync according to the policy.
+    fn write_entries(&mut self, entries: &[WalEntry], batch_end: bool) -> Result<()> {
+        let cf = self.storage.cf_handle(CF_WAL);
+        let mut batch = rocksdb::WriteBatch::default();
+        for entry in entries {
+            // Key format: "wal:{seq}" (zero-padded for sorting)
+            let key = format!("wal:{:020}", entry.seq);
+            batch.put_cf(&cf, key.as_bytes(), serde_json::to_vec(entry)?);
+        }
+
+        let sync_now = match self.sync_policy {
+            WalSyncPolicy::PerWrite => true,
+            WalSyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+            WalSyncPolicy::OnBatch => batch_end,
+        };
+
+        let mut opts = rocksdb::WriteOptions::default();
+        opts.set_sync(sync_now);
+        self.storage.db().write_opt(batch, &opts)?;
+
+        if sync_now {
+            self.last_sync = Instant::now();
+            self.unsynced = 0;
+        } else {
+            self.unsynced += entries.len() as u64;
+        }
+
+        Ok(())
+    }
+
+    /// Create the next entry for an operation.
+    fn next_entry(&self, op: WalOperation) -> WalEntry {
+        // Get next sequence number (atomic increment)
+        let seq = self.current_seq.fetch_add(1, Ordering::SeqCst) + 1;
+
+        WalEntry {
+            seq,
+            op,
+            timestamp: Utc::now().to_rfc3339(),
+        }
+    }
+
     /// Read latest sequence number from storage.
     ///
     /// # Arguments
@@ -166,26 +300,33 @@ impl WriteAheadLog {
     /// let seq = wal.append(op)?;
     /// ```
     pub fn append(&mut self, op: WalOperation) -> Result<u64> {
-        // Get next sequence number (atomic increment)
-        let seq = self.current_seq.fetch_add(1, Ordering::SeqCst) + 1;
-
-        // Create WAL entry
-        let entry = WalEntry {
-            seq,
-            op,
-            timestamp: Utc::now().to_rfc3339(),
-        };
-
-        // Serialize with JSON (supports serde_json::Value)
-        let value = serde_json::to_vec(&entry)?;
+        let entry = self.next_entry(op);
+        self.write_entries(std::slice::from_ref(&entry), false)?;
 
-        // Key format: "wal:{seq}" (zero-padded for sorting)
-        let key = format!("wal:{:020}", seq);
+        Ok(entry.seq)
+    }
 
-        // Write to WAL CF
-        self.storage.put(CF_WAL, key.as_bytes(), &value)?;
+    /// Append several entries in one atomic write.
+    ///
+    /// Under `OnBatch` this is the sync point; `PerWrite` also syncs once
+    /// for the whole batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `ops` - Operations to log, in order
+    ///
+    /// # Returns
+    ///
+    /// Sequence numbers of the logged entries
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if the write or fsync fails
+    pub fn append_batch(&mut self, ops: Vec<WalOperation>) -> Result<Vec<u64>> {
+        let entries: Vec<WalEntry> = ops.into_iter().map(|op| self.next_entry(op)).collect();
+        self.write_entries(&entries, true)?;
 
-        Ok(seq)
+        Ok(entries.iter().map(|e| e.seq).collect())
     }
 
     /// Get WAL entry by sequence number.
@@ -336,6 +477,14 @@ impl WriteAheadLog {
     }
 }
 
+impl Drop for WriteAheadLog {
+    fn drop(&mut self) {
+        if self.unsynced > 0 {
+            let _ = self.sync();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,4 +627,64 @@ mod tests {
             assert!(wal.get(5).unwrap().is_some());
         }
     }
+
+    #[test]
+    fn test_per_write_sync_survives_reopen() {
+        let path = tempfile::tempdir().unwrap();
+        let path_str = path.path().to_str().unwrap().to_string();
+
+        {
+            let storage = Arc::new(Storage::open(&path_str, None).unwrap());
+            let mut wal = WriteAheadLog::new(storage).unwrap();
+            assert_eq!(wal.sync_policy(), WalSyncPolicy::PerWrite);
+
+            for i in 0..3 {
+                wal.append(WalOperation::Insert {
+                    tenant_id: "tenant-1".to_string(),
+                    entity: serde_json::json!({"id": i}),
+                }).unwrap();
+                // Durable as soon as append returns
+                assert_eq!(wal.unsynced_entries(), 0);
+            }
+
+            // Nothing pending, so drop has nothing left to sync
+            drop(wal);
+        }
+
+        let storage = Arc::new(Storage::open(&path_str, None).unwrap());
+        let wal = WriteAheadLog::new(storage).unwrap();
+        assert_eq!(wal.current_position(), 3);
+        assert!(wal.get(3).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_interval_and_batch_sync_policies() {
+        let storage = Arc::new(Storage::open_temp().unwrap());
+        let op = |i: i32| WalOperation::Insert {
+            tenant_id: "tenant-1".to_string(),
+            entity: serde_json::json!({"id": i}),
+        };
+
+        // Interval: writes accumulate until the interval elapses
+        let mut wal = WriteAheadLog::new(storage.clone()).unwrap()
+            .with_sync_policy(WalSyncPolicy::Interval(Duration::from_millis(50)));
+        for i in 0..5 {
+            wal.append(op(i)).unwrap();
+        }
+        assert_eq!(wal.unsynced_entries(), 5);
+
+        std::thread::sleep(Duration::from_millis(60));
+        wal.append(op(5)).unwrap();
+        assert_eq!(wal.unsynced_entries(), 0);
+
+        // OnBatch: single appends wait for the next batch
+        wal.set_sync_policy(WalSyncPolicy::OnBatch);
+        wal.append(op(6)).unwrap();
+        assert_eq!(wal.unsynced_entries(), 1);
+
+        let seqs = wal.append_batch(vec![op(7), op(8)]).unwrap();
+        assert_eq!(seqs, vec![8, 9]);
+        assert_eq!(wal.unsynced_entries(), 0);
+        assert_eq!(wal.get_entries_after(0, 100).unwrap().len(), 9);
+    }
 }
@@ -0,0 +1,365 @@
+//! Rust type generation from registered schemas.
+//!
+//! Emits one `serde` struct per schema so Rust consumers get compile-time
+//! types matching what they registered. JSON Schema types map as:
+//!
+//! | JSON Schema                     | Rust                     |
+//! |---------------------------------|--------------------------|
+//! | `string` (incl. enums, formats) | `String`                 |
+//! | `integer`                       | `i64`                    |
+//! | `number`                        | `f64`                    |
+//! | `boolean`                       | `bool`                   |
+//! | `array` of `T`                  | `Vec<T>`                 |
+//! | `$ref` to `$defs/Model`         | `Model` (also generated) |
+//! | `anyOf [T, null]`               | `Option<T>`              |
+//! | `object`, anything else         | `serde_json::Value`      |
+//!
+//! Fields not listed in `required` become `Option<T>`. Field names that
+//! aren't valid snake_case identifiers are renamed with `#[serde(rename)]`.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Rust keywords that can't be used as plain field names.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+    "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Generate a Rust module with one struct per schema.
+///
+/// Nested Pydantic models (`$defs`) are emitted once each, alongside the
+/// schemas that reference them.
+///
+/// # Arguments
+///
+/// * `schemas` - `(schema name, JSON Schema)` pairs, emitted in order
+///
+/// # Returns
+///
+/// Rust source code
+pub fn generate_rust_types(schemas: &[(String, Value)]) -> String {
+    let mut structs: BTreeMap<String, String> = BTreeMap::new();
+    let mut order = Vec::new();
+
+    for (name, schema) in schemas {
+        if let Some(defs) = schema.get("$defs").or_else(|| schema.get("definitions")).and_then(Value::as_object) {
+            for (def_name, def) in defs {
+                let type_name = to_pascal_case(def_name);
+                if !structs.contains_key(&type_name) {
+                    structs.insert(type_name.clone(), generate_struct(&type_name, def));
+                    order.push(type_name);
+                }
+            }
+        }
+
+        let type_name = struct_name(name, schema);
+        structs.insert(type_name.clone(), generate_struct(&type_name, schema));
+        order.retain(|n| n != &type_name);
+        order.push(type_name);
+    }
+
+    let mut out = String::new();
+    out.push_str("//! Types generated from registered REM schemas. Do not edit.\n\n");
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+    for type_name in &order {
+        out.push('\n');
+        out.push_str(&structs[type_name]);
+    }
+    out
+}
+
+/// Struct name for a schema: its `title`, else the PascalCase schema name.
+pub fn struct_name(name: &str, schema: &Value) -> String {
+    schema
+        .get("title")
+        .and_then(Value::as_str)
+        .map(to_pascal_case)
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| to_pascal_case(name))
+}
+
+/// Generate one struct definition.
+fn generate_struct(type_name: &str, schema: &Value) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|arr| arr.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut out = String::new();
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        write_doc(&mut out, "", description);
+    }
+    out.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    let _ = writeln!(out, "pub struct {} {{", type_name);
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (field, field_schema) in properties {
+            if let Some(description) = field_schema.get("description").and_then(Value::as_str) {
+                write_doc(&mut out, "    ", description);
+            }
+
+            let (ident, renamed) = field_ident(field);
+            let mut rust_type = rust_type(field_schema);
+            let optional = !required.contains(&field.as_str());
+
+            let mut serde_attrs = Vec::new();
+            if renamed {
+                serde_attrs.push(format!("rename = {:?}", field));
+            }
+            if optional && !rust_type.starts_with("Option<") {
+                rust_type = format!("Option<{}>", rust_type);
+            }
+            if optional {
+                serde_attrs.push("default".to_string());
+                serde_attrs.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if !serde_attrs.is_empty() {
+                let _ = writeln!(out, "    #[serde({})]", serde_attrs.join(", "));
+            }
+
+            let _ = writeln!(out, "    pub {}: {},", ident, rust_type);
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Map a JSON Schema property to a Rust type.
+fn rust_type(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .map(to_pascal_case)
+            .unwrap_or_else(|| "serde_json::Value".to_string());
+    }
+
+    // Pydantic emits Optional[T] as anyOf [T, {"type": "null"}]
+    if let Some(variants) = schema.get("anyOf").or_else(|| schema.get("oneOf")).and_then(Value::as_array) {
+        let non_null: Vec<&Value> = variants.iter().filter(|v| !is_null_type(v)).collect();
+        let inner = match non_null.as_slice() {
+            [single] => rust_type(single),
+            _ => "serde_json::Value".to_string(),
+        };
+        return if non_null.len() < variants.len() {
+            format!("Option<{}>", inner)
+        } else {
+            inner
+        };
+    }
+
+    match schema.get("type") {
+        Some(Value::String(ty)) => scalar_type(ty, schema),
+        // ["string", "null"]
+        Some(Value::Array(types)) => {
+            let non_null: Vec<&str> = types.iter().filter_map(Value::as_str).filter(|t| *t != "null").collect();
+            let inner = match non_null.as_slice() {
+                [single] => scalar_type(single, schema),
+                _ => "serde_json::Value".to_string(),
+            };
+            if non_null.len() < types.len() {
+                format!("Option<{}>", inner)
+            } else {
+                inner
+            }
+        }
+        _ if schema.get("enum").is_some() => "String".to_string(),
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+/// Map a single JSON Schema type name.
+fn scalar_type(ty: &str, schema: &Value) -> String {
+    match ty {
+        "string" => "String".to_string(),
+        "integer" => "i64".to_string(),
+        "number" => "f64".to_string(),
+        "boolean" => "bool".to_string(),
+        "array" => {
+            let item = schema.get("items").map(rust_type).unwrap_or_else(|| "serde_json::Value".to_string());
+            format!("Vec<{}>", item)
+        }
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+fn is_null_type(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+/// Rust field identifier for a JSON property, and whether it needs a rename.
+fn field_ident(field: &str) -> (String, bool) {
+    let mut ident = to_snake_case(field);
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident = format!("field_{}", ident);
+    }
+    let renamed = ident != field;
+
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        // `self`/`Self`/`crate`/`super` can't be raw identifiers
+        if matches!(ident.as_str(), "self" | "Self" | "crate" | "super") {
+            return (format!("{}_", ident), true);
+        }
+        return (format!("r#{}", ident), renamed);
+    }
+
+    (ident, renamed)
+}
+
+/// Convert to snake_case, replacing non-identifier characters with `_`.
+fn to_snake_case(s: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+
+    for c in s.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !out.ends_with('_') {
+                out.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+
+    out
+}
+
+/// Convert to PascalCase, dropping non-identifier characters.
+fn to_pascal_case(s: &str) -> String {
+    let mut out = String::new();
+
+    for word in s.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+fn write_doc(out: &mut String, indent: &str, text: &str) {
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            let _ = writeln!(out, "{}///", indent);
+        } else {
+            let _ = writeln!(out, "{}/// {}", indent, line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render a parsed type back to compact text (e.g. `Option<Vec<String>>`).
+    fn type_to_string(ty: &syn::Type) -> String {
+        match ty {
+            syn::Type::Path(path) => path
+                .path
+                .segments
+                .iter()
+                .map(|segment| match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => {
+                        let inner: Vec<String> = args
+                            .args
+                            .iter()
+                            .filter_map(|arg| match arg {
+                                syn::GenericArgument::Type(t) => Some(type_to_string(t)),
+                                _ => None,
+                            })
+                            .collect();
+                        format!("{}<{}>", segment.ident, inner.join(", "))
+                    }
+                    _ => segment.ident.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("::"),
+            _ => panic!("unexpected type"),
+        }
+    }
+
+    #[test]
+    fn test_generated_code_parses() {
+        let schema = serde_json::json!({
+            "title": "Article",
+            "description": "Blog article",
+            "version": "1.0.0",
+            "properties": {
+                "title": {"type": "string", "description": "Headline"},
+                "word_count": {"type": "integer"},
+                "score": {"type": "number"},
+                "published": {"type": "boolean"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "author": {"$ref": "#/$defs/Author"},
+                "summary": {"anyOf": [{"type": "string"}, {"type": "null"}]},
+                "metadata": {"type": "object"},
+                "type": {"type": "string"},
+                "publishedAt": {"type": "string", "format": "date-time"}
+            },
+            "required": ["title", "word_count", "score", "published", "tags", "author", "type"],
+            "$defs": {
+                "Author": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}},
+                    "required": ["name"]
+                }
+            }
+        });
+
+        let code = generate_rust_types(&[("articles".to_string(), schema)]);
+        let file = syn::parse_file(&code).expect("generated code parses");
+
+        let structs: BTreeMap<String, Vec<(String, String)>> = file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Struct(s) => Some((
+                    s.ident.to_string(),
+                    s.fields
+                        .iter()
+                        .map(|f| (f.ident.as_ref().unwrap().to_string(), type_to_string(&f.ty)))
+                        .collect(),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(structs["Author"], vec![("name".to_string(), "String".to_string())]);
+
+        let fields: BTreeMap<String, String> = structs["Article"].iter().cloned().collect();
+        assert_eq!(fields["title"], "String");
+        assert_eq!(fields["word_count"], "i64");
+        assert_eq!(fields["score"], "f64");
+        assert_eq!(fields["published"], "bool");
+        assert_eq!(fields["tags"], "Vec<String>");
+        assert_eq!(fields["author"], "Author");
+        assert_eq!(fields["summary"], "Option<String>");
+        assert_eq!(fields["metadata"], "Option<serde_json::Value>");
+        assert_eq!(fields["r#type"], "String");
+        assert_eq!(fields["published_at"], "Option<String>");
+
+        assert!(code.contains("#[serde(rename = \"publishedAt\", default, skip_serializing_if = \"Option::is_none\")]"));
+        assert!(code.contains("/// Headline"));
+    }
+}
@@ -0,0 +1,75 @@
+//! Per-schema delete policy.
+//!
+//! Set via `json_schema_extra.delete_policy`:
+//! - `soft_only`: entities are only ever marked deleted (audit logs);
+//!   `hard_delete` is refused
+//! - `hard_only`: deletes always purge (caches); `delete` removes the
+//!   entity instead of marking it
+//! - `either` (default): caller chooses `delete` or `hard_delete`
+
+use serde::{Deserialize, Serialize};
+
+/// How entities of a schema may be deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletePolicy {
+    /// Soft delete only; hard deletes are refused
+    SoftOnly,
+
+    /// Every delete is a hard delete
+    HardOnly,
+
+    /// Soft or hard delete, as requested
+    #[default]
+    Either,
+}
+
+impl DeletePolicy {
+    /// Parse policy from string.
+    ///
+    /// Accepts snake_case (`soft_only`) or PascalCase (`SoftOnly`).
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - Policy string
+    ///
+    /// # Returns
+    ///
+    /// Parsed policy or None if invalid
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.replace('_', "").to_lowercase().as_str() {
+            "softonly" => Some(Self::SoftOnly),
+            "hardonly" => Some(Self::HardOnly),
+            "either" => Some(Self::Either),
+            _ => None,
+        }
+    }
+
+    /// Convert policy to string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SoftOnly => "soft_only",
+            Self::HardOnly => "hard_only",
+            Self::Either => "either",
+        }
+    }
+}
+
+impl std::fmt::Display for DeletePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_policy_from_str() {
+        assert_eq!(DeletePolicy::from_str("soft_only"), Some(DeletePolicy::SoftOnly));
+        assert_eq!(DeletePolicy::from_str("HardOnly"), Some(DeletePolicy::HardOnly));
+        assert_eq!(DeletePolicy::from_str("either"), Some(DeletePolicy::Either));
+        assert_eq!(DeletePolicy::from_str("sometimes"), None);
+    }
+}
@@ -0,0 +1,221 @@
+//! JSON Schema inference from sample records.
+//!
+//! Builds a schema that accepts every sample, descending into structure
+//! rather than stopping at `object`/`array`:
+//!
+//! - Nested objects get their own `properties` and `required`
+//! - Arrays get `items` inferred from all elements, so arrays of objects
+//!   describe the element shape
+//! - A field is required if every sample has it with a non-null value
+//! - Conflicting types become a type list (`["string", "null"]`);
+//!   `integer` and `number` widen to `number`
+//!
+//! ```rust,ignore
+//! let schema = infer_schema("person", &[json!({"name": "Alice", "tags": [{"label": "x"}]})])?;
+//! db.register_schema("person", schema)?;
+//! ```
+
+use crate::types::{DatabaseError, Result};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Accumulated shape of the values seen at one position.
+#[derive(Debug, Default)]
+struct Shape {
+    /// JSON Schema type names seen
+    types: BTreeSet<&'static str>,
+    /// Objects seen (for deciding `required`)
+    objects: usize,
+    /// Property shapes with the number of objects holding a non-null value
+    properties: BTreeMap<String, (usize, Shape)>,
+    /// Shape of array elements
+    items: Option<Box<Shape>>,
+}
+
+impl Shape {
+    fn observe(&mut self, value: &Value) {
+        match value {
+            Value::Null => {
+                self.types.insert("null");
+            }
+            Value::Bool(_) => {
+                self.types.insert("boolean");
+            }
+            Value::Number(n) => {
+                self.types.insert(if n.is_f64() { "number" } else { "integer" });
+            }
+            Value::String(_) => {
+                self.types.insert("string");
+            }
+            Value::Array(elements) => {
+                self.types.insert("array");
+                let items = self.items.get_or_insert_with(Default::default);
+                for element in elements {
+                    items.observe(element);
+                }
+            }
+            Value::Object(map) => {
+                self.types.insert("object");
+                self.objects += 1;
+                for (key, value) in map {
+                    let (present, shape) = self.properties.entry(key.clone()).or_default();
+                    if !value.is_null() {
+                        *present += 1;
+                    }
+                    shape.observe(value);
+                }
+            }
+        }
+    }
+
+    fn to_schema(&self) -> Value {
+        let mut types = self.types.clone();
+        if types.contains("number") {
+            types.remove("integer");
+        }
+
+        let mut schema = Map::new();
+        match types.len() {
+            0 => {}
+            1 => {
+                schema.insert("type".to_string(), json!(types.iter().next()));
+            }
+            _ => {
+                schema.insert("type".to_string(), json!(types));
+            }
+        }
+
+        if types.contains("object") {
+            let properties: Map<String, Value> = self.properties
+                .iter()
+                .map(|(key, (_, shape))| (key.clone(), shape.to_schema()))
+                .collect();
+            let required: Vec<&String> = self.properties
+                .iter()
+                .filter(|(_, (present, _))| *present == self.objects)
+                .map(|(key, _)| key)
+                .collect();
+            schema.insert("properties".to_string(), Value::Object(properties));
+            schema.insert("required".to_string(), json!(required));
+        }
+
+        if let Some(items) = self.items.as_ref().filter(|items| !items.types.is_empty()) {
+            schema.insert("items".to_string(), items.to_schema());
+        }
+
+        Value::Object(schema)
+    }
+}
+
+/// Infer a registrable schema from sample records.
+///
+/// # Arguments
+///
+/// * `name` - Schema name (used as `title` and `short_name`)
+/// * `records` - Sample records; every one must be a JSON object
+///
+/// # Returns
+///
+/// Schema with `title`, `version`, `short_name`, `properties`, and `required`
+///
+/// # Errors
+///
+/// Returns `DatabaseError::ValidationError` if there are no records or a
+/// record is not an object
+pub fn infer_schema(name: &str, records: &[Value]) -> Result<Value> {
+    if records.is_empty() {
+        return Err(DatabaseError::validation("Cannot infer a schema from zero records"));
+    }
+
+    let mut shape = Shape::default();
+    for (index, record) in records.iter().enumerate() {
+        if !record.is_object() {
+            return Err(DatabaseError::validation(format!(
+                "Record {} is not a JSON object",
+                index
+            )));
+        }
+        shape.observe(record);
+    }
+
+    let mut schema = shape.to_schema();
+    let obj = schema.as_object_mut().expect("records are objects");
+    obj.insert("title".to_string(), json!(name));
+    obj.insert("version".to_string(), json!("1.0.0"));
+    obj.insert("short_name".to_string(), json!(name));
+    Ok(schema)
+}
+
+/// Infer the JSON Schema of a single value.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// assert_eq!(infer_type(&json!([1, 2.5])), json!({"type": "array", "items": {"type": "number"}}));
+/// ```
+pub fn infer_type(value: &Value) -> Value {
+    let mut shape = Shape::default();
+    shape.observe(value);
+    shape.to_schema()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::SchemaValidator;
+
+    #[test]
+    fn test_infer_nested_objects_and_arrays_of_objects() {
+        let record = json!({
+            "name": "Alice",
+            "address": {"city": "Paris", "zip": "75001"},
+            "orders": [
+                {"sku": "A1", "quantity": 2},
+                {"sku": "B2", "quantity": 1, "note": "gift"}
+            ]
+        });
+
+        let schema = infer_schema("customer", &[record.clone()]).unwrap();
+
+        assert_eq!(schema["properties"]["address"]["properties"]["city"], json!({"type": "string"}));
+        assert_eq!(schema["properties"]["orders"]["items"]["properties"]["quantity"], json!({"type": "integer"}));
+        // Only in some elements, so optional
+        assert_eq!(schema["properties"]["orders"]["items"]["required"], json!(["quantity", "sku"]));
+
+        let validator = SchemaValidator::new(schema).unwrap();
+        assert!(validator.validate(&record).is_ok());
+        assert!(validator.validate(&json!({
+            "name": "Bob",
+            "address": {"city": "Lyon", "zip": "69001"},
+            "orders": []
+        })).is_ok());
+
+        // Wrong nested type
+        assert!(validator.validate(&json!({
+            "name": "Bob",
+            "address": {"city": 42, "zip": "69001"},
+            "orders": []
+        })).is_err());
+        // Array element missing a required field
+        assert!(validator.validate(&json!({
+            "name": "Bob",
+            "address": {"city": "Lyon", "zip": "69001"},
+            "orders": [{"quantity": 1}]
+        })).is_err());
+    }
+
+    #[test]
+    fn test_infer_merges_types_across_records() {
+        let schema = infer_schema("point", &[
+            json!({"x": 1, "label": "a"}),
+            json!({"x": 2.5, "label": null}),
+        ]).unwrap();
+
+        assert_eq!(schema["properties"]["x"], json!({"type": "number"}));
+        assert_eq!(schema["properties"]["label"], json!({"type": ["null", "string"]}));
+        assert_eq!(schema["required"], json!(["x"]));
+
+        assert_eq!(infer_type(&json!([])), json!({"type": "array"}));
+        assert!(infer_schema("point", &[json!([1])]).is_err());
+    }
+}
@@ -6,12 +6,17 @@ pub mod registry;
 pub mod validator;
 pub mod pydantic;
 pub mod category;
+pub mod delete_policy;
 pub mod builtin;
+pub mod codegen;
+pub mod inference;
 
-pub use registry::{SchemaRegistry, SchemaMetadata};
-pub use validator::SchemaValidator;
+pub use registry::{SchemaRegistry, SchemaMetadata, SchemaInfo, DEFAULT_SCHEMA_MIN_SIMILARITY};
+pub use validator::{BatchErrorMode, BatchInsertReport, Coercion, ContentLimit, ContentOverflow, PreflightReport, SchemaValidator};
 pub use pydantic::{PydanticSchemaParser, ToolConfig, ResourceConfig};
 pub use category::SchemaCategory;
+pub use delete_policy::DeletePolicy;
+pub use inference::{infer_schema, infer_type};
 pub use builtin::{
     register_builtin_schemas,
     schemas_table_schema,
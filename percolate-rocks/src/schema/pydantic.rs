@@ -3,6 +3,7 @@
 //! Extracts metadata from `json_schema_extra`.
 
 use crate::schema::category::SchemaCategory;
+use crate::schema::delete_policy::DeletePolicy;
 use serde::{Deserialize, Serialize};
 
 /// MCP tool configuration for agent-lets.
@@ -127,6 +128,71 @@ impl PydanticSchemaParser {
             .map(String::from)
     }
 
+    /// Extract embedding preprocess steps.
+    ///
+    /// See `embeddings::preprocess` for step names. Empty when unset, so
+    /// text is embedded as-is.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// model_config = ConfigDict(
+    ///     json_schema_extra={
+    ///         "embedding_preprocess": ["whitespace", "trim", "lowercase"]
+    ///     }
+    /// )
+    /// ```
+    pub fn extract_embedding_preprocess(schema: &serde_json::Value) -> Vec<String> {
+        schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("embedding_preprocess"))
+            .and_then(|steps| steps.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Extract how multiple embedding fields are combined.
+    ///
+    /// See `embeddings::pooling`. `None` when unset (concatenation).
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// model_config = ConfigDict(
+    ///     json_schema_extra={
+    ///         "embedding_fields": ["title", "content"],
+    ///         "embedding_pooling": "mean"
+    ///     }
+    /// )
+    /// ```
+    pub fn extract_embedding_pooling(schema: &serde_json::Value) -> Option<String> {
+        schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("embedding_pooling"))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    }
+
+    /// Extract per-field weights for weighted embedding pooling.
+    ///
+    /// Non-numeric weights are ignored. Empty when unset.
+    pub fn extract_embedding_field_weights(schema: &serde_json::Value) -> std::collections::HashMap<String, f32> {
+        schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("embedding_field_weights"))
+            .and_then(|w| w.as_object())
+            .map(|obj| {
+                obj.iter()
+                    .filter_map(|(field, w)| w.as_f64().map(|w| (field.clone(), w as f32)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Extract edge storage mode from schema.
     ///
     /// # Arguments
@@ -169,6 +235,82 @@ impl PydanticSchemaParser {
             .unwrap_or_else(|| "inline".to_string())  // Default to inline
     }
 
+    /// Extract whether string values should be coerced to declared types.
+    ///
+    /// Useful for data loaded from CSV or forms, where numbers and booleans
+    /// arrive as strings. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// model_config = ConfigDict(
+    ///     json_schema_extra={
+    ///         "coerce_types": True  # "30" -> 30, "true" -> true
+    ///     }
+    /// )
+    /// ```
+    pub fn extract_coerce_types(schema: &serde_json::Value) -> bool {
+        schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("coerce_types"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Extract the content limit and its overflow policy.
+    ///
+    /// # Returns
+    ///
+    /// `(max_content_chars, content_overflow)`; the limit is `None` when
+    /// unset, the policy `None` when unset (reject)
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// model_config = ConfigDict(
+    ///     json_schema_extra={
+    ///         "max_content_chars": 100000,
+    ///         "content_overflow": "truncate"  # or "reject"
+    ///     }
+    /// )
+    /// ```
+    pub fn extract_content_limit(schema: &serde_json::Value) -> (Option<usize>, Option<String>) {
+        let extra = schema.get("json_schema_extra");
+        let max_chars = extra
+            .and_then(|extra| extra.get("max_content_chars"))
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+        let overflow = extra
+            .and_then(|extra| extra.get("content_overflow"))
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        (max_chars, overflow)
+    }
+
+    /// Extract whether key lookups ignore case.
+    ///
+    /// When set, the key index also stores the lowercased key and
+    /// `get_by_key` matches any casing. Deterministic UUIDs still hash the
+    /// key as written. Off by default.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// model_config = ConfigDict(
+    ///     json_schema_extra={
+    ///         "key_field": "name",
+    ///         "key_case_insensitive": True  # "alice" finds "Alice"
+    ///     }
+    /// )
+    /// ```
+    pub fn extract_key_case_insensitive(schema: &serde_json::Value) -> bool {
+        schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("key_case_insensitive"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
     /// Extract MCP tools from agent-let schema.
     ///
     /// # Arguments
@@ -272,6 +414,47 @@ impl PydanticSchemaParser {
             .unwrap_or_default()
     }
 
+    /// Extract delete policy from schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - Pydantic JSON Schema
+    ///
+    /// # Returns
+    ///
+    /// Delete policy (defaults to `Either`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ValidationError` for unknown policy values
+    ///
+    /// # Example
+    ///
+    /// ```json
+    /// {
+    ///   "json_schema_extra": {
+    ///     "delete_policy": "soft_only"
+    ///   }
+    /// }
+    /// ```
+    pub fn extract_delete_policy(schema: &serde_json::Value) -> crate::types::Result<DeletePolicy> {
+        let value = match schema
+            .get("json_schema_extra")
+            .and_then(|extra| extra.get("delete_policy"))
+        {
+            Some(value) => value,
+            None => return Ok(DeletePolicy::default()),
+        };
+
+        value
+            .as_str()
+            .and_then(DeletePolicy::from_str)
+            .ok_or_else(|| crate::types::DatabaseError::ValidationError(format!(
+                "Invalid delete_policy {} (expected soft_only, hard_only, or either)",
+                value
+            )))
+    }
+
     /// Extract version from schema.
     ///
     /// # Arguments
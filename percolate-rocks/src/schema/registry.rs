@@ -2,8 +2,16 @@
 
 use crate::types::Result;
 use crate::schema::category::SchemaCategory;
+use crate::schema::delete_policy::DeletePolicy;
 use std::collections::HashMap;
 
+/// Default similarity floor for `SchemaRegistry::auto_detect_schema`.
+///
+/// Queries scoring below this against every schema description get no
+/// candidates. Tune per embedding model; override with
+/// `P8_SCHEMA_MIN_SIMILARITY` in `rem ask`.
+pub const DEFAULT_SCHEMA_MIN_SIMILARITY: f32 = 0.3;
+
 /// Schema metadata for tracking versions and categories.
 #[derive(Debug, Clone)]
 pub struct SchemaMetadata {
@@ -13,14 +21,42 @@ pub struct SchemaMetadata {
     pub version: String,
     /// Schema category
     pub category: SchemaCategory,
+    /// Delete policy
+    pub delete_policy: DeletePolicy,
     /// Full JSON Schema
     pub schema: serde_json::Value,
 }
 
+/// Schema metadata extracted for introspection (admin UIs, CLI).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SchemaInfo {
+    /// Schema name
+    pub name: String,
+    /// Semantic version
+    pub version: String,
+    /// Table name (`short_name`)
+    pub short_name: Option<String>,
+    /// Schema description
+    pub description: Option<String>,
+    /// Schema category
+    pub category: SchemaCategory,
+    /// Delete policy
+    pub delete_policy: DeletePolicy,
+    /// Field used for deterministic UUIDs and key lookups
+    pub key_field: Option<String>,
+    /// Fields with a secondary index
+    pub indexed_fields: Vec<String>,
+    /// Fields embedded for semantic search
+    pub embedding_fields: Vec<String>,
+    /// Embedding provider (e.g. `default`, `openai:text-embedding-3-small`)
+    pub embedding_provider: Option<String>,
+}
+
 /// Schema registry for managing entity schemas.
 ///
 /// Tracks schemas by name and organizes them by category.
 /// Supports semantic schema search when >10 schemas registered.
+#[derive(Clone)]
 pub struct SchemaRegistry {
     /// Schema storage by name
     schemas: HashMap<String, SchemaMetadata>,
@@ -63,6 +99,9 @@ impl SchemaRegistry {
         // Extract category (defaults to User)
         let category = PydanticSchemaParser::extract_category(&schema);
 
+        // Extract delete policy (defaults to Either, rejects unknown values)
+        let delete_policy = PydanticSchemaParser::extract_delete_policy(&schema)?;
+
         // Check version compatibility if schema already exists
         if self.has(name) {
             self.check_version_compatibility(name, &version)?;
@@ -73,6 +112,7 @@ impl SchemaRegistry {
             name: name.to_string(),
             version: version.clone(),
             category,
+            delete_policy,
             schema,
         };
 
@@ -123,6 +163,41 @@ impl SchemaRegistry {
         self.schemas.keys().cloned().collect()
     }
 
+    /// Get extracted metadata for a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Schema name
+    ///
+    /// # Returns
+    ///
+    /// `SchemaInfo` with key, index, embedding, and category settings
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SchemaNotFound` if schema doesn't exist
+    pub fn info(&self, name: &str) -> Result<SchemaInfo> {
+        use crate::schema::pydantic::PydanticSchemaParser;
+
+        let meta = self.schemas
+            .get(name)
+            .ok_or_else(|| crate::types::DatabaseError::SchemaNotFound(name.to_string()))?;
+        let schema = &meta.schema;
+
+        Ok(SchemaInfo {
+            name: meta.name.clone(),
+            version: meta.version.clone(),
+            short_name: PydanticSchemaParser::extract_short_name(schema),
+            description: PydanticSchemaParser::extract_description(schema),
+            category: meta.category,
+            delete_policy: meta.delete_policy,
+            key_field: PydanticSchemaParser::extract_key_field(schema),
+            indexed_fields: PydanticSchemaParser::extract_indexed_fields(schema),
+            embedding_fields: PydanticSchemaParser::extract_embedding_fields(schema),
+            embedding_provider: PydanticSchemaParser::extract_embedding_provider(schema),
+        })
+    }
+
     /// Extract embedding fields from schema.
     ///
     /// # Arguments
@@ -170,6 +245,9 @@ impl SchemaRegistry {
 
     /// Embed schema description for semantic schema discovery.
     ///
+    /// The embedded text is `"{name}: {description}"`, or just the name
+    /// for schemas without a description.
+    ///
     /// # Arguments
     ///
     /// * `name` - Schema name
@@ -181,22 +259,25 @@ impl SchemaRegistry {
     ///
     /// # Errors
     ///
-    /// Returns `DatabaseError::EmbeddingError` if embedding fails
+    /// Returns `DatabaseError::SchemaNotFound` if schema doesn't exist, or
+    /// `DatabaseError::EmbeddingError` if embedding fails
     ///
     /// # Note
     ///
     /// Used for semantic schema search when >10 schemas registered.
-    /// Embeddings stored in separate HNSW index: `{db_path}/indexes/_schemas.hnsw`
     pub async fn embed_schema_description(
         &self,
         name: &str,
         embedder: &dyn crate::embeddings::provider::EmbeddingProvider,
     ) -> Result<Vec<f32>> {
-        todo!("Implement SchemaRegistry::embed_schema_description")
+        let text = self.description_text(name)?;
+        embedder.embed(&text).await
     }
 
     /// Find schemas by semantic similarity to query.
     ///
+    /// Scores every registered schema (brute force), so results are exact.
+    ///
     /// # Arguments
     ///
     /// * `query` - Search query
@@ -205,11 +286,11 @@ impl SchemaRegistry {
     ///
     /// # Returns
     ///
-    /// Vector of (schema_name, similarity_score) tuples
+    /// Vector of (schema_name, similarity_score) tuples, best first
     ///
     /// # Errors
     ///
-    /// Returns `DatabaseError::SearchError` if search fails
+    /// Returns `DatabaseError::EmbeddingError` if embedding fails
     ///
     /// # Usage
     ///
@@ -220,7 +301,83 @@ impl SchemaRegistry {
         top_k: usize,
         embedder: &dyn crate::embeddings::provider::EmbeddingProvider,
     ) -> Result<Vec<(String, f32)>> {
-        todo!("Implement SchemaRegistry::search_schemas_by_similarity")
+        use crate::index::similarity::cosine_similarity_batch;
+
+        let mut names = self.list();
+        names.sort();
+        if names.is_empty() || top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let texts = names
+            .iter()
+            .map(|name| self.description_text(name))
+            .collect::<Result<Vec<_>>>()?;
+
+        let query_embedding = embedder.embed(query).await?;
+        let schema_embeddings = embedder.embed_batch(&texts).await?;
+        let scores = cosine_similarity_batch(&query_embedding, &schema_embeddings);
+
+        let mut ranked: Vec<(String, f32)> = names.into_iter().zip(scores).collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(top_k);
+
+        Ok(ranked)
+    }
+
+    /// Pick candidate schemas for a natural language query.
+    ///
+    /// Like `search_schemas_by_similarity`, but drops schemas scoring below
+    /// `min_similarity`. An empty result means no schema is a plausible
+    /// match: callers should ask the user to clarify rather than route the
+    /// query to the least-bad schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Natural language query
+    /// * `top_k` - Maximum number of candidates
+    /// * `min_similarity` - Cosine similarity floor (see `DEFAULT_SCHEMA_MIN_SIMILARITY`)
+    /// * `embedder` - Embedding provider
+    ///
+    /// # Returns
+    ///
+    /// Vector of (schema_name, similarity_score) tuples, best first
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::EmbeddingError` if embedding fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let candidates = registry
+    ///     .auto_detect_schema("find product ABS-234", 3, DEFAULT_SCHEMA_MIN_SIMILARITY, &*embedder)
+    ///     .await?;
+    /// if candidates.is_empty() {
+    ///     println!("Which kind of data are you asking about?");
+    /// }
+    /// ```
+    pub async fn auto_detect_schema(
+        &self,
+        query: &str,
+        top_k: usize,
+        min_similarity: f32,
+        embedder: &dyn crate::embeddings::provider::EmbeddingProvider,
+    ) -> Result<Vec<(String, f32)>> {
+        let mut candidates = self.search_schemas_by_similarity(query, top_k, embedder).await?;
+        candidates.retain(|(_, score)| *score >= min_similarity);
+        Ok(candidates)
+    }
+
+    /// Text embedded for a schema (name plus description).
+    fn description_text(&self, name: &str) -> Result<String> {
+        use crate::schema::pydantic::PydanticSchemaParser;
+
+        let schema = self.get(name)?;
+        Ok(match PydanticSchemaParser::extract_description(schema) {
+            Some(description) => format!("{}: {}", name, description),
+            None => name.to_string(),
+        })
     }
 
     /// Check if schema exists.
@@ -256,6 +413,26 @@ impl SchemaRegistry {
             .ok_or_else(|| crate::types::DatabaseError::SchemaNotFound(name.to_string()))
     }
 
+    /// Get schema delete policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Schema name
+    ///
+    /// # Returns
+    ///
+    /// Delete policy (`Either` unless configured)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::SchemaNotFound` if schema doesn't exist
+    pub fn get_delete_policy(&self, name: &str) -> Result<DeletePolicy> {
+        self.schemas
+            .get(name)
+            .map(|meta| meta.delete_policy)
+            .ok_or_else(|| crate::types::DatabaseError::SchemaNotFound(name.to_string()))
+    }
+
     /// List schemas by category.
     ///
     /// # Arguments
@@ -442,3 +619,67 @@ impl SchemaRegistry {
         Ok(metadata)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::provider::EmbeddingProvider;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    /// Bag-of-words embedder over a fixed vocabulary.
+    struct KeywordEmbedder;
+
+    const VOCABULARY: [&str; 6] = ["product", "pricing", "inventory", "article", "author", "weather"];
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedder {
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let text = text.to_lowercase();
+            Ok(VOCABULARY.iter().map(|word| text.matches(word).count() as f32).collect())
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for text in texts {
+                embeddings.push(self.embed(text).await?);
+            }
+            Ok(embeddings)
+        }
+
+        fn dimensions(&self) -> usize {
+            VOCABULARY.len()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_detect_schema_threshold() {
+        let mut registry = SchemaRegistry::new();
+        registry.register("products", json!({
+            "version": "1.0.0",
+            "description": "Product catalog with pricing and inventory"
+        })).unwrap();
+        registry.register("articles", json!({
+            "version": "1.0.0",
+            "description": "Articles written by an author"
+        })).unwrap();
+
+        let related = registry
+            .auto_detect_schema("product pricing", 3, DEFAULT_SCHEMA_MIN_SIMILARITY, &KeywordEmbedder)
+            .await
+            .unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].0, "products");
+
+        // Unrelated to every description: no candidates rather than a weak match
+        let unrelated = registry
+            .auto_detect_schema("weather tomorrow", 3, DEFAULT_SCHEMA_MIN_SIMILARITY, &KeywordEmbedder)
+            .await
+            .unwrap();
+        assert!(unrelated.is_empty());
+
+        // Without the floor, top-N still ranks every schema
+        let ranked = registry.search_schemas_by_similarity("weather tomorrow", 3, &KeywordEmbedder).await.unwrap();
+        assert_eq!(ranked.len(), 2);
+    }
+}
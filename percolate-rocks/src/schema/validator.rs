@@ -1,11 +1,129 @@
 //! JSON Schema validation.
+//!
+//! With coercion enabled (`json_schema_extra.coerce_types`), top-level
+//! string values of fields declared `integer`, `number`, or `boolean` are
+//! converted before validation when they parse cleanly (`"30"` → `30`,
+//! `"true"` → `true`). Anything else is left as-is and fails validation.
+//!
+//! With a content limit (`json_schema_extra.max_content_chars`), top-level
+//! string values longer than the limit are rejected or truncated to it,
+//! per `json_schema_extra.content_overflow` (`"reject"` by default).
 
 use crate::types::Result;
+use serde_json::Value;
+
+/// A string value converted to its declared type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coercion {
+    /// Top-level property name
+    pub field: String,
+    /// Value as received
+    pub from: Value,
+    /// Value as stored
+    pub to: Value,
+}
+
+/// What to do with string values over the content limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentOverflow {
+    /// Fail validation
+    #[default]
+    Reject,
+    /// Keep the first `max_chars` characters
+    Truncate,
+}
+
+impl ContentOverflow {
+    /// Parse policy from string (`reject`, `truncate`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "truncate" => Ok(Self::Truncate),
+            other => Err(crate::types::DatabaseError::ConfigError(format!(
+                "Unknown content overflow policy '{}' (expected reject or truncate)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Maximum length of top-level string values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentLimit {
+    /// Maximum characters (Unicode scalar values) per string
+    pub max_chars: usize,
+    /// Policy for longer strings
+    pub overflow: ContentOverflow,
+}
+
+/// Outcome of validating a batch of rows without writing them.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct PreflightReport {
+    /// Rows that passed validation
+    pub valid: usize,
+    /// `(row index, message)` for every row that failed, in input order
+    pub errors: Vec<(usize, String)>,
+}
+
+impl PreflightReport {
+    /// Whether every row passed.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// What a batch insert does with rows that fail validation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchErrorMode {
+    /// Reject the whole batch on the first invalid row (nothing is written)
+    #[default]
+    FailFast,
+    /// Insert the valid rows, reporting the indexes of skipped rows
+    SkipInvalid,
+    /// Insert the valid rows, reporting every invalid row with its error
+    CollectErrors,
+}
+
+impl BatchErrorMode {
+    /// Parse mode from string (`fail_fast`, `skip_invalid`, `collect_errors`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ConfigError` for unknown values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().replace('-', "_").as_str() {
+            "fail_fast" => Ok(Self::FailFast),
+            "skip_invalid" => Ok(Self::SkipInvalid),
+            "collect_errors" => Ok(Self::CollectErrors),
+            other => Err(crate::types::DatabaseError::ConfigError(format!(
+                "Unknown batch error mode '{}' (expected fail_fast, skip_invalid, or collect_errors)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Outcome of a batch insert that tolerates invalid rows.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct BatchInsertReport {
+    /// IDs of inserted rows, in input order
+    pub ids: Vec<uuid::Uuid>,
+    /// Indexes of rows not inserted, in input order
+    pub skipped: Vec<usize>,
+    /// `(row index, message)` per invalid row (`CollectErrors` only)
+    pub errors: Vec<(usize, String)>,
+}
 
 /// Schema validator for entity validation.
 pub struct SchemaValidator {
     schema: serde_json::Value,
     compiled: jsonschema::JSONSchema,
+    coerce_types: bool,
+    content_limit: Option<ContentLimit>,
 }
 
 impl SchemaValidator {
@@ -29,7 +147,135 @@ impl SchemaValidator {
         let compiled = jsonschema::JSONSchema::compile(&schema)
             .map_err(|e| DatabaseError::ValidationError(format!("Invalid JSON Schema: {}", e)))?;
 
-        Ok(Self { schema, compiled })
+        Ok(Self { schema, compiled, coerce_types: false, content_limit: None })
+    }
+
+    /// Enable or disable string coercion (see module docs).
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Coerce parseable strings in `validate_and_coerce`
+    ///
+    /// # Returns
+    ///
+    /// Updated `SchemaValidator`
+    pub fn with_coercion(mut self, enabled: bool) -> Self {
+        self.coerce_types = enabled;
+        self
+    }
+
+    /// Set the content limit (see module docs).
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Limit to enforce in `validate_and_coerce` (`None` = unlimited)
+    ///
+    /// # Returns
+    ///
+    /// Updated `SchemaValidator`
+    pub fn with_content_limit(mut self, limit: Option<ContentLimit>) -> Self {
+        self.content_limit = limit;
+        self
+    }
+
+    /// Apply the content limit to top-level string values.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Data to truncate in place
+    ///
+    /// # Returns
+    ///
+    /// Names of truncated fields
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ValidationError` naming the field and limit
+    /// if a value is too long under `ContentOverflow::Reject`
+    pub fn enforce_content_limit(&self, data: &mut Value) -> Result<Vec<String>> {
+        let (Some(limit), Some(object)) = (self.content_limit, data.as_object_mut()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut truncated = Vec::new();
+        for (field, value) in object.iter_mut() {
+            let Value::String(text) = value else { continue };
+            let Some((cut, _)) = text.char_indices().nth(limit.max_chars) else { continue };
+
+            match limit.overflow {
+                ContentOverflow::Reject => {
+                    return Err(crate::types::DatabaseError::ValidationError(format!(
+                        "Field '{}' has {} characters, over the limit of {} (max_content_chars)",
+                        field,
+                        text.chars().count(),
+                        limit.max_chars
+                    )));
+                }
+                ContentOverflow::Truncate => {
+                    text.truncate(cut);
+                    truncated.push(field.clone());
+                }
+            }
+        }
+
+        Ok(truncated)
+    }
+
+    /// Coerce (if enabled) and then validate data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Data to coerce in place and validate
+    ///
+    /// # Returns
+    ///
+    /// Coercions applied (empty if coercion is disabled)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ValidationError` if the coerced data is
+    /// invalid, including strings that couldn't be coerced or are over a
+    /// rejecting content limit
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let validator = SchemaValidator::new(schema)?.with_coercion(true);
+    /// let mut row = json!({"name": "Alice", "age": "30"});
+    /// let coercions = validator.validate_and_coerce(&mut row)?;
+    /// assert_eq!(row["age"], 30);
+    /// ```
+    pub fn validate_and_coerce(&self, data: &mut Value) -> Result<Vec<Coercion>> {
+        let coercions = if self.coerce_types { self.coerce(data) } else { Vec::new() };
+        let truncated = self.enforce_content_limit(data)?;
+        if !truncated.is_empty() {
+            tracing::debug!(?truncated, "Truncated values over max_content_chars");
+        }
+        self.validate(data)?;
+        Ok(coercions)
+    }
+
+    /// Convert parseable strings to their fields' declared types.
+    fn coerce(&self, data: &mut Value) -> Vec<Coercion> {
+        let (Some(properties), Some(object)) = (
+            self.schema.get("properties").and_then(|p| p.as_object()),
+            data.as_object_mut(),
+        ) else {
+            return Vec::new();
+        };
+
+        let mut coercions = Vec::new();
+        for (field, value) in object.iter_mut() {
+            let Some(text) = value.as_str() else { continue };
+            let Some(field_schema) = properties.get(field) else { continue };
+
+            if let Some(coerced) = coerce_str(text, &declared_types(field_schema)) {
+                let from = std::mem::replace(value, coerced.clone());
+                coercions.push(Coercion { field: field.clone(), from, to: coerced });
+            }
+        }
+
+        coercions
     }
 
     /// Validate data against schema.
@@ -200,6 +446,57 @@ impl SchemaValidator {
     }
 }
 
+/// Types a property schema accepts (`type` may be a string or a list).
+///
+/// Pydantic emits `Optional[int]` as `anyOf: [{type: integer}, {type: null}]`,
+/// so `anyOf` branches are included.
+fn declared_types(field_schema: &Value) -> Vec<&str> {
+    let mut types: Vec<&str> = match field_schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if let Some(branches) = field_schema.get("anyOf").and_then(|a| a.as_array()) {
+        for branch in branches {
+            types.extend(declared_types(branch));
+        }
+    }
+    types
+}
+
+/// Parse a string as the first declared type it fits.
+///
+/// `None` if the field also accepts strings (nothing to fix) or the text
+/// doesn't parse.
+fn coerce_str(text: &str, types: &[&str]) -> Option<Value> {
+    if types.contains(&"string") {
+        return None;
+    }
+
+    let trimmed = text.trim();
+    for declared in types {
+        let coerced = match *declared {
+            "integer" => trimmed.parse::<i64>().ok().map(Value::from),
+            "number" => trimmed
+                .parse::<i64>()
+                .ok()
+                .map(Value::from)
+                .or_else(|| trimmed.parse::<f64>().ok().and_then(serde_json::Number::from_f64).map(Value::Number)),
+            "boolean" => match trimmed.to_ascii_lowercase().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if coerced.is_some() {
+            return coerced;
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,4 +528,71 @@ mod tests {
         assert!(SchemaValidator::validate_version_format("invalid").is_err());
         assert!(SchemaValidator::validate_version_format("1.0").is_err());
     }
+
+    fn person_schema() -> Value {
+        json!({
+            "properties": {
+                "name": {"type": "string"},
+                "age": {"type": "integer"},
+                "score": {"type": "number"},
+                "active": {"type": "boolean"},
+                "rank": {"anyOf": [{"type": "integer"}, {"type": "null"}]}
+            },
+            "required": ["name"]
+        })
+    }
+
+    #[test]
+    fn test_coercion() {
+        let validator = SchemaValidator::new(person_schema()).unwrap().with_coercion(true);
+
+        let mut data = json!({"name": "42", "age": "30", "score": " 4.5 ", "active": "TRUE", "rank": "7"});
+        let coercions = validator.validate_and_coerce(&mut data).unwrap();
+
+        assert_eq!(data, json!({"name": "42", "age": 30, "score": 4.5, "active": true, "rank": 7}));
+        let mut fields: Vec<&str> = coercions.iter().map(|c| c.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, vec!["active", "age", "rank", "score"]);
+        let age = coercions.iter().find(|c| c.field == "age").unwrap();
+        assert_eq!((age.from.clone(), age.to.clone()), (json!("30"), json!(30)));
+
+        // Already-typed values are untouched
+        let mut typed = json!({"name": "Bob", "age": 30, "active": false});
+        assert!(validator.validate_and_coerce(&mut typed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_coercion_failures() {
+        let validator = SchemaValidator::new(person_schema()).unwrap().with_coercion(true);
+
+        for bad in [json!({"name": "A", "score": "abc"}), json!({"name": "A", "age": "3.5"}), json!({"name": "A", "active": "yes"})] {
+            let mut data = bad.clone();
+            assert!(validator.validate_and_coerce(&mut data).is_err(), "{} should fail", bad);
+        }
+
+        // Off by default
+        let strict = SchemaValidator::new(person_schema()).unwrap();
+        let mut data = json!({"name": "A", "age": "30"});
+        assert!(strict.validate_and_coerce(&mut data).is_err());
+        assert_eq!(data["age"], "30");
+    }
+
+    #[test]
+    fn test_content_limit() {
+        let limit = |overflow| Some(ContentLimit { max_chars: 5, overflow });
+
+        let reject = SchemaValidator::new(person_schema()).unwrap().with_content_limit(limit(ContentOverflow::Reject));
+        let err = reject.validate_and_coerce(&mut json!({"name": "Alexandra"})).unwrap_err().to_string();
+        assert!(err.contains("'name'") && err.contains("limit of 5"), "{}", err);
+        assert!(reject.validate_and_coerce(&mut json!({"name": "Alex"})).is_ok());
+
+        // Counted in characters, cut on a character boundary
+        let truncate = SchemaValidator::new(person_schema()).unwrap().with_content_limit(limit(ContentOverflow::Truncate));
+        let mut data = json!({"name": "Zoë Ångström"});
+        assert_eq!(truncate.enforce_content_limit(&mut data).unwrap(), vec!["name"]);
+        assert_eq!(data["name"], "Zoë Å");
+
+        assert_eq!(ContentOverflow::parse("Truncate").unwrap(), ContentOverflow::Truncate);
+        assert!(ContentOverflow::parse("drop").is_err());
+    }
 }
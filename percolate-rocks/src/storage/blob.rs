@@ -0,0 +1,235 @@
+//! Chunked blob storage.
+//!
+//! Large values hurt RocksDB compaction, so blob payloads are split into
+//! fixed-size chunks. Layout in `CF_BLOBS`:
+//!
+//! - `blob:{tenant}:{uuid}` - bincode `BlobManifest` (size, chunk count)
+//! - `blob:{tenant}:{uuid}:{chunk_idx}` - raw chunk bytes
+//!
+//! Chunks are written before the manifest, so a manifest never points at
+//! missing chunks. When a blob is replaced by a smaller one, the leftover
+//! chunks are deleted after the new manifest is in place.
+
+use super::column_families::CF_BLOBS;
+use super::keys::{encode_blob_chunk_key, encode_blob_key};
+use super::Storage;
+use crate::types::{BlobManifest, DatabaseError, Result};
+use std::io::Read;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Default chunk size (1 MiB).
+pub const DEFAULT_BLOB_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Read a blob's manifest.
+///
+/// # Arguments
+///
+/// * `storage` - Storage handle
+/// * `tenant_id` - Tenant scope
+/// * `entity_id` - Owning entity UUID
+///
+/// # Returns
+///
+/// `Some(BlobManifest)` if a blob is stored, `None` otherwise
+pub fn read_manifest(storage: &Storage, tenant_id: &str, entity_id: Uuid) -> Result<Option<BlobManifest>> {
+    match storage.get(CF_BLOBS, &encode_blob_key(tenant_id, entity_id))? {
+        Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+        None => Ok(None),
+    }
+}
+
+/// Write a blob from a reader, one chunk at a time.
+///
+/// Memory use is bounded by `chunk_size` regardless of payload size.
+///
+/// # Arguments
+///
+/// * `storage` - Storage handle
+/// * `tenant_id` - Tenant scope
+/// * `entity_id` - Owning entity UUID
+/// * `reader` - Payload source
+/// * `content_type` - MIME type
+/// * `chunk_size` - Bytes per chunk
+///
+/// # Returns
+///
+/// Manifest of the stored blob
+///
+/// # Errors
+///
+/// Returns `DatabaseError::IoError` if reading fails, or
+/// `DatabaseError::ConfigError` for a zero or over-4GiB chunk size
+pub fn write_blob<R: Read>(
+    storage: &Storage,
+    tenant_id: &str,
+    entity_id: Uuid,
+    mut reader: R,
+    content_type: &str,
+    chunk_size: usize,
+) -> Result<BlobManifest> {
+    let chunk_size_u32 = u32::try_from(chunk_size)
+        .ok()
+        .filter(|&size| size > 0)
+        .ok_or_else(|| DatabaseError::ConfigError(format!("Invalid blob chunk size: {}", chunk_size)))?;
+
+    let previous = read_manifest(storage, tenant_id, entity_id)?;
+
+    let mut total_size = 0u64;
+    let mut chunk_count = 0u32;
+    let mut chunk = Vec::with_capacity(chunk_size);
+
+    loop {
+        chunk.clear();
+        (&mut reader).take(chunk_size as u64).read_to_end(&mut chunk)?;
+        if chunk.is_empty() {
+            break;
+        }
+
+        storage.put(CF_BLOBS, &encode_blob_chunk_key(tenant_id, entity_id, chunk_count), &chunk)?;
+        total_size += chunk.len() as u64;
+        chunk_count += 1;
+
+        if chunk.len() < chunk_size {
+            break;
+        }
+    }
+
+    let manifest = BlobManifest {
+        content_type: content_type.to_string(),
+        total_size,
+        chunk_count,
+        chunk_size: chunk_size_u32,
+    };
+    storage.put(CF_BLOBS, &encode_blob_key(tenant_id, entity_id), &bincode::serialize(&manifest)?)?;
+
+    // Drop chunks left over from a larger previous blob
+    if let Some(previous) = previous {
+        for idx in chunk_count..previous.chunk_count {
+            storage.delete(CF_BLOBS, &encode_blob_chunk_key(tenant_id, entity_id, idx))?;
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Delete a blob's manifest and chunks.
+///
+/// # Returns
+///
+/// `true` if a blob was stored
+pub fn delete_blob(storage: &Storage, tenant_id: &str, entity_id: Uuid) -> Result<bool> {
+    let Some(manifest) = read_manifest(storage, tenant_id, entity_id)? else {
+        return Ok(false);
+    };
+
+    storage.delete(CF_BLOBS, &encode_blob_key(tenant_id, entity_id))?;
+    for idx in 0..manifest.chunk_count {
+        storage.delete(CF_BLOBS, &encode_blob_chunk_key(tenant_id, entity_id, idx))?;
+    }
+
+    Ok(true)
+}
+
+/// Streaming reader over a stored blob.
+///
+/// Loads one chunk at a time.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut reader = db.get_blob_reader("tenant1", id)?.expect("blob");
+/// std::io::copy(&mut reader, &mut std::fs::File::create("out.mp3")?)?;
+/// ```
+pub struct BlobReader {
+    storage: Arc<Storage>,
+    tenant_id: String,
+    entity_id: Uuid,
+    manifest: BlobManifest,
+    next_chunk: u32,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl BlobReader {
+    /// Open a reader for a stored blob.
+    ///
+    /// # Returns
+    ///
+    /// `Some(BlobReader)` if a blob is stored, `None` otherwise
+    pub fn open(storage: Arc<Storage>, tenant_id: &str, entity_id: Uuid) -> Result<Option<Self>> {
+        let Some(manifest) = read_manifest(&storage, tenant_id, entity_id)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            storage,
+            tenant_id: tenant_id.to_string(),
+            entity_id,
+            manifest,
+            next_chunk: 0,
+            chunk: Vec::new(),
+            pos: 0,
+        }))
+    }
+
+    /// Manifest of the blob being read.
+    pub fn manifest(&self) -> &BlobManifest {
+        &self.manifest
+    }
+}
+
+impl Read for BlobReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.chunk.len() {
+            if self.next_chunk == self.manifest.chunk_count {
+                return Ok(0);
+            }
+
+            let key = encode_blob_chunk_key(&self.tenant_id, self.entity_id, self.next_chunk);
+            self.chunk = self.storage
+                .get(CF_BLOBS, &key)
+                .map_err(std::io::Error::other)?
+                .ok_or_else(|| std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("Blob {} is missing chunk {}", self.entity_id, self.next_chunk),
+                ))?;
+            self.next_chunk += 1;
+            self.pos = 0;
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_with_smaller_blob_removes_stale_chunks() {
+        let storage = Arc::new(Storage::open_temp().unwrap());
+        let id = Uuid::new_v4();
+
+        let manifest = write_blob(&storage, "tenant1", id, &[7u8; 10][..], "application/octet-stream", 4).unwrap();
+        assert_eq!(manifest.total_size, 10);
+        assert_eq!(manifest.chunk_count, 3);
+
+        let manifest = write_blob(&storage, "tenant1", id, &[1u8; 3][..], "text/plain", 4).unwrap();
+        assert_eq!(manifest.chunk_count, 1);
+        assert!(storage.get(CF_BLOBS, &encode_blob_chunk_key("tenant1", id, 1)).unwrap().is_none());
+        assert!(storage.get(CF_BLOBS, &encode_blob_chunk_key("tenant1", id, 2)).unwrap().is_none());
+
+        let mut data = Vec::new();
+        BlobReader::open(storage.clone(), "tenant1", id).unwrap().unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(data, vec![1u8; 3]);
+
+        assert!(delete_blob(&storage, "tenant1", id).unwrap());
+        assert!(storage.get(CF_BLOBS, &encode_blob_chunk_key("tenant1", id, 0)).unwrap().is_none());
+        assert!(BlobReader::open(storage, "tenant1", id).unwrap().is_none());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Defines column families for logical data separation and performance optimization.
 
+use super::expiry::ExpiryFilter;
 use rocksdb::{ColumnFamilyDescriptor, Options};
 
 /// Main entity storage
@@ -31,6 +32,12 @@ pub const CF_KEYS: &str = "keys";
 /// BM25 keyword search index for fuzzy key lookups
 pub const CF_BM25_INDEX: &str = "bm25_index";
 
+/// Binary payloads attached to entities (images, audio)
+pub const CF_BLOBS: &str = "blobs";
+
+/// Structural (random-walk) entity embeddings, per relationship type
+pub const CF_GRAPH_EMBEDDINGS: &str = "graph_embeddings";
+
 /// Get all column family names.
 ///
 /// # Returns
@@ -47,17 +54,26 @@ pub fn all_column_families() -> Vec<&'static str> {
         CF_WAL,
         CF_KEYS,
         CF_BM25_INDEX,
+        CF_BLOBS,
+        CF_GRAPH_EMBEDDINGS,
     ]
 }
 
 /// Create column family descriptors with optimized settings.
 ///
+/// # Arguments
+///
+/// * `expiry` - Compaction filter installed on `CF_ENTITIES`
+///
 /// # Returns
 ///
 /// Vector of `ColumnFamilyDescriptor` with appropriate options for each CF
-pub fn create_column_family_descriptors() -> Vec<ColumnFamilyDescriptor> {
+pub fn create_column_family_descriptors(expiry: &ExpiryFilter) -> Vec<ColumnFamilyDescriptor> {
+    let mut entities_opts = entity_cf_options();
+    expiry.install(&mut entities_opts);
+
     vec![
-        ColumnFamilyDescriptor::new(CF_ENTITIES, entity_cf_options()),
+        ColumnFamilyDescriptor::new(CF_ENTITIES, entities_opts),
         ColumnFamilyDescriptor::new(CF_KEY_INDEX, index_cf_options()),
         ColumnFamilyDescriptor::new(CF_EDGES, entity_cf_options()),
         ColumnFamilyDescriptor::new(CF_EDGES_REVERSE, entity_cf_options()),
@@ -66,6 +82,8 @@ pub fn create_column_family_descriptors() -> Vec<ColumnFamilyDescriptor> {
         ColumnFamilyDescriptor::new(CF_WAL, entity_cf_options()),
         ColumnFamilyDescriptor::new(CF_KEYS, entity_cf_options()),
         ColumnFamilyDescriptor::new(CF_BM25_INDEX, index_cf_options()),
+        ColumnFamilyDescriptor::new(CF_BLOBS, embedding_cf_options()),
+        ColumnFamilyDescriptor::new(CF_GRAPH_EMBEDDINGS, embedding_cf_options()),
     ]
 }
 
@@ -143,7 +161,7 @@ mod tests {
     fn test_all_column_families() {
         let cfs = all_column_families();
 
-        assert_eq!(cfs.len(), 8);
+        assert_eq!(cfs.len(), 11);
         assert!(cfs.contains(&CF_ENTITIES));
         assert!(cfs.contains(&CF_KEY_INDEX));
         assert!(cfs.contains(&CF_EDGES));
@@ -152,13 +170,16 @@ mod tests {
         assert!(cfs.contains(&CF_INDEXES));
         assert!(cfs.contains(&CF_WAL));
         assert!(cfs.contains(&CF_KEYS));
+        assert!(cfs.contains(&CF_BM25_INDEX));
+        assert!(cfs.contains(&CF_BLOBS));
+        assert!(cfs.contains(&CF_GRAPH_EMBEDDINGS));
     }
 
     #[test]
     fn test_column_family_descriptors() {
-        let descriptors = create_column_family_descriptors();
+        let descriptors = create_column_family_descriptors(&ExpiryFilter::default());
 
-        assert_eq!(descriptors.len(), 8);
+        assert_eq!(descriptors.len(), 11);
 
         // Verify all CFs have descriptors
         let names: Vec<_> = descriptors.iter().map(|d| d.name()).collect();
@@ -170,6 +191,9 @@ mod tests {
         assert!(names.contains(&CF_INDEXES));
         assert!(names.contains(&CF_WAL));
         assert!(names.contains(&CF_KEYS));
+        assert!(names.contains(&CF_BM25_INDEX));
+        assert!(names.contains(&CF_BLOBS));
+        assert!(names.contains(&CF_GRAPH_EMBEDDINGS));
     }
 
     #[test]
@@ -38,6 +38,10 @@ use std::sync::Arc;
 pub struct Storage {
     db: Arc<DB>,
     keypair: Option<Arc<TenantKeyPair>>,
+    /// Opened with `open_secondary` (read-only, follows another process)
+    secondary: bool,
+    /// `CF_ENTITIES` compaction filter (expired and soft-deleted entities)
+    expiry: super::ExpiryFilter,
 }
 
 impl Storage {
@@ -87,7 +91,8 @@ impl Storage {
         opts.set_write_buffer_size(64 * 1024 * 1024); // 64MB
 
         // Create column family descriptors
-        let cfs = super::column_families::create_column_family_descriptors();
+        let expiry = super::ExpiryFilter::default();
+        let cfs = super::column_families::create_column_family_descriptors(&expiry);
 
         // Open database with column families
         let db = DB::open_cf_descriptors(&opts, path, cfs)
@@ -102,7 +107,63 @@ impl Storage {
             None
         };
 
-        Ok(Self { db, keypair })
+        Ok(Self { db, keypair, secondary: false, expiry })
+    }
+
+    /// Open a secondary instance of a database another process has open.
+    ///
+    /// Secondary instances don't take the database lock, so they can run
+    /// next to the writer. They are read-only and see the writer's changes
+    /// only after `catch_up_with_primary`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Primary database directory path
+    /// * `secondary_path` - Directory for the secondary's own info logs
+    ///
+    /// # Returns
+    ///
+    /// Read-only `Storage` instance (no encryption)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if RocksDB fails to open
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let storage = Storage::open_secondary("./data", "/tmp/rem-secondary")?;
+    /// storage.catch_up_with_primary()?;
+    /// ```
+    pub fn open_secondary<P: AsRef<Path>, S: AsRef<Path>>(path: P, secondary_path: S) -> Result<Self> {
+        let mut opts = rocksdb::Options::default();
+        // Secondaries must keep every file open to follow the primary
+        opts.set_max_open_files(-1);
+
+        let db = DB::open_cf_as_secondary(
+            &opts,
+            path.as_ref(),
+            secondary_path.as_ref(),
+            super::column_families::all_column_families(),
+        )
+        .map_err(|e| DatabaseError::StorageError(e.into()))?;
+
+        // Secondaries never compact, so their filter stays idle
+        Ok(Self { db: Arc::new(db), keypair: None, secondary: true, expiry: super::ExpiryFilter::default() })
+    }
+
+    /// Replay the primary's new writes into a secondary instance.
+    ///
+    /// No-op for instances opened with `open`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::StorageError` if RocksDB fails
+    pub fn catch_up_with_primary(&self) -> Result<()> {
+        if self.secondary {
+            self.db.try_catch_up_with_primary()?;
+        }
+        Ok(())
     }
 
     /// Load existing key pair or generate new one.
@@ -338,6 +399,12 @@ impl Storage {
         Ok(())
     }
 
+    /// Get the `CF_ENTITIES` compaction filter (to configure it or take the
+    /// entities it dropped).
+    pub fn expiry_filter(&self) -> &super::ExpiryFilter {
+        &self.expiry
+    }
+
     /// Check if encryption is enabled.
     ///
     /// # Returns
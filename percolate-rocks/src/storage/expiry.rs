@@ -0,0 +1,205 @@
+//! Compaction-time cleanup of expired and soft-deleted entities.
+//!
+//! Instead of a separate reaper scanning `CF_ENTITIES`, an `ExpiryFilter` is
+//! installed as the column family's RocksDB compaction filter. While
+//! compaction rewrites SST files anyway, the filter drops entities whose:
+//!
+//! - `expires_at` (set by `Database::insert_with_ttl`) is in the past, or
+//! - `deleted_at` is older than the soft-delete grace period, if one is set
+//!
+//! Cleanup is therefore amortized into normal compaction. Soft-delete
+//! purging is opt-in: without a grace period, soft-deleted entities are
+//! kept (and restorable) until `Database::hard_delete`. Soft-deleted
+//! entities of retained tables (`DeletePolicy::SoftOnly`) are never dropped;
+//! an explicit TTL still applies to them.
+//!
+//! Reads hide expired entities as soon as `expires_at` passes, so compaction
+//! timing only affects disk usage, not visibility.
+//!
+//! The filter only sees the entity record. Each dropped record is queued
+//! (`take_dropped`) so `Database::vacuum` can remove the key index, field
+//! index, embedding, blob, and edge entries that pointed at it. The queue
+//! is in memory: entries dropped by compactions before a restart are left
+//! for `Database::check_consistency` to repair.
+
+use chrono::{DateTime, Utc};
+use rocksdb::{CompactionDecision, Options};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Name registered with RocksDB for the filter.
+const FILTER_NAME: &str = "percolate_entity_expiry";
+
+/// Stored grace value meaning soft-deleted entities are never dropped.
+const NO_GRACE: u64 = u64::MAX;
+
+/// Fields the filter looks at (other entity fields are skipped).
+#[derive(Deserialize)]
+struct ExpiryFields {
+    #[serde(default)]
+    entity_type: Option<String>,
+    #[serde(default)]
+    expires_at: Option<String>,
+    #[serde(default)]
+    deleted_at: Option<String>,
+}
+
+/// Compaction filter for `CF_ENTITIES`.
+///
+/// Clones share their settings and drop queue, so the filter can be
+/// configured after it has been handed to RocksDB.
+#[derive(Debug, Clone)]
+pub struct ExpiryFilter {
+    grace_secs: Arc<AtomicU64>,
+    retained_tables: Arc<RwLock<HashSet<String>>>,
+    dropped: Arc<Mutex<Vec<(Vec<u8>, Vec<u8>)>>>,
+}
+
+impl Default for ExpiryFilter {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ExpiryFilter {
+    /// Create filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `soft_delete_grace` - How long soft-deleted entities are kept
+    ///   (`None` never drops them)
+    pub fn new(soft_delete_grace: Option<Duration>) -> Self {
+        let filter = Self {
+            grace_secs: Arc::new(AtomicU64::new(NO_GRACE)),
+            retained_tables: Arc::new(RwLock::new(HashSet::new())),
+            dropped: Arc::new(Mutex::new(Vec::new())),
+        };
+        filter.set_soft_delete_grace(soft_delete_grace);
+        filter
+    }
+
+    /// Get the soft-delete grace period (`None` if soft deletes are kept).
+    pub fn soft_delete_grace(&self) -> Option<Duration> {
+        match self.grace_secs.load(Ordering::Relaxed) {
+            NO_GRACE => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// Set the soft-delete grace period (applies to future compactions).
+    pub fn set_soft_delete_grace(&self, grace: Option<Duration>) {
+        let secs = grace.map_or(NO_GRACE, |grace| grace.as_secs().min(NO_GRACE - 1));
+        self.grace_secs.store(secs, Ordering::Relaxed);
+    }
+
+    /// Keep (or stop keeping) a table's soft-deleted entities regardless of
+    /// the grace period.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - Schema/table name
+    /// * `retained` - `true` to never drop the table's soft-deleted entities
+    pub fn set_retained(&self, table: &str, retained: bool) {
+        let mut tables = self.retained_tables.write().unwrap_or_else(|e| e.into_inner());
+        if retained {
+            tables.insert(table.to_string());
+        } else {
+            tables.remove(table);
+        }
+    }
+
+    /// Take the `(key, value)` pairs of entities dropped since the last call.
+    pub fn take_dropped(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        std::mem::take(&mut *self.dropped.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Decide whether a stored entity should be dropped.
+    ///
+    /// Values that don't parse as entities, or carry malformed
+    /// timestamps, are always kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Serialized entity (JSON)
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity has expired or its grace period has passed
+    pub fn should_remove(&self, value: &[u8], now: DateTime<Utc>) -> bool {
+        let Ok(fields) = serde_json::from_slice::<ExpiryFields>(value) else {
+            return false;
+        };
+
+        let parse = |ts: &str| DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc));
+
+        let expired = fields.expires_at.as_deref()
+            .and_then(parse)
+            .is_some_and(|expires_at| expires_at <= now);
+
+        let retained = fields.entity_type.as_ref().is_some_and(|entity_type| {
+            self.retained_tables.read().unwrap_or_else(|e| e.into_inner()).contains(entity_type)
+        });
+        let purge_at = fields.deleted_at.as_deref()
+            .filter(|_| !retained)
+            .and_then(parse)
+            .zip(self.soft_delete_grace().and_then(|grace| chrono::Duration::from_std(grace).ok()))
+            .and_then(|(deleted_at, grace)| deleted_at.checked_add_signed(grace));
+
+        expired || purge_at.is_some_and(|purge_at| purge_at <= now)
+    }
+
+    /// Install the filter on column family options.
+    pub fn install(&self, opts: &mut Options) {
+        let filter = self.clone();
+        opts.set_compaction_filter(FILTER_NAME, move |_level: u32, key: &[u8], value: &[u8]| {
+            if filter.should_remove(value, Utc::now()) {
+                filter.dropped.lock().unwrap_or_else(|e| e.into_inner()).push((key.to_vec(), value.to_vec()));
+                CompactionDecision::Remove
+            } else {
+                CompactionDecision::Keep
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_remove() {
+        let filter = ExpiryFilter::new(Some(Duration::from_secs(3600)));
+        let now = Utc::now();
+        let at = |offset_secs: i64| (now + chrono::Duration::seconds(offset_secs)).to_rfc3339();
+        let entity = |fields: serde_json::Value| serde_json::to_vec(&fields).unwrap();
+
+        assert!(filter.should_remove(&entity(serde_json::json!({"expires_at": at(-1)})), now));
+        assert!(!filter.should_remove(&entity(serde_json::json!({"expires_at": at(60)})), now));
+
+        // Soft deletes wait out the grace period
+        assert!(!filter.should_remove(&entity(serde_json::json!({"deleted_at": at(-60)})), now));
+        assert!(filter.should_remove(&entity(serde_json::json!({"deleted_at": at(-7200)})), now));
+
+        // Without a grace period soft deletes are never dropped
+        let default = ExpiryFilter::default();
+        assert!(default.soft_delete_grace().is_none());
+        assert!(!default.should_remove(&entity(serde_json::json!({"deleted_at": at(-7200)})), now));
+        assert!(default.should_remove(&entity(serde_json::json!({"expires_at": at(-1)})), now));
+
+        // Clones share settings; retained tables keep soft deletes but not expired entities
+        filter.clone().set_retained("audit", true);
+        assert!(!filter.should_remove(&entity(serde_json::json!({"entity_type": "audit", "deleted_at": at(-7200)})), now));
+        assert!(filter.should_remove(&entity(serde_json::json!({"entity_type": "audit", "expires_at": at(-1)})), now));
+        filter.set_retained("audit", false);
+        assert!(filter.should_remove(&entity(serde_json::json!({"entity_type": "audit", "deleted_at": at(-7200)})), now));
+
+        // Live entities and non-entity values are kept
+        assert!(!filter.should_remove(&entity(serde_json::json!({"deleted_at": null})), now));
+        assert!(!filter.should_remove(&entity(serde_json::json!({"expires_at": "soon"})), now));
+        assert!(!filter.should_remove(b"\x00binary", now));
+    }
+}
@@ -117,6 +117,85 @@ pub fn encode_embedding_key(tenant_id: &str, entity_id: Uuid) -> Vec<u8> {
     format!("emb:{}:{}", tenant_id, entity_id).into_bytes()
 }
 
+/// Encode embedding projection key.
+///
+/// Format: `proj:{table}`
+///
+/// # Arguments
+///
+/// * `table` - Schema/table the projection applies to
+///
+/// # Returns
+///
+/// Encoded key as bytes
+pub fn encode_projection_key(table: &str) -> Vec<u8> {
+    format!("proj:{}", table).into_bytes()
+}
+
+/// Encode key of a projection whose vector rewrite has not finished.
+///
+/// Format: `proj_pending:{table}`
+///
+/// # Arguments
+///
+/// * `table` - Schema/table being reduced
+///
+/// # Returns
+///
+/// Encoded key as bytes
+pub fn encode_pending_projection_key(table: &str) -> Vec<u8> {
+    format!("proj_pending:{}", table).into_bytes()
+}
+
+/// Encode graph embedding key.
+///
+/// Format: `gemb:{rel_type}:{uuid}`
+///
+/// # Arguments
+///
+/// * `rel_type` - Relationship type the embedding was trained on
+/// * `entity_id` - Entity UUID
+///
+/// # Returns
+///
+/// Encoded key as bytes
+pub fn encode_graph_embedding_key(rel_type: &str, entity_id: Uuid) -> Vec<u8> {
+    format!("gemb:{}:{}", rel_type, entity_id).into_bytes()
+}
+
+/// Encode blob key.
+///
+/// Format: `blob:{tenant_id}:{uuid}`
+///
+/// # Arguments
+///
+/// * `tenant_id` - Tenant scope
+/// * `entity_id` - Owning entity UUID
+///
+/// # Returns
+///
+/// Encoded key as bytes
+pub fn encode_blob_key(tenant_id: &str, entity_id: Uuid) -> Vec<u8> {
+    format!("blob:{}:{}", tenant_id, entity_id).into_bytes()
+}
+
+/// Encode blob chunk key.
+///
+/// Format: `blob:{tenant_id}:{uuid}:{chunk_idx}`
+///
+/// # Arguments
+///
+/// * `tenant_id` - Tenant scope
+/// * `entity_id` - Owning entity UUID
+/// * `chunk_idx` - Chunk number (0-based)
+///
+/// # Returns
+///
+/// Encoded key as bytes
+pub fn encode_blob_chunk_key(tenant_id: &str, entity_id: Uuid, chunk_idx: u32) -> Vec<u8> {
+    format!("blob:{}:{}:{:010}", tenant_id, entity_id, chunk_idx).into_bytes()  // Zero-padded for chunk ordering
+}
+
 /// Encode index key for field value.
 ///
 /// Format: `idx:{tenant_id}:{field_name}:{field_value}:{uuid}`
@@ -262,6 +341,23 @@ mod tests {
         assert_eq!(key_str, format!("emb:tenant1:{}", id));
     }
 
+    #[test]
+    fn test_encode_projection_key() {
+        let key = encode_projection_key("articles");
+        assert_eq!(String::from_utf8(key).unwrap(), "proj:articles");
+
+        // Must not fall under the `proj:` prefix loaded at open
+        let pending = encode_pending_projection_key("articles");
+        assert!(!pending.starts_with(b"proj:"));
+    }
+
+    #[test]
+    fn test_encode_graph_embedding_key() {
+        let id = Uuid::new_v4();
+        let key = encode_graph_embedding_key("knows", id);
+        assert_eq!(String::from_utf8(key).unwrap(), format!("gemb:knows:{}", id));
+    }
+
     #[test]
     fn test_encode_index_key() {
         let id = Uuid::new_v4();
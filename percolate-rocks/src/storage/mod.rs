@@ -7,6 +7,10 @@
 //! - Embeddings (binary format)
 //! - Field indexes
 //! - Write-ahead log (WAL)
+//! - Chunked binary blobs
+//! - Compaction filter for expired and soft-deleted entities
+//! - Pluggable backends (`StorageProvider`: RocksDB, PostgreSQL)
+//! - Consistency checks between entities and their index entries
 //!
 //! Also includes background worker for async operations.
 
@@ -16,6 +20,13 @@ pub mod batch;
 pub mod iterator;
 pub mod column_families;
 pub mod worker;
+pub mod blob;
+pub mod retry;
+pub mod expiry;
+pub mod provider;
+pub mod consistency;
+#[cfg(feature = "postgres")]
+pub mod postgres;
 
 pub use db::Storage;
 pub use keys::*;
@@ -23,3 +34,10 @@ pub use batch::BatchWriter;
 pub use iterator::PrefixIterator;
 pub use column_families::*;
 pub use worker::{BackgroundWorker, Task, TaskResult, WorkerStatus};
+pub use blob::{BlobReader, DEFAULT_BLOB_CHUNK_SIZE};
+pub use retry::{WriteRetryPolicy, DEFAULT_WRITE_RETRIES};
+pub use expiry::ExpiryFilter;
+pub use provider::{BatchOp, KeyValue, KeyValueIter, StorageProvider};
+pub use consistency::ConsistencyReport;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresProvider;
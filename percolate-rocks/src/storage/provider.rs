@@ -0,0 +1,248 @@
+//! Pluggable key-value storage backends.
+//!
+//! `StorageProvider` is the backend-neutral surface of the storage layer:
+//! get/put/delete, prefix scans, and atomic batches over named column
+//! families. Two backends implement it:
+//!
+//! | Backend | Type | Use |
+//! |---------|------|-----|
+//! | RocksDB | `Storage` | Embedded, single node (default) |
+//! | PostgreSQL | `PostgresProvider` (`postgres` feature) | Enterprise, multi-tenant |
+//!
+//! Keys and values are opaque bytes, encoded exactly as for RocksDB (see
+//! `storage::keys`), so code written against the trait behaves the same on
+//! either backend. RocksDB-only features (snapshots, encryption at rest)
+//! stay on `Storage`.
+//!
+//! `Database` routes entity CRUD, key index, and field index access through
+//! a provider (see `Database::open_with_provider`).
+
+use crate::storage::Storage;
+use crate::types::{DatabaseError, Result};
+
+/// Owned `(key, value)` pair returned by scans.
+pub type KeyValue = (Vec<u8>, Vec<u8>);
+
+/// Iterator over `(key, value)` pairs in key order.
+pub type KeyValueIter<'a> = Box<dyn Iterator<Item = Result<KeyValue>> + 'a>;
+
+/// One operation of an atomic batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOp {
+    /// Insert or overwrite a key
+    Put {
+        cf: &'static str,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+
+    /// Remove a key (no-op if absent)
+    Delete {
+        cf: &'static str,
+        key: Vec<u8>,
+    },
+}
+
+/// Key-value storage backend with column families.
+///
+/// Column family names are the `CF_*` constants in `column_families`.
+pub trait StorageProvider: Send + Sync {
+    /// Backend name (e.g. `rocksdb`, `postgres`).
+    fn backend(&self) -> &'static str;
+
+    /// Get value by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a backend error if the read fails
+    fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Put value, overwriting any existing one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a backend error if the write fails
+    fn put(&self, cf: &str, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Delete key (no-op if absent).
+    ///
+    /// # Errors
+    ///
+    /// Returns a backend error if the write fails
+    fn delete(&self, cf: &str, key: &[u8]) -> Result<()>;
+
+    /// Get values for several keys of one column family.
+    ///
+    /// # Returns
+    ///
+    /// One entry per key, in the same order
+    ///
+    /// # Errors
+    ///
+    /// Returns a backend error if any read fails
+    fn multi_get(&self, cf: &str, keys: &[Vec<u8>]) -> Result<Vec<Option<Vec<u8>>>> {
+        keys.iter().map(|key| self.get(cf, key)).collect()
+    }
+
+    /// Iterate keys starting with `prefix`, in key order, beginning at the
+    /// first key `>= start`.
+    ///
+    /// Used to resume a scan after the last key seen.
+    fn iterator_from<'a>(&'a self, cf: &str, prefix: &[u8], start: &[u8]) -> KeyValueIter<'a>;
+
+    /// Iterate keys starting with `prefix`, in key order.
+    ///
+    /// An empty prefix iterates the whole column family.
+    fn iterator<'a>(&'a self, cf: &str, prefix: &[u8]) -> KeyValueIter<'a> {
+        self.iterator_from(cf, prefix, prefix)
+    }
+
+    /// Apply operations atomically: all or none become visible.
+    ///
+    /// # Errors
+    ///
+    /// Returns a backend error if the commit fails
+    fn write_batch(&self, ops: Vec<BatchOp>) -> Result<()>;
+
+    /// Collect keys starting with `prefix`, in key order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error hit while iterating
+    fn scan_prefix(&self, cf: &str, prefix: &[u8]) -> Result<Vec<KeyValue>> {
+        self.iterator(cf, prefix).collect()
+    }
+}
+
+impl StorageProvider for Storage {
+    fn backend(&self) -> &'static str {
+        "rocksdb"
+    }
+
+    fn get(&self, cf: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Storage::get(self, cf, key)
+    }
+
+    fn put(&self, cf: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        Storage::put(self, cf, key, value)
+    }
+
+    fn delete(&self, cf: &str, key: &[u8]) -> Result<()> {
+        Storage::delete(self, cf, key)
+    }
+
+    fn multi_get(&self, cf: &str, keys: &[Vec<u8>]) -> Result<Vec<Option<Vec<u8>>>> {
+        let handle = self.cf_handle(cf);
+        self.db()
+            .multi_get_cf(keys.iter().map(|key| (&handle, key)))
+            .into_iter()
+            .map(|value| value.map_err(DatabaseError::StorageError))
+            .collect()
+    }
+
+    fn iterator_from<'a>(&'a self, cf: &str, prefix: &[u8], start: &[u8]) -> KeyValueIter<'a> {
+        let handle = self.cf_handle(cf);
+        let prefix = prefix.to_vec();
+        let start = if start < prefix.as_slice() { prefix.clone() } else { start.to_vec() };
+
+        Box::new(
+            self.db()
+                .iterator_cf(&handle, rocksdb::IteratorMode::From(&start, rocksdb::Direction::Forward))
+                .map(|item| item.map(|(key, value)| (key.into_vec(), value.into_vec())).map_err(DatabaseError::StorageError))
+                .take_while(move |item| match item {
+                    Ok((key, _)) => key.starts_with(&prefix),
+                    Err(_) => true, // Propagate errors
+                }),
+        )
+    }
+
+    fn write_batch(&self, ops: Vec<BatchOp>) -> Result<()> {
+        use crate::otel::{db_span, DbOperation};
+
+        let _span = db_span(DbOperation::BatchWrite, None, None).entered();
+
+        let mut batch = rocksdb::WriteBatch::default();
+        for op in &ops {
+            match op {
+                BatchOp::Put { cf, key, value } => batch.put_cf(&self.cf_handle(cf), key, value),
+                BatchOp::Delete { cf, key } => batch.delete_cf(&self.cf_handle(cf), key),
+            }
+        }
+
+        self.db().write(batch).map_err(DatabaseError::StorageError)
+    }
+}
+
+/// Entity CRUD run against any backend through the trait.
+#[cfg(test)]
+pub(crate) fn entity_crud_suite(provider: &dyn StorageProvider) {
+    use crate::storage::column_families::{CF_ENTITIES, CF_KEY_INDEX};
+    use crate::storage::keys::{encode_entity_key, encode_key_index};
+    use crate::types::Entity;
+    use uuid::Uuid;
+
+    let entity = |title: &str| Entity::new(Uuid::new_v4(), "articles".to_string(), serde_json::json!({"title": title}));
+    let encode = |entity: &Entity| serde_json::to_vec(entity).unwrap();
+    let decode = |bytes: &[u8]| serde_json::from_slice::<Entity>(bytes).unwrap();
+
+    // Create
+    let first = entity("First");
+    let first_key = encode_entity_key("tenant1", first.system.id);
+    provider.put(CF_ENTITIES, &first_key, &encode(&first)).unwrap();
+    assert_eq!(decode(&provider.get(CF_ENTITIES, &first_key).unwrap().unwrap()).system.id, first.system.id);
+
+    // Update
+    let mut updated = first.clone();
+    updated.properties = serde_json::json!({"title": "First (edited)"});
+    provider.put(CF_ENTITIES, &first_key, &encode(&updated)).unwrap();
+    assert_eq!(decode(&provider.get(CF_ENTITIES, &first_key).unwrap().unwrap()).properties["title"], "First (edited)");
+
+    // Atomic batch across column families
+    let second = entity("Second");
+    let second_key = encode_entity_key("tenant1", second.system.id);
+    let other_tenant = entity("Other");
+    provider.write_batch(vec![
+        BatchOp::Put { cf: CF_ENTITIES, key: second_key.clone(), value: encode(&second) },
+        BatchOp::Put { cf: CF_KEY_INDEX, key: encode_key_index("tenant1", "Second", second.system.id), value: Vec::new() },
+        BatchOp::Put { cf: CF_ENTITIES, key: encode_entity_key("tenant2", other_tenant.system.id), value: encode(&other_tenant) },
+    ]).unwrap();
+    assert!(provider.get(CF_KEY_INDEX, &encode_key_index("tenant1", "Second", second.system.id)).unwrap().is_some());
+
+    // Prefix scans stay within the tenant, in key order
+    let scanned = provider.scan_prefix(CF_ENTITIES, b"entity:tenant1:").unwrap();
+    let mut expected = vec![first_key.clone(), second_key.clone()];
+    expected.sort();
+    assert_eq!(scanned.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>(), expected);
+    assert_eq!(provider.scan_prefix(CF_ENTITIES, b"").unwrap().len(), 3);
+
+    // Resuming past the first key yields the rest of the prefix only
+    let resumed: Vec<KeyValue> = provider.iterator_from(CF_ENTITIES, b"entity:tenant1:", &expected[1])
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(resumed.len(), 1);
+    assert_eq!(resumed[0].0, expected[1]);
+
+    let values = provider.multi_get(CF_ENTITIES, &[second_key.clone(), b"entity:tenant1:missing".to_vec()]).unwrap();
+    assert_eq!(decode(values[0].as_ref().unwrap()).system.id, second.system.id);
+    assert!(values[1].is_none());
+
+    // Delete
+    provider.delete(CF_ENTITIES, &first_key).unwrap();
+    assert!(provider.get(CF_ENTITIES, &first_key).unwrap().is_none());
+    provider.delete(CF_ENTITIES, &first_key).unwrap();
+
+    provider.write_batch(vec![BatchOp::Delete { cf: CF_ENTITIES, key: second_key }]).unwrap();
+    assert!(provider.iterator(CF_ENTITIES, b"entity:tenant1:").next().is_none());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rocksdb_provider_entity_crud() {
+        let storage = Storage::open_temp().unwrap();
+        assert_eq!(storage.backend(), "rocksdb");
+        entity_crud_suite(&storage);
+    }
+}
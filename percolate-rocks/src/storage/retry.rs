@@ -0,0 +1,70 @@
+//! Retry policy for optimistic write conflicts.
+//!
+//! Read-modify-write operations (`Database::update_with`) read a snapshot,
+//! apply the change, and commit only if the stored value is still the one
+//! they read. On conflict they back off and retry from a fresh read.
+
+use std::time::Duration;
+
+/// Default retries after the first conflicting attempt.
+pub const DEFAULT_WRITE_RETRIES: u32 = 8;
+
+/// How conflicting read-modify-write operations are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteRetryPolicy {
+    /// Retries after the first attempt (0 = fail on first conflict)
+    pub max_retries: u32,
+
+    /// Backoff before the first retry; doubles per retry
+    pub initial_backoff: Duration,
+
+    /// Backoff cap
+    pub max_backoff: Duration,
+}
+
+impl Default for WriteRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_WRITE_RETRIES,
+            initial_backoff: Duration::from_micros(200),
+            max_backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+impl WriteRetryPolicy {
+    /// Policy with the default backoff and `max_retries` retries.
+    pub fn with_retries(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `retry` (1-based).
+    ///
+    /// Exponential, capped at `max_backoff`, with jitter in `[50%, 100%]`
+    /// so contending writers spread out.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        use rand::Rng;
+
+        let exp = self.initial_backoff.saturating_mul(1u32 << retry.saturating_sub(1).min(16));
+        let capped = exp.min(self.max_backoff);
+        capped.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let policy = WriteRetryPolicy::default();
+
+        assert!(policy.backoff(1) <= policy.initial_backoff);
+        assert!(policy.backoff(4) >= policy.initial_backoff * 4);
+        assert!(policy.backoff(30) <= policy.max_backoff);
+        assert_eq!(WriteRetryPolicy::with_retries(2).max_retries, 2);
+    }
+}
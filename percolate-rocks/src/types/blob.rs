@@ -0,0 +1,53 @@
+//! Binary payloads attached to entities.
+//!
+//! Blobs (images, audio, PDFs) live in `CF_BLOBS` under the owning entity's
+//! UUID, so the JSON entity stays small. Payloads are split into fixed-size
+//! chunks described by a `BlobManifest` (see `storage::blob`).
+
+use serde::{Deserialize, Serialize};
+
+/// Binary payload with its MIME type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blob {
+    /// MIME type (e.g. `image/png`)
+    pub content_type: String,
+
+    /// Raw bytes
+    pub data: Vec<u8>,
+}
+
+impl Blob {
+    /// Create blob.
+    pub fn new(data: Vec<u8>, content_type: impl Into<String>) -> Self {
+        Self {
+            content_type: content_type.into(),
+            data,
+        }
+    }
+
+    /// Payload size in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the payload is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+/// Stored description of a chunked blob.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobManifest {
+    /// MIME type (e.g. `image/png`)
+    pub content_type: String,
+
+    /// Payload size in bytes
+    pub total_size: u64,
+
+    /// Number of chunks
+    pub chunk_count: u32,
+
+    /// Chunk size in bytes (the last chunk may be shorter)
+    pub chunk_size: u32,
+}
@@ -94,6 +94,13 @@ pub struct SystemFields {
     /// Soft delete timestamp (ISO 8601), null if not deleted
     pub deleted_at: Option<String>,
 
+    /// Expiry timestamp (ISO 8601), null if the entity never expires.
+    ///
+    /// Expired entities are hidden from reads and dropped by compaction
+    /// (see `storage::expiry`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+
     /// Graph edges (inline mode).
     ///
     /// **Storage Modes:**
@@ -146,6 +153,7 @@ pub struct SystemFields {
 ///         created_at: "2025-10-24T10:00:00Z".to_string(),
 ///         modified_at: "2025-10-24T10:00:00Z".to_string(),
 ///         deleted_at: None,
+///         expires_at: None,
 ///         edges: vec![],
 ///     },
 ///     properties: serde_json::json!({
@@ -186,6 +194,7 @@ impl Entity {
                 created_at: now.clone(),
                 modified_at: now,
                 deleted_at: None,
+                expires_at: None,
                 edges: vec![],
             },
             properties,
@@ -232,6 +241,27 @@ impl Entity {
         self.system.modified_at = chrono::Utc::now().to_rfc3339();
     }
 
+    /// Undo a soft delete.
+    ///
+    /// Clears `deleted_at` and bumps `modified_at`.
+    pub fn mark_restored(&mut self) {
+        self.system.deleted_at = None;
+        self.system.modified_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    /// Set expiry to `ttl` from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - Time to live
+    pub fn set_ttl(&mut self, ttl: std::time::Duration) {
+        // A TTL too large to represent means the entity never expires
+        self.system.expires_at = chrono::Duration::from_std(ttl)
+            .ok()
+            .and_then(|ttl| chrono::Utc::now().checked_add_signed(ttl))
+            .map(|expires_at| expires_at.to_rfc3339());
+    }
+
     /// Check if entity is soft deleted.
     ///
     /// # Returns
@@ -241,6 +271,64 @@ impl Entity {
         self.system.deleted_at.is_some()
     }
 
+    /// Check if entity has expired.
+    ///
+    /// # Returns
+    ///
+    /// `true` if `expires_at` is set and not in the future
+    pub fn is_expired(&self) -> bool {
+        self.system.expires_at.as_deref()
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+    }
+
+    /// Flatten into a single JSON object for API results.
+    ///
+    /// Properties are returned as-is. With `include_system`, system fields
+    /// are added under `_`-prefixed keys so they never collide with user
+    /// properties: `_id`, `_entity_type`, `_created_at`, `_modified_at`,
+    /// `_deleted_at`, `_expires_at` (only if set), and `_edges`.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_system` - Add system fields
+    ///
+    /// # Returns
+    ///
+    /// JSON object (properties alone if they are not an object and
+    /// `include_system` is false)
+    pub fn to_flat_json(&self, include_system: bool) -> serde_json::Value {
+        if !include_system {
+            return self.properties.clone();
+        }
+
+        let mut object = match &self.properties {
+            serde_json::Value::Object(props) => props.clone(),
+            serde_json::Value::Null => serde_json::Map::new(),
+            other => {
+                let mut map = serde_json::Map::new();
+                map.insert("value".to_string(), other.clone());
+                map
+            }
+        };
+
+        let system = &self.system;
+        object.insert("_id".to_string(), serde_json::json!(system.id.to_string()));
+        object.insert("_entity_type".to_string(), serde_json::json!(system.entity_type));
+        object.insert("_created_at".to_string(), serde_json::json!(system.created_at));
+        object.insert("_modified_at".to_string(), serde_json::json!(system.modified_at));
+        object.insert("_deleted_at".to_string(), serde_json::json!(system.deleted_at));
+        if let Some(expires_at) = &system.expires_at {
+            object.insert("_expires_at".to_string(), serde_json::json!(expires_at));
+        }
+        object.insert(
+            "_edges".to_string(),
+            serde_json::to_value(&system.edges).unwrap_or_else(|_| serde_json::json!([])),
+        );
+
+        serde_json::Value::Object(object)
+    }
+
     /// Add or update inline edge.
     ///
     /// If edge with same (dst, rel_type) exists, updates its properties.
@@ -394,11 +482,17 @@ impl Edge {
 
     /// Add property to edge.
     ///
+    /// Setting `weight` drops any `base_weight` left by edge decay, so the
+    /// new weight becomes the base of the next decay run.
+    ///
     /// # Arguments
     ///
     /// * `key` - Property name
     /// * `value` - Property value
     pub fn add_property(&mut self, key: String, value: serde_json::Value) {
+        if key == "weight" {
+            self.data.properties.remove("base_weight");
+        }
         self.data.properties.insert(key, value);
     }
 }
@@ -408,6 +502,31 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_to_flat_json_with_system_fields() {
+        let mut entity = Entity::new(Uuid::new_v4(), "person".to_string(), json!({"name": "Alice"}));
+        entity.add_edge(InlineEdge::new(Uuid::new_v4(), "knows".to_string()));
+
+        let flat = entity.to_flat_json(true);
+        assert_eq!(flat["name"], "Alice");
+        assert_eq!(flat["_id"], entity.system.id.to_string());
+        assert_eq!(flat["_entity_type"], "person");
+        assert_eq!(flat["_created_at"], entity.system.created_at);
+        assert_eq!(flat["_modified_at"], entity.system.modified_at);
+        assert!(flat["_deleted_at"].is_null());
+        assert!(flat.get("_expires_at").is_none());
+        assert_eq!(flat["_edges"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_to_flat_json_properties_only() {
+        let entity = Entity::new(Uuid::new_v4(), "person".to_string(), json!({"name": "Alice", "id": 7}));
+
+        // User "id" property is untouched either way
+        assert_eq!(entity.to_flat_json(false), json!({"name": "Alice", "id": 7}));
+        assert_eq!(entity.to_flat_json(true)["id"], 7);
+    }
+
     #[test]
     fn test_entity_creation() {
         let id = Uuid::new_v4();
@@ -42,6 +42,46 @@ pub enum DatabaseError {
     #[error("Query execution failed: {0}")]
     QueryError(String),
 
+    /// Full-table scan would read more rows than allowed
+    #[error("Scan limit exceeded: query on '{table}' would scan more than {limit} rows. Add an index on the filtered field or raise max_scan_rows for this query")]
+    ScanLimitExceeded {
+        /// Table being scanned
+        table: String,
+        /// Configured row limit
+        limit: usize,
+    },
+
+    /// Delete refused by the schema's delete policy
+    #[error("Delete policy violation: '{table}' is {policy}, {operation} not allowed")]
+    DeletePolicyViolation {
+        /// Table of the entity
+        table: String,
+        /// Configured policy (e.g. `soft_only`)
+        policy: String,
+        /// Refused operation (e.g. `hard delete`)
+        operation: String,
+    },
+
+    /// Edge would close a cycle on an acyclic relationship type
+    #[error("Edge constraint violation: {src_id} -[{rel_type}]-> {dst_id} would create a cycle")]
+    EdgeCycle {
+        /// Relationship type declared acyclic
+        rel_type: String,
+        /// Source entity of the rejected edge
+        src_id: Uuid,
+        /// Destination entity of the rejected edge
+        dst_id: Uuid,
+    },
+
+    /// Read-modify-write kept conflicting with concurrent writers
+    #[error("Write conflict on entity {entity_id}: gave up after {attempts} attempts")]
+    WriteConflict {
+        /// Entity being updated
+        entity_id: Uuid,
+        /// Attempts made (first try plus retries)
+        attempts: u32,
+    },
+
     /// Graph traversal failed
     #[error("Graph traversal failed: {0}")]
     GraphError(String),
@@ -70,6 +110,11 @@ pub enum DatabaseError {
     #[error("Storage error: {0}")]
     StorageError(#[from] rocksdb::Error),
 
+    /// Storage layer error (PostgreSQL backend)
+    #[cfg(feature = "postgres")]
+    #[error("PostgreSQL error: {0}")]
+    PostgresError(#[from] sqlx::Error),
+
     /// JSON serialization/deserialization error
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
@@ -86,6 +131,14 @@ pub enum DatabaseError {
     #[error("HTTP error: {0}")]
     HttpError(#[from] reqwest::Error),
 
+    /// Request did not complete within its timeout
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// Operation cancelled via its cancellation token
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
     /// gRPC error (for replication)
     #[error("gRPC error: {0}")]
     GrpcError(String),
@@ -147,7 +200,7 @@ impl DatabaseError {
     pub fn is_recoverable(&self) -> bool {
         matches!(
             self,
-            Self::HttpError(_) | Self::ReplicationError(_) | Self::GrpcError(_)
+            Self::HttpError(_) | Self::Timeout(_) | Self::ReplicationError(_) | Self::GrpcError(_)
         )
     }
 }
@@ -187,6 +240,8 @@ mod tests {
     fn test_is_recoverable() {
         let recoverable = DatabaseError::ReplicationError("network timeout".to_string());
         assert!(recoverable.is_recoverable());
+        assert!(DatabaseError::Timeout("embeddings request".to_string()).is_recoverable());
+        assert!(!DatabaseError::Cancelled("embeddings request".to_string()).is_recoverable());
 
         let non_recoverable = DatabaseError::ValidationError("invalid schema".to_string());
         assert!(!non_recoverable.is_recoverable());
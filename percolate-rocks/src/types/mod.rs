@@ -3,16 +3,22 @@
 //! Defines fundamental types used throughout the system:
 //! - `Entity`: Core data structure with system fields
 //! - `Edge`: Graph relationship between entities
+//! - `Blob`: Binary payload attached to an entity
+//! - `SearchResult`: Ranked search hit
 //! - `DatabaseError`: Error types for all operations
 //! - `Result`: Convenient result type alias
 //! - `generate_uuid`: Deterministic UUID generation
 
+pub mod blob;
 pub mod entity;
 pub mod error;
 pub mod result;
+pub mod search;
 pub mod uuid_gen;
 
+pub use blob::{Blob, BlobManifest};
 pub use entity::{Entity, Edge, EdgeData, SystemFields, InlineEdge};
 pub use error::DatabaseError;
 pub use result::Result;
-pub use uuid_gen::generate_uuid;
+pub use search::{DistanceMetric, SearchResult};
+pub use uuid_gen::{canonical_number, generate_uuid};
@@ -0,0 +1,161 @@
+//! Ranked search results.
+//!
+//! Vector indexes report distances, where lower is closer and the range
+//! depends on the metric. Results expose a `score` in `[0, 1]` instead,
+//! where higher is more similar, so callers can compare and threshold
+//! scores without knowing the metric:
+//!
+//! | Metric | Distance range | Score |
+//! |--------|----------------|-------|
+//! | Cosine | `[0, 2]` | `1 - d`, clamped to `[0, 1]` (identical = 1, orthogonal or opposite = 0) |
+//! | L2 | `[0, ∞)` | `1 / (1 + d)` (identical = 1, approaches 0 with distance) |
+//!
+//! The original distance is kept in `raw_distance`.
+
+use super::entity::Entity;
+use serde::{Deserialize, Serialize};
+
+/// Distance metric reported by a vector index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceMetric {
+    /// Cosine distance (1 - cosine similarity), used by the HNSW index
+    Cosine,
+    /// Euclidean distance, used by DiskANN
+    L2,
+}
+
+impl DistanceMetric {
+    /// Convert a distance to a similarity score in `[0, 1]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - Distance under this metric
+    ///
+    /// # Returns
+    ///
+    /// Score where higher is more similar (1.0 for an exact match)
+    pub fn to_score(self, distance: f32) -> f32 {
+        match self {
+            DistanceMetric::Cosine => (1.0 - distance).clamp(0.0, 1.0),
+            DistanceMetric::L2 => 1.0 / (1.0 + distance.max(0.0)),
+        }
+    }
+}
+
+/// One hit of a semantic search.
+///
+/// Results are returned best first: `rank` is the 0-based position and
+/// `score` (similarity, higher is better) never increases with rank.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// Matched entity
+    pub entity: Entity,
+    /// Similarity in `[0, 1]`, higher is more similar (see module docs)
+    pub score: f32,
+    /// 0-based position in the result list
+    pub rank: usize,
+    /// Distance reported by the index, when the hit came from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_distance: Option<f32>,
+}
+
+impl SearchResult {
+    /// Rank scored entities by descending score.
+    ///
+    /// Ties keep their input order.
+    ///
+    /// # Arguments
+    ///
+    /// * `scored` - `(entity, score)` pairs in any order
+    ///
+    /// # Returns
+    ///
+    /// Results sorted best first, with `rank` set
+    pub fn ranked(scored: impl IntoIterator<Item = (Entity, f32)>) -> Vec<SearchResult> {
+        let mut scored: Vec<(Entity, f32)> = scored.into_iter().collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        scored
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (entity, score))| SearchResult { entity, score, rank, raw_distance: None })
+            .collect()
+    }
+
+    /// Rank entities by index distance, converting distances to scores.
+    ///
+    /// # Arguments
+    ///
+    /// * `hits` - `(entity, distance)` pairs in any order
+    /// * `metric` - Metric the distances were measured with
+    ///
+    /// # Returns
+    ///
+    /// Results sorted best first, with `rank`, `score`, and `raw_distance` set
+    pub fn from_distances(
+        hits: impl IntoIterator<Item = (Entity, f32)>,
+        metric: DistanceMetric,
+    ) -> Vec<SearchResult> {
+        let mut hits: Vec<(Entity, f32)> = hits.into_iter().collect();
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (entity, distance))| SearchResult {
+                entity,
+                score: metric.to_score(distance),
+                rank,
+                raw_distance: Some(distance),
+            })
+            .collect()
+    }
+}
+
+/// Compatibility with code written against `(Entity, f32)` tuples.
+impl From<SearchResult> for (Entity, f32) {
+    fn from(result: SearchResult) -> Self {
+        (result.entity, result.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_score_range() {
+        let cosine = DistanceMetric::Cosine;
+        assert_eq!(cosine.to_score(0.0), 1.0);
+        assert!((cosine.to_score(0.25) - 0.75).abs() < 1e-6);
+        assert_eq!(cosine.to_score(1.0), 0.0);
+        // Opposite vectors clamp to 0 rather than going negative
+        assert_eq!(cosine.to_score(2.0), 0.0);
+        // Float error on an exact match never exceeds 1
+        assert_eq!(cosine.to_score(-1e-7), 1.0);
+    }
+
+    #[test]
+    fn test_l2_score_range() {
+        let l2 = DistanceMetric::L2;
+        assert_eq!(l2.to_score(0.0), 1.0);
+        assert_eq!(l2.to_score(1.0), 0.5);
+        assert!(l2.to_score(1e6) < 1e-5);
+    }
+
+    #[test]
+    fn test_from_distances_ranks_closest_first() {
+        let entity = |name: &str| Entity::new(uuid::Uuid::new_v4(), "doc".to_string(), serde_json::json!({"name": name}));
+
+        let results = SearchResult::from_distances(
+            vec![(entity("far"), 0.8), (entity("exact"), 0.0), (entity("near"), 0.1)],
+            DistanceMetric::Cosine,
+        );
+
+        let names: Vec<&str> = results.iter().map(|r| r.entity.properties["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["exact", "near", "far"]);
+        assert_eq!(results[1].raw_distance, Some(0.1));
+        assert!((results[1].score - 0.9).abs() < 1e-6);
+        assert_eq!(results.iter().map(|r| r.rank).collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+}
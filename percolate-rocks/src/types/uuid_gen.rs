@@ -107,12 +107,56 @@ fn hash_to_uuid(input: &str) -> Uuid {
 fn value_to_string(value: &serde_json::Value) -> String {
     match value {
         serde_json::Value::String(s) => s.clone(),
-        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::Number(n) => canonical_number(n),
         serde_json::Value::Bool(b) => b.to_string(),
         _ => value.to_string(), // Fallback to JSON string
     }
 }
 
+/// Significant digits kept when rendering non-integral floats for hashing.
+///
+/// Absorbs binary rounding noise (e.g. `0.1 + 0.2`) so values that are
+/// equal to this precision hash to the same key. Relative rather than
+/// fixed-point, so small magnitudes (e.g. `1e-13` vs `2e-13`) stay distinct.
+pub const KEY_FLOAT_SIGNIFICANT_DIGITS: usize = 15;
+
+/// Render a JSON number in canonical form for key construction.
+///
+/// Integral values render without a fractional part regardless of how they
+/// were written (`1`, `1.0` and `1e0` all become `"1"`). Other floats are
+/// rounded to [`KEY_FLOAT_SIGNIFICANT_DIGITS`] significant digits and
+/// printed in shortest decimal form.
+///
+/// # Arguments
+///
+/// * `n` - JSON number
+///
+/// # Returns
+///
+/// Canonical string representation
+pub fn canonical_number(n: &serde_json::Number) -> String {
+    if n.is_i64() || n.is_u64() {
+        return n.to_string();
+    }
+
+    let f = match n.as_f64() {
+        Some(f) if f.is_finite() => f,
+        _ => return n.to_string(),
+    };
+
+    // Integral floats within the exactly-representable range print as integers
+    if f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 {
+        return format!("{}", f as i64);
+    }
+
+    // Round in scientific notation, then print the rounded value plainly
+    let rounded = format!("{:.*e}", KEY_FLOAT_SIGNIFICANT_DIGITS - 1, f);
+    match rounded.parse::<f64>() {
+        Ok(rounded) => rounded.to_string(),
+        Err(_) => rounded,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,4 +250,52 @@ mod tests {
         // Different precedence → different UUIDs
         assert_ne!(id_with_uri, id_with_key);
     }
+
+    #[test]
+    fn test_integral_float_key_matches_integer() {
+        let id_int = generate_uuid("items", &json!({"x": 1}), Some("x"));
+        let id_float = generate_uuid("items", &json!({"x": 1.0}), Some("x"));
+        assert_eq!(id_int, id_float);
+
+        let id_key_int = generate_uuid("items", &json!({"key": -42}), None);
+        let id_key_float = generate_uuid("items", &json!({"key": -42.0}), None);
+        assert_eq!(id_key_int, id_key_float);
+    }
+
+    #[test]
+    fn test_different_numbers_different_uuids() {
+        let id1 = generate_uuid("items", &json!({"x": 1}), Some("x"));
+        let id2 = generate_uuid("items", &json!({"x": 1.5}), Some("x"));
+        let id3 = generate_uuid("items", &json!({"x": 2.0}), Some("x"));
+
+        assert_ne!(id1, id2);
+        assert_ne!(id1, id3);
+        assert_ne!(id2, id3);
+
+        // Small magnitudes don't collapse to zero
+        let tiny1 = generate_uuid("items", &json!({"x": 1e-13}), Some("x"));
+        let tiny2 = generate_uuid("items", &json!({"x": 2e-13}), Some("x"));
+        let zero = generate_uuid("items", &json!({"x": 0}), Some("x"));
+
+        assert_ne!(tiny1, tiny2);
+        assert_ne!(tiny1, zero);
+        assert_ne!(tiny2, zero);
+    }
+
+    #[test]
+    fn test_canonical_number() {
+        let render = |v: serde_json::Value| match v {
+            serde_json::Value::Number(n) => canonical_number(&n),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(render(json!(1)), "1");
+        assert_eq!(render(json!(1.0)), "1");
+        assert_eq!(render(json!(-0.0)), "0");
+        assert_eq!(render(json!(1.25)), "1.25");
+        assert_eq!(render(json!(0.1 + 0.2)), render(json!(0.3)));
+        assert_ne!(render(json!(0.1)), render(json!(0.2)));
+        assert_eq!(render(json!(1e-13)), "0.0000000000001");
+        assert_eq!(render(json!(1e-20 + 2e-20)), render(json!(3e-20)));
+    }
 }
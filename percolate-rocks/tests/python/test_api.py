@@ -1,5 +1,7 @@
 """Integration tests for Python API."""
 
+import json
+
 import pytest
 from rem_db import Database, AsyncDatabase
 from rem_db.models import Article, Person, Sprint
@@ -47,6 +49,30 @@ def test_get_entity(db):
     pass
 
 
+def test_get_entity_system_fields(db):
+    """Test include_system on get (system fields under _-prefixed keys)."""
+    # TODO: get(id) includes _id/_created_at; get(id, include_system=False) returns only properties
+    pass
+
+
+def test_get_typed_entity(db):
+    """Test typed get (Entity object with attribute access)."""
+    db.register_schema("person", json.dumps(Person.model_json_schema()))
+    entity_id = db.insert("person", {"name": "Alice", "email": "alice@example.com", "role": "engineer"})
+
+    entity = db.get(entity_id, typed=True)
+    assert entity.id == entity_id
+    assert entity.entity_type == "person"
+    assert entity.properties["name"] == "Alice"
+    assert entity.created_at and entity.modified_at
+    assert entity.edges == []
+    assert not entity.is_deleted()
+
+    # to_dict round-trips to the untyped form
+    assert entity.to_dict() == db.get(entity_id)
+    assert entity.to_dict(include_system=False) == db.get(entity_id, include_system=False)
+
+
 def test_lookup_by_key(db):
     """Test global key lookup."""
     # TODO: Lookup entity by key field value